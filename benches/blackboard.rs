@@ -0,0 +1,183 @@
+//! Blackboard Throughput Benchmarks
+//!
+//! Measures the hot paths of stigmergic coordination:
+//! - Pheromone intensity evaluation (pure physics)
+//! - Payload encoding: JSON (current wire format) vs MessagePack
+//! - `get_all_intensities`' work on already-read values: decoding every
+//!   pheromone's stored JSON and evaluating its intensity and threshold
+//! - Redis-backed deposits, sniffs and `get_all_intensities`
+//!
+//! The first three groups need no Redis. The Redis group only runs with
+//! `DRIFTGUARD_BENCH_REDIS=1` and a server reachable at `REDIS_URL`
+//! (default `redis://127.0.0.1:6379`); otherwise it is skipped with a note.
+//! It writes to a swarm of its own (`bench-<pid>`) and clears only that, so
+//! a swarm running on the same Redis is left alone. The gap between the two
+//! `get_all_intensities` numbers is the round trips.
+//!
+//! Run with: `cargo bench --bench blackboard`
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+use driftguard::agents::sensor::{AssetQuote, MarketSnapshot};
+use driftguard::core::physics::{Pheromone, PheromonePayload, PheromoneType};
+use driftguard::core::{Blackboard, Config};
+
+fn sample_payload() -> PheromonePayload<MarketSnapshot> {
+    let snapshot = MarketSnapshot::new(vec![
+        AssetQuote::new("SPY", 580.25),
+        AssetQuote::new("BND", 72.10),
+        AssetQuote::new("GLD", 215.40),
+        AssetQuote::new("VNQ", 88.75),
+    ]);
+    PheromonePayload::new(snapshot, Pheromone::with_decay("Price Freshness", 0.3))
+}
+
+fn bench_physics(c: &mut Criterion) {
+    let pheromone = Pheromone::with_decay("bench", 0.3);
+
+    let mut group = c.benchmark_group("physics");
+    group.bench_function("current_intensity", |b| {
+        b.iter(|| black_box(&pheromone).current_intensity())
+    });
+    group.bench_function("time_until_inactive", |b| {
+        b.iter(|| black_box(&pheromone).time_until_inactive(black_box(0.5)))
+    });
+    group.finish();
+}
+
+fn bench_encoding(c: &mut Criterion) {
+    let payload = sample_payload();
+    let json = serde_json::to_vec(&payload).unwrap();
+    let msgpack = rmp_serde::to_vec_named(&payload).unwrap();
+
+    println!(
+        "encoded size: json={} bytes, msgpack={} bytes",
+        json.len(),
+        msgpack.len()
+    );
+
+    let mut group = c.benchmark_group("encoding");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("json/serialize", |b| {
+        b.iter(|| serde_json::to_vec(black_box(&payload)).unwrap())
+    });
+    group.bench_function("msgpack/serialize", |b| {
+        b.iter(|| rmp_serde::to_vec_named(black_box(&payload)).unwrap())
+    });
+    group.bench_function("json/deserialize", |b| {
+        b.iter(|| {
+            serde_json::from_slice::<PheromonePayload<MarketSnapshot>>(black_box(&json)).unwrap()
+        })
+    });
+    group.bench_function("msgpack/deserialize", |b| {
+        b.iter(|| {
+            rmp_serde::from_slice::<PheromonePayload<MarketSnapshot>>(black_box(&msgpack)).unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn bench_intensities(c: &mut Criterion) {
+    let config = Config::default();
+    // What `peek` reads back from Redis for each pheromone type
+    let stored: Vec<(PheromoneType, String)> = PheromoneType::ALL
+        .into_iter()
+        .map(|ptype| {
+            let payload = PheromonePayload::new(
+                sample_payload().data,
+                Pheromone::with_decay(ptype.label(), ptype.decay_rate(&config)),
+            );
+            (ptype, serde_json::to_string(&payload).unwrap())
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("intensities");
+    group.throughput(Throughput::Elements(stored.len() as u64));
+    group.bench_function("get_all_intensities/decode", |b| {
+        b.iter(|| {
+            black_box(&stored)
+                .iter()
+                .map(|(ptype, json)| {
+                    let payload: PheromonePayload<serde_json::Value> = serde_json::from_str(json).unwrap();
+                    let intensity = payload.intensity();
+                    black_box(intensity > ptype.threshold(&config));
+                    (ptype.label().to_string(), intensity)
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+    group.finish();
+}
+
+fn bench_redis(c: &mut Criterion) {
+    if std::env::var("DRIFTGUARD_BENCH_REDIS").as_deref() != Ok("1") {
+        println!("skipping redis benchmarks: set DRIFTGUARD_BENCH_REDIS=1 to run them");
+        return;
+    }
+    let rt = Runtime::new().unwrap();
+    let redis_url = std::env::var("REDIS_URL")
+        .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+    // Keys of a throwaway swarm, never those of one running on this Redis
+    let mut config = Config::default();
+    config.swarm.name = format!("bench-{}", std::process::id());
+    let config = Arc::new(config);
+
+    let connect = rt.block_on(async {
+        tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            Blackboard::new(&redis_url, config),
+        )
+        .await
+    });
+    let board = match connect {
+        Ok(Ok(board)) => Arc::new(board),
+        _ => {
+            println!("skipping redis benchmarks: no server reachable at {}", redis_url);
+            return;
+        }
+    };
+
+    let snapshot = sample_payload().data;
+
+    let mut group = c.benchmark_group("redis");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("deposit", |b| {
+        b.to_async(&rt).iter(|| {
+            let board = board.clone();
+            let snapshot = snapshot.clone();
+            async move {
+                board
+                    .deposit(PheromoneType::PriceFreshness, snapshot)
+                    .await
+                    .unwrap()
+            }
+        })
+    });
+    group.bench_function("sniff", |b| {
+        b.to_async(&rt).iter(|| {
+            let board = board.clone();
+            async move {
+                board
+                    .sniff::<MarketSnapshot>(PheromoneType::PriceFreshness)
+                    .await
+                    .unwrap()
+            }
+        })
+    });
+    group.bench_function("get_all_intensities", |b| {
+        b.to_async(&rt).iter(|| {
+            let board = board.clone();
+            async move { board.get_all_intensities().await.unwrap() }
+        })
+    });
+    group.finish();
+
+    rt.block_on(async { board.clear_all().await.ok() });
+}
+
+criterion_group!(benches, bench_physics, bench_encoding, bench_intensities, bench_redis);
+criterion_main!(benches);
@@ -1,5 +1,5 @@
 import { useWebSocket } from './hooks/useWebSocket'
-import { useState } from 'react'
+import { useState, useEffect } from 'react'
 import { DashboardLayout } from './components/layout/DashboardLayout'
 import { MetricCard } from './components/layout/MetricCard'
 import { PheromoneMonitor } from './components/PheromoneMonitor'
@@ -20,13 +20,24 @@ function App() {
         agentMetrics,
         pheromoneHistory,
         tradeHistory,
+        targetAllocation,
+        presets,
+        driftForecast,
+        systemStatus,
+        allocationError,
         setAllocation,
+        applyPreset,
         reset,
 
     } = useWebSocket()
 
     const [targetStocksPct, setTargetStocksPct] = useState(60)
 
+    // Follow server-side target changes (e.g. glide path steps)
+    useEffect(() => {
+        if (targetAllocation) setTargetStocksPct(targetAllocation.stocks_pct)
+    }, [targetAllocation])
+
     const handleSetAllocation = (stocksPct: number, bondsPct: number) => {
         setTargetStocksPct(stocksPct)
         setAllocation(stocksPct, bondsPct)
@@ -36,13 +47,16 @@ function App() {
     const sidebar = (
         <ControlPanel
             stocksPct={portfolio?.stocks_pct ?? 60}
+            presets={presets}
+            error={allocationError}
             onSetAllocation={handleSetAllocation}
+            onApplyPreset={applyPreset}
             onReset={reset}
         />
     )
 
     return (
-        <DashboardLayout sidebarContent={sidebar} connected={connected}>
+        <DashboardLayout sidebarContent={sidebar} connected={connected} systemStatus={systemStatus}>
             {/* Main Grid: 3 Columns on large screens, stack on mobile */}
             <div className="grid grid-cols-1 xl:grid-cols-3 gap-6 auto-rows-min">
 
@@ -72,6 +86,7 @@ function App() {
                             currentStocksPct={portfolio?.stocks_pct ?? 60}
                             currentBondsPct={portfolio?.bonds_pct ?? 40}
                             targetStocksPct={targetStocksPct}
+                            forecast={driftForecast}
                         />
                     </MetricCard>
                 </div>
@@ -89,6 +89,9 @@ export function AgentStatus({ pheromones, agentMetrics }: Props) {
 
     const getMetric = (name: string) => agentMetrics.find(m => m.name === name)
 
+    // Agents off the pipeline (e.g. Forecast), shown as they report
+    const sideAgents = agentMetrics.filter(m => !agents.some(a => a.name === m.name))
+
     return (
         <div className="space-y-2">
             {/* Network flow — horizontal pipeline */}
@@ -219,6 +222,23 @@ export function AgentStatus({ pheromones, agentMetrics }: Props) {
                     )
                 })}
             </div>
+
+            {sideAgents.length > 0 && (
+                <div className="flex flex-wrap gap-x-4 gap-y-1 px-1 pt-2 border-t border-white/5">
+                    {sideAgents.map(metric => (
+                        <div key={metric.name} className="flex items-center gap-1.5 min-w-0 text-[10px] font-mono">
+                            <span className={`w-1.5 h-1.5 rounded-full shrink-0 ${metric.is_active ? 'bg-cyan-400' : 'bg-white/10'}`} />
+                            <span className="font-display font-semibold text-white">{metric.name}</span>
+                            {metric.action_count > 0 && (
+                                <span className="text-swarm-muted">{metric.action_count} ops</span>
+                            )}
+                            <span className="text-swarm-muted truncate" title={metric.last_action}>
+                                {metric.last_action}
+                            </span>
+                        </div>
+                    ))}
+                </div>
+            )}
         </div>
     )
 }
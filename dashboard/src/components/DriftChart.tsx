@@ -1,14 +1,25 @@
 import { motion } from 'framer-motion'
-import { Target } from 'lucide-react'
+import { Target, Hourglass } from 'lucide-react'
+import type { DriftForecast } from '../hooks/useWebSocket'
 
 interface Props {
     currentStocksPct: number
     currentBondsPct: number
     targetStocksPct: number
+    forecast?: DriftForecast | null
+}
+
+// "Est. days until rebalance" from the Forecast agent's projection
+function formatDaysUntil(forecast?: DriftForecast | null) {
+    const days = forecast?.days_until_breach
+    if (days === undefined || days === null) return '—'
+    if (days === 0) return 'now'
+    if (days < 1) return `${Math.max(1, Math.round(days * 24))}h`
+    return `${days.toFixed(days < 10 ? 1 : 0)} days`
 }
 
 // Radial gauge showing how far current allocation drifts from target
-export function DriftChart({ currentStocksPct, currentBondsPct, targetStocksPct }: Props) {
+export function DriftChart({ currentStocksPct, currentBondsPct, targetStocksPct, forecast }: Props) {
     const targetBondsPct = 100 - targetStocksPct
     const driftPct = Math.abs(currentStocksPct - targetStocksPct)
 
@@ -187,6 +198,18 @@ export function DriftChart({ currentStocksPct, currentBondsPct, targetStocksPct
                         style={{ left: `${targetBondsPct}%` }}
                     />
                 </div>
+
+                {/* Forecast agent projection */}
+                <div
+                    className="flex items-center justify-between text-[10px] font-mono pt-2"
+                    title={forecast ? `Stocks ${forecast.relative_return_pct_per_day >= 0 ? '+' : ''}${forecast.relative_return_pct_per_day.toFixed(3)}%/day vs bonds over ${forecast.samples} samples` : 'Forecast agent collecting prices'}
+                >
+                    <span className="flex items-center gap-1 text-swarm-muted">
+                        <Hourglass className="w-3 h-3" />
+                        Est. days until rebalance
+                    </span>
+                    <span className="text-white/70">{formatDaysUntil(forecast)}</span>
+                </div>
             </div>
         </div>
     )
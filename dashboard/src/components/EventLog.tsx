@@ -6,7 +6,7 @@ interface Props {
     events: SwarmEvent[]
 }
 
-const EVENT_TYPES = ['Deposited', 'Sniffed', 'Decayed'] as const
+const EVENT_TYPES = ['Deposited', 'Sniffed', 'Decayed', 'Error'] as const
 type EventType = typeof EVENT_TYPES[number]
 
 const eventConfig: Record<EventType, { color: string; bg: string; border: string }> = {
@@ -25,6 +25,11 @@ const eventConfig: Record<EventType, { color: string; bg: string; border: string
         bg: 'bg-orange-500/15',
         border: 'border-orange-500/40',
     },
+    'Error': {
+        color: 'text-red-400',
+        bg: 'bg-red-500/15',
+        border: 'border-red-500/40',
+    },
 }
 
 export function EventLog({ events }: Props) {
@@ -125,8 +130,11 @@ export function EventLog({ events }: Props) {
                                         <span className={`font-bold w-20 shrink-0 ${config.color}`}>
                                             {event.type.toUpperCase()}
                                         </span>
-                                        <span className="text-zinc-300 truncate flex-1">
+                                        <span className="text-zinc-300 truncate flex-1" title={event.error?.message}>
                                             {event.pheromone}
+                                            {event.error && (
+                                                <span className="text-red-400"> · {event.error.category}</span>
+                                            )}
                                         </span>
                                         <span className="text-swarm-muted">
                                             {(event.intensity * 100).toFixed(0)}%
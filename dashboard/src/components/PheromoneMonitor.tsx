@@ -77,6 +77,16 @@ const pheromoneConfig: Record<string, { color: string; icon: string; description
         icon: '💰',
         description: 'Order Fill',
     },
+    'Data Quality': {
+        color: '#2dd4bf', // teal-400
+        icon: '🔍',
+        description: 'Feed Health',
+    },
+    'Drift Forecast': {
+        color: '#f472b6', // pink-400
+        icon: '🔮',
+        description: 'Days to Rebalance',
+    },
 }
 
 export function PheromoneMonitor({ pheromones, history }: Props) {
@@ -45,6 +45,15 @@ export function TradeHistory({ trades }: Props) {
                                         <span>${trade.amount.toFixed(2)}</span>
                                         <span>Δ {trade.drift_before.toFixed(2)}%</span>
                                     </div>
+                                    {trade.skipped ? (
+                                        <div className="text-[10px] text-amber-400 mt-0.5 truncate" title={trade.skipped}>
+                                            {trade.skipped}
+                                        </div>
+                                    ) : trade.explanation && (
+                                        <div className="text-[10px] text-swarm-muted mt-0.5 truncate" title={trade.explanation}>
+                                            {trade.explanation}
+                                        </div>
+                                    )}
                                 </div>
                                 <div className="text-right">
                                     <span className="text-sm font-mono text-zinc-300 block">
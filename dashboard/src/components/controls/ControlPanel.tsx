@@ -1,14 +1,26 @@
 import { useState } from 'react'
 import { motion } from 'framer-motion'
 import { RotateCcw, Save, AlertTriangle } from 'lucide-react'
+import type { AllocationPreset } from '../../hooks/useWebSocket'
 
 interface Props {
     stocksPct: number
+    presets: AllocationPreset[]
+    error: string | null
     onSetAllocation: (stocks: number, bonds: number) => void
+    onApplyPreset: (name: string) => void
     onReset: () => void
 }
 
-export function ControlPanel({ stocksPct, onSetAllocation, onReset }: Props) {
+// Used until the server sends its configured presets
+const fallbackPresets = [
+    { label: '80/20', stocks: 80 },
+    { label: '60/40', stocks: 60 },
+    { label: '40/60', stocks: 40 },
+    { label: '20/80', stocks: 20 },
+]
+
+export function ControlPanel({ stocksPct, presets, error, onSetAllocation, onApplyPreset, onReset }: Props) {
     const [localStocks, setLocalStocks] = useState(stocksPct)
     const [isDirty, setIsDirty] = useState(false)
 
@@ -52,14 +64,30 @@ export function ControlPanel({ stocksPct, onSetAllocation, onReset }: Props) {
                     </div>
                 </div>
 
-                {/* Presets */}
+                {/* Presets (server-defined ones apply immediately) */}
+                {presets.length > 0 ? (
+                    <div className="grid grid-cols-3 gap-2 mb-6">
+                        {presets.map((preset) => (
+                            <button
+                                key={preset.name}
+                                onClick={() => {
+                                    setLocalStocks(preset.stocks_pct)
+                                    setIsDirty(false)
+                                    onApplyPreset(preset.name)
+                                }}
+                                title={`${preset.stocks_pct}/${preset.bonds_pct}`}
+                                className={`px-2 py-2 text-xs font-mono rounded-lg transition-all border border-transparent ${localStocks === preset.stocks_pct
+                                    ? 'bg-drift-500/20 text-drift-300 border-drift-500/30'
+                                    : 'bg-white/5 text-swarm-muted hover:bg-white/10 hover:text-white'
+                                    }`}
+                            >
+                                {preset.name}
+                            </button>
+                        ))}
+                    </div>
+                ) : (
                 <div className="grid grid-cols-4 gap-2 mb-6">
-                    {[
-                        { label: '80/20', stocks: 80 },
-                        { label: '60/40', stocks: 60 },
-                        { label: '40/60', stocks: 40 },
-                        { label: '20/80', stocks: 20 },
-                    ].map((preset) => (
+                    {fallbackPresets.map((preset) => (
                         <button
                             key={preset.label}
                             onClick={() => handleStocksChange(preset.stocks)}
@@ -72,6 +100,13 @@ export function ControlPanel({ stocksPct, onSetAllocation, onReset }: Props) {
                         </button>
                     ))}
                 </div>
+                )}
+
+                {error && (
+                    <div className="mb-4 p-3 rounded-lg bg-red-500/10 border border-red-500/20 text-xs text-red-400 font-mono">
+                        {error}
+                    </div>
+                )}
 
                 {isDirty && (
                     <motion.button
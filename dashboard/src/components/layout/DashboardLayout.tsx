@@ -1,14 +1,23 @@
 import { useState, ReactNode } from 'react'
 import { motion, AnimatePresence } from 'framer-motion'
 import { Settings, X, Github, Hexagon } from 'lucide-react'
+import type { SystemStatus } from '../../hooks/useWebSocket'
 
 interface Props {
     children: ReactNode
     sidebarContent: ReactNode
     connected: boolean
+    systemStatus: SystemStatus | null
 }
 
-export function DashboardLayout({ children, sidebarContent, connected }: Props) {
+const MODE_COLORS: Record<SystemStatus['mode'], string> = {
+    LIVE: 'bg-drift-400 animate-pulse',
+    DEGRADED_DATA: 'bg-amber-400',
+    SIMULATION: 'bg-sky-400',
+    HALTED: 'bg-red-500 animate-pulse',
+}
+
+export function DashboardLayout({ children, sidebarContent, connected, systemStatus }: Props) {
     const [isSettingsOpen, setIsSettingsOpen] = useState(false)
 
     return (
@@ -31,10 +40,13 @@ export function DashboardLayout({ children, sidebarContent, connected }: Props)
                 </div>
 
                 <div className="flex items-center gap-4">
-                    <div className="hidden md:flex items-center gap-2 px-3 py-1.5 rounded-full bg-white/5 border border-white/5">
-                        <div className={`w-2 h-2 rounded-full ${connected ? 'bg-drift-400 animate-pulse' : 'bg-swarm-danger'}`} />
+                    <div
+                        className="hidden md:flex items-center gap-2 px-3 py-1.5 rounded-full bg-white/5 border border-white/5"
+                        title={systemStatus?.reasons.join('\n')}
+                    >
+                        <div className={`w-2 h-2 rounded-full ${!connected ? 'bg-swarm-danger' : systemStatus ? MODE_COLORS[systemStatus.mode] : 'bg-drift-400 animate-pulse'}`} />
                         <span className="text-xs font-mono text-swarm-muted uppercase tracking-wider">
-                            {connected ? 'System Online' : 'Reconnecting'}
+                            {!connected ? 'Reconnecting' : systemStatus ? systemStatus.mode.replace('_', ' ') : 'System Online'}
                         </span>
                     </div>
 
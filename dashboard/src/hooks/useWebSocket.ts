@@ -5,6 +5,12 @@ export interface PheromoneStatus {
     intensity: number
     threshold: number
     is_active: boolean
+    decay_rate: number
+    // Seconds until the intensity reaches the threshold (absent when inactive)
+    time_until_threshold_secs?: number
+    // Projected intensities from now, forecast_step_secs apart
+    forecast: number[]
+    forecast_step_secs: number
 }
 
 export interface PortfolioState {
@@ -23,11 +29,21 @@ export interface SwarmState {
     events: SwarmEvent[]
 }
 
+export interface DriftGuardError {
+    category: 'provider' | 'store' | 'execution' | 'config' | 'data_quality' | 'internal'
+    message: string
+}
+
 export interface SwarmEvent {
     id: string
     type: string
     pheromone: string
     intensity: number
+    detail?: string
+    // Categorized error of Error, DataAnomaly and ExecutionFailed events
+    error?: DriftGuardError
+    // Sniff observations coalesced into this event
+    count: number
     timestamp: Date
 }
 
@@ -37,6 +53,8 @@ export interface AgentMetric {
     action_count: number
     last_action: string
     last_action_time: string | null
+    // Error behind the last action, when it failed
+    last_error?: DriftGuardError
 }
 
 export interface PheromoneHistory {
@@ -54,9 +72,130 @@ export interface TradeLogEntry {
     portfolio_value: number
     drift_before: number
     drift_after: number
+    explanation?: string | null
+    /** Why the leg was not placed (wash-sale guard) */
+    skipped?: string | null
+    /** Plan hash of the permit it was executed or skipped under */
+    plan_hash?: string | null
+}
+
+export interface TargetAllocation {
+    stocks_pct: number
+    bonds_pct: number
+}
+
+export interface AllocationPreset {
+    name: string
+    stocks_pct: number
+    bonds_pct: number
+}
+
+export interface MarketQuote {
+    symbol: string
+    price: number
+    change: number
+    change_pct: number
+    source: 'live' | 'cached' | 'simulated'
+    simulated: boolean
+}
+
+export interface DriftForecast {
+    current_stocks_pct: number
+    target_stocks_pct: number
+    drift_pct: number
+    threshold_pct: number
+    // Trend of stocks' return relative to bonds, % per day
+    relative_return_pct_per_day: number
+    // 0 when drift is already past the threshold, absent when no breach is projected
+    days_until_breach?: number | null
+    samples: number
+    timestamp: string
+}
+
+// Rolling correlations of the assets' daily returns
+export interface CorrelationReading {
+    average: number
+    max: number
+    // Most correlated first
+    pairs: { a: string; b: string; correlation: number }[]
+    returns: number
+    // Diversification breakdown: the Guardian is more cautious
+    spiking: boolean
+    computed_at: string
+}
+
+export interface DailySummary {
+    date: string
+    trades: TradeLogEntry[]
+    trade_volume: number
+    permits: number
+    blocks: number
+    // Absent on the first snapshotted day
+    opening_value?: number | null
+    closing_value: number
+    net_cash_flows: number
+    // Change in value net of cash flows
+    pnl?: number | null
+    pnl_pct?: number | null
+    stocks_pct: number
+    target_stocks_pct: number
+    final_drift_pct: number
+    drawdown_pct: number
+    currency: string
+    generated_at: string
+}
+
+// How far the swarm has degraded; the Guardian refuses permits while HALTED
+export interface SystemStatus {
+    mode: 'LIVE' | 'DEGRADED_DATA' | 'SIMULATION' | 'HALTED'
+    // Every condition found, worst first; empty when LIVE
+    reasons: string[]
+    since: string
+    updated_at: string
 }
 
+// An order the execution queue gave up on after its retries
+export interface DeadLetter {
+    idempotency_key: string
+    leg: {
+        symbol: string
+        side: 'buy' | 'sell'
+        notional: number
+    }
+    attempts: number
+    // Last rejection
+    error: string
+    first_attempt_at: string
+    timestamp: string
+}
+
+// Money arrives as exact decimal strings ("1234.56"); the dashboard only
+// displays it, so it is read into numbers as it comes in
+const money = (value: string | number | null | undefined): number => Number(value ?? 0)
+
+const readPortfolio = (p: any): PortfolioState => ({
+    ...p,
+    total_value: money(p.total_value),
+    stocks_value: money(p.stocks_value),
+    bonds_value: money(p.bonds_value),
+    stocks_pct: money(p.stocks_pct),
+    bonds_pct: money(p.bonds_pct),
+})
+
+const readTrade = (t: any): TradeLogEntry => ({
+    ...t,
+    amount: money(t.amount),
+    price: money(t.price),
+    portfolio_value: money(t.portfolio_value),
+    drift_before: money(t.drift_before),
+    drift_after: money(t.drift_after),
+})
+
 const WS_URL = (import.meta as any).env.VITE_WS_URL || 'ws://localhost:8080/ws'
+// Sent with allocation changes when the server requires an operator token
+const OPERATOR_TOKEN: string | undefined = (import.meta as any).env.VITE_OPERATOR_TOKEN
+// The user's key when the server has [[auth.users]] (browsers can't set WebSocket headers)
+const API_KEY: string | undefined = (import.meta as any).env.VITE_API_KEY
 
 export function useWebSocket() {
     const [state, setState] = useState<SwarmState>({
@@ -69,19 +208,36 @@ export function useWebSocket() {
     const [agentMetrics, setAgentMetrics] = useState<AgentMetric[]>([])
     const [pheromoneHistory, setPheromoneHistory] = useState<Map<string, number[]>>(new Map())
     const [tradeHistory, setTradeHistory] = useState<TradeLogEntry[]>([])
+    const [marketQuotes, setMarketQuotes] = useState<MarketQuote[]>([])
+    const [targetAllocation, setTargetAllocation] = useState<TargetAllocation | null>(null)
+    const [presets, setPresets] = useState<AllocationPreset[]>([])
+    const [driftForecast, setDriftForecast] = useState<DriftForecast | null>(null)
+    const [correlationHistory, setCorrelationHistory] = useState<CorrelationReading[]>([])
+    const [dailySummary, setDailySummary] = useState<DailySummary | null>(null)
+    const [deadLetters, setDeadLetters] = useState<DeadLetter[]>([])
+    const [systemStatus, setSystemStatus] = useState<SystemStatus | null>(null)
+    const [allocationError, setAllocationError] = useState<string | null>(null)
 
     const wsRef = useRef<WebSocket | null>(null)
     const reconnectTimeoutRef = useRef<number | null>(null)
+    // Server timestamp of the newest event seen, for replay after a reconnect
+    const lastEventTimeRef = useRef<string | null>(null)
 
     const connect = useCallback(() => {
         if (wsRef.current?.readyState === WebSocket.OPEN) return
 
-        const ws = new WebSocket(WS_URL)
+        const ws = new WebSocket(
+            API_KEY ? `${WS_URL}${WS_URL.includes('?') ? '&' : '?'}api_key=${encodeURIComponent(API_KEY)}` : WS_URL
+        )
         wsRef.current = ws
 
         ws.onopen = () => {
             console.log('WebSocket connected')
             setState(prev => ({ ...prev, connected: true }))
+            // Backfill anything missed while disconnected
+            if (lastEventTimeRef.current) {
+                ws.send(JSON.stringify({ type: 'replay', since: lastEventTimeRef.current }))
+            }
         }
 
         ws.onclose = () => {
@@ -116,24 +272,54 @@ export function useWebSocket() {
                 } else if (data.type === 'portfolio_update') {
                     setState(prev => ({
                         ...prev,
-                        portfolio: data.portfolio,
+                        portfolio: data.portfolio ? readPortfolio(data.portfolio) : null,
                     }))
                 } else if (data.type === 'agent_metrics') {
                     setAgentMetrics(data.agents)
                 } else if (data.type === 'trade_history') {
-                    setTradeHistory(data.trades)
-                } else if (data.type === 'event') {
-                    const newEvent: SwarmEvent = {
-                        id: crypto.randomUUID(),
-                        type: data.event_type,
-                        pheromone: data.pheromone,
-                        intensity: data.intensity,
-                        timestamp: new Date(),
+                    setTradeHistory(data.trades.map(readTrade))
+                } else if (data.type === 'target_update') {
+                    setTargetAllocation(data.target)
+                    setAllocationError(null)
+                } else if (data.type === 'system_mode') {
+                    setSystemStatus(data.status)
+                } else if (data.type === 'forecast_update') {
+                    setDriftForecast(data.forecast)
+                } else if (data.type === 'correlation_update') {
+                    setCorrelationHistory(data.history)
+                } else if (data.type === 'daily_summary') {
+                    setDailySummary(data.summary)
+                } else if (data.type === 'dead_letters') {
+                    setDeadLetters(data.orders.map((o: any) => ({ ...o, leg: { ...o.leg, notional: money(o.leg.notional) } })))
+                } else if (data.type === 'presets') {
+                    setPresets(data.presets)
+                } else if (data.type === 'error') {
+                    setAllocationError(data.message)
+                } else if (data.type === 'market_update') {
+                    setMarketQuotes(data.quotes)
+                } else if (data.type === 'event' || data.type === 'replay') {
+                    const incoming = data.type === 'event' ? [data] : data.events
+                    const newEvents: SwarmEvent[] = incoming.map((e: any) => ({
+                        id: `${e.timestamp}-${e.seq}`,
+                        type: e.event_type,
+                        pheromone: e.pheromone,
+                        intensity: e.intensity,
+                        detail: e.detail,
+                        error: e.error,
+                        count: e.count ?? 1,
+                        timestamp: new Date(e.timestamp),
+                    })).reverse()
+                    if (incoming.length > 0) {
+                        lastEventTimeRef.current = incoming[incoming.length - 1].timestamp
                     }
-                    setState(prev => ({
-                        ...prev,
-                        events: [newEvent, ...prev.events].slice(0, 50), // Keep last 50 events
-                    }))
+                    setState(prev => {
+                        const seen = new Set(prev.events.map(e => e.id))
+                        const fresh = newEvents.filter(e => !seen.has(e.id))
+                        return {
+                            ...prev,
+                            events: [...fresh, ...prev.events].slice(0, 50), // Keep last 50 events
+                        }
+                    })
                 }
             } catch (e) {
                 console.error('Failed to parse message:', e)
@@ -154,13 +340,25 @@ export function useWebSocket() {
                 type: 'set_allocation',
                 stocks_pct: stocksPct,
                 bonds_pct: bondsPct,
+                token: OPERATOR_TOKEN,
+            }))
+        }
+    }, [])
+
+    const applyPreset = useCallback((preset: string) => {
+        if (wsRef.current?.readyState === WebSocket.OPEN) {
+            wsRef.current.send(JSON.stringify({
+                type: 'apply_preset',
+                preset,
+                token: OPERATOR_TOKEN,
             }))
         }
     }, [])
 
     const reset = useCallback(() => {
         if (wsRef.current?.readyState === WebSocket.OPEN) {
-            wsRef.current.send(JSON.stringify({ type: 'reset' }))
+            // Pheromones only; portfolio and hard resets need a token and confirmation
+            wsRef.current.send(JSON.stringify({ type: 'reset', scope: 'soft' }))
         }
     }, [])
 
@@ -174,7 +372,17 @@ export function useWebSocket() {
         agentMetrics,
         pheromoneHistory: Array.from(pheromoneHistory.entries()).map(([name, readings]) => ({ name, readings })),
         tradeHistory,
+        marketQuotes,
+        targetAllocation,
+        presets,
+        driftForecast,
+        correlationHistory,
+        dailySummary,
+        deadLetters,
+        systemStatus,
+        allocationError,
         setAllocation,
+        applyPreset,
         reset,
         reconnect: connect,
     }
@@ -2,10 +2,16 @@
 //! 
 //! The "brain" of the swarm. Sniffs for fresh price data, calculates
 //! portfolio drift, and deposits Rebalance_Opportunity if drift exceeds threshold.
+//! Each tick reads its inputs through one `snapshot_view`, so the prices,
+//! portfolio, target and baseline it compares are from the same instant.
+//! With asset groups configured, group and member drift (see `groups`)
+//! decide in place of the stocks/bonds sleeves.
 
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::time::{interval, Duration};
@@ -13,9 +19,22 @@ use tracing::{debug, info, warn};
 
 use crate::agents::sensor::MarketSnapshot;
 use crate::agents::Agent;
-use crate::core::blackboard::{AgentMetrics, PortfolioState};
+use crate::core::allocation;
+use crate::core::analysis::{self, AnalysisPolicy, PriceInputs};
+use crate::core::calendar;
+use crate::core::groups;
+use crate::core::holdings;
+use crate::core::blackboard::{AgentMetrics, PortfolioState, RebalanceBaseline, TargetAllocation};
 use crate::core::physics::PheromoneType;
+use crate::core::pipeline::{self, AgentRole};
+use crate::core::rebalance;
+use crate::core::runtime;
+use crate::core::config::{CostConfig, OpportunityScoringConfig, RebalanceBenefitConfig};
+use crate::core::money;
+use crate::core::scripting::{self, ScriptHook};
 use crate::core::{Blackboard, Config};
+use crate::execution::OrderLeg;
+use crate::market::daily_history::DailyBar;
 
 /// Drift analysis payload
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +46,478 @@ pub struct DriftAnalysis {
     pub drift_pct: f64,
     pub recommended_action: String,
     pub market_snapshot: MarketSnapshot,
+    /// Structured "why" behind the recommendation
+    #[serde(default)]
+    pub explanation: DriftExplanation,
+    /// Multi-factor score, when opportunity scoring is enabled
+    #[serde(default)]
+    pub opportunity: Option<OpportunityScore>,
+    /// Estimated benefit of rebalancing vs its cost
+    #[serde(default)]
+    pub cost_benefit: Option<CostBenefit>,
+    /// What caused this opportunity to be raised
+    #[serde(default)]
+    pub trigger: RebalanceTrigger,
+    /// Score from the `[scripting] analyst_score` script, which decided
+    /// in place of the built-in rule
+    #[serde(default)]
+    pub script_score: Option<f64>,
+    /// When the analysis was made (none from versions before it was recorded)
+    #[serde(default)]
+    pub analyzed_at: Option<String>,
+}
+
+/// Why the Analyst raised a rebalance opportunity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RebalanceTrigger {
+    /// Drift (or opportunity score) crossed its threshold
+    #[default]
+    Drift,
+    /// Scheduled calendar rebalance, regardless of drift
+    Calendar,
+    /// An asset group or group member drifted past the threshold
+    Groups,
+}
+
+/// Expected benefit of a rebalance compared with its estimated cost
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostBenefit {
+    /// Annualized tracking error removed by rebalancing (%)
+    pub tracking_error_reduction_pct: f64,
+    /// Dollar value of removing that tracking error over the horizon
+    pub expected_benefit: f64,
+    /// Commissions + spread for the trade ($)
+    pub estimated_cost: f64,
+}
+
+impl CostBenefit {
+    /// Cost is that of the planned `legs` (see `rebalance::leg_costs`)
+    pub fn estimate(
+        drift_pct: f64,
+        total_value: f64,
+        legs: &[OrderLeg],
+        benefit: &RebalanceBenefitConfig,
+        costs: &CostConfig,
+    ) -> Self {
+        // Volatility of the stocks-minus-bonds spread
+        let spread_variance = benefit.stocks_volatility.powi(2)
+            + benefit.bonds_volatility.powi(2)
+            - 2.0 * benefit.correlation * benefit.stocks_volatility * benefit.bonds_volatility;
+        let tracking_error = drift_pct / 100.0 * spread_variance.max(0.0).sqrt();
+        
+        Self {
+            tracking_error_reduction_pct: tracking_error * 100.0,
+            expected_benefit: 0.5
+                * benefit.risk_aversion
+                * tracking_error.powi(2)
+                * total_value
+                * benefit.horizon_years,
+            estimated_cost: money::to_f64(rebalance::leg_costs(legs, costs)),
+        }
+    }
+    
+    /// Whether the trade costs more than it is expected to be worth
+    pub fn is_unprofitable(&self) -> bool {
+        self.estimated_cost > self.expected_benefit
+    }
+}
+
+/// Raw inputs to the opportunity score
+#[derive(Debug, Clone)]
+pub struct OpportunityInputs {
+    pub drift_pct: f64,
+    pub drift_threshold: f64,
+    /// Relative return of the overweight asset vs the other (%, smoothed);
+    /// positive means drift is still growing
+    pub aligned_momentum_pct: f64,
+    pub days_since_rebalance: f64,
+    pub total_value: f64,
+}
+
+/// Multi-factor opportunity score and its components, each in [0, 1]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpportunityScore {
+    pub score: f64,
+    pub drift_factor: f64,
+    pub momentum_factor: f64,
+    pub staleness_factor: f64,
+    pub cost_factor: f64,
+    /// Estimated commissions + spread for the full rebalance ($)
+    pub estimated_cost: f64,
+}
+
+impl OpportunityScore {
+    /// Cost is that of the planned `legs` (see `rebalance::leg_costs`)
+    pub fn compute(
+        inputs: &OpportunityInputs,
+        legs: &[OrderLeg],
+        scoring: &OpportunityScoringConfig,
+        costs: &CostConfig,
+    ) -> Self {
+        let unit = |x: f64| if x.is_finite() { x.clamp(0.0, 1.0) } else { 0.0 };
+        
+        let drift_factor = unit(inputs.drift_pct / (2.0 * inputs.drift_threshold));
+        let momentum_factor = if scoring.momentum_scale_pct > 0.0 {
+            unit(0.5 + 0.5 * (inputs.aligned_momentum_pct / scoring.momentum_scale_pct).tanh())
+        } else {
+            0.5
+        };
+        let staleness_factor = unit(inputs.days_since_rebalance / scoring.stale_after_days);
+        
+        let turnover = money::to_f64(legs.iter().map(|leg| leg.notional).sum());
+        let estimated_cost = money::to_f64(rebalance::leg_costs(legs, costs));
+        let cost_factor = if turnover > 0.0 {
+            let effective_bps = estimated_cost / turnover * 10_000.0;
+            unit(1.0 - effective_bps / scoring.max_cost_bps)
+        } else {
+            0.0
+        };
+        
+        let weights = [
+            (scoring.drift_weight, drift_factor),
+            (scoring.momentum_weight, momentum_factor),
+            (scoring.staleness_weight, staleness_factor),
+            (scoring.cost_weight, cost_factor),
+        ];
+        let total_weight: f64 = weights.iter().map(|(w, _)| w.max(0.0)).sum();
+        let score = if total_weight > 0.0 {
+            weights.iter().map(|(w, f)| w.max(0.0) * f).sum::<f64>() / total_weight
+        } else {
+            0.0
+        };
+        
+        Self {
+            score: unit(score),
+            drift_factor,
+            momentum_factor,
+            staleness_factor,
+            cost_factor,
+            estimated_cost,
+        }
+    }
+}
+
+/// Smoothed relative return of stocks vs bonds between snapshots
+#[derive(Debug, Default)]
+struct MomentumTracker {
+    last_prices: Option<(f64, f64)>,
+    ema_pct: f64,
+}
+
+impl MomentumTracker {
+    const ALPHA: f64 = 0.3;
+    
+    /// Feed new prices and return the smoothed stocks-minus-bonds return (%)
+    fn update(&mut self, stocks_price: f64, bonds_price: f64) -> f64 {
+        if let Some((prev_stocks, prev_bonds)) = self.last_prices {
+            if prev_stocks > 0.0 && prev_bonds > 0.0 {
+                let relative = (stocks_price / prev_stocks - bonds_price / prev_bonds) * 100.0;
+                self.ema_pct = Self::ALPHA * relative + (1.0 - Self::ALPHA) * self.ema_pct;
+            }
+        }
+        self.last_prices = Some((stocks_price, bonds_price));
+        self.ema_pct
+    }
+}
+
+/// Current vs target weight of one asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetWeight {
+    pub symbol: String,
+    pub current_pct: f64,
+    pub target_pct: f64,
+    /// current − target, in percentage points
+    pub deviation_pct: f64,
+    /// Where that deviation came from since the last rebalance
+    #[serde(default)]
+    pub attribution: DriftAttribution,
+}
+
+/// Tolerance band that an asset's weight broke out of
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandBreach {
+    pub symbol: String,
+    pub deviation_pct: f64,
+    /// Half-width of the band (the drift threshold)
+    pub band_pct: f64,
+}
+
+/// Signed contributions (percentage points) to one asset's deviation since
+/// the last rebalance
+///
+/// Contributions and withdrawals restart the baseline like a trade does, so
+/// any weight change since it is a price move.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DriftAttribution {
+    /// Weight change from relative price moves
+    pub price_moves_pct: f64,
+    /// Deviation created by moving the target itself
+    pub target_change_pct: f64,
+}
+
+/// Structured explanation attached to every drift analysis
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DriftExplanation {
+    pub assets: Vec<AssetWeight>,
+    pub band_breached: Option<BandBreach>,
+    /// One-line human-readable summary
+    pub summary: String,
+}
+
+impl DriftExplanation {
+    /// Explain the current deviation from target
+    /// 
+    /// Without a baseline, all deviation is attributed to price moves.
+    pub fn build(
+        portfolio: &PortfolioState,
+        target: &TargetAllocation,
+        baseline: Option<&RebalanceBaseline>,
+        config: &Config,
+    ) -> Self {
+        let band_pct = config.portfolio.drift_threshold;
+        let (stocks_pct, bonds_pct) = (money::to_f64(portfolio.stocks_pct), money::to_f64(portfolio.bonds_pct));
+        let weight = |symbol: &str, current_pct: f64, target_pct: f64, base: Option<(f64, f64)>| AssetWeight {
+            symbol: symbol.to_string(),
+            current_pct,
+            target_pct,
+            deviation_pct: current_pct - target_pct,
+            attribution: match base {
+                Some((base_pct, base_target_pct)) => DriftAttribution {
+                    price_moves_pct: current_pct - base_pct,
+                    target_change_pct: base_target_pct - target_pct,
+                },
+                None => DriftAttribution {
+                    price_moves_pct: current_pct - target_pct,
+                    ..Default::default()
+                },
+            },
+        };
+        let assets = vec![
+            weight(
+                &config.portfolio.stocks_symbol,
+                stocks_pct,
+                target.stocks_pct,
+                baseline.map(|b| (b.stocks_pct, b.target_stocks_pct)),
+            ),
+            weight(
+                &config.portfolio.bonds_symbol,
+                bonds_pct,
+                target.bonds_pct,
+                baseline.map(|b| (b.bonds_pct, b.target_bonds_pct)),
+            ),
+        ];
+        
+        let band_breached = assets
+            .iter()
+            .filter(|a| a.deviation_pct.abs() > band_pct)
+            .min_by(|a, b| b.deviation_pct.abs().total_cmp(&a.deviation_pct.abs()))
+            .map(|a| BandBreach {
+                symbol: a.symbol.clone(),
+                deviation_pct: a.deviation_pct,
+                band_pct,
+            });
+        
+        let summary = match &band_breached {
+            Some(b) => {
+                let attribution = assets
+                    .iter()
+                    .find(|a| a.symbol == b.symbol)
+                    .map(|a| a.attribution.clone())
+                    .unwrap_or_default();
+                format!(
+                    "{} is {:+.1}pp from target, outside the ±{:.1}pp band (price moves {:+.1}pp, target change {:+.1}pp)",
+                    b.symbol,
+                    b.deviation_pct,
+                    b.band_pct,
+                    attribution.price_moves_pct,
+                    attribution.target_change_pct
+                )
+            }
+            None => format!("All assets within the ±{:.1}pp band", band_pct),
+        };
+        
+        Self {
+            assets,
+            band_breached,
+            summary,
+        }
+    }
+}
+
+/// Inputs handed to an `analyst_score` script's `score(ctx)`
+#[derive(Debug, Serialize)]
+struct ScoreContext<'a> {
+    drift_pct: f64,
+    drift_threshold: f64,
+    /// Scores above this wake the Guardian
+    activation_threshold: f64,
+    portfolio: ScriptPortfolio<'a>,
+    target: &'a TargetAllocation,
+    /// Built-in score, when opportunity scoring is enabled
+    opportunity: Option<&'a OpportunityScore>,
+    cost_benefit: &'a CostBenefit,
+}
+
+/// The portfolio as numbers a script can do arithmetic on, where its
+/// serialized decimals would be strings
+#[derive(Debug, Serialize)]
+struct ScriptPortfolio<'a> {
+    total_value: f64,
+    stocks_value: f64,
+    bonds_value: f64,
+    stocks_pct: f64,
+    bonds_pct: f64,
+    last_trade_time: Option<&'a str>,
+}
+
+impl<'a> From<&'a PortfolioState> for ScriptPortfolio<'a> {
+    fn from(state: &'a PortfolioState) -> Self {
+        Self {
+            total_value: money::to_f64(state.total_value),
+            stocks_value: money::to_f64(state.stocks_value),
+            bonds_value: money::to_f64(state.bonds_value),
+            stocks_pct: money::to_f64(state.stocks_pct),
+            bonds_pct: money::to_f64(state.bonds_pct),
+            last_trade_time: state.last_trade_time.as_deref(),
+        }
+    }
+}
+
+/// The Analyst's reading of the current drift, before calendar schedules
+#[derive(Debug, Clone)]
+pub struct DriftAssessment {
+    pub stocks_pct: f64,
+    pub bonds_pct: f64,
+    pub drift: f64,
+    pub opportunity: Option<OpportunityScore>,
+    pub cost_benefit: CostBenefit,
+    pub script_score: Option<f64>,
+    /// Score that decided (scripted or built-in); none when the drift
+    /// threshold decided
+    pub score: Option<f64>,
+    /// Whether drift calls for a rebalance
+    pub triggered: bool,
+    /// Strength of the RebalanceOpportunity deposit for a drift trigger
+    pub intensity: f64,
+}
+
+impl DriftAssessment {
+    /// Whether a drift-triggered rebalance is dropped as not worth its cost
+    pub fn is_suppressed(&self, config: &Config) -> bool {
+        self.triggered && config.rebalance_benefit.suppress_unprofitable && self.cost_benefit.is_unprofitable()
+    }
+}
+
+/// Drift scoring under one configuration: the momentum it tracks across
+/// snapshots and its `analyst_score` script. The Analyst owns one; shadow
+/// mode runs a second under other parameters.
+pub struct DriftModel {
+    config: Arc<Config>,
+    momentum: MomentumTracker,
+    /// User script replacing the opportunity score
+    score_script: Option<ScriptHook>,
+}
+
+impl DriftModel {
+    pub fn new(config: Arc<Config>) -> Self {
+        let score_script = scripting::load_hook(
+            config.scripting.analyst_score.as_deref(),
+            "score",
+            &config.scripting,
+        );
+        Self {
+            config,
+            momentum: MomentumTracker::default(),
+            score_script,
+        }
+    }
+    
+    /// Prime momentum with the daily closes both symbols have in common,
+    /// so it doesn't start from zero; returns the closes used
+    pub fn seed_momentum(&mut self, stocks: &[DailyBar], bonds: &[DailyBar]) -> usize {
+        let bonds: HashMap<NaiveDate, f64> = bonds.iter().map(|bar| (bar.date, bar.close)).collect();
+        let mut seeded = 0;
+        for bar in stocks {
+            if let Some(&bond_close) = bonds.get(&bar.date) {
+                self.momentum.update(bar.close, bond_close);
+                seeded += 1;
+            }
+        }
+        seeded
+    }
+    
+    /// Assess drift from target and whether it calls for a rebalance (see
+    /// `analysis::evaluate`), tracking momentum across snapshots and
+    /// letting the `analyst_score` script rescore
+    pub fn assess(
+        &mut self,
+        snapshot: &MarketSnapshot,
+        portfolio: &PortfolioState,
+        target: &TargetAllocation,
+        baseline: Option<&RebalanceBaseline>,
+    ) -> DriftAssessment {
+        let prices = PriceInputs {
+            momentum_pct: self.momentum(snapshot),
+            now: Some(chrono::Utc::now()),
+        };
+        let policy = AnalysisPolicy::from_config(&self.config);
+        let mut assessment = analysis::evaluate(portfolio, &prices, target, baseline, &policy);
+        if let Some(o) = &assessment.opportunity {
+            debug!(
+                "Analyst: Opportunity score {:.2} (drift {:.2}, momentum {:.2}, staleness {:.2}, cost {:.2})",
+                o.score, o.drift_factor, o.momentum_factor, o.staleness_factor, o.cost_factor
+            );
+        }
+        
+        let script_score = self.script_score(&ScoreContext {
+            drift_pct: assessment.drift,
+            drift_threshold: self.config.portfolio.drift_threshold,
+            activation_threshold: policy.activation_threshold,
+            portfolio: portfolio.into(),
+            target,
+            opportunity: assessment.opportunity.as_ref(),
+            cost_benefit: &assessment.cost_benefit,
+        });
+        if let Some(score) = script_score {
+            debug!("Analyst: Script score {:.2}", score);
+            analysis::rescore(&mut assessment, score, &policy);
+        }
+        assessment
+    }
+    
+    /// Score from the user script, if one is loaded; a failing script
+    /// leaves the decision to the built-in rule
+    fn script_score(&self, ctx: &ScoreContext<'_>) -> Option<f64> {
+        let script = self.score_script.as_ref()?;
+        match script.call_f64("score", ctx) {
+            Ok(score) if score.is_finite() => Some(score.clamp(0.0, 1.0)),
+            Ok(score) => {
+                warn!("Analyst: Script {} returned {}. Using the built-in rule.", script.path(), score);
+                None
+            }
+            Err(e) => {
+                warn!("Analyst: {:#}. Using the built-in rule.", e);
+                None
+            }
+        }
+    }
+    
+    /// Smoothed stocks-minus-bonds return up to `snapshot` (tracked only
+    /// while opportunity scoring, the one thing that reads it, is enabled)
+    fn momentum(&mut self, snapshot: &MarketSnapshot) -> f64 {
+        if !self.config.opportunity_scoring.enabled {
+            return 0.0;
+        }
+        let prices = (
+            snapshot.price(&self.config.portfolio.stocks_symbol),
+            snapshot.price(&self.config.portfolio.bonds_symbol),
+        );
+        match prices {
+            (Some(stocks), Some(bonds)) => self.momentum.update(stocks, bonds),
+            _ => 0.0,
+        }
+    }
 }
 
 pub struct AnalystAgent {
@@ -35,12 +526,14 @@ pub struct AnalystAgent {
     running: AtomicBool,
     active: AtomicBool,
     action_count: AtomicU64,
+    model: tokio::sync::Mutex<DriftModel>,
 }
 
 impl AnalystAgent {
     pub fn new(config: Arc<Config>) -> Self {
         Self {
             name: "Analyst".to_string(),
+            model: tokio::sync::Mutex::new(DriftModel::new(config.clone())),
             config,
             running: AtomicBool::new(false),
             active: AtomicBool::new(false),
@@ -48,6 +541,26 @@ impl AnalystAgent {
         }
     }
 
+    /// The calendar period owed a scheduled rebalance, if one is due and
+    /// has not already fired
+    async fn calendar_period_due(&self, board: &Blackboard) -> Result<Option<String>> {
+        let schedule = &self.config.portfolio.calendar_rebalance;
+        if !schedule.enabled {
+            return Ok(None);
+        }
+        
+        let Ok(at) = chrono::NaiveTime::parse_from_str(&schedule.at, "%H:%M") else {
+            warn!("Analyst: Invalid calendar_rebalance.at '{}', skipping schedule", schedule.at);
+            return Ok(None);
+        };
+        let Some(period) = calendar::due_period(chrono::Utc::now(), schedule.frequency, at, self.config.exchange.timezone) else {
+            return Ok(None);
+        };
+        
+        let last = board.get_last_calendar_rebalance().await?;
+        Ok((last.as_deref() != Some(period.as_str())).then_some(period))
+    }
+
     /// Get the number of drift analyses performed
     pub fn action_count(&self) -> u64 {
         self.action_count.load(Ordering::SeqCst)
@@ -76,76 +589,212 @@ impl Agent for AnalystAgent {
         
         info!("🧠 Analyst agent started (sniffing every {}ms)", self.config.agent.sniff_interval_ms);
         
+        if self.config.opportunity_scoring.enabled {
+            let portfolio = &self.config.portfolio;
+            let stocks = board.get_daily_history(&portfolio.stocks_symbol).await?;
+            let bonds = board.get_daily_history(&portfolio.bonds_symbol).await?;
+            if let (Some(stocks), Some(bonds)) = (stocks, bonds) {
+                let seeded = self.model.lock().await.seed_momentum(&stocks.bars, &bonds.bars);
+                info!("🧠 Analyst: Momentum seeded from {} daily closes", seeded);
+            }
+        }
+        
         while self.running.load(Ordering::SeqCst) {
-            ticker.tick().await;
+            runtime::tick(&mut ticker).await;
             
-            // Sniff for fresh market data
-            let market_data: Option<MarketSnapshot> = board
-                .sniff(PheromoneType::PriceFreshness)
-                .await?;
+            // Read the tick's inputs at one instant, then sniff for fresh market data
+            let view = board.snapshot_view().await?;
+            let market_data: Option<MarketSnapshot> = view.sniff(PheromoneType::PriceFreshness)?;
             
             if let Some(snapshot) = market_data {
+                let missing = pipeline::inactive_in_view(&view, &self.config, AgentRole::Analyst, &[PheromoneType::PriceFreshness]);
+                if !missing.is_empty() {
+                    debug!("Analyst: Waiting on {:?}. Dormant.", missing);
+                    continue;
+                }
+                
                 self.active.store(true, Ordering::SeqCst);
                 
-                // Get current portfolio state
-                let portfolio = board.get_portfolio_state().await?.unwrap_or_default();
-                let target = board.get_target_allocation().await?;
+                // Portfolio state, target and baseline as of the same read
+                let portfolio = view.portfolio_state().cloned().unwrap_or_default();
+                let configured = view.target_allocation().clone();
+                let baseline = view.rebalance_baseline().cloned();
+                
+                // Locked holdings stay put; rebalance the rest toward what's reachable
+                let target = holdings::achievable_target(&board, &portfolio, &configured, Some(&snapshot)).await?;
+                if target != configured {
+                    debug!(
+                        "🔒 Analyst: Locked holdings make {:.1}%/{:.1}% unreachable; targeting {:.1}%/{:.1}%",
+                        configured.stocks_pct, configured.bonds_pct, target.stocks_pct, target.bonds_pct
+                    );
+                }
                 
                 // Calculate drift
-                let drift = (portfolio.stocks_pct - target.stocks_pct).abs();
+                let assessment = self
+                    .model
+                    .lock()
+                    .await
+                    .assess(&snapshot, &portfolio, &target, baseline.as_ref());
+                let suppressed = assessment.is_suppressed(&self.config);
+                let DriftAssessment {
+                    stocks_pct,
+                    bonds_pct,
+                    drift,
+                    opportunity,
+                    cost_benefit,
+                    script_score,
+                    score,
+                    triggered,
+                    intensity,
+                } = assessment;
+                
+                // With asset groups, group and member drift decide instead of the sleeves
+                let group_drift = groups::current(&board, &self.config, |symbol| snapshot.price(symbol)).await?;
+                let (drift, triggered, suppressed) = match &group_drift {
+                    Some(tree) => (tree.max_drift_pct(), tree.breached, false),
+                    None => (drift, triggered, suppressed),
+                };
                 
                 info!(
                     "📈 Analyst: Current allocation {:.1}%/{:.1}% vs Target {:.1}%/{:.1}% = Drift {:.1}%",
-                    portfolio.stocks_pct,
-                    portfolio.bonds_pct,
+                    stocks_pct,
+                    bonds_pct,
                     target.stocks_pct,
                     target.bonds_pct,
                     drift
                 );
                 
-                if drift > self.config.portfolio.drift_threshold {
-                    let action = if portfolio.stocks_pct > target.stocks_pct {
+                // A due calendar period forces a rebalance even inside the band
+                let calendar_period = if triggered || drift <= 0.0 {
+                    None
+                } else {
+                    self.calendar_period_due(&board).await?
+                };
+                let trigger = if triggered && group_drift.is_some() {
+                    Some(RebalanceTrigger::Groups)
+                } else if triggered {
+                    Some(RebalanceTrigger::Drift)
+                } else {
+                    calendar_period.as_ref().map(|_| RebalanceTrigger::Calendar)
+                };
+                // Never recommend moving to a target outside an asset's limits
+                let limits = allocation::check_asset_limits(&target, &self.config.portfolio);
+                
+                if let (Some(_), Err(e)) = (&trigger, &limits) {
+                    warn!("🚧 Analyst: Drift {:.1}% but target {}. No recommendation.", drift, e);
+                    let _ = board.set_agent_metrics(&AgentMetrics {
+                        name: "Analyst".to_string(),
+                        is_active: true,
+                        action_count: self.action_count.load(Ordering::SeqCst),
+                        last_action: format!("Held: target {}", e),
+                        last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                        last_error: None,
+                    }).await;
+                } else if suppressed {
+                    info!(
+                        "💸 Analyst: Drift {:.1}% not worth trading — cost ${:.2} > benefit ${:.2}",
+                        drift, cost_benefit.estimated_cost, cost_benefit.expected_benefit
+                    );
+                    let _ = board.set_agent_metrics(&AgentMetrics {
+                        name: "Analyst".to_string(),
+                        is_active: true,
+                        action_count: self.action_count.load(Ordering::SeqCst),
+                        last_action: format!(
+                            "Suppressed: cost ${:.2} > benefit ${:.2}",
+                            cost_benefit.estimated_cost, cost_benefit.expected_benefit
+                        ),
+                        last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                        last_error: None,
+                    }).await;
+                } else if let Some(trigger) = trigger {
+                    let action = if trigger == RebalanceTrigger::Groups {
+                        "Rebalance asset groups"
+                    } else if stocks_pct > target.stocks_pct {
                         "SELL stocks, BUY bonds"
                     } else {
                         "BUY stocks, SELL bonds"
                     };
                     
-                    warn!(
-                        "⚠️ Analyst: Drift {:.1}% exceeds threshold {:.1}%! Recommending: {}",
-                        drift,
-                        self.config.portfolio.drift_threshold,
-                        action
+                    let intensity = match trigger {
+                        RebalanceTrigger::Drift => intensity,
+                        RebalanceTrigger::Calendar | RebalanceTrigger::Groups => 1.0,
+                    };
+                    
+                    if let Some(period) = &calendar_period {
+                        warn!(
+                            "📅 Analyst: Scheduled {} rebalance (drift {:.1}%). Recommending: {}",
+                            period, drift, action
+                        );
+                    } else {
+                        warn!(
+                            "⚠️ Analyst: Drift {:.1}% (threshold {:.1}%, intensity {:.2})! Recommending: {}",
+                            drift,
+                            self.config.portfolio.drift_threshold,
+                            intensity,
+                            action
+                        );
+                    }
+                    
+                    let explanation = DriftExplanation::build(
+                        &portfolio,
+                        &target,
+                        baseline.as_ref(),
+                        &self.config,
                     );
+                    info!("🧾 Analyst: {}", explanation.summary);
                     
                     let analysis = DriftAnalysis {
-                        current_stocks_pct: portfolio.stocks_pct,
-                        current_bonds_pct: portfolio.bonds_pct,
+                        current_stocks_pct: stocks_pct,
+                        current_bonds_pct: bonds_pct,
                         target_stocks_pct: target.stocks_pct,
                         target_bonds_pct: target.bonds_pct,
                         drift_pct: drift,
                         recommended_action: action.to_string(),
                         market_snapshot: snapshot,
+                        explanation,
+                        opportunity,
+                        cost_benefit: Some(cost_benefit),
+                        trigger,
+                        script_score,
+                        analyzed_at: Some(chrono::Utc::now().to_rfc3339()),
                     };
                     
                     // Deposit opportunity for Guardian
-                    board.deposit(PheromoneType::RebalanceOpportunity, analysis).await?;
+                    if self.config.pipeline.wiring(AgentRole::Analyst).deposits(PheromoneType::RebalanceOpportunity) {
+                        board
+                            .deposit_with_intensity(PheromoneType::RebalanceOpportunity, analysis, intensity)
+                            .await?;
+                    }
                     self.action_count.fetch_add(1, Ordering::SeqCst);
                     
+                    let last_action = match &calendar_period {
+                        Some(period) => {
+                            board.set_last_calendar_rebalance(period).await?;
+                            format!("Scheduled {} rebalance — {}", period, action)
+                        }
+                        None => format!("Drift {:.1}% — {}", drift, action),
+                    };
                     let _ = board.set_agent_metrics(&AgentMetrics {
                         name: "Analyst".to_string(),
                         is_active: true,
                         action_count: self.action_count.load(Ordering::SeqCst),
-                        last_action: format!("Drift {:.1}% — {}", drift, action),
+                        last_action,
                         last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                        last_error: None,
                     }).await;
                 } else {
                     debug!("Analyst: Drift {:.1}% within threshold, no action needed", drift);
+                    let last_action = match score {
+                        Some(score) => format!("Drift {:.1}% — score {:.2} below activation", drift, score),
+                        None => format!("Drift {:.1}% within threshold", drift),
+                    };
                     let _ = board.set_agent_metrics(&AgentMetrics {
                         name: "Analyst".to_string(),
                         is_active: true,
                         action_count: self.action_count.load(Ordering::SeqCst),
-                        last_action: format!("Drift {:.1}% within threshold", drift),
+                        last_action,
                         last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                        last_error: None,
                     }).await;
                 }
                 
@@ -160,3 +809,115 @@ impl Agent for AnalystAgent {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn portfolio(stocks_pct: f64) -> PortfolioState {
+        PortfolioState {
+            stocks_pct: money::from_f64(stocks_pct),
+            bonds_pct: money::from_f64(100.0 - stocks_pct),
+            ..Default::default()
+        }
+    }
+    
+    fn target(stocks_pct: f64) -> TargetAllocation {
+        TargetAllocation {
+            stocks_pct,
+            bonds_pct: 100.0 - stocks_pct,
+        }
+    }
+    
+    #[test]
+    fn test_explanation_attributes_target_change() {
+        let config = Config::default();
+        let current = portfolio(60.0);
+        let baseline = RebalanceBaseline::new(&current, &target(60.0));
+        
+        // User moved the target from 60% to 50% stocks
+        let explanation = DriftExplanation::build(&current, &target(50.0), Some(&baseline), &config);
+        
+        let breach = explanation.band_breached.expect("band should be breached");
+        assert_eq!(breach.symbol, "SPY");
+        assert!((breach.deviation_pct - 10.0).abs() < 1e-9);
+        let (stocks, bonds) = (&explanation.assets[0].attribution, &explanation.assets[1].attribution);
+        assert_eq!(stocks.price_moves_pct, 0.0);
+        assert!((stocks.target_change_pct - 10.0).abs() < 1e-9);
+        assert!((bonds.target_change_pct + 10.0).abs() < 1e-9);
+    }
+    
+    /// A rebalance's legs: drift% of `total_value` each way
+    fn legs(drift_pct: f64, total_value: f64) -> Vec<OrderLeg> {
+        let delta = money::from_f64(drift_pct / 100.0 * total_value);
+        rebalance::plan_legs(&Config::default().portfolio, -delta, delta)
+    }
+    
+    #[test]
+    fn test_opportunity_score_grows_with_drift() {
+        let scoring = OpportunityScoringConfig::default();
+        let costs = CostConfig::default();
+        let inputs = |drift_pct| OpportunityInputs {
+            drift_pct,
+            drift_threshold: 5.0,
+            aligned_momentum_pct: 0.0,
+            days_since_rebalance: 0.0,
+            total_value: 100_000.0,
+        };
+        
+        let small = OpportunityScore::compute(&inputs(2.0), &legs(2.0, 100_000.0), &scoring, &costs);
+        let large = OpportunityScore::compute(&inputs(10.0), &legs(10.0, 100_000.0), &scoring, &costs);
+        
+        assert!(large.score > small.score);
+        assert_eq!(large.drift_factor, 1.0);
+        assert_eq!(large.momentum_factor, 0.5);
+        // 2 bps spread on $20k turnover
+        assert!((large.estimated_cost - 4.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_cost_benefit() {
+        let benefit = RebalanceBenefitConfig::default();
+        let free = CostConfig { commission_per_trade: 0.0, spread_bps: 2.0, ..Default::default() };
+        let pricey = CostConfig { commission_per_trade: 25.0, spread_bps: 2.0, ..Default::default() };
+        
+        let legs = legs(6.0, 100_000.0);
+        let cheap = CostBenefit::estimate(6.0, 100_000.0, &legs, &benefit, &free);
+        assert!(cheap.expected_benefit > 0.0);
+        assert!(!cheap.is_unprofitable());
+        
+        let expensive = CostBenefit::estimate(6.0, 100_000.0, &legs, &benefit, &pricey);
+        assert_eq!(expensive.expected_benefit, cheap.expected_benefit);
+        assert!(expensive.is_unprofitable());
+    }
+    
+    #[test]
+    fn test_seed_momentum() {
+        let mut model = DriftModel::new(Arc::new(Config::default()));
+        let bar = |day, close| DailyBar { date: NaiveDate::from_ymd_opt(2024, 7, day).unwrap(), close };
+        let stocks = [bar(29, 100.0), bar(30, 101.0), bar(31, 102.0)];
+        // No bond close on the 30th
+        let bonds = [bar(29, 50.0), bar(31, 50.0)];
+        assert_eq!(model.seed_momentum(&stocks, &bonds), 2);
+        assert!((model.momentum.ema_pct - 0.6).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_momentum_tracker() {
+        let mut tracker = MomentumTracker::default();
+        assert_eq!(tracker.update(100.0, 50.0), 0.0);
+        // Stocks +1%, bonds flat
+        let ema = tracker.update(101.0, 50.0);
+        assert!((ema - 0.3).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_explanation_within_band() {
+        let config = Config::default();
+        let explanation = DriftExplanation::build(&portfolio(62.0), &target(60.0), None, &config);
+        
+        assert!(explanation.band_breached.is_none());
+        assert!((explanation.assets[0].attribution.price_moves_pct - 2.0).abs() < 1e-9);
+        assert!((explanation.assets[1].attribution.price_moves_pct + 2.0).abs() < 1e-9);
+    }
+}
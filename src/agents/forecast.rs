@@ -0,0 +1,299 @@
+//! Forecast Agent
+//!
+//! Projects when drift will next breach the threshold. Sniffs fresh price
+//! data, keeps a rolling window of stocks and bonds prices, and fits the
+//! trend of stocks' return relative to bonds (the slope of
+//! `ln(stocks / bonds)` over time). Compounding the current allocation at
+//! that trend gives the time until it leaves the drift band, deposited as
+//! Drift_Forecast and shown on the dashboard as "days until rebalance".
+//!
+//! The projection is a straight-line extrapolation of recent prices, not a
+//! prediction: it is most useful as an order of magnitude for planning.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{debug, info};
+
+use crate::agents::sensor::MarketSnapshot;
+use crate::agents::Agent;
+use crate::core::blackboard::AgentMetrics;
+use crate::core::money;
+use crate::core::physics::PheromoneType;
+use crate::core::pipeline::{self, AgentRole};
+use crate::core::runtime;
+use crate::core::{Blackboard, Config};
+
+const SECS_PER_DAY: f64 = 86_400.0;
+
+/// Projected time until drift breaches the threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriftForecast {
+    pub current_stocks_pct: f64,
+    pub target_stocks_pct: f64,
+    pub drift_pct: f64,
+    pub threshold_pct: f64,
+    /// Trend of stocks' return relative to bonds, % per day
+    pub relative_return_pct_per_day: f64,
+    /// Estimated days until rebalance: zero when drift is already past the
+    /// threshold, absent when the trend is flat or the breach lies beyond
+    /// `[forecast] max_horizon_days`
+    pub days_until_breach: Option<f64>,
+    /// Price samples the trend was fitted to
+    pub samples: usize,
+    pub timestamp: String,
+}
+
+/// Stocks and bonds prices of one market snapshot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PricePoint {
+    /// Seconds since the Unix epoch
+    pub time_secs: f64,
+    pub stocks: f64,
+    pub bonds: f64,
+}
+
+/// Least-squares slope of `ln(stocks / bonds)` against time, per second;
+/// needs two samples at distinct times
+pub fn relative_trend(points: &[PricePoint]) -> Option<f64> {
+    let series: Vec<(f64, f64)> = points
+        .iter()
+        .filter(|p| p.stocks > 0.0 && p.bonds > 0.0)
+        .map(|p| (p.time_secs, (p.stocks / p.bonds).ln()))
+        .collect();
+    if series.len() < 2 {
+        return None;
+    }
+    
+    let n = series.len() as f64;
+    let mean_t = series.iter().map(|s| s.0).sum::<f64>() / n;
+    let mean_y = series.iter().map(|s| s.1).sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (t, y) in &series {
+        cov += (t - mean_t) * (y - mean_y);
+        var += (t - mean_t).powi(2);
+    }
+    (var > 0.0).then(|| cov / var)
+}
+
+/// Seconds until the stocks share, compounding at `trend_per_sec` relative
+/// to bonds, reaches the edge of the drift band it is heading for (passing
+/// the target first if it is moving back toward it). Zero when already
+/// outside the band; none when the trend is flat or that edge is out of
+/// reach (0% or 100%).
+pub fn secs_until_breach(stocks_pct: f64, target_stocks_pct: f64, threshold_pct: f64, trend_per_sec: f64) -> Option<f64> {
+    if (stocks_pct - target_stocks_pct).abs() >= threshold_pct {
+        return Some(0.0);
+    }
+    let bound = if trend_per_sec > 0.0 {
+        target_stocks_pct + threshold_pct
+    } else if trend_per_sec < 0.0 {
+        target_stocks_pct - threshold_pct
+    } else {
+        return None;
+    };
+    if bound <= 0.0 || bound >= 100.0 || stocks_pct <= 0.0 || stocks_pct >= 100.0 {
+        return None;
+    }
+    
+    // The stocks/bonds value ratio grows as e^(trend × t)
+    let odds = |pct: f64| pct / (100.0 - pct);
+    Some((odds(bound) / odds(stocks_pct)).ln() / trend_per_sec)
+}
+
+pub struct ForecastAgent {
+    name: String,
+    config: Arc<Config>,
+    running: AtomicBool,
+    active: AtomicBool,
+    action_count: AtomicU64,
+    window: tokio::sync::Mutex<VecDeque<PricePoint>>,
+}
+
+impl ForecastAgent {
+    pub fn new(config: Arc<Config>) -> Self {
+        Self {
+            name: "Forecast".to_string(),
+            window: tokio::sync::Mutex::new(VecDeque::with_capacity(config.forecast.lookback_samples)),
+            config,
+            running: AtomicBool::new(false),
+            active: AtomicBool::new(false),
+            action_count: AtomicU64::new(0),
+        }
+    }
+
+    /// Add a snapshot's prices to the window; returns false for a snapshot
+    /// already seen or missing either price
+    async fn sample(&self, snapshot: &MarketSnapshot) -> bool {
+        let portfolio = &self.config.portfolio;
+        let Ok(time) = chrono::DateTime::parse_from_rfc3339(&snapshot.timestamp) else {
+            return false;
+        };
+        let (Some(stocks), Some(bonds)) = (snapshot.price(&portfolio.stocks_symbol), snapshot.price(&portfolio.bonds_symbol)) else {
+            return false;
+        };
+        let point = PricePoint { time_secs: time.timestamp_millis() as f64 / 1000.0, stocks, bonds };
+        
+        let mut window = self.window.lock().await;
+        if window.back().is_some_and(|last| last.time_secs >= point.time_secs) {
+            return false;
+        }
+        if window.len() >= self.config.forecast.lookback_samples.max(2) {
+            window.pop_front();
+        }
+        window.push_back(point);
+        true
+    }
+
+    async fn set_metrics(&self, board: &Blackboard, last_action: String) {
+        let _ = board.set_agent_metrics(&AgentMetrics {
+            name: self.name.clone(),
+            is_active: true,
+            action_count: self.action_count.load(Ordering::SeqCst),
+            last_action,
+            last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+            last_error: None,
+        }).await;
+    }
+}
+
+#[async_trait]
+impl Agent for ForecastAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        info!("🛑 Forecast agent stopping...");
+    }
+
+    fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    async fn run(&self, board: Arc<Blackboard>) -> Result<()> {
+        self.running.store(true, Ordering::SeqCst);
+        let mut ticker = interval(Duration::from_millis(self.config.agent.sniff_interval_ms));
+        let settings = &self.config.forecast;
+        
+        info!(
+            "🔮 Forecast agent started ({} sample lookback, sniffing every {}ms)",
+            settings.lookback_samples, self.config.agent.sniff_interval_ms
+        );
+        
+        while self.running.load(Ordering::SeqCst) {
+            runtime::tick(&mut ticker).await;
+            
+            let Some(snapshot) = board.sniff::<MarketSnapshot>(PheromoneType::PriceFreshness).await? else {
+                debug!("Forecast: No fresh market data. Dormant.");
+                continue;
+            };
+            let missing = pipeline::inactive_inputs(&board, AgentRole::Forecast, &[PheromoneType::PriceFreshness]).await?;
+            if !missing.is_empty() {
+                debug!("Forecast: Waiting on {:?}. Dormant.", missing);
+                continue;
+            }
+            if !self.sample(&snapshot).await {
+                continue;
+            }
+            
+            self.active.store(true, Ordering::SeqCst);
+            
+            let (samples, trend) = {
+                let mut window = self.window.lock().await;
+                (window.len(), relative_trend(window.make_contiguous()))
+            };
+            let trend = match trend {
+                Some(trend) if samples >= settings.min_samples.max(2) => trend,
+                _ => {
+                    self.set_metrics(&board, format!("Collecting prices ({} of {})", samples, settings.min_samples)).await;
+                    self.active.store(false, Ordering::SeqCst);
+                    continue;
+                }
+            };
+            
+            let portfolio = board.get_portfolio_state().await?.unwrap_or_default();
+            let target = board.get_target_allocation().await?;
+            let stocks_pct = money::to_f64(portfolio.stocks_pct);
+            let threshold = self.config.portfolio.drift_threshold;
+            let days = secs_until_breach(stocks_pct, target.stocks_pct, threshold, trend)
+                .map(|secs| secs / SECS_PER_DAY)
+                .filter(|days| *days <= settings.max_horizon_days);
+            
+            let forecast = DriftForecast {
+                current_stocks_pct: stocks_pct,
+                target_stocks_pct: target.stocks_pct,
+                drift_pct: (stocks_pct - target.stocks_pct).abs(),
+                threshold_pct: threshold,
+                relative_return_pct_per_day: trend * SECS_PER_DAY * 100.0,
+                days_until_breach: days,
+                samples,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            };
+            
+            let last_action = match days {
+                Some(days) if days > 0.0 => format!("Rebalance in ~{:.1} days", days),
+                Some(_) => "Drift past threshold".to_string(),
+                None => "No breach projected".to_string(),
+            };
+            debug!(
+                "🔮 Forecast: Stocks {:+.3}%/day vs bonds, drift {:.1}% — {}",
+                forecast.relative_return_pct_per_day, forecast.drift_pct, last_action
+            );
+            
+            if self.config.pipeline.wiring(AgentRole::Forecast).deposits(PheromoneType::DriftForecast) {
+                board.deposit(PheromoneType::DriftForecast, forecast).await?;
+            }
+            self.action_count.fetch_add(1, Ordering::SeqCst);
+            self.set_metrics(&board, last_action).await;
+            
+            self.active.store(false, Ordering::SeqCst);
+        }
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_trend() {
+        // Stocks gain 1% a day on flat bonds
+        let points: Vec<PricePoint> = (0..5)
+            .map(|day| PricePoint {
+                time_secs: day as f64 * SECS_PER_DAY,
+                stocks: 100.0 * 1.01f64.powi(day),
+                bonds: 50.0,
+            })
+            .collect();
+        let trend = relative_trend(&points).unwrap();
+        assert!((trend * SECS_PER_DAY - 1.01f64.ln()).abs() < 1e-12);
+        assert_eq!(relative_trend(&points[..1]), None);
+    }
+
+    #[test]
+    fn test_secs_until_breach() {
+        let per_day = |r: f64| r / SECS_PER_DAY;
+        
+        // 60% stocks reaches 65% when the stocks/bonds ratio grows from 1.5 to 65/35
+        let secs = secs_until_breach(60.0, 60.0, 5.0, per_day(0.01)).unwrap();
+        let expected = ((65.0 / 35.0) / 1.5f64).ln() / 0.01;
+        assert!((secs / SECS_PER_DAY - expected).abs() < 1e-9);
+        
+        // Falling stocks head for the lower edge
+        let down = secs_until_breach(60.0, 60.0, 5.0, per_day(-0.01)).unwrap();
+        assert!((down / SECS_PER_DAY - (1.5f64 / (55.0 / 45.0)).ln() / 0.01).abs() < 1e-9);
+        
+        assert_eq!(secs_until_breach(66.0, 60.0, 5.0, per_day(0.01)), Some(0.0));
+        assert_eq!(secs_until_breach(60.0, 60.0, 5.0, 0.0), None);
+        // An edge past 100% is never reached
+        assert_eq!(secs_until_breach(97.0, 98.0, 5.0, per_day(0.01)), None);
+    }
+}
@@ -3,35 +3,220 @@
 //! The "circuit breaker" of the swarm. Monitors market volatility (VIX)
 //! and only permits trade execution when conditions are stable.
 //! This is the key safety mechanism that prevents trading during high volatility.
+//! A permit approves exact legs, planned when it is issued, and carries a
+//! hash of them; the Trader sends those and nothing else, and refuses a
+//! permit whose legs no longer match the hash.
 
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::time::{interval, Duration};
 use tracing::{debug, error, info, warn};
 
 use crate::agents::analyst::DriftAnalysis;
+use crate::agents::sensor::DataQuality;
 use crate::agents::Agent;
-use crate::core::blackboard::AgentMetrics;
+use crate::core::blackboard::{AgentMetrics, TargetAllocation};
+use crate::core::calendar::{self, EventDay};
+use crate::core::correlation;
+use crate::core::groups;
+use crate::core::mode::SystemMode;
+use crate::core::holdings;
 use crate::core::physics::PheromoneType;
+use crate::core::pipeline::{self, AgentRole};
+use crate::core::rebalance;
+use crate::core::risk::{self, Decision, RiskInputs, RiskRules};
+use crate::core::runtime;
+use crate::core::scripting::{self, ScriptHook};
+use crate::core::warmup;
 use crate::core::{Blackboard, Config};
-use crate::market::MarketDataProvider;
+use crate::execution::OrderLeg;
+use crate::market::{daily_history, realized_vol};
+use crate::market::{MarketDataProvider, QuoteSource};
+
+/// Which volatility signal a permit decision was based on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VolatilitySignal {
+    #[default]
+    Vix,
+    /// Realized volatility of live prices, mapped to the VIX scale
+    RealizedVolatility,
+    /// Simulated VIX, used only when nothing better is available
+    SimulatedVix,
+    /// Volatility of the portfolio's own assets (`[risk_index]`)
+    CompositeIndex,
+}
+
+impl VolatilitySignal {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Vix => "VIX",
+            Self::RealizedVolatility => "realized vol",
+            Self::SimulatedVix => "simulated VIX",
+            Self::CompositeIndex => "composite risk",
+        }
+    }
+}
+
+/// A volatility reading on the VIX scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolatilityReading {
+    pub value: f64,
+    pub signal: VolatilitySignal,
+}
 
 /// Execution permit with volatility assessment
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionPermit {
+    /// Volatility on the VIX scale (see `volatility_signal` for its source)
     pub vix_value: f64,
+    #[serde(default)]
+    pub volatility_signal: VolatilitySignal,
     pub volatility_status: String,
+    /// Sensor data-quality score the permit was granted on
+    pub data_quality: f64,
     pub drift_analysis: DriftAnalysis,
+    /// Legs the permit approves, sells first; the Trader sends exactly these
+    #[serde(default)]
+    pub legs: Vec<OrderLeg>,
+    /// Target the legs rebalance to (the achievable target when approved)
+    #[serde(default)]
+    pub target: Option<TargetAllocation>,
+    /// SHA-256 of the approved plan (see `plan_hash`), checked by the Trader
+    #[serde(default)]
+    pub plan_hash: Option<String>,
     pub timestamp: String,
 }
 
+impl ExecutionPermit {
+    /// Hex SHA-256 of what the permit approves: its legs, their target and
+    /// when it was issued
+    pub fn compute_plan_hash(&self) -> String {
+        let plan = serde_json::json!({
+            "legs": self.legs,
+            "target": self.target,
+            "timestamp": self.timestamp,
+        });
+        Sha256::digest(plan.to_string().as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Check the plan against the hash the Guardian sealed it with
+    pub fn verify_plan(&self) -> std::result::Result<(), String> {
+        let Some(sealed) = &self.plan_hash else {
+            return Err("permit carries no plan hash".to_string());
+        };
+        let actual = self.compute_plan_hash();
+        if &actual == sealed {
+            Ok(())
+        } else {
+            Err(format!("plan hashes to {} but was approved as {}", &actual[..12], sealed.get(..12).unwrap_or(sealed)))
+        }
+    }
+}
+
+/// Inputs handed to a `guardian_rule` script's `permit(ctx)`
+#[derive(Debug, Serialize)]
+struct PermitContext<'a> {
+    /// Volatility on the VIX scale
+    volatility: f64,
+    volatility_signal: VolatilitySignal,
+    /// LOW | MODERATE | HIGH against the configured thresholds
+    volatility_status: &'a str,
+    vix_low_threshold: f64,
+    vix_high_threshold: f64,
+    data_quality: f64,
+    /// Whether the assets' correlations have spiked
+    correlation_spike: bool,
+    analysis: &'a DriftAnalysis,
+}
+
+pub use crate::core::risk::PermitRefusal;
+
+/// The permit rule under one configuration: blackout calendar, data
+/// quality floor and the volatility threshold or `guardian_rule` script.
+/// The Guardian owns one; shadow mode runs a second under other parameters.
+pub struct PermitModel {
+    config: Arc<Config>,
+    /// Economic event days from the blackout calendar
+    event_days: Vec<EventDay>,
+    /// User script replacing the volatility permit rule
+    permit_script: Option<ScriptHook>,
+}
+
+impl PermitModel {
+    pub fn new(config: Arc<Config>) -> Self {
+        let event_days = match &config.blackout.events_file {
+            Some(path) if config.blackout.enabled => calendar::load_event_days(path).unwrap_or_else(|e| {
+                warn!("Guardian: {:#}. Event blackouts disabled.", e);
+                Vec::new()
+            }),
+            _ => Vec::new(),
+        };
+        let permit_script = scripting::load_hook(
+            config.scripting.guardian_rule.as_deref(),
+            "permit",
+            &config.scripting,
+        );
+        Self { config, event_days, permit_script }
+    }
+    
+    /// The rules `risk::decide` applies under this configuration
+    pub fn rules(&self) -> RiskRules<'_> {
+        RiskRules::from_config(&self.config, &self.event_days)
+    }
+    
+    /// Decide on an opportunity (see `risk::decide`), running the
+    /// `guardian_rule` script in place of the volatility ceiling
+    pub fn decide(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        analysis: &DriftAnalysis,
+        mode: Option<SystemMode>,
+        data_quality: Option<f64>,
+        volatility: Option<VolatilityReading>,
+        correlation_spike: bool,
+    ) -> Decision {
+        let rules = self.rules();
+        let script_permit = match (&self.permit_script, volatility) {
+            (Some(script), Some(reading)) => {
+                let ctx = PermitContext {
+                    volatility: reading.value,
+                    volatility_signal: reading.signal,
+                    volatility_status: rules.volatility_status(reading.value),
+                    vix_low_threshold: rules.vix_low_threshold,
+                    vix_high_threshold: rules.vix_high_threshold,
+                    data_quality: data_quality.unwrap_or(0.0),
+                    correlation_spike,
+                    analysis,
+                };
+                // A failing rule must not let trades through
+                Some(script.call_bool("permit", &ctx).unwrap_or_else(|e| {
+                    error!("Guardian: {:#}. Halting for safety.", e);
+                    false
+                }))
+            }
+            _ => None,
+        };
+        risk::decide(
+            &RiskInputs { now, analysis, mode, data_quality, volatility, script_permit, correlation_spike },
+            &rules,
+        )
+    }
+    
+    fn script_path(&self) -> Option<&str> {
+        self.permit_script.as_ref().map(|s| s.path())
+    }
+}
+
 pub struct GuardianAgent {
     name: String,
     config: Arc<Config>,
     market: Arc<dyn MarketDataProvider>,
+    model: PermitModel,
     running: AtomicBool,
     active: AtomicBool,
     action_count: AtomicU64,
@@ -41,6 +226,7 @@ impl GuardianAgent {
     pub fn new(config: Arc<Config>, market: Arc<dyn MarketDataProvider>) -> Self {
         Self {
             name: "Guardian".to_string(),
+            model: PermitModel::new(config.clone()),
             config,
             market,
             running: AtomicBool::new(false),
@@ -80,8 +266,12 @@ impl Agent for GuardianAgent {
             self.config.market.vix_high_threshold
         );
         
+        let wiring = self.config.pipeline.wiring(AgentRole::Guardian);
+        let started = chrono::Utc::now();
+        let mut warm = !self.config.warmup.enabled;
+        
         while self.running.load(Ordering::SeqCst) {
-            ticker.tick().await;
+            runtime::tick(&mut ticker).await;
             
             // Sniff for rebalance opportunity
             let analysis: Option<DriftAnalysis> = board
@@ -89,75 +279,134 @@ impl Agent for GuardianAgent {
                 .await?;
             
             if let Some(drift_analysis) = analysis {
+                // Other pheromones the pipeline wiring makes us wait for
+                let handled = [PheromoneType::RebalanceOpportunity, PheromoneType::DataQuality];
+                let missing = pipeline::inactive_inputs(&board, AgentRole::Guardian, &handled).await?;
+                if !missing.is_empty() {
+                    debug!("Guardian: Waiting on {:?}. Dormant.", missing);
+                    continue;
+                }
+                
                 self.active.store(true, Ordering::SeqCst);
                 
-                // Check market volatility
-                match self.market.get_vix().await {
-                    Ok(vix) => {
-                        let volatility_status = if vix < self.config.market.vix_low_threshold {
-                            "LOW"
-                        } else if vix > self.config.market.vix_high_threshold {
-                            "HIGH"
-                        } else {
-                            "MODERATE"
-                        };
-                        
-                        info!(
-                            "🌡️ Guardian: VIX = {:.2} ({})",
-                            vix, volatility_status
-                        );
-                        
-                        if vix <= self.config.market.vix_high_threshold {
-                            // Volatility acceptable - permit execution
-                            info!(
-                                "✅ Guardian: Volatility acceptable! Issuing execution permit for: {}",
-                                drift_analysis.recommended_action
-                            );
-                            
-                            let permit = ExecutionPermit {
-                                vix_value: vix,
-                                volatility_status: volatility_status.to_string(),
-                                drift_analysis,
-                                timestamp: chrono::Utc::now().to_rfc3339(),
-                            };
-                            
-                            board.deposit(PheromoneType::ExecutionPermit, permit).await?;
-                            self.action_count.fetch_add(1, Ordering::SeqCst);
-                            
-                            let _ = board.set_agent_metrics(&AgentMetrics {
-                                name: "Guardian".to_string(),
-                                is_active: true,
-                                action_count: self.action_count.load(Ordering::SeqCst),
-                                last_action: format!("Permit issued (VIX {:.1})", vix),
-                                last_action_time: Some(chrono::Utc::now().to_rfc3339()),
-                            }).await;
-                        } else {
-                            // High volatility - HALT the chain
-                            warn!(
-                                "🚫 Guardian: HIGH VOLATILITY! VIX {:.2} > threshold {}. Trade BLOCKED!",
-                                vix,
-                                self.config.market.vix_high_threshold
-                            );
-                            
-                            let _ = board.set_agent_metrics(&AgentMetrics {
-                                name: "Guardian".to_string(),
-                                is_active: true,
-                                action_count: self.action_count.load(Ordering::SeqCst),
-                                last_action: format!("BLOCKED (VIX {:.1})", vix),
-                                last_action_time: Some(chrono::Utc::now().to_rfc3339()),
-                            }).await;
-                        }
+                // Never permit on the first data points after startup
+                if !warm {
+                    let progress = warmup::progress(&board, &self.config, started).await?;
+                    let min_returns = self.config.market.realized_vol.min_returns;
+                    if let Some(waiting) = progress.pending(&self.config.warmup, min_returns) {
+                        info!("🌱 Guardian: Warming up ({}). Withholding permit.", waiting);
+                        let _ = board.set_agent_metrics(&AgentMetrics {
+                            name: "Guardian".to_string(),
+                            is_active: true,
+                            action_count: self.action_count.load(Ordering::SeqCst),
+                            last_action: format!("Withheld (warming up: {})", waiting),
+                            last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                            last_error: None,
+                        }).await;
+                        self.active.store(false, Ordering::SeqCst);
+                        continue;
                     }
-                    Err(e) => {
-                        error!("Guardian: Failed to fetch VIX: {}. Halting for safety.", e);
+                    info!("🌱 Guardian: Warmup complete ({} snapshots).", progress.snapshots);
+                    warm = true;
+                }
+                
+                if !wiring.deposits(PheromoneType::ExecutionPermit) {
+                    debug!("Guardian: Pipeline does not route ExecutionPermit. Skipping volatility check.");
+                    self.active.store(false, Ordering::SeqCst);
+                    continue;
+                }
+                
+                // System mode, blackout, asset limits, data quality and
+                // volatility (see `core::risk`)
+                let mode = board.get_system_status().await?.map(|status| status.mode);
+                let quality: Option<DataQuality> = board.sniff(PheromoneType::DataQuality).await?;
+                let volatility = read_volatility(self.market.as_ref(), &board, &self.config).await;
+                let correlation_spike = correlation::spike_active(&board, &self.config).await?;
+                if correlation_spike {
+                    info!("🔗 Guardian: Correlations have spiked. Volatility ceiling lowered to {:.1}.",
+                        self.model.rules().volatility_ceiling(true));
+                }
+                if let Ok(reading) = &volatility {
+                    info!(
+                        "🌡️ Guardian: {} = {:.2} ({})",
+                        reading.signal.label(), reading.value, self.model.rules().volatility_status(reading.value)
+                    );
+                }
+                
+                let now = chrono::Utc::now();
+                let decision = self.model.decide(
+                    now,
+                    &drift_analysis,
+                    mode,
+                    quality.map(|q| q.score),
+                    volatility.as_ref().ok().copied(),
+                    correlation_spike,
+                );
+                
+                if let (true, Ok(reading)) = (decision.is_permit(), &volatility) {
+                    // The permit covers these legs only, whatever changes later
+                    let (target, legs) = approved_legs(&board, &self.config, &drift_analysis).await?;
+                    if legs.is_empty() {
+                        info!("🛡️ Guardian: No leg worth sending. Withholding permit.");
                         let _ = board.set_agent_metrics(&AgentMetrics {
                             name: "Guardian".to_string(),
-                            is_active: false,
+                            is_active: true,
                             action_count: self.action_count.load(Ordering::SeqCst),
-                            last_action: format!("VIX error: {}", e),
+                            last_action: "Withheld (no leg worth sending)".to_string(),
                             last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                            last_error: None,
                         }).await;
+                        self.active.store(false, Ordering::SeqCst);
+                        continue;
                     }
+                    
+                    // Volatility acceptable - permit execution
+                    info!(
+                        "✅ Guardian: Volatility acceptable! Issuing execution permit for {} leg(s): {}",
+                        legs.len(),
+                        drift_analysis.recommended_action
+                    );
+                    
+                    let mut permit = ExecutionPermit {
+                        vix_value: reading.value,
+                        volatility_signal: reading.signal,
+                        volatility_status: self.model.rules().volatility_status(reading.value).to_string(),
+                        data_quality: decision.data_quality,
+                        drift_analysis,
+                        legs,
+                        target: Some(target),
+                        plan_hash: None,
+                        timestamp: now.to_rfc3339(),
+                    };
+                    permit.plan_hash = Some(permit.compute_plan_hash());
+                    
+                    board.deposit(PheromoneType::ExecutionPermit, permit).await?;
+                    self.action_count.fetch_add(1, Ordering::SeqCst);
+                    
+                    let _ = board.set_agent_metrics(&AgentMetrics {
+                        name: "Guardian".to_string(),
+                        is_active: true,
+                        action_count: self.action_count.load(Ordering::SeqCst),
+                        last_action: format!("Permit issued ({} {:.1})", reading.signal.label(), reading.value),
+                        last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                        last_error: None,
+                    }).await;
+                } else if let Some(refusal) = decision.reasons.first() {
+                    let last_action = match (refusal, &volatility) {
+                        (PermitRefusal::VolatilityUnavailable, Err(e)) => {
+                            error!("Guardian: Failed to fetch VIX: {}. Halting for safety.", e);
+                            format!("VIX error: {}", e)
+                        }
+                        _ => self.refused(refusal),
+                    };
+                    let _ = board.set_agent_metrics(&AgentMetrics {
+                        name: "Guardian".to_string(),
+                        is_active: !matches!(refusal, PermitRefusal::VolatilityUnavailable),
+                        action_count: self.action_count.load(Ordering::SeqCst),
+                        last_action,
+                        last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                        last_error: None,
+                    }).await;
                 }
                 
                 self.active.store(false, Ordering::SeqCst);
@@ -169,3 +418,132 @@ impl Agent for GuardianAgent {
         Ok(())
     }
 }
+
+/// The legs that rebalance the portfolio to its achievable target now,
+/// planned as the Trader sends them (sells first); with asset groups, one
+/// leg per off-target member
+async fn approved_legs(
+    board: &Blackboard,
+    config: &Config,
+    analysis: &DriftAnalysis,
+) -> Result<(TargetAllocation, Vec<OrderLeg>)> {
+    let state = board.get_portfolio_state().await?.unwrap_or_default();
+    let target = board.get_target_allocation().await?;
+    let target = holdings::achievable_target(board, &state, &target, Some(&analysis.market_snapshot)).await?;
+    if let Some(drift) = groups::current(board, config, |symbol| analysis.market_snapshot.price(symbol)).await? {
+        return Ok((target, groups::member_legs(&drift, &config.holdings_import)));
+    }
+    let (stocks_delta, bonds_delta) = rebalance::deltas(&state, state.total_value, &target);
+    Ok((target, rebalance::plan_legs(&config.portfolio, stocks_delta, bonds_delta)))
+}
+
+/// Read volatility on the VIX scale, preferring a real VIX, then
+/// realized volatility of live prices, then a simulated VIX; a fresh
+/// composite risk index comes first when the Guardian is set to use it
+pub async fn read_volatility(
+    market: &dyn MarketDataProvider,
+    board: &Blackboard,
+    config: &Config,
+) -> Result<VolatilityReading> {
+    if config.risk_index.use_for_guardian {
+        match board.get_composite_risk_index().await? {
+            Some(index) if index.is_fresh(chrono::Utc::now(), config.risk_index.stale_after_secs) => {
+                return Ok(VolatilityReading { value: index.value, signal: VolatilitySignal::CompositeIndex });
+            }
+            _ => debug!("Guardian: No fresh composite risk index. Falling back to VIX."),
+        }
+    }
+    
+    let vix = market.get_vix_quote().await;
+    if let Ok(quote) = &vix {
+        if quote.source != QuoteSource::Simulated {
+            return Ok(VolatilityReading { value: quote.price, signal: VolatilitySignal::Vix });
+        }
+    }
+    
+    if let Some(value) = realized_volatility(board, config).await? {
+        match &vix {
+            Err(e) => warn!("Guardian: VIX unavailable ({}). Using realized volatility {:.2}.", e, value),
+            Ok(_) => debug!("Guardian: VIX is simulated. Using realized volatility {:.2}.", value),
+        }
+        return Ok(VolatilityReading { value, signal: VolatilitySignal::RealizedVolatility });
+    }
+    
+    let quote = vix?;
+    debug!("Guardian: No realized volatility yet. Falling back to simulated VIX.");
+    Ok(VolatilityReading { value: quote.price, signal: VolatilitySignal::SimulatedVix })
+}
+
+/// Realized volatility of live prices on the VIX scale, if enough
+/// samples have been collected, else of the daily closes loaded at startup
+async fn realized_volatility(board: &Blackboard, config: &Config) -> Result<Option<f64>> {
+    let config = &config.market.realized_vol;
+    if !config.enabled {
+        return Ok(None);
+    }
+    
+    let samples = board.get_price_samples(&config.symbol).await?;
+    let vol = match realized_vol::annualized_volatility(&samples, config.min_returns) {
+        Some(vol) => Some(vol),
+        None => board
+            .get_daily_history(&config.symbol)
+            .await?
+            .and_then(|history| daily_history::daily_volatility(&history.bars, config.min_returns)),
+    };
+    Ok(vol.map(|vol| realized_vol::vix_equivalent(vol, config.vix_multiplier)))
+}
+
+impl GuardianAgent {
+    /// Log why the permit was refused and return the action to report
+    fn refused(&self, refusal: &PermitRefusal) -> String {
+        match refusal {
+            PermitRefusal::SystemMode(mode) => {
+                warn!("🚦 Guardian: System mode {}. Trade BLOCKED!", mode);
+                format!("BLOCKED (system mode {})", mode)
+            }
+            PermitRefusal::Blackout(reason) => {
+                // Never trade into the open/close auctions or event days
+                info!("⏸️ Guardian: Blackout ({}). Withholding permit.", reason);
+                format!("Withheld (blackout: {})", reason)
+            }
+            PermitRefusal::AssetLimit(reason) => {
+                warn!("🚧 Guardian: Target {}. Trade BLOCKED!", reason);
+                format!("BLOCKED (target {})", reason)
+            }
+            PermitRefusal::DataQuality(Some(score)) => {
+                warn!(
+                    "🚫 Guardian: Data quality {:.2} below floor {:.2}. Trade BLOCKED!",
+                    score, self.config.data_quality.min_score
+                );
+                format!("BLOCKED (data quality {:.2})", score)
+            }
+            PermitRefusal::DataQuality(None) => {
+                debug!("Guardian: No data quality signal. Withholding permit.");
+                "Withheld (no data quality signal)".to_string()
+            }
+            PermitRefusal::VolatilityUnavailable => {
+                error!("Guardian: No volatility reading. Halting for safety.");
+                "Withheld (volatility unavailable)".to_string()
+            }
+            PermitRefusal::Volatility { reading, by_script: true, .. } => {
+                warn!(
+                    "🚫 Guardian: Script {} withheld the permit ({} {:.2}). Trade BLOCKED!",
+                    self.model.script_path().unwrap_or("guardian_rule"),
+                    reading.signal.label(),
+                    reading.value
+                );
+                format!("BLOCKED by script ({} {:.1})", reading.signal.label(), reading.value)
+            }
+            PermitRefusal::Volatility { reading, ceiling, by_script: false } => {
+                // High volatility - HALT the chain
+                warn!(
+                    "🚫 Guardian: HIGH VOLATILITY! {} {:.2} > threshold {}. Trade BLOCKED!",
+                    reading.signal.label(),
+                    reading.value,
+                    ceiling
+                );
+                format!("BLOCKED ({} {:.1})", reading.signal.label(), reading.value)
+            }
+        }
+    }
+}
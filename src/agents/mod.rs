@@ -1,15 +1,20 @@
 //! Agent Module
 //! 
-//! Implements the four agents of the DriftGuard swarm:
+//! Implements the agents of the DriftGuard swarm:
 //! - Sensor: Ingests market data, deposits Price_Freshness
 //! - Analyst: Calculates drift, deposits Rebalance_Opportunity
 //! - Guardian: Checks volatility, deposits Execution_Permit
 //! - Trader: Executes trades
+//! - Forecast: Projects when drift will breach the threshold, deposits Drift_Forecast
+//!
+//! `shadow` compares the Analyst and Guardian under a second configuration.
 
 pub mod sensor;
 pub mod analyst;
 pub mod guardian;
 pub mod trader;
+pub mod forecast;
+pub mod shadow;
 
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -18,6 +23,7 @@ pub use sensor::SensorAgent;
 pub use analyst::AnalystAgent;
 pub use guardian::GuardianAgent;
 pub use trader::TraderAgent;
+pub use forecast::ForecastAgent;
 
 use crate::core::Blackboard;
 
@@ -1,39 +1,281 @@
 //! Sensor Agent
 //! 
-//! The "eyes" of the swarm. Ingests real market data from Alpha Vantage
-//! and deposits Price_Freshness pheromones for the Analyst to detect.
+//! The "eyes" of the swarm. Ingests real market data from the configured
+//! provider and deposits Price_Freshness pheromones for the Analyst to
+//! detect. It polls on a timer, and with a streaming provider also
+//! deposits as soon as streamed prices move.
 
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::time::{interval, Duration};
 use tracing::{debug, error, info, warn};
 
 use crate::agents::Agent;
-use crate::core::blackboard::AgentMetrics;
+use crate::core::blackboard::{AgentMetrics, MarketQuoteUpdate, MarketUpdate};
+use crate::core::clock;
 use crate::core::physics::PheromoneType;
+use crate::core::pipeline::{self, AgentRole};
+use crate::core::runtime;
+use crate::core::config::{AdaptivePollingConfig, DataQualityConfig, MarketConfig};
+use crate::core::error::DriftGuardError;
+use crate::core::money;
 use crate::core::{Blackboard, Config};
-use crate::market::MarketDataProvider;
+use crate::market::realized_vol::PriceSample;
+use crate::market::{MarketDataProvider, Quote, QuoteSource};
+
+/// A single asset's price within a market snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetQuote {
+    pub symbol: String,
+    pub price: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bid: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ask: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f64>,
+}
+
+impl AssetQuote {
+    pub fn new(symbol: impl Into<String>, price: f64) -> Self {
+        Self {
+            symbol: symbol.into(),
+            price,
+            bid: None,
+            ask: None,
+            volume: None,
+        }
+    }
+}
 
 /// Market data payload deposited by Sensor
+/// 
+/// Deserializes both the current per-asset shape and the legacy
+/// `{ stocks_*, bonds_* }` shape, so payloads already sitting on the
+/// blackboard keep working across upgrades.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "MarketSnapshotRepr")]
 pub struct MarketSnapshot {
-    pub stocks_symbol: String,
-    pub stocks_price: f64,
-    pub bonds_symbol: String,
-    pub bonds_price: f64,
+    pub quotes: Vec<AssetQuote>,
     pub timestamp: String,
 }
 
+impl MarketSnapshot {
+    pub fn new(quotes: Vec<AssetQuote>) -> Self {
+        Self {
+            quotes,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+    
+    /// Look up the quote for a symbol
+    pub fn quote(&self, symbol: &str) -> Option<&AssetQuote> {
+        self.quotes.iter().find(|q| q.symbol == symbol)
+    }
+    
+    /// Look up the price for a symbol
+    pub fn price(&self, symbol: &str) -> Option<f64> {
+        self.quote(symbol).map(|q| q.price)
+    }
+}
+
+/// Wire representations accepted for `MarketSnapshot`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MarketSnapshotRepr {
+    Current {
+        quotes: Vec<AssetQuote>,
+        timestamp: String,
+    },
+    Legacy {
+        stocks_symbol: String,
+        stocks_price: f64,
+        bonds_symbol: String,
+        bonds_price: f64,
+        timestamp: String,
+    },
+}
+
+impl From<MarketSnapshotRepr> for MarketSnapshot {
+    fn from(repr: MarketSnapshotRepr) -> Self {
+        match repr {
+            MarketSnapshotRepr::Current { quotes, timestamp } => Self { quotes, timestamp },
+            MarketSnapshotRepr::Legacy {
+                stocks_symbol,
+                stocks_price,
+                bonds_symbol,
+                bonds_price,
+                timestamp,
+            } => Self {
+                quotes: vec![
+                    AssetQuote::new(stocks_symbol, stocks_price),
+                    AssetQuote::new(bonds_symbol, bonds_price),
+                ],
+                timestamp,
+            },
+        }
+    }
+}
+
+/// Quality assessment of a single quote within a snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteQuality {
+    pub symbol: String,
+    pub source: QuoteSource,
+    pub age_secs: f64,
+    pub score: f64,
+}
+
+/// Data-quality payload deposited by Sensor alongside each snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataQuality {
+    /// Overall score in [0, 1] — the weakest quote, penalized by disagreement
+    pub score: f64,
+    pub quotes: Vec<QuoteQuality>,
+    /// Largest cross-provider price disagreement (%), when cross-checked
+    pub disagreement_pct: Option<f64>,
+    pub timestamp: String,
+}
+
+impl DataQuality {
+    /// Score a set of quotes: provenance sets the base score, age erodes it
+    /// linearly, and cross-provider disagreement scales the result down.
+    pub fn assess(
+        quotes: &[Quote],
+        disagreement_pct: Option<f64>,
+        config: &DataQualityConfig,
+    ) -> Self {
+        let quotes: Vec<QuoteQuality> = quotes
+            .iter()
+            .map(|quote| {
+                let base = match quote.source {
+                    QuoteSource::Live => 1.0,
+                    QuoteSource::Cached => config.cached_score,
+                    QuoteSource::Simulated => config.simulated_score,
+                };
+                let freshness = if config.max_quote_age_secs > 0.0 {
+                    (1.0 - quote.age_secs / config.max_quote_age_secs).clamp(0.0, 1.0)
+                } else {
+                    1.0
+                };
+                QuoteQuality {
+                    symbol: quote.symbol.clone(),
+                    source: quote.source,
+                    age_secs: quote.age_secs,
+                    score: (base * freshness).clamp(0.0, 1.0),
+                }
+            })
+            .collect();
+        
+        let weakest = quotes
+            .iter()
+            .map(|q| q.score)
+            .fold(None, |acc: Option<f64>, s| Some(acc.map_or(s, |a| a.min(s))))
+            .unwrap_or(0.0);
+        
+        let agreement = match disagreement_pct {
+            Some(d) if config.max_disagreement_pct > 0.0 => {
+                (1.0 - d / config.max_disagreement_pct).clamp(0.0, 1.0)
+            }
+            _ => 1.0,
+        };
+        
+        Self {
+            score: weakest * agreement,
+            quotes,
+            disagreement_pct,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
 pub struct SensorAgent {
     name: String,
     config: Arc<Config>,
     market: Arc<dyn MarketDataProvider>,
+    /// Secondary provider used to cross-check primary prices
+    validator: Option<Arc<dyn MarketDataProvider>>,
     running: AtomicBool,
     active: AtomicBool,
     action_count: AtomicU64,
+    /// Previous price per symbol, for change reporting
+    last_prices: tokio::sync::RwLock<HashMap<String, f64>>,
+}
+
+/// Market conditions the adaptive poll interval reacts to
+#[derive(Debug, Clone, Default)]
+pub struct PollConditions {
+    pub vix: Option<f64>,
+    /// Current drift divided by the drift threshold
+    pub drift_ratio: Option<f64>,
+    pub in_session: bool,
+}
+
+/// Scale the base poll interval by current conditions
+/// 
+/// Each condition multiplies the interval: high VIX or drift near the
+/// threshold halve it, calm VIX or drift far from the threshold grow it,
+/// and being outside the trading session stretches it further. The
+/// result is clamped to the configured bounds.
+pub fn adaptive_poll_interval(conditions: &PollConditions, market: &MarketConfig) -> Duration {
+    let adaptive = &market.adaptive_polling;
+    let mut factor = 1.0;
+    
+    if let Some(vix) = conditions.vix {
+        if vix >= market.vix_high_threshold {
+            factor *= 0.5;
+        } else if vix <= market.vix_low_threshold {
+            factor *= 1.5;
+        }
+    }
+    
+    if let Some(ratio) = conditions.drift_ratio {
+        if ratio >= adaptive.near_drift_ratio {
+            factor *= 0.5;
+        } else if ratio < adaptive.far_drift_ratio {
+            factor *= 2.0;
+        }
+    }
+    
+    if !conditions.in_session {
+        factor *= 4.0;
+    }
+    
+    let min = adaptive.min_interval_ms.min(adaptive.max_interval_ms) as f64;
+    let max = adaptive.max_interval_ms as f64;
+    let ms = (market.poll_interval_ms as f64 * factor).clamp(min, max);
+    Duration::from_millis(ms as u64)
+}
+
+/// Whether `now` falls inside the configured weekday trading session,
+/// in the exchange's time zone
+pub fn in_trading_session(now: chrono::DateTime<chrono::Utc>, config: &AdaptivePollingConfig, tz: Tz) -> bool {
+    use chrono::{Datelike, NaiveTime, Weekday};
+    
+    if matches!(clock::today(now, tz).weekday(), Weekday::Sat | Weekday::Sun) {
+        return false;
+    }
+    
+    let parse = |s: &str| NaiveTime::parse_from_str(s, "%H:%M").ok();
+    match (parse(&config.session_open), parse(&config.session_close)) {
+        (Some(open), Some(close)) => {
+            let t = clock::time_of_day(now, tz);
+            t >= open && t < close
+        }
+        // Unparseable session bounds: assume the market is open
+        _ => true,
+    }
+}
+
+/// Result of comparing primary prices against the validator
+struct CrossCheck {
+    max_divergence_pct: f64,
+    breaches: Vec<String>,
 }
 
 impl SensorAgent {
@@ -42,11 +284,19 @@ impl SensorAgent {
             name: "Sensor".to_string(),
             config,
             market,
+            validator: None,
             running: AtomicBool::new(false),
             active: AtomicBool::new(false),
             action_count: AtomicU64::new(0),
+            last_prices: tokio::sync::RwLock::new(HashMap::new()),
         }
     }
+    
+    /// Cross-check every price against a second provider before depositing
+    pub fn with_cross_check(mut self, validator: Arc<dyn MarketDataProvider>) -> Self {
+        self.validator = Some(validator);
+        self
+    }
 
     /// Get the number of data ingestions performed
     pub fn action_count(&self) -> u64 {
@@ -73,17 +323,46 @@ impl Agent for SensorAgent {
         self.running.store(true, Ordering::SeqCst);
         let poll_interval = Duration::from_millis(self.config.market.poll_interval_ms);
         let mut ticker = interval(poll_interval);
+        let updates = self.market.price_updates();
         
-        info!("👁️ Sensor agent started (polling every {}ms)", self.config.market.poll_interval_ms);
+        info!("👁️ Sensor agent started (polling every {}ms{}{})",
+            self.config.market.poll_interval_ms,
+            if self.config.market.adaptive_polling.enabled { ", adaptive" } else { "" },
+            if updates.is_some() { ", and on streamed prices" } else { "" }
+        );
         
+        let mut streamed = false;
         while self.running.load(Ordering::SeqCst) {
-            ticker.tick().await;
+            match &updates {
+                Some(updates) => {
+                    let not_before = tokio::time::Instant::now() + updates.min_interval;
+                    streamed = runtime::tick_or_notified(&mut ticker, &updates.notify, not_before).await;
+                }
+                None => runtime::tick(&mut ticker).await,
+            }
+            
+            // The Sensor has no inputs by default; honour any the pipeline adds
+            let missing = pipeline::inactive_inputs(&board, AgentRole::Sensor, &[]).await?;
+            if !missing.is_empty() {
+                debug!("Sensor: Waiting on {:?}. Dormant.", missing);
+                continue;
+            }
             
             self.active.store(true, Ordering::SeqCst);
             
             // Fetch market data
-            match self.fetch_and_deposit(&board).await {
-                Ok(_) => {
+            match self.fetch_and_deposit(&board, streamed).await {
+                Ok(false) => {
+                    let _ = board.set_agent_metrics(&AgentMetrics {
+                        name: "Sensor".to_string(),
+                        is_active: true,
+                        action_count: self.action_count.load(Ordering::SeqCst),
+                        last_action: "Suppressed: provider divergence".to_string(),
+                        last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                        last_error: None,
+                    }).await;
+                }
+                Ok(true) => {
                     self.action_count.fetch_add(1, Ordering::SeqCst);
                     debug!("Sensor: Successfully deposited market data");
                     // Publish metrics
@@ -93,10 +372,13 @@ impl Agent for SensorAgent {
                         action_count: self.action_count.load(Ordering::SeqCst),
                         last_action: "Deposited market data".to_string(),
                         last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                        last_error: None,
                     }).await;
                 }
                 Err(e) => {
                     error!("Sensor: Failed to fetch market data: {}", e);
+                    let error = DriftGuardError::classify(&e, DriftGuardError::Provider);
+                    board.report_error(PheromoneType::PriceFreshness, &error).await;
                     // Publish dormant metrics
                     let _ = board.set_agent_metrics(&AgentMetrics {
                         name: "Sensor".to_string(),
@@ -104,10 +386,18 @@ impl Agent for SensorAgent {
                         action_count: self.action_count.load(Ordering::SeqCst),
                         last_action: format!("Error: {}", e),
                         last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                        last_error: Some(error),
                     }).await;
                 }
             }
             
+            if self.config.market.adaptive_polling.enabled {
+                let conditions = self.poll_conditions(&board).await;
+                let next = adaptive_poll_interval(&conditions, &self.config.market);
+                debug!("Sensor: Next poll in {}ms ({:?})", next.as_millis(), conditions);
+                ticker.reset_after(next);
+            }
+            
             self.active.store(false, Ordering::SeqCst);
         }
         
@@ -116,30 +406,288 @@ impl Agent for SensorAgent {
 }
 
 impl SensorAgent {
-    async fn fetch_and_deposit(&self, board: &Blackboard) -> Result<()> {
-        // Get current prices
-        let stocks_price = self.market.get_price(&self.config.portfolio.stocks_symbol).await?;
-        let bonds_price = self.market.get_price(&self.config.portfolio.bonds_symbol).await?;
-        
-        let snapshot = MarketSnapshot {
-            stocks_symbol: self.config.portfolio.stocks_symbol.clone(),
-            stocks_price,
-            bonds_symbol: self.config.portfolio.bonds_symbol.clone(),
-            bonds_price,
+    /// Fetch prices and deposit them; returns `false` when the snapshot
+    /// was suppressed by the cross-check. A deposit prompted by `streamed`
+    /// prices logs quietly, as they can arrive several times a second.
+    async fn fetch_and_deposit(&self, board: &Blackboard, streamed: bool) -> Result<bool> {
+        let wiring = self.config.pipeline.wiring(AgentRole::Sensor);
+        
+        // Get current prices for every configured asset
+        let mut quotes = Vec::new();
+        for asset in self.config.assets() {
+            quotes.push(self.market.get_quote(&asset.symbol).await?);
+        }
+        
+        let snapshot = MarketSnapshot::new(
+            quotes
+                .iter()
+                .map(|q| AssetQuote::new(q.symbol.clone(), q.price))
+                .collect(),
+        );
+        
+        let cross_check = self.cross_check(&quotes).await;
+        
+        let quality = DataQuality::assess(
+            &quotes,
+            cross_check.as_ref().map(|c| c.max_divergence_pct),
+            &self.config.data_quality,
+        );
+        
+        let prices = snapshot
+            .quotes
+            .iter()
+            .map(|q| format!("{} = ${:.2}", q.symbol, q.price))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if streamed {
+            debug!("📊 Market data (streamed): {}", prices);
+        } else {
+            info!("📊 Market data: {}", prices);
+        }
+        
+        // Publish prices for the dashboard (display only — not a pheromone)
+        let update = self.market_update(&quotes).await;
+        if let Err(e) = board.set_market_update(&update).await {
+            warn!("Sensor: Failed to publish market update: {}", e);
+        }
+        
+        // Keep live prices for the Guardian's realized-volatility fallback
+        let realized_vol = &self.config.market.realized_vol;
+        if realized_vol.enabled {
+            let live = quotes
+                .iter()
+                .find(|q| q.symbol == realized_vol.symbol && q.source == QuoteSource::Live);
+            if let Some(quote) = live {
+                let sample = PriceSample { price: quote.price, timestamp: chrono::Utc::now() };
+                if let Err(e) = board.push_price_sample(&quote.symbol, &sample, realized_vol.max_samples).await {
+                    warn!("Sensor: Failed to record price sample: {}", e);
+                }
+            }
+        }
+        
+        // Deposit quality assessment for Guardian
+        debug!("Sensor: Data quality score {:.2}", quality.score);
+        if wiring.deposits(PheromoneType::DataQuality) {
+            board.deposit(PheromoneType::DataQuality, quality).await?;
+        }
+        
+        // A disputed price never reaches the Analyst — let freshness decay
+        if let Some(check) = cross_check.filter(|c| !c.breaches.is_empty()) {
+            board
+                .report_anomaly(PheromoneType::PriceFreshness, check.breaches.join("; "))
+                .await?;
+            return Ok(false);
+        }
+        
+        // Deposit pheromone for Analyst
+        if wiring.deposits(PheromoneType::PriceFreshness) {
+            board.deposit(PheromoneType::PriceFreshness, snapshot).await?;
+        }
+        
+        // Count toward the Guardian's startup warmup
+        let warmup = &self.config.warmup;
+        if warmup.enabled {
+            if let Err(e) = board.record_sensor_snapshot(chrono::Utc::now(), warmup.min_snapshots).await {
+                warn!("Sensor: Failed to record snapshot for warmup: {}", e);
+            }
+        }
+        
+        Ok(true)
+    }
+    
+    /// Build the dashboard market update, tracking change since last poll
+    async fn market_update(&self, quotes: &[Quote]) -> MarketUpdate {
+        let mut last_prices = self.last_prices.write().await;
+        
+        let quotes = quotes
+            .iter()
+            .map(|q| {
+                let previous = last_prices.insert(q.symbol.clone(), q.price).unwrap_or(q.price);
+                let change = q.price - previous;
+                MarketQuoteUpdate {
+                    symbol: q.symbol.clone(),
+                    price: q.price,
+                    change,
+                    change_pct: if previous > 0.0 { change / previous * 100.0 } else { 0.0 },
+                    source: q.source,
+                    simulated: q.source == QuoteSource::Simulated,
+                }
+            })
+            .collect();
+        
+        MarketUpdate {
+            quotes,
             timestamp: chrono::Utc::now().to_rfc3339(),
+            providers: self.market.provider_health(),
+        }
+    }
+    
+    /// Gather the inputs for the adaptive poll interval
+    /// 
+    /// Unavailable inputs are simply left out rather than failing the loop.
+    async fn poll_conditions(&self, board: &Blackboard) -> PollConditions {
+        let vix = self.market.get_vix().await.ok();
+        
+        let threshold = self.config.portfolio.drift_threshold;
+        let drift_ratio = match (board.get_portfolio_state().await, board.get_target_allocation().await) {
+            (Ok(Some(portfolio)), Ok(target)) if threshold > 0.0 => {
+                Some((money::to_f64(portfolio.stocks_pct) - target.stocks_pct).abs() / threshold)
+            }
+            _ => None,
+        };
+        
+        PollConditions {
+            vix,
+            drift_ratio,
+            in_session: in_trading_session(chrono::Utc::now(), &self.config.market.adaptive_polling, self.config.exchange.timezone),
+        }
+    }
+    
+    /// Compare primary quotes against the validator provider
+    /// 
+    /// Returns `None` when cross-checking is disabled or the validator is
+    /// unavailable, in which case the primary data is used unverified.
+    async fn cross_check(&self, quotes: &[Quote]) -> Option<CrossCheck> {
+        let validator = self.validator.as_ref()?;
+        let tolerance = self.config.market.cross_check.tolerance_pct;
+        
+        let mut check = CrossCheck {
+            max_divergence_pct: 0.0,
+            breaches: Vec::new(),
         };
         
-        info!(
-            "📊 Market data: {} = ${:.2}, {} = ${:.2}",
-            snapshot.stocks_symbol,
-            snapshot.stocks_price,
-            snapshot.bonds_symbol,
-            snapshot.bonds_price
+        for quote in quotes {
+            let reference = match validator.get_price(&quote.symbol).await {
+                Ok(price) if price > 0.0 => price,
+                Ok(price) => {
+                    warn!("Sensor: Validator returned invalid price {} for {}", price, quote.symbol);
+                    return None;
+                }
+                Err(e) => {
+                    warn!("Sensor: Cross-check unavailable for {}: {}", quote.symbol, e);
+                    return None;
+                }
+            };
+            
+            let divergence = divergence_pct(quote.price, reference);
+            check.max_divergence_pct = check.max_divergence_pct.max(divergence);
+            
+            if divergence > tolerance {
+                check.breaches.push(format!(
+                    "{} diverges {:.2}% (primary ${:.2} vs validator ${:.2}, tolerance {:.2}%)",
+                    quote.symbol, divergence, quote.price, reference, tolerance
+                ));
+            }
+        }
+        
+        Some(check)
+    }
+}
+
+/// Relative price difference in percent, measured against the reference
+fn divergence_pct(price: f64, reference: f64) -> f64 {
+    ((price - reference) / reference).abs() * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn quote(symbol: &str, source: QuoteSource, age_secs: f64) -> Quote {
+        Quote {
+            symbol: symbol.to_string(),
+            price: 100.0,
+            source,
+            age_secs,
+        }
+    }
+    
+    #[test]
+    fn test_snapshot_reads_legacy_shape() {
+        let legacy = r#"{
+            "stocks_symbol": "SPY", "stocks_price": 580.0,
+            "bonds_symbol": "BND", "bonds_price": 72.0,
+            "timestamp": "2024-01-01T00:00:00Z"
+        }"#;
+        let snapshot: MarketSnapshot = serde_json::from_str(legacy).unwrap();
+        
+        assert_eq!(snapshot.quotes.len(), 2);
+        assert_eq!(snapshot.price("SPY"), Some(580.0));
+        assert_eq!(snapshot.price("BND"), Some(72.0));
+        
+        // Round-trips through the current shape
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let again: MarketSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(again.price("SPY"), Some(580.0));
+    }
+    
+    #[test]
+    fn test_adaptive_poll_interval_bounds() {
+        let mut market = Config::default().market;
+        market.poll_interval_ms = 5000;
+        
+        let calm = PollConditions { vix: Some(12.0), drift_ratio: Some(0.1), in_session: false };
+        assert_eq!(
+            adaptive_poll_interval(&calm, &market),
+            Duration::from_millis(market.adaptive_polling.max_interval_ms)
         );
         
-        // Deposit pheromone for Analyst
-        board.deposit(PheromoneType::PriceFreshness, snapshot).await?;
+        let hot = PollConditions { vix: Some(35.0), drift_ratio: Some(0.9), in_session: true };
+        assert_eq!(adaptive_poll_interval(&hot, &market), Duration::from_millis(1250));
+    }
+    
+    #[test]
+    fn test_in_trading_session() {
+        use chrono::TimeZone;
+        let config = AdaptivePollingConfig::default();
         
-        Ok(())
+        // Wednesday 2024-01-03
+        let open = chrono::Utc.with_ymd_and_hms(2024, 1, 3, 15, 0, 0).unwrap();
+        let night = chrono::Utc.with_ymd_and_hms(2024, 1, 3, 3, 0, 0).unwrap();
+        // Saturday 2024-01-06
+        let weekend = chrono::Utc.with_ymd_and_hms(2024, 1, 6, 15, 0, 0).unwrap();
+        
+        assert!(in_trading_session(open, &config, Tz::UTC));
+        assert!(!in_trading_session(night, &config, Tz::UTC));
+        assert!(!in_trading_session(weekend, &config, Tz::UTC));
+    }
+    
+    #[test]
+    fn test_divergence_pct() {
+        assert!((divergence_pct(101.0, 100.0) - 1.0).abs() < 1e-9);
+        assert!((divergence_pct(99.0, 100.0) - 1.0).abs() < 1e-9);
+        assert_eq!(divergence_pct(100.0, 100.0), 0.0);
+    }
+    
+    #[test]
+    fn test_quality_uses_weakest_quote() {
+        let config = DataQualityConfig::default();
+        let quality = DataQuality::assess(
+            &[
+                quote("SPY", QuoteSource::Live, 0.0),
+                quote("BND", QuoteSource::Simulated, 0.0),
+            ],
+            None,
+            &config,
+        );
+        
+        assert!((quality.score - config.simulated_score).abs() < 1e-9);
+        assert_eq!(quality.quotes.len(), 2);
+    }
+    
+    #[test]
+    fn test_quality_penalizes_age_and_disagreement() {
+        let config = DataQualityConfig::default();
+        let half_aged = quote("SPY", QuoteSource::Live, config.max_quote_age_secs / 2.0);
+        
+        let aged = DataQuality::assess(std::slice::from_ref(&half_aged), None, &config);
+        assert!((aged.score - 0.5).abs() < 1e-9);
+        
+        let disputed = DataQuality::assess(
+            &[half_aged],
+            Some(config.max_disagreement_pct),
+            &config,
+        );
+        assert_eq!(disputed.score, 0.0);
     }
 }
@@ -0,0 +1,345 @@
+//! Shadow Mode
+//!
+//! A/B harness for tuning the Analyst and Guardian. A second configuration
+//! (`[shadow] config_file`) is evaluated side by side with the live one on
+//! every fresh market snapshot, against the same portfolio, target, data
+//! quality and volatility. Both sides run the drift model and permit rule
+//! the agents use, so any difference in outcome comes from the parameters
+//! (thresholds, scoring weights, scripts) alone.
+//!
+//! The shadow never deposits or trades; it logs what it would have done.
+//! Snapshots on which the two sides decide differently are kept in
+//! `shadow:divergences`, with running totals in `state:shadow`
+//! (`GET /api/shadow`). Calendar rebalances and pipeline gating are the
+//! same for both sides and are not compared.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{debug, info};
+
+use crate::agents::analyst::{DriftAnalysis, DriftExplanation, DriftModel};
+use crate::agents::guardian::{self, PermitModel, VolatilityReading};
+use crate::agents::sensor::{DataQuality, MarketSnapshot};
+use crate::core::blackboard::{PortfolioState, RebalanceBaseline, TargetAllocation};
+use crate::core::correlation;
+use crate::core::physics::PheromoneType;
+use crate::core::runs;
+use crate::core::{Blackboard, Config};
+use crate::market::MarketDataProvider;
+
+/// Divergences returned by `GET /api/shadow` when no limit is given
+pub const DEFAULT_DIVERGENCE_LIMIT: usize = 50;
+
+/// What one side would have done with a snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum ShadowDecision {
+    /// Drift within threshold (or score below activation)
+    Hold,
+    /// Drift-triggered, but the trade costs more than it is worth
+    Suppressed,
+    /// Rebalance recommended, permit refused
+    Blocked { reason: String },
+    /// Rebalance recommended and permitted
+    Permit { action: String },
+}
+
+impl ShadowDecision {
+    /// Whether two decisions lead to different outcomes (the reasons a
+    /// permit was refused may differ without counting as a divergence)
+    pub fn diverges_from(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) != std::mem::discriminant(other)
+    }
+}
+
+/// Number of each decision taken by one side
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecisionCounts {
+    pub hold: u64,
+    pub suppressed: u64,
+    pub blocked: u64,
+    pub permit: u64,
+}
+
+impl DecisionCounts {
+    fn record(&mut self, decision: &ShadowDecision) {
+        match decision {
+            ShadowDecision::Hold => self.hold += 1,
+            ShadowDecision::Suppressed => self.suppressed += 1,
+            ShadowDecision::Blocked { .. } => self.blocked += 1,
+            ShadowDecision::Permit { .. } => self.permit += 1,
+        }
+    }
+}
+
+/// One snapshot on which live and shadow decided differently
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Divergence {
+    pub timestamp: String,
+    /// Timestamp of the market snapshot both sides evaluated
+    pub snapshot_timestamp: String,
+    pub drift_pct: f64,
+    pub live: ShadowDecision,
+    pub shadow: ShadowDecision,
+    /// Opportunity or script score each side decided on, if any
+    pub live_score: Option<f64>,
+    pub shadow_score: Option<f64>,
+}
+
+/// Running comparison of the live and shadow configurations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShadowReport {
+    pub started_at: String,
+    /// Shadow configuration file
+    pub config_file: String,
+    pub live_config_hash: String,
+    pub shadow_config_hash: String,
+    /// Snapshots evaluated by both sides
+    pub evaluations: u64,
+    pub divergences: u64,
+    pub live: DecisionCounts,
+    pub shadow: DecisionCounts,
+    pub last_evaluated_at: Option<String>,
+}
+
+/// Inputs both sides see for one snapshot
+struct Inputs<'a> {
+    snapshot: &'a MarketSnapshot,
+    portfolio: &'a PortfolioState,
+    target: &'a TargetAllocation,
+    baseline: Option<&'a RebalanceBaseline>,
+    quality: Option<&'a DataQuality>,
+    /// Whether the assets' correlations have spiked
+    correlation_spike: bool,
+    now: chrono::DateTime<chrono::Utc>,
+}
+
+/// One configuration's Analyst and Guardian
+struct Side {
+    config: Arc<Config>,
+    drift: DriftModel,
+    permit: PermitModel,
+}
+
+impl Side {
+    fn new(config: Arc<Config>) -> Self {
+        Self {
+            drift: DriftModel::new(config.clone()),
+            permit: PermitModel::new(config.clone()),
+            config,
+        }
+    }
+
+    /// Decide on a snapshot; `volatility` is this side's reading (none when
+    /// it could not be read, which blocks like it does for the Guardian).
+    /// Returns the decision, the drift and the score that decided.
+    fn decide(&mut self, inputs: &Inputs<'_>, volatility: Option<VolatilityReading>) -> (ShadowDecision, f64, Option<f64>) {
+        let assessment = self.drift.assess(inputs.snapshot, inputs.portfolio, inputs.target, inputs.baseline);
+        let (drift, score) = (assessment.drift, assessment.score);
+        if !assessment.triggered {
+            return (ShadowDecision::Hold, drift, score);
+        }
+        if assessment.is_suppressed(&self.config) {
+            return (ShadowDecision::Suppressed, drift, score);
+        }
+        
+        let action = if assessment.stocks_pct > inputs.target.stocks_pct {
+            "SELL stocks, BUY bonds"
+        } else {
+            "BUY stocks, SELL bonds"
+        };
+        let analysis = DriftAnalysis {
+            current_stocks_pct: assessment.stocks_pct,
+            current_bonds_pct: assessment.bonds_pct,
+            target_stocks_pct: inputs.target.stocks_pct,
+            target_bonds_pct: inputs.target.bonds_pct,
+            drift_pct: drift,
+            recommended_action: action.to_string(),
+            market_snapshot: inputs.snapshot.clone(),
+            explanation: DriftExplanation::build(inputs.portfolio, inputs.target, inputs.baseline, &self.config),
+            opportunity: assessment.opportunity,
+            cost_benefit: Some(assessment.cost_benefit),
+            trigger: Default::default(),
+            script_score: assessment.script_score,
+            analyzed_at: Some(inputs.now.to_rfc3339()),
+        };
+        
+        // Policies are compared on the data; the system mode refuses both alike
+        let decision = self.permit.decide(
+            inputs.now,
+            &analysis,
+            None,
+            inputs.quality.map(|q| q.score),
+            volatility,
+            inputs.correlation_spike,
+        );
+        match decision.reasons.first() {
+            None => (ShadowDecision::Permit { action: action.to_string() }, drift, score),
+            Some(refusal) => (ShadowDecision::Blocked { reason: refusal.to_string() }, drift, score),
+        }
+    }
+}
+
+/// Evaluate live and shadow on the same snapshot; returns the divergence,
+/// if they disagree, and updates the report
+fn compare(
+    live: &mut Side,
+    shadow: &mut Side,
+    inputs: &Inputs<'_>,
+    volatility: (Option<VolatilityReading>, Option<VolatilityReading>),
+    report: &mut ShadowReport,
+) -> Option<Divergence> {
+    let (live_decision, drift_pct, live_score) = live.decide(inputs, volatility.0);
+    let (shadow_decision, _, shadow_score) = shadow.decide(inputs, volatility.1);
+    
+    let timestamp = inputs.now.to_rfc3339();
+    report.evaluations += 1;
+    report.live.record(&live_decision);
+    report.shadow.record(&shadow_decision);
+    report.last_evaluated_at = Some(timestamp.clone());
+    
+    if !live_decision.diverges_from(&shadow_decision) {
+        return None;
+    }
+    report.divergences += 1;
+    Some(Divergence {
+        timestamp,
+        snapshot_timestamp: inputs.snapshot.timestamp.clone(),
+        drift_pct,
+        live: live_decision,
+        shadow: shadow_decision,
+        live_score,
+        shadow_score,
+    })
+}
+
+/// Compare the shadow configuration with the live one on every fresh
+/// market snapshot until the process exits
+pub async fn run_shadow(board: Arc<Blackboard>, market: Arc<dyn MarketDataProvider>) -> Result<()> {
+    let live_config = Arc::new(board.config().clone());
+    let settings = live_config.shadow.clone();
+    let shadow_config = Arc::new(
+        Config::load(&settings.config_file)
+            .with_context(|| format!("Failed to load shadow config {}", settings.config_file))?,
+    );
+    
+    let mut live = Side::new(live_config.clone());
+    let mut shadow = Side::new(shadow_config.clone());
+    let mut report = ShadowReport {
+        started_at: chrono::Utc::now().to_rfc3339(),
+        config_file: settings.config_file.clone(),
+        live_config_hash: runs::config_hash(&live_config),
+        shadow_config_hash: runs::config_hash(&shadow_config),
+        ..Default::default()
+    };
+    board.set_shadow_report(&report).await?;
+    
+    info!(
+        "👻 Shadow mode: comparing {} (config {}) with the live config {}",
+        settings.config_file, report.shadow_config_hash, report.live_config_hash
+    );
+    
+    let mut ticker = interval(Duration::from_millis(live_config.agent.sniff_interval_ms));
+    let mut last_snapshot: Option<String> = None;
+    loop {
+        ticker.tick().await;
+        
+        // Peek rather than sniff: the shadow must not show up as activity
+        let Some(reading) = board.peek::<MarketSnapshot>(PheromoneType::PriceFreshness).await? else {
+            continue;
+        };
+        let snapshot = reading.data;
+        if !reading.is_active || last_snapshot.as_ref() == Some(&snapshot.timestamp) {
+            continue;
+        }
+        last_snapshot = Some(snapshot.timestamp.clone());
+        
+        let portfolio = board.get_portfolio_state().await?.unwrap_or_default();
+        let target = board.get_target_allocation().await?;
+        let baseline = board.get_rebalance_baseline().await?;
+        let quality = board
+            .peek::<DataQuality>(PheromoneType::DataQuality)
+            .await?
+            .filter(|r| r.is_active)
+            .map(|r| r.data);
+        let volatility = (
+            guardian::read_volatility(market.as_ref(), &board, &live.config).await.ok(),
+            guardian::read_volatility(market.as_ref(), &board, &shadow.config).await.ok(),
+        );
+        
+        let inputs = Inputs {
+            snapshot: &snapshot,
+            portfolio: &portfolio,
+            target: &target,
+            baseline: baseline.as_ref(),
+            quality: quality.as_ref(),
+            correlation_spike: correlation::spike_active(&board, &live.config).await?,
+            now: chrono::Utc::now(),
+        };
+        match compare(&mut live, &mut shadow, &inputs, volatility, &mut report) {
+            Some(divergence) => {
+                info!(
+                    "👻 Shadow: Drift {:.1}% — live {:?}, shadow would {:?}",
+                    divergence.drift_pct, divergence.live, divergence.shadow
+                );
+                board.push_shadow_divergence(&divergence, settings.max_divergences).await?;
+            }
+            None => debug!("Shadow: Agrees with live on snapshot {}", snapshot.timestamp),
+        }
+        board.set_shadow_report(&report).await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::guardian::VolatilitySignal;
+    use crate::core::money;
+
+    fn side(drift_threshold: f64, vix_high_threshold: f64) -> Side {
+        let mut config = Config::default();
+        config.portfolio.drift_threshold = drift_threshold;
+        config.market.vix_high_threshold = vix_high_threshold;
+        config.blackout.enabled = false;
+        Side::new(Arc::new(config))
+    }
+
+    #[test]
+    fn test_divergent_thresholds() {
+        let snapshot = MarketSnapshot::new(Vec::new());
+        let portfolio = PortfolioState::from_values(money::from_f64(67_000.0), money::from_f64(33_000.0), None);
+        let target = TargetAllocation { stocks_pct: 60.0, bonds_pct: 40.0 };
+        let quality = DataQuality { score: 1.0, quotes: Vec::new(), disagreement_pct: None, timestamp: String::new() };
+        let inputs = Inputs {
+            snapshot: &snapshot,
+            portfolio: &portfolio,
+            target: &target,
+            baseline: None,
+            quality: Some(&quality),
+            correlation_spike: false,
+            now: chrono::Utc::now(),
+        };
+        let calm = Some(VolatilityReading { value: 15.0, signal: VolatilitySignal::Vix });
+        let mut report = ShadowReport::default();
+        
+        // Drift 7%: the live 5% threshold trades, a 10% shadow threshold holds
+        let divergence = compare(&mut side(5.0, 25.0), &mut side(10.0, 25.0), &inputs, (calm, calm), &mut report).unwrap();
+        assert!(matches!(divergence.live, ShadowDecision::Permit { .. }));
+        assert_eq!(divergence.shadow, ShadowDecision::Hold);
+        assert!((divergence.drift_pct - 7.0).abs() < 1e-9);
+        
+        // A tighter VIX ceiling blocks where live permits
+        let divergence = compare(&mut side(5.0, 25.0), &mut side(5.0, 12.0), &inputs, (calm, calm), &mut report).unwrap();
+        assert!(matches!(divergence.shadow, ShadowDecision::Blocked { ref reason } if reason == "VIX 15.0"));
+        
+        // Same parameters agree
+        assert!(compare(&mut side(5.0, 25.0), &mut side(5.0, 25.0), &inputs, (calm, calm), &mut report).is_none());
+        
+        assert_eq!(report.evaluations, 3);
+        assert_eq!(report.divergences, 2);
+        assert_eq!(report.live.permit, 3);
+        assert_eq!((report.shadow.hold, report.shadow.blocked, report.shadow.permit), (1, 1, 1));
+    }
+}
@@ -1,39 +1,104 @@
 //! Trader Agent
 //!
 //! The "executor" of the swarm. Only activates when given explicit permission
-//! from the Guardian (via ExecutionPermit pheromone). Executes the rebalance
-//! trade and updates the simulated portfolio balance.
+//! from the Guardian (via ExecutionPermit pheromone). Executes the legs the
+//! permit approved, never re-planned from a target that may have changed
+//! since, through an `Executor` and commits the new portfolio balance, rolling
+//! filled legs back if the trade cannot be completed. Partially filled legs
+//! are topped up only while the permit is still active. With the wash-sale
+//! guard enabled, a rebalance with a leg that would complete a wash sale is
+//! skipped and its legs recorded in the trade log. With the order queue
+//! enabled, permits wait on the blackboard for the execution window.
+//! Right before sending legs the Trader re-checks, in one composite sniff,
+//! that its permit and Price Freshness are still active; if either decayed
+//! since the permit was sniffed it aborts with a `PermitExpired` outcome.
+//! It also aborts, as `StaleState`, when the permit's drift analysis no
+//! longer describes the portfolio it would trade (the allocation changed or
+//! the trade already happened), recording the mismatch in the trade log.
+//! Legs that don't match the permit's plan hash (altered or corrupted on
+//! the blackboard) are refused as `Tampered` and never sent. While the
+//! kill switch is engaged or the swarm is HALTED nothing is sent either:
+//! permits are refused as `Halted` and queued orders expire.
+//! With asset groups, fills are written back to the imported holdings so
+//! the next group drift reading reflects the trade.
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use async_trait::async_trait;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::time::{interval, Duration};
-use tracing::{debug, info, warn};
+use tokio::time::{interval, Duration, Instant};
+use tracing::{debug, error, info, warn};
 
-use crate::agents::guardian::ExecutionPermit;
+use crate::agents::analyst::{DriftAnalysis, DriftExplanation};
+use crate::agents::guardian::{ExecutionPermit, VolatilitySignal};
 use crate::agents::Agent;
-use crate::core::blackboard::{AgentMetrics, PortfolioState, TradeLogEntry};
+use crate::core::blackboard::{AgentMetrics, PortfolioState, RebalanceBaseline, TargetAllocation, TradeLogEntry};
+use crate::core::bootstrap;
+use crate::core::error::DriftGuardError;
+use crate::core::groups;
+use crate::core::holdings;
+use crate::core::mode;
+use crate::core::money::{self, MoneyFormat};
 use crate::core::physics::PheromoneType;
+use crate::core::pipeline::{self, AgentRole};
+use crate::core::runtime;
+use crate::core::rebalance::{TaxImpact, MIN_LEG_NOTIONAL};
+use crate::core::timeline::TradeTimeline;
+use crate::core::order_queue::{self, PendingOrder};
+use crate::core::wash_sale::{self, RecentFill};
 use crate::core::{Blackboard, Config};
+use crate::execution::{Executor, Fill, OrderLeg, Side, UnfilledPolicy};
+
+/// Compare-and-set attempts before a filled trade is rolled back
+const MAX_COMMIT_ATTEMPTS: usize = 5;
+
+/// Pheromones re-checked right before a permit's legs are sent
+const PERMIT_INPUTS: [PheromoneType; 2] = [PheromoneType::ExecutionPermit, PheromoneType::PriceFreshness];
 
 /// Trade execution record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeRecord {
     pub trade_id: String,
     pub action: String,
-    pub stocks_delta: f64,
-    pub bonds_delta: f64,
+    pub stocks_delta: Decimal,
+    pub bonds_delta: Decimal,
     pub before_state: PortfolioState,
     pub after_state: PortfolioState,
     pub vix_at_execution: f64,
+    /// Signal behind `vix_at_execution` (VIX or its realized-vol stand-in)
+    #[serde(default)]
+    pub volatility_signal: VolatilitySignal,
+    /// The Analyst's explanation carried through the permit
+    #[serde(default)]
+    pub explanation: DriftExplanation,
     pub timestamp: String,
 }
 
+/// How a rebalance ended, short of an error
+#[derive(Debug, Clone)]
+pub enum ExecutionOutcome {
+    Executed(Box<TradeRecord>),
+    /// The wash-sale guard refused a leg; the legs are in the trade log
+    WashSaleSkipped,
+    /// A pheromone the execution relies on decayed before any leg was sent
+    PermitExpired(String),
+    /// The permit's analysis no longer matches the portfolio; the legs are
+    /// in the trade log
+    StaleState(String),
+    /// The permit's legs don't match its plan hash; the legs are in the
+    /// trade log
+    Tampered(String),
+    /// The kill switch is engaged or the swarm is HALTED; the legs are in
+    /// the trade log
+    Halted(String),
+}
+
 pub struct TraderAgent {
     name: String,
     config: Arc<Config>,
+    executor: Arc<dyn Executor>,
     running: AtomicBool,
     active: AtomicBool,
     action_count: AtomicU64,
@@ -42,10 +107,11 @@ pub struct TraderAgent {
 }
 
 impl TraderAgent {
-    pub fn new(config: Arc<Config>) -> Self {
+    pub fn new(config: Arc<Config>, executor: Arc<dyn Executor>) -> Self {
         Self {
             name: "Trader".to_string(),
             config,
+            executor,
             running: AtomicBool::new(false),
             active: AtomicBool::new(false),
             action_count: AtomicU64::new(0),
@@ -78,11 +144,27 @@ impl Agent for TraderAgent {
         self.running.store(true, Ordering::SeqCst);
         let sniff_interval = Duration::from_millis(self.config.agent.sniff_interval_ms);
         let mut ticker = interval(sniff_interval);
+        let queue = &self.config.order_queue;
         
-        info!("💰 Trader agent started (awaiting execution permits)");
+        if queue.enabled {
+            info!(
+                "💰 Trader agent started (awaiting execution permits, queued for {}–{} {})",
+                queue.window_open, queue.window_close, self.config.exchange.timezone
+            );
+        } else {
+            info!("💰 Trader agent started (awaiting execution permits)");
+        }
         
         while self.running.load(Ordering::SeqCst) {
-            ticker.tick().await;
+            runtime::tick(&mut ticker).await;
+            
+            // Queued orders run once the execution window is open
+            if queue.enabled {
+                if let Err(e) = self.process_queue(&board).await {
+                    warn!("Trader: Failed to process the order queue: {:#}", e);
+                }
+                self.active.store(false, Ordering::SeqCst);
+            }
             
             // Sniff for execution permit
             let permit: Option<ExecutionPermit> = board
@@ -90,6 +172,12 @@ impl Agent for TraderAgent {
                 .await?;
             
             if let Some(exec_permit) = permit {
+                let missing = pipeline::inactive_inputs(&board, AgentRole::Trader, &[PheromoneType::ExecutionPermit]).await?;
+                if !missing.is_empty() {
+                    debug!("Trader: Waiting on {:?}. Dormant.", missing);
+                    continue;
+                }
+                
                 // Idempotency check: skip if we already consumed this permit
                 {
                     let last = self.last_permit_timestamp.read().await;
@@ -105,64 +193,28 @@ impl Agent for TraderAgent {
                     *last = Some(exec_permit.timestamp.clone());
                 }
                 
+                // Another Trader process may have seen the same permit
+                if !board.claim_permit(&exec_permit.timestamp).await? {
+                    debug!("Trader: Permit {} claimed by another process, skipping.", exec_permit.timestamp);
+                    continue;
+                }
+                
                 self.active.store(true, Ordering::SeqCst);
                 
                 info!(
-                    "📜 Trader: Execution permit received! VIX={:.2} Action: {}",
+                    "📜 Trader: Execution permit received! {}={:.2} Action: {}",
+                    exec_permit.volatility_signal.label(),
                     exec_permit.vix_value,
                     exec_permit.drift_analysis.recommended_action
                 );
                 
-                // Execute the trade (simulated)
-                match self.execute_trade(&board, &exec_permit).await {
-                    Ok(record) => {
-                        info!(
-                            "✅ TRADE EXECUTED: {} | Δ Stocks: ${:.2} | Δ Bonds: ${:.2}",
-                            record.action,
-                            record.stocks_delta,
-                            record.bonds_delta
-                        );
-                        
-                        // Deposit trade record for audit trail
-                        board.deposit(PheromoneType::TradeExecuted, record.clone()).await?;
-                        self.action_count.fetch_add(1, Ordering::SeqCst);
-                        
-                        // Log to persistent trade history
-                        let log_entry = TradeLogEntry {
-                            id: uuid::Uuid::new_v4().to_string(),
-                            timestamp: chrono::Utc::now().to_rfc3339(),
-                            action: record.action.clone(),
-                            symbol: if record.stocks_delta.abs() > 0.01 {
-                                self.config.portfolio.stocks_symbol.clone()
-                            } else {
-                                self.config.portfolio.bonds_symbol.clone()
-                            },
-                            amount: record.stocks_delta.abs(),
-                            price: record.before_state.stocks_value / 100.0, // approximate per-share
-                            portfolio_value: record.after_state.stocks_value + record.after_state.bonds_value,
-                            drift_before: exec_permit.drift_analysis.drift_pct,
-                            drift_after: 0.0, // Will improve when multi-asset is connected
-                        };
-                        let _ = board.log_trade(&log_entry).await;
-                        
-                        let _ = board.set_agent_metrics(&AgentMetrics {
-                            name: "Trader".to_string(),
-                            is_active: true,
-                            action_count: self.action_count.load(Ordering::SeqCst),
-                            last_action: format!("Executed: {}", record.action),
-                            last_action_time: Some(chrono::Utc::now().to_rfc3339()),
-                        }).await;
-                    }
-                    Err(e) => {
-                        tracing::error!("Trader: Failed to execute trade: {}", e);
-                        let _ = board.set_agent_metrics(&AgentMetrics {
-                            name: "Trader".to_string(),
-                            is_active: false,
-                            action_count: self.action_count.load(Ordering::SeqCst),
-                            last_action: format!("Error: {}", e),
-                            last_action_time: Some(chrono::Utc::now().to_rfc3339()),
-                        }).await;
-                    }
+                if queue.enabled {
+                    self.queue_order(&board, exec_permit).await?;
+                } else {
+                    // Remainders are only retried while the permit is still active
+                    let permit_deadline = Instant::now()
+                        + board.time_until_inactive(PheromoneType::ExecutionPermit).await?.unwrap_or_default();
+                    self.handle_execution(&board, &exec_permit, permit_deadline, &PERMIT_INPUTS, true).await?;
                 }
                 
                 self.active.store(false, Ordering::SeqCst);
@@ -176,51 +228,678 @@ impl Agent for TraderAgent {
 }
 
 impl TraderAgent {
+    async fn set_metrics(&self, board: &Blackboard, is_active: bool, last_action: String) {
+        let _ = board.set_agent_metrics(&AgentMetrics {
+            name: self.name.clone(),
+            is_active,
+            action_count: self.action_count.load(Ordering::SeqCst),
+            last_action,
+            last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+            last_error: None,
+        }).await;
+    }
+    
+    /// Execute a permit's rebalance and report the outcome; remainders are
+    /// retried until `deadline`, `verify` must still be active when the legs
+    /// go out, and with `check_state` the permit's analysis must still match
+    /// the portfolio
+    async fn handle_execution(
+        &self,
+        board: &Blackboard,
+        permit: &ExecutionPermit,
+        deadline: Instant,
+        verify: &[PheromoneType],
+        check_state: bool,
+    ) -> Result<()> {
+        match self.execute_trade(board, permit, deadline, verify, check_state).await {
+            Ok(ExecutionOutcome::WashSaleSkipped) => {
+                self.set_metrics(board, true, "Skipped: wash sale".to_string()).await;
+            }
+            Ok(ExecutionOutcome::PermitExpired(reason)) => {
+                warn!("⌛ Trader: PermitExpired — {}; no legs sent", reason);
+                self.set_metrics(board, true, format!("PermitExpired: {}", reason)).await;
+            }
+            Ok(ExecutionOutcome::StaleState(reason)) => {
+                warn!("🧭 Trader: StaleState — {}; no legs sent", reason);
+                self.set_metrics(board, true, format!("StaleState: {}", reason)).await;
+            }
+            Ok(ExecutionOutcome::Tampered(reason)) => {
+                error!("🚨 Trader: Tampered — {}; no legs sent", reason);
+                self.set_metrics(board, true, format!("Tampered: {}", reason)).await;
+            }
+            Ok(ExecutionOutcome::Halted(reason)) => {
+                warn!("🛑 Trader: Halted — {}; no legs sent", reason);
+                self.set_metrics(board, true, format!("Halted: {}", reason)).await;
+            }
+            Ok(ExecutionOutcome::Executed(record)) => {
+                info!(
+                    "✅ TRADE EXECUTED: {} | Δ Stocks: ${:.2} | Δ Bonds: ${:.2}",
+                    record.action,
+                    record.stocks_delta,
+                    record.bonds_delta
+                );
+                
+                // Deposit trade record for audit trail
+                if self.config.pipeline.wiring(AgentRole::Trader).deposits(PheromoneType::TradeExecuted) {
+                    board.deposit(PheromoneType::TradeExecuted, record.clone()).await?;
+                }
+                self.action_count.fetch_add(1, Ordering::SeqCst);
+                self.set_metrics(board, true, format!("Executed: {}", record.action)).await;
+            }
+            Err(e) => {
+                tracing::error!("Trader: Failed to execute trade: {:#}", e);
+                let error = DriftGuardError::classify(&e, DriftGuardError::Execution);
+                let _ = board.report_execution_failure(&error).await;
+                let _ = board.set_agent_metrics(&AgentMetrics {
+                    name: self.name.clone(),
+                    is_active: false,
+                    action_count: self.action_count.load(Ordering::SeqCst),
+                    last_action: format!("Error: {}", e),
+                    last_action_time: Some(chrono::Utc::now().to_rfc3339()),
+                    last_error: Some(error),
+                }).await;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Queue a permit for the execution window, superseding any order still
+    /// waiting (the newer permit reflects the latest drift)
+    async fn queue_order(&self, board: &Blackboard, permit: ExecutionPermit) -> Result<()> {
+        for stale in board.get_pending_orders().await? {
+            if board.take_pending_order(&stale.id).await? {
+                info!("🗑️ Trader: Order queued at {} superseded by a newer permit", stale.queued_at);
+            }
+        }
+        
+        let now = chrono::Utc::now();
+        let order = PendingOrder::new(permit, now, &self.config.order_queue);
+        board.put_pending_order(&order).await?;
+        
+        let opens = order_queue::next_open(now, &self.config.order_queue, self.config.exchange.timezone)
+            .map_or_else(|| "no window configured".to_string(), |at| at.to_rfc3339());
+        info!("🕙 Trader: Rebalance queued for the execution window (opens {})", opens);
+        self.set_metrics(board, true, format!("Queued until {}", opens)).await;
+        Ok(())
+    }
+    
+    /// Execute queued orders while the execution window is open, expiring
+    /// those whose opportunity has decayed, and every one while halted
+    async fn process_queue(&self, board: &Blackboard) -> Result<()> {
+        let now = chrono::Utc::now();
+        if let Some(reason) = mode::halt_reason(board).await? {
+            for order in board.get_pending_orders().await? {
+                if !board.take_pending_order(&order.id).await? {
+                    continue;
+                }
+                warn!("⌛ Trader: Queued rebalance expired ({})", reason);
+                let state = board.get_portfolio_state().await?.unwrap_or_default();
+                let logged = format!("expired: {}", reason);
+                self.log_skipped(board, &order.permit, &state, &order.legs, "EXPIRED", &logged).await?;
+                self.set_metrics(board, true, format!("Expired: {}", reason)).await;
+            }
+            return Ok(());
+        }
+        let Some(close) = order_queue::open_until(now, &self.config.order_queue, self.config.exchange.timezone) else {
+            return Ok(());
+        };
+        
+        for order in board.get_pending_orders().await? {
+            if !board.take_pending_order(&order.id).await? {
+                continue;
+            }
+            self.active.store(true, Ordering::SeqCst);
+            
+            let state = board.get_portfolio_state().await?.unwrap_or_default();
+            let target = board.get_target_allocation().await?;
+            let target = holdings::achievable_target(
+                board,
+                &state,
+                &target,
+                Some(&order.permit.drift_analysis.market_snapshot),
+            ).await?;
+            let drift = (money::to_f64(state.stocks_pct) - target.stocks_pct).abs();
+            
+            if let Some(reason) = order.expiry_reason(now, drift, self.config.portfolio.drift_threshold) {
+                warn!("⌛ Trader: Queued rebalance expired ({})", reason);
+                let logged = format!("expired: {}", reason);
+                self.log_skipped(board, &order.permit, &state, &order.legs, "EXPIRED", &logged).await?;
+                self.set_metrics(board, true, format!("Expired: {}", reason)).await;
+            } else {
+                info!("🕙 Trader: Execution window open; executing rebalance queued at {}", order.queued_at);
+                // The queued permit has long decayed and its analysis aged with
+                // it; only the prices must be fresh
+                let deadline = Instant::now() + (close - now).to_std().unwrap_or_default();
+                self.handle_execution(board, &order.permit, deadline, &[PheromoneType::PriceFreshness], false).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TraderAgent {
+    /// Execute a permit's legs: fill every leg, then commit state, baseline
+    /// and trade log atomically. Filled legs are
+    /// reversed if any leg fails or the commit cannot be applied, and
+    /// remainders retried only until `permit_deadline`. Nothing is sent
+    /// unless the swarm isn't halted, every pheromone in `verify` is still
+    /// active and, with `check_state`, the permit's analysis still matches
+    /// the portfolio.
     async fn execute_trade(
         &self,
         board: &Blackboard,
         permit: &ExecutionPermit,
-    ) -> Result<TradeRecord> {
-        let before_state = board.get_portfolio_state().await?.unwrap_or_default();
-        let target = board.get_target_allocation().await?;
+        permit_deadline: Instant,
+        verify: &[PheromoneType],
+        check_state: bool,
+    ) -> Result<ExecutionOutcome> {
+        let (before_state, _) = board
+            .get_portfolio_snapshot()
+            .await?
+            .ok_or_else(|| anyhow!("No portfolio state to trade against"))?;
+        let current_target = board.get_target_allocation().await?;
+        let current_target = holdings::achievable_target(
+            board,
+            &before_state,
+            &current_target,
+            Some(&permit.drift_analysis.market_snapshot),
+        ).await?;
+        
+        // Only what the Guardian approved is sent
+        let legs = &permit.legs;
+        if legs.is_empty() {
+            return Ok(ExecutionOutcome::StaleState("permit approves no legs".to_string()));
+        }
+        let target = permit.target.clone().unwrap_or_else(|| current_target.clone());
         
-        // Calculate the trade amounts to reach target allocation
-        let total_value = before_state.total_value;
-        let target_stocks_value = total_value * (target.stocks_pct / 100.0);
-        let target_bonds_value = total_value * (target.bonds_pct / 100.0);
+        // Legs altered since the Guardian sealed them are never sent
+        if let Err(mismatch) = permit.verify_plan() {
+            self.log_skipped(board, permit, &before_state, legs, "TAMPERED", &format!("tampered plan: {}", mismatch)).await?;
+            return Ok(ExecutionOutcome::Tampered(mismatch));
+        }
         
-        let stocks_delta = target_stocks_value - before_state.stocks_value;
-        let bonds_delta = target_bonds_value - before_state.bonds_value;
+        // Nothing else is evaluated once an operator (or an outage) has
+        // halted the swarm
+        if let Some(reason) = mode::halt_reason(board).await? {
+            self.log_skipped(board, permit, &before_state, legs, "HALTED", &format!("halted: {}", reason)).await?;
+            return Ok(ExecutionOutcome::Halted(reason));
+        }
         
-        let action = if stocks_delta > 0.0 {
-            format!("BUY ${:.2} stocks, SELL ${:.2} bonds", stocks_delta.abs(), bonds_delta.abs())
-        } else {
-            format!("SELL ${:.2} stocks, BUY ${:.2} bonds", stocks_delta.abs(), bonds_delta.abs())
+        // Don't act on an analysis the portfolio has moved on from
+        let tolerance = self.config.execution.stale_state_tolerance_pct;
+        if let Some(mismatch) = check_state
+            .then(|| stale_state(&permit.drift_analysis, &before_state, &current_target, tolerance))
+            .flatten()
+        {
+            self.log_skipped(board, permit, &before_state, legs, "STALE", &format!("stale state: {}", mismatch)).await?;
+            return Ok(ExecutionOutcome::StaleState(mismatch));
+        }
+        
+        // The sells fund the buys, so a wash-sale conflict on any leg skips them all
+        let wash_sale = &self.config.wash_sale;
+        let holdings = if wash_sale.enabled { board.get_holdings().await? } else { None };
+        let realized_gain = |leg: &OrderLeg| {
+            holdings
+                .as_ref()
+                .and_then(|h| TaxImpact::estimate(std::slice::from_ref(leg), &before_state, h, &self.config))
+                .map(|tax| tax.realized_gain)
         };
+        if wash_sale.enabled {
+            let recent = board.get_recent_fills(wash_sale.max_fills).await?;
+            let conflicts = wash_sale::conflicts(legs, realized_gain, &recent, chrono::Utc::now(), wash_sale);
+            if !conflicts.is_empty() {
+                let reasons: Vec<&str> = conflicts.iter().map(|c| c.reason.as_str()).collect();
+                let reason = format!("wash sale: {}", reasons.join("; "));
+                warn!("🧾 Trader: Rebalance skipped ({})", reason);
+                self.log_skipped(board, permit, &before_state, legs, "SKIPPED", &reason).await?;
+                return Ok(ExecutionOutcome::WashSaleSkipped);
+            }
+        }
+        
+        // The permit was sniffed a tick ago; make sure it hasn't decayed since
+        let decayed: Vec<&str> = board
+            .sniff_composite(verify)
+            .await?
+            .iter()
+            .filter(|(_, data)| data.is_none())
+            .map(|(ptype, _)| ptype.label())
+            .collect();
+        if !decayed.is_empty() {
+            return Ok(ExecutionOutcome::PermitExpired(format!("{} no longer active", decayed.join(" and "))));
+        }
+        
+        let fills = self.fill_legs(legs, permit_deadline).await?;
+        if Instant::now() >= permit_deadline {
+            info!("⏳ Trader: Execution permit decayed while filling; committing what was filled");
+        }
+        
+        match self.commit(board, permit, &fills, &target).await {
+            Ok(record) => {
+                let timeline = TradeTimeline::assemble(permit, &record, &fills, board.run_id().map(str::to_string));
+                if let Err(e) = board.set_trade_timeline(&timeline).await {
+                    warn!("Trader: Failed to store the timeline of trade {}: {}", record.trade_id, e);
+                }
+                if wash_sale.enabled {
+                    for fill in fills.iter().filter(|f| f.filled_notional > Decimal::ZERO) {
+                        let filled = OrderLeg { notional: fill.filled_notional, ..fill.leg.clone() };
+                        let recent = RecentFill {
+                            symbol: fill.leg.symbol.clone(),
+                            side: fill.leg.side,
+                            notional: fill.filled_notional,
+                            realized_gain: (fill.leg.side == Side::Sell).then(|| realized_gain(&filled)).flatten(),
+                            timestamp: record.timestamp.clone(),
+                        };
+                        if let Err(e) = board.push_recent_fill(&recent, wash_sale.max_fills).await {
+                            warn!("Trader: Failed to record fill for the wash-sale guard: {}", e);
+                        }
+                    }
+                }
+                Ok(ExecutionOutcome::Executed(Box::new(record)))
+            }
+            Err(e) => {
+                self.compensate(&fills).await;
+                Err(e)
+            }
+        }
+    }
+    
+    /// Record every leg of a rebalance that was not executed (skipped by
+    /// the wash-sale guard, on stale state, a tampered plan or a halt, or
+    /// expired in the order queue)
+    async fn log_skipped(
+        &self,
+        board: &Blackboard,
+        permit: &ExecutionPermit,
+        state: &PortfolioState,
+        legs: &[OrderLeg],
+        label: &str,
+        reason: &str,
+    ) -> Result<()> {
+        let currency = bootstrap::current(board).await?.base_currency;
+        let fmt = MoneyFormat::new(&currency, self.config.reporting.locale);
+        
+        for leg in legs {
+            let side = match leg.side {
+                Side::Buy => "BUY",
+                Side::Sell => "SELL",
+            };
+            board.log_trade(&TradeLogEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                action: format!("{} {} {} {}", label, side, fmt.format(leg.notional), leg.symbol),
+                symbol: leg.symbol.clone(),
+                amount: leg.notional,
+                price: Decimal::ZERO,
+                portfolio_value: state.total_value,
+                drift_before: money::from_f64(permit.drift_analysis.drift_pct),
+                drift_after: money::from_f64(permit.drift_analysis.drift_pct),
+                explanation: Some(permit.drift_analysis.explanation.summary.clone()),
+                stocks_delta: Decimal::ZERO,
+                bonds_delta: Decimal::ZERO,
+                run_id: board.run_id().map(str::to_string),
+                skipped: Some(reason.to_string()),
+                plan_hash: permit.plan_hash.clone(),
+            }).await?;
+        }
+        Ok(())
+    }
+    
+    /// Execute legs in order; on failure, reverse whatever already filled
+    async fn fill_legs(&self, legs: &[OrderLeg], permit_deadline: Instant) -> Result<Vec<Fill>> {
+        let mut fills = Vec::with_capacity(legs.len());
+        for leg in legs {
+            match self.fill_leg(leg, permit_deadline).await {
+                Ok(fill) => fills.push(fill),
+                Err(e) => {
+                    self.compensate(&fills).await;
+                    return Err(e.context(format!("{:?} {} leg rejected", leg.side, leg.symbol)));
+                }
+            }
+        }
+        Ok(fills)
+    }
+    
+    /// Execute one leg, handling any unfilled remainder per `unfilled_policy`.
+    /// Only the first attempt can fail the leg; a failed retry keeps what
+    /// was already filled.
+    async fn fill_leg(&self, leg: &OrderLeg, permit_deadline: Instant) -> Result<Fill> {
+        let execution = &self.config.execution;
+        let mut fill = self.executor.execute(leg).await?;
+        let mut retries = 0;
+        
+        while fill.remaining() > MIN_LEG_NOTIONAL {
+            let stop_reason = if execution.unfilled_policy == UnfilledPolicy::Drop {
+                Some("drop policy".to_string())
+            } else if retries >= execution.max_fill_retries {
+                Some(format!("{} retries exhausted", retries))
+            } else if Instant::now() >= permit_deadline {
+                Some("permit decayed".to_string())
+            } else {
+                None
+            };
+            if let Some(reason) = stop_reason {
+                warn!(
+                    "✂️ Trader: Dropping unfilled ${:.2} of {:?} {} ({})",
+                    fill.remaining(), leg.side, leg.symbol, reason
+                );
+                break;
+            }
+            
+            retries += 1;
+            let remainder = OrderLeg { notional: fill.remaining(), ..leg.clone() };
+            match self.executor.execute(&remainder).await {
+                Ok(more) => {
+                    debug!("Trader: Retry {} filled ${:.2} of {}", retries, more.filled_notional, leg.symbol);
+                    fill.filled_notional += more.filled_notional;
+                }
+                Err(e) => {
+                    warn!("Trader: Retry for {} remainder failed: {}. Keeping partial fill.", leg.symbol, e);
+                    break;
+                }
+            }
+        }
         
-        // Update portfolio state
-        let after_state = PortfolioState {
-            total_value,
-            stocks_value: target_stocks_value,
-            bonds_value: target_bonds_value,
-            stocks_pct: target.stocks_pct,
-            bonds_pct: target.bonds_pct,
-            last_trade_time: Some(chrono::Utc::now().to_rfc3339()),
+        Ok(fill)
+    }
+    
+    /// Apply fills to the latest portfolio state with compare-and-set,
+    /// retrying if another writer (e.g. valuation) updated it meanwhile
+    async fn commit(
+        &self,
+        board: &Blackboard,
+        permit: &ExecutionPermit,
+        fills: &[Fill],
+        target: &TargetAllocation,
+    ) -> Result<TradeRecord> {
+        // Fills settle in whole minor units of the base currency
+        let currency = bootstrap::current(board).await?.base_currency;
+        // Group members count toward the sleeve their holdings import under
+        let portfolio = &self.config.portfolio;
+        let is_bonds = |symbol: &str| {
+            symbol == portfolio.bonds_symbol
+                || (symbol != portfolio.stocks_symbol && self.config.holdings_import.is_bond(symbol))
+        };
+        let filled = |bonds: bool| -> Decimal {
+            money::sum(fills.iter().filter(|f| is_bonds(&f.leg.symbol) == bonds).map(Fill::signed_notional), &currency)
         };
+        let stocks_delta = filled(false);
+        let bonds_delta = filled(true);
+        let fmt = MoneyFormat::new(&currency, self.config.reporting.locale);
+        let action = if stocks_delta > Decimal::ZERO {
+            format!("BUY {} stocks, SELL {} bonds", fmt.format(stocks_delta.abs()), fmt.format(bonds_delta.abs()))
+        } else {
+            format!("SELL {} stocks, BUY {} bonds", fmt.format(stocks_delta.abs()), fmt.format(bonds_delta.abs()))
+        };
+        
+        // One ID for the trade record, its trade log entry and its timeline
+        let trade_id = uuid::Uuid::new_v4().to_string();
+        for _ in 0..MAX_COMMIT_ATTEMPTS {
+            let (before_state, version) = board
+                .get_portfolio_snapshot()
+                .await?
+                .ok_or_else(|| anyhow!("Portfolio state disappeared during execution"))?;
+            
+            let after_state = PortfolioState::from_values(
+                money::sum([before_state.stocks_value, stocks_delta], &currency),
+                money::sum([before_state.bonds_value, bonds_delta], &currency),
+                Some(chrono::Utc::now().to_rfc3339()),
+            );
+            
+            let record = TradeRecord {
+                trade_id: trade_id.clone(),
+                action: action.clone(),
+                stocks_delta,
+                bonds_delta,
+                before_state,
+                after_state,
+                vix_at_execution: permit.vix_value,
+                volatility_signal: permit.volatility_signal,
+                explanation: permit.drift_analysis.explanation.clone(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            };
+            
+            // Persistent trade history entry, committed with the state
+            let log_entry = TradeLogEntry {
+                id: trade_id.clone(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                action: record.action.clone(),
+                symbol: if record.stocks_delta.abs() >= MIN_LEG_NOTIONAL {
+                    self.config.portfolio.stocks_symbol.clone()
+                } else {
+                    self.config.portfolio.bonds_symbol.clone()
+                },
+                amount: record.stocks_delta.abs(),
+                price: record.before_state.stocks_value / Decimal::ONE_HUNDRED, // approximate per-share
+                portfolio_value: record.after_state.total_value,
+                drift_before: money::from_f64(permit.drift_analysis.drift_pct),
+                drift_after: (record.after_state.stocks_pct - money::from_f64(target.stocks_pct)).abs(),
+                explanation: Some(record.explanation.summary.clone()),
+                stocks_delta: record.stocks_delta,
+                bonds_delta: record.bonds_delta,
+                run_id: board.run_id().map(str::to_string),
+                skipped: None,
+                plan_hash: permit.plan_hash.clone(),
+            };
+            
+            let baseline = RebalanceBaseline::new(&record.after_state, target);
+            if board.commit_trade(&version, &record.after_state, &baseline, &log_entry).await? {
+                if !self.config.portfolio.groups.is_empty() {
+                    if let Some(mut imported) = board.get_holdings().await? {
+                        groups::apply_fills(&mut imported, fills);
+                        board.set_holdings(&imported).await?;
+                    }
+                }
+                return Ok(record);
+            }
+            debug!("Trader: Portfolio changed during commit, retrying");
+        }
+        
+        bail!("Portfolio kept changing; could not commit after {} attempts", MAX_COMMIT_ATTEMPTS)
+    }
+    
+    /// Reverse filled legs (newest first) to undo a partial execution
+    async fn compensate(&self, fills: &[Fill]) {
+        for fill in fills.iter().rev().filter(|f| f.filled_notional > Decimal::ZERO) {
+            let reversal = OrderLeg {
+                symbol: fill.leg.symbol.clone(),
+                side: fill.leg.side.opposite(),
+                notional: fill.filled_notional,
+            };
+            match self.executor.execute(&reversal).await {
+                Ok(_) => warn!("↩️ Trader: Reversed {:?} ${:.2} {}", fill.leg.side, fill.filled_notional, fill.leg.symbol),
+                Err(e) => error!(
+                    "Trader: Failed to reverse {:?} ${:.2} {}: {}. Manual reconciliation needed!",
+                    fill.leg.side, fill.filled_notional, fill.leg.symbol, e
+                ),
+            }
+        }
+    }
+}
+
+/// How the permit's analysis differs from the portfolio about to be traded
+/// and its target, if by more than `tolerance_pct` points of stocks weight
+fn stale_state(
+    analysis: &DriftAnalysis,
+    state: &PortfolioState,
+    target: &TargetAllocation,
+    tolerance_pct: f64,
+) -> Option<String> {
+    if tolerance_pct <= 0.0 {
+        return None;
+    }
+    let checks = [
+        ("stocks", analysis.current_stocks_pct, money::to_f64(state.stocks_pct)),
+        ("target stocks", analysis.target_stocks_pct, target.stocks_pct),
+    ];
+    let mismatches: Vec<String> = checks
+        .iter()
+        .filter(|(_, permit, now)| (permit - now).abs() > tolerance_pct)
+        .map(|(label, permit, now)| format!("{} {:.1}% in permit vs {:.1}% now", label, permit, now))
+        .collect();
+    (!mismatches.is_empty()).then(|| mismatches.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::sensor::MarketSnapshot;
+    use crate::core::rebalance;
+    use std::sync::Mutex;
+    
+    /// Fills every leg except those for `reject_symbol`, recording calls
+    struct FlakyExecutor {
+        reject_symbol: String,
+        executed: Mutex<Vec<(String, Side)>>,
+    }
+    
+    #[async_trait]
+    impl Executor for FlakyExecutor {
+        async fn execute(&self, leg: &OrderLeg) -> Result<Fill> {
+            self.executed.lock().unwrap().push((leg.symbol.clone(), leg.side));
+            if leg.symbol == self.reject_symbol {
+                bail!("rejected by venue");
+            }
+            Ok(Fill { leg: leg.clone(), filled_notional: leg.notional })
+        }
+    }
+    
+    /// Fills half of whatever it is sent
+    struct HalfFillExecutor;
+    
+    #[async_trait]
+    impl Executor for HalfFillExecutor {
+        async fn execute(&self, leg: &OrderLeg) -> Result<Fill> {
+            Ok(Fill { leg: leg.clone(), filled_notional: leg.notional / Decimal::TWO })
+        }
+    }
+    
+    fn far_deadline() -> Instant {
+        Instant::now() + Duration::from_secs(60)
+    }
+    
+    fn trader_with_policy(policy: UnfilledPolicy, max_fill_retries: u32) -> TraderAgent {
+        let mut config = Config::default();
+        config.execution.unfilled_policy = policy;
+        config.execution.max_fill_retries = max_fill_retries;
+        TraderAgent::new(Arc::new(config), Arc::new(HalfFillExecutor))
+    }
+    
+    #[tokio::test]
+    async fn test_partial_fill_remainder_retried() {
+        let trader = trader_with_policy(UnfilledPolicy::Retry, 2);
+        let legs = rebalance::plan_legs(&trader.config.portfolio, Decimal::from(1_000), Decimal::from(-1_000));
+        let fills = trader.fill_legs(&legs, far_deadline()).await.unwrap();
         
-        board.set_portfolio_state(&after_state).await?;
-        
-        let record = TradeRecord {
-            trade_id: uuid::Uuid::new_v4().to_string(),
-            action: action.clone(),
-            stocks_delta,
-            bonds_delta,
-            before_state,
-            after_state,
-            vix_at_execution: permit.vix_value,
-            timestamp: chrono::Utc::now().to_rfc3339(),
+        // 500 + 250 + 125 across the first attempt and two retries
+        for fill in &fills {
+            assert_eq!(fill.leg.notional, Decimal::from(1_000));
+            assert_eq!(fill.filled_notional, Decimal::from(875));
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_partial_fill_remainder_dropped() {
+        let trader = trader_with_policy(UnfilledPolicy::Drop, 2);
+        let legs = rebalance::plan_legs(&trader.config.portfolio, Decimal::from(1_000), Decimal::from(-1_000));
+        let fills = trader.fill_legs(&legs, far_deadline()).await.unwrap();
+        assert!(fills.iter().all(|f| f.filled_notional == Decimal::from(500)));
+    }
+    
+    #[tokio::test]
+    async fn test_no_retry_after_permit_decays() {
+        let trader = trader_with_policy(UnfilledPolicy::Retry, 2);
+        let legs = rebalance::plan_legs(&trader.config.portfolio, Decimal::from(1_000), Decimal::from(-1_000));
+        let fills = trader.fill_legs(&legs, Instant::now()).await.unwrap();
+        assert!(fills.iter().all(|f| f.filled_notional == Decimal::from(500)));
+    }
+    
+    fn analysis(current: f64, target: f64) -> DriftAnalysis {
+        DriftAnalysis {
+            current_stocks_pct: current,
+            current_bonds_pct: 100.0 - current,
+            target_stocks_pct: target,
+            target_bonds_pct: 100.0 - target,
+            drift_pct: (current - target).abs(),
+            recommended_action: "SELL stocks, BUY bonds".to_string(),
+            market_snapshot: MarketSnapshot::new(Vec::new()),
+            explanation: DriftExplanation::default(),
+            opportunity: None,
+            cost_benefit: None,
+            trigger: Default::default(),
+            script_score: None,
+            analyzed_at: None,
+        }
+    }
+    
+    #[test]
+    fn test_stale_state() {
+        // 60/40 portfolio against a 60/40 target
+        let state = PortfolioState::default();
+        let target = TargetAllocation { stocks_pct: 60.0, bonds_pct: 40.0 };
+        
+        // Prices moved a little since the analysis: still the same trade
+        assert_eq!(stale_state(&analysis(60.6, 60.0), &state, &target, 1.0), None);
+        // The rebalance already happened
+        assert_eq!(
+            stale_state(&analysis(66.0, 60.0), &state, &target, 1.0),
+            Some("stocks 66.0% in permit vs 60.0% now".to_string())
+        );
+        // The target was changed under the permit
+        assert!(stale_state(&analysis(60.0, 50.0), &state, &target, 1.0).unwrap().starts_with("target stocks"));
+        // 0 disables the check
+        assert_eq!(stale_state(&analysis(66.0, 50.0), &state, &target, 0.0), None);
+    }
+    
+    #[test]
+    fn test_plan_hash() {
+        let config = Config::default();
+        let mut permit = ExecutionPermit {
+            vix_value: 15.0,
+            volatility_signal: VolatilitySignal::Vix,
+            volatility_status: "MODERATE".to_string(),
+            data_quality: 1.0,
+            drift_analysis: analysis(67.0, 60.0),
+            legs: rebalance::plan_legs(&config.portfolio, Decimal::from(-7_000), Decimal::from(7_000)),
+            target: Some(TargetAllocation { stocks_pct: 60.0, bonds_pct: 40.0 }),
+            plan_hash: None,
+            timestamp: "2026-03-02T15:00:00Z".to_string(),
         };
+        assert_eq!(permit.verify_plan(), Err("permit carries no plan hash".to_string()));
+        
+        permit.plan_hash = Some(permit.compute_plan_hash());
+        assert_eq!(permit.verify_plan(), Ok(()));
+        // The analysis isn't part of the plan
+        permit.drift_analysis.drift_pct = 8.0;
+        assert_eq!(permit.verify_plan(), Ok(()));
+        
+        // A leg inflated on the blackboard is caught
+        let mut tampered = permit.clone();
+        tampered.legs[0].notional = Decimal::from(70_000);
+        assert!(tampered.verify_plan().unwrap_err().contains("but was approved as"));
+        let mut retargeted = permit.clone();
+        retargeted.target = Some(TargetAllocation { stocks_pct: 20.0, bonds_pct: 80.0 });
+        assert!(retargeted.verify_plan().is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_failed_leg_reverses_filled_legs() {
+        let executor = Arc::new(FlakyExecutor {
+            reject_symbol: "BND".to_string(),
+            executed: Mutex::new(Vec::new()),
+        });
+        let trader = TraderAgent::new(Arc::new(Config::default()), executor.clone());
+        
+        // Overweight stocks: sell SPY first, then the BND buy is rejected
+        let legs = rebalance::plan_legs(&trader.config.portfolio, Decimal::from(-5_000), Decimal::from(5_000));
+        assert!(trader.fill_legs(&legs, far_deadline()).await.is_err());
         
-        Ok(record)
+        assert_eq!(
+            *executor.executed.lock().unwrap(),
+            vec![
+                ("SPY".to_string(), Side::Sell),
+                ("BND".to_string(), Side::Buy),
+                ("SPY".to_string(), Side::Buy),
+            ]
+        );
     }
 }
@@ -0,0 +1,42 @@
+//! Kafka Sink
+//!
+//! Produces bridged messages to the topic names, keyed by pheromone (or
+//! symbol for trades) so related messages stay ordered within a partition.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use std::time::Duration;
+
+use super::EventSink;
+
+/// How long a send may wait for room in the producer queue
+const QUEUE_TIMEOUT: Duration = Duration::from_secs(1);
+
+pub struct KafkaSink {
+    producer: FutureProducer,
+}
+
+impl KafkaSink {
+    /// Create a producer for comma-separated bootstrap servers
+    pub fn new(servers: &str) -> Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", servers)
+            .set("message.timeout.ms", "10000")
+            .create()?;
+        Ok(Self { producer })
+    }
+}
+
+#[async_trait]
+impl EventSink for KafkaSink {
+    async fn publish(&self, topic: &str, key: &str, payload: Vec<u8>) -> Result<()> {
+        let record = FutureRecord::to(topic).key(key).payload(&payload);
+        self.producer
+            .send(record, QUEUE_TIMEOUT)
+            .await
+            .map_err(|(e, _)| e)?;
+        Ok(())
+    }
+}
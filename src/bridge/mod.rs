@@ -0,0 +1,328 @@
+//! Event Bridge
+//!
+//! Publishes what happens in the swarm to an existing event backbone:
+//! pheromone events on `<prefix>.events`, executed trades on
+//! `<prefix>.trades`, and risk alerts (data anomalies, failed executions,
+//! position mismatches, agent errors) on `<prefix>.alerts`. Alerts carry
+//! the categorized error, when there is one, for routing by category. Every message carries the
+//! swarm name and run ID and is encoded as JSON or MessagePack
+//! (`[bridge] format`).
+//!
+//! Backends are optional features, `nats` and `kafka`. Events are
+//! broadcast in-process, so each process publishes the events of the
+//! agents it runs. Publishing is best effort: failures are logged and
+//! never hold up the swarm.
+
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "nats")]
+pub mod nats;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use crate::core::blackboard::{PheromoneAction, PheromoneEvent, TradeLogEntry};
+use crate::core::config::BridgeConfig;
+use crate::core::error::DriftGuardError;
+use crate::core::physics::PheromoneType;
+use crate::core::Blackboard;
+use crate::server::encoding::WireEncoding;
+
+/// Trades fetched per check; more than one rebalance's legs
+const TRADE_PAGE: usize = 50;
+
+/// Messaging system the bridge publishes to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BridgeBackend {
+    #[default]
+    Nats,
+    Kafka,
+}
+
+impl BridgeBackend {
+    /// Cargo feature that compiles this backend in
+    pub fn feature(&self) -> &'static str {
+        match self {
+            Self::Nats => "nats",
+            Self::Kafka => "kafka",
+        }
+    }
+}
+
+/// A message published by the bridge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BridgeMessage {
+    /// A pheromone event, as streamed to the dashboard
+    Event {
+        swarm: String,
+        seq: u64,
+        timestamp: String,
+        action: String,
+        pheromone: String,
+        intensity: f64,
+        detail: Option<String>,
+        count: u32,
+        run_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<DriftGuardError>,
+    },
+    /// One executed trade leg from the trade log
+    Trade { swarm: String, trade: TradeLogEntry },
+    /// An event that needs attention
+    Alert {
+        swarm: String,
+        timestamp: String,
+        kind: String,
+        pheromone: String,
+        detail: Option<String>,
+        run_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<DriftGuardError>,
+    },
+}
+
+/// Destination of bridged messages
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// Publish one encoded message; `key` groups related messages (Kafka
+    /// partitioning)
+    async fn publish(&self, topic: &str, key: &str, payload: Vec<u8>) -> Result<()>;
+}
+
+/// Topic (NATS subject) names under a prefix
+#[derive(Debug, Clone)]
+pub struct Topics {
+    pub events: String,
+    pub trades: String,
+    pub alerts: String,
+}
+
+impl Topics {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            events: format!("{}.events", prefix),
+            trades: format!("{}.trades", prefix),
+            alerts: format!("{}.alerts", prefix),
+        }
+    }
+}
+
+/// Whether an event is a risk alert
+pub fn is_alert(action: &PheromoneAction) -> bool {
+    matches!(
+        action,
+        PheromoneAction::DataAnomaly
+            | PheromoneAction::ExecutionFailed
+            | PheromoneAction::PositionMismatch
+            | PheromoneAction::Error
+    )
+}
+
+/// Whether an event announces newly logged trades
+fn is_trade(event: &PheromoneEvent) -> bool {
+    matches!(event.action, PheromoneAction::Deposited)
+        && event.pheromone_type == PheromoneType::TradeExecuted.label()
+}
+
+/// Messages to publish for one event, with their topics
+pub fn messages_for(
+    event: &PheromoneEvent,
+    swarm: &str,
+    topics: &Topics,
+    include_sniffed: bool,
+) -> Vec<(String, BridgeMessage)> {
+    if matches!(event.action, PheromoneAction::Sniffed) && !include_sniffed {
+        return Vec::new();
+    }
+    
+    let timestamp = event.timestamp.to_rfc3339();
+    let mut messages = vec![(
+        topics.events.clone(),
+        BridgeMessage::Event {
+            swarm: swarm.to_string(),
+            seq: event.seq,
+            timestamp: timestamp.clone(),
+            action: format!("{:?}", event.action),
+            pheromone: event.pheromone_type.clone(),
+            intensity: event.intensity,
+            detail: event.detail.clone(),
+            count: event.count,
+            run_id: event.run_id.clone(),
+            error: event.error.clone(),
+        },
+    )];
+    if is_alert(&event.action) {
+        messages.push((
+            topics.alerts.clone(),
+            BridgeMessage::Alert {
+                swarm: swarm.to_string(),
+                timestamp,
+                kind: format!("{:?}", event.action),
+                pheromone: event.pheromone_type.clone(),
+                detail: event.detail.clone(),
+                run_id: event.run_id.clone(),
+                error: event.error.clone(),
+            },
+        ));
+    }
+    messages
+}
+
+/// Encode a message in the configured format
+pub fn encode(msg: &BridgeMessage, format: WireEncoding) -> Result<Vec<u8>> {
+    Ok(match format {
+        WireEncoding::Json => serde_json::to_vec(msg)?,
+        WireEncoding::Msgpack => rmp_serde::to_vec_named(msg)?,
+    })
+}
+
+/// Connect to the configured backend
+pub async fn connect(config: &BridgeConfig) -> Result<Arc<dyn EventSink>> {
+    match config.backend {
+        #[cfg(feature = "nats")]
+        BridgeBackend::Nats => Ok(Arc::new(nats::NatsSink::connect(&config.servers).await?)),
+        #[cfg(feature = "kafka")]
+        BridgeBackend::Kafka => Ok(Arc::new(kafka::KafkaSink::new(&config.servers)?)),
+        #[allow(unreachable_patterns)]
+        backend => bail!(
+            "bridge backend {:?} requires building with `--features {}`",
+            backend,
+            backend.feature()
+        ),
+    }
+}
+
+/// Forward this process's events, trades and alerts to the bridge
+pub async fn run_bridge(board: Arc<Blackboard>) -> Result<()> {
+    let config = board.config().bridge.clone();
+    let swarm = board.config().swarm.name.clone();
+    let topics = Topics::new(&config.topic_prefix);
+    let sink = connect(&config).await?;
+    let mut events = board.subscribe();
+    
+    // Only trades executed from now on are published
+    let mut last_trade = board.get_trade_log_page(0, 1).await?.first().map(|t| t.id.clone());
+    
+    info!(
+        "🌉 Event bridge publishing to {:?} at {} ({}.*)",
+        config.backend, config.servers, config.topic_prefix
+    );
+    
+    let publish = |topic: String, msg: BridgeMessage| {
+        let sink = sink.clone();
+        async move {
+            let key = match &msg {
+                BridgeMessage::Event { pheromone, .. } | BridgeMessage::Alert { pheromone, .. } => pheromone.clone(),
+                BridgeMessage::Trade { trade, .. } => trade.symbol.clone(),
+            };
+            let result = match encode(&msg, config.format) {
+                Ok(payload) => sink.publish(&topic, &key, payload).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("Event bridge: Failed to publish to {}: {:#}", topic, e);
+            }
+        }
+    };
+    
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("Event bridge: Fell behind, {} events not published", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => return Ok(()),
+        };
+        
+        if is_trade(&event) {
+            let recent = board.get_trade_log_page(0, TRADE_PAGE).await?;
+            let new: Vec<_> = recent
+                .into_iter()
+                .take_while(|t| Some(&t.id) != last_trade.as_ref())
+                .collect();
+            if let Some(newest) = new.first() {
+                last_trade = Some(newest.id.clone());
+            }
+            for trade in new.into_iter().rev() {
+                publish(topics.trades.clone(), BridgeMessage::Trade { swarm: swarm.clone(), trade }).await;
+            }
+        }
+        
+        for (topic, msg) in messages_for(&event, &swarm, &topics, config.include_sniffed) {
+            publish(topic, msg).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(action: PheromoneAction, pheromone: PheromoneType) -> PheromoneEvent {
+        PheromoneEvent {
+            seq: 7,
+            timestamp: chrono::Utc::now(),
+            pheromone_type: pheromone.label().to_string(),
+            intensity: 0.9,
+            action,
+            detail: Some("price jump".to_string()),
+            count: 1,
+            run_id: Some("run-1".to_string()),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_routes_events_and_alerts() {
+        let topics = Topics::new("driftguard");
+        
+        let deposit = event(PheromoneAction::Deposited, PheromoneType::PriceFreshness);
+        let messages = messages_for(&deposit, "default", &topics, false);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].0, "driftguard.events");
+        
+        let anomaly = event(PheromoneAction::DataAnomaly, PheromoneType::PriceFreshness);
+        let messages = messages_for(&anomaly, "default", &topics, false);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].0, "driftguard.alerts");
+        assert!(matches!(&messages[1].1, BridgeMessage::Alert { kind, .. } if kind == "DataAnomaly"));
+        
+        let sniff = event(PheromoneAction::Sniffed, PheromoneType::PriceFreshness);
+        assert!(messages_for(&sniff, "default", &topics, false).is_empty());
+        assert_eq!(messages_for(&sniff, "default", &topics, true).len(), 1);
+        
+        assert!(is_trade(&event(PheromoneAction::Deposited, PheromoneType::TradeExecuted)));
+        assert!(!is_trade(&event(PheromoneAction::Decayed, PheromoneType::TradeExecuted)));
+    }
+
+    #[test]
+    fn test_encoding_formats() {
+        let topics = Topics::new("dg");
+        let anomaly = event(PheromoneAction::ExecutionFailed, PheromoneType::TradeExecuted);
+        let (_, alert) = messages_for(&anomaly, "retirement", &topics, false).pop().unwrap();
+        
+        let json: serde_json::Value = serde_json::from_slice(&encode(&alert, WireEncoding::Json).unwrap()).unwrap();
+        assert_eq!(json["type"], "alert");
+        assert_eq!(json["swarm"], "retirement");
+        assert_eq!(json["run_id"], "run-1");
+        
+        let packed = encode(&alert, WireEncoding::Msgpack).unwrap();
+        let decoded: BridgeMessage = rmp_serde::from_slice(&packed).unwrap();
+        assert!(matches!(decoded, BridgeMessage::Alert { kind, .. } if kind == "ExecutionFailed"));
+        
+        // Agent errors are alerts that carry their category
+        let mut failure = event(PheromoneAction::Error, PheromoneType::PriceFreshness);
+        failure.error = Some(DriftGuardError::Provider("timed out".to_string()));
+        let (_, alert) = messages_for(&failure, "retirement", &topics, false).pop().unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&encode(&alert, WireEncoding::Json).unwrap()).unwrap();
+        assert_eq!(json["kind"], "Error");
+        assert_eq!(json["error"]["category"], "provider");
+    }
+}
@@ -0,0 +1,35 @@
+//! NATS Sink
+//!
+//! Publishes bridged messages as core NATS messages on the topic subjects.
+//! The client reconnects on its own; messages published while it is
+//! disconnected are buffered by the client.
+
+use anyhow::Result;
+use async_nats::{Client, ServerAddr};
+use async_trait::async_trait;
+
+use super::EventSink;
+
+pub struct NatsSink {
+    client: Client,
+}
+
+impl NatsSink {
+    /// Connect to one or more comma-separated server URLs
+    pub async fn connect(servers: &str) -> Result<Self> {
+        let addrs = servers
+            .split(',')
+            .map(|s| s.trim().parse::<ServerAddr>())
+            .collect::<Result<Vec<_>, _>>()?;
+        let client = async_nats::connect(addrs).await?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl EventSink for NatsSink {
+    async fn publish(&self, topic: &str, _key: &str, payload: Vec<u8>) -> Result<()> {
+        self.client.publish(topic.to_string(), payload.into()).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+//! DriftGuard Client SDK
+//!
+//! Typed async client for a running DriftGuard server, for Rust bots,
+//! alternative dashboards and integration tests. REST calls return the
+//! same types the server serializes, and `subscribe` opens a WebSocket
+//! stream of `DashboardMessage`s that reconnects on its own and backfills
+//! events missed while disconnected.
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use driftguard::client::Client;
+//! use driftguard::server::DashboardMessage;
+//!
+//! let client = Client::new("http://localhost:8080").with_operator("bot");
+//! let mut updates = client.subscribe();
+//! while let Some(msg) = updates.next().await {
+//!     if let DashboardMessage::PortfolioUpdate { portfolio } = msg {
+//!         println!("portfolio: {:.2}", portfolio.total_value);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Enabled with the `client` feature.
+
+mod subscription;
+
+pub use subscription::Subscription;
+
+use anyhow::{bail, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::core::allocation::AllocationRequest;
+use crate::core::audit::{AuditEntry, AuditQuery};
+use crate::core::blackboard::{MarketUpdate, PheromoneReading, PortfolioState, TargetAllocation};
+use crate::core::bootstrap::{PortfolioBootstrap, PortfolioInit};
+use crate::core::config::AllocationPreset;
+use crate::server::encoding::WireEncoding;
+
+/// Client for one DriftGuard server
+#[derive(Debug, Clone)]
+pub struct Client {
+    /// e.g. `http://localhost:8080`
+    base_url: String,
+    http: reqwest::Client,
+    token: Option<String>,
+    operator: Option<String>,
+    api_key: Option<String>,
+    encoding: WireEncoding,
+}
+
+/// Response of `POST /api/portfolio/init`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PortfolioInitResult {
+    pub init: PortfolioBootstrap,
+    pub portfolio: PortfolioState,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            token: None,
+            operator: None,
+            api_key: None,
+            encoding: WireEncoding::Json,
+        }
+    }
+
+    /// Operator token sent with commands (`X-Operator-Token`)
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// API key of an `[[auth.users]]` user (`X-Api-Key`)
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Name recorded in the server's audit log
+    pub fn with_operator(mut self, operator: impl Into<String>) -> Self {
+        self.operator = Some(operator.into());
+        self
+    }
+
+    /// Frame encoding requested for subscriptions
+    pub fn with_encoding(mut self, encoding: WireEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// The operator token, for WebSocket commands that carry it
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// Open a WebSocket subscription (connects in the background)
+    pub fn subscribe(&self) -> Subscription {
+        let ws_base = self
+            .base_url
+            .replacen("https://", "wss://", 1)
+            .replacen("http://", "ws://", 1);
+        let mut url = format!(
+            "{}/ws?encoding={}",
+            ws_base,
+            match self.encoding {
+                WireEncoding::Json => "json",
+                WireEncoding::Msgpack => "msgpack",
+            }
+        );
+        if let Some(operator) = &self.operator {
+            url.push_str("&operator=");
+            url.extend(form_urlencoded(operator));
+        }
+        if let Some(api_key) = &self.api_key {
+            url.push_str("&api_key=");
+            url.extend(form_urlencoded(api_key));
+        }
+        Subscription::spawn(url)
+    }
+
+    /// Whether the server answers its health check
+    pub async fn health(&self) -> Result<bool> {
+        let response = self.http.get(self.url("/health")).send().await?;
+        Ok(response.status().is_success())
+    }
+
+    /// Every pheromone with its payload (read without sniffing)
+    pub async fn pheromones(&self) -> Result<Vec<PheromoneReading<serde_json::Value>>> {
+        self.get("/api/pheromones").await
+    }
+
+    /// Latest market quotes
+    pub async fn market(&self) -> Result<MarketUpdate> {
+        self.get("/api/market").await
+    }
+
+    /// Configured allocation presets
+    pub async fn presets(&self) -> Result<Vec<AllocationPreset>> {
+        self.get("/api/presets").await
+    }
+
+    /// Current starting point of the deployment
+    pub async fn portfolio_init(&self) -> Result<PortfolioBootstrap> {
+        self.get("/api/portfolio/init").await
+    }
+
+    /// Audited client commands, most recent first
+    pub async fn audit(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        let request = self.http.get(self.url("/api/audit")).query(query);
+        self.send(request).await
+    }
+
+    /// Change the target allocation (preset or explicit weights)
+    pub async fn set_allocation(&self, request: &AllocationRequest) -> Result<TargetAllocation> {
+        let request = self.http.post(self.url("/api/allocation")).json(request);
+        self.send(request).await
+    }
+
+    /// Set starting cash, holdings and base currency and restart the portfolio
+    pub async fn init_portfolio(&self, init: &PortfolioInit) -> Result<PortfolioInitResult> {
+        let request = self.http.post(self.url("/api/portfolio/init")).json(init);
+        self.send(request).await
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send(self.http.get(self.url(path))).await
+    }
+
+    /// Attach identity headers, send, and decode the body or the server's error
+    async fn send<T: DeserializeOwned>(&self, mut request: reqwest::RequestBuilder) -> Result<T> {
+        if let Some(token) = &self.token {
+            request = request.header("x-operator-token", token);
+        }
+        if let Some(operator) = &self.operator {
+            request = request.header("x-operator-id", operator);
+        }
+        if let Some(api_key) = &self.api_key {
+            request = request.header("x-api-key", api_key);
+        }
+        
+        let response = request.send().await?;
+        let status = response.status();
+        let body = response.bytes().await?;
+        if !status.is_success() {
+            match serde_json::from_slice::<ErrorBody>(&body) {
+                Ok(err) => bail!("{}: {}", status, err.error),
+                Err(_) => bail!("{}", status),
+            }
+        }
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
+
+/// Percent-encode a query value
+fn form_urlencoded(value: &str) -> impl Iterator<Item = String> + '_ {
+    value.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urls() {
+        let client = Client::new("http://localhost:8080/").with_operator("ops team");
+        assert_eq!(client.url("/api/market"), "http://localhost:8080/api/market");
+        assert_eq!(form_urlencoded("ops team").collect::<String>(), "ops%20team");
+    }
+}
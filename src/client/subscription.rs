@@ -0,0 +1,183 @@
+//! WebSocket Subscription
+//!
+//! A background task keeps one connection to `/ws` open, reconnecting with
+//! exponential backoff. After a reconnect it asks the server to replay the
+//! events since the last one it saw, so consumers see a gap-free stream.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use futures::{SinkExt, StreamExt};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+
+use crate::server::protocol::{ClientMessage, DashboardMessage};
+
+/// First and longest wait between reconnect attempts
+const RECONNECT_MIN: Duration = Duration::from_millis(500);
+const RECONNECT_MAX: Duration = Duration::from_secs(30);
+
+/// A live stream of dashboard messages
+///
+/// Commands sent while disconnected are delivered after the reconnect.
+/// Dropping the subscription closes the connection.
+pub struct Subscription {
+    messages: mpsc::UnboundedReceiver<DashboardMessage>,
+    commands: mpsc::UnboundedSender<ClientMessage>,
+    task: JoinHandle<()>,
+}
+
+impl Subscription {
+    pub(super) fn spawn(url: String) -> Self {
+        let (message_tx, messages) = mpsc::unbounded_channel();
+        let (commands, command_rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(run(url, message_tx, command_rx));
+        Self { messages, commands, task }
+    }
+
+    /// Next message from the server (`None` once the subscription ends)
+    pub async fn next(&mut self) -> Option<DashboardMessage> {
+        self.messages.recv().await
+    }
+
+    /// Send a command to the server
+    pub fn send(&self, msg: ClientMessage) -> Result<()> {
+        self.commands.send(msg).map_err(|_| anyhow!("subscription closed"))
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Wait before reconnect attempt `attempt` (0-based)
+fn reconnect_delay(attempt: u32) -> Duration {
+    RECONNECT_MIN
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(RECONNECT_MAX)
+}
+
+/// Timestamp of the newest event in a message, if it carries events
+fn last_event_time(msg: &DashboardMessage) -> Option<DateTime<Utc>> {
+    let event = match msg {
+        DashboardMessage::Replay { events, .. } => events.last()?,
+        other => other,
+    };
+    match event {
+        DashboardMessage::Event { timestamp, .. } => DateTime::parse_from_rfc3339(timestamp)
+            .ok()
+            .map(|t| t.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
+fn decode(frame: &Message) -> Option<DashboardMessage> {
+    match frame {
+        Message::Text(text) => serde_json::from_str(text).ok(),
+        Message::Binary(bytes) => rmp_serde::from_slice(bytes).ok(),
+        _ => None,
+    }
+}
+
+async fn run(
+    url: String,
+    messages: mpsc::UnboundedSender<DashboardMessage>,
+    mut commands: mpsc::UnboundedReceiver<ClientMessage>,
+) {
+    let mut attempt = 0;
+    let mut last_event: Option<DateTime<Utc>> = None;
+    
+    loop {
+        match tokio_tungstenite::connect_async(url.as_str()).await {
+            Ok((stream, _)) => {
+                debug!("Connected to {}", url);
+                attempt = 0;
+                let (mut sink, mut source) = stream.split();
+                
+                if let Some(since) = last_event {
+                    if let Ok(json) = serde_json::to_string(&ClientMessage::Replay { since }) {
+                        let _ = sink.send(Message::Text(json)).await;
+                    }
+                }
+                
+                loop {
+                    tokio::select! {
+                        frame = source.next() => {
+                            let Some(Ok(frame)) = frame else {
+                                break;
+                            };
+                            if frame.is_close() {
+                                break;
+                            }
+                            if let Some(msg) = decode(&frame) {
+                                if let Some(time) = last_event_time(&msg) {
+                                    last_event = Some(time);
+                                }
+                                if messages.send(msg).is_err() {
+                                    return;
+                                }
+                            }
+                        }
+                        command = commands.recv() => {
+                            let Some(command) = command else {
+                                return;
+                            };
+                            match serde_json::to_string(&command) {
+                                Ok(json) => {
+                                    if sink.send(Message::Text(json)).await.is_err() {
+                                        break;
+                                    }
+                                }
+                                Err(e) => warn!("Failed to encode command: {}", e),
+                            }
+                        }
+                    }
+                }
+                warn!("Disconnected from {}; reconnecting", url);
+            }
+            Err(e) => warn!("Failed to connect to {}: {}", url, e),
+        }
+        
+        if messages.is_closed() {
+            return;
+        }
+        tokio::time::sleep(reconnect_delay(attempt)).await;
+        attempt = attempt.saturating_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_backoff() {
+        assert_eq!(reconnect_delay(0), Duration::from_millis(500));
+        assert_eq!(reconnect_delay(3), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(10), RECONNECT_MAX);
+        assert_eq!(reconnect_delay(u32::MAX), RECONNECT_MAX);
+    }
+
+    #[test]
+    fn test_tracks_last_event_time() {
+        let event = DashboardMessage::Event {
+            seq: 1,
+            timestamp: "2026-03-02T15:00:00+00:00".to_string(),
+            event_type: "Deposited".to_string(),
+            pheromone: "Price Freshness".to_string(),
+            intensity: 1.0,
+            detail: None,
+            count: 1,
+            run_id: None,
+            error: None,
+        };
+        let replay = DashboardMessage::Replay { events: vec![event], trades: vec![] };
+        let time = last_event_time(&replay).unwrap();
+        assert_eq!(time.to_rfc3339(), "2026-03-02T15:00:00+00:00");
+        assert!(last_event_time(&DashboardMessage::Error { message: "x".to_string() }).is_none());
+    }
+}
@@ -0,0 +1,128 @@
+//! Agent Activity History
+//!
+//! `AgentMetrics` only keeps an agent's latest state. Every change is also
+//! appended to a capped per-agent stream (`agent:<name>:activity`), so the
+//! dashboard can draw activity sparklines and operators can see how often
+//! an agent acted over the last hour rather than just its last action.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::blackboard::AgentMetrics;
+
+/// One recorded change of an agent's metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentActivity {
+    pub timestamp: DateTime<Utc>,
+    pub is_active: bool,
+    pub action_count: u64,
+    pub action: String,
+}
+
+impl AgentActivity {
+    pub fn from_metrics(metrics: &AgentMetrics, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            timestamp,
+            is_active: metrics.is_active,
+            action_count: metrics.action_count,
+            action: metrics.last_action.clone(),
+        }
+    }
+}
+
+/// Activity of one agent over a trailing window
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivitySummary {
+    pub agent: String,
+    pub window_secs: u64,
+    /// Actions taken within the window (increments of `action_count`)
+    pub activations: u64,
+    /// Actions per equal-width bucket, oldest first
+    pub sparkline: Vec<u64>,
+    /// Recorded changes within the window, most recent first
+    pub entries: Vec<AgentActivity>,
+}
+
+/// Summarize an agent's activity stream (most recent first) over the
+/// `window` ending at `now`
+pub fn summarize(
+    agent: &str,
+    entries: Vec<AgentActivity>,
+    now: DateTime<Utc>,
+    window: Duration,
+    buckets: usize,
+) -> ActivitySummary {
+    let start = now - window;
+    let buckets = buckets.max(1);
+    let bucket_ms = (window.num_milliseconds() / buckets as i64).max(1);
+    let mut sparkline = vec![0u64; buckets];
+    let mut activations = 0;
+    
+    // Walk oldest to newest; counters restart from zero when an agent restarts
+    let mut previous: Option<u64> = None;
+    for entry in entries.iter().rev() {
+        let delta = match previous {
+            Some(prev) if entry.action_count >= prev => entry.action_count - prev,
+            Some(_) => entry.action_count,
+            None => 0,
+        };
+        previous = Some(entry.action_count);
+        
+        if entry.timestamp < start || entry.timestamp > now || delta == 0 {
+            continue;
+        }
+        let bucket = ((entry.timestamp - start).num_milliseconds() / bucket_ms) as usize;
+        sparkline[bucket.min(buckets - 1)] += delta;
+        activations += delta;
+    }
+    
+    ActivitySummary {
+        agent: agent.to_string(),
+        window_secs: window.num_seconds().max(0) as u64,
+        activations,
+        sparkline,
+        entries: entries.into_iter().filter(|e| e.timestamp >= start).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(now: DateTime<Utc>, minutes_ago: i64, action_count: u64) -> AgentActivity {
+        AgentActivity {
+            timestamp: now - Duration::minutes(minutes_ago),
+            is_active: true,
+            action_count,
+            action: format!("action {}", action_count),
+        }
+    }
+
+    #[test]
+    fn test_counts_activations_within_window() {
+        let now = Utc::now();
+        // Most recent first: 2 actions before the window, 4 inside it
+        let entries = vec![
+            entry(now, 5, 7),
+            entry(now, 20, 5),
+            entry(now, 50, 4),
+            entry(now, 90, 3),
+            entry(now, 120, 1),
+        ];
+        
+        let summary = summarize("analyst", entries, now, Duration::hours(1), 4);
+        assert_eq!(summary.activations, 4);
+        assert_eq!(summary.sparkline, vec![1, 0, 1, 2]);
+        assert_eq!(summary.entries.len(), 3);
+        assert_eq!(summary.window_secs, 3600);
+    }
+
+    #[test]
+    fn test_counter_reset_after_restart() {
+        let now = Utc::now();
+        let entries = vec![entry(now, 1, 2), entry(now, 2, 40), entry(now, 3, 38)];
+        
+        let summary = summarize("trader", entries, now, Duration::minutes(10), 1);
+        assert_eq!(summary.activations, 4);
+    }
+}
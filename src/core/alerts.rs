@@ -0,0 +1,367 @@
+//! Alert Rules
+//!
+//! DriftGuard observes more than it acts on: drift the Guardian keeps
+//! refusing, a deepening drawdown, a Sensor that stopped depositing.
+//! `[[alerts]]` rules turn such conditions into notifications. Each
+//! condition is a Rhai expression (`drift > 8`, `drawdown_pct > 10 &&
+//! data_quality < 0.8`) over an `AlertContext` read from the blackboard,
+//! compiled in the scripting sandbox and evaluated every `[alerting]
+//! interval_secs` in the services process. A rule notifies its channel
+//! (`log`, `slack` or `webhook`) when its condition becomes true and again
+//! every `cooldown_secs` while it stays true; the cooldown also spans a
+//! condition that flaps. Slack and webhook notifications go through the
+//! delivery queue (`notify_queue`), which retries them. Rule state is kept
+//! on the blackboard, so a restart doesn't notify again.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rhai::{Engine, Scope, AST};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{debug, info, warn};
+
+use crate::agents::forecast::DriftForecast;
+use crate::agents::sensor::DataQuality;
+use crate::core::config::{AlertRule, AlertingConfig, ScriptingConfig};
+use crate::core::physics::PheromoneType;
+use crate::core::{money, notify_queue, scripting, Blackboard};
+
+/// Where a rule's notifications go
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertChannel {
+    /// A warning in the services log
+    #[default]
+    Log,
+    /// Slack incoming webhook
+    Slack,
+    /// JSON POST to a URL
+    Webhook,
+}
+
+/// Blackboard state conditions are evaluated against; each field is a
+/// variable of the expression
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AlertContext {
+    /// Stocks weight minus target, in absolute percentage points
+    pub drift: f64,
+    pub stocks_pct: f64,
+    pub bonds_pct: f64,
+    pub target_stocks_pct: f64,
+    pub portfolio_value: f64,
+    pub drawdown_pct: f64,
+    pub max_drawdown_pct: f64,
+    /// Latest Sensor data-quality score (0 before the first snapshot)
+    pub data_quality: f64,
+    /// Current Price Freshness intensity (0 once the Sensor stops)
+    pub price_freshness: f64,
+    /// Rebalances waiting for the execution window
+    pub pending_orders: i64,
+    /// Agents whose last action failed
+    pub agent_errors: i64,
+    /// Forecast days until drift breaches the threshold (-1 without one)
+    pub days_until_breach: f64,
+    /// Average pairwise correlation of the assets (0 without a reading)
+    pub avg_correlation: f64,
+    /// Highest pairwise correlation (0 without a reading)
+    pub max_correlation: f64,
+}
+
+/// Read the alert context; none until there is a portfolio to judge
+pub async fn context(board: &Blackboard) -> Result<Option<AlertContext>> {
+    let Some(state) = board.get_portfolio_state().await? else {
+        return Ok(None);
+    };
+    let target = board.get_target_allocation().await?;
+    let stocks_pct = money::to_f64(state.stocks_pct);
+    let drawdown = board.get_drawdown().await?;
+    let data_quality = board.peek::<DataQuality>(PheromoneType::DataQuality).await?;
+    let forecast = board.peek::<DriftForecast>(PheromoneType::DriftForecast).await?;
+    let correlation = board.get_correlation().await?;
+    let agent_errors = board
+        .get_all_agent_metrics()
+        .await?
+        .iter()
+        .filter(|m| m.last_error.is_some())
+        .count();
+    
+    Ok(Some(AlertContext {
+        drift: (stocks_pct - target.stocks_pct).abs(),
+        stocks_pct,
+        bonds_pct: money::to_f64(state.bonds_pct),
+        target_stocks_pct: target.stocks_pct,
+        portfolio_value: money::to_f64(state.total_value),
+        drawdown_pct: drawdown.as_ref().map_or(0.0, |d| d.drawdown_pct),
+        max_drawdown_pct: drawdown.as_ref().map_or(0.0, |d| d.max_drawdown_pct),
+        data_quality: data_quality.map_or(0.0, |reading| reading.data.score),
+        price_freshness: board.get_intensity(PheromoneType::PriceFreshness).await?,
+        pending_orders: board.get_pending_orders().await?.len() as i64,
+        agent_errors: agent_errors as i64,
+        days_until_breach: forecast.and_then(|reading| reading.data.days_until_breach).unwrap_or(-1.0),
+        avg_correlation: correlation.as_ref().map_or(0.0, |reading| reading.average),
+        max_correlation: correlation.as_ref().map_or(0.0, |reading| reading.max),
+    }))
+}
+
+/// The configured rules, compiled
+pub struct AlertEvaluator {
+    engine: Engine,
+    rules: Vec<(AlertRule, AST)>,
+}
+
+impl AlertEvaluator {
+    /// Compile every rule and try it on an empty context, so a syntax error
+    /// or unknown variable fails at startup rather than on every check
+    pub fn compile(rules: &[AlertRule], scripting: &ScriptingConfig) -> Result<Self> {
+        let engine = scripting::sandboxed_engine(scripting);
+        let mut compiled = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let ast = engine
+                .compile_expression(&rule.condition)
+                .map_err(|e| anyhow!("alert '{}': {}", rule.name(), e))?;
+            compiled.push((rule.clone(), ast));
+        }
+        let evaluator = Self { engine, rules: compiled };
+        for (rule, ast) in &evaluator.rules {
+            evaluator.eval(rule, ast, &AlertContext::default())?;
+        }
+        Ok(evaluator)
+    }
+
+    /// Evaluate every rule against `ctx`
+    pub fn evaluate(&self, ctx: &AlertContext) -> Vec<(&AlertRule, Result<bool>)> {
+        self.rules.iter().map(|(rule, ast)| (rule, self.eval(rule, ast, ctx))).collect()
+    }
+
+    fn eval(&self, rule: &AlertRule, ast: &AST, ctx: &AlertContext) -> Result<bool> {
+        let vars: rhai::Map = rhai::serde::to_dynamic(ctx)
+            .map_err(|e| anyhow!("{}", e))?
+            .try_cast()
+            .ok_or_else(|| anyhow!("alert context is not a map"))?;
+        let mut scope = Scope::new();
+        for (name, value) in vars {
+            scope.push_dynamic(name.to_string(), value);
+        }
+        let value = self
+            .engine
+            .eval_ast_with_scope::<rhai::Dynamic>(&mut scope, ast)
+            .map_err(|e| anyhow!("alert '{}': {}", rule.name(), e))?;
+        match value.as_bool() {
+            Ok(firing) => Ok(firing),
+            Err(t) => bail!("alert '{}': condition returned {}, expected a bool", rule.name(), t),
+        }
+    }
+}
+
+/// A rule's notification state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleState {
+    pub firing: bool,
+    /// When the condition last became true
+    pub since: Option<DateTime<Utc>>,
+    pub last_notified: Option<DateTime<Utc>>,
+}
+
+impl RuleState {
+    /// Record an evaluation at `now`; whether a notification is due
+    pub fn observe(&mut self, firing: bool, now: DateTime<Utc>, cooldown: ChronoDuration) -> bool {
+        if !firing {
+            self.firing = false;
+            self.since = None;
+            return false;
+        }
+        if !self.firing {
+            self.firing = true;
+            self.since = Some(now);
+        }
+        self.last_notified.is_none_or(|at| now - at >= cooldown)
+    }
+}
+
+/// A rule with its current state, for `GET /api/alerts`
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertStatus {
+    pub name: String,
+    pub condition: String,
+    pub channel: AlertChannel,
+    pub cooldown_secs: u64,
+    pub state: RuleState,
+}
+
+/// Every configured rule with its stored state
+pub fn statuses(rules: &[AlertRule], config: &AlertingConfig, states: &BTreeMap<String, RuleState>) -> Vec<AlertStatus> {
+    rules
+        .iter()
+        .map(|rule| AlertStatus {
+            name: rule.name().to_string(),
+            condition: rule.condition.clone(),
+            channel: rule.channel,
+            cooldown_secs: rule.cooldown_secs.unwrap_or(config.cooldown_secs),
+            state: states.get(rule.name()).cloned().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Evaluate the rules every `interval_secs` and deliver notifications
+pub async fn run_alerts(board: Arc<Blackboard>) -> Result<()> {
+    let config = board.config();
+    let evaluator = AlertEvaluator::compile(&config.alerts, &config.scripting)?;
+    let mut states = board.get_alert_states().await?;
+    let mut ticker = interval(Duration::from_secs(config.alerting.interval_secs.max(1)));
+    
+    info!("🚨 Alerts started ({} rules, every {}s)", config.alerts.len(), config.alerting.interval_secs);
+    
+    loop {
+        ticker.tick().await;
+        if let Err(e) = check(&board, &evaluator, &mut states).await {
+            warn!("Alerts: Check failed: {:#}", e);
+        }
+    }
+}
+
+/// One evaluation pass
+async fn check(
+    board: &Blackboard,
+    evaluator: &AlertEvaluator,
+    states: &mut BTreeMap<String, RuleState>,
+) -> Result<()> {
+    let Some(ctx) = context(board).await? else {
+        debug!("Alerts: No portfolio yet");
+        return Ok(());
+    };
+    let config = &board.config().alerting;
+    let now = Utc::now();
+    let before = states.clone();
+    
+    for (rule, result) in evaluator.evaluate(&ctx) {
+        let firing = match result {
+            Ok(firing) => firing,
+            Err(e) => {
+                warn!("Alerts: {:#}", e);
+                continue;
+            }
+        };
+        let cooldown = ChronoDuration::seconds(rule.cooldown_secs.unwrap_or(config.cooldown_secs) as i64);
+        let state = states.entry(rule.name().to_string()).or_default();
+        if !state.observe(firing, now, cooldown) {
+            continue;
+        }
+        
+        let message = message(rule, &ctx, board.swarm_name());
+        let payload = serde_json::json!({
+            "rule": rule.name(),
+            "condition": rule.condition,
+            "message": message,
+            "swarm": board.swarm_name(),
+            "timestamp": now.to_rfc3339(),
+            "context": ctx,
+        });
+        notify_queue::enqueue(board, rule.channel, &format!("alert:{}", rule.name()), &message, payload).await;
+        state.last_notified = Some(now);
+    }
+    
+    if *states != before {
+        board.set_alert_states(states).await?;
+    }
+    Ok(())
+}
+
+fn message(rule: &AlertRule, ctx: &AlertContext, swarm: &str) -> String {
+    format!(
+        "🚨 DriftGuard [{}] {}: `{}` (drift {:.1}%, stocks {:.1}% vs target {:.1}%, drawdown {:.1}%)",
+        swarm,
+        rule.name(),
+        rule.condition,
+        ctx.drift,
+        ctx.stocks_pct,
+        ctx.target_stocks_pct,
+        ctx.drawdown_pct
+    )
+}
+
+/// Send `message` to Slack, or `payload` to the webhook, at the URL named
+/// by `[alerting]`; the log channel is left to the caller
+pub async fn post(
+    client: &reqwest::Client,
+    config: &AlertingConfig,
+    channel: AlertChannel,
+    message: &str,
+    payload: &serde_json::Value,
+) -> Result<()> {
+    let url = |env: &str| {
+        std::env::var(env)
+            .ok()
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| anyhow!("{} is not set", env))
+    };
+    let request = match channel {
+        AlertChannel::Log => return Ok(()),
+        AlertChannel::Slack => client
+            .post(url(&config.slack_webhook_env)?)
+            .json(&serde_json::json!({ "text": message })),
+        AlertChannel::Webhook => client.post(url(&config.webhook_url_env)?).json(payload),
+    };
+    // A Slack webhook URL is itself the credential; keep it out of errors
+    request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(reqwest::Error::without_url)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(condition: &str) -> AlertRule {
+        AlertRule {
+            name: String::new(),
+            condition: condition.to_string(),
+            channel: AlertChannel::Log,
+            cooldown_secs: None,
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let rules = [rule("drift > 8"), rule("drawdown_pct > 10 && data_quality < 0.8")];
+        let evaluator = AlertEvaluator::compile(&rules, &ScriptingConfig::default()).unwrap();
+        let ctx = AlertContext { drift: 8.4, drawdown_pct: 12.0, data_quality: 0.9, ..Default::default() };
+        let results: Vec<bool> = evaluator.evaluate(&ctx).into_iter().map(|(_, r)| r.unwrap()).collect();
+        assert_eq!(results, [true, false]);
+        
+        // Syntax errors, unknown variables and non-bool conditions fail at startup
+        let scripting = ScriptingConfig::default();
+        assert!(AlertEvaluator::compile(&[rule("drift >")], &scripting).is_err());
+        assert!(AlertEvaluator::compile(&[rule("vix > 30")], &scripting).is_err());
+        assert!(AlertEvaluator::compile(&[rule("drift + 1")], &scripting).is_err());
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let start = Utc::now();
+        let at = |mins: i64| start + ChronoDuration::minutes(mins);
+        let cooldown = ChronoDuration::minutes(60);
+        let mut state = RuleState::default();
+        let mut observe = |firing: bool, mins: i64| {
+            let due = state.observe(firing, at(mins), cooldown);
+            if due {
+                state.last_notified = Some(at(mins));
+            }
+            due
+        };
+        
+        assert!(!observe(false, 0));
+        assert!(observe(true, 1));
+        assert!(!observe(true, 30));
+        // Clearing and firing again within the cooldown is deduplicated
+        assert!(!observe(false, 40));
+        assert!(!observe(true, 45));
+        assert!(observe(true, 61));
+        assert!(!observe(true, 62));
+        assert!(observe(true, 125));
+    }
+}
@@ -0,0 +1,213 @@
+//! Target Allocation Requests
+//!
+//! Resolves and validates target allocation changes coming from the
+//! dashboard or REST API before they reach `config:target_allocation`.
+//! A request either names a preset or gives explicit percentages, and is
+//! rejected unless it sums to 100%, respects the per-asset bounds and
+//! keeps each asset within its own `min_pct`/`max_pct` limits.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::core::blackboard::TargetAllocation;
+use crate::core::config::{AllocationConfig, PortfolioConfig};
+use crate::core::secrets;
+
+/// Allowed rounding slack when checking that weights sum to 100%
+const SUM_TOLERANCE_PCT: f64 = 0.01;
+
+/// A requested target allocation: a preset name or explicit weights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AllocationRequest {
+    Preset { preset: String },
+    Weights { stocks_pct: f64, bonds_pct: f64 },
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AllocationError {
+    #[error("operator token missing or invalid")]
+    Unauthorized,
+    #[error("unknown preset '{0}'")]
+    UnknownPreset(String),
+    #[error("weights must be finite numbers")]
+    NotFinite,
+    #[error("weights sum to {0:.2}%, expected 100%")]
+    BadSum(f64),
+    #[error("{asset} weight {pct:.2}% outside allowed range {min:.2}%–{max:.2}%")]
+    OutOfBounds { asset: &'static str, pct: f64, min: f64, max: f64 },
+    #[error("{symbol} weight {pct:.2}% breaks its {bound} of {limit:.2}%")]
+    AssetLimit { symbol: String, pct: f64, bound: &'static str, limit: f64 },
+}
+
+/// The operator token required for allocation changes, if configured
+pub fn operator_token(config: &AllocationConfig) -> Option<String> {
+    std::env::var(&config.operator_token_env)
+        .ok()
+        .filter(|token| !token.is_empty())
+}
+
+/// Check the operator token (in constant time) when one is configured
+pub fn authorize(expected: Option<&str>, provided: Option<&str>) -> Result<(), AllocationError> {
+    match expected {
+        Some(expected) if !provided.is_some_and(|provided| secrets::constant_time_eq(provided.as_bytes(), expected.as_bytes())) => {
+            Err(AllocationError::Unauthorized)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The `min_pct`/`max_pct` limits of a symbol's `[[portfolio.assets]]` entry
+pub fn asset_limits(portfolio: &PortfolioConfig, symbol: &str) -> (Option<f64>, Option<f64>) {
+    portfolio
+        .assets
+        .iter()
+        .find(|asset| asset.symbol == symbol)
+        .map_or((None, None), |asset| (asset.min_pct, asset.max_pct))
+}
+
+/// Range of stocks weights the stocks and bonds assets' limits allow
+/// (empty when they contradict each other)
+pub fn stocks_limits(portfolio: &PortfolioConfig) -> (f64, f64) {
+    let (stocks_min, stocks_max) = asset_limits(portfolio, &portfolio.stocks_symbol);
+    let (bonds_min, bonds_max) = asset_limits(portfolio, &portfolio.bonds_symbol);
+    let lower = stocks_min.unwrap_or(0.0).max(100.0 - bonds_max.unwrap_or(100.0));
+    let upper = stocks_max.unwrap_or(100.0).min(100.0 - bonds_min.unwrap_or(0.0));
+    (lower, upper)
+}
+
+/// Check a target against the stocks and bonds assets' `min_pct`/`max_pct`
+pub fn check_asset_limits(target: &TargetAllocation, portfolio: &PortfolioConfig) -> Result<(), AllocationError> {
+    let weights = [
+        (&portfolio.stocks_symbol, target.stocks_pct),
+        (&portfolio.bonds_symbol, target.bonds_pct),
+    ];
+    for (symbol, pct) in weights {
+        let (min, max) = asset_limits(portfolio, symbol);
+        let breach = match (min, max) {
+            (Some(min), _) if pct < min => Some(("minimum", min)),
+            (_, Some(max)) if pct > max => Some(("maximum", max)),
+            _ => None,
+        };
+        if let Some((bound, limit)) = breach {
+            return Err(AllocationError::AssetLimit { symbol: symbol.clone(), pct, bound, limit });
+        }
+    }
+    Ok(())
+}
+
+/// Validate explicit weights against the configured rules
+pub fn validate(
+    stocks_pct: f64,
+    bonds_pct: f64,
+    config: &AllocationConfig,
+    portfolio: &PortfolioConfig,
+) -> Result<TargetAllocation, AllocationError> {
+    if !stocks_pct.is_finite() || !bonds_pct.is_finite() {
+        return Err(AllocationError::NotFinite);
+    }
+    
+    let sum = stocks_pct + bonds_pct;
+    if (sum - 100.0).abs() > SUM_TOLERANCE_PCT {
+        return Err(AllocationError::BadSum(sum));
+    }
+    
+    let min = config.min_asset_pct.max(0.0);
+    let max = config.max_asset_pct.min(100.0);
+    for (asset, pct) in [("stocks", stocks_pct), ("bonds", bonds_pct)] {
+        if pct < min || pct > max {
+            return Err(AllocationError::OutOfBounds { asset, pct, min, max });
+        }
+    }
+    
+    let target = TargetAllocation { stocks_pct, bonds_pct };
+    check_asset_limits(&target, portfolio)?;
+    Ok(target)
+}
+
+/// Turn a request into a validated target allocation
+pub fn resolve(
+    request: &AllocationRequest,
+    config: &AllocationConfig,
+    portfolio: &PortfolioConfig,
+) -> Result<TargetAllocation, AllocationError> {
+    match request {
+        AllocationRequest::Preset { preset } => {
+            let found = config
+                .presets
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(preset))
+                .ok_or_else(|| AllocationError::UnknownPreset(preset.clone()))?;
+            validate(found.stocks_pct, found.bonds_pct, config, portfolio)
+        }
+        AllocationRequest::Weights { stocks_pct, bonds_pct } => {
+            validate(*stocks_pct, *bonds_pct, config, portfolio)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::{AssetConfig, Config};
+    
+    fn asset(symbol: &str, min_pct: Option<f64>, max_pct: Option<f64>) -> AssetConfig {
+        AssetConfig { symbol: symbol.to_string(), name: String::new(), target_pct: 50.0, min_pct, max_pct, exchange: None, currency: None }
+    }
+    
+    #[test]
+    fn test_rejects_bad_allocations() {
+        let portfolio = Config::default().portfolio;
+        let config = AllocationConfig {
+            min_asset_pct: 10.0,
+            max_asset_pct: 90.0,
+            ..AllocationConfig::default()
+        };
+        
+        assert!(validate(60.0, 40.0, &config, &portfolio).is_ok());
+        assert_eq!(validate(60.0, 50.0, &config, &portfolio), Err(AllocationError::BadSum(110.0)));
+        assert!(matches!(
+            validate(95.0, 5.0, &config, &portfolio),
+            Err(AllocationError::OutOfBounds { asset: "stocks", .. })
+        ));
+        assert_eq!(validate(f64::NAN, 40.0, &config, &portfolio), Err(AllocationError::NotFinite));
+    }
+    
+    #[test]
+    fn test_resolves_presets_and_tokens() {
+        let config = AllocationConfig::default();
+        let portfolio = Config::default().portfolio;
+        
+        let preset = AllocationRequest::Preset { preset: "balanced".to_string() };
+        assert_eq!(resolve(&preset, &config, &portfolio).unwrap().stocks_pct, 60.0);
+        
+        let unknown = AllocationRequest::Preset { preset: "yolo".to_string() };
+        assert_eq!(resolve(&unknown, &config, &portfolio), Err(AllocationError::UnknownPreset("yolo".to_string())));
+        
+        assert!(authorize(None, None).is_ok());
+        assert!(authorize(Some("secret"), Some("secret")).is_ok());
+        assert_eq!(authorize(Some("secret"), None), Err(AllocationError::Unauthorized));
+    }
+    
+    #[test]
+    fn test_asset_limits() {
+        let portfolio = PortfolioConfig {
+            assets: vec![asset("SPY", Some(40.0), Some(70.0)), asset("BND", Some(35.0), None)],
+            ..Config::default().portfolio
+        };
+        let config = AllocationConfig::default();
+        
+        assert!(validate(60.0, 40.0, &config, &portfolio).is_ok());
+        assert_eq!(
+            validate(75.0, 25.0, &config, &portfolio),
+            Err(AllocationError::AssetLimit { symbol: "SPY".to_string(), pct: 75.0, bound: "maximum", limit: 70.0 })
+        );
+        // Bonds' 35% floor caps stocks at 65%
+        assert!(matches!(
+            validate(68.0, 32.0, &config, &portfolio),
+            Err(AllocationError::AssetLimit { bound: "minimum", .. })
+        ));
+        assert_eq!(stocks_limits(&portfolio), (40.0, 65.0));
+        assert_eq!(stocks_limits(&PortfolioConfig { assets: Vec::new(), ..portfolio }), (0.0, 100.0));
+    }
+}
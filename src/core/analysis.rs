@@ -0,0 +1,166 @@
+//! Drift Analysis
+//!
+//! The Analyst's drift and opportunity computation as a pure, synchronous
+//! function of holdings, prices, target and policy, so it can be unit
+//! tested, fuzzed and reused (what-if previews, offline replays) without a
+//! blackboard or an agent loop. What the Analyst carries between snapshots
+//! — its price momentum and its `analyst_score` script — stays in
+//! `DriftModel`, which feeds the momentum in here and lets the script
+//! rescore the result.
+
+use chrono::{DateTime, Utc};
+
+use crate::agents::analyst::{CostBenefit, DriftAssessment, OpportunityInputs, OpportunityScore};
+use crate::core::blackboard::{PortfolioState, RebalanceBaseline, TargetAllocation};
+use crate::core::config::{CostConfig, OpportunityScoringConfig, PortfolioConfig, RebalanceBenefitConfig};
+use crate::core::money;
+use crate::core::physics::PheromoneType;
+use crate::core::rebalance;
+use crate::core::Config;
+
+/// What the prices say beyond the holdings' values
+#[derive(Debug, Clone, Default)]
+pub struct PriceInputs {
+    /// Smoothed stocks-minus-bonds return between snapshots (%)
+    pub momentum_pct: f64,
+    /// Time of the evaluation; none counts the last rebalance as just now
+    pub now: Option<DateTime<Utc>>,
+}
+
+/// The parts of the configuration that decide an evaluation
+#[derive(Debug, Clone)]
+pub struct AnalysisPolicy<'a> {
+    pub portfolio: &'a PortfolioConfig,
+    pub scoring: &'a OpportunityScoringConfig,
+    pub costs: &'a CostConfig,
+    pub benefit: &'a RebalanceBenefitConfig,
+    /// RebalanceOpportunity threshold a score must beat to wake the Guardian
+    pub activation_threshold: f64,
+}
+
+impl<'a> AnalysisPolicy<'a> {
+    pub fn from_config(config: &'a Config) -> Self {
+        Self {
+            portfolio: &config.portfolio,
+            scoring: &config.opportunity_scoring,
+            costs: &config.costs,
+            benefit: &config.rebalance_benefit,
+            activation_threshold: PheromoneType::RebalanceOpportunity.threshold(config),
+        }
+    }
+}
+
+/// Assess drift from target and whether it calls for a rebalance, by the
+/// built-in rules (opportunity score when enabled, else the drift threshold)
+pub fn evaluate(
+    holdings: &PortfolioState,
+    prices: &PriceInputs,
+    target: &TargetAllocation,
+    baseline: Option<&RebalanceBaseline>,
+    policy: &AnalysisPolicy<'_>,
+) -> DriftAssessment {
+    let (stocks_pct, bonds_pct) = (money::to_f64(holdings.stocks_pct), money::to_f64(holdings.bonds_pct));
+    let drift = (stocks_pct - target.stocks_pct).abs();
+    let total_value = money::to_f64(holdings.total_value);
+    // Costs are those of the legs a rebalance would actually send
+    let (stocks_delta, bonds_delta) = rebalance::deltas(holdings, holdings.total_value, target);
+    let legs = rebalance::plan_legs(policy.portfolio, stocks_delta, bonds_delta);
+
+    let opportunity = policy.scoring.enabled.then(|| {
+        // Positive when the overweight side keeps outperforming
+        let aligned_momentum_pct = if stocks_pct >= target.stocks_pct {
+            prices.momentum_pct
+        } else {
+            -prices.momentum_pct
+        };
+        let last_rebalance = baseline
+            .map(|b| b.timestamp.as_str())
+            .or(holdings.last_trade_time.as_deref())
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+        let days_since_rebalance = match (prices.now, last_rebalance) {
+            (Some(now), Some(last)) => (now - last.with_timezone(&Utc)).num_seconds() as f64 / 86_400.0,
+            _ => 0.0,
+        };
+        OpportunityScore::compute(
+            &OpportunityInputs {
+                drift_pct: drift,
+                drift_threshold: policy.portfolio.drift_threshold,
+                aligned_momentum_pct,
+                days_since_rebalance,
+                total_value,
+            },
+            &legs,
+            policy.scoring,
+            policy.costs,
+        )
+    });
+    let cost_benefit = CostBenefit::estimate(drift, total_value, &legs, policy.benefit, policy.costs);
+
+    let mut assessment = DriftAssessment {
+        stocks_pct,
+        bonds_pct,
+        drift,
+        score: opportunity.as_ref().map(|o| o.score),
+        opportunity,
+        cost_benefit,
+        script_score: None,
+        triggered: false,
+        intensity: 1.0,
+    };
+    decide(&mut assessment, policy);
+    assessment
+}
+
+/// Let `score` (from a script) decide in place of the built-in rule
+pub fn rescore(assessment: &mut DriftAssessment, score: f64, policy: &AnalysisPolicy<'_>) {
+    assessment.script_score = Some(score);
+    assessment.score = Some(score);
+    decide(assessment, policy);
+}
+
+/// With a score, the score rather than a hard drift threshold decides
+/// whether the deposit is strong enough to wake the Guardian
+fn decide(assessment: &mut DriftAssessment, policy: &AnalysisPolicy<'_>) {
+    let drift = assessment.drift;
+    (assessment.triggered, assessment.intensity) = match assessment.score {
+        Some(score) => (drift > 0.0 && score > policy.activation_threshold, score),
+        None => (drift > policy.portfolio.drift_threshold, 1.0),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let mut config = Config::default();
+        let target = TargetAllocation { stocks_pct: 60.0, bonds_pct: 40.0 };
+        let holdings = |stocks: f64| PortfolioState::from_values(money::from_f64(stocks), money::from_f64(100_000.0 - stocks), None);
+
+        // Threshold rule: 5pp by default
+        let policy = AnalysisPolicy::from_config(&config);
+        let calm = evaluate(&holdings(63_000.0), &PriceInputs::default(), &target, None, &policy);
+        assert!((calm.drift - 3.0).abs() < 1e-9);
+        assert!(!calm.triggered);
+        assert!(calm.opportunity.is_none());
+        let drifted = evaluate(&holdings(70_000.0), &PriceInputs::default(), &target, None, &policy);
+        assert!(drifted.triggered);
+        assert_eq!(drifted.intensity, 1.0);
+
+        // A script's score decides instead
+        let mut rescored = drifted.clone();
+        rescore(&mut rescored, 0.0, &policy);
+        assert!(!rescored.triggered);
+        assert_eq!(rescored.script_score, Some(0.0));
+
+        // Scoring: momentum pushing drift further raises the score
+        config.opportunity_scoring.enabled = true;
+        let policy = AnalysisPolicy::from_config(&config);
+        let flat = evaluate(&holdings(70_000.0), &PriceInputs::default(), &target, None, &policy);
+        let running = PriceInputs { momentum_pct: 1.0, ..Default::default() };
+        let running = evaluate(&holdings(70_000.0), &running, &target, None, &policy);
+        assert!(running.score.unwrap() > flat.score.unwrap());
+        assert_eq!(running.intensity, running.score.unwrap());
+    }
+}
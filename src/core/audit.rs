@@ -0,0 +1,246 @@
+//! Command Audit Log
+//!
+//! Every state-changing command from a client — allocation changes,
+//! presets, resets and holdings imports, over the WebSocket, REST or the
+//! CLI — is appended to a capped audit stream (`audit:commands`) with the
+//! client's identity, the outcome and the state before and after. Rejected
+//! commands are recorded too, so failed token checks are visible.
+//!
+//! Operator names are self-declared (`?operator=` on the WebSocket,
+//! `x-operator-id` on REST); the shared operator token is what authorizes.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::core::Blackboard;
+
+/// Longest value kept for self-declared identity fields
+/// Entries returned by `GET /api/audit` when no limit is given, and at most
+const DEFAULT_QUERY_LIMIT: usize = 100;
+const MAX_QUERY_LIMIT: usize = 1000;
+
+/// Entries read from Redis per page while filtering
+const SCAN_PAGE: usize = 500;
+
+/// Who sent a command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClientIdentity {
+    /// websocket | rest | cli
+    pub channel: String,
+    /// Self-declared operator name
+    pub operator: Option<String>,
+    /// Peer address of the connection
+    pub ip: Option<String>,
+    /// `X-Forwarded-For` as sent by a proxy (not verified)
+    pub forwarded_for: Option<String>,
+    /// Identifies one WebSocket connection across its commands
+    pub connection_id: Option<String>,
+    /// `[[auth.users]]` user whose API key the client presented
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+impl ClientIdentity {
+    pub fn websocket(operator: Option<String>, remote: Option<SocketAddr>, forwarded_for: Option<String>) -> Self {
+        Self {
+            channel: "websocket".to_string(),
+            operator: clean(operator),
+            ip: remote.map(|addr| addr.ip().to_string()),
+            forwarded_for: clean(forwarded_for),
+            connection_id: Some(Uuid::new_v4().to_string()),
+            user: None,
+        }
+    }
+
+    pub fn rest(operator: Option<String>, remote: Option<SocketAddr>, forwarded_for: Option<String>) -> Self {
+        Self {
+            channel: "rest".to_string(),
+            operator: clean(operator),
+            ip: remote.map(|addr| addr.ip().to_string()),
+            forwarded_for: clean(forwarded_for),
+            connection_id: None,
+            user: None,
+        }
+    }
+
+    /// The client authenticated as `user`
+    pub fn with_user(mut self, user: Option<String>) -> Self {
+        self.user = user;
+        self
+    }
+
+    /// A command run from the command line by the local user
+    pub fn cli() -> Self {
+        Self {
+            channel: "cli".to_string(),
+            operator: clean(std::env::var("USER").ok()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Trim and bound self-declared values before they are stored
+fn clean(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().chars().take(MAX_IDENTITY_LEN).collect::<String>())
+        .filter(|v| !v.is_empty())
+}
+
+const MAX_IDENTITY_LEN: usize = 128;
+
+/// What happened to a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Applied,
+    /// Refused (bad token or invalid request); nothing changed
+    Rejected,
+    /// Accepted but storing the change failed
+    Failed,
+}
+
+/// One audited command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// set_allocation | apply_preset | reset | import_holdings
+    pub command: String,
+    pub client: ClientIdentity,
+    pub outcome: AuditOutcome,
+    /// Why a command was rejected or failed
+    pub error: Option<String>,
+    /// The request as sent (tokens stripped)
+    pub request: Option<serde_json::Value>,
+    pub previous: Option<serde_json::Value>,
+    pub new: Option<serde_json::Value>,
+}
+
+impl AuditEntry {
+    pub fn new(command: &str, client: &ClientIdentity) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            command: command.to_string(),
+            client: client.clone(),
+            outcome: AuditOutcome::Applied,
+            error: None,
+            request: None,
+            previous: None,
+            new: None,
+        }
+    }
+
+    pub fn request(mut self, request: impl Serialize) -> Self {
+        self.request = serde_json::to_value(request).ok();
+        self
+    }
+
+    pub fn previous(mut self, previous: impl Serialize) -> Self {
+        self.previous = serde_json::to_value(previous).ok();
+        self
+    }
+
+    pub fn new_value(mut self, new: impl Serialize) -> Self {
+        self.new = serde_json::to_value(new).ok();
+        self
+    }
+
+    pub fn rejected(mut self, error: impl ToString) -> Self {
+        self.outcome = AuditOutcome::Rejected;
+        self.error = Some(error.to_string());
+        self
+    }
+
+    pub fn failed(mut self, error: impl ToString) -> Self {
+        self.outcome = AuditOutcome::Failed;
+        self.error = Some(error.to_string());
+        self
+    }
+}
+
+/// Filters for `GET /api/audit`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditQuery {
+    pub command: Option<String>,
+    pub operator: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.command.as_ref().is_none_or(|c| *c == entry.command)
+            && self.operator.as_ref().is_none_or(|o| entry.client.operator.as_ref() == Some(o))
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_QUERY_LIMIT).clamp(1, MAX_QUERY_LIMIT)
+    }
+}
+
+/// Store an audit entry; failures are logged rather than failing the command
+pub async fn record(board: &Blackboard, entry: AuditEntry) {
+    if let Err(e) = board.log_audit(&entry).await {
+        warn!("Failed to record audit entry for {}: {}", entry.command, e);
+    }
+}
+
+/// Most recent entries matching the query (most recent first)
+pub async fn query(board: &Blackboard, query: &AuditQuery) -> anyhow::Result<Vec<AuditEntry>> {
+    let limit = query.limit();
+    let mut matched = Vec::new();
+    let mut offset = 0;
+    
+    while matched.len() < limit && offset < board.config().audit.max_entries {
+        let page = board.get_audit_page(offset, SCAN_PAGE).await?;
+        let exhausted = page.len() < SCAN_PAGE;
+        matched.extend(page.into_iter().filter(|e| query.matches(e)));
+        if exhausted {
+            break;
+        }
+        offset += SCAN_PAGE;
+    }
+    
+    matched.truncate(limit);
+    Ok(matched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_builder_and_filter() {
+        let remote: SocketAddr = "10.0.0.7:51234".parse().unwrap();
+        let client = ClientIdentity::rest(Some("  alice ".to_string()), Some(remote), None);
+        let entry = AuditEntry::new("set_allocation", &client)
+            .request(serde_json::json!({"stocks_pct": 70.0, "bonds_pct": 30.0}))
+            .previous(serde_json::json!({"stocks_pct": 60.0}))
+            .rejected("operator token missing or invalid");
+        
+        assert_eq!(entry.client.operator.as_deref(), Some("alice"));
+        assert_eq!(entry.client.ip.as_deref(), Some("10.0.0.7"));
+        assert_eq!(entry.outcome, AuditOutcome::Rejected);
+        assert!(entry.new.is_none());
+        
+        let by_operator = AuditQuery { operator: Some("alice".to_string()), ..Default::default() };
+        assert!(by_operator.matches(&entry));
+        let by_command = AuditQuery { command: Some("reset".to_string()), ..Default::default() };
+        assert!(!by_command.matches(&entry));
+        
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["outcome"], "rejected");
+        assert_eq!(json["client"]["channel"], "rest");
+    }
+
+    #[test]
+    fn test_websocket_connections_get_distinct_ids() {
+        let a = ClientIdentity::websocket(None, None, Some("  ".to_string()));
+        let b = ClientIdentity::websocket(None, None, None);
+        assert_ne!(a.connection_id, b.connection_id);
+        assert!(a.forwarded_for.is_none());
+    }
+}
@@ -0,0 +1,191 @@
+//! API Users
+//!
+//! A small advisory team can share one deployment: each portfolio runs as
+//! its own swarm (see `federation`) and `[[auth.users]]` maps each user's
+//! API key to the swarms they manage. A server serves one swarm, so once
+//! any user is configured it only answers requests whose key (`X-Api-Key`
+//! header or `?api_key=`, which browsers need for WebSockets) belongs to a
+//! user of its swarm; its WebSocket messages and commands are then that
+//! user's portfolio only. An authenticated user's commands need no operator
+//! token, and the audit log records the user behind each. Keys are read
+//! from the environment when the server starts and compared in constant
+//! time. With `[auth] required`, or when the Trader runs on live market
+//! data, a server refuses to start while anyone could send it commands.
+
+use thiserror::Error;
+use tracing::warn;
+
+use crate::core::allocation::{self, AllocationError};
+use crate::core::audit::ClientIdentity;
+use crate::core::config::{AuthConfig, Config};
+use crate::core::pipeline::AgentRole;
+use crate::core::secrets;
+
+/// Swarm pattern granting every swarm
+const ALL_SWARMS: &str = "*";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AuthError {
+    #[error("API key required")]
+    MissingKey,
+    #[error("unknown API key")]
+    UnknownKey,
+    #[error("user '{user}' has no access to swarm '{swarm}'")]
+    Forbidden { user: String, swarm: String },
+}
+
+/// An authenticated user and the swarms they may use
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiUser {
+    pub name: String,
+    pub swarms: Vec<String>,
+}
+
+impl ApiUser {
+    pub fn can_access(&self, swarm: &str) -> bool {
+        self.swarms.iter().any(|s| s == swarm || s == ALL_SWARMS)
+    }
+}
+
+/// Configured users by API key
+#[derive(Debug, Clone, Default)]
+pub struct ApiUsers {
+    /// Whether any user is configured (even one whose key is missing)
+    enabled: bool,
+    /// Searched in full on every request, so timing reveals nothing of the keys
+    by_key: Vec<(String, ApiUser)>,
+}
+
+impl ApiUsers {
+    /// Users of `[[auth.users]]` whose key variable is set
+    pub fn from_config(config: &AuthConfig) -> Self {
+        let mut users = Vec::new();
+        for user in &config.users {
+            match std::env::var(&user.api_key_env) {
+                Ok(key) if !key.is_empty() => {
+                    users.push((key, ApiUser { name: user.name.clone(), swarms: user.swarms.clone() }));
+                }
+                _ => warn!("Auth: {} is not set; user '{}' can't sign in", user.api_key_env, user.name),
+            }
+        }
+        let mut api_users = Self::new(users);
+        api_users.enabled = !config.users.is_empty();
+        api_users
+    }
+
+    pub fn new(users: impl IntoIterator<Item = (String, ApiUser)>) -> Self {
+        let by_key: Vec<(String, ApiUser)> = users.into_iter().collect();
+        Self { enabled: !by_key.is_empty(), by_key }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The user behind `key`, who must have access to `swarm`; none when
+    /// no user is configured
+    pub fn authenticate(&self, key: Option<&str>, swarm: &str) -> Result<Option<ApiUser>, AuthError> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        let key = key.filter(|key| !key.is_empty()).ok_or(AuthError::MissingKey)?;
+        let user = self
+            .by_key
+            .iter()
+            .filter(|(candidate, _)| secrets::constant_time_eq(candidate.as_bytes(), key.as_bytes()))
+            .fold(None, |found, (_, user)| found.or(Some(user)))
+            .ok_or(AuthError::UnknownKey)?;
+        if !user.can_access(swarm) {
+            return Err(AuthError::Forbidden { user: user.name.clone(), swarm: swarm.to_string() });
+        }
+        Ok(Some(user.clone()))
+    }
+}
+
+/// Authorize a command: a user authenticated for the swarm may send it,
+/// anyone else needs the operator token (when one is configured)
+pub fn authorize(client: &ClientIdentity, config: &Config, token: Option<&str>) -> Result<(), AllocationError> {
+    if client.user.is_some() {
+        return Ok(());
+    }
+    allocation::authorize(allocation::operator_token(&config.allocation).as_deref(), token)
+}
+
+/// Whether this process trades on live market data: the Trader runs here
+/// and `live_quotes`, the resolved provider or chain quotes real prices
+pub fn live_execution(config: &Config, live_quotes: bool) -> bool {
+    live_quotes && config.agent.roles.contains(&AgentRole::Trader)
+}
+
+/// Refuse an API whose commands anyone could send (neither the operator
+/// token nor any API user configured) when `[auth] required` is set or the
+/// swarm trades `live`
+pub fn check_required(config: &Config, live: bool) -> anyhow::Result<()> {
+    let open = config.auth.users.is_empty() && allocation::operator_token(&config.allocation).is_none();
+    if open && (config.auth.required || live) {
+        anyhow::bail!(
+            "{}, but neither {} nor [[auth.users]] is configured",
+            if config.auth.required { "[auth] required is set" } else { "the Trader runs on live market data" },
+            config.allocation.operator_token_env
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authenticate() {
+        let user = |name: &str, swarms: &[&str]| ApiUser {
+            name: name.to_string(),
+            swarms: swarms.iter().map(|s| s.to_string()).collect(),
+        };
+        let users = ApiUsers::new([
+            ("k-alice".to_string(), user("alice", &["client-a", "client-b"])),
+            ("k-admin".to_string(), user("admin", &["*"])),
+        ]);
+        
+        assert_eq!(users.authenticate(Some("k-alice"), "client-b").unwrap().unwrap().name, "alice");
+        assert_eq!(
+            users.authenticate(Some("k-alice"), "client-c"),
+            Err(AuthError::Forbidden { user: "alice".to_string(), swarm: "client-c".to_string() })
+        );
+        assert!(users.authenticate(Some("k-admin"), "client-c").is_ok());
+        assert_eq!(users.authenticate(Some("k-bob"), "client-a"), Err(AuthError::UnknownKey));
+        assert_eq!(users.authenticate(None, "client-a"), Err(AuthError::MissingKey));
+        assert_eq!(ApiUsers::default().authenticate(None, "client-a"), Ok(None));
+        
+        // A user whose key isn't set keeps the API closed rather than open
+        let config = AuthConfig {
+            users: vec![crate::core::config::ApiUserConfig {
+                name: "carol".to_string(),
+                api_key_env: "DRIFTGUARD_TEST_UNSET_KEY".to_string(),
+                swarms: vec!["client-a".to_string()],
+            }],
+            required: false,
+        };
+        let closed = ApiUsers::from_config(&config);
+        assert!(closed.enabled());
+        assert_eq!(closed.authenticate(Some(""), "client-a"), Err(AuthError::MissingKey));
+        
+        let operator = ClientIdentity { user: Some("alice".to_string()), ..ClientIdentity::default() };
+        assert!(authorize(&operator, &Config::default(), None).is_ok());
+        
+        // A required API must be protected one way or the other
+        let mut required = Config::default();
+        required.auth.required = true;
+        required.allocation.operator_token_env = "DRIFTGUARD_TEST_UNSET_TOKEN".to_string();
+        assert!(check_required(&required, false).is_err());
+        
+        // So must one the Trader trades live through
+        let mut live = required.clone();
+        live.auth.required = false;
+        assert!(check_required(&live, false).is_ok());
+        assert!(check_required(&live, true).is_err());
+        
+        required.auth = AuthConfig { required: true, ..config };
+        assert!(check_required(&required, true).is_ok());
+    }
+}
@@ -0,0 +1,135 @@
+//! Benchmark Tracking
+//!
+//! "Ghost" portfolios valued alongside the real one from the same price
+//! moves, so rebalancing can be judged against naive alternatives:
+//! - never rebalanced: the starting split left to drift
+//! - calendar rebalanced: reset to target at each period boundary
+//! - benchmark: 100% in a single symbol (buy-and-hold)
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::core::blackboard::TargetAllocation;
+use crate::core::config::Config;
+
+/// A hypothetical portfolio: dollar value held per symbol
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GhostPortfolio {
+    pub holdings: BTreeMap<String, f64>,
+}
+
+impl GhostPortfolio {
+    /// `total` split between the stocks and bonds symbols at `target`
+    pub fn split(total: f64, target: &TargetAllocation, config: &Config) -> Self {
+        let mut holdings = BTreeMap::new();
+        holdings.insert(config.portfolio.stocks_symbol.clone(), total * target.stocks_pct / 100.0);
+        *holdings.entry(config.portfolio.bonds_symbol.clone()).or_default() += total * target.bonds_pct / 100.0;
+        Self { holdings }
+    }
+    
+    /// `total` held entirely in `symbol`
+    pub fn single(total: f64, symbol: &str) -> Self {
+        Self { holdings: BTreeMap::from([(symbol.to_string(), total)]) }
+    }
+    
+    pub fn total_value(&self) -> f64 {
+        self.holdings.values().sum()
+    }
+    
+    /// Apply price moves (new / old price per symbol)
+    pub fn mark(&mut self, ratio: &impl Fn(&str) -> f64) {
+        for (symbol, value) in self.holdings.iter_mut() {
+            *value *= ratio(symbol);
+        }
+    }
+}
+
+/// The set of ghost portfolios tracked on the blackboard (`state:benchmarks`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkSet {
+    pub no_rebalance: GhostPortfolio,
+    pub calendar_rebalanced: GhostPortfolio,
+    pub benchmark: GhostPortfolio,
+    pub benchmark_symbol: String,
+    /// Calendar period the calendar-rebalanced ghost was last reset in
+    pub calendar_period: String,
+    pub started_at: String,
+    pub updated_at: String,
+}
+
+impl BenchmarkSet {
+    /// Start every ghost from `total` at today's target
+    pub fn new(total: f64, target: &TargetAllocation, config: &Config, today: NaiveDate) -> Self {
+        let settings = &config.benchmarks;
+        let now = chrono::Utc::now().to_rfc3339();
+        Self {
+            no_rebalance: GhostPortfolio::split(total, target, config),
+            calendar_rebalanced: GhostPortfolio::split(total, target, config),
+            benchmark: GhostPortfolio::single(total, &settings.benchmark_symbol),
+            benchmark_symbol: settings.benchmark_symbol.clone(),
+            calendar_period: settings.calendar_frequency.period_id(today),
+            started_at: now.clone(),
+            updated_at: now,
+        }
+    }
+    
+    pub fn mark(&mut self, ratio: &impl Fn(&str) -> f64) {
+        self.no_rebalance.mark(ratio);
+        self.calendar_rebalanced.mark(ratio);
+        self.benchmark.mark(ratio);
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+    }
+    
+    /// Reset the calendar ghost to target when a new period starts.
+    /// Returns true if it rebalanced.
+    pub fn rebalance_calendar(&mut self, target: &TargetAllocation, config: &Config, today: NaiveDate) -> bool {
+        let period = config.benchmarks.calendar_frequency.period_id(today);
+        if period == self.calendar_period {
+            return false;
+        }
+        self.calendar_rebalanced = GhostPortfolio::split(self.calendar_rebalanced.total_value(), target, config);
+        self.calendar_period = period;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn target() -> TargetAllocation {
+        TargetAllocation { stocks_pct: 60.0, bonds_pct: 40.0 }
+    }
+    
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+    
+    #[test]
+    fn test_ghosts_follow_price_moves() {
+        let config = Config::default();
+        let mut set = BenchmarkSet::new(100_000.0, &target(), &config, date(2026, 1, 5));
+        
+        // Stocks +10%, bonds unchanged
+        set.mark(&|symbol: &str| if symbol == "SPY" { 1.1 } else { 1.0 });
+        
+        assert!((set.no_rebalance.total_value() - 106_000.0).abs() < 1e-6);
+        assert!((set.benchmark.total_value() - 110_000.0).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn test_calendar_ghost_rebalances_on_new_period() {
+        let config = Config::default();
+        let mut set = BenchmarkSet::new(100_000.0, &target(), &config, date(2026, 1, 5));
+        set.mark(&|symbol: &str| if symbol == "SPY" { 1.1 } else { 1.0 });
+        
+        assert!(!set.rebalance_calendar(&target(), &config, date(2026, 3, 31)));
+        assert!(set.rebalance_calendar(&target(), &config, date(2026, 4, 1)));
+        
+        let stocks = set.calendar_rebalanced.holdings["SPY"];
+        assert!((stocks / set.calendar_rebalanced.total_value() - 0.6).abs() < 1e-9);
+        // The never-rebalanced ghost keeps drifting
+        assert!(set.no_rebalance.holdings["SPY"] / set.no_rebalance.total_value() > 0.6);
+    }
+}
@@ -6,24 +6,148 @@
 //!
 //! This implements the core stigmergic pattern: indirect coordination
 //! through environmental signals.
+//!
+//! Every key is scoped to the swarm's namespace (see `core::federation`),
+//! so several swarms can share one Redis without seeing each other's
+//! pheromones.
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use redis::aio::ConnectionManager;
 use redis::AsyncCommands;
+use rust_decimal::Decimal;
 use serde::{de::DeserializeOwned, Serialize};
-use std::sync::Arc;
-use tokio::sync::broadcast;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, info, trace, warn};
 
-use crate::core::physics::{Pheromone, PheromonePayload, PheromoneType};
+use crate::core::activity::AgentActivity;
+use crate::core::alerts::RuleState;
+use crate::core::audit::AuditEntry;
+use crate::core::benchmarks::BenchmarkSet;
+use crate::core::daily_summary::DailySummary;
+use crate::core::bootstrap::PortfolioBootstrap;
+use crate::core::error::DriftGuardError;
+use crate::core::federation::{self, SwarmHeartbeat};
+use crate::core::holdings::ImportedHoldings;
+use crate::core::metrics_writer;
+use crate::core::money;
+use crate::core::order_queue::PendingOrder;
+use crate::core::performance::CashFlow;
+use crate::core::retention::{self, StorageReport};
+use crate::core::sampling::EventSampler;
+use crate::core::runs::RunMetadata;
+use crate::core::runtime::{self, AgentRuntime};
+use crate::core::pipeline::AgentRole;
+use crate::core::physics::{compose, Pheromone, PheromonePayload, PheromoneType};
+use crate::core::statements::{DailySnapshot, MonthlyStatement};
+use crate::core::timeline::TradeTimeline;
+use crate::core::trails::TrailEntry;
+use crate::core::valuation::{DrawdownState, PortfolioHistoryPoint};
+use crate::core::wash_sale::RecentFill;
 use crate::core::Config;
+use crate::agents::shadow::{Divergence, ShadowReport};
+use crate::execution::queue::DeadLetter;
+use crate::execution::reconcile::ReconciliationReport;
+use crate::core::mode::{KillSwitch, SystemStatus};
+use crate::core::notify_queue::{Notification, NotifyDeadLetter};
+use crate::core::correlation::CorrelationReading;
+use crate::core::risk_index::CompositeRiskIndex;
+use crate::core::sim_clock::ClockState;
+use crate::market::daily_history::DailyHistory;
+use crate::market::realized_vol::PriceSample;
+use crate::market::{ProviderHealth, QuoteSource};
 
 /// Event emitted when pheromone state changes
 #[derive(Debug, Clone)]
 pub struct PheromoneEvent {
+    /// Position in this process's event sequence
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
     pub pheromone_type: String,
     pub intensity: f64,
     pub action: PheromoneAction,
+    /// Optional human-readable context (e.g. anomaly details)
+    pub detail: Option<String>,
+    /// Observations this event stands for (>1 when sniffs were coalesced)
+    pub count: u32,
+    /// Categorized error, for Error, DataAnomaly and ExecutionFailed events
+    pub error: Option<DriftGuardError>,
+    /// Run of the process that emitted it
+    pub run_id: Option<String>,
+}
+
+/// A pheromone as seen by `Blackboard::peek`
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct PheromoneReading<T> {
+    pub pheromone_type: PheromoneType,
+    pub intensity: f64,
+    pub threshold: f64,
+    pub is_active: bool,
+    pub pheromone: Pheromone,
+    pub data: T,
+}
+
+/// What one agent tick reads, taken at one instant by
+/// `Blackboard::snapshot_view`
+#[derive(Clone)]
+pub struct SnapshotView<'a> {
+    board: &'a Blackboard,
+    /// When the keys were read; intensities are evaluated at this instant
+    pub read_at: DateTime<Utc>,
+    pheromones: Vec<(PheromoneType, Option<PheromonePayload<serde_json::Value>>)>,
+    portfolio: Option<PortfolioState>,
+    target: TargetAllocation,
+    baseline: Option<RebalanceBaseline>,
+}
+
+impl SnapshotView<'_> {
+    /// Sniff a pheromone as of the view: its payload while active, with
+    /// the same Sniffed/Decayed event as `Blackboard::sniff`
+    pub fn sniff<T: DeserializeOwned>(&self, pheromone_type: PheromoneType) -> Result<Option<T>> {
+        let Some(payload) = self.payload(pheromone_type) else {
+            trace!("👃 SNIFF [{}] - no pheromone found", pheromone_type.label());
+            return Ok(None);
+        };
+        let intensity = payload.pheromone.intensity_at(self.read_at);
+        let threshold = pheromone_type.threshold(&self.board.config);
+        if intensity > threshold {
+            debug!("👃 SNIFF [{}] intensity={:.2} (threshold={:.2}) ✓ ACTIVE (view)", pheromone_type.label(), intensity, threshold);
+            self.board.emit_live(pheromone_type, intensity, PheromoneAction::Sniffed, None);
+            Ok(Some(serde_json::from_value(payload.data.clone())?))
+        } else {
+            debug!("👃 SNIFF [{}] intensity={:.2} (threshold={:.2}) ✗ DECAYED (view)", pheromone_type.label(), intensity, threshold);
+            self.board.emit_live(pheromone_type, intensity, PheromoneAction::Decayed, None);
+            Ok(None)
+        }
+    }
+    
+    /// A pheromone's intensity as of the view (0 when absent); emits no event
+    pub fn intensity(&self, pheromone_type: PheromoneType) -> f64 {
+        self.payload(pheromone_type).map_or(0.0, |payload| payload.pheromone.intensity_at(self.read_at))
+    }
+    
+    pub fn portfolio_state(&self) -> Option<&PortfolioState> {
+        self.portfolio.as_ref()
+    }
+    
+    pub fn target_allocation(&self) -> &TargetAllocation {
+        &self.target
+    }
+    
+    pub fn rebalance_baseline(&self) -> Option<&RebalanceBaseline> {
+        self.baseline.as_ref()
+    }
+    
+    fn payload(&self, pheromone_type: PheromoneType) -> Option<&PheromonePayload<serde_json::Value>> {
+        self.pheromones
+            .iter()
+            .find(|(ptype, _)| *ptype == pheromone_type)
+            .and_then(|(_, payload)| payload.as_ref())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,8 +155,19 @@ pub enum PheromoneAction {
     Deposited,
     Sniffed,
     Decayed,
+    /// Incoming data was rejected before it could be deposited
+    DataAnomaly,
+    /// A trade was rolled back instead of being committed
+    ExecutionFailed,
+    /// Blackboard holdings disagree with the execution backend
+    PositionMismatch,
+    /// An agent failed; the event carries the categorized error
+    Error,
 }
 
+/// How long a permit claim is kept; far longer than any permit stays active
+const PERMIT_CLAIM_TTL_SECS: u64 = 3600;
+
 /// The shared environment for stigmergic coordination
 pub struct Blackboard {
     /// Redis connection manager (handles reconnection)
@@ -41,8 +176,26 @@ pub struct Blackboard {
     /// Configuration for decay rates and thresholds
     config: Arc<Config>,
     
+    /// Key prefix isolating this swarm from others on the same Redis
+    namespace: String,
+    
     /// Broadcast channel for real-time updates (for dashboard)
     event_tx: broadcast::Sender<PheromoneEvent>,
+    
+    /// Recent events kept for dashboard replay after a reconnect
+    recent_events: Mutex<VecDeque<PheromoneEvent>>,
+    
+    /// Sequence number of the next event
+    next_seq: AtomicU64,
+    
+    /// Coalesces repeated sniff observations before they are broadcast
+    sampler: Mutex<EventSampler>,
+    
+    /// Run this process registered, once it has
+    run_id: OnceLock<String>,
+    
+    /// Feeds the metrics writer task, once started
+    metrics_tx: OnceLock<mpsc::Sender<AgentMetrics>>,
 }
 
 impl Blackboard {
@@ -58,12 +211,20 @@ impl Blackboard {
         // Create broadcast channel for dashboard updates
         let (event_tx, _) = broadcast::channel(100);
         
-        info!("Blackboard connected to Redis at {}", redis_url);
+        let namespace = federation::key_prefix(&config.swarm.name)?;
+        info!("Blackboard connected to Redis at {} (swarm '{}')", redis_url, config.swarm.name);
         
+        let replay_capacity = config.dashboard.replay_buffer_size;
         Ok(Self {
             redis,
             config,
+            namespace,
             event_tx,
+            recent_events: Mutex::new(VecDeque::with_capacity(replay_capacity)),
+            next_seq: AtomicU64::new(1),
+            sampler: Mutex::new(EventSampler::default()),
+            run_id: OnceLock::new(),
+            metrics_tx: OnceLock::new(),
         })
     }
     
@@ -72,6 +233,42 @@ impl Blackboard {
         &self.config
     }
     
+    /// Name of the swarm this blackboard belongs to
+    pub fn swarm_name(&self) -> &str {
+        &self.config.swarm.name
+    }
+    
+    /// Tag this process's events and trades with a run (first call wins)
+    pub fn set_run_id(&self, id: &str) {
+        let _ = self.run_id.set(id.to_string());
+    }
+    
+    /// Run this process registered, if any
+    pub fn run_id(&self) -> Option<&str> {
+        self.run_id.get().map(String::as_str)
+    }
+    
+    /// Route agent metrics through a batching writer task (see
+    /// `core::metrics_writer`); until then they are written directly
+    pub fn start_metrics_writer(self: &Arc<Self>) {
+        let (tx, rx) = mpsc::channel(metrics_writer::CHANNEL_CAPACITY);
+        if self.metrics_tx.set(tx).is_ok() {
+            tokio::spawn(metrics_writer::run(self.clone(), rx));
+        }
+    }
+    
+    /// A Redis connection, counted against the calling agent's loop (see
+    /// `core::runtime`)
+    fn conn(&self) -> ConnectionManager {
+        runtime::record_redis_call();
+        self.redis.clone()
+    }
+    
+    /// Redis key for `key` within this swarm's namespace
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.namespace, key)
+    }
+    
     /// Deposit a pheromone with associated data
     /// 
     /// Agents call this to signal information to other agents
@@ -79,16 +276,43 @@ impl Blackboard {
         &self,
         pheromone_type: PheromoneType,
         data: T,
+    ) -> Result<()> {
+        self.deposit_with_intensity(pheromone_type, data, 1.0).await
+    }
+    
+    /// Deposit a pheromone with an explicit initial intensity
+    /// 
+    /// Weaker deposits decay below the activation threshold sooner, or
+    /// never activate downstream agents at all.
+    pub async fn deposit_with_intensity<T: Serialize + Clone>(
+        &self,
+        pheromone_type: PheromoneType,
+        data: T,
+        intensity: f64,
     ) -> Result<()> {
         let decay_rate = pheromone_type.decay_rate(&self.config);
         
-        let pheromone = Pheromone::with_decay(pheromone_type.label(), decay_rate);
+        let pheromone = Pheromone::new(pheromone_type.label(), intensity.clamp(0.0, 1.0), decay_rate);
         let payload = PheromonePayload::new(data, pheromone.clone());
         
         let serialized = serde_json::to_string(&payload)?;
         
-        let mut conn = self.redis.clone();
-        conn.set::<_, _, ()>(pheromone_type.key(), &serialized).await?;
+        let mut pipe = redis::pipe();
+        pipe.set(self.key(pheromone_type.key()), &serialized).ignore();
+        let trail_max = self.config.dashboard.trail_max_entries;
+        if trail_max > 0 {
+            let trail = self.key("history:pheromones");
+            pipe.lpush(&trail, serde_json::to_string(&TrailEntry::new(pheromone_type, &pheromone))?).ignore();
+            pipe.ltrim(&trail, 0, trail_max as isize - 1).ignore();
+        }
+        let recent_max = self.config.pheromones.recent_deposits;
+        if recent_max > 0 {
+            let recent = self.key(&pheromone_type.recent_key());
+            pipe.lpush(&recent, &serialized).ignore();
+            pipe.ltrim(&recent, 0, recent_max as isize - 1).ignore();
+        }
+        let mut conn = self.conn();
+        pipe.query_async::<_, ()>(&mut conn).await?;
         
         let intensity = pheromone.current_intensity();
         info!(
@@ -99,11 +323,7 @@ impl Blackboard {
         );
         
         // Notify dashboard
-        let _ = self.event_tx.send(PheromoneEvent {
-            pheromone_type: pheromone_type.label().to_string(),
-            intensity,
-            action: PheromoneAction::Deposited,
-        });
+        self.emit(pheromone_type, intensity, PheromoneAction::Deposited, None);
         
         Ok(())
     }
@@ -118,8 +338,8 @@ impl Blackboard {
     ) -> Result<Option<T>> {
         let threshold = pheromone_type.threshold(&self.config);
         
-        let mut conn = self.redis.clone();
-        let raw: Option<String> = conn.get(pheromone_type.key()).await?;
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key(pheromone_type.key())).await?;
         
         let Some(serialized) = raw else {
             trace!("👃 SNIFF [{}] - no pheromone found", pheromone_type.label());
@@ -137,11 +357,7 @@ impl Blackboard {
                 threshold
             );
             
-            let _ = self.event_tx.send(PheromoneEvent {
-                pheromone_type: pheromone_type.label().to_string(),
-                intensity,
-                action: PheromoneAction::Sniffed,
-            });
+            self.emit_live(pheromone_type, intensity, PheromoneAction::Sniffed, None);
             
             Ok(Some(payload.data))
         } else {
@@ -152,28 +368,241 @@ impl Blackboard {
                 threshold
             );
             
-            let _ = self.event_tx.send(PheromoneEvent {
-                pheromone_type: pheromone_type.label().to_string(),
-                intensity,
-                action: PheromoneAction::Decayed,
-            });
+            self.emit_live(pheromone_type, intensity, PheromoneAction::Decayed, None);
             
             Ok(None)
         }
     }
     
+    /// Sniff a noisy signal smoothed over its recent deposits
+    /// 
+    /// Active only while the latest deposit is, like `sniff`; the payload
+    /// returned merges the last `[pheromones] recent_deposits` deposits,
+    /// each weighted by its current intensity (see `physics::compose`).
+    pub async fn sniff_smoothed<T: DeserializeOwned>(
+        &self,
+        pheromone_type: PheromoneType,
+    ) -> Result<Option<T>> {
+        let threshold = pheromone_type.threshold(&self.config);
+        let recent_max = self.config.pheromones.recent_deposits;
+        
+        let mut conn = self.conn();
+        let raw: Vec<String> = if recent_max > 0 {
+            conn.lrange(self.key(&pheromone_type.recent_key()), 0, recent_max as isize - 1).await?
+        } else {
+            conn.get::<_, Option<String>>(self.key(pheromone_type.key())).await?.into_iter().collect()
+        };
+        let deposits: Vec<PheromonePayload<serde_json::Value>> =
+            raw.iter().filter_map(|s| serde_json::from_str(s).ok()).collect();
+        let now = Utc::now();
+        
+        let Some(latest) = deposits.first() else {
+            trace!("👃 SNIFF [{}] - no pheromone found", pheromone_type.label());
+            return Ok(None);
+        };
+        let intensity = latest.pheromone.intensity_at(now);
+        if intensity <= threshold {
+            debug!(
+                "👃 SNIFF [{}] intensity={:.2} (threshold={:.2}) ✗ DECAYED",
+                pheromone_type.label(),
+                intensity,
+                threshold
+            );
+            self.emit_live(pheromone_type, intensity, PheromoneAction::Decayed, None);
+            return Ok(None);
+        }
+        
+        debug!(
+            "👃 SNIFF [{}] intensity={:.2} (threshold={:.2}) ✓ ACTIVE, smoothed over {} deposits",
+            pheromone_type.label(),
+            intensity,
+            threshold,
+            deposits.len()
+        );
+        self.emit_live(pheromone_type, intensity, PheromoneAction::Sniffed, None);
+        
+        match compose::merge(&deposits, now) {
+            Some(merged) => Ok(Some(serde_json::from_value(merged)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Sniff several pheromones as of one instant
+    /// 
+    /// A single MGET reads them together and every intensity is evaluated
+    /// at the same moment, so none can decay between the checks the way it
+    /// could across separate sniffs. Returns each payload in order, `None`
+    /// for the absent and decayed ones.
+    pub async fn sniff_composite(
+        &self,
+        pheromone_types: &[PheromoneType],
+    ) -> Result<Vec<(PheromoneType, Option<serde_json::Value>)>> {
+        if pheromone_types.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.conn();
+        let keys: Vec<String> = pheromone_types.iter().map(|ptype| self.key(ptype.key())).collect();
+        let raw: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?;
+        let now = Utc::now();
+        
+        let mut readings = Vec::with_capacity(pheromone_types.len());
+        for (&ptype, serialized) in pheromone_types.iter().zip(raw) {
+            let Some(serialized) = serialized else {
+                trace!("👃 SNIFF [{}] - no pheromone found", ptype.label());
+                readings.push((ptype, None));
+                continue;
+            };
+            let payload: PheromonePayload<serde_json::Value> = serde_json::from_str(&serialized)?;
+            let intensity = payload.pheromone.intensity_at(now);
+            let active = intensity > ptype.threshold(&self.config);
+            let action = if active { PheromoneAction::Sniffed } else { PheromoneAction::Decayed };
+            self.emit_live(ptype, intensity, action, None);
+            readings.push((ptype, active.then_some(payload.data)));
+        }
+        Ok(readings)
+    }
+    
+    /// Read everything an agent's tick consults as of one instant
+    /// 
+    /// One MGET fetches every pheromone with the portfolio state, target
+    /// allocation and rebalance baseline, so an agent reading several of
+    /// them can't see a trade or target change land between its reads.
+    /// Sniffs through the view emit the usual events.
+    pub async fn snapshot_view(&self) -> Result<SnapshotView<'_>> {
+        let state_keys = ["state:portfolio", "config:target_allocation", "state:rebalance_baseline"];
+        let keys: Vec<String> = PheromoneType::ALL
+            .iter()
+            .map(|ptype| ptype.key())
+            .chain(state_keys)
+            .map(|key| self.key(key))
+            .collect();
+        let mut conn = self.conn();
+        let mut raw: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?;
+        let read_at = Utc::now();
+        
+        let state = raw.split_off(PheromoneType::ALL.len());
+        let pheromones = PheromoneType::ALL
+            .iter()
+            .zip(raw)
+            .map(|(&ptype, serialized)| Ok((ptype, serialized.map(|s| serde_json::from_str(&s)).transpose()?)))
+            .collect::<Result<Vec<_>>>()?;
+        let [portfolio, target, baseline]: [Option<String>; 3] = state
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("MGET returned the wrong number of keys"))?;
+        Ok(SnapshotView {
+            board: self,
+            read_at,
+            pheromones,
+            portfolio: portfolio.map(|s| serde_json::from_str(&s)).transpose()?,
+            target: match target {
+                Some(s) => serde_json::from_str(&s)?,
+                None => self.default_target_allocation(),
+            },
+            baseline: baseline.map(|s| serde_json::from_str(&s)).transpose()?,
+        })
+    }
+    
+    /// Look at a pheromone and its payload without sniffing it
+    /// 
+    /// Unlike `sniff`, this emits no Sniffed/Decayed event and returns the
+    /// payload whether or not the pheromone is active, so diagnostics and
+    /// the dashboard can observe the blackboard without showing up as
+    /// agent activity.
+    pub async fn peek<T: DeserializeOwned>(&self, pheromone_type: PheromoneType) -> Result<Option<PheromoneReading<T>>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key(pheromone_type.key())).await?;
+        
+        let Some(serialized) = raw else {
+            return Ok(None);
+        };
+        
+        let payload: PheromonePayload<T> = serde_json::from_str(&serialized)?;
+        let threshold = pheromone_type.threshold(&self.config);
+        let intensity = payload.intensity();
+        Ok(Some(PheromoneReading {
+            pheromone_type,
+            intensity,
+            threshold,
+            is_active: intensity > threshold,
+            pheromone: payload.pheromone,
+            data: payload.data,
+        }))
+    }
+    
     /// Get current intensity of a pheromone (for dashboard visualization)
     pub async fn get_intensity(&self, pheromone_type: PheromoneType) -> Result<f64> {
-        let mut conn = self.redis.clone();
-        let raw: Option<String> = conn.get(pheromone_type.key()).await?;
+        Ok(self
+            .peek::<serde_json::Value>(pheromone_type)
+            .await?
+            .map_or(0.0, |reading| reading.intensity))
+    }
+    
+    /// Read a pheromone's decay state without its data (emits no event)
+    pub async fn get_pheromone(&self, pheromone_type: PheromoneType) -> Result<Option<Pheromone>> {
+        Ok(self
+            .peek::<serde_json::Value>(pheromone_type)
+            .await?
+            .map(|reading| reading.pheromone))
+    }
+    
+    /// Announce that a pheromone has decayed below its threshold
+    /// 
+    /// With `cleanup` the key is also deleted, unless a fresh deposit has
+    /// replaced that pheromone in the meantime.
+    pub async fn announce_decay(
+        &self,
+        pheromone_type: PheromoneType,
+        pheromone: &Pheromone,
+        cleanup: bool,
+    ) -> Result<()> {
+        let threshold = pheromone_type.threshold(&self.config);
+        info!(
+            "🍂 DECAYED [{}] below {:.2} after {:.1}s",
+            pheromone_type.label(),
+            threshold,
+            pheromone.age_secs()
+        );
+        
+        self.emit(
+            pheromone_type,
+            pheromone.current_intensity(),
+            PheromoneAction::Decayed,
+            Some(format!("crossed threshold {:.2}", threshold)),
+        );
+        
+        if cleanup {
+            let mut conn = self.conn();
+            let created_at = serde_json::to_value(pheromone.created_at)?;
+            let _: i32 = redis::Script::new(
+                r"local raw = redis.call('GET', KEYS[1])
+                  if not raw then return 0 end
+                  if cjson.decode(raw).pheromone.created_at ~= ARGV[1] then return 0 end
+                  return redis.call('DEL', KEYS[1])",
+            )
+            .key(self.key(pheromone_type.key()))
+            .arg(created_at.as_str().unwrap_or_default())
+            .invoke_async(&mut conn)
+            .await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Time left before a pheromone decays below its activation threshold
+    /// (`None` if it is absent or already inactive)
+    pub async fn time_until_inactive(&self, pheromone_type: PheromoneType) -> Result<Option<Duration>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key(pheromone_type.key())).await?;
         
         let Some(serialized) = raw else {
-            return Ok(0.0);
+            return Ok(None);
         };
         
-        // Parse just to get the pheromone, ignore data type
         let payload: PheromonePayload<serde_json::Value> = serde_json::from_str(&serialized)?;
-        Ok(payload.intensity())
+        Ok(payload
+            .pheromone
+            .time_until_inactive(pheromone_type.threshold(&self.config))
+            .map(Duration::from_secs_f64))
     }
     
     /// Get all pheromone intensities (for dashboard)
@@ -187,23 +616,180 @@ impl Blackboard {
         Ok(result)
     }
     
+    /// Report that data destined for a pheromone was rejected as anomalous
+    /// 
+    /// Nothing is deposited; the event only informs observers (dashboard)
+    /// why the signal is being allowed to decay.
+    pub async fn report_anomaly(
+        &self,
+        pheromone_type: PheromoneType,
+        detail: impl Into<String>,
+    ) -> Result<()> {
+        let intensity = self.get_intensity(pheromone_type).await?;
+        let detail = detail.into();
+        
+        warn!("🚨 ANOMALY [{}] {}", pheromone_type.label(), detail);
+        
+        let error = DriftGuardError::DataQuality(detail.clone());
+        self.emit_typed(pheromone_type, intensity, PheromoneAction::DataAnomaly, Some(detail), Some(error));
+        
+        Ok(())
+    }
+    
+    /// Report a trade that could not be executed or committed
+    pub async fn report_execution_failure(&self, error: &DriftGuardError) -> Result<()> {
+        let intensity = self.get_intensity(PheromoneType::TradeExecuted).await?;
+        
+        warn!("🚨 EXECUTION FAILED: {}", error.message());
+        
+        self.emit_typed(
+            PheromoneType::TradeExecuted,
+            intensity,
+            PheromoneAction::ExecutionFailed,
+            Some(error.message().to_string()),
+            Some(error.clone()),
+        );
+        
+        Ok(())
+    }
+    
+    /// Report an agent failure as an `Error` event on the pheromone the
+    /// agent produces. Never fails, so store outages are reported too.
+    pub async fn report_error(&self, pheromone_type: PheromoneType, error: &DriftGuardError) {
+        let intensity = self.get_intensity(pheromone_type).await.unwrap_or(0.0);
+        
+        warn!("🚨 ERROR [{}] {}", pheromone_type.label(), error);
+        
+        self.emit_typed(
+            pheromone_type,
+            intensity,
+            PheromoneAction::Error,
+            Some(error.message().to_string()),
+            Some(error.clone()),
+        );
+    }
+    
+    /// Report holdings that disagree with the execution backend
+    pub async fn report_position_mismatch(&self, detail: impl Into<String>) -> Result<()> {
+        let intensity = self.get_intensity(PheromoneType::TradeExecuted).await?;
+        let detail = detail.into();
+        
+        warn!("🚨 POSITION MISMATCH: {}", detail);
+        
+        self.emit(PheromoneType::TradeExecuted, intensity, PheromoneAction::PositionMismatch, Some(detail));
+        
+        Ok(())
+    }
+    
     /// Subscribe to pheromone events (for dashboard WebSocket)
     pub fn subscribe(&self) -> broadcast::Receiver<PheromoneEvent> {
         self.event_tx.subscribe()
     }
     
+    /// Buffered events emitted after `since`, oldest first
+    pub fn events_since(&self, since: DateTime<Utc>) -> Vec<PheromoneEvent> {
+        let recent = self.recent_events.lock().unwrap_or_else(|e| e.into_inner());
+        recent.iter().filter(|e| e.timestamp > since).cloned().collect()
+    }
+    
+    /// Broadcast an event and keep it for replay
+    fn emit(
+        &self,
+        pheromone_type: PheromoneType,
+        intensity: f64,
+        action: PheromoneAction,
+        detail: Option<String>,
+    ) {
+        self.emit_typed(pheromone_type, intensity, action, detail, None);
+    }
+    
+    /// `emit` with a categorized error attached
+    fn emit_typed(
+        &self,
+        pheromone_type: PheromoneType,
+        intensity: f64,
+        action: PheromoneAction,
+        detail: Option<String>,
+        error: Option<DriftGuardError>,
+    ) {
+        let event = self.broadcast(pheromone_type, intensity, action, detail, error, 1);
+        
+        let capacity = self.config.dashboard.replay_buffer_size;
+        if capacity == 0 {
+            return;
+        }
+        let mut recent = self.recent_events.lock().unwrap_or_else(|e| e.into_inner());
+        while recent.len() >= capacity {
+            recent.pop_front();
+        }
+        recent.push_back(event);
+    }
+    
+    /// Broadcast a sniff observation, sampled per `[event_sampling]` and
+    /// not buffered (observations repeat every tick and would crowd real
+    /// transitions out of the replay buffer); muted types broadcast none
+    fn emit_live(
+        &self,
+        pheromone_type: PheromoneType,
+        intensity: f64,
+        action: PheromoneAction,
+        detail: Option<String>,
+    ) {
+        if !self.config.event_sampling.observes(pheromone_type) {
+            return;
+        }
+        let interval = self
+            .config
+            .event_sampling
+            .interval(matches!(action, PheromoneAction::Decayed));
+        let key = format!("{}:{:?}", pheromone_type.key(), action);
+        let admitted = self
+            .sampler
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .admit(&key, interval, std::time::Instant::now());
+        
+        if let Some(count) = admitted {
+            self.broadcast(pheromone_type, intensity, action, detail, None, count);
+        }
+    }
+    
+    fn broadcast(
+        &self,
+        pheromone_type: PheromoneType,
+        intensity: f64,
+        action: PheromoneAction,
+        detail: Option<String>,
+        error: Option<DriftGuardError>,
+        count: u32,
+    ) -> PheromoneEvent {
+        let event = PheromoneEvent {
+            seq: self.next_seq.fetch_add(1, Ordering::SeqCst),
+            timestamp: Utc::now(),
+            pheromone_type: pheromone_type.label().to_string(),
+            intensity,
+            action,
+            detail,
+            count,
+            error,
+            run_id: self.run_id().map(str::to_string),
+        };
+        let _ = self.event_tx.send(event.clone());
+        event
+    }
+    
     /// Store portfolio state
     pub async fn set_portfolio_state(&self, state: &PortfolioState) -> Result<()> {
-        let mut conn = self.redis.clone();
+        let mut conn = self.conn();
         let serialized = serde_json::to_string(state)?;
-        conn.set::<_, _, ()>("state:portfolio", &serialized).await?;
+        conn.set::<_, _, ()>(self.key("state:portfolio"), &serialized).await?;
         Ok(())
     }
     
     /// Get portfolio state
     pub async fn get_portfolio_state(&self) -> Result<Option<PortfolioState>> {
-        let mut conn = self.redis.clone();
-        let raw: Option<String> = conn.get("state:portfolio").await?;
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:portfolio")).await?;
         
         match raw {
             Some(s) => Ok(Some(serde_json::from_str(&s)?)),
@@ -211,89 +797,1110 @@ impl Blackboard {
         }
     }
     
-    /// Store target allocation (from UI)
-    pub async fn set_target_allocation(&self, stocks_pct: f64, bonds_pct: f64) -> Result<()> {
-        let mut conn = self.redis.clone();
-        let allocation = TargetAllocation { stocks_pct, bonds_pct };
-        let serialized = serde_json::to_string(&allocation)?;
-        conn.set::<_, _, ()>("config:target_allocation", &serialized).await?;
-        info!("Target allocation updated: {}% stocks, {}% bonds", stocks_pct, bonds_pct);
+    /// Get portfolio state along with a version token for compare-and-set
+    pub async fn get_portfolio_snapshot(&self) -> Result<Option<(PortfolioState, PortfolioVersion)>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:portfolio")).await?;
+        
+        match raw {
+            Some(s) => Ok(Some((serde_json::from_str(&s)?, PortfolioVersion(s)))),
+            None => Ok(None),
+        }
+    }
+    
+    /// Replace portfolio state only if it is still at `expected`
+    /// 
+    /// Returns false (and writes nothing) when another writer got there first.
+    pub async fn compare_and_set_portfolio(
+        &self,
+        expected: &PortfolioVersion,
+        state: &PortfolioState,
+    ) -> Result<bool> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(state)?;
+        let swapped: i32 = redis::Script::new(
+            r"if redis.call('GET', KEYS[1]) ~= ARGV[1] then return 0 end
+              redis.call('SET', KEYS[1], ARGV[2])
+              return 1",
+        )
+        .key(self.key("state:portfolio"))
+        .arg(&expected.0)
+        .arg(&serialized)
+        .invoke_async(&mut conn)
+        .await?;
+        Ok(swapped == 1)
+    }
+    
+    /// Atomically commit a trade: new portfolio state, rebalance baseline
+    /// and trade log entry, only if the portfolio is still at `expected`
+    pub async fn commit_trade(
+        &self,
+        expected: &PortfolioVersion,
+        state: &PortfolioState,
+        baseline: &RebalanceBaseline,
+        entry: &TradeLogEntry,
+    ) -> Result<bool> {
+        let mut conn = self.conn();
+        let committed: i32 = redis::Script::new(
+            r"if redis.call('GET', KEYS[1]) ~= ARGV[1] then return 0 end
+              redis.call('SET', KEYS[1], ARGV[2])
+              redis.call('SET', KEYS[2], ARGV[3])
+              redis.call('LPUSH', KEYS[3], ARGV[4])
+              redis.call('LTRIM', KEYS[3], 0, tonumber(ARGV[5]) - 1)
+              return 1",
+        )
+        .key(self.key("state:portfolio"))
+        .key(self.key("state:rebalance_baseline"))
+        .key(self.key("trade_log"))
+        .arg(&expected.0)
+        .arg(serde_json::to_string(state)?)
+        .arg(serde_json::to_string(baseline)?)
+        .arg(serde_json::to_string(entry)?)
+        .arg(self.config.trade_log.max_entries.max(1))
+        .invoke_async(&mut conn)
+        .await?;
+        Ok(committed == 1)
+    }
+    
+    /// Record the allocation and target in force right after a rebalance
+    /// 
+    /// The Analyst compares against this to attribute later drift.
+    pub async fn set_rebalance_baseline(&self, baseline: &RebalanceBaseline) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(baseline)?;
+        conn.set::<_, _, ()>(self.key("state:rebalance_baseline"), &serialized).await?;
         Ok(())
     }
     
-    /// Get target allocation
-    pub async fn get_target_allocation(&self) -> Result<TargetAllocation> {
-        let mut conn = self.redis.clone();
-        let raw: Option<String> = conn.get("config:target_allocation").await?;
+    /// Get the allocation recorded at the last rebalance
+    pub async fn get_rebalance_baseline(&self) -> Result<Option<RebalanceBaseline>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:rebalance_baseline")).await?;
         
         match raw {
-            Some(s) => Ok(serde_json::from_str(&s)?),
-            None => Ok(TargetAllocation {
-                stocks_pct: self.config.portfolio.default_stocks_pct,
-                bonds_pct: self.config.portfolio.default_bonds_pct,
-            }),
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
         }
     }
     
-    /// Clear all pheromones (for testing/reset)
-    pub async fn clear_all(&self) -> Result<()> {
-        let mut conn = self.redis.clone();
-        for ptype in PheromoneType::ALL {
-            conn.del::<_, ()>(ptype.key()).await?;
-        }
-        
-        warn!("🧹 All pheromones cleared");
+    /// Store the janitor's latest storage report
+    pub async fn set_storage_report(&self, report: &StorageReport) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(report)?;
+        conn.set::<_, _, ()>(self.key("state:storage"), &serialized).await?;
         Ok(())
     }
     
-    /// Store agent metrics
-    pub async fn set_agent_metrics(&self, metrics: &AgentMetrics) -> Result<()> {
-        let mut conn = self.redis.clone();
-        let key = format!("agent:{}", metrics.name.to_lowercase());
-        let serialized = serde_json::to_string(metrics)?;
-        conn.set::<_, _, ()>(&key, &serialized).await?;
-        Ok(())
+    /// Get the janitor's latest storage report
+    pub async fn get_storage_report(&self) -> Result<Option<StorageReport>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:storage")).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
     }
     
-    /// Get all agent metrics
-    pub async fn get_all_agent_metrics(&self) -> Result<Vec<AgentMetrics>> {
-        let mut conn = self.redis.clone();
-        let agent_names = ["sensor", "analyst", "guardian", "trader"];
-        let mut metrics = Vec::new();
+    /// Full Redis keys in this swarm's namespace matching `pattern`
+    pub async fn matching_keys(&self, pattern: &str) -> Result<Vec<String>> {
+        let mut conn = self.conn();
+        let mut iter = conn.scan_match::<_, String>(self.key(pattern)).await?;
+        let mut keys = Vec::new();
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        Ok(keys)
+    }
+    
+    /// Cap a newest-first list (full key) at `max_entries` and drop entries
+    /// older than `cutoff` off its tail; returns how many were removed
+    pub async fn trim_list(&self, key: &str, max_entries: usize, cutoff: Option<DateTime<Utc>>) -> Result<usize> {
+        const PAGE: isize = 100;
+        let mut conn = self.conn();
+        let before: usize = conn.llen(key).await?;
+        conn.ltrim::<_, ()>(key, 0, max_entries.max(1) as isize - 1).await?;
         
-        for name in agent_names {
-            let key = format!("agent:{}", name);
-            let raw: Option<String> = conn.get(&key).await?;
-            if let Some(serialized) = raw {
-                if let Ok(m) = serde_json::from_str::<AgentMetrics>(&serialized) {
-                    metrics.push(m);
+        if let Some(cutoff) = cutoff {
+            loop {
+                let page: Vec<String> = conn.lrange(key, -PAGE, -1).await?;
+                let expired = retention::expired_tail(&page, cutoff);
+                if expired == 0 {
+                    break;
+                }
+                // Counted from the tail, so entries pushed meanwhile are kept
+                conn.ltrim::<_, ()>(key, 0, -(expired as isize) - 1).await?;
+                if expired < page.len() {
+                    break;
                 }
             }
         }
         
-        Ok(metrics)
+        let after: usize = conn.llen(key).await?;
+        Ok(before.saturating_sub(after))
     }
     
-    /// Log a trade to persistent history (FIFO, capped at max_entries)
-    pub async fn log_trade(&self, entry: &TradeLogEntry) -> Result<()> {
-        let mut conn = self.redis.clone();
-        let serialized = serde_json::to_string(entry)?;
-        
-        // Push to the front of the list
-        conn.lpush::<_, _, ()>("trade_log", &serialized).await?;
-        
-        // Trim to max entries
-        let max = self.config.trade_log.max_entries as isize;
-        conn.ltrim::<_, ()>("trade_log", 0_isize, max - 1).await?;
-        
-        debug!("📝 Trade logged: {} {} {}", entry.action, entry.symbol, entry.amount);
-        Ok(())
+    /// Delete daily snapshots and summaries dated before `cutoff`; returns how many
+    pub async fn delete_daily_snapshots_before(&self, cutoff: chrono::NaiveDate) -> Result<usize> {
+        let mut keys = self.matching_keys("snapshots:daily:*").await?;
+        keys.extend(self.matching_keys("summaries:daily:*").await?);
+        let expired: Vec<String> = keys
+            .into_iter()
+            .filter(|key| retention::snapshot_expired(key, cutoff))
+            .collect();
+        if expired.is_empty() {
+            return Ok(0);
+        }
+        let mut conn = self.conn();
+        Ok(conn.del::<_, usize>(&expired).await?)
     }
     
-    /// Get trade history (most recent first)
-    pub async fn get_trade_history(&self, count: usize) -> Result<Vec<TradeLogEntry>> {
-        let mut conn = self.redis.clone();
-        let raw: Vec<String> = conn.lrange("trade_log", 0_isize, (count as isize) - 1).await?;
+    /// Number of keys in this swarm's namespace and their total `MEMORY
+    /// USAGE` in bytes. The default swarm has no prefix, so keys of the other
+    /// registered swarms and the shared federation registry are left out.
+    pub async fn memory_usage(&self) -> Result<(usize, u64)> {
+        let others: Vec<String> = if self.namespace.is_empty() {
+            self.get_swarm_heartbeats()
+                .await?
+                .into_iter()
+                .filter(|heartbeat| heartbeat.name != self.config.swarm.name)
+                .map(|heartbeat| format!("{}:", heartbeat.name))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let keys: Vec<String> = self
+            .matching_keys("*")
+            .await?
+            .into_iter()
+            .filter(|key| key != federation::FEDERATION_KEY && !others.iter().any(|prefix| key.starts_with(prefix)))
+            .collect();
+        
+        let mut conn = self.conn();
+        let mut bytes = 0;
+        for chunk in keys.chunks(500) {
+            let mut pipe = redis::pipe();
+            for key in chunk {
+                pipe.cmd("MEMORY").arg("USAGE").arg(key);
+            }
+            let usage: Vec<Option<u64>> = pipe.query_async(&mut conn).await?;
+            bytes += usage.into_iter().flatten().sum::<u64>();
+        }
+        Ok((keys.len(), bytes))
+    }
+    
+    /// Store the latest position reconciliation report
+    pub async fn set_reconciliation(&self, report: &ReconciliationReport) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(report)?;
+        conn.set::<_, _, ()>(self.key("state:reconciliation"), &serialized).await?;
+        Ok(())
+    }
+    
+    /// Get the latest position reconciliation report
+    pub async fn get_reconciliation(&self) -> Result<Option<ReconciliationReport>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:reconciliation")).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Store the decision timeline of a trade, expiring with the trade log
+    /// when it is aged out
+    pub async fn set_trade_timeline(&self, timeline: &TradeTimeline) -> Result<()> {
+        let mut conn = self.conn();
+        let key = self.key(&format!("trades:timeline:{}", timeline.trade_id));
+        let serialized = serde_json::to_string(timeline)?;
+        let retention = &self.config.retention;
+        if retention.enabled && retention.trade_log_max_age_days > 0 {
+            let ttl = u64::from(retention.trade_log_max_age_days) * 86_400;
+            conn.set_ex::<_, _, ()>(&key, &serialized, ttl).await?;
+        } else {
+            conn.set::<_, _, ()>(&key, &serialized).await?;
+        }
+        Ok(())
+    }
+    
+    /// Get a trade's decision timeline by trade ID
+    pub async fn get_trade_timeline(&self, trade_id: &str) -> Result<Option<TradeTimeline>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key(&format!("trades:timeline:{}", trade_id))).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Store the notification state of every alert rule, by rule name
+    pub async fn set_alert_states(&self, states: &BTreeMap<String, RuleState>) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(states)?;
+        conn.set::<_, _, ()>(self.key("state:alerts"), &serialized).await?;
+        Ok(())
+    }
+    
+    /// Get the notification state of every alert rule (empty before the first check)
+    pub async fn get_alert_states(&self) -> Result<BTreeMap<String, RuleState>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:alerts")).await?;
+        
+        match raw {
+            Some(s) => Ok(serde_json::from_str(&s)?),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+    
+    /// Store the runtime log level overrides, by agent name or target
+    pub async fn set_log_levels(&self, levels: &BTreeMap<String, String>) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(levels)?;
+        conn.set::<_, _, ()>(self.key("state:log_levels"), &serialized).await?;
+        Ok(())
+    }
+    
+    /// Get the runtime log level overrides (empty when none are set)
+    pub async fn get_log_levels(&self) -> Result<BTreeMap<String, String>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:log_levels")).await?;
+        
+        match raw {
+            Some(s) => Ok(serde_json::from_str(&s)?),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+    
+    /// Store holdings imported from a broker export
+    pub async fn set_holdings(&self, holdings: &ImportedHoldings) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(holdings)?;
+        conn.set::<_, _, ()>(self.key("state:holdings"), &serialized).await?;
+        Ok(())
+    }
+    
+    /// Get imported holdings, if any
+    pub async fn get_holdings(&self) -> Result<Option<ImportedHoldings>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:holdings")).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Forget imported holdings
+    pub async fn clear_holdings(&self) -> Result<()> {
+        let mut conn = self.conn();
+        conn.del::<_, ()>(self.key("state:holdings")).await?;
+        Ok(())
+    }
+    
+    /// Store the deployment's starting point
+    pub async fn set_portfolio_init(&self, init: &PortfolioBootstrap) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(init)?;
+        conn.set::<_, _, ()>(self.key("state:portfolio_init"), &serialized).await?;
+        Ok(())
+    }
+    
+    /// Get the deployment's starting point, if one was set
+    pub async fn get_portfolio_init(&self) -> Result<Option<PortfolioBootstrap>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:portfolio_init")).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Add a run to the registry (`run:{id}`, and the head of `runs`)
+    pub async fn register_run(&self, run: &RunMetadata) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(run)?;
+        conn.set::<_, _, ()>(self.key(&format!("run:{}", run.id)), &serialized).await?;
+        conn.lpush::<_, _, ()>(self.key("runs"), &serialized).await?;
+        Ok(())
+    }
+    
+    /// Get a registered run
+    pub async fn get_run(&self, id: &str) -> Result<Option<RunMetadata>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key(&format!("run:{}", id))).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Get `count` registered runs starting at `offset` (most recent first)
+    pub async fn get_runs_page(&self, offset: usize, count: usize) -> Result<Vec<RunMetadata>> {
+        self.get_list_page("runs", offset, count).await
+    }
+    
+    /// Make a run the swarm's current one
+    pub async fn set_current_run(&self, run: &RunMetadata) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(run)?;
+        conn.set::<_, _, ()>(self.key("state:run"), &serialized).await?;
+        Ok(())
+    }
+    
+    /// Get the swarm's current run, if one has started
+    pub async fn get_current_run(&self) -> Result<Option<RunMetadata>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:run")).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Store the shadow-mode comparison totals
+    pub async fn set_shadow_report(&self, report: &ShadowReport) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(report)?;
+        conn.set::<_, _, ()>(self.key("state:shadow"), &serialized).await?;
+        Ok(())
+    }
+    
+    /// Get the shadow-mode comparison totals, if shadow mode has run
+    pub async fn get_shadow_report(&self) -> Result<Option<ShadowReport>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:shadow")).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Record a live/shadow divergence, keeping the newest `max_len`
+    pub async fn push_shadow_divergence(&self, divergence: &Divergence, max_len: usize) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(divergence)?;
+        conn.lpush::<_, _, ()>(self.key("shadow:divergences"), &serialized).await?;
+        conn.ltrim::<_, ()>(self.key("shadow:divergences"), 0, max_len.max(1) as isize - 1).await?;
+        Ok(())
+    }
+    
+    /// Get the `count` most recent live/shadow divergences
+    pub async fn get_shadow_divergences(&self, count: usize) -> Result<Vec<Divergence>> {
+        self.get_list_page("shadow:divergences", 0, count).await
+    }
+    
+    /// Remember a filled leg for the wash-sale guard, keeping the newest `max_len`
+    pub async fn push_recent_fill(&self, fill: &RecentFill, max_len: usize) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(fill)?;
+        conn.lpush::<_, _, ()>(self.key("wash_sale:fills"), &serialized).await?;
+        conn.ltrim::<_, ()>(self.key("wash_sale:fills"), 0, max_len.max(1) as isize - 1).await?;
+        Ok(())
+    }
+    
+    /// Get the `count` most recent filled legs, newest first
+    pub async fn get_recent_fills(&self, count: usize) -> Result<Vec<RecentFill>> {
+        self.get_list_page("wash_sale:fills", 0, count).await
+    }
+    
+    /// Record a leg the command queue gave up on, keeping the newest `max_len`
+    pub async fn push_dead_letter(&self, letter: &DeadLetter, max_len: usize) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(letter)?;
+        conn.lpush::<_, _, ()>(self.key("orders:dead_letter"), &serialized).await?;
+        conn.ltrim::<_, ()>(self.key("orders:dead_letter"), 0, max_len.max(1) as isize - 1).await?;
+        Ok(())
+    }
+    
+    /// Get the `count` most recent dead-lettered legs, newest first
+    pub async fn get_dead_letters(&self, count: usize) -> Result<Vec<DeadLetter>> {
+        self.get_list_page("orders:dead_letter", 0, count).await
+    }
+    
+    /// Queue a notification, or store its next attempt
+    pub async fn put_notification(&self, notification: &Notification) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(notification)?;
+        conn.hset::<_, _, _, ()>(self.key("notify:queue"), &notification.id, &serialized).await?;
+        Ok(())
+    }
+    
+    /// Notifications waiting for delivery, oldest first
+    pub async fn get_notifications(&self) -> Result<Vec<Notification>> {
+        let mut conn = self.conn();
+        let raw: Vec<String> = conn.hvals(self.key("notify:queue")).await?;
+        let mut notifications: Vec<Notification> = raw.iter().filter_map(|s| serde_json::from_str(s).ok()).collect();
+        notifications.sort_by_key(|n| n.created_at);
+        Ok(notifications)
+    }
+    
+    pub async fn remove_notification(&self, id: &str) -> Result<()> {
+        let mut conn = self.conn();
+        conn.hdel::<_, _, ()>(self.key("notify:queue"), id).await?;
+        Ok(())
+    }
+    
+    pub async fn push_notify_dead_letter(&self, letter: &NotifyDeadLetter, max_len: usize) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(letter)?;
+        conn.lpush::<_, _, ()>(self.key("notify:dead_letter"), &serialized).await?;
+        conn.ltrim::<_, ()>(self.key("notify:dead_letter"), 0, max_len.max(1) as isize - 1).await?;
+        Ok(())
+    }
+    
+    /// Get the `count` most recent notifications given up on, newest first
+    pub async fn get_notify_dead_letters(&self, count: usize) -> Result<Vec<NotifyDeadLetter>> {
+        self.get_list_page("notify:dead_letter", 0, count).await
+    }
+    
+    /// Queue an order for the execution window
+    pub async fn put_pending_order(&self, order: &PendingOrder) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(order)?;
+        conn.hset::<_, _, _, ()>(self.key("orders:pending"), &order.id, &serialized).await?;
+        Ok(())
+    }
+    
+    /// Orders waiting for the execution window, oldest first
+    pub async fn get_pending_orders(&self) -> Result<Vec<PendingOrder>> {
+        let mut conn = self.conn();
+        let raw: Vec<String> = conn.hvals(self.key("orders:pending")).await?;
+        
+        let mut orders: Vec<PendingOrder> = raw
+            .iter()
+            .filter_map(|s| serde_json::from_str(s).ok())
+            .collect();
+        orders.sort_by(|a, b| a.queued_at.cmp(&b.queued_at));
+        Ok(orders)
+    }
+    
+    /// Remove a queued order; returns false if it was already gone, so
+    /// only one Trader takes it when several share the blackboard
+    pub async fn take_pending_order(&self, id: &str) -> Result<bool> {
+        let mut conn = self.conn();
+        let removed: usize = conn.hdel(self.key("orders:pending"), id).await?;
+        Ok(removed > 0)
+    }
+    
+    /// Store a daily snapshot; returns false if that day already has one
+    pub async fn put_daily_snapshot(&self, snapshot: &DailySnapshot) -> Result<bool> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(snapshot)?;
+        let created: bool = conn.set_nx(self.key(&format!("snapshots:daily:{}", snapshot.date)), &serialized).await?;
+        Ok(created)
+    }
+    
+    /// Get the daily snapshots that exist for `dates`, in order
+    pub async fn get_daily_snapshots(&self, dates: &[chrono::NaiveDate]) -> Result<Vec<DailySnapshot>> {
+        if dates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.conn();
+        let keys: Vec<String> = dates.iter().map(|d| self.key(&format!("snapshots:daily:{}", d))).collect();
+        let raw: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?;
+        Ok(raw.iter().flatten().filter_map(|s| serde_json::from_str(s).ok()).collect())
+    }
+    
+    /// Store a day's summary and make it the latest; returns false if that
+    /// day already has one
+    pub async fn put_daily_summary(&self, summary: &DailySummary) -> Result<bool> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(summary)?;
+        let created: bool = conn.set_nx(self.key(&format!("summaries:daily:{}", summary.date)), &serialized).await?;
+        if created {
+            conn.set::<_, _, ()>(self.key("state:daily_summary"), &serialized).await?;
+        }
+        Ok(created)
+    }
+    
+    /// Get the daily summaries that exist for `dates`, in order
+    pub async fn get_daily_summaries(&self, dates: &[chrono::NaiveDate]) -> Result<Vec<DailySummary>> {
+        if dates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.conn();
+        let keys: Vec<String> = dates.iter().map(|d| self.key(&format!("summaries:daily:{}", d))).collect();
+        let raw: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?;
+        Ok(raw.iter().flatten().filter_map(|s| serde_json::from_str(s).ok()).collect())
+    }
+    
+    /// Get the most recently consolidated daily summary
+    pub async fn get_latest_daily_summary(&self) -> Result<Option<DailySummary>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:daily_summary")).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Store a month's statement (JSON and rendered text); returns false
+    /// if that month already has one
+    pub async fn put_statement(&self, statement: &MonthlyStatement, text: &str) -> Result<bool> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(statement)?;
+        let created: bool = conn.set_nx(self.key(&format!("statements:{}:json", statement.month)), &serialized).await?;
+        if created {
+            conn.set::<_, _, ()>(self.key(&format!("statements:{}:txt", statement.month)), text).await?;
+            conn.sadd::<_, _, ()>(self.key("statements:index"), &statement.month).await?;
+        }
+        Ok(created)
+    }
+    
+    /// Get a month's statement (`YYYY-MM`)
+    pub async fn get_statement(&self, month: &str) -> Result<Option<MonthlyStatement>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key(&format!("statements:{}:json", month))).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Get a month's rendered statement text
+    pub async fn get_statement_text(&self, month: &str) -> Result<Option<String>> {
+        let mut conn = self.conn();
+        Ok(conn.get(self.key(&format!("statements:{}:txt", month))).await?)
+    }
+    
+    /// Months with a stored statement, oldest first
+    pub async fn list_statements(&self) -> Result<Vec<String>> {
+        let mut conn = self.conn();
+        let mut months: Vec<String> = conn.smembers(self.key("statements:index")).await?;
+        months.sort();
+        Ok(months)
+    }
+    
+    /// Store the benchmark ghost portfolios
+    pub async fn set_benchmarks(&self, benchmarks: &BenchmarkSet) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(benchmarks)?;
+        conn.set::<_, _, ()>(self.key("state:benchmarks"), &serialized).await?;
+        Ok(())
+    }
+    
+    /// Get the benchmark ghost portfolios
+    pub async fn get_benchmarks(&self) -> Result<Option<BenchmarkSet>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:benchmarks")).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Store the high-water mark and drawdown
+    pub async fn set_drawdown(&self, drawdown: &DrawdownState) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(drawdown)?;
+        conn.set::<_, _, ()>(self.key("state:drawdown"), &serialized).await?;
+        Ok(())
+    }
+    
+    /// Get the high-water mark and drawdown
+    pub async fn get_drawdown(&self) -> Result<Option<DrawdownState>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:drawdown")).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Record the calendar period a scheduled rebalance last fired for
+    pub async fn set_last_calendar_rebalance(&self, period: &str) -> Result<()> {
+        let mut conn = self.conn();
+        conn.set::<_, _, ()>(self.key("state:calendar_rebalance"), period).await?;
+        Ok(())
+    }
+    
+    /// Get the calendar period a scheduled rebalance last fired for
+    pub async fn get_last_calendar_rebalance(&self) -> Result<Option<String>> {
+        let mut conn = self.conn();
+        Ok(conn.get(self.key("state:calendar_rebalance")).await?)
+    }
+    
+    /// Append a live price sample, keeping the newest `max_len`
+    pub async fn push_price_sample(&self, symbol: &str, sample: &PriceSample, max_len: usize) -> Result<()> {
+        let mut conn = self.conn();
+        let key = self.key(&format!("history:price:{}", symbol));
+        let serialized = serde_json::to_string(sample)?;
+        conn.lpush::<_, _, ()>(&key, &serialized).await?;
+        conn.ltrim::<_, ()>(&key, 0, max_len.max(1) as isize - 1).await?;
+        Ok(())
+    }
+    
+    /// Get recent live price samples, oldest first
+    pub async fn get_price_samples(&self, symbol: &str) -> Result<Vec<PriceSample>> {
+        let mut conn = self.conn();
+        let raw: Vec<String> = conn.lrange(self.key(&format!("history:price:{}", symbol)), 0, -1).await?;
+        
+        let mut samples: Vec<PriceSample> = raw
+            .iter()
+            .filter_map(|s| serde_json::from_str(s).ok())
+            .collect();
+        samples.reverse();
+        Ok(samples)
+    }
+    
+    /// Check the store answers
+    pub async fn ping(&self) -> Result<()> {
+        let mut conn = self.conn();
+        redis::cmd("PING").query_async::<_, String>(&mut conn).await?;
+        Ok(())
+    }
+    
+    pub async fn put_system_status(&self, status: &SystemStatus) -> Result<()> {
+        let mut conn = self.conn();
+        conn.set::<_, _, ()>(self.key("system:mode"), serde_json::to_string(status)?).await?;
+        Ok(())
+    }
+    
+    /// The system mode as last derived, if the monitor has run
+    pub async fn get_system_status(&self) -> Result<Option<SystemStatus>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("system:mode")).await?;
+        Ok(raw.and_then(|s| serde_json::from_str(&s).ok()))
+    }
+    
+    /// Engage the kill switch, or release it with `None`
+    pub async fn set_kill_switch(&self, switch: Option<&KillSwitch>) -> Result<()> {
+        let mut conn = self.conn();
+        let key = self.key("control:kill_switch");
+        match switch {
+            Some(switch) => conn.set::<_, _, ()>(key, serde_json::to_string(switch)?).await?,
+            None => conn.del::<_, ()>(key).await?,
+        }
+        Ok(())
+    }
+    
+    pub async fn get_kill_switch(&self) -> Result<Option<KillSwitch>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("control:kill_switch")).await?;
+        Ok(raw.and_then(|s| serde_json::from_str(&s).ok()))
+    }
+    
+    pub async fn put_sim_clock(&self, state: &ClockState) -> Result<()> {
+        let mut conn = self.conn();
+        conn.set::<_, _, ()>(self.key("sim:clock"), serde_json::to_string(state)?).await?;
+        Ok(())
+    }
+    
+    /// The simulated clock replays follow, once started
+    pub async fn get_sim_clock(&self) -> Result<Option<ClockState>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("sim:clock")).await?;
+        Ok(raw.and_then(|s| serde_json::from_str(&s).ok()))
+    }
+    
+    pub async fn put_composite_risk_index(&self, index: &CompositeRiskIndex) -> Result<()> {
+        let mut conn = self.conn();
+        conn.set::<_, _, ()>(self.key("risk:composite_index"), serde_json::to_string(index)?).await?;
+        Ok(())
+    }
+    
+    /// The composite risk index as last computed
+    pub async fn get_composite_risk_index(&self) -> Result<Option<CompositeRiskIndex>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("risk:composite_index")).await?;
+        Ok(raw.and_then(|s| serde_json::from_str(&s).ok()))
+    }
+    
+    /// Store the latest correlation reading and append it to the series
+    pub async fn put_correlation(&self, reading: &CorrelationReading, max_len: usize) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(reading)?;
+        let history = self.key("risk:correlation:history");
+        let mut pipe = redis::pipe();
+        pipe.set(self.key("risk:correlation"), &serialized).ignore();
+        pipe.lpush(&history, &serialized).ignore();
+        pipe.ltrim(&history, 0, max_len.max(1) as isize - 1).ignore();
+        pipe.query_async::<_, ()>(&mut conn).await?;
+        Ok(())
+    }
+    
+    /// The correlation reading as last computed
+    pub async fn get_correlation(&self) -> Result<Option<CorrelationReading>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("risk:correlation")).await?;
+        Ok(raw.and_then(|s| serde_json::from_str(&s).ok()))
+    }
+    
+    /// The `count` most recent correlation readings, newest first
+    pub async fn get_correlation_history(&self, count: usize) -> Result<Vec<CorrelationReading>> {
+        self.get_list_page("risk:correlation:history", 0, count).await
+    }
+    
+    /// Cache a symbol's daily closes
+    pub async fn put_daily_history(&self, history: &DailyHistory) -> Result<()> {
+        let mut conn = self.conn();
+        conn.hset::<_, _, _, ()>(self.key("history:daily"), &history.symbol, serde_json::to_string(history)?).await?;
+        Ok(())
+    }
+    
+    /// A symbol's cached daily closes, if loaded
+    pub async fn get_daily_history(&self, symbol: &str) -> Result<Option<DailyHistory>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.hget(self.key("history:daily"), symbol).await?;
+        Ok(raw.and_then(|s| serde_json::from_str(&s).ok()))
+    }
+    
+    /// Record when the Sensor published a snapshot, keeping the newest `max_len`
+    pub async fn record_sensor_snapshot(&self, at: DateTime<Utc>, max_len: usize) -> Result<()> {
+        let mut conn = self.conn();
+        let key = self.key("history:snapshots");
+        conn.lpush::<_, _, ()>(&key, at.to_rfc3339()).await?;
+        conn.ltrim::<_, ()>(&key, 0, max_len.max(1) as isize - 1).await?;
+        Ok(())
+    }
+    
+    /// Get when the Sensor published its recent snapshots, newest first
+    pub async fn get_sensor_snapshot_times(&self) -> Result<Vec<DateTime<Utc>>> {
+        let mut conn = self.conn();
+        let raw: Vec<String> = conn.lrange(self.key("history:snapshots"), 0, -1).await?;
+        Ok(raw
+            .iter()
+            .filter_map(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|at| at.with_timezone(&Utc))
+            .collect())
+    }
+    
+    /// Get the most recent `count` pheromone deposits (most recent first)
+    pub async fn get_pheromone_trail(&self, count: usize) -> Result<Vec<TrailEntry>> {
+        self.get_list_page("history:pheromones", 0, count).await
+    }
+    
+    /// Store the latest market quotes (for dashboard display)
+    pub async fn set_market_update(&self, update: &MarketUpdate) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(update)?;
+        conn.set::<_, _, ()>(self.key("state:market"), &serialized).await?;
+        Ok(())
+    }
+    
+    /// Get the latest market quotes
+    pub async fn get_market_update(&self) -> Result<Option<MarketUpdate>> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("state:market")).await?;
+        
+        match raw {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Store target allocation (from UI)
+    pub async fn set_target_allocation(&self, stocks_pct: f64, bonds_pct: f64) -> Result<()> {
+        let mut conn = self.conn();
+        let allocation = TargetAllocation { stocks_pct, bonds_pct };
+        let serialized = serde_json::to_string(&allocation)?;
+        conn.set::<_, _, ()>(self.key("config:target_allocation"), &serialized).await?;
+        info!("Target allocation updated: {}% stocks, {}% bonds", stocks_pct, bonds_pct);
+        Ok(())
+    }
+    
+    /// Get target allocation
+    pub async fn get_target_allocation(&self) -> Result<TargetAllocation> {
+        let mut conn = self.conn();
+        let raw: Option<String> = conn.get(self.key("config:target_allocation")).await?;
+        
+        match raw {
+            Some(s) => Ok(serde_json::from_str(&s)?),
+            None => Ok(self.default_target_allocation()),
+        }
+    }
+    
+    /// The configured default until a target is set
+    fn default_target_allocation(&self) -> TargetAllocation {
+        TargetAllocation {
+            stocks_pct: self.config.portfolio.default_stocks_pct,
+            bonds_pct: self.config.portfolio.default_bonds_pct,
+        }
+    }
+    
+    /// Clear all pheromones (for testing/reset)
+    pub async fn clear_all(&self) -> Result<()> {
+        let mut conn = self.conn();
+        for ptype in PheromoneType::ALL {
+            conn.del::<_, ()>(&[self.key(ptype.key()), self.key(&ptype.recent_key())]).await?;
+        }
+        
+        warn!("🧹 All pheromones cleared");
+        Ok(())
+    }
+    
+    /// Delete trade log, histories, snapshots, statements, agent activity and
+    /// derived state (hard reset); returns the number of keys removed
+    pub async fn clear_history(&self) -> Result<usize> {
+        let mut keys: Vec<String> = [
+            "trade_log",
+            "history:portfolio",
+            "statements:index",
+            "state:reconciliation",
+            "state:calendar_rebalance",
+            "wash_sale:fills",
+            "history:cash_flows",
+            "orders:pending",
+            "orders:dead_letter",
+            "notify:queue",
+            "notify:dead_letter",
+            "history:snapshots",
+            "state:storage",
+            "history:pheromones",
+            "state:alerts",
+            "state:daily_summary",
+        ]
+        .iter()
+        .map(|key| self.key(key))
+        .collect();
+        
+        for pattern in ["history:price:*", "snapshots:daily:*", "summaries:daily:*", "statements:*", "agent:*:activity", "lock:permit:*", "trades:timeline:*"] {
+            let mut conn = self.conn();
+            let mut iter = conn.scan_match::<_, String>(self.key(pattern)).await?;
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+        }
+        
+        let mut conn = self.conn();
+        let mut removed = 0;
+        for chunk in keys.chunks(500) {
+            removed += conn.del::<_, usize>(chunk).await?;
+        }
+        
+        warn!("🧹 History cleared ({} keys)", removed);
+        Ok(removed)
+    }
+    
+    /// Report agent metrics: queued for the metrics writer when it runs
+    /// (dropped if its queue is full), otherwise written at once
+    pub async fn set_agent_metrics(&self, metrics: &AgentMetrics) -> Result<()> {
+        let Some(tx) = self.metrics_tx.get() else {
+            return self.write_agent_metrics(std::slice::from_ref(metrics)).await;
+        };
+        match tx.try_send(metrics.clone()) {
+            Ok(()) => Ok(()),
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                debug!("📊 Metrics writer busy, dropped {} metrics", metrics.name);
+                Ok(())
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                self.write_agent_metrics(std::slice::from_ref(metrics)).await
+            }
+        }
+    }
+    
+    /// Store agents' metrics and append them to their activity streams
+    /// (skipped when nothing changed since the last entry), in one round trip
+    pub async fn write_agent_metrics(&self, batch: &[AgentMetrics]) -> Result<()> {
+        const APPEND_ACTIVITY: &str = r"local head = redis.call('LINDEX', KEYS[1], 0)
+              if head then
+                local last = cjson.decode(head)
+                if last.action == ARGV[2] and last.action_count == tonumber(ARGV[3]) then return 0 end
+              end
+              redis.call('LPUSH', KEYS[1], ARGV[1])
+              redis.call('LTRIM', KEYS[1], 0, tonumber(ARGV[4]) - 1)
+              return 1";
+        
+        let now = chrono::Utc::now();
+        let mut pipe = redis::pipe();
+        for metrics in batch {
+            let key = self.key(&format!("agent:{}", metrics.name.to_lowercase()));
+            pipe.set(&key, serde_json::to_string(metrics)?).ignore();
+            
+            let activity = AgentActivity::from_metrics(metrics, now);
+            pipe.cmd("EVAL")
+                .arg(APPEND_ACTIVITY)
+                .arg(1)
+                .arg(format!("{}:activity", key))
+                .arg(serde_json::to_string(&activity)?)
+                .arg(&activity.action)
+                .arg(activity.action_count)
+                .arg(self.config.agent.activity_max_entries.max(1))
+                .ignore();
+        }
+        
+        let mut conn = self.conn();
+        pipe.query_async::<_, ()>(&mut conn).await?;
+        Ok(())
+    }
+    
+    /// Get an agent's most recent `count` activity entries (most recent first)
+    pub async fn get_agent_activity(&self, name: &str, count: usize) -> Result<Vec<AgentActivity>> {
+        self.get_list_page(&format!("agent:{}:activity", name.to_lowercase()), 0, count).await
+    }
+    
+    /// Store agents' latest runtime reports
+    pub async fn put_agent_runtime(&self, reports: &[AgentRuntime]) -> Result<()> {
+        let mut pipe = redis::pipe();
+        for report in reports {
+            pipe.hset(self.key("agent_runtime"), &report.name, serde_json::to_string(report)?).ignore();
+        }
+        let mut conn = self.conn();
+        pipe.query_async::<_, ()>(&mut conn).await?;
+        Ok(())
+    }
+    
+    /// Latest runtime report of every agent that has reported, by name
+    pub async fn get_agent_runtime(&self) -> Result<Vec<AgentRuntime>> {
+        let mut conn = self.conn();
+        let raw: Vec<String> = conn.hvals(self.key("agent_runtime")).await?;
+        
+        let mut reports: Vec<AgentRuntime> = raw
+            .iter()
+            .filter_map(|s| serde_json::from_str(s).ok())
+            .collect();
+        reports.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(reports)
+    }
+    
+    /// Metrics of every agent that has reported, in pipeline order
+    pub async fn get_all_agent_metrics(&self) -> Result<Vec<AgentMetrics>> {
+        let mut conn = self.conn();
+        let mut metrics = Vec::new();
+        
+        for role in AgentRole::ALL {
+            let key = self.key(&format!("agent:{}", role.label().to_lowercase()));
+            let raw: Option<String> = conn.get(&key).await?;
+            if let Some(serialized) = raw {
+                if let Ok(m) = serde_json::from_str::<AgentMetrics>(&serialized) {
+                    metrics.push(m);
+                }
+            }
+        }
+        
+        Ok(metrics)
+    }
+    
+    /// Log a trade to persistent history (FIFO, capped at max_entries)
+    pub async fn log_trade(&self, entry: &TradeLogEntry) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(entry)?;
+        
+        // Push to the front of the list
+        conn.lpush::<_, _, ()>(self.key("trade_log"), &serialized).await?;
+        
+        // Trim to max entries
+        let max = self.config.trade_log.max_entries as isize;
+        conn.ltrim::<_, ()>(self.key("trade_log"), 0_isize, max - 1).await?;
+        
+        debug!("📝 Trade logged: {} {} {}", entry.action, entry.symbol, entry.amount);
+        Ok(())
+    }
+    
+    /// Get `count` trade log entries starting at `offset` (most recent first)
+    pub async fn get_trade_log_page(&self, offset: usize, count: usize) -> Result<Vec<TradeLogEntry>> {
+        self.get_list_page("trade_log", offset, count).await
+    }
+    
+    /// Append a portfolio history sample, keeping the newest `max_len`
+    pub async fn push_portfolio_history(&self, point: &PortfolioHistoryPoint, max_len: usize) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(point)?;
+        conn.lpush::<_, _, ()>(self.key("history:portfolio"), &serialized).await?;
+        conn.ltrim::<_, ()>(self.key("history:portfolio"), 0, max_len.max(1) as isize - 1).await?;
+        Ok(())
+    }
+    
+    /// Get `count` portfolio history samples starting at `offset` (most recent first)
+    pub async fn get_portfolio_history_page(&self, offset: usize, count: usize) -> Result<Vec<PortfolioHistoryPoint>> {
+        self.get_list_page("history:portfolio", offset, count).await
+    }
+    
+    /// Record money moved into or out of the portfolio, keeping the newest `max_len`
+    pub async fn push_cash_flow(&self, flow: &CashFlow, max_len: usize) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(flow)?;
+        conn.lpush::<_, _, ()>(self.key("history:cash_flows"), &serialized).await?;
+        conn.ltrim::<_, ()>(self.key("history:cash_flows"), 0, max_len.max(1) as isize - 1).await?;
+        Ok(())
+    }
+    
+    /// Get the `count` most recent cash flows, newest first
+    pub async fn get_cash_flows(&self, count: usize) -> Result<Vec<CashFlow>> {
+        self.get_list_page("history:cash_flows", 0, count).await
+    }
+    
+    /// Append a command to the audit stream (FIFO, capped at `[audit] max_entries`)
+    pub async fn log_audit(&self, entry: &AuditEntry) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(entry)?;
+        conn.lpush::<_, _, ()>(self.key("audit:commands"), &serialized).await?;
+        conn.ltrim::<_, ()>(self.key("audit:commands"), 0, self.config.audit.max_entries.max(1) as isize - 1).await?;
+        Ok(())
+    }
+    
+    /// Get `count` audit entries starting at `offset` (most recent first)
+    pub async fn get_audit_page(&self, offset: usize, count: usize) -> Result<Vec<AuditEntry>> {
+        self.get_list_page("audit:commands", offset, count).await
+    }
+    
+    async fn get_list_page<T: DeserializeOwned>(&self, key: &str, offset: usize, count: usize) -> Result<Vec<T>> {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.conn();
+        let raw: Vec<String> = conn.lrange(self.key(key), offset as isize, (offset + count) as isize - 1).await?;
+        Ok(raw.iter().filter_map(|entry| serde_json::from_str(entry).ok()).collect())
+    }
+    
+    /// Claim an execution permit for this process (SET NX), so that only
+    /// one Trader acts on it when several run against the same blackboard
+    pub async fn claim_permit(&self, permit_timestamp: &str) -> Result<bool> {
+        let mut conn = self.conn();
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(self.key(&format!("lock:permit:{}", permit_timestamp)))
+            .arg(std::process::id())
+            .arg("NX")
+            .arg("EX")
+            .arg(PERMIT_CLAIM_TTL_SECS)
+            .query_async(&mut conn)
+            .await?;
+        Ok(claimed.is_some())
+    }
+    
+    /// Publish this swarm's heartbeat to the federation registry (shared by all swarms)
+    pub async fn put_swarm_heartbeat(&self, heartbeat: &SwarmHeartbeat) -> Result<()> {
+        let mut conn = self.conn();
+        let serialized = serde_json::to_string(heartbeat)?;
+        conn.hset::<_, _, _, ()>(federation::FEDERATION_KEY, &heartbeat.name, &serialized).await?;
+        Ok(())
+    }
+    
+    /// Remove a swarm from the federation registry
+    pub async fn remove_swarm_heartbeat(&self, name: &str) -> Result<()> {
+        let mut conn = self.conn();
+        conn.hdel::<_, _, ()>(federation::FEDERATION_KEY, name).await?;
+        Ok(())
+    }
+    
+    /// Heartbeats of every swarm registered on this Redis, by name
+    pub async fn get_swarm_heartbeats(&self) -> Result<Vec<SwarmHeartbeat>> {
+        let mut conn = self.conn();
+        let raw: Vec<String> = conn.hvals(federation::FEDERATION_KEY).await?;
+        
+        let mut heartbeats: Vec<SwarmHeartbeat> = raw
+            .iter()
+            .filter_map(|s| serde_json::from_str(s).ok())
+            .collect();
+        heartbeats.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(heartbeats)
+    }
+    
+    /// Get trade history (most recent first)
+    pub async fn get_trade_history(&self, count: usize) -> Result<Vec<TradeLogEntry>> {
+        let mut conn = self.conn();
+        let raw: Vec<String> = conn.lrange(self.key("trade_log"), 0_isize, (count as isize) - 1).await?;
         
         let mut trades = Vec::new();
         for entry in raw {
@@ -306,24 +1913,86 @@ impl Blackboard {
     }
 }
 
-/// Portfolio state stored in Redis
+/// Portfolio state stored in Redis. Values are exact decimals, stored
+/// (and sent to the dashboard) as decimal strings; states stored as JSON
+/// numbers still read.
 #[derive(Debug, Clone, Serialize, serde::Deserialize)]
 pub struct PortfolioState {
-    pub total_value: f64,
-    pub stocks_value: f64,
-    pub bonds_value: f64,
-    pub stocks_pct: f64,
-    pub bonds_pct: f64,
+    pub total_value: Decimal,
+    pub stocks_value: Decimal,
+    pub bonds_value: Decimal,
+    pub stocks_pct: Decimal,
+    pub bonds_pct: Decimal,
     pub last_trade_time: Option<String>,
 }
 
-/// Target allocation set via UI
+impl PortfolioState {
+    /// State holding the given values, with total and percentages derived
+    pub fn from_values(stocks_value: Decimal, bonds_value: Decimal, last_trade_time: Option<String>) -> Self {
+        let total_value = stocks_value + bonds_value;
+        Self {
+            total_value,
+            stocks_value,
+            bonds_value,
+            stocks_pct: money::percent(stocks_value, total_value),
+            bonds_pct: money::percent(bonds_value, total_value),
+            last_trade_time,
+        }
+    }
+}
+
+/// Latest price of one symbol, as published to the dashboard
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct MarketQuoteUpdate {
+    pub symbol: String,
+    pub price: f64,
+    /// Absolute change since the previous snapshot
+    pub change: f64,
+    /// Percentage change since the previous snapshot
+    pub change_pct: f64,
+    pub source: QuoteSource,
+    pub simulated: bool,
+}
+
+/// Latest market snapshot published by the Sensor
 #[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct MarketUpdate {
+    pub quotes: Vec<MarketQuoteUpdate>,
+    pub timestamp: String,
+    /// Health of each provider of a failover chain, in chain order
+    #[serde(default)]
+    pub providers: Vec<ProviderHealth>,
+}
+
+/// Target allocation set via UI (or the glide path scheduler)
+#[derive(Debug, Clone, PartialEq, Serialize, serde::Deserialize)]
 pub struct TargetAllocation {
     pub stocks_pct: f64,
     pub bonds_pct: f64,
 }
 
+/// Allocation snapshot taken at the last rebalance (or bootstrap)
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct RebalanceBaseline {
+    pub stocks_pct: f64,
+    pub bonds_pct: f64,
+    pub target_stocks_pct: f64,
+    pub target_bonds_pct: f64,
+    pub timestamp: String,
+}
+
+impl RebalanceBaseline {
+    pub fn new(portfolio: &PortfolioState, target: &TargetAllocation) -> Self {
+        Self {
+            stocks_pct: money::to_f64(portfolio.stocks_pct),
+            bonds_pct: money::to_f64(portfolio.bonds_pct),
+            target_stocks_pct: target.stocks_pct,
+            target_bonds_pct: target.bonds_pct,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
 /// Agent metrics for dashboard display
 #[derive(Debug, Clone, Serialize, serde::Deserialize)]
 pub struct AgentMetrics {
@@ -332,8 +2001,15 @@ pub struct AgentMetrics {
     pub action_count: u64,
     pub last_action: String,
     pub last_action_time: Option<String>,
+    /// Error behind the last action, when it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<DriftGuardError>,
 }
 
+/// Opaque version of the stored portfolio state, for compare-and-set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortfolioVersion(String);
+
 /// Persistent trade log entry
 #[derive(Debug, Clone, Serialize, serde::Deserialize)]
 pub struct TradeLogEntry {
@@ -341,22 +2017,33 @@ pub struct TradeLogEntry {
     pub timestamp: String,
     pub action: String,
     pub symbol: String,
-    pub amount: f64,
-    pub price: f64,
-    pub portfolio_value: f64,
-    pub drift_before: f64,
-    pub drift_after: f64,
+    pub amount: Decimal,
+    pub price: Decimal,
+    pub portfolio_value: Decimal,
+    pub drift_before: Decimal,
+    pub drift_after: Decimal,
+    /// Analyst's "why" for the trade
+    #[serde(default)]
+    pub explanation: Option<String>,
+    /// Filled change in stocks holdings ($; 0 for entries logged before it was recorded)
+    #[serde(default)]
+    pub stocks_delta: Decimal,
+    /// Filled change in bonds holdings ($)
+    #[serde(default)]
+    pub bonds_delta: Decimal,
+    /// Run of the Trader that executed it (none before runs were recorded)
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// Why the leg was not placed (e.g. the wash-sale guard); none for fills
+    #[serde(default)]
+    pub skipped: Option<String>,
+    /// Plan hash of the permit it was executed or skipped under
+    #[serde(default)]
+    pub plan_hash: Option<String>,
 }
 
 impl Default for PortfolioState {
     fn default() -> Self {
-        Self {
-            total_value: 100000.0,
-            stocks_value: 60000.0,
-            bonds_value: 40000.0,
-            stocks_pct: 60.0,
-            bonds_pct: 40.0,
-            last_trade_time: None,
-        }
+        Self::from_values(Decimal::from(60_000), Decimal::from(40_000), None)
     }
 }
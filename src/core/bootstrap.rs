@@ -0,0 +1,220 @@
+//! Portfolio Bootstrap
+//!
+//! Where a deployment starts from: starting cash, optional initial holdings
+//! and the base currency. It is set with `POST /api/portfolio/init` or
+//! `driftguard init` — also while the swarm is already running — and kept
+//! in `state:portfolio_init`, so restarts begin from the same point.
+//! Without one, `[portfolio] initial_balance` and `base_currency` apply.
+//! Holdings imported later with `driftguard import` take precedence until
+//! the next init.
+
+use anyhow::{bail, ensure, Result};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::core::allocation;
+use crate::core::blackboard::{PortfolioState, RebalanceBaseline};
+use crate::core::holdings::{self, Holding, ImportedHoldings};
+use crate::core::money;
+use crate::core::valuation::DrawdownState;
+use crate::core::{Blackboard, Config};
+
+/// Body of `POST /api/portfolio/init`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PortfolioInit {
+    /// Cash to start with, as a decimal string or a number; defaults to
+    /// `initial_balance` without holdings and to nothing alongside them
+    #[serde(default)]
+    pub starting_cash: Option<Decimal>,
+    /// Positions held from the start (cash counts toward bonds when
+    /// `[holdings_import] cash_as_bonds` is set)
+    #[serde(default)]
+    pub holdings: Vec<Holding>,
+    /// ISO 4217 code, e.g. "EUR" (default `[portfolio] base_currency`)
+    #[serde(default)]
+    pub base_currency: Option<String>,
+}
+
+/// The stored starting point of a deployment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioBootstrap {
+    pub starting_cash: Decimal,
+    pub base_currency: String,
+    /// Number of initial holdings (kept in `state:holdings`)
+    pub holdings: usize,
+    /// api | cli | config
+    pub source: String,
+    pub initialized_at: Option<String>,
+}
+
+impl PortfolioBootstrap {
+    /// The starting point implied by config alone
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            starting_cash: money::from_f64(config.portfolio.initial_balance),
+            base_currency: config.portfolio.base_currency.clone(),
+            holdings: 0,
+            source: "config".to_string(),
+            initialized_at: None,
+        }
+    }
+}
+
+/// Normalize an ISO 4217 currency code
+pub fn parse_currency(code: &str) -> Result<String> {
+    let code = code.trim().to_ascii_uppercase();
+    ensure!(
+        code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()),
+        "base currency must be a 3-letter ISO 4217 code, got '{}'",
+        code
+    );
+    Ok(code)
+}
+
+impl PortfolioInit {
+    /// Validate the request against config, filling in defaults
+    pub fn resolve(&self, config: &Config, source: &str) -> Result<PortfolioBootstrap> {
+        let default_cash = if self.holdings.is_empty() { money::from_f64(config.portfolio.initial_balance) } else { Decimal::ZERO };
+        let starting_cash = self.starting_cash.unwrap_or(default_cash);
+        ensure!(starting_cash >= Decimal::ZERO, "starting cash must be a non-negative number");
+        
+        for holding in &self.holdings {
+            if holding.symbol.trim().is_empty() {
+                bail!("holding without a symbol");
+            }
+            ensure!(
+                holding.market_value.is_finite() && holding.market_value >= 0.0,
+                "{}: market value must be a non-negative number",
+                holding.symbol
+            );
+        }
+        
+        let base_currency = match &self.base_currency {
+            Some(code) => parse_currency(code)?,
+            None => config.portfolio.base_currency.clone(),
+        };
+        
+        Ok(PortfolioBootstrap {
+            starting_cash,
+            base_currency,
+            holdings: self.holdings.len(),
+            source: source.to_string(),
+            initialized_at: Some(chrono::Utc::now().to_rfc3339()),
+        })
+    }
+}
+
+/// The current starting point (stored, or from config)
+pub async fn current(board: &Blackboard) -> Result<PortfolioBootstrap> {
+    Ok(board
+        .get_portfolio_init()
+        .await?
+        .unwrap_or_else(|| PortfolioBootstrap::from_config(board.config())))
+}
+
+/// Store a new starting point and restart the portfolio, baseline,
+/// drawdown and benchmarks from it (trade log and history are kept)
+pub async fn initialize(
+    board: &Blackboard,
+    init: &PortfolioInit,
+    source: &str,
+) -> Result<(PortfolioBootstrap, PortfolioState)> {
+    let bootstrap = init.resolve(board.config(), source)?;
+    
+    let portfolio = if init.holdings.is_empty() {
+        let portfolio = holdings::split_balance(bootstrap.starting_cash, board.config());
+        ensure!(portfolio.total_value > Decimal::ZERO, "starting portfolio has no value");
+        board.clear_holdings().await?;
+        holdings::restart_from(board, &portfolio, "portfolio init").await?;
+        portfolio
+    } else {
+        let imported = ImportedHoldings {
+            holdings: init.holdings.clone(),
+            cash: money::to_f64(bootstrap.starting_cash),
+            source: source.to_string(),
+            imported_at: chrono::Utc::now().to_rfc3339(),
+        };
+        holdings::apply_import(board, &imported).await?
+    };
+    board.set_portfolio_init(&bootstrap).await?;
+    
+    info!(
+        "🏁 Portfolio initialized ({}): {:.2} {} with {} holdings",
+        source, portfolio.total_value, bootstrap.base_currency, bootstrap.holdings
+    );
+    Ok((bootstrap, portfolio))
+}
+
+/// Startup bootstrap: set the portfolio from the stored starting point (or
+/// imported holdings, or config) and the target to the default allocation
+pub async fn bootstrap(board: &Blackboard) -> Result<PortfolioState> {
+    let config = board.config();
+    let initial_portfolio = holdings::initial_portfolio(board).await?;
+    board.set_portfolio_state(&initial_portfolio).await?;
+    board.set_target_allocation(
+        config.portfolio.default_stocks_pct,
+        config.portfolio.default_bonds_pct,
+    ).await?;
+    let initial_target = board.get_target_allocation().await?;
+    if let Err(e) = allocation::check_asset_limits(&initial_target, &config.portfolio) {
+        warn!("Default target allocation {}. Rebalances stay blocked until the target changes.", e);
+    }
+    board.set_rebalance_baseline(&RebalanceBaseline::new(&initial_portfolio, &initial_target)).await?;
+    if board.get_drawdown().await?.is_none() {
+        board.set_drawdown(&DrawdownState::new(money::to_f64(initial_portfolio.total_value))).await?;
+    }
+    
+    info!("📊 Initial portfolio: {:.2} {} ({:.0}% stocks / {:.0}% bonds)",
+        initial_portfolio.total_value,
+        current(board).await?.base_currency,
+        initial_portfolio.stocks_pct,
+        initial_portfolio.bonds_pct
+    );
+    Ok(initial_portfolio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(symbol: &str, market_value: f64) -> Holding {
+        Holding { symbol: symbol.to_string(), quantity: None, price: None, market_value, cost_basis: None }
+    }
+
+    #[test]
+    fn test_resolve_defaults() {
+        let config = Config::default();
+        
+        let cash_only = PortfolioInit::default().resolve(&config, "api").unwrap();
+        assert_eq!(cash_only.starting_cash, money::from_f64(config.portfolio.initial_balance));
+        assert_eq!(cash_only.base_currency, "USD");
+        
+        let with_holdings = PortfolioInit {
+            holdings: vec![holding("VTI", 50_000.0)],
+            base_currency: Some(" eur ".to_string()),
+            ..Default::default()
+        };
+        let resolved = with_holdings.resolve(&config, "api").unwrap();
+        assert_eq!(resolved.starting_cash, Decimal::ZERO);
+        assert_eq!(resolved.base_currency, "EUR");
+        assert_eq!(resolved.holdings, 1);
+        
+        // Cash is read exactly, from a string or a number
+        let exact: PortfolioInit = serde_json::from_str(r#"{"starting_cash": "1000000.10"}"#).unwrap();
+        assert_eq!(exact.starting_cash, Some(Decimal::new(100_000_010, 2)));
+        let number: PortfolioInit = serde_json::from_str(r#"{"starting_cash": 1000000.10}"#).unwrap();
+        assert_eq!(number.starting_cash, exact.starting_cash);
+    }
+
+    #[test]
+    fn test_resolve_rejects_bad_input() {
+        let config = Config::default();
+        let reject = |init: PortfolioInit| init.resolve(&config, "api").is_err();
+        
+        assert!(reject(PortfolioInit { starting_cash: Some(Decimal::NEGATIVE_ONE), ..Default::default() }));
+        assert!(reject(PortfolioInit { base_currency: Some("dollars".to_string()), ..Default::default() }));
+        assert!(reject(PortfolioInit { holdings: vec![holding(" ", 10.0)], ..Default::default() }));
+        assert!(reject(PortfolioInit { holdings: vec![holding("BND", -5.0)], ..Default::default() }));
+    }
+}
@@ -0,0 +1,189 @@
+//! Rebalance Calendar
+//!
+//! Date arithmetic for calendar-driven rebalancing: which period a date
+//! falls in, and when that period's first trading day begins. Also decides
+//! trading blackouts around the session open/close and economic events.
+//! Dates, weekdays and times of day are the exchange's (`core::clock`).
+//! Trading days are approximated as weekdays (no exchange holiday calendar).
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::core::clock;
+use crate::core::config::BlackoutConfig;
+
+/// How often a calendar rebalance fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RebalanceFrequency {
+    Monthly,
+    Quarterly,
+    Annually,
+}
+
+impl RebalanceFrequency {
+    /// First calendar day of the period containing `date`
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        let month = match self {
+            Self::Monthly => date.month(),
+            Self::Quarterly => (date.month() - 1) / 3 * 3 + 1,
+            Self::Annually => 1,
+        };
+        NaiveDate::from_ymd_opt(date.year(), month, 1).unwrap_or(date)
+    }
+    
+    /// Stable identifier for the period containing `date`
+    /// (e.g. "2024-03", "2024-Q1", "2024")
+    pub fn period_id(&self, date: NaiveDate) -> String {
+        match self {
+            Self::Monthly => format!("{}-{:02}", date.year(), date.month()),
+            Self::Quarterly => format!("{}-Q{}", date.year(), (date.month() - 1) / 3 + 1),
+            Self::Annually => format!("{}", date.year()),
+        }
+    }
+}
+
+/// First weekday on or after `date`
+pub fn first_weekday_on_or_after(mut date: NaiveDate) -> NaiveDate {
+    while matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        date = date.succ_opt().unwrap_or(date);
+    }
+    date
+}
+
+/// The period a calendar rebalance is due for at `now`, if any
+/// 
+/// A period becomes due at `at` (exchange time) on its first trading day
+/// and stays due for the rest of the period, so a missed trigger still
+/// fires late.
+pub fn due_period(
+    now: DateTime<Utc>,
+    frequency: RebalanceFrequency,
+    at: NaiveTime,
+    tz: Tz,
+) -> Option<String> {
+    let today = clock::today(now, tz);
+    let first_trading_day = first_weekday_on_or_after(frequency.period_start(today));
+    let due_at = clock::at(first_trading_day, at, tz);
+    
+    (now >= due_at).then(|| frequency.period_id(today))
+}
+
+/// A dated economic event (e.g. an FOMC decision) that blacks out trading
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventDay {
+    pub date: NaiveDate,
+    pub label: String,
+}
+
+/// Parse an event calendar: one `YYYY-MM-DD [label]` per line, `#` comments
+pub fn parse_event_days(content: &str) -> Result<Vec<EventDay>> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (date, label) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .with_context(|| format!("Invalid event date '{}'", date))?;
+            let label = if label.trim().is_empty() { "economic event" } else { label.trim() };
+            Ok(EventDay { date, label: label.to_string() })
+        })
+        .collect()
+}
+
+/// Load an event calendar file
+pub fn load_event_days(path: impl AsRef<Path>) -> Result<Vec<EventDay>> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read event calendar {}", path.display()))?;
+    parse_event_days(&content)
+}
+
+/// Why trading is blacked out at `now`, if it is
+pub fn blackout_reason(now: DateTime<Utc>, config: &BlackoutConfig, events: &[EventDay], tz: Tz) -> Option<String> {
+    if !config.enabled {
+        return None;
+    }
+    
+    let today = clock::today(now, tz);
+    if let Some(event) = events.iter().find(|e| e.date == today) {
+        return Some(format!("{} day", event.label));
+    }
+    
+    if matches!(today.weekday(), Weekday::Sat | Weekday::Sun) {
+        return None;
+    }
+    let parse = |s: &str| NaiveTime::parse_from_str(s, "%H:%M").ok();
+    let (Some(open), Some(close)) = (parse(&config.session_open), parse(&config.session_close)) else {
+        return None;
+    };
+    
+    let t = clock::time_of_day(now, tz);
+    if t >= open && t < open + Duration::minutes(config.after_open_minutes as i64) {
+        Some(format!("first {} min of session", config.after_open_minutes))
+    } else if t < close && t >= close - Duration::minutes(config.before_close_minutes as i64) {
+        Some(format!("last {} min of session", config.before_close_minutes))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    #[test]
+    fn test_quarterly_first_trading_day() {
+        let at = NaiveTime::from_hms_opt(15, 0, 0).unwrap();
+        
+        // 2024-06-01 is a Saturday; Q3 starts Monday 2024-07-01
+        let before = Utc.with_ymd_and_hms(2024, 7, 1, 14, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 7, 1, 15, 30, 0).unwrap();
+        let later = Utc.with_ymd_and_hms(2024, 8, 20, 9, 0, 0).unwrap();
+        
+        assert_eq!(due_period(before, RebalanceFrequency::Quarterly, at, Tz::UTC), None);
+        assert_eq!(due_period(after, RebalanceFrequency::Quarterly, at, Tz::UTC).as_deref(), Some("2024-Q3"));
+        assert_eq!(due_period(later, RebalanceFrequency::Quarterly, at, Tz::UTC).as_deref(), Some("2024-Q3"));
+    }
+    
+    #[test]
+    fn test_period_start_skips_weekend() {
+        // 2024-06-01 is a Saturday
+        let june = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+        let start = RebalanceFrequency::Monthly.period_start(june);
+        assert_eq!(first_weekday_on_or_after(start), NaiveDate::from_ymd_opt(2024, 6, 3).unwrap());
+    }
+    
+    #[test]
+    fn test_blackout_windows() {
+        let config = BlackoutConfig { enabled: true, ..BlackoutConfig::default() };
+        let events = parse_event_days("# FOMC\n2024-07-31 FOMC\n").unwrap();
+        
+        // 2024-07-30 is a Tuesday; session 14:30–21:00 UTC
+        let at = |h, m| Utc.with_ymd_and_hms(2024, 7, 30, h, m, 0).unwrap();
+        assert!(blackout_reason(at(14, 40), &config, &events, Tz::UTC).is_some());
+        assert_eq!(blackout_reason(at(15, 0), &config, &events, Tz::UTC), None);
+        assert!(blackout_reason(at(20, 50), &config, &events, Tz::UTC).is_some());
+        
+        let fomc = Utc.with_ymd_and_hms(2024, 7, 31, 17, 0, 0).unwrap();
+        assert_eq!(blackout_reason(fomc, &config, &events, Tz::UTC).as_deref(), Some("FOMC day"));
+        
+        let disabled = BlackoutConfig::default();
+        assert_eq!(blackout_reason(fomc, &disabled, &events, Tz::UTC), None);
+        
+        // Session hours in New York time follow its daylight saving
+        let ny = chrono_tz::America::New_York;
+        let local = BlackoutConfig {
+            session_open: "09:30".to_string(),
+            session_close: "16:00".to_string(),
+            ..config
+        };
+        assert!(blackout_reason(at(13, 40), &local, &[], ny).is_some());
+        assert_eq!(blackout_reason(at(14, 40), &local, &[], ny), None);
+    }
+}
@@ -0,0 +1,98 @@
+//! Exchange Clock
+//!
+//! Session hours, blackout windows and daily jobs are wall-clock times at
+//! the exchange, which a fixed UTC time can't express: New York opens at
+//! 14:30 UTC in winter and 13:30 UTC in summer. `[exchange] timezone`
+//! names the exchange's IANA zone (e.g. "America/New_York"). Every "HH:MM"
+//! setting is read in it, and weekdays and dates are the exchange's. It
+//! defaults to UTC; the settings' older `*_utc` names are still accepted
+//! but are read in this zone like the rest. REST responses keep every timestamp in UTC and, under another zone,
+//! add its exchange-local form as a `<field>_local` sibling.
+
+use chrono::{DateTime, Duration, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// The exchange's calendar date at `now`
+pub fn today(now: DateTime<Utc>, tz: Tz) -> NaiveDate {
+    now.with_timezone(&tz).date_naive()
+}
+
+/// The exchange's wall-clock time at `now`
+pub fn time_of_day(now: DateTime<Utc>, tz: Tz) -> NaiveTime {
+    now.with_timezone(&tz).time()
+}
+
+/// The instant the exchange's clock reads `time` on `date`
+///
+/// A time repeated when clocks go back resolves to its first occurrence;
+/// one skipped when they go forward, to the instant after the gap.
+pub fn at(date: NaiveDate, time: NaiveTime, tz: Tz) -> DateTime<Utc> {
+    let local = date.and_time(time);
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(at) | LocalResult::Ambiguous(at, _) => at.with_timezone(&Utc),
+        LocalResult::None => tz
+            .from_local_datetime(&(local + Duration::hours(1)))
+            .earliest()
+            .map_or_else(|| local.and_utc(), |at| at.with_timezone(&Utc)),
+    }
+}
+
+/// Add a `<field>_local` exchange-time copy next to every RFC 3339
+/// timestamp in `value`; nothing under UTC
+pub fn localize(value: &mut serde_json::Value, tz: Tz) {
+    if tz == Tz::UTC {
+        return;
+    }
+    match value {
+        serde_json::Value::Object(map) => {
+            let local: Vec<(String, String)> = map
+                .iter()
+                .filter(|(key, _)| !map.contains_key(&format!("{}_local", key)))
+                .filter_map(|(key, field)| {
+                    let at = DateTime::parse_from_rfc3339(field.as_str()?).ok()?;
+                    Some((format!("{}_local", key), at.with_timezone(&tz).to_rfc3339()))
+                })
+                .collect();
+            for field in map.values_mut() {
+                localize(field, tz);
+            }
+            map.extend(local.into_iter().map(|(key, at)| (key, serde_json::Value::String(at))));
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|item| localize(item, tz)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exchange_time() {
+        let ny = chrono_tz::America::New_York;
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let open = NaiveTime::from_hms_opt(9, 30, 0).unwrap();
+
+        // 09:30 New York is 14:30 UTC in winter, 13:30 UTC in summer
+        assert_eq!(at(date(1, 3), open, ny), Utc.with_ymd_and_hms(2024, 1, 3, 14, 30, 0).unwrap());
+        assert_eq!(at(date(7, 30), open, ny), Utc.with_ymd_and_hms(2024, 7, 30, 13, 30, 0).unwrap());
+        // 02:30 doesn't exist on 2024-03-10; the clock reads 03:30 then
+        let gap = at(date(3, 10), NaiveTime::from_hms_opt(2, 30, 0).unwrap(), ny);
+        assert_eq!(gap, Utc.with_ymd_and_hms(2024, 3, 10, 7, 30, 0).unwrap());
+
+        // 23:00 UTC is still the 30th in New York, already the 31st in Tokyo
+        let late = Utc.with_ymd_and_hms(2024, 7, 30, 23, 0, 0).unwrap();
+        assert_eq!(today(late, ny), date(7, 30));
+        assert_eq!(today(late, chrono_tz::Asia::Tokyo), date(7, 31));
+        assert_eq!(time_of_day(late, ny), NaiveTime::from_hms_opt(19, 0, 0).unwrap());
+
+        let mut body = serde_json::json!({
+            "generated_at": "2024-07-30T13:30:00+00:00",
+            "orders": [{ "queued_at": "2024-07-30T14:00:00+00:00", "date": "2024-07-30" }],
+        });
+        localize(&mut body, ny);
+        assert_eq!(body["generated_at_local"], "2024-07-30T09:30:00-04:00");
+        assert_eq!(body["orders"][0]["queued_at_local"], "2024-07-30T10:00:00-04:00");
+        assert!(body["orders"][0].get("date_local").is_none());
+    }
+}
@@ -2,10 +2,31 @@
 //! 
 //! Loads settings from config.toml including pheromone decay rates,
 //! portfolio allocations, and market data parameters.
+//!
+//! A profile (`--profile live`) layers `profiles/<name>.toml`, next to the
+//! base file, over it: tables merge key by key and anything else, arrays of
+//! tables included, is replaced whole. One base file then serves a
+//! simulated dev setup and a live one without copies drifting apart.
 
 use anyhow::Result;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
 use serde::Deserialize;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::alerts::AlertChannel;
+use crate::core::calendar::RebalanceFrequency;
+use crate::core::error::DriftGuardError;
+use crate::core::money::ReportLocale;
+use crate::core::physics::PheromoneType;
+use crate::core::pipeline::AgentRole;
+use crate::core::target_engine::WeightingMethod;
+use crate::execution::reconcile::MismatchAction;
+use crate::export::ledger::LedgerFormat;
+use crate::execution::UnfilledPolicy;
+use crate::bridge::BridgeBackend;
+use crate::server::encoding::WireEncoding;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
@@ -15,92 +36,2093 @@ pub struct Config {
     pub market: MarketConfig,
     pub agent: AgentConfig,
     #[serde(default)]
+    pub exchange: ExchangeConfig,
+    #[serde(default)]
+    pub system_mode: SystemModeConfig,
+    #[serde(default)]
+    pub risk_index: RiskIndexConfig,
+    #[serde(default)]
+    pub correlation: CorrelationConfig,
+    #[serde(default)]
     pub trade_log: TradeLogConfig,
+    #[serde(default)]
+    pub data_quality: DataQualityConfig,
+    #[serde(default)]
+    pub costs: CostConfig,
+    #[serde(default)]
+    pub opportunity_scoring: OpportunityScoringConfig,
+    #[serde(default)]
+    pub rebalance_benefit: RebalanceBenefitConfig,
+    #[serde(default)]
+    pub allocation: AllocationConfig,
+    #[serde(default)]
+    pub target_engine: TargetEngineConfig,
+    #[serde(default)]
+    pub optimizer: OptimizerConfig,
+    #[serde(default)]
+    pub blackout: BlackoutConfig,
+    #[serde(default)]
+    pub valuation: ValuationConfig,
+    #[serde(default)]
+    pub execution: ExecutionConfig,
+    #[serde(default)]
+    pub reconciliation: ReconciliationConfig,
+    #[serde(default)]
+    pub holdings_import: HoldingsImportConfig,
+    #[serde(default)]
+    pub statements: StatementsConfig,
+    #[serde(default)]
+    pub benchmarks: BenchmarksConfig,
+    #[serde(default)]
+    pub decay_scheduler: DecaySchedulerConfig,
+    #[serde(default)]
+    pub pipeline: PipelineConfig,
+    #[serde(default)]
+    pub swarm: SwarmConfig,
+    #[serde(default)]
+    pub dashboard: DashboardConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub event_sampling: EventSamplingConfig,
+    #[serde(default)]
+    pub scripting: ScriptingConfig,
+    #[serde(default)]
+    pub bridge: BridgeConfig,
+    #[serde(default)]
+    pub ledger: LedgerConfig,
+    #[serde(default)]
+    pub reporting: ReportingConfig,
+    #[serde(default)]
+    pub simulation: SimulationConfig,
+    #[serde(default)]
+    pub soak: SoakConfig,
+    #[serde(default)]
+    pub shadow: ShadowConfig,
+    #[serde(default)]
+    pub forecast: ForecastConfig,
+    #[serde(default)]
+    pub wash_sale: WashSaleConfig,
+    #[serde(default)]
+    pub order_queue: OrderQueueConfig,
+    #[serde(default)]
+    pub warmup: WarmupConfig,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub alerting: AlertingConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    /// User-defined alert rules (`[[alerts]]`)
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+    /// Profile merged over the base file, if any
+    #[serde(skip)]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PheromoneConfig {
+    pub price_freshness_decay: f64,
+    pub rebalance_opportunity_decay: f64,
+    pub execution_permit_decay: f64,
+    pub trade_executed_decay: f64,
+    #[serde(default = "default_data_quality_decay")]
+    pub data_quality_decay: f64,
+    #[serde(default = "default_drift_forecast_decay")]
+    pub drift_forecast_decay: f64,
+    /// Deposits of each type kept for `Blackboard::sniff_smoothed`
+    /// (0 = none, smoothed sniffs read the latest deposit alone)
+    #[serde(default = "default_recent_deposits")]
+    pub recent_deposits: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThresholdConfig {
+    pub price_freshness: f64,
+    pub rebalance_opportunity: f64,
+    pub execution_permit: f64,
+    pub trade_executed: f64,
+    #[serde(default = "default_data_quality_threshold")]
+    pub data_quality: f64,
+    #[serde(default = "default_drift_forecast_threshold")]
+    pub drift_forecast: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PortfolioConfig {
+    /// Multi-asset definitions (preferred)
+    #[serde(default)]
+    pub assets: Vec<AssetConfig>,
+    
+    /// Asset groups with targets at group and member level; their members
+    /// stand in for `assets` when that list is empty
+    #[serde(default)]
+    pub groups: Vec<AssetGroupConfig>,
+    
+    /// Legacy 2-asset fallback
+    #[serde(default = "default_spy")]
+    pub stocks_symbol: String,
+    #[serde(default = "default_bnd")]
+    pub bonds_symbol: String,
+    #[serde(default = "default_60")]
+    pub default_stocks_pct: f64,
+    #[serde(default = "default_40")]
+    pub default_bonds_pct: f64,
+    pub drift_threshold: f64,
+    pub initial_balance: f64,
+    /// ISO 4217 code all values are reported in
+    #[serde(default = "default_base_currency")]
+    pub base_currency: String,
+    /// Scheduled rebalancing on top of drift bands
+    #[serde(default)]
+    pub calendar_rebalance: CalendarRebalanceConfig,
+    /// Time-varying target allocation
+    #[serde(default)]
+    pub glide_path: GlidePathConfig,
+}
+
+/// Glide path: move the stocks target over time, either linearly from
+/// `default_stocks_pct` or along a dated schedule
+#[derive(Debug, Clone, Deserialize)]
+pub struct GlidePathConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Date the linear path starts from `default_stocks_pct`
+    #[serde(default)]
+    pub start_date: Option<NaiveDate>,
+    /// Change in stocks weight per year (negative to de-risk)
+    #[serde(default)]
+    pub annual_stocks_change_pct: f64,
+    #[serde(default = "default_glide_min_stocks")]
+    pub min_stocks_pct: f64,
+    #[serde(default = "default_glide_max_stocks")]
+    pub max_stocks_pct: f64,
+    /// Dated targets; takes precedence over the linear path when present
+    #[serde(default)]
+    pub schedule: Vec<GlideStep>,
+    #[serde(default = "default_glide_check_secs")]
+    pub check_interval_secs: u64,
+}
+
+/// One dated step of a glide path schedule
+#[derive(Debug, Clone, Deserialize)]
+pub struct GlideStep {
+    pub date: NaiveDate,
+    pub stocks_pct: f64,
+}
+
+/// Calendar trigger: rebalance on the first trading day of each period
+/// regardless of drift ("bands + calendar" hybrid policy)
+#[derive(Debug, Clone, Deserialize)]
+pub struct CalendarRebalanceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_rebalance_frequency")]
+    pub frequency: RebalanceFrequency,
+    /// Time of day ("HH:MM", exchange time) the trigger becomes due
+    #[serde(default = "default_calendar_at", alias = "at_utc")]
+    pub at: String,
+}
+
+/// Individual asset configuration for multi-asset portfolios
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct AssetConfig {
+    pub symbol: String,
+    #[serde(default)]
+    pub name: String,
+    pub target_pct: f64,
+    /// Lowest weight the target may give this asset, in percent
+    #[serde(default)]
+    pub min_pct: Option<f64>,
+    /// Highest weight the target may give this asset, in percent
+    #[serde(default)]
+    pub max_pct: Option<f64>,
+    /// Exchange it trades on, e.g. "LSE" (default: from the symbol's
+    /// suffix, else US)
+    #[serde(default)]
+    pub exchange: Option<String>,
+    /// Currency it's quoted in (default: the exchange's)
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+/// A group of assets (e.g. Equities → SPY, QQQ, IWM) with a target for
+/// the group and for each member
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct AssetGroupConfig {
+    pub name: String,
+    /// Share of the portfolio, in percent
+    pub target_pct: f64,
+    pub members: Vec<GroupMemberConfig>,
+}
+
+/// One asset of a group
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct GroupMemberConfig {
+    pub symbol: String,
+    #[serde(default)]
+    pub name: String,
+    /// Share of the group, in percent
+    pub target_pct: f64,
+    #[serde(default)]
+    pub exchange: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarketConfig {
+    /// Quote provider: "alpha_vantage", "polygon", "finnhub" or "yahoo"
+    #[serde(default = "default_market_provider")]
+    pub provider: String,
+    /// Polygon.io settings when `provider = "polygon"`
+    #[serde(default)]
+    pub polygon: PolygonConfig,
+    /// Finnhub settings when `provider = "finnhub"`
+    #[serde(default)]
+    pub finnhub: FinnhubConfig,
+    /// Yahoo Finance, used when `provider = "yahoo"` or as the keyless fallback
+    #[serde(default)]
+    pub yahoo: YahooConfig,
+    /// Ordered failover chain replacing `provider` when not empty
+    #[serde(default)]
+    pub providers: ProvidersConfig,
+    pub poll_interval_ms: u64,
+    pub vix_high_threshold: f64,
+    pub vix_low_threshold: f64,
+    /// VIX data source: "simulation", "cboe" or "fred" (read through
+    /// `vix_feed`), "history" (replays `vix_history`), "etf" or "futures"
+    /// (derived from `vix_proxy`), or "polygon" (the I:VIX index, with
+    /// `provider = "polygon"`) or "yahoo" (`^VIX`, when quotes come from
+    /// Yahoo Finance)
+    #[serde(default = "default_vix_source")]
+    pub vix_source: String,
+    /// Historical VIX replayed when `vix_source = "history"`
+    #[serde(default)]
+    pub vix_history: VixHistoryConfig,
+    /// Volatility proxy read when `vix_source = "etf"` or `"futures"`
+    #[serde(default)]
+    pub vix_proxy: VixProxyConfig,
+    /// CBOE's delayed quote or FRED's VIXCLS when `vix_source = "cboe"` or `"fred"`
+    #[serde(default)]
+    pub vix_feed: VixFeedConfig,
+    /// Optional second-provider price validation
+    #[serde(default)]
+    pub cross_check: CrossCheckConfig,
+    /// Dynamic Sensor poll interval
+    #[serde(default)]
+    pub adaptive_polling: AdaptivePollingConfig,
+    /// Realized-volatility fallback when VIX is unavailable or simulated
+    #[serde(default)]
+    pub realized_vol: RealizedVolConfig,
+    /// Exchange rates used when the provider has none, e.g.
+    /// `GBPUSD = 1.27` (dollars per pound); either direction serves both
+    #[serde(default)]
+    pub fx_rates: BTreeMap<String, f64>,
+}
+
+/// Realized volatility of live prices, used by the Guardian in place of a
+/// missing or simulated VIX
+#[derive(Debug, Clone, Deserialize)]
+pub struct RealizedVolConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_spy")]
+    pub symbol: String,
+    /// Live price samples kept on the blackboard
+    #[serde(default = "default_realized_vol_max_samples")]
+    pub max_samples: usize,
+    /// Returns required before the estimate is trusted
+    #[serde(default = "default_realized_vol_min_returns")]
+    pub min_returns: usize,
+    /// Implied-over-realized premium applied when mapping to the VIX scale
+    #[serde(default = "default_vix_multiplier")]
+    pub vix_multiplier: f64,
+}
+
+/// Historical VIX series replayed in place of the simulated VIX
+#[derive(Debug, Clone, Deserialize)]
+pub struct VixHistoryConfig {
+    /// CSV with a date/timestamp column and a close/value column
+    #[serde(default = "default_vix_history_file")]
+    pub file: String,
+    /// First day replayed (default the first row)
+    #[serde(default)]
+    pub start: Option<chrono::NaiveDate>,
+    /// Simulated seconds each observation is replayed for (wall-clock
+    /// seconds at the default speed)
+    #[serde(default = "default_vix_history_step_secs")]
+    pub step_secs: u64,
+}
+
+/// Polygon.io quotes (real-time or 15-minute delayed, depending on the plan)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolygonConfig {
+    /// Environment variable holding the API key
+    #[serde(default = "default_polygon_api_key_env")]
+    pub api_key_env: String,
+    #[serde(default = "default_polygon_base_url")]
+    pub base_url: String,
+    /// How long a quote is served from the cache before it is fetched again
+    #[serde(default = "default_polygon_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+}
+
+/// Finnhub quotes, streamed trade by trade over its websocket
+#[derive(Debug, Clone, Deserialize)]
+pub struct FinnhubConfig {
+    /// Environment variable holding the API key
+    #[serde(default = "default_finnhub_api_key_env")]
+    pub api_key_env: String,
+    #[serde(default = "default_finnhub_base_url")]
+    pub base_url: String,
+    /// Subscribe to the trade stream; off polls REST quotes only
+    #[serde(default = "default_true")]
+    pub stream: bool,
+    #[serde(default = "default_finnhub_stream_url")]
+    pub stream_url: String,
+    /// A streamed price older than this is refreshed from the REST quote
+    #[serde(default = "default_finnhub_stale_after_secs")]
+    pub stale_after_secs: u64,
+    /// Shortest gap between the Sensor's deposits while trades stream in
+    #[serde(default = "default_finnhub_min_deposit_interval_ms")]
+    pub min_deposit_interval_ms: u64,
+    /// How long a REST quote is served from the cache before it is fetched again
+    #[serde(default = "default_finnhub_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+}
+
+/// Yahoo Finance's public quotes, which need no key
+#[derive(Debug, Clone, Deserialize)]
+pub struct YahooConfig {
+    /// Quote from Yahoo Finance when the configured provider has no key,
+    /// instead of simulating
+    #[serde(default = "default_true")]
+    pub fallback: bool,
+    #[serde(default = "default_yahoo_base_url")]
+    pub base_url: String,
+    /// How long a quote is served from the cache before it is fetched again
+    #[serde(default = "default_yahoo_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+}
+
+/// Providers quoting in turn, each falling through to the next when it
+/// fails or is cooling down
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProvidersConfig {
+    /// Provider names, most preferred first (empty = `provider` alone)
+    #[serde(default)]
+    pub chain: Vec<String>,
+    /// Consecutive failures after which a provider is skipped
+    #[serde(default = "default_failover_max_failures")]
+    pub max_failures: u32,
+    /// How long a failing provider is skipped before it is tried again
+    #[serde(default = "default_failover_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+/// Real VIX read from CBOE or FRED, which need no key
+#[derive(Debug, Clone, Deserialize)]
+pub struct VixFeedConfig {
+    /// CBOE's delayed `_VIX` quote (JSON)
+    #[serde(default = "default_cboe_vix_url")]
+    pub cboe_url: String,
+    /// FRED's VIXCLS series of daily closes (CSV)
+    #[serde(default = "default_fred_vix_url")]
+    pub fred_url: String,
+    /// How long a reading is served from the cache before it is fetched again
+    #[serde(default = "default_vix_feed_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    /// Simulate VIX while the source and the provider's own VIX fail,
+    /// rather than failing the read (which halts the Guardian)
+    #[serde(default = "default_true")]
+    pub simulate_on_failure: bool,
+}
+
+/// A VIX-equivalent derived from a volatility ETF or VX futures quote
+#[derive(Debug, Clone, Deserialize)]
+pub struct VixProxyConfig {
+    /// Quoted symbol (default VIXY for "etf", VX for "futures")
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// ETF exposure to short-term VIX futures (UVXY 1.5, SVXY -0.5)
+    #[serde(default = "default_proxy_leverage")]
+    pub leverage: f64,
+    /// ETF price at which VIX read `reference_vix` (default the first live
+    /// price seen)
+    #[serde(default)]
+    pub reference_price: Option<f64>,
+    #[serde(default = "default_proxy_reference_vix")]
+    pub reference_vix: f64,
+    /// Futures price units per VIX point
+    #[serde(default = "default_proxy_futures_scale")]
+    pub futures_scale: f64,
+}
+
+/// Dynamic Sensor polling: poll faster when something interesting is
+/// happening, slower when it is not, within `[min_interval_ms, max_interval_ms]`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AdaptivePollingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_min_poll_ms")]
+    pub min_interval_ms: u64,
+    #[serde(default = "default_max_poll_ms")]
+    pub max_interval_ms: u64,
+    /// Drift / threshold ratio above which drift counts as "near threshold"
+    #[serde(default = "default_near_drift_ratio")]
+    pub near_drift_ratio: f64,
+    /// Drift / threshold ratio below which drift counts as "far from threshold"
+    #[serde(default = "default_far_drift_ratio")]
+    pub far_drift_ratio: f64,
+    /// Regular session open, "HH:MM" exchange time (weekdays only)
+    #[serde(default = "default_session_open", alias = "session_open_utc")]
+    pub session_open: String,
+    /// Regular session close, "HH:MM" exchange time
+    #[serde(default = "default_session_close", alias = "session_close_utc")]
+    pub session_close: String,
+}
+
+/// Cross-provider price validation for the Sensor
+#[derive(Debug, Clone, Deserialize)]
+pub struct CrossCheckConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Secondary provider name ("alpha_vantage", "polygon", "finnhub" or "yahoo")
+    #[serde(default = "default_cross_check_provider")]
+    pub provider: String,
+    /// Maximum tolerated price divergence between providers (%)
+    #[serde(default = "default_cross_check_tolerance")]
+    pub tolerance_pct: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AgentConfig {
+    pub sniff_interval_ms: u64,
+    /// Agents this process runs (the rest may run elsewhere on the same Redis)
+    #[serde(default = "default_agent_roles")]
+    pub roles: Vec<AgentRole>,
+    /// Bootstrap blackboard state and run the background services
+    /// (valuation, statements, schedulers); exactly one process should
+    #[serde(default = "default_true")]
+    pub run_services: bool,
+    /// Entries kept in each agent's activity stream
+    #[serde(default = "default_activity_max_entries")]
+    pub activity_max_entries: usize,
+    /// Agent metrics are batched and written at most this often
+    #[serde(default = "default_metrics_flush_ms")]
+    pub metrics_flush_ms: u64,
+    /// Unchanged metrics are still rewritten this often, keeping
+    /// `last_action_time` fresh
+    #[serde(default = "default_metrics_heartbeat_secs")]
+    pub metrics_heartbeat_secs: u64,
+    /// Agents' loop timings and Redis call counts are published this often
+    #[serde(default = "default_runtime_report_secs")]
+    pub runtime_report_secs: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TradeLogConfig {
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+}
+
+/// Data-quality scoring for Sensor snapshots
+#[derive(Debug, Clone, Deserialize)]
+pub struct DataQualityConfig {
+    /// Minimum snapshot score the Guardian requires before permitting trades
+    #[serde(default = "default_min_score")]
+    pub min_score: f64,
+    /// Quotes older than this contribute zero freshness
+    #[serde(default = "default_max_quote_age_secs")]
+    pub max_quote_age_secs: f64,
+    /// Base score for a quote served from the provider cache
+    #[serde(default = "default_cached_score")]
+    pub cached_score: f64,
+    /// Base score for a locally simulated quote
+    #[serde(default = "default_simulated_score")]
+    pub simulated_score: f64,
+    /// Cross-provider price disagreement (%) at which the score drops to zero
+    #[serde(default = "default_max_disagreement_pct")]
+    pub max_disagreement_pct: f64,
+}
+
+/// Transaction cost model
+#[derive(Debug, Clone, Deserialize)]
+pub struct CostConfig {
+    /// Flat commission per executed leg ($)
+    #[serde(default)]
+    pub commission_per_trade: f64,
+    /// Half-spread paid on every dollar traded (basis points)
+    #[serde(default = "default_spread_bps")]
+    pub spread_bps: f64,
+    /// Tax rate on realized gains, for allocation previews (0.15 = 15%)
+    #[serde(default = "default_capital_gains_rate")]
+    pub capital_gains_rate: f64,
+}
+
+/// Benefit model: the value of removing tracking error, compared against
+/// the cost model before an opportunity is deposited
+#[derive(Debug, Clone, Deserialize)]
+pub struct RebalanceBenefitConfig {
+    /// Drop opportunities whose estimated cost exceeds the benefit
+    #[serde(default = "default_true")]
+    pub suppress_unprofitable: bool,
+    /// Annualized volatility of the stocks sleeve
+    #[serde(default = "default_stocks_volatility")]
+    pub stocks_volatility: f64,
+    /// Annualized volatility of the bonds sleeve
+    #[serde(default = "default_bonds_volatility")]
+    pub bonds_volatility: f64,
+    #[serde(default = "default_stock_bond_correlation")]
+    pub correlation: f64,
+    /// Risk aversion (λ) converting tracking variance into dollars
+    #[serde(default = "default_risk_aversion")]
+    pub risk_aversion: f64,
+    /// Horizon over which the avoided tracking error is valued (years)
+    #[serde(default = "default_benefit_horizon_years")]
+    pub horizon_years: f64,
+}
+
+/// Target allocation presets and the rules any new target must satisfy
+#[derive(Debug, Clone, Deserialize)]
+pub struct AllocationConfig {
+    /// Lowest weight any single asset may be given (%)
+    #[serde(default)]
+    pub min_asset_pct: f64,
+    /// Highest weight any single asset may be given (%)
+    #[serde(default = "default_max_asset_pct")]
+    pub max_asset_pct: f64,
+    /// Environment variable holding the operator token; when it is set,
+    /// allocation changes must present the same token
+    #[serde(default = "default_operator_token_env")]
+    pub operator_token_env: String,
+    #[serde(default = "default_presets")]
+    pub presets: Vec<AllocationPreset>,
+}
+
+/// Periodic portfolio valuation and drawdown tracking
+#[derive(Debug, Clone, Deserialize)]
+pub struct ValuationConfig {
+    #[serde(default = "default_valuation_interval_ms")]
+    pub interval_ms: u64,
+    /// Revalue holdings from the Sensor's price moves
+    #[serde(default = "default_true")]
+    pub mark_to_market: bool,
+    /// Spacing of portfolio history samples (for export)
+    #[serde(default = "default_history_interval_secs")]
+    pub history_interval_secs: u64,
+    /// History samples kept; 0 disables history
+    #[serde(default = "default_history_max_entries")]
+    pub history_max_entries: usize,
+}
+
+/// Simulated execution behaviour (paper trading)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecutionConfig {
+    /// Delay before each simulated fill
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// Uniform random extra delay added on top of `latency_ms`
+    #[serde(default)]
+    pub latency_jitter_ms: u64,
+    /// Chance (0-1) that a leg is only partially filled
+    #[serde(default)]
+    pub partial_fill_probability: f64,
+    /// Smallest fraction of a leg a partial fill covers
+    #[serde(default = "default_min_fill_ratio")]
+    pub min_fill_ratio: f64,
+    /// What the Trader does with the unfilled remainder of a leg
+    #[serde(default)]
+    pub unfilled_policy: UnfilledPolicy,
+    /// Retries per leg under the `retry` policy (only while the permit is active)
+    #[serde(default = "default_max_fill_retries")]
+    pub max_fill_retries: u32,
+    /// Percentage points the permit's current or target stocks weight may
+    /// differ from the state being traded before the Trader aborts (0 = off)
+    #[serde(default = "default_stale_state_tolerance_pct")]
+    pub stale_state_tolerance_pct: f64,
+    /// Rate-limited command queue in front of the backend
+    #[serde(default)]
+    pub queue: ExecutionQueueConfig,
+}
+
+/// Serialized, rate-limited submission of legs to the execution backend
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecutionQueueConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Orders submitted per rolling minute (0 = unlimited)
+    #[serde(default = "default_max_orders_per_minute")]
+    pub max_orders_per_minute: u32,
+    /// Resubmissions of a rejected leg, under the same idempotency key
+    #[serde(default = "default_queue_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first resubmission; doubles with each one
+    #[serde(default = "default_queue_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// Legs kept on the dead-letter list (`orders:dead_letter`)
+    #[serde(default = "default_dead_letter_max_entries")]
+    pub dead_letter_max_entries: usize,
+}
+
+/// Periodic comparison of blackboard holdings with the execution backend
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReconciliationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_reconciliation_interval_secs")]
+    pub interval_secs: u64,
+    /// Per-symbol difference ($) tolerated before flagging a mismatch
+    #[serde(default = "default_reconciliation_tolerance_usd")]
+    pub tolerance_usd: f64,
+    #[serde(default)]
+    pub on_mismatch: MismatchAction,
+}
+
+/// Background announcement of pheromone decay
+#[derive(Debug, Clone, Deserialize)]
+pub struct DecaySchedulerConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Longest sleep between checks (picks up new deposits)
+    #[serde(default = "default_decay_max_sleep_ms")]
+    pub max_sleep_ms: u64,
+    /// Delete a pheromone's key once it has decayed
+    #[serde(default)]
+    pub cleanup_keys: bool,
+}
+
+/// Which pheromones an agent waits for and which it may deposit
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AgentWiring {
+    /// All of these must be active before the agent acts
+    #[serde(default)]
+    pub requires: Vec<PheromoneType>,
+    /// Pheromones the agent is allowed to deposit
+    #[serde(default)]
+    pub deposits: Vec<PheromoneType>,
+}
+
+/// Sniff/deposit wiring between agents (see `core::pipeline`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PipelineConfig {
+    #[serde(default = "default_sensor_wiring")]
+    pub sensor: AgentWiring,
+    #[serde(default = "default_analyst_wiring")]
+    pub analyst: AgentWiring,
+    #[serde(default = "default_guardian_wiring")]
+    pub guardian: AgentWiring,
+    #[serde(default = "default_trader_wiring")]
+    pub trader: AgentWiring,
+    #[serde(default = "default_forecast_wiring")]
+    pub forecast: AgentWiring,
+}
+
+/// Identity of this swarm on a shared Redis (see `core::federation`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SwarmConfig {
+    /// Swarm name; keys of any swarm but `default` are prefixed `<name>:`
+    #[serde(default = "default_swarm_name")]
+    pub name: String,
+    #[serde(default = "default_heartbeat_interval_secs")]
+    pub heartbeat_interval_secs: u64,
+    /// A swarm not heard from for this long is reported as down
+    #[serde(default = "default_heartbeat_timeout_secs")]
+    pub heartbeat_timeout_secs: u64,
+}
+
+/// Dashboard connection settings
+#[derive(Debug, Clone, Deserialize)]
+pub struct DashboardConfig {
+    /// Recent events kept in memory for `replay` after a reconnect (0 disables)
+    #[serde(default = "default_replay_buffer_size")]
+    pub replay_buffer_size: usize,
+    /// Serve only read-only endpoints (analytics replica, see `driftguard serve`)
+    #[serde(default)]
+    pub read_only: bool,
+    /// How far ahead each pheromone's projected decay curve reaches
+    #[serde(default = "default_forecast_horizon_secs")]
+    pub forecast_horizon_secs: f64,
+    /// Points sampled along the projected curve (0 disables it)
+    #[serde(default = "default_forecast_samples")]
+    pub forecast_samples: usize,
+    /// Deposits kept for the pheromone heatmap (0 disables the trail)
+    #[serde(default = "default_trail_max_entries")]
+    pub trail_max_entries: usize,
+}
+
+/// The exchange whose clock sessions and schedules follow
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExchangeConfig {
+    /// IANA time zone in which "HH:MM" settings, weekdays and dates are read
+    #[serde(default = "default_exchange_timezone")]
+    pub timezone: Tz,
+}
+
+/// Derivation of the explicit system mode (LIVE … HALTED)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SystemModeConfig {
+    #[serde(default = "default_system_mode_interval_secs")]
+    pub interval_secs: u64,
+    /// A market snapshot older than this degrades the data
+    #[serde(default = "default_stale_after_secs")]
+    pub stale_after_secs: u64,
+    /// Whether the Guardian may permit trades while every price is simulated
+    #[serde(default = "default_true")]
+    pub permit_in_simulation: bool,
+}
+
+/// Composite risk index: the portfolio's own volatility on the VIX scale
+#[derive(Debug, Clone, Deserialize)]
+pub struct RiskIndexConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Whether the Guardian thresholds on the index in place of VIX
+    #[serde(default)]
+    pub use_for_guardian: bool,
+    #[serde(default = "default_risk_index_interval_secs")]
+    pub interval_secs: u64,
+    /// Daily returns common to every asset required before it is published
+    #[serde(default = "default_realized_vol_min_returns")]
+    pub min_returns: usize,
+    /// Implied-over-realized premium applied when mapping to the VIX scale
+    #[serde(default = "default_vix_multiplier")]
+    pub vix_multiplier: f64,
+    /// An index older than this is ignored by the Guardian
+    #[serde(default = "default_stale_after_secs")]
+    pub stale_after_secs: u64,
+}
+
+/// Rolling correlations among the portfolio's assets
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorrelationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_correlation_interval_secs")]
+    pub interval_secs: u64,
+    /// Most recent daily returns common to every asset correlated
+    #[serde(default = "default_correlation_window")]
+    pub window: usize,
+    /// Average pairwise correlation at which diversification has broken down
+    #[serde(default = "default_correlation_threshold")]
+    pub threshold: f64,
+    /// A spike lasts until the average falls below this
+    #[serde(default = "default_correlation_release_below")]
+    pub release_below: f64,
+    /// Where a spike is announced
+    #[serde(default)]
+    pub channel: AlertChannel,
+    /// Readings kept for the dashboard series (`risk:correlation:history`)
+    #[serde(default = "default_correlation_history_len")]
+    pub history_len: usize,
+    /// During a spike the Guardian's volatility ceiling is multiplied by
+    /// this (1 leaves the Guardian alone)
+    #[serde(default = "default_correlation_caution_factor")]
+    pub guardian_caution_factor: f64,
+    /// A reading older than this is ignored by the Guardian
+    #[serde(default = "default_stale_after_secs")]
+    pub stale_after_secs: u64,
+}
+
+/// Coalescing of repeated sniff events before they reach the dashboard
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventSamplingConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// At most one Sniffed event per pheromone per interval (0 = every one)
+    #[serde(default = "default_sample_interval_ms")]
+    pub sniffed_interval_ms: u64,
+    /// At most one Decayed observation per pheromone per interval
+    #[serde(default = "default_sample_interval_ms")]
+    pub decayed_interval_ms: u64,
+    /// Pheromone types whose sniff observations are not broadcast at all
+    #[serde(default)]
+    pub muted: Vec<PheromoneType>,
+}
+
+/// User scripts replacing built-in decision rules
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScriptingConfig {
+    /// Rhai script defining `score(ctx)`, the Analyst's opportunity score
+    #[serde(default)]
+    pub analyst_score: Option<String>,
+    /// Rhai script defining `permit(ctx)`, the Guardian's permit rule
+    #[serde(default)]
+    pub guardian_rule: Option<String>,
+    /// Operations a single call may run before it is aborted
+    #[serde(default = "default_script_max_operations")]
+    pub max_operations: u64,
+    /// Maximum function call nesting
+    #[serde(default = "default_script_max_call_levels")]
+    pub max_call_levels: usize,
+}
+
+/// Publishing of events, trades and alerts to NATS or Kafka
+#[derive(Debug, Clone, Deserialize)]
+pub struct BridgeConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub backend: BridgeBackend,
+    /// NATS server URLs or Kafka bootstrap servers, comma-separated
+    #[serde(default = "default_bridge_servers")]
+    pub servers: String,
+    /// Topics are `<prefix>.events`, `<prefix>.trades` and `<prefix>.alerts`
+    #[serde(default = "default_bridge_topic_prefix")]
+    pub topic_prefix: String,
+    #[serde(default)]
+    pub format: WireEncoding,
+    /// Also publish (sampled) Sniffed observations
+    #[serde(default)]
+    pub include_sniffed: bool,
+}
+
+/// Plain-text accounting (ledger-cli / beancount) journal of executed trades
+#[derive(Debug, Clone, Deserialize)]
+pub struct LedgerConfig {
+    /// Journal file kept up to date by the swarm (unset: export API only)
+    #[serde(default)]
+    pub journal_file: Option<String>,
+    #[serde(default)]
+    pub format: LedgerFormat,
+    /// Parent account of the holdings, e.g. `Assets:DriftGuard:SPY`
+    #[serde(default = "default_ledger_account_prefix")]
+    pub account_prefix: String,
+    /// Counter account of the opening balance
+    #[serde(default = "default_ledger_opening_account")]
+    pub opening_account: String,
+    /// How often the journal file is checked for new trades
+    #[serde(default = "default_ledger_write_interval")]
+    pub write_interval_secs: u64,
+}
+
+/// How amounts are presented in statements and trade descriptions
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReportingConfig {
+    /// BCP 47 tag, e.g. "en-US", "de-DE", "fr-FR"
+    #[serde(default)]
+    pub locale: ReportLocale,
+}
+
+/// Randomness of simulated prices, VIX and fills
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SimulationConfig {
+    /// Seed of every simulated draw; random (and recorded) when unset
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+/// `driftguard soak`: an accelerated simulated run checked for invariants
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct SoakConfig {
+    /// Simulated hours to run for
+    #[serde(default = "default_soak_hours")]
+    pub hours: f64,
+    /// Simulated seconds per wall-clock second
+    #[serde(default = "default_soak_speed")]
+    pub speed: f64,
+    /// Wall-clock time between invariant checks
+    #[serde(default = "default_soak_check_interval_ms")]
+    pub check_interval_ms: u64,
+    /// Most trades allowed in any simulated hour
+    #[serde(default = "default_soak_max_trades_per_hour")]
+    pub max_trades_per_hour: usize,
+    /// Engage the kill switch every this many simulated hours (0 = never)
+    #[serde(default = "default_soak_halt_every_hours")]
+    pub halt_every_hours: f64,
+    /// Simulated minutes the kill switch stays engaged
+    #[serde(default = "default_soak_halt_minutes")]
+    pub halt_minutes: f64,
+    /// Where the failure report is written
+    #[serde(default = "default_soak_report_file")]
+    pub report_file: String,
+}
+
+/// Projection of when drift will next breach the threshold (Forecast agent)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ForecastConfig {
+    /// Market snapshots kept in the rolling trend window
+    #[serde(default = "default_forecast_lookback_samples")]
+    pub lookback_samples: usize,
+    /// Snapshots required before the first forecast
+    #[serde(default = "default_forecast_min_samples")]
+    pub min_samples: usize,
+    /// Breaches projected further out than this are reported as none
+    #[serde(default = "default_forecast_max_horizon_days")]
+    pub max_horizon_days: f64,
+}
+
+/// Trader guard against round trips that would be wash sales
+#[derive(Debug, Clone, Deserialize)]
+pub struct WashSaleConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Days before (and after) a loss sale in which the symbol is not re-bought
+    #[serde(default = "default_wash_sale_window_days")]
+    pub window_days: i64,
+    /// Treat a sell as a loss when there is no cost basis to tell
+    #[serde(default)]
+    pub assume_loss_without_basis: bool,
+    /// Filled legs kept for the check
+    #[serde(default = "default_wash_sale_max_fills")]
+    pub max_fills: usize,
+}
+
+/// Pending-order queue holding approved rebalances until the execution window
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderQueueConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Execution window start, "HH:MM" exchange time (weekdays only)
+    #[serde(default = "default_order_window_open", alias = "window_open_utc")]
+    pub window_open: String,
+    /// Execution window end, "HH:MM" exchange time
+    #[serde(default = "default_order_window_close", alias = "window_close_utc")]
+    pub window_close: String,
+    /// Minutes skipped after the window opens
+    #[serde(default = "default_blackout_minutes")]
+    pub skip_first_minutes: u32,
+    /// Minutes skipped before the window closes
+    #[serde(default = "default_blackout_minutes")]
+    pub skip_last_minutes: u32,
+    /// A queued order older than this expires unexecuted
+    #[serde(default = "default_order_max_age_minutes")]
+    pub max_age_minutes: i64,
+}
+
+/// Startup warmup during which the Guardian refuses permits while the
+/// Sensor builds history
+#[derive(Debug, Clone, Deserialize)]
+pub struct WarmupConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Sensor snapshots required since the Guardian started
+    #[serde(default = "default_warmup_min_snapshots")]
+    pub min_snapshots: usize,
+    /// Also wait for the realized-volatility window (`[market.realized_vol]
+    /// min_returns`) while the Sensor is receiving live prices for it
+    #[serde(default = "default_true")]
+    pub require_realized_vol: bool,
+    /// Daily closes loaded at startup from TIME_SERIES_DAILY (0 = none)
+    #[serde(default)]
+    pub history_days: usize,
+}
+
+/// Background janitor enforcing retention on the blackboard's histories and
+/// measuring the swarm's Redis footprint
+#[derive(Debug, Clone, Deserialize)]
+pub struct RetentionConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_retention_interval_secs")]
+    pub interval_secs: u64,
+    /// Trade log entries older than this are removed (0 = by count only)
+    #[serde(default)]
+    pub trade_log_max_age_days: u32,
+    /// Agent activity, audit log, shadow divergences and dead-lettered orders
+    #[serde(default)]
+    pub events_max_age_days: u32,
+    /// Portfolio history, live price samples and the pheromone trail
+    #[serde(default)]
+    pub history_max_age_days: u32,
+    /// Daily snapshots and summaries (month-end statements are always kept)
+    #[serde(default)]
+    pub snapshots_max_age_days: u32,
+    /// Footprint above which the janitor warns (0 = no budget)
+    #[serde(default)]
+    pub memory_budget_mb: u64,
+}
+
+/// Log verbosity, overall and per agent or tracing target
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoggingConfig {
+    /// trace | debug | info | warn | error | off
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    /// Levels by agent name (`sensor`) or tracing target
+    #[serde(default)]
+    pub levels: BTreeMap<String, String>,
+    /// How often each process checks for levels changed at runtime
+    #[serde(default = "default_log_reload_interval_secs")]
+    pub reload_interval_secs: u64,
+}
+
+/// API keys of the users sharing one deployment, each scoped to their
+/// own swarms; empty leaves the API open
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuthConfig {
+    #[serde(default)]
+    pub users: Vec<ApiUserConfig>,
+    /// Refuse to serve the API unless commands are protected by the
+    /// operator token or API users
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// One user of `[[auth.users]]`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiUserConfig {
+    pub name: String,
+    /// Environment variable holding the user's API key
+    pub api_key_env: String,
+    /// Swarms (portfolios) the user may see and command; "*" for all
+    pub swarms: Vec<String>,
+}
+
+/// Evaluation and delivery of `[[alerts]]` rules
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertingConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_alert_interval_secs")]
+    pub interval_secs: u64,
+    /// Minimum time between repeat notifications of a rule that stays true
+    /// (rules can override it)
+    #[serde(default = "default_alert_cooldown_secs")]
+    pub cooldown_secs: u64,
+    /// Environment variable holding the Slack incoming-webhook URL
+    #[serde(default = "default_slack_webhook_env")]
+    pub slack_webhook_env: String,
+    /// Environment variable holding the URL `webhook` alerts are POSTed to
+    #[serde(default = "default_alert_webhook_env")]
+    pub webhook_url_env: String,
+    /// Retries of Slack and webhook notifications
+    #[serde(default)]
+    pub delivery: NotifyDeliveryConfig,
+}
+
+/// Retrying delivery of queued notifications
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotifyDeliveryConfig {
+    /// Deliveries retried after the first failure
+    #[serde(default = "default_notify_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles with each one
+    #[serde(default = "default_notify_retry_backoff_secs")]
+    pub retry_backoff_secs: u64,
+    #[serde(default = "default_notify_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+    /// Notifications kept on the dead-letter list (`notify:dead_letter`)
+    #[serde(default = "default_dead_letter_max_entries")]
+    pub dead_letter_max_entries: usize,
+}
+
+/// A condition to be notified about, e.g. `condition = "drift > 8"`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertRule {
+    /// Defaults to the condition
+    #[serde(default)]
+    pub name: String,
+    /// Rhai expression over the alert context (see `core::alerts`)
+    pub condition: String,
+    #[serde(default)]
+    pub channel: AlertChannel,
+    /// Overrides `[alerting] cooldown_secs`
+    #[serde(default)]
+    pub cooldown_secs: Option<u64>,
+}
+
+impl AlertRule {
+    pub fn name(&self) -> &str {
+        if self.name.is_empty() { &self.condition } else { &self.name }
+    }
+}
+
+/// Second Analyst/Guardian configuration evaluated alongside the live one
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShadowConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Config file of the shadow parameters (only its analyst and guardian
+    /// settings matter)
+    #[serde(default = "default_shadow_config_file")]
+    pub config_file: String,
+    /// Divergences kept in `shadow:divergences`
+    #[serde(default = "default_shadow_max_divergences")]
+    pub max_divergences: usize,
+}
+
+/// Audit log of state-changing client commands
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuditConfig {
+    /// Entries kept in the `audit:commands` stream
+    #[serde(default = "default_audit_max_entries")]
+    pub max_entries: usize,
+}
+
+/// Ghost portfolios valued alongside the real one
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchmarksConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Symbol held by the 100% buy-and-hold benchmark (must be quoted by the Sensor)
+    #[serde(default = "default_spy")]
+    pub benchmark_symbol: String,
+    /// How often the calendar-rebalanced ghost resets to target
+    #[serde(default = "default_rebalance_frequency")]
+    pub calendar_frequency: RebalanceFrequency,
+}
+
+/// Daily snapshots and month-end statements
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatementsConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Time of day ("HH:MM", exchange time) after which the daily snapshot is taken
+    #[serde(default = "default_snapshot_at", alias = "snapshot_at_utc")]
+    pub snapshot_at: String,
+    #[serde(default = "default_statements_check_interval_secs")]
+    pub check_interval_secs: u64,
+    /// Consolidate each day into a `DailySummary` after its snapshot
+    #[serde(default = "default_true")]
+    pub daily_summary: bool,
+    /// Where daily summaries are sent (URLs as configured in `[alerting]`)
+    #[serde(default = "default_summary_channels")]
+    pub summary_channels: Vec<AlertChannel>,
+}
+
+/// How imported broker holdings map onto the stocks/bonds portfolio
+#[derive(Debug, Clone, Deserialize)]
+pub struct HoldingsImportConfig {
+    /// Symbols counted as bonds; everything else counts as stocks
+    #[serde(default = "default_bond_symbols")]
+    pub bond_symbols: Vec<String>,
+    /// Count cash and money-market balances as bonds (otherwise ignored)
+    #[serde(default = "default_true")]
+    pub cash_as_bonds: bool,
+    /// Positions that are never traded (e.g. legacy holdings with large
+    /// embedded gains); the rest of the portfolio is rebalanced around them
+    #[serde(default)]
+    pub locked_symbols: Vec<String>,
+}
+
+impl HoldingsImportConfig {
+    pub fn is_bond(&self, symbol: &str) -> bool {
+        self.bond_symbols.iter().any(|s| s.eq_ignore_ascii_case(symbol))
+    }
+    
+    pub fn is_locked(&self, symbol: &str) -> bool {
+        self.locked_symbols.iter().any(|s| s.eq_ignore_ascii_case(symbol))
+    }
+}
+
+/// Windows during which the Guardian withholds execution permits
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlackoutConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Regular session open, "HH:MM" exchange time (weekdays only)
+    #[serde(default = "default_session_open", alias = "session_open_utc")]
+    pub session_open: String,
+    /// Regular session close, "HH:MM" exchange time
+    #[serde(default = "default_session_close", alias = "session_close_utc")]
+    pub session_close: String,
+    #[serde(default = "default_blackout_minutes")]
+    pub after_open_minutes: u32,
+    #[serde(default = "default_blackout_minutes")]
+    pub before_close_minutes: u32,
+    /// Event calendar (one `YYYY-MM-DD [label]` per line); whole days are blacked out
+    #[serde(default)]
+    pub events_file: Option<String>,
+}
+
+/// Risk-based target weights recomputed from recent market snapshots
+#[derive(Debug, Clone, Deserialize)]
+pub struct TargetEngineConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_weighting_method")]
+    pub method: WeightingMethod,
+    /// Snapshots kept in the rolling return window
+    #[serde(default = "default_lookback_samples")]
+    pub lookback_samples: usize,
+    /// Snapshots required before the first recompute
+    #[serde(default = "default_min_samples")]
+    pub min_samples: usize,
+    #[serde(default = "default_recompute_interval_secs")]
+    pub recompute_interval_secs: u64,
+}
+
+/// Mean-variance target suggestions at `POST /api/optimize`
+#[derive(Debug, Clone, Deserialize)]
+pub struct OptimizerConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Annual risk-free rate the Sharpe ratio is measured against, as a fraction
+    #[serde(default)]
+    pub risk_free_rate: f64,
+    /// Most recent daily returns the estimates are drawn from
+    #[serde(default = "default_optimizer_lookback_days")]
+    pub lookback_days: usize,
+    /// Daily returns required before anything is suggested
+    #[serde(default = "default_optimizer_min_returns")]
+    pub min_returns: usize,
+    /// Finest grid spacing searched (%); coarsened for many assets
+    #[serde(default = "default_optimizer_step_pct")]
+    pub step_pct: f64,
+}
+
+/// Named target allocation selectable from the dashboard or REST API
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct AllocationPreset {
+    pub name: String,
+    pub stocks_pct: f64,
+    pub bonds_pct: f64,
+}
+
+/// Multi-factor opportunity scoring for the Analyst
+/// 
+/// Factor scores in [0, 1] are combined by weighted average; the result
+/// becomes the deposited Rebalance Opportunity intensity.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpportunityScoringConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_drift_weight")]
+    pub drift_weight: f64,
+    #[serde(default = "default_momentum_weight")]
+    pub momentum_weight: f64,
+    #[serde(default = "default_staleness_weight")]
+    pub staleness_weight: f64,
+    #[serde(default = "default_cost_weight")]
+    pub cost_weight: f64,
+    /// Relative return (%) that saturates the momentum factor
+    #[serde(default = "default_momentum_scale_pct")]
+    pub momentum_scale_pct: f64,
+    /// Days since last rebalance at which the staleness factor saturates
+    #[serde(default = "default_stale_after_days")]
+    pub stale_after_days: f64,
+    /// Effective cost (bps of turnover) at which the cost factor hits zero
+    #[serde(default = "default_max_cost_bps")]
+    pub max_cost_bps: f64,
+}
+
+fn default_spy() -> String { "SPY".to_string() }
+fn default_bnd() -> String { "BND".to_string() }
+fn default_60() -> f64 { 60.0 }
+fn default_40() -> f64 { 40.0 }
+fn default_vix_source() -> String { "simulation".to_string() }
+fn default_cboe_vix_url() -> String { "https://cdn.cboe.com/api/global/delayed_quotes/quotes/_VIX.json".to_string() }
+fn default_fred_vix_url() -> String { "https://fred.stlouisfed.org/graph/fredgraph.csv?id=VIXCLS".to_string() }
+fn default_vix_feed_cache_ttl_secs() -> u64 { 60 }
+fn default_failover_max_failures() -> u32 { 3 }
+fn default_failover_cooldown_secs() -> u64 { 60 }
+fn default_soak_hours() -> f64 { 4.0 }
+fn default_soak_speed() -> f64 { 10.0 }
+fn default_soak_check_interval_ms() -> u64 { 500 }
+fn default_soak_max_trades_per_hour() -> usize { 60 }
+fn default_soak_halt_every_hours() -> f64 { 1.0 }
+fn default_soak_halt_minutes() -> f64 { 10.0 }
+fn default_soak_report_file() -> String { "soak-failure.json".to_string() }
+fn default_market_provider() -> String { "alpha_vantage".to_string() }
+fn default_polygon_api_key_env() -> String { "POLYGON_API_KEY".to_string() }
+fn default_polygon_base_url() -> String { "https://api.polygon.io".to_string() }
+fn default_polygon_cache_ttl_secs() -> u64 { 15 }
+fn default_finnhub_api_key_env() -> String { "FINNHUB_API_KEY".to_string() }
+fn default_finnhub_base_url() -> String { "https://finnhub.io".to_string() }
+fn default_finnhub_stream_url() -> String { "wss://ws.finnhub.io".to_string() }
+fn default_finnhub_stale_after_secs() -> u64 { 60 }
+fn default_finnhub_min_deposit_interval_ms() -> u64 { 250 }
+fn default_finnhub_cache_ttl_secs() -> u64 { 15 }
+fn default_yahoo_base_url() -> String { "https://query1.finance.yahoo.com".to_string() }
+fn default_yahoo_cache_ttl_secs() -> u64 { 60 }
+fn default_vix_history_file() -> String { "data/VIX_History.csv".to_string() }
+fn default_vix_history_step_secs() -> u64 { 60 }
+fn default_proxy_leverage() -> f64 { 1.0 }
+fn default_proxy_reference_vix() -> f64 { 18.0 }
+fn default_proxy_futures_scale() -> f64 { 1.0 }
+fn default_max_entries() -> usize { 500 }
+fn default_min_poll_ms() -> u64 { 1000 }
+fn default_max_poll_ms() -> u64 { 60000 }
+fn default_near_drift_ratio() -> f64 { 0.8 }
+fn default_far_drift_ratio() -> f64 { 0.3 }
+fn default_exchange_timezone() -> Tz { Tz::UTC }
+fn default_system_mode_interval_secs() -> u64 { 5 }
+fn default_stale_after_secs() -> u64 { 300 }
+fn default_optimizer_lookback_days() -> usize { 252 }
+fn default_optimizer_min_returns() -> usize { 60 }
+fn default_optimizer_step_pct() -> f64 { 1.0 }
+fn default_correlation_interval_secs() -> u64 { 300 }
+fn default_correlation_window() -> usize { 20 }
+fn default_correlation_threshold() -> f64 { 0.8 }
+fn default_correlation_release_below() -> f64 { 0.7 }
+fn default_correlation_history_len() -> usize { 288 }
+fn default_correlation_caution_factor() -> f64 { 0.8 }
+fn default_risk_index_interval_secs() -> u64 { 60 }
+fn default_session_open() -> String { "14:30".to_string() }
+fn default_session_close() -> String { "21:00".to_string() }
+fn default_spread_bps() -> f64 { 2.0 }
+fn default_capital_gains_rate() -> f64 { 0.15 }
+fn default_true() -> bool { true }
+fn default_bridge_servers() -> String { "nats://localhost:4222".to_string() }
+fn default_bridge_topic_prefix() -> String { "driftguard".to_string() }
+fn default_ledger_account_prefix() -> String { "Assets:DriftGuard".to_string() }
+fn default_ledger_opening_account() -> String { "Equity:Opening-Balances".to_string() }
+fn default_ledger_write_interval() -> u64 { 60 }
+fn default_script_max_operations() -> u64 { 100_000 }
+fn default_script_max_call_levels() -> usize { 16 }
+fn default_stocks_volatility() -> f64 { 0.16 }
+fn default_bonds_volatility() -> f64 { 0.05 }
+fn default_stock_bond_correlation() -> f64 { 0.1 }
+fn default_risk_aversion() -> f64 { 3.0 }
+fn default_benefit_horizon_years() -> f64 { 1.0 }
+fn default_drift_weight() -> f64 { 0.6 }
+fn default_momentum_weight() -> f64 { 0.1 }
+fn default_staleness_weight() -> f64 { 0.15 }
+fn default_cost_weight() -> f64 { 0.15 }
+fn default_momentum_scale_pct() -> f64 { 0.5 }
+fn default_stale_after_days() -> f64 { 90.0 }
+fn default_max_cost_bps() -> f64 { 50.0 }
+fn default_max_asset_pct() -> f64 { 100.0 }
+fn default_operator_token_env() -> String { "DRIFTGUARD_OPERATOR_TOKEN".to_string() }
+fn default_presets() -> Vec<AllocationPreset> {
+    [("Conservative", 30.0), ("Balanced", 60.0), ("Aggressive", 80.0)]
+        .into_iter()
+        .map(|(name, stocks_pct)| AllocationPreset {
+            name: name.to_string(),
+            stocks_pct,
+            bonds_pct: 100.0 - stocks_pct,
+        })
+        .collect()
+}
+fn default_realized_vol_max_samples() -> usize { 390 }
+fn default_realized_vol_min_returns() -> usize { 20 }
+fn default_vix_multiplier() -> f64 { 1.2 }
+fn default_valuation_interval_ms() -> u64 { 5000 }
+fn default_history_interval_secs() -> u64 { 60 }
+fn default_history_max_entries() -> usize { 10080 }
+fn default_min_fill_ratio() -> f64 { 0.5 }
+fn default_max_fill_retries() -> u32 { 3 }
+fn default_max_orders_per_minute() -> u32 { 60 }
+fn default_queue_max_retries() -> u32 { 3 }
+fn default_queue_retry_backoff_ms() -> u64 { 500 }
+fn default_dead_letter_max_entries() -> usize { 500 }
+fn default_stale_state_tolerance_pct() -> f64 { 1.0 }
+fn default_reconciliation_interval_secs() -> u64 { 300 }
+fn default_decay_max_sleep_ms() -> u64 { 1000 }
+fn default_replay_buffer_size() -> usize { 500 }
+fn default_forecast_horizon_secs() -> f64 { 10.0 }
+fn default_forecast_samples() -> usize { 21 }
+fn default_trail_max_entries() -> usize { 5000 }
+fn default_audit_max_entries() -> usize { 10_000 }
+fn default_sample_interval_ms() -> u64 { 1000 }
+fn default_base_currency() -> String { "USD".to_string() }
+fn default_swarm_name() -> String { crate::core::federation::DEFAULT_SWARM.to_string() }
+fn default_heartbeat_interval_secs() -> u64 { 5 }
+fn default_heartbeat_timeout_secs() -> u64 { 30 }
+fn default_activity_max_entries() -> usize { 2000 }
+fn default_metrics_flush_ms() -> u64 { 1000 }
+fn default_metrics_heartbeat_secs() -> u64 { 30 }
+fn default_runtime_report_secs() -> u64 { 10 }
+fn default_agent_roles() -> Vec<AgentRole> { AgentRole::ALL.to_vec() }
+fn default_sensor_wiring() -> AgentWiring {
+    AgentWiring {
+        requires: vec![],
+        deposits: vec![PheromoneType::DataQuality, PheromoneType::PriceFreshness],
+    }
+}
+fn default_analyst_wiring() -> AgentWiring {
+    AgentWiring {
+        requires: vec![PheromoneType::PriceFreshness],
+        deposits: vec![PheromoneType::RebalanceOpportunity],
+    }
+}
+fn default_guardian_wiring() -> AgentWiring {
+    AgentWiring {
+        requires: vec![PheromoneType::RebalanceOpportunity, PheromoneType::DataQuality],
+        deposits: vec![PheromoneType::ExecutionPermit],
+    }
+}
+fn default_trader_wiring() -> AgentWiring {
+    AgentWiring {
+        requires: vec![PheromoneType::ExecutionPermit],
+        deposits: vec![PheromoneType::TradeExecuted],
+    }
+}
+fn default_forecast_wiring() -> AgentWiring {
+    AgentWiring {
+        requires: vec![PheromoneType::PriceFreshness],
+        deposits: vec![PheromoneType::DriftForecast],
+    }
+}
+fn default_snapshot_at() -> String { "21:30".to_string() }
+fn default_statements_check_interval_secs() -> u64 { 60 }
+fn default_summary_channels() -> Vec<AlertChannel> { vec![AlertChannel::Log] }
+fn default_bond_symbols() -> Vec<String> {
+    ["BND", "AGG", "BIV", "BSV", "BLV", "TLT", "IEF", "SHY", "SCHZ", "VGIT", "VGLT", "VGSH", "VTIP", "TIP"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+fn default_reconciliation_tolerance_usd() -> f64 { 1.0 }
+fn default_blackout_minutes() -> u32 { 15 }
+fn default_forecast_lookback_samples() -> usize { 120 }
+fn default_forecast_min_samples() -> usize { 10 }
+fn default_forecast_max_horizon_days() -> f64 { 365.0 }
+fn default_wash_sale_window_days() -> i64 { 30 }
+fn default_wash_sale_max_fills() -> usize { 500 }
+fn default_order_window_open() -> String { "14:00".to_string() }
+fn default_order_window_close() -> String { "19:30".to_string() }
+fn default_order_max_age_minutes() -> i64 { 1440 }
+fn default_warmup_min_snapshots() -> usize { 5 }
+fn default_retention_interval_secs() -> u64 { 3600 }
+fn default_alert_interval_secs() -> u64 { 60 }
+fn default_log_level() -> String { "debug".to_string() }
+fn default_log_reload_interval_secs() -> u64 { 5 }
+fn default_alert_cooldown_secs() -> u64 { 3600 }
+fn default_slack_webhook_env() -> String { "SLACK_WEBHOOK_URL".to_string() }
+fn default_alert_webhook_env() -> String { "DRIFTGUARD_ALERT_WEBHOOK_URL".to_string() }
+fn default_notify_max_retries() -> u32 { 8 }
+fn default_notify_retry_backoff_secs() -> u64 { 5 }
+fn default_notify_max_backoff_secs() -> u64 { 600 }
+fn default_shadow_config_file() -> String { "config.shadow.toml".to_string() }
+fn default_shadow_max_divergences() -> usize { 500 }
+fn default_weighting_method() -> WeightingMethod { WeightingMethod::RiskParity }
+fn default_lookback_samples() -> usize { 120 }
+fn default_min_samples() -> usize { 30 }
+fn default_recompute_interval_secs() -> u64 { 3600 }
+fn default_glide_min_stocks() -> f64 { 0.0 }
+fn default_glide_max_stocks() -> f64 { 100.0 }
+fn default_glide_check_secs() -> u64 { 3600 }
+fn default_rebalance_frequency() -> RebalanceFrequency { RebalanceFrequency::Quarterly }
+fn default_calendar_at() -> String { "15:00".to_string() }
+fn default_cross_check_provider() -> String { "alpha_vantage".to_string() }
+fn default_cross_check_tolerance() -> f64 { 1.0 }
+fn default_data_quality_decay() -> f64 { 0.3 }
+fn default_data_quality_threshold() -> f64 { 0.5 }
+fn default_drift_forecast_decay() -> f64 { 0.05 }
+fn default_drift_forecast_threshold() -> f64 { 0.3 }
+fn default_recent_deposits() -> usize { 5 }
+fn default_min_score() -> f64 { 0.4 }
+fn default_max_quote_age_secs() -> f64 { 300.0 }
+fn default_cached_score() -> f64 { 0.9 }
+fn default_simulated_score() -> f64 { 0.5 }
+fn default_max_disagreement_pct() -> f64 { 2.0 }
+
+impl Default for TradeLogConfig {
+    fn default() -> Self {
+        Self { max_entries: 500 }
+    }
+}
+
+impl Default for CalendarRebalanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            frequency: default_rebalance_frequency(),
+            at: default_calendar_at(),
+        }
+    }
+}
+
+impl Default for GlidePathConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_date: None,
+            annual_stocks_change_pct: 0.0,
+            min_stocks_pct: default_glide_min_stocks(),
+            max_stocks_pct: default_glide_max_stocks(),
+            schedule: Vec::new(),
+            check_interval_secs: default_glide_check_secs(),
+        }
+    }
+}
+
+impl Default for AllocationConfig {
+    fn default() -> Self {
+        Self {
+            min_asset_pct: 0.0,
+            max_asset_pct: default_max_asset_pct(),
+            operator_token_env: default_operator_token_env(),
+            presets: default_presets(),
+        }
+    }
+}
+
+impl Default for ValuationConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: default_valuation_interval_ms(),
+            mark_to_market: true,
+            history_interval_secs: default_history_interval_secs(),
+            history_max_entries: default_history_max_entries(),
+        }
+    }
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            latency_ms: 0,
+            latency_jitter_ms: 0,
+            partial_fill_probability: 0.0,
+            min_fill_ratio: default_min_fill_ratio(),
+            unfilled_policy: UnfilledPolicy::default(),
+            max_fill_retries: default_max_fill_retries(),
+            stale_state_tolerance_pct: default_stale_state_tolerance_pct(),
+            queue: ExecutionQueueConfig::default(),
+        }
+    }
+}
+
+impl Default for ExecutionQueueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_orders_per_minute: default_max_orders_per_minute(),
+            max_retries: default_queue_max_retries(),
+            retry_backoff_ms: default_queue_retry_backoff_ms(),
+            dead_letter_max_entries: default_dead_letter_max_entries(),
+        }
+    }
+}
+
+impl Default for ReconciliationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_reconciliation_interval_secs(),
+            tolerance_usd: default_reconciliation_tolerance_usd(),
+            on_mismatch: MismatchAction::default(),
+        }
+    }
+}
+
+impl Default for DecaySchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_sleep_ms: default_decay_max_sleep_ms(),
+            cleanup_keys: false,
+        }
+    }
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            sensor: default_sensor_wiring(),
+            analyst: default_analyst_wiring(),
+            guardian: default_guardian_wiring(),
+            trader: default_trader_wiring(),
+            forecast: default_forecast_wiring(),
+        }
+    }
+}
+
+impl Default for SwarmConfig {
+    fn default() -> Self {
+        Self {
+            name: default_swarm_name(),
+            heartbeat_interval_secs: default_heartbeat_interval_secs(),
+            heartbeat_timeout_secs: default_heartbeat_timeout_secs(),
+        }
+    }
+}
+
+impl Default for DashboardConfig {
+    fn default() -> Self {
+        Self {
+            replay_buffer_size: default_replay_buffer_size(),
+            read_only: false,
+            forecast_horizon_secs: default_forecast_horizon_secs(),
+            forecast_samples: default_forecast_samples(),
+            trail_max_entries: default_trail_max_entries(),
+        }
+    }
+}
+
+impl Default for ExchangeConfig {
+    fn default() -> Self {
+        Self { timezone: default_exchange_timezone() }
+    }
+}
+
+impl Default for SystemModeConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_system_mode_interval_secs(),
+            stale_after_secs: default_stale_after_secs(),
+            permit_in_simulation: true,
+        }
+    }
+}
+
+impl Default for RiskIndexConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            use_for_guardian: false,
+            interval_secs: default_risk_index_interval_secs(),
+            min_returns: default_realized_vol_min_returns(),
+            vix_multiplier: default_vix_multiplier(),
+            stale_after_secs: default_stale_after_secs(),
+        }
+    }
+}
+
+impl Default for ProvidersConfig {
+    fn default() -> Self {
+        Self {
+            chain: Vec::new(),
+            max_failures: default_failover_max_failures(),
+            cooldown_secs: default_failover_cooldown_secs(),
+        }
+    }
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            hours: default_soak_hours(),
+            speed: default_soak_speed(),
+            check_interval_ms: default_soak_check_interval_ms(),
+            max_trades_per_hour: default_soak_max_trades_per_hour(),
+            halt_every_hours: default_soak_halt_every_hours(),
+            halt_minutes: default_soak_halt_minutes(),
+            report_file: default_soak_report_file(),
+        }
+    }
+}
+
+impl Default for OptimizerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            risk_free_rate: 0.0,
+            lookback_days: default_optimizer_lookback_days(),
+            min_returns: default_optimizer_min_returns(),
+            step_pct: default_optimizer_step_pct(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct PheromoneConfig {
-    pub price_freshness_decay: f64,
-    pub rebalance_opportunity_decay: f64,
-    pub execution_permit_decay: f64,
-    pub trade_executed_decay: f64,
+impl Default for CorrelationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_correlation_interval_secs(),
+            window: default_correlation_window(),
+            threshold: default_correlation_threshold(),
+            release_below: default_correlation_release_below(),
+            channel: AlertChannel::default(),
+            history_len: default_correlation_history_len(),
+            guardian_caution_factor: default_correlation_caution_factor(),
+            stale_after_secs: default_stale_after_secs(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct ThresholdConfig {
-    pub price_freshness: f64,
-    pub rebalance_opportunity: f64,
-    pub execution_permit: f64,
-    pub trade_executed: f64,
+impl Default for EventSamplingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sniffed_interval_ms: default_sample_interval_ms(),
+            decayed_interval_ms: default_sample_interval_ms(),
+            muted: Vec::new(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct PortfolioConfig {
-    /// Multi-asset definitions (preferred)
-    #[serde(default)]
-    pub assets: Vec<AssetConfig>,
-    
-    /// Legacy 2-asset fallback
-    #[serde(default = "default_spy")]
-    pub stocks_symbol: String,
-    #[serde(default = "default_bnd")]
-    pub bonds_symbol: String,
-    #[serde(default = "default_60")]
-    pub default_stocks_pct: f64,
-    #[serde(default = "default_40")]
-    pub default_bonds_pct: f64,
-    pub drift_threshold: f64,
-    pub initial_balance: f64,
+impl Default for ScriptingConfig {
+    fn default() -> Self {
+        Self {
+            analyst_score: None,
+            guardian_rule: None,
+            max_operations: default_script_max_operations(),
+            max_call_levels: default_script_max_call_levels(),
+        }
+    }
 }
 
-/// Individual asset configuration for multi-asset portfolios
-#[derive(Debug, Clone, Deserialize, serde::Serialize)]
-pub struct AssetConfig {
-    pub symbol: String,
-    #[serde(default)]
-    pub name: String,
-    pub target_pct: f64,
+impl Default for BridgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: BridgeBackend::default(),
+            servers: default_bridge_servers(),
+            topic_prefix: default_bridge_topic_prefix(),
+            format: WireEncoding::default(),
+            include_sniffed: false,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct MarketConfig {
-    pub poll_interval_ms: u64,
-    pub vix_high_threshold: f64,
-    pub vix_low_threshold: f64,
-    /// VIX data source: "simulation" or "cboe"
-    #[serde(default = "default_vix_source")]
-    pub vix_source: String,
+impl Default for LedgerConfig {
+    fn default() -> Self {
+        Self {
+            journal_file: None,
+            format: LedgerFormat::default(),
+            account_prefix: default_ledger_account_prefix(),
+            opening_account: default_ledger_opening_account(),
+            write_interval_secs: default_ledger_write_interval(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct AgentConfig {
-    pub sniff_interval_ms: u64,
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self { max_entries: default_audit_max_entries() }
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct TradeLogConfig {
-    #[serde(default = "default_max_entries")]
-    pub max_entries: usize,
+impl Default for BenchmarksConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            benchmark_symbol: default_spy(),
+            calendar_frequency: default_rebalance_frequency(),
+        }
+    }
 }
 
-fn default_spy() -> String { "SPY".to_string() }
-fn default_bnd() -> String { "BND".to_string() }
-fn default_60() -> f64 { 60.0 }
-fn default_40() -> f64 { 40.0 }
-fn default_vix_source() -> String { "simulation".to_string() }
-fn default_max_entries() -> usize { 500 }
+impl Default for StatementsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            snapshot_at: default_snapshot_at(),
+            check_interval_secs: default_statements_check_interval_secs(),
+            daily_summary: true,
+            summary_channels: default_summary_channels(),
+        }
+    }
+}
 
-impl Default for TradeLogConfig {
+impl Default for HoldingsImportConfig {
     fn default() -> Self {
-        Self { max_entries: 500 }
+        Self {
+            bond_symbols: default_bond_symbols(),
+            cash_as_bonds: true,
+            locked_symbols: Vec::new(),
+        }
+    }
+}
+
+impl Default for ForecastConfig {
+    fn default() -> Self {
+        Self {
+            lookback_samples: default_forecast_lookback_samples(),
+            min_samples: default_forecast_min_samples(),
+            max_horizon_days: default_forecast_max_horizon_days(),
+        }
+    }
+}
+
+impl Default for WashSaleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_days: default_wash_sale_window_days(),
+            assume_loss_without_basis: false,
+            max_fills: default_wash_sale_max_fills(),
+        }
+    }
+}
+
+impl Default for OrderQueueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_open: default_order_window_open(),
+            window_close: default_order_window_close(),
+            skip_first_minutes: default_blackout_minutes(),
+            skip_last_minutes: default_blackout_minutes(),
+            max_age_minutes: default_order_max_age_minutes(),
+        }
+    }
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_snapshots: default_warmup_min_snapshots(),
+            require_realized_vol: true,
+            history_days: 0,
+        }
+    }
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: default_retention_interval_secs(),
+            trade_log_max_age_days: 0,
+            events_max_age_days: 0,
+            history_max_age_days: 0,
+            snapshots_max_age_days: 0,
+            memory_budget_mb: 0,
+        }
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: default_log_level(),
+            levels: BTreeMap::new(),
+            reload_interval_secs: default_log_reload_interval_secs(),
+        }
+    }
+}
+
+impl Default for AlertingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: default_alert_interval_secs(),
+            cooldown_secs: default_alert_cooldown_secs(),
+            slack_webhook_env: default_slack_webhook_env(),
+            webhook_url_env: default_alert_webhook_env(),
+            delivery: NotifyDeliveryConfig::default(),
+        }
+    }
+}
+
+impl Default for NotifyDeliveryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_notify_max_retries(),
+            retry_backoff_secs: default_notify_retry_backoff_secs(),
+            max_backoff_secs: default_notify_max_backoff_secs(),
+            dead_letter_max_entries: default_dead_letter_max_entries(),
+        }
+    }
+}
+
+impl Default for ShadowConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            config_file: default_shadow_config_file(),
+            max_divergences: default_shadow_max_divergences(),
+        }
+    }
+}
+
+impl Default for BlackoutConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            session_open: default_session_open(),
+            session_close: default_session_close(),
+            after_open_minutes: default_blackout_minutes(),
+            before_close_minutes: default_blackout_minutes(),
+            events_file: None,
+        }
+    }
+}
+
+impl Default for TargetEngineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            method: default_weighting_method(),
+            lookback_samples: default_lookback_samples(),
+            min_samples: default_min_samples(),
+            recompute_interval_secs: default_recompute_interval_secs(),
+        }
+    }
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            commission_per_trade: 0.0,
+            spread_bps: default_spread_bps(),
+            capital_gains_rate: default_capital_gains_rate(),
+        }
+    }
+}
+
+impl Default for RebalanceBenefitConfig {
+    fn default() -> Self {
+        Self {
+            suppress_unprofitable: true,
+            stocks_volatility: default_stocks_volatility(),
+            bonds_volatility: default_bonds_volatility(),
+            correlation: default_stock_bond_correlation(),
+            risk_aversion: default_risk_aversion(),
+            horizon_years: default_benefit_horizon_years(),
+        }
+    }
+}
+
+impl Default for OpportunityScoringConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            drift_weight: default_drift_weight(),
+            momentum_weight: default_momentum_weight(),
+            staleness_weight: default_staleness_weight(),
+            cost_weight: default_cost_weight(),
+            momentum_scale_pct: default_momentum_scale_pct(),
+            stale_after_days: default_stale_after_days(),
+            max_cost_bps: default_max_cost_bps(),
+        }
+    }
+}
+
+impl Default for AdaptivePollingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_interval_ms: default_min_poll_ms(),
+            max_interval_ms: default_max_poll_ms(),
+            near_drift_ratio: default_near_drift_ratio(),
+            far_drift_ratio: default_far_drift_ratio(),
+            session_open: default_session_open(),
+            session_close: default_session_close(),
+        }
+    }
+}
+
+impl Default for VixHistoryConfig {
+    fn default() -> Self {
+        Self {
+            file: default_vix_history_file(),
+            start: None,
+            step_secs: default_vix_history_step_secs(),
+        }
+    }
+}
+
+impl Default for PolygonConfig {
+    fn default() -> Self {
+        Self {
+            api_key_env: default_polygon_api_key_env(),
+            base_url: default_polygon_base_url(),
+            cache_ttl_secs: default_polygon_cache_ttl_secs(),
+        }
+    }
+}
+
+impl Default for FinnhubConfig {
+    fn default() -> Self {
+        Self {
+            api_key_env: default_finnhub_api_key_env(),
+            base_url: default_finnhub_base_url(),
+            stream: true,
+            stream_url: default_finnhub_stream_url(),
+            stale_after_secs: default_finnhub_stale_after_secs(),
+            min_deposit_interval_ms: default_finnhub_min_deposit_interval_ms(),
+            cache_ttl_secs: default_finnhub_cache_ttl_secs(),
+        }
+    }
+}
+
+impl Default for YahooConfig {
+    fn default() -> Self {
+        Self {
+            fallback: true,
+            base_url: default_yahoo_base_url(),
+            cache_ttl_secs: default_yahoo_cache_ttl_secs(),
+        }
+    }
+}
+
+impl Default for VixFeedConfig {
+    fn default() -> Self {
+        Self {
+            cboe_url: default_cboe_vix_url(),
+            fred_url: default_fred_vix_url(),
+            cache_ttl_secs: default_vix_feed_cache_ttl_secs(),
+            simulate_on_failure: true,
+        }
+    }
+}
+
+impl Default for VixProxyConfig {
+    fn default() -> Self {
+        Self {
+            symbol: None,
+            leverage: default_proxy_leverage(),
+            reference_price: None,
+            reference_vix: default_proxy_reference_vix(),
+            futures_scale: default_proxy_futures_scale(),
+        }
+    }
+}
+
+impl Default for RealizedVolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            symbol: default_spy(),
+            max_samples: default_realized_vol_max_samples(),
+            min_returns: default_realized_vol_min_returns(),
+            vix_multiplier: default_vix_multiplier(),
+        }
+    }
+}
+
+impl Default for CrossCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: default_cross_check_provider(),
+            tolerance_pct: default_cross_check_tolerance(),
+        }
+    }
+}
+
+impl Default for DataQualityConfig {
+    fn default() -> Self {
+        Self {
+            min_score: default_min_score(),
+            max_quote_age_secs: default_max_quote_age_secs(),
+            cached_score: default_cached_score(),
+            simulated_score: default_simulated_score(),
+            max_disagreement_pct: default_max_disagreement_pct(),
+        }
+    }
+}
+
+/// File holding the overrides of `profile` for the base config at `path`
+pub fn profile_path(path: &Path, profile: &str) -> PathBuf {
+    path.parent().unwrap_or(Path::new("")).join("profiles").join(format!("{}.toml", profile))
+}
+
+/// Merge `overrides` into `base`: tables key by key, any other value
+/// (arrays of tables included) replaced whole
+pub fn merge_toml(base: &mut toml::Value, overrides: toml::Value) {
+    match (base, overrides) {
+        (toml::Value::Table(base), toml::Value::Table(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
     }
 }
 
+fn read_toml(path: &Path) -> Result<toml::Value> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| DriftGuardError::Config(format!("cannot read {}: {}", path.display(), e)))?;
+    let value = toml::from_str(&content)
+        .map_err(|e| DriftGuardError::Config(format!("invalid {}: {}", path.display(), e)))?;
+    Ok(value)
+}
+
 impl Config {
     /// Load configuration from file
     pub fn load(path: impl AsRef<Path>) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| DriftGuardError::Config(format!("cannot read {}: {}", path.display(), e)))?;
+        let config: Config = toml::from_str(&content)
+            .map_err(|e| DriftGuardError::Config(format!("invalid {}: {}", path.display(), e)))?;
         Ok(config)
     }
     
@@ -109,21 +2131,59 @@ impl Config {
         Self::load("config.toml")
     }
     
-    /// Get portfolio assets — returns multi-asset list or falls back to 2-asset legacy
+    /// Load `path` with the overrides of `profile` merged over it
+    pub fn load_profile(path: impl AsRef<Path>, profile: &str) -> Result<Self> {
+        let path = path.as_ref();
+        let overrides_path = profile_path(path, profile);
+        let mut merged = read_toml(path)?;
+        merge_toml(&mut merged, read_toml(&overrides_path)?);
+        let mut config: Config = merged.try_into().map_err(|e| {
+            DriftGuardError::Config(format!("invalid {} with {}: {}", path.display(), overrides_path.display(), e))
+        })?;
+        config.profile = Some(profile.to_string());
+        Ok(config)
+    }
+    
+    /// Get portfolio assets — returns multi-asset list, the members of the
+    /// asset groups (at their share of the portfolio), or falls back to 2-asset legacy
     pub fn assets(&self) -> Vec<AssetConfig> {
         if !self.portfolio.assets.is_empty() {
             self.portfolio.assets.clone()
+        } else if !self.portfolio.groups.is_empty() {
+            self.portfolio
+                .groups
+                .iter()
+                .flat_map(|group| {
+                    group.members.iter().map(|member| AssetConfig {
+                        symbol: member.symbol.clone(),
+                        name: member.name.clone(),
+                        target_pct: group.target_pct * member.target_pct / 100.0,
+                        min_pct: None,
+                        max_pct: None,
+                        exchange: member.exchange.clone(),
+                        currency: member.currency.clone(),
+                    })
+                })
+                .collect()
         } else {
             vec![
                 AssetConfig {
                     symbol: self.portfolio.stocks_symbol.clone(),
                     name: "Stocks".to_string(),
                     target_pct: self.portfolio.default_stocks_pct,
+                    min_pct: None,
+                    max_pct: None,
+                    exchange: None,
+                    currency: None,
                 },
                 AssetConfig {
                     symbol: self.portfolio.bonds_symbol.clone(),
                     name: "Bonds".to_string(),
                     target_pct: self.portfolio.default_bonds_pct,
+                    min_pct: None,
+                    max_pct: None,
+                    exchange: None,
+                    currency: None,
                 },
             ]
         }
@@ -136,6 +2196,8 @@ impl Config {
             "rebalance_opportunity" => self.pheromones.rebalance_opportunity_decay,
             "execution_permit" => self.pheromones.execution_permit_decay,
             "trade_executed" => self.pheromones.trade_executed_decay,
+            "data_quality" => self.pheromones.data_quality_decay,
+            "drift_forecast" => self.pheromones.drift_forecast_decay,
             _ => 0.3,
         }
     }
@@ -147,6 +2209,8 @@ impl Config {
             "rebalance_opportunity" => self.thresholds.rebalance_opportunity,
             "execution_permit" => self.thresholds.execution_permit,
             "trade_executed" => self.thresholds.trade_executed,
+            "data_quality" => self.thresholds.data_quality,
+            "drift_forecast" => self.thresholds.drift_forecast,
             _ => 0.5,
         }
     }
@@ -160,12 +2224,17 @@ impl Default for Config {
                 rebalance_opportunity_decay: 0.2,
                 execution_permit_decay: 0.5,
                 trade_executed_decay: 0.1,
+                data_quality_decay: default_data_quality_decay(),
+                drift_forecast_decay: default_drift_forecast_decay(),
+                recent_deposits: default_recent_deposits(),
             },
             thresholds: ThresholdConfig {
                 price_freshness: 0.7,
                 rebalance_opportunity: 0.6,
                 execution_permit: 0.5,
                 trade_executed: 0.3,
+                data_quality: default_data_quality_threshold(),
+                drift_forecast: default_drift_forecast_threshold(),
             },
             portfolio: PortfolioConfig {
                 assets: vec![
@@ -173,30 +2242,145 @@ impl Default for Config {
                         symbol: "SPY".to_string(),
                         name: "S&P 500 ETF".to_string(),
                         target_pct: 60.0,
+                        min_pct: None,
+                        max_pct: None,
+                        exchange: None,
+                        currency: None,
                     },
                     AssetConfig {
                         symbol: "BND".to_string(),
                         name: "Total Bond ETF".to_string(),
                         target_pct: 40.0,
+                        min_pct: None,
+                        max_pct: None,
+                        exchange: None,
+                        currency: None,
                     },
                 ],
+                groups: Vec::new(),
                 stocks_symbol: "SPY".to_string(),
                 bonds_symbol: "BND".to_string(),
                 default_stocks_pct: 60.0,
                 default_bonds_pct: 40.0,
                 drift_threshold: 5.0,
                 initial_balance: 100000.0,
+                base_currency: default_base_currency(),
+                calendar_rebalance: CalendarRebalanceConfig::default(),
+                glide_path: GlidePathConfig::default(),
             },
             market: MarketConfig {
+                provider: default_market_provider(),
+                polygon: PolygonConfig::default(),
+                finnhub: FinnhubConfig::default(),
+                yahoo: YahooConfig::default(),
+                providers: ProvidersConfig::default(),
                 poll_interval_ms: 5000,
                 vix_high_threshold: 25.0,
                 vix_low_threshold: 15.0,
                 vix_source: "simulation".to_string(),
+                vix_history: VixHistoryConfig::default(),
+                vix_proxy: VixProxyConfig::default(),
+                vix_feed: VixFeedConfig::default(),
+                cross_check: CrossCheckConfig::default(),
+                adaptive_polling: AdaptivePollingConfig::default(),
+                realized_vol: RealizedVolConfig::default(),
+                fx_rates: BTreeMap::new(),
             },
             agent: AgentConfig {
                 sniff_interval_ms: 500,
+                roles: default_agent_roles(),
+                run_services: true,
+                activity_max_entries: default_activity_max_entries(),
+                metrics_flush_ms: default_metrics_flush_ms(),
+                metrics_heartbeat_secs: default_metrics_heartbeat_secs(),
+                runtime_report_secs: default_runtime_report_secs(),
             },
             trade_log: TradeLogConfig::default(),
+            data_quality: DataQualityConfig::default(),
+            costs: CostConfig::default(),
+            opportunity_scoring: OpportunityScoringConfig::default(),
+            rebalance_benefit: RebalanceBenefitConfig::default(),
+            allocation: AllocationConfig::default(),
+            target_engine: TargetEngineConfig::default(),
+            optimizer: OptimizerConfig::default(),
+            blackout: BlackoutConfig::default(),
+            valuation: ValuationConfig::default(),
+            execution: ExecutionConfig::default(),
+            reconciliation: ReconciliationConfig::default(),
+            holdings_import: HoldingsImportConfig::default(),
+            statements: StatementsConfig::default(),
+            benchmarks: BenchmarksConfig::default(),
+            decay_scheduler: DecaySchedulerConfig::default(),
+            pipeline: PipelineConfig::default(),
+            swarm: SwarmConfig::default(),
+            dashboard: DashboardConfig::default(),
+            audit: AuditConfig::default(),
+            event_sampling: EventSamplingConfig::default(),
+            exchange: ExchangeConfig::default(),
+            system_mode: SystemModeConfig::default(),
+            risk_index: RiskIndexConfig::default(),
+            correlation: CorrelationConfig::default(),
+            scripting: ScriptingConfig::default(),
+            bridge: BridgeConfig::default(),
+            ledger: LedgerConfig::default(),
+            reporting: ReportingConfig::default(),
+            simulation: SimulationConfig::default(),
+            soak: SoakConfig::default(),
+            shadow: ShadowConfig::default(),
+            forecast: ForecastConfig::default(),
+            wash_sale: WashSaleConfig::default(),
+            order_queue: OrderQueueConfig::default(),
+            warmup: WarmupConfig::default(),
+            retention: RetentionConfig::default(),
+            alerting: AlertingConfig::default(),
+            logging: LoggingConfig::default(),
+            auth: AuthConfig::default(),
+            alerts: Vec::new(),
+            profile: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_shipped_config_parses() {
+        let config: Config = toml::from_str(include_str!("../../config.toml")).unwrap();
+        assert_eq!(config.portfolio.drift_threshold, 5.0);
+        assert_eq!(config.portfolio.assets.len(), 4);
+        assert!(config.alerts.iter().all(|rule| !rule.condition.is_empty()));
+
+        // The older `*_utc` names still parse
+        let legacy: OrderQueueConfig = toml::from_str("window_open_utc = \"13:30\"").unwrap();
+        assert_eq!(legacy.window_open, "13:30");
+    }
+    
+    #[test]
+    fn test_shipped_profiles_merge() {
+        let profile = |overrides: &str| {
+            let mut merged: toml::Value = toml::from_str(include_str!("../../config.toml")).unwrap();
+            merge_toml(&mut merged, toml::from_str(overrides).unwrap());
+            merged.try_into::<Config>().unwrap()
+        };
+        
+        let live = profile(include_str!("../../profiles/live.toml"));
+        assert!(!live.system_mode.permit_in_simulation);
+        assert!(live.auth.required && live.execution.queue.enabled);
+        // Untouched settings, including the rest of an overridden table, stay
+        assert_eq!(live.execution.queue.max_orders_per_minute, 60);
+        assert_eq!(live.portfolio.assets.len(), 4);
+        
+        let dev = profile(include_str!("../../profiles/dev.toml"));
+        assert_eq!(dev.simulation.seed, Some(42));
+        assert_eq!(dev.pheromones.trade_executed_decay, 0.1);
+        assert_eq!(profile(include_str!("../../profiles/demo.toml")).market.vix_source, "history");
+        assert_eq!(profile(include_str!("../../profiles/paper.toml")).warmup.history_days, 60);
+        
+        // Arrays are replaced, not appended to
+        let single = profile("[[portfolio.assets]]\nsymbol = \"VT\"\ntarget_pct = 100\n");
+        assert_eq!(single.portfolio.assets.len(), 1);
+        assert_eq!(profile_path(Path::new("deploy/config.toml"), "live"), Path::new("deploy/profiles/live.toml"));
+    }
+}
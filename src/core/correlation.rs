@@ -0,0 +1,217 @@
+//! Correlation Monitor
+//!
+//! A portfolio of several assets is diversified only while they don't move
+//! together, and in a selloff correlations tend to jump toward one. A task
+//! in the services process correlates the daily returns of every pair of
+//! assets over the last `[correlation] window` sessions (today's live price
+//! standing in for today's close), every `interval_secs`, and records the
+//! average and highest pairwise correlation (`risk:correlation`, with a
+//! series for the dashboard in `risk:correlation:history`, both at
+//! `GET /api/correlation`).
+//!
+//! When the average reaches `threshold` a spike begins: it is announced on
+//! `channel`, and lasts until the average falls below `release_below`.
+//! While a fresh reading shows a spike, the Guardian's volatility ceiling
+//! is multiplied by `guardian_caution_factor`. Alert rules see the latest
+//! values as `avg_correlation` and `max_correlation`.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{debug, info, warn};
+
+use crate::core::config::CorrelationConfig;
+use crate::core::risk_index::{self, AssetSeries};
+use crate::core::{notify_queue, Blackboard, Config};
+
+/// Correlation of one pair of assets' daily returns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairCorrelation {
+    pub a: String,
+    pub b: String,
+    pub correlation: f64,
+}
+
+/// Rolling correlations at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorrelationReading {
+    /// Mean over every pair of assets
+    pub average: f64,
+    pub max: f64,
+    /// Every pair, most correlated first
+    pub pairs: Vec<PairCorrelation>,
+    /// Daily returns correlated
+    pub returns: usize,
+    /// Whether diversification has broken down (see the module docs)
+    pub spiking: bool,
+    pub computed_at: DateTime<Utc>,
+}
+
+impl CorrelationReading {
+    /// Whether it was computed within `max_age_secs` of `now`
+    pub fn is_fresh(&self, now: DateTime<Utc>, max_age_secs: u64) -> bool {
+        (now - self.computed_at).num_seconds() <= max_age_secs as i64
+    }
+}
+
+/// Pearson correlation of two equally long samples (0 when either is flat)
+fn pearson(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let (mean_x, mean_y) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(y) {
+        cov += (a - mean_x) * (b - mean_y);
+        var_x += (a - mean_x).powi(2);
+        var_y += (b - mean_y).powi(2);
+    }
+    let scale = (var_x * var_y).sqrt();
+    if scale > 0.0 { (cov / scale).clamp(-1.0, 1.0) } else { 0.0 }
+}
+
+/// The reading over the last `config.window` returns common to every
+/// asset; `None` with fewer than two assets or too little history. A spike
+/// carries on from `previous` until the average drops below the release.
+pub fn compute(
+    series: &[AssetSeries],
+    config: &CorrelationConfig,
+    previous: Option<&CorrelationReading>,
+    now: DateTime<Utc>,
+) -> Option<CorrelationReading> {
+    let window = config.window.max(2);
+    let returns = risk_index::common_returns(series);
+    if series.len() < 2 || returns.len() < window {
+        return None;
+    }
+    let recent = &returns[returns.len() - window..];
+    let column = |i: usize| recent.iter().map(|r| r[i]).collect::<Vec<f64>>();
+
+    let mut pairs = Vec::new();
+    for i in 0..series.len() {
+        for j in i + 1..series.len() {
+            pairs.push(PairCorrelation {
+                a: series[i].symbol.clone(),
+                b: series[j].symbol.clone(),
+                correlation: pearson(&column(i), &column(j)),
+            });
+        }
+    }
+    pairs.sort_by(|x, y| y.correlation.total_cmp(&x.correlation));
+
+    let average = pairs.iter().map(|p| p.correlation).sum::<f64>() / pairs.len() as f64;
+    let was_spiking = previous.is_some_and(|reading| reading.spiking);
+    Some(CorrelationReading {
+        average,
+        max: pairs[0].correlation,
+        spiking: average >= config.threshold || (was_spiking && average >= config.release_below),
+        pairs,
+        returns: window,
+        computed_at: now,
+    })
+}
+
+/// Whether the Guardian should be cautious: a fresh reading shows a spike
+pub async fn spike_active(board: &Blackboard, config: &Config) -> Result<bool> {
+    let config = &config.correlation;
+    if !config.enabled {
+        return Ok(false);
+    }
+    Ok(board
+        .get_correlation()
+        .await?
+        .is_some_and(|reading| reading.spiking && reading.is_fresh(Utc::now(), config.stale_after_secs)))
+}
+
+/// Recompute the correlations every `[correlation] interval_secs`
+pub async fn run_monitor(board: Arc<Blackboard>) -> Result<()> {
+    let config = &board.config().correlation;
+    let mut ticker = interval(Duration::from_secs(config.interval_secs.max(1)));
+
+    info!("🔗 Correlation monitor started (every {}s, {} sessions)", config.interval_secs, config.window);
+
+    loop {
+        ticker.tick().await;
+        if let Err(e) = check(&board, config).await {
+            warn!("🔗 Correlation monitor: {:#}", e);
+        }
+    }
+}
+
+/// One pass: compute, store and announce a spike as it begins
+async fn check(board: &Blackboard, config: &CorrelationConfig) -> Result<()> {
+    let (series, missing) = risk_index::read_series(board, board.config()).await?;
+    if !missing.is_empty() {
+        debug!("🔗 Correlation monitor: No daily history for {}", missing.join(", "));
+        return Ok(());
+    }
+    let previous = board.get_correlation().await?;
+    let Some(reading) = compute(&series, config, previous.as_ref(), Utc::now()) else {
+        debug!("🔗 Correlation monitor: Not enough common history yet");
+        return Ok(());
+    };
+    board.put_correlation(&reading, config.history_len).await?;
+
+    let was_spiking = previous.is_some_and(|previous| previous.spiking);
+    let top = &reading.pairs[0];
+    if reading.spiking && !was_spiking {
+        let message = format!(
+            "Diversification breakdown: average correlation {:.2} over {} sessions (threshold {:.2}); {}/{} at {:.2}",
+            reading.average, reading.returns, config.threshold, top.a, top.b, top.correlation
+        );
+        let payload = serde_json::to_value(&reading)?;
+        notify_queue::enqueue(board, config.channel, "correlation:spike", &message, payload).await;
+    } else if was_spiking && !reading.spiking {
+        info!("🔗 Correlations back to normal (average {:.2})", reading.average);
+    } else {
+        debug!("🔗 Average correlation {:.2}, highest {}/{} {:.2}", reading.average, top.a, top.b, top.correlation);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::daily_history::DailyBar;
+    use chrono::NaiveDate;
+
+    fn series(symbol: &str, closes: &[f64]) -> AssetSeries {
+        let start = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let bars = closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| DailyBar { date: start + chrono::Duration::days(i as i64), close: *close })
+            .collect();
+        AssetSeries { symbol: symbol.to_string(), weight: 50.0, bars }
+    }
+
+    #[test]
+    fn test_rolling_correlation_spike() {
+        let now = Utc::now();
+        let config = CorrelationConfig { window: 4, threshold: 0.8, release_below: 0.5, ..Default::default() };
+        let stocks = series("SPY", &[100.0, 102.0, 99.0, 103.0, 100.0, 104.0]);
+        let mirrored = series("TLT", &[100.0, 98.0, 101.0, 97.0, 100.0, 96.0]);
+        let together = series("QQQ", &[200.0, 204.0, 198.0, 206.0, 200.0, 208.0]);
+
+        // Offsetting assets are diversified
+        let hedged = compute(&[stocks.clone(), mirrored], &config, None, now).unwrap();
+        assert!(hedged.average < -0.9, "{:?}", hedged);
+        assert!(!hedged.spiking);
+        assert_eq!(hedged.returns, 4);
+
+        // Moving together is a spike, reported most correlated pair first
+        let spike = compute(&[stocks.clone(), together.clone()], &config, None, now).unwrap();
+        assert!(spike.spiking && spike.max > 0.99);
+        assert_eq!((spike.pairs[0].a.as_str(), spike.pairs[0].b.as_str()), ("SPY", "QQQ"));
+
+        // Between release and threshold a spike persists but doesn't start
+        let middling = CorrelationReading { average: 0.6, spiking: true, ..spike.clone() };
+        let loose = CorrelationConfig { threshold: 1.5, ..config.clone() };
+        assert!(compute(&[stocks.clone(), together.clone()], &loose, Some(&middling), now).unwrap().spiking);
+        assert!(!compute(&[stocks.clone(), together], &loose, None, now).unwrap().spiking);
+
+        // Too little history, or a single asset
+        assert!(compute(&[stocks.clone(), series("GLD", &[1.0, 2.0, 3.0])], &config, None, now).is_none());
+        assert!(compute(&[stocks], &config, None, now).is_none());
+    }
+}
@@ -0,0 +1,269 @@
+//! Daily Summaries
+//!
+//! The daily snapshot records where the portfolio ended the day; the rest of
+//! the day is spread over the trade log, the Guardian's activity stream and
+//! the cash flows. Right after the snapshot, the statement scheduler
+//! consolidates them into one `DailySummary` (`summaries:daily:<date>`):
+//! the day's trades, permits issued and blocks, end-of-day drift and P&L.
+//! A new summary is sent to `[statements] summary_channels` and pushed to
+//! dashboards. Permits are counted from the Guardian's action counter;
+//! blocks are counted per episode, since consecutive identical blocks are
+//! recorded once in its activity stream.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::core::activity::{self, AgentActivity};
+use crate::core::alerts::AlertChannel;
+use crate::core::blackboard::TradeLogEntry;
+use crate::core::money::{self, MoneyFormat, ReportLocale};
+use crate::core::performance::CashFlow;
+use crate::core::statements::DailySnapshot;
+use crate::core::{bootstrap, notify_queue, Blackboard};
+use crate::export::{self, DateRange};
+
+/// One trading day, consolidated; written once and never updated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailySummary {
+    pub date: NaiveDate,
+    /// Trades executed that day, oldest first
+    pub trades: Vec<TradeLogEntry>,
+    /// Sum of traded notional
+    pub trade_volume: f64,
+    /// Execution permits the Guardian issued
+    pub permits: u64,
+    /// Times the Guardian blocked a trade
+    pub blocks: u64,
+    /// Previous day's closing value; none on the first snapshotted day
+    pub opening_value: Option<f64>,
+    pub closing_value: f64,
+    /// Money added (or withdrawn) that day
+    pub net_cash_flows: f64,
+    /// Change in value net of cash flows
+    pub pnl: Option<f64>,
+    pub pnl_pct: Option<f64>,
+    pub stocks_pct: f64,
+    pub target_stocks_pct: f64,
+    /// End-of-day stock allocation minus target (percentage points)
+    pub final_drift_pct: f64,
+    pub drawdown_pct: f64,
+    pub currency: String,
+    pub generated_at: String,
+}
+
+impl DailySummary {
+    /// Consolidate the day of `snapshot`. `opening` is the previous day's
+    /// snapshot; `guardian` the Guardian's activity stream (most recent
+    /// first) and `flows` the recorded cash flows, both of any dates.
+    pub fn build(
+        snapshot: &DailySnapshot,
+        opening: Option<&DailySnapshot>,
+        trades: Vec<TradeLogEntry>,
+        guardian: &[AgentActivity],
+        flows: &[CashFlow],
+        currency: &str,
+    ) -> Self {
+        let date = snapshot.date;
+        let start = date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let end = start + Duration::days(1);
+        let on_date = |at: DateTime<Utc>| at >= start && at < end;
+        
+        let last_ms = end - Duration::milliseconds(1);
+        let permits = activity::summarize("Guardian", guardian.to_vec(), last_ms, last_ms - start, 1).activations;
+        let blocks = guardian
+            .iter()
+            .filter(|entry| on_date(entry.timestamp) && entry.action.starts_with("BLOCKED"))
+            .count() as u64;
+        let net_cash_flows: f64 = flows
+            .iter()
+            .filter(|flow| {
+                DateTime::parse_from_rfc3339(&flow.timestamp).is_ok_and(|at| on_date(at.with_timezone(&Utc)))
+            })
+            .map(|flow| flow.amount)
+            .sum();
+        
+        let opening_value = opening.map(|s| s.total_value);
+        let pnl = opening_value.map(|open| snapshot.total_value - open - net_cash_flows);
+        let pnl_pct = opening_value
+            .zip(pnl)
+            .filter(|(open, _)| *open > 0.0)
+            .map(|(open, pnl)| pnl / open * 100.0);
+        
+        Self {
+            date,
+            trade_volume: money::to_f64(money::sum(trades.iter().map(|t| t.amount), currency)),
+            trades,
+            permits,
+            blocks,
+            opening_value,
+            closing_value: snapshot.total_value,
+            net_cash_flows,
+            pnl,
+            pnl_pct,
+            stocks_pct: snapshot.stocks_pct,
+            target_stocks_pct: snapshot.target_stocks_pct,
+            final_drift_pct: snapshot.drift_pct,
+            drawdown_pct: snapshot.drawdown_pct,
+            currency: currency.to_string(),
+            generated_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// One-line notification text, amounts formatted for `locale`
+    pub fn message(&self, swarm: &str, locale: ReportLocale) -> String {
+        let money = MoneyFormat::new(&self.currency, locale);
+        let amount = |v: f64| money.format(money::from_f64(v));
+        let pnl = match self.pnl.zip(self.pnl_pct) {
+            Some((pnl, pct)) => format!(
+                "{}{} ({:+.2}%)",
+                if pnl < 0.0 { "-" } else { "+" },
+                amount(pnl.abs()),
+                pct
+            ),
+            None => "first day".to_string(),
+        };
+        format!(
+            "📅 DriftGuard [{}] {}: {} ({}), {} trades ({}), {} permits, {} blocks, drift {:+.2} pts",
+            swarm,
+            self.date,
+            amount(self.closing_value),
+            pnl,
+            self.trades.len(),
+            amount(self.trade_volume),
+            self.permits,
+            self.blocks,
+            self.final_drift_pct
+        )
+    }
+}
+
+/// Build, store and send the summary of `date`, whose snapshot has been
+/// taken (no-op if it already exists or there is no snapshot)
+pub async fn consolidate(board: &Blackboard, date: NaiveDate) -> Result<()> {
+    let Some(snapshot) = board.get_daily_snapshots(&[date]).await?.pop() else {
+        debug!("Daily summary: No snapshot for {}", date);
+        return Ok(());
+    };
+    let opening = match date.pred_opt() {
+        Some(day) => board.get_daily_snapshots(&[day]).await?.pop(),
+        None => None,
+    };
+    let config = board.config();
+    let trades = export::collect_trades(board, DateRange { from: Some(date), to: Some(date) }).await?;
+    let guardian = board.get_agent_activity("guardian", config.agent.activity_max_entries).await?;
+    let flows = board.get_cash_flows(config.valuation.history_max_entries).await?;
+    let currency = bootstrap::current(board).await?.base_currency;
+    
+    let summary = DailySummary::build(&snapshot, opening.as_ref(), trades, &guardian, &flows, &currency);
+    if !board.put_daily_summary(&summary).await? {
+        debug!("Daily summary: {} already exists", date);
+        return Ok(());
+    }
+    notify(board, &summary).await;
+    Ok(())
+}
+
+/// Send a new summary to each of `[statements] summary_channels`
+async fn notify(board: &Blackboard, summary: &DailySummary) {
+    let config = board.config();
+    let message = summary.message(board.swarm_name(), config.reporting.locale);
+    for &channel in &config.statements.summary_channels {
+        if channel == AlertChannel::Log {
+            info!("{}", message);
+            continue;
+        }
+        let payload = serde_json::json!({
+            "message": message,
+            "swarm": board.swarm_name(),
+            "summary": summary,
+        });
+        notify_queue::enqueue(board, channel, &format!("daily_summary:{}", summary.date), &message, payload).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn snapshot(date: NaiveDate, total_value: f64) -> DailySnapshot {
+        DailySnapshot {
+            date,
+            taken_at: String::new(),
+            total_value,
+            stocks_value: total_value * 0.6,
+            bonds_value: total_value * 0.4,
+            stocks_pct: 61.5,
+            bonds_pct: 38.5,
+            target_stocks_pct: 60.0,
+            target_bonds_pct: 40.0,
+            drift_pct: 1.5,
+            drawdown_pct: 0.4,
+        }
+    }
+
+    #[test]
+    fn test_build() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let at = |day: u32, hour: u32| {
+            NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(hour, 0, 0).unwrap().and_utc()
+        };
+        let activity = |timestamp, action_count, action: &str| AgentActivity {
+            timestamp,
+            is_active: true,
+            action_count,
+            action: action.to_string(),
+        };
+        // Most recent first: one permit the day before, two on the day
+        // (one batched into a single entry) and two blocks
+        let guardian = [
+            activity(at(3, 9), 5, "Permit issued (VIX 15.0)"),
+            activity(at(2, 16), 4, "BLOCKED (VIX 31.0)"),
+            activity(at(2, 15), 4, "Permit issued (VIX 18.0)"),
+            activity(at(2, 14), 2, "BLOCKED (VIX 35.0)"),
+            activity(at(1, 15), 2, "Permit issued (VIX 14.0)"),
+            activity(at(1, 14), 1, "Permit issued (VIX 14.0)"),
+        ];
+        let flows = [
+            CashFlow { timestamp: at(2, 10).to_rfc3339(), amount: 500.0, source: "deposit".to_string() },
+            CashFlow { timestamp: at(1, 10).to_rfc3339(), amount: 100_000.0, source: "portfolio init".to_string() },
+        ];
+        let trade = TradeLogEntry {
+            id: "t1".to_string(),
+            timestamp: at(2, 15).to_rfc3339(),
+            action: "SELL".to_string(),
+            symbol: "SPY".to_string(),
+            amount: Decimal::from(1_200),
+            price: Decimal::from(512),
+            portfolio_value: Decimal::from(101_500),
+            drift_before: Decimal::from(6),
+            drift_after: Decimal::ZERO,
+            explanation: None,
+            stocks_delta: Decimal::from(-1_200),
+            bonds_delta: Decimal::from(1_200),
+            run_id: None,
+            skipped: None,
+            plan_hash: None,
+        };
+        
+        let opening = snapshot(date.pred_opt().unwrap(), 100_000.0);
+        let summary = DailySummary::build(&snapshot(date, 101_500.0), Some(&opening), vec![trade], &guardian, &flows, "USD");
+        assert_eq!(summary.permits, 2);
+        assert_eq!(summary.blocks, 2);
+        assert_eq!(summary.trade_volume, 1_200.0);
+        assert_eq!(summary.net_cash_flows, 500.0);
+        assert_eq!(summary.pnl, Some(1_000.0));
+        assert_eq!(summary.pnl_pct, Some(1.0));
+        assert_eq!(summary.final_drift_pct, 1.5);
+        assert_eq!(
+            summary.message("default", ReportLocale::EN_US),
+            "📅 DriftGuard [default] 2026-03-02: $101,500.00 (+$1,000.00 (+1.00%)), 1 trades ($1,200.00), 2 permits, 2 blocks, drift +1.50 pts"
+        );
+        
+        let first = DailySummary::build(&snapshot(date, 101_500.0), None, Vec::new(), &[], &[], "USD");
+        assert_eq!(first.pnl, None);
+        assert!(first.message("default", ReportLocale::EN_US).contains("(first day)"));
+    }
+}
@@ -0,0 +1,112 @@
+//! Decay Scheduler
+//!
+//! `Decayed` events normally fire only when an agent sniffs a stale
+//! pheromone. This task works out when each pheromone will cross below
+//! its activation threshold and announces the transition at that moment,
+//! so observers see decay even while every agent is dormant. Optionally
+//! the decayed key is removed from Redis.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, info, warn};
+
+use crate::core::physics::{Pheromone, PheromoneType};
+use crate::core::Blackboard;
+
+/// What the scheduler should do with one pheromone right now
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecayStep {
+    /// Still active; it crosses the threshold after this many seconds
+    Wait(f64),
+    /// Below threshold and not yet announced
+    Announce,
+    /// Already announced (or absent)
+    Idle,
+}
+
+/// Decide the next step for a pheromone, given the deposit (by creation
+/// time) whose decay was last announced
+pub fn next_step(pheromone: &Pheromone, threshold: f64, announced: Option<&DateTime<Utc>>) -> DecayStep {
+    if announced == Some(&pheromone.created_at) {
+        return DecayStep::Idle;
+    }
+    match pheromone.time_until_inactive(threshold) {
+        Some(secs) => DecayStep::Wait(secs),
+        None => DecayStep::Announce,
+    }
+}
+
+/// Announce pheromone decay at the moment each one crosses its threshold
+pub async fn run_decay_scheduler(board: Arc<Blackboard>) -> Result<()> {
+    let settings = &board.config().decay_scheduler;
+    let max_sleep = Duration::from_millis(settings.max_sleep_ms.max(10));
+    let mut announced: HashMap<PheromoneType, DateTime<Utc>> = HashMap::new();
+    let mut first_pass = true;
+    
+    info!(
+        "🍂 Decay scheduler started (key cleanup {})",
+        if settings.cleanup_keys { "on" } else { "off" }
+    );
+    
+    loop {
+        let mut next_wake = max_sleep;
+        
+        for ptype in PheromoneType::ALL {
+            let pheromone = match board.get_pheromone(ptype).await {
+                Ok(Some(pheromone)) => pheromone,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("Decay scheduler: Failed to read {}: {}", ptype.label(), e);
+                    continue;
+                }
+            };
+            
+            match next_step(&pheromone, ptype.threshold(board.config()), announced.get(&ptype)) {
+                DecayStep::Wait(secs) => {
+                    next_wake = next_wake.min(Duration::from_secs_f64(secs));
+                }
+                DecayStep::Announce => {
+                    // Pheromones already stale at startup decayed while nobody
+                    // was watching; record them without a belated event
+                    if !first_pass {
+                        board.announce_decay(ptype, &pheromone, settings.cleanup_keys).await?;
+                    } else {
+                        debug!("Decay scheduler: {} already inactive at startup", ptype.label());
+                    }
+                    announced.insert(ptype, pheromone.created_at);
+                }
+                DecayStep::Idle => {}
+            }
+        }
+        
+        first_pass = false;
+        // Wake just after the earliest crossing so it reads as inactive
+        sleep(next_wake + Duration::from_millis(5)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_next_step() {
+        let fresh = Pheromone::new("test", 1.0, 0.5);
+        match next_step(&fresh, 0.5, None) {
+            // ln(2) / 0.5 ≈ 1.386s until it halves
+            DecayStep::Wait(secs) => assert!((secs - 1.386).abs() < 0.05),
+            other => panic!("expected Wait, got {:?}", other),
+        }
+        
+        let mut stale = Pheromone::new("test", 1.0, 0.5);
+        stale.created_at = Utc::now() - chrono::Duration::seconds(10);
+        assert_eq!(next_step(&stale, 0.5, None), DecayStep::Announce);
+        assert_eq!(next_step(&stale, 0.5, Some(&stale.created_at)), DecayStep::Idle);
+        
+        // A newer deposit after an announced one is tracked again
+        assert!(matches!(next_step(&fresh, 0.5, Some(&stale.created_at)), DecayStep::Wait(_)));
+    }
+}
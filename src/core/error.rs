@@ -0,0 +1,109 @@
+//! Error Taxonomy
+//!
+//! `DriftGuardError` sorts failures into categories consumers can react to
+//! (retry later on a provider outage, page on a store outage, fix the
+//! config) without matching on message text. Functions still return
+//! `anyhow::Result`; a `DriftGuardError` raised inside one travels with it,
+//! and `classify` recovers a category for any error from its known causes
+//! (Redis, HTTP, TOML). Agents keep the last one in their metrics and
+//! report it as an `Error` event, which reaches the dashboard and the
+//! bridge's alerts topic.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+#[serde(tag = "category", content = "message", rename_all = "snake_case")]
+pub enum DriftGuardError {
+    /// Market data provider unreachable or returning unusable data
+    #[error("provider error: {0}")]
+    Provider(String),
+    /// Blackboard (Redis) unreachable or holding unreadable data
+    #[error("store error: {0}")]
+    Store(String),
+    /// A trade could not be executed or committed
+    #[error("execution error: {0}")]
+    Execution(String),
+    /// Configuration missing or invalid
+    #[error("config error: {0}")]
+    Config(String),
+    /// Data rejected by quality checks
+    #[error("data quality error: {0}")]
+    DataQuality(String),
+    /// Anything without a more specific category
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl DriftGuardError {
+    /// Category name, as serialized
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::Provider(_) => "provider",
+            Self::Store(_) => "store",
+            Self::Execution(_) => "execution",
+            Self::Config(_) => "config",
+            Self::DataQuality(_) => "data_quality",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Provider(m)
+            | Self::Store(m)
+            | Self::Execution(m)
+            | Self::Config(m)
+            | Self::DataQuality(m)
+            | Self::Internal(m) => m,
+        }
+    }
+
+    /// Category of any error: a `DriftGuardError` in its chain, else one
+    /// inferred from a known cause, else `fallback` (the caller's context,
+    /// e.g. `DriftGuardError::Provider` for a failed fetch). The message is
+    /// the whole chain.
+    pub fn classify(error: &anyhow::Error, fallback: fn(String) -> Self) -> Self {
+        let message = format!("{:#}", error);
+        for cause in error.chain() {
+            if let Some(typed) = cause.downcast_ref::<DriftGuardError>() {
+                return typed.clone();
+            }
+            if cause.is::<redis::RedisError>() {
+                return Self::Store(message);
+            }
+            if cause.is::<reqwest::Error>() {
+                return Self::Provider(message);
+            }
+            if cause.is::<toml::de::Error>() {
+                return Self::Config(message);
+            }
+        }
+        fallback(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_classify() {
+        // A typed error survives added context
+        let typed = Err::<(), _>(DriftGuardError::Config("no stocks_symbol".to_string()))
+            .context("loading config.toml")
+            .unwrap_err();
+        assert_eq!(DriftGuardError::classify(&typed, DriftGuardError::Internal).category(), "config");
+        
+        // Known causes are recognized; anything else takes the fallback
+        let toml = anyhow::Error::new(toml::from_str::<toml::Value>("= broken").unwrap_err());
+        assert_eq!(DriftGuardError::classify(&toml, DriftGuardError::Internal).category(), "config");
+        let other = anyhow::anyhow!("venue rejected order").context("BUY SPY leg");
+        let classified = DriftGuardError::classify(&other, DriftGuardError::Execution);
+        assert_eq!(classified, DriftGuardError::Execution("BUY SPY leg: venue rejected order".to_string()));
+        
+        let json = serde_json::to_value(&classified).unwrap();
+        assert_eq!(json["category"], "execution");
+    }
+}
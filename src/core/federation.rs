@@ -0,0 +1,135 @@
+//! Swarm Federation
+//!
+//! Several named swarms — each with its own config and portfolio — can
+//! coordinate on one Redis. Every blackboard key of a swarm other than
+//! `default` is prefixed with `<name>:`, so the default swarm keeps the
+//! plain keys it has always used. Each running swarm also publishes a
+//! heartbeat to the shared `federation:swarms` hash, which the server
+//! lists at `GET /api/swarms` for a multi-tenant dashboard.
+
+use anyhow::{ensure, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{info, warn};
+
+use crate::core::Blackboard;
+
+/// Name of the swarm whose keys are not prefixed
+pub const DEFAULT_SWARM: &str = "default";
+
+/// Redis hash (swarm name → heartbeat JSON) shared by all swarms
+pub const FEDERATION_KEY: &str = "federation:swarms";
+
+/// Key prefix for a swarm, rejecting names that could collide with other keys
+pub fn key_prefix(name: &str) -> Result<String> {
+    ensure!(
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+        "Invalid swarm name '{}': use letters, digits, '-' or '_'",
+        name
+    );
+    
+    if name == DEFAULT_SWARM {
+        Ok(String::new())
+    } else {
+        Ok(format!("{}:", name))
+    }
+}
+
+/// Liveness record a swarm publishes to the federation registry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwarmHeartbeat {
+    pub name: String,
+    pub started_at: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// Port serving this swarm's dashboard API
+    pub dashboard_port: u16,
+    pub portfolio_value: Option<Decimal>,
+}
+
+/// A registered swarm as reported by `GET /api/swarms`
+#[derive(Debug, Clone, Serialize)]
+pub struct SwarmStatus {
+    #[serde(flatten)]
+    pub heartbeat: SwarmHeartbeat,
+    /// Heartbeat seen within the timeout
+    pub alive: bool,
+    pub age_secs: f64,
+}
+
+impl SwarmHeartbeat {
+    pub fn status(self, now: DateTime<Utc>, timeout_secs: u64) -> SwarmStatus {
+        let age_secs = (now - self.last_seen).num_milliseconds().max(0) as f64 / 1000.0;
+        SwarmStatus {
+            alive: age_secs <= timeout_secs as f64,
+            age_secs,
+            heartbeat: self,
+        }
+    }
+}
+
+/// Publish this swarm's heartbeat until the process exits
+pub async fn run_heartbeat(board: Arc<Blackboard>, dashboard_port: u16) -> Result<()> {
+    let settings = &board.config().swarm;
+    let mut ticker = interval(Duration::from_secs(settings.heartbeat_interval_secs.max(1)));
+    let started_at = Utc::now();
+    
+    info!("🛰️ Swarm '{}' joined the federation", board.swarm_name());
+    
+    loop {
+        ticker.tick().await;
+        
+        let portfolio_value = match board.get_portfolio_state().await {
+            Ok(state) => state.map(|s| s.total_value),
+            Err(e) => {
+                warn!("Heartbeat: Failed to read portfolio: {}", e);
+                None
+            }
+        };
+        
+        let heartbeat = SwarmHeartbeat {
+            name: board.swarm_name().to_string(),
+            started_at,
+            last_seen: Utc::now(),
+            dashboard_port,
+            portfolio_value,
+        };
+        if let Err(e) = board.put_swarm_heartbeat(&heartbeat).await {
+            warn!("Heartbeat: Failed to publish: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_prefix() {
+        assert_eq!(key_prefix("default").unwrap(), "");
+        assert_eq!(key_prefix("retirement").unwrap(), "retirement:");
+        assert_eq!(key_prefix("kid-529_a").unwrap(), "kid-529_a:");
+        assert!(key_prefix("").is_err());
+        assert!(key_prefix("a:b").is_err());
+        assert!(key_prefix("has space").is_err());
+    }
+
+    #[test]
+    fn test_status_liveness() {
+        let now = Utc::now();
+        let heartbeat = SwarmHeartbeat {
+            name: "retirement".to_string(),
+            started_at: now - chrono::Duration::hours(1),
+            last_seen: now - chrono::Duration::seconds(10),
+            dashboard_port: 8081,
+            portfolio_value: Some(Decimal::from(100_000)),
+        };
+        
+        let status = heartbeat.clone().status(now, 30);
+        assert!(status.alive);
+        assert!((status.age_secs - 10.0).abs() < 1e-9);
+        assert!(!heartbeat.status(now, 5).alive);
+    }
+}
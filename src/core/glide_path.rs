@@ -0,0 +1,130 @@
+//! Glide Path
+//!
+//! Time-varying target allocations (target-date-fund style). The stocks
+//! weight either follows a dated schedule or moves linearly from
+//! `default_stocks_pct` by a fixed amount per year. A small scheduler
+//! writes the result to `config:target_allocation`, where the Analyst and
+//! the dashboard pick it up like any other target change.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{info, warn};
+
+use crate::core::allocation;
+use crate::core::config::PortfolioConfig;
+use crate::core::Blackboard;
+
+/// Target stocks weight (%) on `date`, if the glide path has started,
+/// kept within the stocks and bonds assets' `min_pct`/`max_pct`
+pub fn target_stocks_pct(portfolio: &PortfolioConfig, date: NaiveDate) -> Option<f64> {
+    let glide = &portfolio.glide_path;
+    
+    let stocks_pct = if !glide.schedule.is_empty() {
+        // Dated sequence: the latest step already in effect
+        glide
+            .schedule
+            .iter()
+            .filter(|step| step.date <= date)
+            .max_by_key(|step| step.date)
+            .map(|step| step.stocks_pct)?
+    } else {
+        let start = glide.start_date?;
+        if date < start {
+            return None;
+        }
+        let years = (date - start).num_days() as f64 / 365.25;
+        portfolio.default_stocks_pct + glide.annual_stocks_change_pct * years
+    };
+    
+    let stocks_pct = stocks_pct.clamp(glide.min_stocks_pct, glide.max_stocks_pct);
+    let (lower, upper) = allocation::stocks_limits(portfolio);
+    Some(if lower <= upper { stocks_pct.clamp(lower, upper) } else { stocks_pct })
+}
+
+/// Periodically apply the glide path target to the blackboard
+/// 
+/// The target is only written when the glide path value changes, so a
+/// manual override from the dashboard holds until the next step.
+pub async fn run_scheduler(board: Arc<Blackboard>) -> Result<()> {
+    let portfolio = &board.config().portfolio;
+    let glide = &portfolio.glide_path;
+    let mut ticker = interval(Duration::from_secs(glide.check_interval_secs.max(1)));
+    let mut last_applied: Option<f64> = None;
+    
+    info!("🛬 Glide path scheduler started (checking every {}s)", glide.check_interval_secs);
+    
+    loop {
+        ticker.tick().await;
+        
+        let today = chrono::Utc::now().date_naive();
+        let Some(stocks_pct) = target_stocks_pct(portfolio, today) else {
+            continue;
+        };
+        // Round to basis points so tiny daily steps don't spam updates
+        let stocks_pct = (stocks_pct * 100.0).round() / 100.0;
+        if last_applied == Some(stocks_pct) {
+            continue;
+        }
+        
+        let bonds_pct = 100.0 - stocks_pct;
+        match board.set_target_allocation(stocks_pct, bonds_pct).await {
+            Ok(()) => {
+                info!("🛬 Glide path: target now {:.2}% stocks / {:.2}% bonds", stocks_pct, bonds_pct);
+                last_applied = Some(stocks_pct);
+            }
+            Err(e) => warn!("Glide path: failed to update target allocation: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::{GlidePathConfig, GlideStep};
+    use crate::core::Config;
+    
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+    
+    #[test]
+    fn test_linear_glide_path() {
+        let mut portfolio = Config::default().portfolio;
+        portfolio.default_stocks_pct = 60.0;
+        portfolio.glide_path = GlidePathConfig {
+            enabled: true,
+            start_date: Some(date("2020-01-01")),
+            annual_stocks_change_pct: -1.0,
+            min_stocks_pct: 55.0,
+            ..GlidePathConfig::default()
+        };
+        
+        assert_eq!(target_stocks_pct(&portfolio, date("2019-06-01")), None);
+        let after_two_years = target_stocks_pct(&portfolio, date("2022-01-01")).unwrap();
+        assert!((after_two_years - 58.0).abs() < 0.01);
+        assert_eq!(target_stocks_pct(&portfolio, date("2040-01-01")), Some(55.0));
+    }
+    
+    #[test]
+    fn test_dated_schedule() {
+        let mut portfolio = Config::default().portfolio;
+        portfolio.glide_path = GlidePathConfig {
+            enabled: true,
+            schedule: vec![
+                GlideStep { date: date("2030-01-01"), stocks_pct: 50.0 },
+                GlideStep { date: date("2025-01-01"), stocks_pct: 70.0 },
+            ],
+            ..GlidePathConfig::default()
+        };
+        
+        assert_eq!(target_stocks_pct(&portfolio, date("2024-12-31")), None);
+        assert_eq!(target_stocks_pct(&portfolio, date("2027-03-01")), Some(70.0));
+        assert_eq!(target_stocks_pct(&portfolio, date("2031-01-01")), Some(50.0));
+        
+        // A stocks asset floor holds the path above it
+        portfolio.assets[0].min_pct = Some(55.0);
+        assert_eq!(target_stocks_pct(&portfolio, date("2031-01-01")), Some(55.0));
+    }
+}
@@ -0,0 +1,394 @@
+//! Asset Groups
+//!
+//! Hierarchical targets for multi-ETF portfolios. Each `[[portfolio.groups]]`
+//! entry (Equities, Fixed Income) has a share of the portfolio and each of
+//! its members a share of the group. Drift is measured at both levels: a
+//! group against its portfolio target, a member against its target within
+//! the group. Positions come from imported holdings, marked to the latest
+//! prices where the export gave a quantity.
+//!
+//! With groups configured and holdings imported, this drift decides: the
+//! Analyst raises a `groups` opportunity when any group or member breaches
+//! the threshold, the Guardian approves one leg per off-target member, and
+//! the Trader writes the fills back to the holdings. `GET /api/groups`
+//! reports the same view.
+
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::core::config::{AssetGroupConfig, HoldingsImportConfig};
+use crate::core::holdings::{Holding, ImportedHoldings};
+use crate::core::money;
+use crate::core::rebalance::MIN_LEG_NOTIONAL;
+use crate::core::{Blackboard, Config};
+use crate::execution::{Fill, OrderLeg, Side};
+
+/// Allowed rounding slack when checking that targets sum to 100%
+const SUM_TOLERANCE_PCT: f64 = 0.01;
+
+/// One member's weight within its group
+#[derive(Debug, Clone, Serialize)]
+pub struct MemberDrift {
+    pub symbol: String,
+    pub value: f64,
+    /// Share of the group, in percent
+    pub current_pct: f64,
+    pub target_pct: f64,
+    /// current − target, in percentage points of the group
+    pub drift_pct: f64,
+}
+
+/// One group's weight in the portfolio and its members' drift
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupDrift {
+    pub name: String,
+    pub value: f64,
+    /// Share of the portfolio, in percent
+    pub current_pct: f64,
+    pub target_pct: f64,
+    /// current − target, in percentage points of the portfolio
+    pub drift_pct: f64,
+    pub members: Vec<MemberDrift>,
+}
+
+impl GroupDrift {
+    /// Largest member drift within the group, in percentage points
+    pub fn max_member_drift_pct(&self) -> f64 {
+        self.members.iter().map(|m| m.drift_pct.abs()).fold(0.0, f64::max)
+    }
+}
+
+/// Drift of every group and member against its target
+#[derive(Debug, Clone, Serialize)]
+pub struct HierarchicalDrift {
+    /// Value of the grouped positions, which the group shares are of
+    pub total_value: f64,
+    pub threshold_pct: f64,
+    pub groups: Vec<GroupDrift>,
+    /// Held symbols that belong to no group (left out of the total)
+    pub unassigned: Vec<String>,
+    /// Whether any group or member drifted past the threshold
+    pub breached: bool,
+}
+
+impl HierarchicalDrift {
+    /// Largest group or member drift, in percentage points
+    pub fn max_drift_pct(&self) -> f64 {
+        self.groups
+            .iter()
+            .map(|g| g.drift_pct.abs().max(g.max_member_drift_pct()))
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Problems with the group targets, for logging at startup
+pub fn warnings(groups: &[AssetGroupConfig]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if groups.is_empty() {
+        return warnings;
+    }
+    
+    let total: f64 = groups.iter().map(|g| g.target_pct).sum();
+    if (total - 100.0).abs() > SUM_TOLERANCE_PCT {
+        warnings.push(format!("group targets sum to {:.2}%, expected 100%", total));
+    }
+    
+    let mut seen = HashSet::new();
+    for group in groups {
+        if group.members.is_empty() {
+            warnings.push(format!("group '{}' has no members", group.name));
+            continue;
+        }
+        let members: f64 = group.members.iter().map(|m| m.target_pct).sum();
+        if (members - 100.0).abs() > SUM_TOLERANCE_PCT {
+            warnings.push(format!("members of '{}' sum to {:.2}% of the group, expected 100%", group.name, members));
+        }
+        for member in &group.members {
+            if !seen.insert(member.symbol.to_uppercase()) {
+                warnings.push(format!("{} is a member of more than one group", member.symbol));
+            }
+        }
+    }
+    warnings
+}
+
+/// Market value of each imported holding, marked to `price` when the
+/// export gave a quantity (cash is left out)
+pub fn positions(holdings: &ImportedHoldings, price: impl Fn(&str) -> Option<f64>) -> Vec<(String, f64)> {
+    holdings
+        .holdings
+        .iter()
+        .map(|holding| {
+            let value = match (holding.quantity, price(&holding.symbol)) {
+                (Some(quantity), Some(price)) => quantity * price,
+                _ => holding.market_value,
+            };
+            (holding.symbol.clone(), value)
+        })
+        .collect()
+}
+
+/// Measure each group against its portfolio target and each member against
+/// its target within the group
+pub fn hierarchical_drift(groups: &[AssetGroupConfig], positions: &[(String, f64)], threshold_pct: f64) -> HierarchicalDrift {
+    let value_of = |symbol: &str| -> f64 {
+        positions
+            .iter()
+            .filter(|(held, _)| held.eq_ignore_ascii_case(symbol))
+            .map(|(_, value)| value)
+            .sum()
+    };
+    let share = |part: f64, whole: f64| if whole > 0.0 { part / whole * 100.0 } else { 0.0 };
+    let values: Vec<Vec<f64>> = groups
+        .iter()
+        .map(|group| group.members.iter().map(|m| value_of(&m.symbol)).collect())
+        .collect();
+    let total_value: f64 = values.iter().flatten().sum();
+    
+    let groups: Vec<GroupDrift> = groups
+        .iter()
+        .zip(values)
+        .map(|(group, values)| {
+            let value: f64 = values.iter().sum();
+            let members = group
+                .members
+                .iter()
+                .zip(values)
+                .map(|(member, member_value)| {
+                    let current_pct = share(member_value, value);
+                    MemberDrift {
+                        symbol: member.symbol.clone(),
+                        value: member_value,
+                        current_pct,
+                        target_pct: member.target_pct,
+                        drift_pct: current_pct - member.target_pct,
+                    }
+                })
+                .collect();
+            let current_pct = share(value, total_value);
+            GroupDrift {
+                name: group.name.clone(),
+                value,
+                current_pct,
+                target_pct: group.target_pct,
+                drift_pct: current_pct - group.target_pct,
+                members,
+            }
+        })
+        .collect();
+    
+    let unassigned = positions
+        .iter()
+        .filter(|(symbol, _)| {
+            !groups
+                .iter()
+                .flat_map(|g| &g.members)
+                .any(|m| m.symbol.eq_ignore_ascii_case(symbol))
+        })
+        .map(|(symbol, _)| symbol.clone())
+        .collect();
+    let breached = groups
+        .iter()
+        .any(|g| g.drift_pct.abs() > threshold_pct || g.max_member_drift_pct() > threshold_pct);
+    
+    HierarchicalDrift { total_value, threshold_pct, groups, unassigned, breached }
+}
+
+/// Group and member drift of the imported holdings marked to `price`;
+/// none without asset groups or imported holdings
+pub async fn current(
+    board: &Blackboard,
+    config: &Config,
+    price: impl Fn(&str) -> Option<f64>,
+) -> Result<Option<HierarchicalDrift>> {
+    if config.portfolio.groups.is_empty() {
+        return Ok(None);
+    }
+    let Some(holdings) = board.get_holdings().await? else {
+        return Ok(None);
+    };
+    let positions = positions(&holdings, price);
+    Ok(Some(hierarchical_drift(&config.portfolio.groups, &positions, config.portfolio.drift_threshold)))
+}
+
+/// Legs that bring every member to its target share of the grouped value,
+/// sells first. Locked members are never sold, and the larger side (buys
+/// or sells) shrinks to match the other so the legs net to zero.
+pub fn member_legs(drift: &HierarchicalDrift, import: &HoldingsImportConfig) -> Vec<OrderLeg> {
+    let deltas: Vec<(String, Decimal)> = drift
+        .groups
+        .iter()
+        .flat_map(|group| {
+            group.members.iter().map(move |member| {
+                let target = drift.total_value * group.target_pct / 100.0 * member.target_pct / 100.0;
+                (member.symbol.clone(), money::from_f64(target - member.value))
+            })
+        })
+        .filter(|(symbol, delta)| *delta > Decimal::ZERO || !import.is_locked(symbol))
+        .collect();
+    
+    let buys: Decimal = deltas.iter().map(|(_, d)| (*d).max(Decimal::ZERO)).sum();
+    let sells: Decimal = deltas.iter().map(|(_, d)| (-d).max(Decimal::ZERO)).sum();
+    let funded = buys.min(sells);
+    let mut legs: Vec<OrderLeg> = deltas
+        .into_iter()
+        .map(|(symbol, delta)| {
+            let side_total = if delta > Decimal::ZERO { buys } else { sells };
+            (symbol, delta * funded / side_total.max(MIN_LEG_NOTIONAL))
+        })
+        .filter(|(_, delta)| delta.abs() > MIN_LEG_NOTIONAL)
+        .map(|(symbol, delta)| OrderLeg {
+            symbol,
+            side: if delta > Decimal::ZERO { Side::Buy } else { Side::Sell },
+            notional: delta.abs().round_dp(2),
+        })
+        .collect();
+    legs.sort_by_key(|leg| leg.side == Side::Buy);
+    legs
+}
+
+/// Write filled legs back to the imported holdings so group drift sees the
+/// trade. Quantity and cost basis scale with the position's value; a buy
+/// of a symbol not yet held opens a position at cost.
+pub fn apply_fills(holdings: &mut ImportedHoldings, fills: &[Fill]) {
+    for fill in fills {
+        let change = money::to_f64(fill.signed_notional());
+        let held = holdings
+            .holdings
+            .iter_mut()
+            .find(|h| h.symbol.eq_ignore_ascii_case(&fill.leg.symbol));
+        match held {
+            Some(holding) => {
+                let value = (holding.market_value + change).max(0.0);
+                let scale = (holding.market_value > 0.0).then(|| value / holding.market_value);
+                holding.quantity = holding.quantity.zip(scale).map(|(quantity, scale)| quantity * scale);
+                holding.cost_basis = holding
+                    .cost_basis
+                    .map(|cost| if change > 0.0 { cost + change } else { cost * scale.unwrap_or(0.0) });
+                holding.market_value = value;
+            }
+            None if change > 0.0 => holdings.holdings.push(Holding {
+                symbol: fill.leg.symbol.clone(),
+                quantity: None,
+                price: None,
+                market_value: change,
+                cost_basis: Some(change),
+            }),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::GroupMemberConfig;
+
+    fn group(name: &str, target_pct: f64, members: &[(&str, f64)]) -> AssetGroupConfig {
+        AssetGroupConfig {
+            name: name.to_string(),
+            target_pct,
+            members: members
+                .iter()
+                .map(|(symbol, pct)| GroupMemberConfig { symbol: symbol.to_string(), name: String::new(), target_pct: *pct, exchange: None, currency: None })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_hierarchical_drift() {
+        let groups = vec![
+            group("Equities", 60.0, &[("SPY", 50.0), ("QQQ", 30.0), ("IWM", 20.0)]),
+            group("Fixed Income", 40.0, &[("BND", 75.0), ("TIP", 25.0)]),
+        ];
+        let positions: Vec<(String, f64)> = [("SPY", 35_000.0), ("QQQ", 21_000.0), ("IWM", 14_000.0), ("BND", 22_500.0), ("TIP", 7_500.0)]
+            .iter()
+            .map(|(s, v)| (s.to_string(), *v))
+            .collect();
+        
+        let drift = hierarchical_drift(&groups, &positions, 5.0);
+        let equities = &drift.groups[0];
+        // Equities are 70% of the portfolio against a 60% target
+        assert!((equities.current_pct - 70.0).abs() < 1e-9);
+        assert!((equities.drift_pct - 10.0).abs() < 1e-9);
+        // ...but hold their members at exactly their targets
+        assert!(equities.max_member_drift_pct() < 1e-9);
+        assert!(drift.breached);
+        assert!(drift.unassigned.is_empty());
+        
+        // Within band at the group level, off-target inside Fixed Income
+        let positions: Vec<(String, f64)> = [("SPY", 60.0), ("BND", 20.0), ("TIP", 20.0), ("GLD", 0.0)]
+            .iter()
+            .map(|(s, v)| (s.to_string(), *v))
+            .collect();
+        let drift = hierarchical_drift(&groups, &positions, 5.0);
+        assert!((drift.groups[1].members[0].drift_pct + 25.0).abs() < 1e-9);
+        assert!(drift.breached);
+        assert_eq!(drift.unassigned, vec!["GLD".to_string()]);
+    }
+
+    #[test]
+    fn test_warnings() {
+        assert!(warnings(&[]).is_empty());
+        let groups = vec![
+            group("Equities", 60.0, &[("SPY", 100.0)]),
+            group("Fixed Income", 30.0, &[("BND", 60.0), ("SPY", 30.0)]),
+        ];
+        let warnings = warnings(&groups);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("90.00%"));
+    }
+    
+    #[test]
+    fn test_member_legs() {
+        let groups = vec![
+            group("Equities", 60.0, &[("SPY", 50.0), ("QQQ", 30.0), ("IWM", 20.0)]),
+            group("Fixed Income", 40.0, &[("BND", 75.0), ("TIP", 25.0)]),
+        ];
+        let positions: Vec<(String, f64)> = [("SPY", 35_000.0), ("QQQ", 21_000.0), ("IWM", 14_000.0), ("BND", 22_500.0), ("TIP", 7_500.0)]
+            .iter()
+            .map(|(s, v)| (s.to_string(), *v))
+            .collect();
+        let drift = hierarchical_drift(&groups, &positions, 5.0);
+        let leg = |legs: &[OrderLeg], symbol: &str| legs.iter().find(|l| l.symbol == symbol).map(|l| (l.side, l.notional));
+        
+        // Every member moves to its share of the grouped value, sells first
+        let legs = member_legs(&drift, &HoldingsImportConfig::default());
+        assert_eq!(legs.len(), 5);
+        assert!(legs[..3].iter().all(|l| l.side == Side::Sell));
+        assert_eq!(leg(&legs, "SPY"), Some((Side::Sell, Decimal::from(5_000))));
+        assert_eq!(leg(&legs, "BND"), Some((Side::Buy, Decimal::from(7_500))));
+        let net: Decimal = legs.iter().map(|l| l.signed(l.notional)).sum();
+        assert_eq!(net, Decimal::ZERO);
+        
+        // A locked SPY is not sold; the buys shrink to what QQQ and IWM fund
+        let import = HoldingsImportConfig { locked_symbols: vec!["spy".to_string()], ..HoldingsImportConfig::default() };
+        let legs = member_legs(&drift, &import);
+        assert_eq!(leg(&legs, "SPY"), None);
+        assert_eq!(leg(&legs, "BND"), Some((Side::Buy, Decimal::from(3_750))));
+        assert_eq!(leg(&legs, "TIP"), Some((Side::Buy, Decimal::from(1_250))));
+    }
+    
+    #[test]
+    fn test_apply_fills() {
+        let mut holdings = ImportedHoldings {
+            holdings: vec![Holding { symbol: "SPY".to_string(), quantity: Some(100.0), price: Some(100.0), market_value: 10_000.0, cost_basis: Some(8_000.0) }],
+            cash: 0.0,
+            source: "test".to_string(),
+            imported_at: String::new(),
+        };
+        let fill = |symbol: &str, side: Side, notional: i64| Fill {
+            leg: OrderLeg { symbol: symbol.to_string(), side, notional: Decimal::from(notional) },
+            filled_notional: Decimal::from(notional),
+        };
+        apply_fills(&mut holdings, &[fill("spy", Side::Sell, 2_500), fill("TIP", Side::Buy, 1_000), fill("BND", Side::Sell, 500)]);
+        
+        let spy = &holdings.holdings[0];
+        assert_eq!((spy.market_value, spy.quantity, spy.cost_basis), (7_500.0, Some(75.0), Some(6_000.0)));
+        assert_eq!(holdings.holdings.len(), 2);
+        assert_eq!(holdings.holdings[1].symbol, "TIP");
+        assert_eq!(holdings.holdings[1].cost_basis, Some(1_000.0));
+    }
+}
@@ -0,0 +1,385 @@
+//! Imported Holdings
+//!
+//! Parses position exports from common brokers (Fidelity, Schwab,
+//! Vanguard or any CSV with symbol/quantity/value columns) into a
+//! holdings model with cost basis, and derives the two-bucket portfolio
+//! state the agents trade against. Imported holdings replace the
+//! synthetic `initial_balance` split at startup and on reset.
+
+use anyhow::{bail, Context, Result};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::agents::sensor::MarketSnapshot;
+use crate::core::benchmarks::BenchmarkSet;
+use crate::core::blackboard::{PortfolioState, RebalanceBaseline, TargetAllocation};
+use crate::core::config::{Config, HoldingsImportConfig};
+use crate::core::money;
+use crate::core::performance::CashFlow;
+use crate::core::rebalance;
+use crate::core::valuation::DrawdownState;
+use crate::core::Blackboard;
+
+/// Header names (lower-case) recognised for each column, most specific first
+const SYMBOL_HEADERS: &[&str] = &["symbol", "ticker", "symbol/cusip"];
+const QUANTITY_HEADERS: &[&str] = &["quantity", "shares", "qty"];
+const PRICE_HEADERS: &[&str] = &["last price", "share price", "price", "current price"];
+const VALUE_HEADERS: &[&str] = &["current value", "market value", "total value", "value"];
+const COST_HEADERS: &[&str] = &["cost basis total", "cost basis", "total cost", "cost"];
+
+/// Symbols brokers use for cash and money-market sweep positions
+const CASH_MARKERS: &[&str] = &["cash", "spaxx", "fdrxx", "fzfxx", "core", "vmfxx", "swvxx"];
+
+/// One position from a broker export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Holding {
+    pub symbol: String,
+    pub quantity: Option<f64>,
+    pub price: Option<f64>,
+    pub market_value: f64,
+    /// Total cost basis, when the export includes it
+    pub cost_basis: Option<f64>,
+}
+
+impl Holding {
+    /// Unrealized gain (market value minus cost basis)
+    pub fn unrealized_gain(&self) -> Option<f64> {
+        self.cost_basis.map(|cost| self.market_value - cost)
+    }
+}
+
+/// Value of the locked positions in each sleeve
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LockedHoldings {
+    pub stocks_value: Decimal,
+    pub bonds_value: Decimal,
+}
+
+/// A parsed broker export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedHoldings {
+    pub holdings: Vec<Holding>,
+    /// Cash and money-market balances
+    pub cash: f64,
+    pub source: String,
+    pub imported_at: String,
+}
+
+impl ImportedHoldings {
+    /// Collapse holdings into the stocks/bonds portfolio the agents manage
+    pub fn portfolio_state(&self, config: &HoldingsImportConfig) -> PortfolioState {
+        let mut stocks_value = Decimal::ZERO;
+        let mut bonds_value = if config.cash_as_bonds { money::from_f64(self.cash) } else { Decimal::ZERO };
+        for holding in &self.holdings {
+            if config.is_bond(&holding.symbol) {
+                bonds_value += money::from_f64(holding.market_value);
+            } else {
+                stocks_value += money::from_f64(holding.market_value);
+            }
+        }
+        
+        PortfolioState::from_values(stocks_value, bonds_value, None)
+    }
+    
+    /// Locked positions by sleeve, marked to `price` where the export gave
+    /// a quantity
+    pub fn locked(&self, config: &HoldingsImportConfig, price: impl Fn(&str) -> Option<f64>) -> LockedHoldings {
+        let mut locked = LockedHoldings::default();
+        for holding in self.holdings.iter().filter(|h| config.is_locked(&h.symbol)) {
+            let value = match (holding.quantity, price(&holding.symbol)) {
+                (Some(quantity), Some(price)) => quantity * price,
+                _ => holding.market_value,
+            };
+            if config.is_bond(&holding.symbol) {
+                locked.bonds_value += money::from_f64(value);
+            } else {
+                locked.stocks_value += money::from_f64(value);
+            }
+        }
+        locked
+    }
+    
+    /// Total cost basis of the stocks or bonds sleeve; none unless every
+    /// position in it has one (cash counts at cost)
+    pub fn sleeve_cost_basis(&self, config: &HoldingsImportConfig, bonds: bool) -> Option<Decimal> {
+        let mut total = if bonds && config.cash_as_bonds { money::from_f64(self.cash) } else { Decimal::ZERO };
+        for holding in self.holdings.iter().filter(|h| config.is_bond(&h.symbol) == bonds) {
+            total += money::from_f64(holding.cost_basis?);
+        }
+        Some(total)
+    }
+}
+
+/// Parse a number as brokers print it: `$1,234.56`, `(12.50)`, `+3.2%`, `--`
+fn parse_amount(raw: &str) -> Option<f64> {
+    let trimmed = raw.trim();
+    let negative = (trimmed.starts_with('(') && trimmed.ends_with(')')) || trimmed.starts_with('-');
+    let digits: String = trimmed
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let value: f64 = digits.parse().ok()?;
+    Some(if negative { -value } else { value })
+}
+
+fn is_cash(symbol: &str) -> bool {
+    let lower = symbol.to_lowercase();
+    CASH_MARKERS.iter().any(|marker| lower.starts_with(marker))
+}
+
+/// Column index of the first header matching one of `names`
+fn find_column(headers: &[String], names: &[&str]) -> Option<usize> {
+    names.iter().find_map(|name| headers.iter().position(|h| h == name))
+}
+
+/// Parse a broker positions CSV
+///
+/// Preamble lines before the header row (account names, dates) and
+/// footer rows without a usable symbol or value are skipped.
+pub fn parse_broker_csv(text: &str, source: &str) -> Result<ImportedHoldings> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+    let mut records = reader.records();
+    
+    // Find the header row: the first one naming a symbol column
+    let headers: Vec<String> = loop {
+        let Some(record) = records.next() else {
+            bail!("no header row with a symbol column found");
+        };
+        let row: Vec<String> = record?.iter().map(|h| h.to_lowercase()).collect();
+        if find_column(&row, SYMBOL_HEADERS).is_some() {
+            break row;
+        }
+    };
+    let symbol_col = find_column(&headers, SYMBOL_HEADERS).unwrap_or_default();
+    let quantity_col = find_column(&headers, QUANTITY_HEADERS);
+    let price_col = find_column(&headers, PRICE_HEADERS);
+    let value_col = find_column(&headers, VALUE_HEADERS);
+    let cost_col = find_column(&headers, COST_HEADERS);
+    if value_col.is_none() && (quantity_col.is_none() || price_col.is_none()) {
+        bail!("export needs a market value column, or quantity and price columns");
+    }
+    
+    let mut holdings: Vec<Holding> = Vec::new();
+    let mut cash = 0.0;
+    for record in records {
+        let record = record?;
+        let field = |col: Option<usize>| col.and_then(|c| record.get(c)).and_then(parse_amount);
+        
+        let symbol = record.get(symbol_col).unwrap_or_default().trim_end_matches('*').to_string();
+        if symbol.is_empty() || (symbol.contains(' ') && !is_cash(&symbol)) {
+            continue; // totals, disclaimers, blank lines
+        }
+        
+        let quantity = field(quantity_col);
+        let price = field(price_col);
+        let Some(market_value) = field(value_col).or_else(|| Some(quantity? * price?)) else {
+            continue;
+        };
+        
+        if is_cash(&symbol) {
+            cash += market_value;
+            continue;
+        }
+        
+        // The same symbol can appear in several lots; merge them
+        let cost_basis = field(cost_col);
+        match holdings.iter_mut().find(|h| h.symbol == symbol) {
+            Some(existing) => {
+                existing.market_value += market_value;
+                existing.quantity = existing.quantity.zip(quantity).map(|(a, b)| a + b);
+                existing.cost_basis = existing.cost_basis.zip(cost_basis).map(|(a, b)| a + b);
+            }
+            None => holdings.push(Holding { symbol, quantity, price, market_value, cost_basis }),
+        }
+    }
+    
+    if holdings.is_empty() && cash == 0.0 {
+        bail!("no positions found");
+    }
+    
+    Ok(ImportedHoldings {
+        holdings,
+        cash,
+        source: source.to_string(),
+        imported_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Portfolio to start from: imported holdings if present, otherwise the
+/// starting cash set by `POST /api/portfolio/init` (or the configured
+/// `initial_balance`) split at the default allocation
+pub async fn initial_portfolio(board: &Blackboard) -> Result<PortfolioState> {
+    let config = board.config();
+    if let Some(imported) = board.get_holdings().await? {
+        return Ok(imported.portfolio_state(&config.holdings_import));
+    }
+    if let Some(init) = board.get_portfolio_init().await? {
+        return Ok(split_balance(init.starting_cash, config));
+    }
+    Ok(synthetic_portfolio(config))
+}
+
+/// `initial_balance` split at the default allocation
+pub fn synthetic_portfolio(config: &Config) -> PortfolioState {
+    split_balance(money::from_f64(config.portfolio.initial_balance), config)
+}
+
+/// A balance split at the default allocation
+pub fn split_balance(balance: Decimal, config: &Config) -> PortfolioState {
+    let portfolio = &config.portfolio;
+    let share = |pct: f64| balance * money::from_f64(pct) / Decimal::ONE_HUNDRED;
+    PortfolioState::from_values(share(portfolio.default_stocks_pct), share(portfolio.default_bonds_pct), None)
+}
+
+/// The target to rebalance toward with locked holdings held fixed: the
+/// configured target, or the closest one that sells none of them
+pub async fn achievable_target(
+    board: &Blackboard,
+    state: &PortfolioState,
+    target: &TargetAllocation,
+    snapshot: Option<&MarketSnapshot>,
+) -> Result<TargetAllocation> {
+    let config = &board.config().holdings_import;
+    if config.locked_symbols.is_empty() {
+        return Ok(target.clone());
+    }
+    let Some(imported) = board.get_holdings().await? else {
+        return Ok(target.clone());
+    };
+    let locked = imported.locked(config, |symbol| snapshot.and_then(|s| s.price(symbol)));
+    Ok(rebalance::achievable_target(state, target, &locked))
+}
+
+/// Restart portfolio, baseline, drawdown and benchmarks from `portfolio`,
+/// recording the change in value as a cash flow from `source`
+pub async fn restart_from(board: &Blackboard, portfolio: &PortfolioState, source: &str) -> Result<()> {
+    if let Some(previous) = board.get_portfolio_state().await? {
+        let amount = money::to_f64(portfolio.total_value - previous.total_value);
+        if amount != 0.0 {
+            let flow = CashFlow { timestamp: chrono::Utc::now().to_rfc3339(), amount, source: source.to_string() };
+            board.push_cash_flow(&flow, board.config().valuation.history_max_entries).await?;
+        }
+    }
+    board.set_portfolio_state(portfolio).await?;
+    let target = board.get_target_allocation().await?;
+    board.set_rebalance_baseline(&RebalanceBaseline::new(portfolio, &target)).await?;
+    let total_value = money::to_f64(portfolio.total_value);
+    board.set_drawdown(&DrawdownState::new(total_value)).await?;
+    let today = chrono::Utc::now().date_naive();
+    board.set_benchmarks(&BenchmarkSet::new(total_value, &target, board.config(), today)).await?;
+    Ok(())
+}
+
+/// Store imported holdings and restart portfolio, baseline, drawdown and
+/// benchmarks from them
+pub async fn apply_import(board: &Blackboard, imported: &ImportedHoldings) -> Result<PortfolioState> {
+    let portfolio = imported.portfolio_state(&board.config().holdings_import);
+    if portfolio.total_value <= Decimal::ZERO {
+        bail!("imported holdings have no value");
+    }
+    
+    board.set_holdings(imported).await.context("storing holdings")?;
+    restart_from(board, &portfolio, "holdings import").await?;
+    
+    info!(
+        "📥 Imported {} holdings from {}: ${:.2} ({:.1}% stocks / {:.1}% bonds)",
+        imported.holdings.len(),
+        imported.source,
+        portfolio.total_value,
+        portfolio.stocks_pct,
+        portfolio.bonds_pct
+    );
+    Ok(portfolio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const FIDELITY: &str = "\
+Account Number,Account Name,Symbol,Description,Quantity,Last Price,Current Value,Cost Basis Total
+X123,Individual,SPAXX**,HELD IN MONEY MARKET,,,$1500.00,
+X123,Individual,VTI,VANGUARD TOTAL STOCK MKT,100,$250.00,\"$25,000.00\",\"$20,000.00\"
+X123,Individual,BND,VANGUARD TOTAL BOND MKT,200,$72.50,\"$14,500.00\",\"$15,200.00\"
+X123,Individual,VTI,VANGUARD TOTAL STOCK MKT,10,$250.00,\"$2,500.00\",\"$2,100.00\"
+
+\"The data and information in this spreadsheet is provided to you solely for your use.\"
+";
+    
+    const SCHWAB: &str = "\
+\"Positions for account Individual ...123 as of 10:00 AM ET, 2026/03/05\"
+
+\"Symbol\",\"Description\",\"Quantity\",\"Price\",\"Market Value\",\"Cost Basis\"
+\"SCHB\",\"SCHWAB US BROAD MARKET ETF\",\"300\",\"$22.10\",\"$6,630.00\",\"$5,900.00\"
+\"AGG\",\"ISHARES CORE US AGGREGATE\",\"40\",\"$98.00\",\"$3,920.00\",\"--\"
+\"Cash & Cash Investments\",\"--\",\"--\",\"--\",\"$450.00\",\"--\"
+\"Account Total\",\"--\",\"--\",\"--\",\"$11,000.00\",\"--\"
+";
+    
+    #[test]
+    fn test_parse_fidelity_merges_lots_and_cash() {
+        let imported = parse_broker_csv(FIDELITY, "fidelity.csv").unwrap();
+        assert_eq!(imported.cash, 1500.0);
+        assert_eq!(imported.holdings.len(), 2);
+        
+        let vti = &imported.holdings[0];
+        assert_eq!(vti.symbol, "VTI");
+        assert_eq!(vti.quantity, Some(110.0));
+        assert_eq!(vti.market_value, 27_500.0);
+        assert_eq!(vti.unrealized_gain(), Some(5_400.0));
+    }
+    
+    #[test]
+    fn test_parse_schwab_skips_preamble_and_totals() {
+        let imported = parse_broker_csv(SCHWAB, "schwab.csv").unwrap();
+        assert_eq!(imported.cash, 450.0);
+        let symbols: Vec<_> = imported.holdings.iter().map(|h| h.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["SCHB", "AGG"]);
+        assert_eq!(imported.holdings[1].cost_basis, None);
+    }
+    
+    #[test]
+    fn test_value_from_quantity_and_price() {
+        let imported = parse_broker_csv("Ticker,Shares,Share Price\nVXUS,10,$60.00\n", "vanguard.csv").unwrap();
+        assert_eq!(imported.holdings[0].market_value, 600.0);
+    }
+    
+    #[test]
+    fn test_missing_symbol_column_rejected() {
+        assert!(parse_broker_csv("Name,Value\nFoo,1\n", "x.csv").is_err());
+    }
+    
+    #[test]
+    fn test_portfolio_state_buckets() {
+        let imported = parse_broker_csv(SCHWAB, "schwab.csv").unwrap();
+        let state = imported.portfolio_state(&HoldingsImportConfig::default());
+        assert_eq!(state.stocks_value, Decimal::from(6_630));
+        assert_eq!(state.bonds_value, Decimal::from(3_920 + 450));
+        assert!((state.stocks_pct + state.bonds_pct - Decimal::ONE_HUNDRED).abs() < Decimal::new(1, 9));
+    }
+    
+    #[test]
+    fn test_locked_holdings_marked_to_price() {
+        let imported = parse_broker_csv(FIDELITY, "fidelity.csv").unwrap();
+        let config = HoldingsImportConfig { locked_symbols: vec!["vti".to_string()], ..HoldingsImportConfig::default() };
+        
+        let locked = imported.locked(&config, |_| None);
+        assert_eq!(locked.stocks_value, Decimal::from(27_500));
+        assert_eq!(locked.bonds_value, Decimal::ZERO);
+        // 110 shares at a newer $300
+        let locked = imported.locked(&config, |symbol| (symbol == "VTI").then_some(300.0));
+        assert_eq!(locked.stocks_value, Decimal::from(33_000));
+    }
+    
+    #[test]
+    fn test_parse_amount_formats() {
+        assert_eq!(parse_amount("$1,234.56"), Some(1234.56));
+        assert_eq!(parse_amount("(12.50)"), Some(-12.5));
+        assert_eq!(parse_amount("--"), None);
+    }
+}
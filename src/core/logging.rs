@@ -0,0 +1,160 @@
+//! Log Levels
+//!
+//! Logging goes through a reloadable filter, so verbosity can change
+//! without a restart. `[logging] level` is the default; `[logging.levels]`
+//! sets levels per agent (`sensor = "trace"`, which covers the agent's own
+//! module) or per tracing target (`"driftguard::core::blackboard" =
+//! "warn"`). Operators override them at runtime with `POST /api/logging`
+//! or a `set_log_level` dashboard command. Overrides are stored on the
+//! blackboard and every process of the swarm applies them within
+//! `reload_interval_secs`. Blackboard deposits and sniffs are logged
+//! under the blackboard's target rather than the agent's, so an agent's
+//! level does not cover them.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock};
+use tokio::time::{interval, Duration};
+use tracing::level_filters::LevelFilter;
+use tracing::{info, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+use crate::core::config::LoggingConfig;
+use crate::core::pipeline::AgentRole;
+use crate::core::secrets::RedactingStdout;
+use crate::core::Blackboard;
+
+/// Handle to the installed filter; unset when the binary didn't call `init`
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Install the global subscriber with a reloadable filter at `level`,
+/// writing through secret redaction
+pub fn init(level: &str) {
+    let filter = EnvFilter::try_new(level).unwrap_or_else(|_| EnvFilter::new("debug"));
+    let (filter, handle) = reload::Layer::new(filter);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            fmt::layer()
+                .with_target(true)
+                .with_thread_ids(false)
+                .with_file(false)
+                .with_line_number(false)
+                .with_writer(RedactingStdout)
+                .pretty(),
+        )
+        .init();
+    let _ = FILTER.set(handle);
+}
+
+/// Tracing target of an agent name, or the name itself if it is a valid
+/// module path
+pub fn target(name: &str) -> Result<String> {
+    if let Ok(role) = name.parse::<AgentRole>() {
+        return Ok(format!("driftguard::agents::{}", role.label().to_lowercase()));
+    }
+    let valid = !name.is_empty()
+        && name.split("::").all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+    if !valid {
+        return Err(anyhow!("'{}' is neither an agent nor a tracing target", name));
+    }
+    Ok(name.to_string())
+}
+
+/// Check a level name (trace, debug, info, warn, error, off)
+pub fn parse_level(level: &str) -> Result<LevelFilter> {
+    level
+        .parse::<LevelFilter>()
+        .map_err(|_| anyhow!("unknown log level '{}' (trace, debug, info, warn, error or off)", level))
+}
+
+/// A runtime change to one agent's or target's level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLevelRequest {
+    /// Agent name or tracing target
+    pub target: String,
+    /// None clears the override
+    #[serde(default)]
+    pub level: Option<String>,
+}
+
+/// Filter directives for the configured levels with `overrides` applied
+pub fn directives(config: &LoggingConfig, overrides: &BTreeMap<String, String>) -> Result<String> {
+    let mut levels: BTreeMap<String, LevelFilter> = BTreeMap::new();
+    for (name, level) in config.levels.iter().chain(overrides) {
+        levels.insert(target(name)?, parse_level(level)?);
+    }
+    let mut directives = vec![parse_level(&config.level)?.to_string().to_lowercase()];
+    directives.extend(levels.iter().map(|(target, level)| format!("{}={}", target, level.to_string().to_lowercase())));
+    let directives = directives.join(",");
+    EnvFilter::try_new(&directives).map_err(|e| anyhow!("invalid log filter '{}': {}", directives, e))?;
+    Ok(directives)
+}
+
+/// Reload the filter with `overrides`; returns the directives applied
+pub fn apply(config: &LoggingConfig, overrides: &BTreeMap<String, String>) -> Result<String> {
+    let directives = directives(config, overrides)?;
+    if let Some(handle) = FILTER.get() {
+        handle.reload(EnvFilter::try_new(&directives)?)?;
+    }
+    Ok(directives)
+}
+
+/// Apply the blackboard's overrides whenever they change
+pub async fn watch(board: Arc<Blackboard>) {
+    let config = &board.config().logging;
+    let mut ticker = interval(Duration::from_secs(config.reload_interval_secs.max(1)));
+    let mut applied: Option<BTreeMap<String, String>> = None;
+    
+    loop {
+        ticker.tick().await;
+        let overrides = match board.get_log_levels().await {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                warn!("Logging: Failed to read log levels: {}", e);
+                continue;
+            }
+        };
+        if applied.as_ref() == Some(&overrides) {
+            continue;
+        }
+        match apply(config, &overrides) {
+            Ok(directives) => {
+                if applied.is_some() || !overrides.is_empty() {
+                    info!("🔊 Log levels: {}", directives);
+                }
+            }
+            Err(e) => warn!("Logging: Ignoring log levels: {:#}", e),
+        }
+        applied = Some(overrides);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directives() {
+        let mut config = LoggingConfig::default();
+        config.levels.insert("guardian".to_string(), "warn".to_string());
+        config.levels.insert("hyper".to_string(), "info".to_string());
+        
+        let mut overrides = BTreeMap::new();
+        overrides.insert("Sensor".to_string(), "TRACE".to_string());
+        overrides.insert("guardian".to_string(), "error".to_string());
+        assert_eq!(
+            directives(&config, &overrides).unwrap(),
+            "debug,driftguard::agents::guardian=error,driftguard::agents::sensor=trace,hyper=info"
+        );
+        
+        overrides.insert("trader".to_string(), "loud".to_string());
+        assert!(directives(&config, &overrides).is_err());
+        overrides.remove("trader");
+        overrides.insert("not a target!".to_string(), "info".to_string());
+        assert!(directives(&config, &overrides).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+//! Agent Metrics Writer
+//!
+//! Agents report their metrics on every loop iteration, and an idle swarm
+//! reports the same thing over and over: written straight through, that is
+//! two Redis round trips per agent every `sniff_interval_ms`. Once the
+//! writer is started, `Blackboard::set_agent_metrics` hands each report to
+//! this task over a bounded channel instead. The task keeps the latest
+//! report per agent and every `[agent] metrics_flush_ms` writes the ones
+//! that changed (ignoring `last_action_time`) in a single pipelined round
+//! trip. Unchanged metrics are rewritten every `metrics_heartbeat_secs` so
+//! the dashboard's last-action time stays live. A report that finds the
+//! channel full is dropped; the agent's next report supersedes it anyway.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::core::blackboard::{AgentMetrics, Blackboard};
+
+/// Reports buffered between flushes before new ones are dropped
+pub const CHANNEL_CAPACITY: usize = 256;
+
+/// Change detection: what was last written for each agent, and when
+#[derive(Debug, Default)]
+pub struct MetricsTracker {
+    written: HashMap<String, (AgentMetrics, Instant)>,
+}
+
+impl MetricsTracker {
+    /// Whether `metrics` needs writing at `now` (its agent's state changed
+    /// since the last write, or the heartbeat is due); if so it is recorded
+    /// as written
+    pub fn admit(&mut self, metrics: &AgentMetrics, heartbeat: Duration, now: Instant) -> bool {
+        match self.written.get(&metrics.name) {
+            Some((last, at)) if same_state(last, metrics) && now.duration_since(*at) < heartbeat => false,
+            _ => {
+                self.written.insert(metrics.name.clone(), (metrics.clone(), now));
+                true
+            }
+        }
+    }
+
+    /// Forget what was written, so the next report of every agent is written
+    pub fn reset(&mut self) {
+        self.written.clear();
+    }
+}
+
+/// Equal apart from `last_action_time`
+fn same_state(a: &AgentMetrics, b: &AgentMetrics) -> bool {
+    a.is_active == b.is_active
+        && a.action_count == b.action_count
+        && a.last_action == b.last_action
+        && a.last_error == b.last_error
+}
+
+/// Drain reports from `rx` and write them in batches until every sender is gone
+pub async fn run(board: Arc<Blackboard>, mut rx: mpsc::Receiver<AgentMetrics>) {
+    let config = &board.config().agent;
+    let flush = Duration::from_millis(config.metrics_flush_ms);
+    let heartbeat = Duration::from_secs(config.metrics_heartbeat_secs);
+    let mut tracker = MetricsTracker::default();
+    let mut latest: HashMap<String, AgentMetrics> = HashMap::new();
+    
+    while let Some(first) = rx.recv().await {
+        latest.insert(first.name.clone(), first);
+        tokio::time::sleep(flush).await;
+        while let Ok(metrics) = rx.try_recv() {
+            latest.insert(metrics.name.clone(), metrics);
+        }
+        
+        let now = Instant::now();
+        let batch: Vec<AgentMetrics> = latest
+            .drain()
+            .map(|(_, metrics)| metrics)
+            .filter(|metrics| tracker.admit(metrics, heartbeat, now))
+            .collect();
+        if batch.is_empty() {
+            continue;
+        }
+        if let Err(e) = board.write_agent_metrics(&batch).await {
+            warn!("📊 Failed to write metrics for {} agents: {:#}", batch.len(), e);
+            tracker.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_detection() {
+        let metrics = |count: u64, time: &str| AgentMetrics {
+            name: "Sensor".to_string(),
+            is_active: true,
+            action_count: count,
+            last_action: format!("Fetched prices ({})", count),
+            last_action_time: Some(time.to_string()),
+            last_error: None,
+        };
+        let mut tracker = MetricsTracker::default();
+        let start = Instant::now();
+        let heartbeat = Duration::from_secs(30);
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        
+        assert!(tracker.admit(&metrics(1, "10:00:00"), heartbeat, at(0)));
+        // Only the timestamp moved: skipped until the heartbeat is due
+        assert!(!tracker.admit(&metrics(1, "10:00:01"), heartbeat, at(1)));
+        assert!(!tracker.admit(&metrics(1, "10:00:29"), heartbeat, at(29)));
+        assert!(tracker.admit(&metrics(1, "10:00:30"), heartbeat, at(30)));
+        // A real change is written at once
+        assert!(tracker.admit(&metrics(2, "10:00:31"), heartbeat, at(31)));
+        let mut idle = metrics(2, "10:00:32");
+        idle.is_active = false;
+        assert!(tracker.admit(&idle, heartbeat, at(32)));
+        
+        // After a failed write everything is written again
+        tracker.reset();
+        assert!(tracker.admit(&idle, heartbeat, at(33)));
+    }
+}
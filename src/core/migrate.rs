@@ -0,0 +1,202 @@
+//! Store Migration
+//!
+//! `driftguard migrate --from <url> --to <url>` copies a swarm's blackboard
+//! (portfolio state, trade log, histories, snapshots, statements and every
+//! other key in its namespace) to another store, then re-reads each copied
+//! key and compares it with the source. Keys are copied with `DUMP` /
+//! `RESTORE`, which keeps each key's type, contents and remaining TTL, and
+//! are translated between swarm namespaces when `--to-swarm` differs, so a
+//! swarm can be moved to another Redis or renamed. The federation registry
+//! and other swarms' keys are left behind. Redis is the only blackboard
+//! backend so far: URLs of other stores (`postgres://`, ...) are rejected
+//! until one is added here.
+
+use anyhow::{bail, ensure, Context, Result};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::core::federation;
+
+/// Keys copied per pipelined round trip
+const BATCH: usize = 200;
+
+/// A blackboard store, by URL scheme
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreUrl {
+    Redis(String),
+}
+
+impl StoreUrl {
+    pub fn parse(url: &str) -> Result<Self> {
+        let scheme = url.split_once("://").map(|(scheme, _)| scheme.to_lowercase());
+        match scheme.as_deref() {
+            Some("redis" | "rediss" | "redis+unix" | "unix") => Ok(Self::Redis(url.to_string())),
+            Some(scheme) => bail!(
+                "'{}' stores are not supported: the blackboard only has a Redis backend (redis:// or rediss://)",
+                scheme
+            ),
+            None => bail!("'{}' is not a store URL (expected redis://host:port)", url),
+        }
+    }
+
+    async fn connect(&self) -> Result<ConnectionManager> {
+        match self {
+            Self::Redis(url) => {
+                let client = redis::Client::open(url.as_str()).context("Failed to create Redis client")?;
+                ConnectionManager::new(client).await.context("Failed to connect to Redis")
+            }
+        }
+    }
+}
+
+/// What a migration copied and whether the copies matched
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MigrationReport {
+    pub keys_copied: usize,
+    /// Keys that expired or were deleted between listing and copying
+    pub keys_vanished: usize,
+    pub keys_verified: usize,
+    /// Destination keys that differ from (or are missing next to) their source
+    pub mismatched: Vec<String>,
+}
+
+/// Destination key of `key`, moved from one swarm's prefix to another's;
+/// none for keys outside the source swarm
+pub fn translate_key(key: &str, from_prefix: &str, to_prefix: &str, other_prefixes: &[String]) -> Option<String> {
+    if key == federation::FEDERATION_KEY || other_prefixes.iter().any(|prefix| key.starts_with(prefix.as_str())) {
+        return None;
+    }
+    key.strip_prefix(from_prefix).map(|rest| format!("{}{}", to_prefix, rest))
+}
+
+/// Keys of `prefix` on the store (the default swarm's being those not
+/// prefixed by another registered swarm)
+async fn list_keys(conn: &mut ConnectionManager, prefix: &str, other_prefixes: &[String]) -> Result<Vec<String>> {
+    let mut keys = Vec::new();
+    let mut iter = conn.scan_match::<_, String>(format!("{}*", prefix)).await?;
+    while let Some(key) = iter.next_item().await {
+        keys.push(key);
+    }
+    drop(iter);
+    keys.retain(|key| translate_key(key, prefix, "", other_prefixes).is_some());
+    keys.sort();
+    Ok(keys)
+}
+
+/// Serialized value and remaining TTL (ms, 0 for none) of each key; none
+/// for keys that no longer exist
+async fn dump(conn: &mut ConnectionManager, keys: &[String]) -> Result<Vec<Option<(Vec<u8>, u64)>>> {
+    let mut pipe = redis::pipe();
+    for key in keys {
+        pipe.cmd("DUMP").arg(key).cmd("PTTL").arg(key);
+    }
+    let raw: Vec<(Option<Vec<u8>>, i64)> = pipe.query_async(conn).await?;
+    Ok(raw
+        .into_iter()
+        .map(|(payload, ttl)| payload.filter(|_| ttl != -2).map(|payload| (payload, ttl.max(0) as u64)))
+        .collect())
+}
+
+/// Copy swarm `from_swarm` from store `from` to swarm `to_swarm` on store
+/// `to`, then verify every copied key. Refuses to write into a destination
+/// namespace that already has keys unless `overwrite` is set.
+pub async fn migrate(from: &str, to: &str, from_swarm: &str, to_swarm: &str, overwrite: bool) -> Result<MigrationReport> {
+    let (source, destination) = (StoreUrl::parse(from)?, StoreUrl::parse(to)?);
+    ensure!(
+        source != destination || from_swarm != to_swarm,
+        "source and destination are the same swarm on the same store"
+    );
+    let from_prefix = federation::key_prefix(from_swarm)?;
+    let to_prefix = federation::key_prefix(to_swarm)?;
+    let mut src = source.connect().await?;
+    let mut dst = destination.connect().await?;
+    
+    // The default swarm's keys are unprefixed; tell them apart from the
+    // other swarms registered on each store
+    let others = |swarms: Vec<String>, own: &str| -> Vec<String> {
+        swarms.into_iter().filter(|name| name != own).map(|name| format!("{}:", name)).collect()
+    };
+    let src_others = others(src.hkeys(federation::FEDERATION_KEY).await?, from_swarm);
+    let dst_others = others(dst.hkeys(federation::FEDERATION_KEY).await?, to_swarm);
+    
+    let existing = list_keys(&mut dst, &to_prefix, &dst_others).await?;
+    if !existing.is_empty() && !overwrite {
+        bail!(
+            "destination swarm '{}' already has {} keys; pass --overwrite to replace them",
+            to_swarm,
+            existing.len()
+        );
+    }
+    
+    let keys = list_keys(&mut src, &from_prefix, &src_others).await?;
+    info!("📦 Migrating {} keys of swarm '{}' to swarm '{}'", keys.len(), from_swarm, to_swarm);
+    let mut report = MigrationReport::default();
+    let mut copied: Vec<(String, String, Vec<u8>)> = Vec::new();
+    
+    for chunk in keys.chunks(BATCH) {
+        let dumps = dump(&mut src, chunk).await?;
+        let restored = copied.len();
+        let mut pipe = redis::pipe();
+        for (key, dumped) in chunk.iter().zip(dumps) {
+            let Some((payload, ttl)) = dumped else {
+                report.keys_vanished += 1;
+                continue;
+            };
+            let Some(target) = translate_key(key, &from_prefix, &to_prefix, &src_others) else {
+                continue;
+            };
+            pipe.cmd("RESTORE").arg(&target).arg(ttl).arg(&payload).arg("REPLACE").ignore();
+            copied.push((key.clone(), target, payload));
+        }
+        if copied.len() > restored {
+            pipe.query_async::<_, ()>(&mut dst).await?;
+        }
+    }
+    report.keys_copied = copied.len();
+    
+    // Verification: every copy must serialize exactly like its source did
+    for chunk in copied.chunks(BATCH) {
+        let targets: Vec<String> = chunk.iter().map(|(_, target, _)| target.clone()).collect();
+        let dumps = dump(&mut dst, &targets).await?;
+        for ((source_key, _, payload), dumped) in chunk.iter().zip(dumps) {
+            match dumped {
+                Some((copy, _)) if copy == *payload => report.keys_verified += 1,
+                _ => report.mismatched.push(source_key.clone()),
+            }
+        }
+    }
+    
+    if report.mismatched.is_empty() {
+        info!("✅ Migrated and verified {} keys", report.keys_verified);
+    } else {
+        warn!("⚠️ {} of {} migrated keys failed verification", report.mismatched.len(), report.keys_copied);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_url_and_keys() {
+        assert_eq!(
+            StoreUrl::parse("redis://127.0.0.1:6379").unwrap(),
+            StoreUrl::Redis("redis://127.0.0.1:6379".to_string())
+        );
+        assert!(StoreUrl::parse("rediss://cache.example.com:6380/1").is_ok());
+        assert!(StoreUrl::parse("postgres://localhost/driftguard").is_err());
+        assert!(StoreUrl::parse("localhost:6379").is_err());
+        
+        let others = vec!["lab:".to_string()];
+        // Default swarm to a named one
+        assert_eq!(translate_key("trade_log", "", "prod:", &others).as_deref(), Some("prod:trade_log"));
+        assert_eq!(translate_key("lab:trade_log", "", "prod:", &others), None);
+        assert_eq!(translate_key(federation::FEDERATION_KEY, "", "prod:", &others), None);
+        // Named swarm to the default one
+        assert_eq!(translate_key("lab:snapshots:daily:2026-03-02", "lab:", "", &[]).as_deref(), Some("snapshots:daily:2026-03-02"));
+        assert_eq!(translate_key("trade_log", "lab:", "", &[]), None);
+    }
+}
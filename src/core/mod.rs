@@ -4,10 +4,108 @@
 //! - Pheromone: Time-decaying signals for indirect agent coordination
 //! - Blackboard: Redis-backed shared environment for agent communication
 //! - Config: Centralized configuration management
+//! - Activity: Per-agent activity history
+//! - Alerts: User-defined alert rules evaluated against blackboard state
+//! - Allocation: Validation of target allocation changes
+//! - Analysis: Pure drift/opportunity evaluation behind the Analyst
+//! - Audit: Log of state-changing client commands
+//! - Auth: API keys of users mapped to the swarms they manage
+//! - Benchmarks: Ghost portfolios for comparing against naive strategies
+//! - Bootstrap: Starting cash, holdings and base currency of a deployment
+//! - Calendar: Period arithmetic for scheduled rebalancing
+//! - Clock: Exchange time zone for sessions, schedules and API timestamps
+//! - Correlation: Rolling asset correlations and diversification-breakdown alerts
+//! - Daily Summary: End-of-day consolidation of trades, permits, drift and P&L
+//! - Decay: Proactive announcement of pheromone decay
+//! - Error: Categorized errors carried in metrics and events
+//! - Federation: Several named swarms sharing one Redis
+//! - Glide Path: Time-varying target allocations
+//! - Groups: Hierarchical asset groups and group/member drift
+//! - Holdings: Broker position imports with cost basis
+//! - Logging: Reloadable log filter with per-agent levels
+//! - Metrics Writer: Batched, change-detected agent metrics writes
+//! - Migrate: Copying a swarm's blackboard to another store, with verification
+//! - Mode: Explicit system mode (LIVE … HALTED) and the kill switch
+//! - Money: Minor-unit rounding and locale-aware formatting of amounts
+//! - Notify Queue: Retried, dead-lettered delivery of Slack and webhook notifications
+//! - Optimizer: Mean-variance target suggestions within per-asset bounds
+//! - Order Queue: Approved rebalances waiting for the execution window
+//! - Performance: Time- and money-weighted returns net of cash flows
+//! - Pipeline: Configurable sniff/deposit wiring between agents
+//! - Plan: Read-only report of the rebalance the Trader would send now
+//! - Rebalance: Trade sizing and leg planning, and buy-only contribution plans
+//! - Retention: Janitor ageing out histories and measuring the Redis footprint
+//! - Reset: Soft, portfolio and hard reset scopes
+//! - Runtime: Per-agent loop timing and Redis call counts
+//! - Risk: Pure permit decision behind the Guardian
+//! - Risk Index: Composite portfolio volatility on the VIX scale
+//! - Runs: Registry of runs with version, config hash, providers and seed
+//! - Sampling: Coalescing of repeated sniff events
+//! - Scripting: Sandboxed Rhai hooks for Analyst and Guardian decisions
+//! - Secrets: Constant-time credential checks and redaction of secrets from logs
+//! - Sim Clock: Simulated market clock behind replays (speed, pause, jump)
+//! - Simulation: Seeded randomness for reproducible simulated runs
+//! - Soak: Invariants checked over a long accelerated simulated run
+//! - Statements: Daily snapshots and month-end statements
+//! - Target Engine: Risk-based (risk parity, min-variance) target weights
+//! - Timeline: Causal chain from snapshot to fills behind each trade
+//! - Trails: Recorded pheromone deposits and the heatmap built from them
+//! - Valuation: Mark-to-market, high-water mark and drawdown
+//! - Warmup: Startup history the Guardian waits for before permitting trades
+//! - Wash Sale: Guard against re-buying around loss sales
 
 pub mod physics;
 pub mod blackboard;
 pub mod config;
+pub mod activity;
+pub mod alerts;
+pub mod allocation;
+pub mod analysis;
+pub mod audit;
+pub mod auth;
+pub mod benchmarks;
+pub mod bootstrap;
+pub mod calendar;
+pub mod clock;
+pub mod correlation;
+pub mod daily_summary;
+pub mod decay;
+pub mod error;
+pub mod federation;
+pub mod glide_path;
+pub mod groups;
+pub mod holdings;
+pub mod logging;
+pub mod metrics_writer;
+pub mod migrate;
+pub mod mode;
+pub mod money;
+pub mod notify_queue;
+pub mod optimizer;
+pub mod order_queue;
+pub mod performance;
+pub mod pipeline;
+pub mod plan;
+pub mod rebalance;
+pub mod reset;
+pub mod retention;
+pub mod risk;
+pub mod risk_index;
+pub mod runs;
+pub mod runtime;
+pub mod sampling;
+pub mod scripting;
+pub mod secrets;
+pub mod sim_clock;
+pub mod simulation;
+pub mod soak;
+pub mod statements;
+pub mod target_engine;
+pub mod timeline;
+pub mod trails;
+pub mod valuation;
+pub mod warmup;
+pub mod wash_sale;
 
 pub use physics::Pheromone;
 pub use blackboard::Blackboard;
@@ -0,0 +1,273 @@
+//! System Mode
+//!
+//! One explicit answer to "how much can the swarm be trusted right now",
+//! in place of the provider's silent fall-back to simulated prices. From
+//! best to worst:
+//! - LIVE: live prices, a fresh snapshot, the store reachable
+//! - DEGRADED_DATA: some prices simulated, the snapshot stale or missing,
+//!   or a provider of the failover chain cooling down
+//! - SIMULATION: every price simulated
+//! - HALTED: the kill switch is engaged, or Redis is unreachable
+//!
+//! A monitor in the services process derives the mode every
+//! `[system_mode] interval_secs`, and the kill switch re-derives it at
+//! once. It is stored on the blackboard (`system:mode`), from where every
+//! dashboard tick and `GET /api/mode` serve it. The Guardian refuses
+//! permits while HALTED and, unless `permit_in_simulation`, in SIMULATION;
+//! the Trader sends no legs while HALTED (see `halt_reason`).
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{info, warn};
+
+use crate::core::blackboard::MarketUpdate;
+use crate::core::config::SystemModeConfig;
+use crate::core::Blackboard;
+use crate::market::QuoteSource;
+
+/// How far the swarm has degraded, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SystemMode {
+    Live,
+    DegradedData,
+    Simulation,
+    Halted,
+}
+
+impl fmt::Display for SystemMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Live => "LIVE",
+            Self::DegradedData => "DEGRADED_DATA",
+            Self::Simulation => "SIMULATION",
+            Self::Halted => "HALTED",
+        })
+    }
+}
+
+/// Body of `POST /api/kill-switch`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillSwitchRequest {
+    pub engaged: bool,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Operator stop for all trading (`POST /api/kill-switch`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KillSwitch {
+    pub reason: String,
+    pub engaged_by: String,
+    pub engaged_at: DateTime<Utc>,
+}
+
+/// The mode and what put the swarm in it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SystemStatus {
+    pub mode: SystemMode,
+    /// Every condition found, worst first; empty when LIVE
+    pub reasons: Vec<String>,
+    /// When the swarm entered this mode
+    pub since: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SystemStatus {
+    /// What the dashboard shows when the store itself can't be read
+    pub fn unreachable(error: impl fmt::Display, now: DateTime<Utc>) -> Self {
+        Self {
+            mode: SystemMode::Halted,
+            reasons: vec![format!("store unreachable: {}", error)],
+            since: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// What the mode is derived from
+#[derive(Debug, Clone, Default)]
+pub struct ModeInputs<'a> {
+    pub kill_switch: Option<&'a KillSwitch>,
+    /// Why the store could not be reached, if it couldn't
+    pub store_error: Option<String>,
+    /// The Sensor's latest snapshot
+    pub market: Option<&'a MarketUpdate>,
+}
+
+/// The worst mode the inputs call for, with every reason
+pub fn derive(inputs: &ModeInputs<'_>, now: DateTime<Utc>, config: &SystemModeConfig) -> (SystemMode, Vec<String>) {
+    let mut found: Vec<(SystemMode, String)> = Vec::new();
+
+    if let Some(switch) = inputs.kill_switch {
+        found.push((SystemMode::Halted, format!("kill switch engaged by {}: {}", switch.engaged_by, switch.reason)));
+    }
+    if let Some(error) = &inputs.store_error {
+        found.push((SystemMode::Halted, format!("store unreachable: {}", error)));
+    }
+
+    match inputs.market {
+        None => found.push((SystemMode::DegradedData, "no market snapshot yet".to_string())),
+        Some(market) => {
+            let simulated: Vec<&str> = market
+                .quotes
+                .iter()
+                .filter(|q| q.source == QuoteSource::Simulated)
+                .map(|q| q.symbol.as_str())
+                .collect();
+            if !market.quotes.is_empty() && simulated.len() == market.quotes.len() {
+                found.push((SystemMode::Simulation, "all prices simulated".to_string()));
+            } else if !simulated.is_empty() {
+                found.push((SystemMode::DegradedData, format!("simulated prices for {}", simulated.join(", "))));
+            }
+            let age_secs = DateTime::parse_from_rfc3339(&market.timestamp)
+                .map_or(f64::INFINITY, |at| (now - at.with_timezone(&Utc)).num_milliseconds() as f64 / 1000.0);
+            if age_secs > config.stale_after_secs as f64 {
+                found.push((SystemMode::DegradedData, format!("market snapshot stale ({:.0}s old)", age_secs)));
+            }
+            for provider in market.providers.iter().filter(|p| p.cooldown_remaining_secs.is_some()) {
+                found.push((
+                    SystemMode::DegradedData,
+                    format!(
+                        "{} cooling down after {} failures ({})",
+                        provider.name,
+                        provider.consecutive_failures,
+                        provider.last_error.as_deref().unwrap_or("unknown error")
+                    ),
+                ));
+            }
+        }
+    }
+
+    found.sort_by_key(|(mode, _)| std::cmp::Reverse(*mode));
+    let mode = found.first().map_or(SystemMode::Live, |(mode, _)| *mode);
+    (mode, found.into_iter().map(|(_, reason)| reason).collect())
+}
+
+/// Derive the mode now and store it; `since` carries over from the
+/// stored status while the mode holds
+pub async fn refresh(board: &Blackboard) -> SystemStatus {
+    let now = Utc::now();
+    let previous = board.get_system_status().await.ok().flatten();
+    let (kill_switch, market, store_error) = match read_inputs(board).await {
+        Ok((kill_switch, market)) => (kill_switch, market, None),
+        Err(e) => (None, None, Some(format!("{:#}", e))),
+    };
+    let inputs = ModeInputs { kill_switch: kill_switch.as_ref(), store_error, market: market.as_ref() };
+    let (mode, reasons) = derive(&inputs, now, &board.config().system_mode);
+
+    let since = match previous {
+        Some(previous) if previous.mode == mode => previous.since,
+        _ => {
+            match mode {
+                SystemMode::Live => info!("🚦 System mode: {}", mode),
+                _ => warn!("🚦 System mode: {} ({})", mode, reasons.join("; ")),
+            }
+            now
+        }
+    };
+    let status = SystemStatus { mode, reasons, since, updated_at: now };
+    if let Err(e) = board.put_system_status(&status).await {
+        warn!("🚦 Failed to store system mode: {:#}", e);
+    }
+    status
+}
+
+/// Refresh the mode every `[system_mode] interval_secs`
+pub async fn run_monitor(board: Arc<Blackboard>) -> Result<()> {
+    let config = &board.config().system_mode;
+    let mut ticker = interval(Duration::from_secs(config.interval_secs.max(1)));
+
+    info!("🚦 System mode monitor started (every {}s)", config.interval_secs);
+
+    loop {
+        ticker.tick().await;
+        refresh(&board).await;
+    }
+}
+
+/// Why nothing may trade right now: the kill switch read directly (the
+/// stored mode lags it by up to an interval), else a stored HALTED mode
+pub async fn halt_reason(board: &Blackboard) -> Result<Option<String>> {
+    if let Some(switch) = board.get_kill_switch().await? {
+        return Ok(Some(format!("kill switch engaged by {}: {}", switch.engaged_by, switch.reason)));
+    }
+    Ok(board
+        .get_system_status()
+        .await?
+        .filter(|status| status.mode == SystemMode::Halted)
+        .map(|status| format!("system HALTED ({})", status.reasons.join("; "))))
+}
+
+async fn read_inputs(board: &Blackboard) -> Result<(Option<KillSwitch>, Option<MarketUpdate>)> {
+    board.ping().await?;
+    Ok((board.get_kill_switch().await?, board.get_market_update().await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::blackboard::MarketQuoteUpdate;
+    use crate::market::ProviderHealth;
+
+    fn quote(symbol: &str, source: QuoteSource) -> MarketQuoteUpdate {
+        MarketQuoteUpdate {
+            symbol: symbol.to_string(),
+            price: 100.0,
+            change: 0.0,
+            change_pct: 0.0,
+            source,
+            simulated: source == QuoteSource::Simulated,
+        }
+    }
+
+    #[test]
+    fn test_derive_ladder() {
+        let config = SystemModeConfig::default();
+        let now = Utc::now();
+        let market = |sources: &[QuoteSource]| MarketUpdate {
+            quotes: sources.iter().zip(["SPY", "BND"]).map(|(source, symbol)| quote(symbol, *source)).collect(),
+            timestamp: now.to_rfc3339(),
+            providers: vec![],
+        };
+
+        let live = market(&[QuoteSource::Live, QuoteSource::Cached]);
+        let inputs = ModeInputs { market: Some(&live), ..Default::default() };
+        assert_eq!(derive(&inputs, now, &config), (SystemMode::Live, vec![]));
+
+        let partial = market(&[QuoteSource::Live, QuoteSource::Simulated]);
+        let (mode, reasons) = derive(&ModeInputs { market: Some(&partial), ..Default::default() }, now, &config);
+        assert_eq!(mode, SystemMode::DegradedData);
+        assert_eq!(reasons, vec!["simulated prices for BND".to_string()]);
+
+        let simulated = market(&[QuoteSource::Simulated, QuoteSource::Simulated]);
+        let inputs = ModeInputs { market: Some(&simulated), ..Default::default() };
+        assert_eq!(derive(&inputs, now, &config).0, SystemMode::Simulation);
+        let later = now + chrono::Duration::seconds(config.stale_after_secs as i64 + 1);
+        assert_eq!(derive(&inputs, later, &config).1.len(), 2);
+
+        // A chain member cooling down degrades an otherwise live snapshot
+        let cooling = ProviderHealth {
+            name: "polygon".to_string(),
+            consecutive_failures: 3,
+            last_error: Some("HTTP 429".to_string()),
+            cooldown_remaining_secs: Some(42.0),
+            ..Default::default()
+        };
+        let failing_over = MarketUpdate { providers: vec![cooling, ProviderHealth::default()], ..live.clone() };
+        let (mode, reasons) = derive(&ModeInputs { market: Some(&failing_over), ..Default::default() }, now, &config);
+        assert_eq!(mode, SystemMode::DegradedData);
+        assert_eq!(reasons, vec!["polygon cooling down after 3 failures (HTTP 429)".to_string()]);
+
+        // The kill switch outranks everything, and its reason comes first
+        let switch = KillSwitch { reason: "broker outage".to_string(), engaged_by: "ops".to_string(), engaged_at: now };
+        let (mode, reasons) = derive(&ModeInputs { kill_switch: Some(&switch), ..inputs }, now, &config);
+        assert_eq!(mode, SystemMode::Halted);
+        assert_eq!(reasons[0], "kill switch engaged by ops: broker outage");
+        assert!(SystemMode::Halted > SystemMode::Simulation);
+    }
+}
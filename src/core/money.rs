@@ -0,0 +1,237 @@
+//! Money
+//!
+//! Currency-aware rounding and formatting of amounts in the base currency.
+//! Money is held as `Decimal`, so sums of many trades stay exact. Each
+//! ISO 4217 currency has a number of minor-unit digits (2 for USD cents, 0
+//! for JPY, 3 for KWD), and settled amounts such as trade fills are
+//! rounded to that grid so they match the broker to the cent.
+//!
+//! Reports (statements, trade descriptions) format amounts for the
+//! `[reporting] locale`: decimal and grouping separators and where the
+//! currency symbol goes.
+//!
+//! Serialized, a `Decimal` is a string ("1234.56"), so an amount is never
+//! rounded through a float on its way to Redis or a client. Amounts stored
+//! as JSON numbers by earlier versions still deserialize.
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::Deserialize;
+
+/// Currencies without minor units
+const ZERO_DIGIT: &[&str] = &[
+    "BIF", "CLP", "DJF", "GNF", "ISK", "JPY", "KMF", "KRW", "PYG", "RWF", "UGX", "VND", "VUV", "XAF", "XOF", "XPF",
+];
+
+/// Currencies with thousandths
+const THREE_DIGIT: &[&str] = &["BHD", "IQD", "JOD", "KWD", "LYD", "OMR", "TND"];
+
+/// Digits after the decimal point in `currency`'s minor unit
+pub fn minor_unit_digits(currency: &str) -> u32 {
+    if ZERO_DIGIT.contains(&currency) {
+        0
+    } else if THREE_DIGIT.contains(&currency) {
+        3
+    } else {
+        2
+    }
+}
+
+/// Round to the currency's minor unit, half away from zero
+pub fn round(amount: Decimal, currency: &str) -> Decimal {
+    amount.round_dp_with_strategy(minor_unit_digits(currency), RoundingStrategy::MidpointAwayFromZero)
+}
+
+/// Amount in whole minor units (cents)
+pub fn to_minor(amount: Decimal, currency: &str) -> i64 {
+    let mut rounded = round(amount, currency);
+    rounded.rescale(minor_unit_digits(currency));
+    rounded.mantissa() as i64
+}
+
+pub fn from_minor(units: i64, currency: &str) -> Decimal {
+    Decimal::new(units, minor_unit_digits(currency))
+}
+
+/// Sum of amounts, each rounded to the minor unit first
+pub fn sum(amounts: impl IntoIterator<Item = Decimal>, currency: &str) -> Decimal {
+    amounts.into_iter().map(|a| round(a, currency)).sum()
+}
+
+/// `part` as a percentage of `whole` (0 when there is no whole)
+pub fn percent(part: Decimal, whole: Decimal) -> Decimal {
+    if whole > Decimal::ZERO {
+        part / whole * Decimal::ONE_HUNDRED
+    } else {
+        Decimal::ZERO
+    }
+}
+
+/// Decimal from a float read from a broker, a price feed or a statistic;
+/// NaN and infinities become 0
+pub fn from_f64(value: f64) -> Decimal {
+    Decimal::from_f64(value).unwrap_or_default()
+}
+
+/// Float for statistics and ratios that do not need exact money
+pub fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+/// Symbol printed for a currency; others are printed as their code
+pub fn currency_symbol(currency: &str) -> Option<&'static str> {
+    match currency {
+        "USD" => Some("$"),
+        "EUR" => Some("€"),
+        "GBP" => Some("£"),
+        "JPY" => Some("¥"),
+        "INR" => Some("₹"),
+        "KRW" => Some("₩"),
+        _ => None,
+    }
+}
+
+/// Number conventions of a reporting locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct ReportLocale {
+    pub decimal: char,
+    pub group: char,
+    /// `$1,234.56` rather than `1.234,56 €`
+    pub symbol_first: bool,
+}
+
+impl ReportLocale {
+    pub const EN_US: Self = Self { decimal: '.', group: ',', symbol_first: true };
+
+    /// Conventions for a BCP 47 tag such as `en-US`, `de-DE` or `fr`
+    pub fn parse(tag: &str) -> Option<Self> {
+        let tag = tag.trim().replace('_', "-");
+        let (language, region) = tag.split_once('-').unwrap_or((&tag, ""));
+        let comma = |group| Self { decimal: ',', group, symbol_first: false };
+        let locale = match (language.to_ascii_lowercase().as_str(), region.to_ascii_uppercase().as_str()) {
+            ("de", "CH") | ("it", "CH") | ("fr", "CH") => Self { decimal: '.', group: '\'', symbol_first: true },
+            ("en" | "ja" | "zh" | "ko" | "he" | "th", _) => Self::EN_US,
+            ("de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el", _) => comma('.'),
+            ("fr" | "sv" | "nb" | "no" | "fi" | "pl" | "cs" | "sk" | "ru" | "uk" | "hu", _) => comma(' '),
+            _ => return None,
+        };
+        Some(locale)
+    }
+}
+
+impl Default for ReportLocale {
+    fn default() -> Self {
+        Self::EN_US
+    }
+}
+
+impl TryFrom<String> for ReportLocale {
+    type Error = String;
+
+    fn try_from(tag: String) -> Result<Self, Self::Error> {
+        Self::parse(&tag).ok_or_else(|| format!("unsupported reporting locale '{}'", tag))
+    }
+}
+
+/// Formats amounts of one currency for a locale, e.g. `$1,234.56`,
+/// `1.234,56 €` or `CHF 1'234.56`
+#[derive(Debug, Clone)]
+pub struct MoneyFormat {
+    currency: String,
+    locale: ReportLocale,
+}
+
+impl MoneyFormat {
+    pub fn new(currency: &str, locale: ReportLocale) -> Self {
+        Self { currency: currency.to_string(), locale }
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    /// Number with grouping and the currency's minor-unit digits, no symbol
+    pub fn number(&self, amount: Decimal) -> String {
+        let digits = minor_unit_digits(&self.currency) as usize;
+        let fixed = format!("{:.*}", digits, round(amount, &self.currency).abs());
+        let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+        let grouped: Vec<String> = whole
+            .as_bytes()
+            .rchunks(3)
+            .rev()
+            .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+            .collect();
+        let mut out = grouped.join(&self.locale.group.to_string());
+        if !fraction.is_empty() {
+            out.push(self.locale.decimal);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    pub fn format(&self, amount: Decimal) -> String {
+        let sign = if to_minor(amount, &self.currency) < 0 { "-" } else { "" };
+        let number = self.number(amount);
+        match (currency_symbol(&self.currency), self.locale.symbol_first) {
+            (Some(symbol), true) => format!("{}{}{}", sign, symbol, number),
+            (None, true) => format!("{}{} {}", sign, self.currency, number),
+            (symbol, false) => format!("{}{} {}", sign, number, symbol.unwrap_or(&self.currency)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+    
+    #[test]
+    fn test_minor_units() {
+        assert_eq!(to_minor(dec("12.345"), "USD"), 1235);
+        assert_eq!(to_minor(dec("-0.004"), "USD"), 0);
+        assert_eq!(to_minor(dec("7"), "USD"), 700);
+        assert_eq!(from_minor(1235, "USD"), dec("12.35"));
+        assert_eq!(round(dec("1234.5"), "JPY"), dec("1235"));
+        assert_eq!(round(dec("1.23456"), "KWD"), dec("1.235"));
+        
+        // A thousand 10-cent fills add up to exactly $100
+        assert_eq!(sum(std::iter::repeat_n(dec("0.1"), 1000), "USD"), dec("100"));
+        assert_ne!(std::iter::repeat_n(0.1, 1000).sum::<f64>(), 100.0);
+        
+        assert_eq!(percent(dec("60000"), dec("100000")), dec("60"));
+        assert_eq!(percent(dec("1"), Decimal::ZERO), Decimal::ZERO);
+        assert_eq!(from_f64(f64::NAN), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_serde() {
+        // 0.1 + 0.2 has no exact f64, but survives as a string
+        let amount = dec("0.1") + dec("0.2");
+        assert_eq!(serde_json::to_string(&amount).unwrap(), "\"0.3\"");
+        assert_eq!(serde_json::from_str::<Decimal>("\"0.3\"").unwrap(), amount);
+        // Legacy floats and integers
+        assert_eq!(serde_json::from_str::<Decimal>("1234.56").unwrap(), dec("1234.56"));
+        assert_eq!(serde_json::from_str::<Decimal>("100").unwrap(), dec("100"));
+    }
+
+    #[test]
+    fn test_locale_formatting() {
+        let usd = MoneyFormat::new("USD", ReportLocale::EN_US);
+        assert_eq!(usd.format(dec("1234567.891")), "$1,234,567.89");
+        assert_eq!(usd.format(dec("-950")), "-$950.00");
+        assert_eq!(usd.format(Decimal::ZERO), "$0.00");
+        assert_eq!(usd.format(dec("-0.001")), "$0.00");
+        
+        let amount = dec("1234.5");
+        let de = ReportLocale::parse("de-DE").unwrap();
+        assert_eq!(MoneyFormat::new("EUR", de).format(amount), "1.234,50 €");
+        assert_eq!(MoneyFormat::new("JPY", ReportLocale::parse("ja").unwrap()).format(amount), "¥1,235");
+        assert_eq!(MoneyFormat::new("CHF", ReportLocale::parse("de_CH").unwrap()).format(amount), "CHF 1'234.50");
+        assert_eq!(MoneyFormat::new("SEK", ReportLocale::parse("sv-SE").unwrap()).format(-amount), "-1 234,50 SEK");
+        assert!(ReportLocale::parse("xx-YY").is_none());
+    }
+}
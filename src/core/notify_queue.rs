@@ -0,0 +1,189 @@
+//! Notification Delivery Queue
+//!
+//! Slack and webhook endpoints are most likely to be briefly unreachable
+//! during the incidents an alert is about. Instead of posting once and
+//! giving up, alerts and daily summaries are queued on the blackboard
+//! (`notify:queue`) and a worker in the services process delivers them,
+//! retrying a failed delivery up to `[alerting.delivery] max_retries` times
+//! with doubling backoff (capped at `max_backoff_secs`). A notification that
+//! still fails is appended to the dead-letter list (`notify:dead_letter`,
+//! `GET /api/notifications`). Queued notifications survive a restart.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::core::alerts::{self, AlertChannel};
+use crate::core::config::NotifyDeliveryConfig;
+use crate::core::Blackboard;
+
+/// How often the worker looks for notifications due
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A notification waiting for delivery
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: String,
+    pub channel: AlertChannel,
+    /// What sent it, e.g. `alert:Drift well past threshold`
+    pub source: String,
+    pub message: String,
+    /// Body of a webhook delivery
+    pub payload: serde_json::Value,
+    /// Deliveries tried so far
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub next_attempt_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+impl Notification {
+    pub fn new(channel: AlertChannel, source: &str, message: &str, payload: serde_json::Value, now: DateTime<Utc>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            channel,
+            source: source.to_string(),
+            message: message.to_string(),
+            payload,
+            attempts: 0,
+            created_at: now,
+            next_attempt_at: now,
+            last_error: None,
+        }
+    }
+
+    /// Record a failed delivery at `now` and schedule the next; false once
+    /// its retries are spent
+    pub fn failed(&mut self, error: String, now: DateTime<Utc>, config: &NotifyDeliveryConfig) -> bool {
+        self.attempts += 1;
+        self.last_error = Some(error);
+        if self.attempts > config.max_retries {
+            return false;
+        }
+        let backoff = config
+            .retry_backoff_secs
+            .saturating_mul(1u64 << (self.attempts - 1).min(32))
+            .min(config.max_backoff_secs.max(config.retry_backoff_secs));
+        self.next_attempt_at = now + ChronoDuration::seconds(backoff as i64);
+        true
+    }
+}
+
+/// A notification given up on after its retries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyDeadLetter {
+    pub notification: Notification,
+    /// When it was given up on
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Queue `message` for `channel`; the log channel is written at once.
+/// If the queue can't be reached the notification is posted directly,
+/// once, so it isn't lost with the store.
+pub async fn enqueue(
+    board: &Blackboard,
+    channel: AlertChannel,
+    source: &str,
+    message: &str,
+    payload: serde_json::Value,
+) {
+    if channel == AlertChannel::Log {
+        warn!("{}", message);
+        return;
+    }
+    let notification = Notification::new(channel, source, message, payload, Utc::now());
+    if let Err(e) = board.put_notification(&notification).await {
+        warn!("Notifications: Failed to queue {:?} notification ({:#}), sending directly", channel, e);
+        if let Err(e) = deliver(board, &notification).await {
+            warn!("Notifications: {:?} delivery failed ({:#}): {}", channel, e, message);
+        }
+    }
+}
+
+async fn deliver(board: &Blackboard, notification: &Notification) -> Result<()> {
+    let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(10)).build()?;
+    let config = &board.config().alerting;
+    alerts::post(&client, config, notification.channel, &notification.message, &notification.payload).await
+}
+
+/// Deliver queued notifications as they fall due
+pub async fn run_delivery(board: Arc<Blackboard>) -> Result<()> {
+    let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(10)).build()?;
+    let mut ticker = interval(POLL_INTERVAL);
+
+    info!("📨 Notification delivery started");
+
+    loop {
+        ticker.tick().await;
+        if let Err(e) = deliver_due(&board, &client).await {
+            warn!("Notifications: Delivery pass failed: {:#}", e);
+        }
+    }
+}
+
+/// One pass over the queue
+async fn deliver_due(board: &Blackboard, client: &reqwest::Client) -> Result<()> {
+    let config = &board.config().alerting;
+    let now = Utc::now();
+
+    for mut notification in board.get_notifications().await? {
+        if notification.next_attempt_at > now {
+            continue;
+        }
+        let Err(e) = alerts::post(client, config, notification.channel, &notification.message, &notification.payload).await else {
+            debug!("Notifications: Delivered {} ({:?}, attempt {})",
+                notification.source, notification.channel, notification.attempts + 1);
+            board.remove_notification(&notification.id).await?;
+            continue;
+        };
+        if notification.failed(format!("{:#}", e), Utc::now(), &config.delivery) {
+            warn!("Notifications: {:?} delivery of {} failed ({:#}), retrying at {}",
+                notification.channel, notification.source, e, notification.next_attempt_at.to_rfc3339());
+            board.put_notification(&notification).await?;
+        } else {
+            error!("Notifications: Giving up on {} after {} attempts ({:#}): {}",
+                notification.source, notification.attempts, e, notification.message);
+            board.remove_notification(&notification.id).await?;
+            let letter = NotifyDeadLetter { notification, timestamp: Utc::now() };
+            board.push_notify_dead_letter(&letter, config.delivery.dead_letter_max_entries).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let config = NotifyDeliveryConfig {
+            max_retries: 3,
+            retry_backoff_secs: 10,
+            max_backoff_secs: 25,
+            dead_letter_max_entries: 10,
+        };
+        let now = Utc::now();
+        let mut notification = Notification::new(AlertChannel::Slack, "alert:drift", "drift", serde_json::json!({}), now);
+        assert_eq!(notification.next_attempt_at, now);
+
+        // 10s, then 20s, then capped at 25s
+        assert!(notification.failed("timeout".to_string(), now, &config));
+        assert_eq!(notification.attempts, 1);
+        assert_eq!(notification.next_attempt_at, now + ChronoDuration::seconds(10));
+        assert!(notification.failed("timeout".to_string(), now, &config));
+        assert_eq!(notification.next_attempt_at, now + ChronoDuration::seconds(20));
+        assert!(notification.failed("503".to_string(), now, &config));
+        assert_eq!(notification.next_attempt_at, now + ChronoDuration::seconds(25));
+        assert_eq!(notification.last_error.as_deref(), Some("503"));
+
+        // Retries spent
+        assert!(!notification.failed("503".to_string(), now, &config));
+        assert_eq!(notification.attempts, 4);
+    }
+}
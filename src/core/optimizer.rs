@@ -0,0 +1,437 @@
+//! Portfolio Optimizer
+//!
+//! Suggests target weights at `POST /api/optimize` (with `[optimizer]
+//! enabled`); nothing is applied. Each asset's expected return and
+//! volatility, and their correlations, are estimated from the last
+//! `lookback_days` daily returns of the startup history (with today's live
+//! price), annualized. The weights that maximize the Sharpe ratio, or
+//! minimize variance, are then searched within each asset's bounds: the
+//! request's `constraints`, else the asset's `min_pct`/`max_pct`, always
+//! inside `[allocation] min_asset_pct`/`max_asset_pct`.
+//!
+//! The solver walks a grid of weights summing to 100% (`step_pct` apart,
+//! coarser when many assets would make it too large), then refines the
+//! best point by moving ever smaller amounts between pairs of assets. For
+//! the stocks/bonds portfolio the suggestion comes with the body that
+//! applies it through `POST /api/allocation`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+use crate::core::allocation;
+use crate::core::blackboard::TargetAllocation;
+use crate::core::config::Config;
+use crate::core::risk_index::{self, AssetSeries, TRADING_DAYS_PER_YEAR};
+
+/// Most grid points searched before the grid is coarsened
+const MAX_GRID_POINTS: f64 = 250_000.0;
+
+/// Refinement stops below this transfer (%)
+const MIN_TRANSFER_PCT: f64 = 0.005;
+
+/// What the suggested weights optimize
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    #[default]
+    MaxSharpe,
+    MinVariance,
+}
+
+/// Bounds on one asset's weight (%)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct WeightBounds {
+    #[serde(default)]
+    pub min_pct: Option<f64>,
+    #[serde(default)]
+    pub max_pct: Option<f64>,
+}
+
+/// Body of `POST /api/optimize`; every field is optional
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OptimizeRequest {
+    #[serde(default)]
+    pub objective: Objective,
+    /// Bounds by symbol, in place of the asset's configured limits
+    #[serde(default)]
+    pub constraints: BTreeMap<String, WeightBounds>,
+    /// Annual, as a fraction (default `[optimizer] risk_free_rate`)
+    #[serde(default)]
+    pub risk_free_rate: Option<f64>,
+    #[serde(default)]
+    pub lookback_days: Option<usize>,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum OptimizeError {
+    #[error("no daily history for {0} (see [warmup] history_days)")]
+    MissingHistory(String),
+    #[error("{found} daily returns in common, {needed} needed")]
+    NotEnoughHistory { found: usize, needed: usize },
+    #[error("constraint for '{0}', which isn't a configured asset")]
+    UnknownSymbol(String),
+    #[error("{symbol}: bounds {min:.2}%–{max:.2}% are empty")]
+    EmptyBounds { symbol: String, min: f64, max: f64 },
+    #[error("bounds allow {min:.2}%–{max:.2}% in total, which excludes 100%")]
+    Infeasible { min: f64, max: f64 },
+    #[error("risk_free_rate must be a finite number")]
+    NotFinite,
+}
+
+/// Annualized return and risk estimates, in `symbols` order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimates {
+    pub symbols: Vec<String>,
+    /// Mean daily log return × trading days
+    pub returns: Vec<f64>,
+    pub covariance: Vec<Vec<f64>>,
+    /// Daily returns the estimates rest on
+    pub samples: usize,
+}
+
+impl Estimates {
+    /// From the last `lookback` returns common to every asset
+    pub fn from_series(series: &[AssetSeries], lookback: usize) -> Self {
+        let all = risk_index::common_returns(series);
+        let recent = &all[all.len().saturating_sub(lookback.max(2))..];
+        let n = recent.len() as f64;
+        let k = series.len();
+        let means: Vec<f64> = (0..k).map(|i| recent.iter().map(|r| r[i]).sum::<f64>() / n).collect();
+        let covariance = (0..k)
+            .map(|i| {
+                (0..k)
+                    .map(|j| {
+                        recent.iter().map(|r| (r[i] - means[i]) * (r[j] - means[j])).sum::<f64>() / (n - 1.0).max(1.0)
+                            * TRADING_DAYS_PER_YEAR
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            symbols: series.iter().map(|s| s.symbol.clone()).collect(),
+            returns: means.iter().map(|mean| mean * TRADING_DAYS_PER_YEAR).collect(),
+            covariance,
+            samples: recent.len(),
+        }
+    }
+
+    pub fn volatility(&self, i: usize) -> f64 {
+        self.covariance[i][i].max(0.0).sqrt()
+    }
+
+    pub fn correlations(&self) -> Vec<Vec<f64>> {
+        let k = self.symbols.len();
+        (0..k)
+            .map(|i| {
+                (0..k)
+                    .map(|j| match (i == j, self.volatility(i) * self.volatility(j)) {
+                        (true, _) => 1.0,
+                        (false, scale) if scale > 0.0 => (self.covariance[i][j] / scale).clamp(-1.0, 1.0),
+                        _ => 0.0,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Expected return and volatility of weights given in percent
+    pub fn portfolio(&self, weights_pct: &[f64]) -> (f64, f64) {
+        let x: Vec<f64> = weights_pct.iter().map(|w| w / 100.0).collect();
+        let expected = x.iter().zip(&self.returns).map(|(w, r)| w * r).sum();
+        let variance: f64 = (0..x.len()).map(|i| (0..x.len()).map(|j| x[i] * x[j] * self.covariance[i][j]).sum::<f64>()).sum();
+        (expected, variance.max(0.0).sqrt())
+    }
+}
+
+/// One asset's suggested weight
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SuggestedWeight {
+    pub symbol: String,
+    pub weight_pct: f64,
+    /// The configured target, for comparison
+    pub current_pct: f64,
+    pub min_pct: f64,
+    pub max_pct: f64,
+    pub expected_return: f64,
+    pub volatility: f64,
+}
+
+/// The optimizer's answer
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Suggestion {
+    pub objective: Objective,
+    pub weights: Vec<SuggestedWeight>,
+    /// Annualized, as fractions
+    pub expected_return: f64,
+    pub volatility: f64,
+    pub sharpe: Option<f64>,
+    pub risk_free_rate: f64,
+    /// Pairwise correlations, in `weights` order
+    pub correlations: Vec<Vec<f64>>,
+    pub returns: usize,
+    /// `POST /api/allocation` body applying the suggestion, for the
+    /// stocks/bonds portfolio
+    pub allocation: Option<TargetAllocation>,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// Each asset's (min, max) weight, in `series` order
+pub fn bounds(series: &[AssetSeries], request: &OptimizeRequest, config: &Config) -> Result<Vec<(f64, f64)>, OptimizeError> {
+    if let Some(unknown) = request.constraints.keys().find(|symbol| !series.iter().any(|s| &s.symbol == *symbol)) {
+        return Err(OptimizeError::UnknownSymbol(unknown.clone()));
+    }
+    let global = (config.allocation.min_asset_pct.max(0.0), config.allocation.max_asset_pct.min(100.0));
+    let bounds = series
+        .iter()
+        .map(|s| {
+            let (min, max) = match request.constraints.get(&s.symbol) {
+                Some(bounds) => (bounds.min_pct, bounds.max_pct),
+                None => allocation::asset_limits(&config.portfolio, &s.symbol),
+            };
+            let (min, max) = (min.unwrap_or(0.0).max(global.0), max.unwrap_or(100.0).min(global.1));
+            if min > max {
+                return Err(OptimizeError::EmptyBounds { symbol: s.symbol.clone(), min, max });
+            }
+            Ok((min, max))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let (total_min, total_max) = bounds.iter().fold((0.0, 0.0), |(lo, hi), (min, max)| (lo + min, hi + max));
+    if total_min > 100.0 + 1e-9 || total_max < 100.0 - 1e-9 {
+        return Err(OptimizeError::Infeasible { min: total_min, max: total_max });
+    }
+    Ok(bounds)
+}
+
+/// Suggest weights for `series` (at least two assets with enough history)
+pub fn optimize(
+    series: &[AssetSeries],
+    request: &OptimizeRequest,
+    config: &Config,
+    now: DateTime<Utc>,
+) -> Result<Suggestion, OptimizeError> {
+    let settings = &config.optimizer;
+    let risk_free_rate = request.risk_free_rate.unwrap_or(settings.risk_free_rate);
+    if !risk_free_rate.is_finite() {
+        return Err(OptimizeError::NotFinite);
+    }
+    let bounds = bounds(series, request, config)?;
+    let estimates = Estimates::from_series(series, request.lookback_days.unwrap_or(settings.lookback_days));
+    let needed = settings.min_returns.max(2);
+    if estimates.samples < needed {
+        return Err(OptimizeError::NotEnoughHistory { found: estimates.samples, needed });
+    }
+
+    let score = |weights: &[f64]| {
+        let (expected, volatility) = estimates.portfolio(weights);
+        match request.objective {
+            Objective::MinVariance => -volatility,
+            Objective::MaxSharpe => (expected - risk_free_rate) / volatility.max(1e-12),
+        }
+    };
+    let weights = round_weights(refine(&grid_search(&bounds, settings.step_pct, &score), &bounds, settings.step_pct, &score), &bounds);
+    let (expected_return, volatility) = estimates.portfolio(&weights);
+
+    let allocation = two_asset_target(series, &weights, config);
+    Ok(Suggestion {
+        objective: request.objective,
+        weights: series
+            .iter()
+            .enumerate()
+            .map(|(i, s)| SuggestedWeight {
+                symbol: s.symbol.clone(),
+                weight_pct: weights[i],
+                current_pct: s.weight,
+                min_pct: bounds[i].0,
+                max_pct: bounds[i].1,
+                expected_return: estimates.returns[i],
+                volatility: estimates.volatility(i),
+            })
+            .collect(),
+        expected_return,
+        volatility,
+        sharpe: (volatility > 0.0).then(|| (expected_return - risk_free_rate) / volatility),
+        risk_free_rate,
+        correlations: estimates.correlations(),
+        returns: estimates.samples,
+        allocation,
+        computed_at: now,
+    })
+}
+
+/// Grid units per 100%: `step_pct` apart, halved until the grid is small enough
+fn grid_units(assets: usize, step_pct: f64) -> usize {
+    let mut units = (100.0 / step_pct.clamp(0.01, 100.0)).round().max(1.0) as usize;
+    // Weightings of `units` over `assets` assets: C(units + assets - 1, assets - 1)
+    let points = |units: usize| (1..assets).map(|i| (units + i) as f64 / i as f64).product::<f64>();
+    while units > 1 && points(units) > MAX_GRID_POINTS {
+        units /= 2;
+    }
+    units
+}
+
+/// The best grid point inside the bounds, or the lowest-bound weights
+/// topped up in order when no grid point fits
+fn grid_search(bounds: &[(f64, f64)], step_pct: f64, score: &impl Fn(&[f64]) -> f64) -> Vec<f64> {
+    let units = grid_units(bounds.len(), step_pct);
+    let unit_pct = 100.0 / units as f64;
+
+    let mut fallback: Vec<f64> = bounds.iter().map(|(min, _)| *min).collect();
+    let mut left = 100.0 - fallback.iter().sum::<f64>();
+    for (weight, (_, max)) in fallback.iter_mut().zip(bounds) {
+        let add = left.min(max - *weight).max(0.0);
+        *weight += add;
+        left -= add;
+    }
+
+    let mut best = (score(&fallback), fallback);
+    let mut weights = vec![0.0; bounds.len()];
+    walk_grid(0, units, unit_pct, bounds, &mut weights, &mut |weights| {
+        let value = score(weights);
+        if value > best.0 {
+            best = (value, weights.to_vec());
+        }
+    });
+    best.1
+}
+
+/// Visit every grid weighting of the remaining `units` over assets `i..`
+fn walk_grid(i: usize, units: usize, unit_pct: f64, bounds: &[(f64, f64)], weights: &mut [f64], visit: &mut impl FnMut(&[f64])) {
+    let (min, max) = bounds[i];
+    let lowest = (min / unit_pct - 1e-9).ceil().max(0.0) as usize;
+    let highest = ((max / unit_pct + 1e-9).floor() as usize).min(units);
+    if i == bounds.len() - 1 {
+        if (lowest..=highest).contains(&units) {
+            weights[i] = units as f64 * unit_pct;
+            visit(weights);
+        }
+        return;
+    }
+    for taken in lowest..=highest {
+        weights[i] = taken as f64 * unit_pct;
+        walk_grid(i + 1, units - taken, unit_pct, bounds, weights, visit);
+    }
+}
+
+/// Move weight between pairs of assets while it helps, halving the amount
+/// moved down to `MIN_TRANSFER_PCT`
+fn refine(start: &[f64], bounds: &[(f64, f64)], step_pct: f64, score: &impl Fn(&[f64]) -> f64) -> Vec<f64> {
+    let mut weights = start.to_vec();
+    let mut best = score(&weights);
+    let mut transfer = 100.0 / grid_units(bounds.len(), step_pct) as f64 / 2.0;
+    while transfer >= MIN_TRANSFER_PCT {
+        let mut improved = false;
+        for from in 0..weights.len() {
+            for to in 0..weights.len() {
+                let amount = transfer.min(weights[from] - bounds[from].0).min(bounds[to].1 - weights[to]);
+                if from == to || amount <= 1e-12 {
+                    continue;
+                }
+                let mut candidate = weights.clone();
+                candidate[from] -= amount;
+                candidate[to] += amount;
+                let value = score(&candidate);
+                if value > best + 1e-15 {
+                    (weights, best, improved) = (candidate, value, true);
+                }
+            }
+        }
+        if !improved {
+            transfer /= 2.0;
+        }
+    }
+    weights
+}
+
+/// Round to basis points, the largest weight absorbing the rounding
+fn round_weights(weights: Vec<f64>, bounds: &[(f64, f64)]) -> Vec<f64> {
+    let mut rounded: Vec<f64> = weights
+        .iter()
+        .zip(bounds)
+        .map(|(w, (min, max))| ((w * 100.0).round() / 100.0).clamp(*min, *max))
+        .collect();
+    let residual = 100.0 - rounded.iter().sum::<f64>();
+    if let Some(largest) = (0..rounded.len()).max_by(|a, b| rounded[*a].total_cmp(&rounded[*b])) {
+        rounded[largest] = ((rounded[largest] + residual) * 100.0).round() / 100.0;
+    }
+    rounded
+}
+
+/// The allocation request for the stocks/bonds portfolio, if it validates
+fn two_asset_target(series: &[AssetSeries], weights: &[f64], config: &Config) -> Option<TargetAllocation> {
+    let portfolio = &config.portfolio;
+    let weight = |symbol: &str| series.iter().position(|s| s.symbol == symbol).map(|i| weights[i]);
+    if series.len() != 2 {
+        return None;
+    }
+    let stocks_pct = weight(&portfolio.stocks_symbol)?;
+    weight(&portfolio.bonds_symbol)?;
+    allocation::validate(stocks_pct, 100.0 - stocks_pct, &config.allocation, portfolio).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::daily_history::DailyBar;
+    use chrono::NaiveDate;
+
+    fn series(symbol: &str, weight: f64, returns: &[f64]) -> AssetSeries {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut close = 100.0;
+        let mut bars = vec![DailyBar { date: start, close }];
+        for (i, r) in returns.iter().enumerate() {
+            close *= 1.0 + r;
+            bars.push(DailyBar { date: start + chrono::Duration::days(i as i64 + 1), close });
+        }
+        AssetSeries { symbol: symbol.to_string(), weight, bars }
+    }
+
+    #[test]
+    fn test_optimize_with_constraints() {
+        let mut config = Config::default();
+        config.optimizer.min_returns = 10;
+        let now = Utc::now();
+        // Stocks swing ±2% and drift up; bonds swing ±0.5%, uncorrelated
+        let stocks: Vec<f64> = (0..40).map(|i| if i % 2 == 0 { 0.022 } else { -0.018 }).collect();
+        let bonds: Vec<f64> = (0..40).map(|i| if (i / 2) % 2 == 0 { 0.0052 } else { -0.0048 }).collect();
+        let assets = [series(&config.portfolio.stocks_symbol, 60.0, &stocks), series(&config.portfolio.bonds_symbol, 40.0, &bonds)];
+
+        // Uncorrelated min-variance weights are proportional to 1/σ²: about 6/94
+        let request = OptimizeRequest { objective: Objective::MinVariance, ..Default::default() };
+        let min_variance = optimize(&assets, &request, &config, now).unwrap();
+        assert!((min_variance.weights[0].weight_pct - 5.9).abs() < 0.5, "{:?}", min_variance.weights);
+        assert_eq!(min_variance.weights.iter().map(|w| w.weight_pct).sum::<f64>(), 100.0);
+        assert!(min_variance.correlations[0][1].abs() < 0.1);
+        let target = min_variance.allocation.as_ref().unwrap();
+        assert_eq!(target.stocks_pct, min_variance.weights[0].weight_pct);
+
+        // Max Sharpe leans further into the drifting asset; a cap binds
+        let sharpe = optimize(&assets, &OptimizeRequest::default(), &config, now).unwrap();
+        assert!(sharpe.weights[0].weight_pct > min_variance.weights[0].weight_pct);
+        assert!(sharpe.sharpe.unwrap() >= min_variance.sharpe.unwrap());
+        let capped = OptimizeRequest {
+            constraints: BTreeMap::from([(config.portfolio.bonds_symbol.clone(), WeightBounds { min_pct: None, max_pct: Some(70.0) })]),
+            ..request.clone()
+        };
+        assert_eq!(optimize(&assets, &capped, &config, now).unwrap().weights[1].weight_pct, 70.0);
+
+        // Three assets, the grid coarsened and refined
+        let three = [assets[0].clone(), assets[1].clone(), series("GLD", 0.0, &bonds)];
+        let spread = optimize(&three, &request, &config, now).unwrap();
+        assert!(spread.allocation.is_none());
+        assert!((spread.weights.iter().map(|w| w.weight_pct).sum::<f64>() - 100.0).abs() < 1e-9);
+
+        // Bad constraints and too little history
+        let infeasible = OptimizeRequest {
+            constraints: BTreeMap::from([("SPY".to_string(), WeightBounds { min_pct: Some(60.0), max_pct: None }),
+                ("BND".to_string(), WeightBounds { min_pct: Some(60.0), max_pct: None })]),
+            ..Default::default()
+        };
+        assert!(matches!(optimize(&assets, &infeasible, &config, now), Err(OptimizeError::Infeasible { .. })));
+        let unknown = OptimizeRequest { constraints: BTreeMap::from([("QQQ".to_string(), WeightBounds::default())]), ..Default::default() };
+        assert_eq!(optimize(&assets, &unknown, &config, now), Err(OptimizeError::UnknownSymbol("QQQ".to_string())));
+        let short = OptimizeRequest { lookback_days: Some(5), ..Default::default() };
+        assert_eq!(optimize(&assets, &short, &config, now), Err(OptimizeError::NotEnoughHistory { found: 5, needed: 10 }));
+    }
+}
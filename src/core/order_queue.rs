@@ -0,0 +1,116 @@
+//! Order Queue
+//!
+//! Approved rebalances waiting for the execution window. With
+//! `[order_queue] enabled`, the Trader queues each permit it claims on the
+//! blackboard (`orders:pending`) instead of trading at once, and executes it
+//! once the window is open: weekdays between `window_open` and
+//! `window_close` (exchange time), minus their first and last minutes. A
+//! newer permit supersedes a waiting one. By the time the window opens the opportunity
+//! may have decayed: an order expires unexecuted when it has waited longer
+//! than `max_age_minutes`, or when it was raised on drift and drift is now
+//! back inside the threshold.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::agents::analyst::RebalanceTrigger;
+use crate::agents::guardian::ExecutionPermit;
+use crate::core::clock;
+use crate::core::config::OrderQueueConfig;
+use crate::execution::OrderLeg;
+
+/// Days searched for the next window (covers a weekend)
+const MAX_DAYS_AHEAD: i64 = 7;
+
+/// An approved rebalance waiting for the execution window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingOrder {
+    pub id: String,
+    pub permit: ExecutionPermit,
+    /// Legs the permit approved; sent as they are once the window opens
+    pub legs: Vec<OrderLeg>,
+    pub queued_at: String,
+    pub expires_at: String,
+}
+
+impl PendingOrder {
+    pub fn new(permit: ExecutionPermit, now: DateTime<Utc>, config: &OrderQueueConfig) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            legs: permit.legs.clone(),
+            permit,
+            queued_at: now.to_rfc3339(),
+            expires_at: (now + Duration::minutes(config.max_age_minutes.max(0))).to_rfc3339(),
+        }
+    }
+
+    /// Why the order should no longer execute at `now`, if it shouldn't
+    pub fn expiry_reason(&self, now: DateTime<Utc>, drift_pct: f64, threshold_pct: f64) -> Option<String> {
+        let expired = DateTime::parse_from_rfc3339(&self.expires_at).map_or(true, |at| now >= at);
+        if expired {
+            return Some(format!("queued since {}, past its max age", self.queued_at));
+        }
+        let on_drift = self.permit.drift_analysis.trigger == RebalanceTrigger::Drift;
+        (on_drift && drift_pct < threshold_pct)
+            .then(|| format!("drift back to {:.1}% (threshold {:.1}%)", drift_pct, threshold_pct))
+    }
+}
+
+/// The execution window on `date` with its edges skipped; none on weekends,
+/// for unparseable times, or when the skipped edges leave nothing
+pub fn window_on(date: NaiveDate, config: &OrderQueueConfig, tz: Tz) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        return None;
+    }
+    let parse = |s: &str| NaiveTime::parse_from_str(s, "%H:%M").ok();
+    let open = clock::at(date, parse(&config.window_open)?, tz) + Duration::minutes(config.skip_first_minutes as i64);
+    let close = clock::at(date, parse(&config.window_close)?, tz) - Duration::minutes(config.skip_last_minutes as i64);
+    (open < close).then_some((open, close))
+}
+
+/// When the window open at `now` closes, if one is
+pub fn open_until(now: DateTime<Utc>, config: &OrderQueueConfig, tz: Tz) -> Option<DateTime<Utc>> {
+    window_on(clock::today(now, tz), config, tz)
+        .filter(|(open, close)| now >= *open && now < *close)
+        .map(|(_, close)| close)
+}
+
+/// Start of the next window after `now` (now itself when one is open)
+pub fn next_open(now: DateTime<Utc>, config: &OrderQueueConfig, tz: Tz) -> Option<DateTime<Utc>> {
+    if open_until(now, config, tz).is_some() {
+        return Some(now);
+    }
+    (0..=MAX_DAYS_AHEAD)
+        .filter_map(|days| window_on(clock::today(now, tz) + Duration::days(days), config, tz))
+        .map(|(open, _)| open)
+        .find(|open| *open > now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_execution_window() {
+        let config = OrderQueueConfig { enabled: true, ..OrderQueueConfig::default() };
+        
+        // 2024-07-30 is a Tuesday; window 14:00–19:30 UTC, trading 14:15–19:15
+        let at = |d, h, m| Utc.with_ymd_and_hms(2024, 7, d, h, m, 0).unwrap();
+        assert_eq!(open_until(at(30, 14, 5), &config, Tz::UTC), None);
+        assert_eq!(open_until(at(30, 14, 15), &config, Tz::UTC), Some(at(30, 19, 15)));
+        assert_eq!(open_until(at(30, 19, 20), &config, Tz::UTC), None);
+        
+        assert_eq!(next_open(at(30, 9, 0), &config, Tz::UTC), Some(at(30, 14, 15)));
+        assert_eq!(next_open(at(30, 16, 0), &config, Tz::UTC), Some(at(30, 16, 0)));
+        assert_eq!(next_open(at(30, 20, 0), &config, Tz::UTC), Some(at(31, 14, 15)));
+        // Friday evening waits for Monday
+        let friday = Utc.with_ymd_and_hms(2024, 8, 2, 20, 0, 0).unwrap();
+        assert_eq!(next_open(friday, &config, Tz::UTC), Some(Utc.with_ymd_and_hms(2024, 8, 5, 14, 15, 0).unwrap()));
+        
+        // Skipped edges that cover the whole window leave none
+        let closed = OrderQueueConfig { skip_first_minutes: 200, skip_last_minutes: 200, ..config };
+        assert_eq!(next_open(at(30, 9, 0), &closed, Tz::UTC), None);
+    }
+}
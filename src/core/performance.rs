@@ -0,0 +1,188 @@
+//! Performance
+//!
+//! Time-weighted and money-weighted returns over a date range, from the
+//! portfolio value history and the cash flows recorded whenever the
+//! portfolio is restarted from new cash or holdings (`history:cash_flows`).
+//! The time-weighted return chains the returns between history samples with
+//! each flow taken out of the sample it lands in, so contributions and
+//! withdrawals don't read as gains; the money-weighted return is the IRR
+//! of the starting value, the flows and the ending value.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::valuation::PortfolioHistoryPoint;
+use crate::export::{parse_timestamp, DateRange};
+
+const SECS_PER_YEAR: f64 = 365.25 * 86_400.0;
+
+/// Money added to (positive) or taken from the portfolio from outside
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CashFlow {
+    pub timestamp: String,
+    pub amount: f64,
+    /// What moved the money, e.g. "holdings import" or "portfolio init"
+    pub source: String,
+}
+
+/// Returns over a range
+#[derive(Debug, Clone, Serialize)]
+pub struct Performance {
+    pub range: String,
+    /// First and last history sample in the range
+    pub start: String,
+    pub end: String,
+    pub start_value: f64,
+    pub end_value: f64,
+    pub net_cash_flows: f64,
+    /// Change in value not explained by cash flows
+    pub gain: f64,
+    pub time_weighted_return_pct: f64,
+    /// IRR compounded over the range; none when it has no solution
+    pub money_weighted_return_pct: Option<f64>,
+    /// IRR per year; none for ranges shorter than a year
+    pub annualized_irr_pct: Option<f64>,
+    pub samples: usize,
+    pub cash_flows: usize,
+}
+
+/// Dates covered by a named range (`ytd`, `mtd`, `1m`, `3m`, `6m`, `1y`,
+/// `all`) as of `today`
+pub fn named_range(name: &str, today: NaiveDate) -> Result<DateRange> {
+    let from = match name.to_lowercase().as_str() {
+        "all" => None,
+        "ytd" => NaiveDate::from_ymd_opt(today.year(), 1, 1),
+        "mtd" => today.with_day(1),
+        "1m" => today.checked_sub_months(chrono::Months::new(1)),
+        "3m" => today.checked_sub_months(chrono::Months::new(3)),
+        "6m" => today.checked_sub_months(chrono::Months::new(6)),
+        "1y" => today.checked_sub_months(chrono::Months::new(12)),
+        other => bail!("unknown range '{}' (expected ytd, mtd, 1m, 3m, 6m, 1y or all)", other),
+    };
+    Ok(DateRange { from, to: None })
+}
+
+/// Rate solving `Σ amount × (1 + r)^-years = 0` by bisection, if the
+/// flows change sign across the search interval
+fn irr(flows: &[(f64, f64)]) -> Option<f64> {
+    let npv = |rate: f64| -> f64 {
+        flows.iter().map(|(years, amount)| amount / (1.0 + rate).powf(*years)).sum()
+    };
+    let (mut low, mut high) = (-0.9999, 1_000.0);
+    if npv(low).signum() == npv(high).signum() {
+        return None;
+    }
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if npv(mid).signum() == npv(low).signum() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+/// Returns over the samples of `history` (oldest first) with `flows`
+/// between them; none with fewer than two samples
+pub fn compute(range: &str, history: &[PortfolioHistoryPoint], flows: &[CashFlow]) -> Option<Performance> {
+    let samples: Vec<(DateTime<Utc>, f64)> = history
+        .iter()
+        .filter_map(|p| Some((parse_timestamp(&p.timestamp)?, p.total_value)))
+        .collect();
+    let (&(start, start_value), &(end, end_value)) = (samples.first()?, samples.last()?);
+    if samples.len() < 2 || start_value <= 0.0 {
+        return None;
+    }
+    let flows: Vec<(DateTime<Utc>, f64)> = flows
+        .iter()
+        .filter_map(|f| Some((parse_timestamp(&f.timestamp)?, f.amount)))
+        .filter(|(at, _)| *at > start && *at <= end)
+        .collect();
+    
+    // Chain sub-period returns, removing each flow from the sample after it
+    let mut growth = 1.0;
+    for pair in samples.windows(2) {
+        let ((from, before), (to, after)) = (pair[0], pair[1]);
+        let flow: f64 = flows.iter().filter(|(at, _)| *at > from && *at <= to).map(|(_, amount)| amount).sum();
+        if before > 0.0 {
+            growth *= (after - flow) / before;
+        }
+    }
+    
+    let years = |at: DateTime<Utc>| (at - start).num_milliseconds() as f64 / 1000.0 / SECS_PER_YEAR;
+    let span = years(end);
+    let mut irr_flows = vec![(0.0, -start_value)];
+    irr_flows.extend(flows.iter().map(|(at, amount)| (years(*at), -amount)));
+    irr_flows.push((span, end_value));
+    let rate = if span > 0.0 { irr(&irr_flows) } else { None };
+    
+    let net_cash_flows: f64 = flows.iter().map(|(_, amount)| amount).sum();
+    Some(Performance {
+        range: range.to_string(),
+        start: start.to_rfc3339(),
+        end: end.to_rfc3339(),
+        start_value,
+        end_value,
+        net_cash_flows,
+        gain: end_value - start_value - net_cash_flows,
+        time_weighted_return_pct: (growth - 1.0) * 100.0,
+        money_weighted_return_pct: rate.map(|r| ((1.0 + r).powf(span) - 1.0) * 100.0),
+        annualized_irr_pct: rate.filter(|_| end - start >= Duration::days(365)).map(|r| r * 100.0),
+        samples: samples.len(),
+        cash_flows: flows.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::blackboard::PortfolioState;
+    use crate::core::money;
+    use crate::core::valuation::DrawdownState;
+    use rust_decimal::Decimal;
+
+    fn point(day: i64, total_value: f64) -> PortfolioHistoryPoint {
+        let at = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap() + Duration::days(day);
+        let state = PortfolioState::from_values(money::from_f64(total_value), Decimal::ZERO, None);
+        let mut point = PortfolioHistoryPoint::new(&state, 60.0, &DrawdownState::new(total_value), None);
+        point.timestamp = at.to_rfc3339();
+        point
+    }
+
+    fn flow(day: i64, amount: f64) -> CashFlow {
+        let at = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap() + Duration::days(day);
+        CashFlow { timestamp: at.to_rfc3339(), amount, source: "test".to_string() }
+    }
+
+    #[test]
+    fn test_contributions_are_not_returns() {
+        // +10% on 100k, then a 100k contribution that earns nothing
+        let history = [point(0, 100_000.0), point(100, 110_000.0), point(101, 210_000.0), point(200, 210_000.0)];
+        let flows = [flow(101, 100_000.0)];
+        let performance = compute("all", &history, &flows).unwrap();
+        
+        assert!((performance.time_weighted_return_pct - 10.0).abs() < 1e-9);
+        assert!((performance.gain - 10_000.0).abs() < 1e-9);
+        // Most of the money was at work in the flat half, so the IRR sits lower
+        let mwr = performance.money_weighted_return_pct.unwrap();
+        assert!(mwr > 0.0 && mwr < 10.0);
+        assert!(performance.annualized_irr_pct.is_none());
+        
+        // Without flows the two agree
+        let flat = compute("all", &[point(0, 100.0), point(400, 110.0)], &[]).unwrap();
+        assert!((flat.money_weighted_return_pct.unwrap() - 10.0).abs() < 1e-6);
+        assert!(flat.annualized_irr_pct.is_some());
+        assert!(compute("all", &history[..1], &flows).is_none());
+    }
+
+    #[test]
+    fn test_named_ranges() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        assert_eq!(named_range("ytd", today).unwrap().from, NaiveDate::from_ymd_opt(2026, 1, 1));
+        assert_eq!(named_range("3m", today).unwrap().from, NaiveDate::from_ymd_opt(2026, 7, 16));
+        assert_eq!(named_range("all", today).unwrap().from, None);
+        assert!(named_range("forever", today).is_err());
+    }
+}
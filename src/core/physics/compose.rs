@@ -0,0 +1,149 @@
+//! Pheromone Composition
+//!
+//! A single deposit of a noisy signal (one price snapshot) can swing the
+//! agents that read it. Composition merges the last few deposits of a type
+//! into one payload, each weighted by its current intensity, so the fresh
+//! deposit dominates and older ones fade out of the mix as they decay.
+//!
+//! Only floating-point fields are averaged. Everything else — strings,
+//! integers, flags, the payload's shape — comes from the most recent
+//! deposit. An array element is matched against the element at the same
+//! index in older deposits only when the arrays are the same length and
+//! the element's string fields agree (so one symbol's price is never
+//! averaged with another's).
+
+use chrono::{DateTime, Utc};
+use serde_json::{Number, Value};
+
+use super::PheromonePayload;
+
+/// Merge `deposits` (most recent first) as of `now`; `None` without any
+pub fn merge(deposits: &[PheromonePayload<Value>], now: DateTime<Utc>) -> Option<Value> {
+    let weighted: Vec<(&Value, f64)> = deposits
+        .iter()
+        .map(|deposit| (&deposit.data, deposit.pheromone.intensity_at(now)))
+        .collect();
+    let (latest, _) = weighted.first()?;
+    Some(merge_value(latest, &weighted))
+}
+
+/// `latest` with its floats replaced by the weighted mean over `weighted`
+/// (which includes `latest` itself)
+fn merge_value(latest: &Value, weighted: &[(&Value, f64)]) -> Value {
+    match latest {
+        Value::Number(n) if n.is_f64() => {
+            let (sum, total) = weighted
+                .iter()
+                .filter_map(|(value, weight)| value.as_f64().map(|x| (x * weight, *weight)))
+                .fold((0.0, 0.0), |(sum, total), (x, weight)| (sum + x, total + weight));
+            if total > 0.0 {
+                Number::from_f64(sum / total).map_or_else(|| latest.clone(), Value::Number)
+            } else {
+                latest.clone()
+            }
+        }
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(name, value)| {
+                    let matching: Vec<(&Value, f64)> = weighted
+                        .iter()
+                        .filter_map(|(other, weight)| other.get(name).map(|v| (v, *weight)))
+                        .collect();
+                    (name.clone(), merge_value(value, &matching))
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let matching: Vec<(&Value, f64)> = weighted
+                        .iter()
+                        .filter_map(|(other, weight)| {
+                            let other = other.as_array().filter(|a| a.len() == items.len())?.get(i)?;
+                            same_identity(item, other).then_some((other, *weight))
+                        })
+                        .collect();
+                    merge_value(item, &matching)
+                })
+                .collect(),
+        ),
+        _ => latest.clone(),
+    }
+}
+
+/// Whether two array elements describe the same thing: objects must agree
+/// on every string field, other values only need to be the same kind
+fn same_identity(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => a
+            .iter()
+            .filter(|(_, value)| value.is_string())
+            .all(|(name, value)| b.get(name) == Some(value)),
+        _ => std::mem::discriminant(a) == std::mem::discriminant(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::physics::Pheromone;
+    use serde_json::json;
+
+    fn deposit(data: Value, intensity: f64, age_secs: i64, now: DateTime<Utc>) -> PheromonePayload<Value> {
+        let mut pheromone = Pheromone::new("Price Freshness", intensity, 0.0);
+        pheromone.created_at = now - chrono::Duration::seconds(age_secs);
+        PheromonePayload::new(data, pheromone)
+    }
+
+    fn snapshot(spy: f64, bnd: f64, stamp: &str) -> Value {
+        json!({
+            "quotes": [{"symbol": "SPY", "price": spy}, {"symbol": "BND", "price": bnd}],
+            "timestamp": stamp,
+            "count": 2,
+        })
+    }
+
+    #[test]
+    fn test_merge_by_intensity() {
+        let now = Utc::now();
+        assert!(merge(&[], now).is_none());
+
+        // Weights 0.75 and 0.25 (no decay, so the initial intensities)
+        let merged = merge(
+            &[
+                deposit(snapshot(100.0, 80.0, "t2"), 0.75, 1, now),
+                deposit(snapshot(104.0, 76.0, "t1"), 0.25, 5, now),
+            ],
+            now,
+        )
+        .unwrap();
+        assert_eq!(merged["quotes"][0]["price"].as_f64(), Some(101.0));
+        assert_eq!(merged["quotes"][1]["price"].as_f64(), Some(79.0));
+        assert_eq!(merged["timestamp"], "t2");
+        assert_eq!(merged["count"], 2);
+
+        // Reordered quotes don't mix symbols
+        let swapped = json!({
+            "quotes": [{"symbol": "BND", "price": 60.0}, {"symbol": "SPY", "price": 200.0}],
+            "timestamp": "t1",
+            "count": 2,
+        });
+        let merged = merge(
+            &[deposit(snapshot(100.0, 80.0, "t2"), 0.5, 0, now), deposit(swapped, 0.5, 5, now)],
+            now,
+        )
+        .unwrap();
+        assert_eq!(merged["quotes"][0]["price"].as_f64(), Some(100.0));
+
+        // Decay shifts the weight toward the fresh deposit
+        let mut fresh = deposit(json!({"score": 1.0}), 1.0, 0, now);
+        fresh.pheromone.decay_rate = 0.5;
+        let mut old = deposit(json!({"score": 0.0}), 1.0, 10, now);
+        old.pheromone.decay_rate = 0.5;
+        let score = merge(&[fresh, old], now).unwrap()["score"].as_f64().unwrap();
+        assert!(score > 0.99 && score < 1.0, "{}", score);
+    }
+}
@@ -0,0 +1,64 @@
+//! Decay Curve
+//!
+//! The clock-free math behind `Pheromone`: every function takes elapsed
+//! time explicitly and uses nothing beyond std float math. It therefore
+//! compiles for wasm32 as well — the browser package in `wasm/` includes
+//! this file as-is, so the dashboard computes the same intensities as the
+//! swarm between server pushes.
+
+/// Seconds between two millisecond timestamps, at the precision pheromones
+/// are aged with
+pub fn elapsed_secs(created_at_ms: i64, now_ms: i64) -> f64 {
+    (now_ms - created_at_ms) as f64 / 1000.0
+}
+
+/// Intensity `elapsed_secs` after deposit, I(t) = I₀ × e^(-λt), clamped to
+/// the valid range
+pub fn intensity_at(initial_intensity: f64, decay_rate: f64, elapsed_secs: f64) -> f64 {
+    (initial_intensity * (-decay_rate * elapsed_secs).exp()).clamp(0.0, 1.0)
+}
+
+/// Seconds after deposit at which the intensity reaches `threshold`
+/// (solve threshold = I₀ × e^(-λt) for t)
+pub fn time_to_threshold(initial_intensity: f64, decay_rate: f64, threshold: f64) -> f64 {
+    -(threshold / initial_intensity).ln() / decay_rate
+}
+
+/// Half-life in seconds
+pub fn half_life(decay_rate: f64) -> f64 {
+    0.693 / decay_rate
+}
+
+/// Intensities at `samples` evenly spaced points from `elapsed_secs` to
+/// `horizon_secs` later (inclusive)
+pub fn forecast(
+    initial_intensity: f64,
+    decay_rate: f64,
+    elapsed_secs: f64,
+    horizon_secs: f64,
+    samples: usize,
+) -> Vec<f64> {
+    let step = if samples > 1 { horizon_secs / (samples - 1) as f64 } else { 0.0 };
+    (0..samples)
+        .map(|i| intensity_at(initial_intensity, decay_rate, elapsed_secs + i as f64 * step))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_is_deterministic() {
+        assert_eq!(intensity_at(1.0, 0.5, 0.0), 1.0);
+        assert!((intensity_at(1.0, 0.3, half_life(0.3)) - 0.5).abs() < 0.001);
+        assert_eq!(intensity_at(1.5, 0.1, 0.0), 1.0);
+        
+        let t = time_to_threshold(0.9, 0.2, 0.3);
+        assert!((intensity_at(0.9, 0.2, t) - 0.3).abs() < 1e-9);
+        assert_eq!(elapsed_secs(1_000, 3_500), 2.5);
+        
+        let curve = forecast(1.0, 0.5, 2.0, 4.0, 3);
+        assert_eq!(curve, vec![intensity_at(1.0, 0.5, 2.0), intensity_at(1.0, 0.5, 4.0), intensity_at(1.0, 0.5, 6.0)]);
+    }
+}
@@ -7,10 +7,16 @@
 //! This creates "antifragile" behavior: when data sources fail, pheromones
 //! decay naturally, causing downstream agents to go dormant rather than
 //! acting on stale data.
+//!
+//! The decay math itself lives in `curve`, free of clocks so it can be
+//! shared with the browser build; `compose` merges recent deposits of a
+//! type into one freshness-weighted payload.
+
+pub mod compose;
+pub mod curve;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::time::Instant;
 
 use crate::core::Config;
 
@@ -50,11 +56,25 @@ impl Pheromone {
     /// Calculate current intensity using exponential decay
     /// I(t) = I₀ × e^(-λt)
     pub fn current_intensity(&self) -> f64 {
-        let elapsed_secs = (Utc::now() - self.created_at).num_milliseconds() as f64 / 1000.0;
-        let intensity = self.initial_intensity * (-self.decay_rate * elapsed_secs).exp();
-        
-        // Clamp to valid range
-        intensity.max(0.0).min(1.0)
+        curve::intensity_at(self.initial_intensity, self.decay_rate, self.age_secs())
+    }
+    
+    /// Intensity at instant `at`, so several pheromones can be judged as of
+    /// the same moment
+    pub fn intensity_at(&self, at: DateTime<Utc>) -> f64 {
+        let elapsed = curve::elapsed_secs(self.created_at.timestamp_millis(), at.timestamp_millis());
+        curve::intensity_at(self.initial_intensity, self.decay_rate, elapsed)
+    }
+    
+    /// Projected intensity `secs_ahead` seconds from now
+    pub fn intensity_in(&self, secs_ahead: f64) -> f64 {
+        curve::intensity_at(self.initial_intensity, self.decay_rate, self.age_secs() + secs_ahead)
+    }
+    
+    /// Projected intensities at `samples` evenly spaced points from now to
+    /// `horizon_secs` ahead (inclusive)
+    pub fn forecast(&self, horizon_secs: f64, samples: usize) -> Vec<f64> {
+        curve::forecast(self.initial_intensity, self.decay_rate, self.age_secs(), horizon_secs, samples)
     }
     
     /// Check if pheromone is still "active" (above threshold)
@@ -65,27 +85,23 @@ impl Pheromone {
     /// Calculate time remaining until pheromone drops below threshold
     /// Returns None if already below threshold
     pub fn time_until_inactive(&self, threshold: f64) -> Option<f64> {
-        let current = self.current_intensity();
-        if current <= threshold {
+        let elapsed = self.age_secs();
+        if curve::intensity_at(self.initial_intensity, self.decay_rate, elapsed) <= threshold {
             return None;
         }
         
-        // Solve for t: threshold = I₀ × e^(-λt)
-        // t = -ln(threshold/I₀) / λ
-        let elapsed = (Utc::now() - self.created_at).num_milliseconds() as f64 / 1000.0;
-        let total_time = -(threshold / self.initial_intensity).ln() / self.decay_rate;
-        
+        let total_time = curve::time_to_threshold(self.initial_intensity, self.decay_rate, threshold);
         Some((total_time - elapsed).max(0.0))
     }
     
     /// Get the half-life of this pheromone in seconds
     pub fn half_life(&self) -> f64 {
-        0.693 / self.decay_rate
+        curve::half_life(self.decay_rate)
     }
     
     /// Get age of pheromone in seconds
     pub fn age_secs(&self) -> f64 {
-        (Utc::now() - self.created_at).num_milliseconds() as f64 / 1000.0
+        curve::elapsed_secs(self.created_at.timestamp_millis(), Utc::now().timestamp_millis())
     }
 }
 
@@ -130,15 +146,23 @@ pub enum PheromoneType {
     
     /// Deposited by Trader after executing a trade
     TradeExecuted,
+    
+    /// Deposited by Sensor alongside each snapshot with its quality score
+    DataQuality,
+    
+    /// Deposited by Forecast with the projected time until drift breaches the threshold
+    DriftForecast,
 }
 
 impl PheromoneType {
     /// All pheromone types for iteration
-    pub const ALL: [PheromoneType; 4] = [
+    pub const ALL: [PheromoneType; 6] = [
         PheromoneType::PriceFreshness,
         PheromoneType::RebalanceOpportunity,
         PheromoneType::ExecutionPermit,
         PheromoneType::TradeExecuted,
+        PheromoneType::DataQuality,
+        PheromoneType::DriftForecast,
     ];
 
     /// Get the Redis key for this pheromone type
@@ -148,8 +172,15 @@ impl PheromoneType {
             Self::RebalanceOpportunity => "pheromone:rebalance_opportunity",
             Self::ExecutionPermit => "pheromone:execution_permit",
             Self::TradeExecuted => "pheromone:trade_executed",
+            Self::DataQuality => "pheromone:data_quality",
+            Self::DriftForecast => "pheromone:drift_forecast",
         }
     }
+
+    /// Redis key of the ring of this type's most recent deposits
+    pub fn recent_key(&self) -> String {
+        format!("{}:recent", self.key())
+    }
     
     /// Get human-readable label
     pub fn label(&self) -> &'static str {
@@ -158,6 +189,8 @@ impl PheromoneType {
             Self::RebalanceOpportunity => "Rebalance Opportunity",
             Self::ExecutionPermit => "Execution Permit",
             Self::TradeExecuted => "Trade Executed",
+            Self::DataQuality => "Data Quality",
+            Self::DriftForecast => "Drift Forecast",
         }
     }
 
@@ -168,6 +201,8 @@ impl PheromoneType {
             Self::RebalanceOpportunity => config.pheromones.rebalance_opportunity_decay,
             Self::ExecutionPermit => config.pheromones.execution_permit_decay,
             Self::TradeExecuted => config.pheromones.trade_executed_decay,
+            Self::DataQuality => config.pheromones.data_quality_decay,
+            Self::DriftForecast => config.pheromones.drift_forecast_decay,
         }
     }
 
@@ -178,6 +213,8 @@ impl PheromoneType {
             Self::RebalanceOpportunity => config.thresholds.rebalance_opportunity,
             Self::ExecutionPermit => config.thresholds.execution_permit,
             Self::TradeExecuted => config.thresholds.trade_executed,
+            Self::DataQuality => config.thresholds.data_quality,
+            Self::DriftForecast => config.thresholds.drift_forecast,
         }
     }
 }
@@ -214,6 +251,31 @@ mod tests {
         assert!(!pheromone.is_active(0.5));
     }
     
+    #[test]
+    fn test_forecast_follows_decay_curve() {
+        let pheromone = Pheromone::new("test", 1.0, 0.5);
+        let forecast = pheromone.forecast(4.0, 5);
+        
+        assert_eq!(forecast.len(), 5);
+        for (i, intensity) in forecast.iter().enumerate() {
+            let expected = (-0.5 * i as f64).exp();
+            assert!((intensity - expected).abs() < 0.01, "t={}: {} vs {}", i, intensity, expected);
+        }
+        
+        // The curve crosses the threshold when time_until_inactive says it does
+        let until = pheromone.time_until_inactive(0.5).unwrap();
+        assert!((pheromone.intensity_in(until) - 0.5).abs() < 0.01);
+    }
+    
+    #[test]
+    fn test_intensity_at_instant() {
+        let pheromone = Pheromone::new("test", 1.0, 1.0);
+        let later = pheromone.created_at + chrono::Duration::milliseconds(693);
+        
+        assert!((pheromone.intensity_at(pheromone.created_at) - 1.0).abs() < 1e-12);
+        assert!((pheromone.intensity_at(later) - 0.5).abs() < 0.001);
+    }
+    
     #[test]
     fn test_half_life_calculation() {
         let pheromone = Pheromone::new("test", 1.0, 0.3);
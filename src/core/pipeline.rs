@@ -0,0 +1,232 @@
+//! Pipeline Wiring
+//!
+//! Which pheromone feeds which agent, read from the `[pipeline]` config
+//! section. An agent acts only while every pheromone in its `requires`
+//! list is active, and deposits only the pheromones in its `deposits`
+//! list — so e.g. the Guardian can be made to wait for both
+//! `RebalanceOpportunity` and `DataQuality` without code changes.
+//!
+//! Each agent's primary input (the pheromone whose payload it works on,
+//! such as the Analyst's market snapshot) is still sniffed in code; the
+//! wiring adds gates on top of it and decides which outputs are emitted.
+//!
+//! `AgentRole` also selects which agents a process runs (`[agent] roles`
+//! or `driftguard run --agents`), so the swarm can be split across hosts.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::core::config::{AgentWiring, PipelineConfig};
+use crate::core::physics::PheromoneType;
+use crate::core::blackboard::SnapshotView;
+use crate::core::{Blackboard, Config};
+
+/// The agents whose wiring is configurable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentRole {
+    Sensor,
+    Analyst,
+    Guardian,
+    Trader,
+    Forecast,
+}
+
+impl AgentRole {
+    pub const ALL: [AgentRole; 5] = [
+        AgentRole::Sensor,
+        AgentRole::Analyst,
+        AgentRole::Guardian,
+        AgentRole::Trader,
+        AgentRole::Forecast,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Sensor => "Sensor",
+            Self::Analyst => "Analyst",
+            Self::Guardian => "Guardian",
+            Self::Trader => "Trader",
+            Self::Forecast => "Forecast",
+        }
+    }
+
+    /// The pheromone whose payload this agent consumes (read regardless of wiring)
+    pub fn primary_input(&self) -> Option<PheromoneType> {
+        match self {
+            Self::Sensor => None,
+            Self::Analyst => Some(PheromoneType::PriceFreshness),
+            Self::Guardian => Some(PheromoneType::RebalanceOpportunity),
+            Self::Trader => Some(PheromoneType::ExecutionPermit),
+            Self::Forecast => Some(PheromoneType::PriceFreshness),
+        }
+    }
+
+    /// The pheromone this agent exists to produce, which its errors are reported on
+    pub fn primary_output(&self) -> PheromoneType {
+        match self {
+            Self::Sensor => PheromoneType::PriceFreshness,
+            Self::Analyst => PheromoneType::RebalanceOpportunity,
+            Self::Guardian => PheromoneType::ExecutionPermit,
+            Self::Trader => PheromoneType::TradeExecuted,
+            Self::Forecast => PheromoneType::DriftForecast,
+        }
+    }
+}
+
+impl std::str::FromStr for AgentRole {
+    type Err = anyhow::Error;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "sensor" => Ok(Self::Sensor),
+            "analyst" => Ok(Self::Analyst),
+            "guardian" => Ok(Self::Guardian),
+            "trader" => Ok(Self::Trader),
+            "forecast" => Ok(Self::Forecast),
+            other => bail!("unknown agent '{}' (expected sensor, analyst, guardian, trader or forecast)", other),
+        }
+    }
+}
+
+impl AgentWiring {
+    pub fn requires(&self, ptype: PheromoneType) -> bool {
+        self.requires.contains(&ptype)
+    }
+
+    pub fn deposits(&self, ptype: PheromoneType) -> bool {
+        self.deposits.contains(&ptype)
+    }
+}
+
+impl PipelineConfig {
+    /// Wiring for one agent
+    pub fn wiring(&self, role: AgentRole) -> &AgentWiring {
+        match role {
+            AgentRole::Sensor => &self.sensor,
+            AgentRole::Analyst => &self.analyst,
+            AgentRole::Guardian => &self.guardian,
+            AgentRole::Trader => &self.trader,
+            AgentRole::Forecast => &self.forecast,
+        }
+    }
+
+    /// Wiring mistakes that would leave an agent dormant or ignore config
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        
+        for role in AgentRole::ALL {
+            let wiring = self.wiring(role);
+            
+            for &ptype in &wiring.requires {
+                let fed = AgentRole::ALL
+                    .iter()
+                    .any(|other| *other != role && self.wiring(*other).deposits(ptype));
+                if !fed {
+                    warnings.push(format!(
+                        "{} requires {} but no other agent deposits it",
+                        role.label(), ptype.label()
+                    ));
+                }
+            }
+            
+            if let Some(primary) = role.primary_input() {
+                if !wiring.requires(primary) {
+                    warnings.push(format!(
+                        "{} always reads {}; add it to requires",
+                        role.label(), primary.label()
+                    ));
+                }
+            }
+        }
+        
+        warnings
+    }
+}
+
+/// Required pheromones currently below their activation threshold, skipping
+/// the ones the agent checks itself (emits no events)
+pub async fn inactive_inputs(
+    board: &Blackboard,
+    role: AgentRole,
+    handled: &[PheromoneType],
+) -> Result<Vec<PheromoneType>> {
+    let mut inactive = Vec::new();
+    
+    for &ptype in &board.config().pipeline.wiring(role).requires {
+        if handled.contains(&ptype) {
+            continue;
+        }
+        if board.get_intensity(ptype).await? < ptype.threshold(board.config()) {
+            inactive.push(ptype);
+        }
+    }
+    
+    Ok(inactive)
+}
+
+/// `inactive_inputs` as of a snapshot view
+pub fn inactive_in_view(view: &SnapshotView<'_>, config: &Config, role: AgentRole, handled: &[PheromoneType]) -> Vec<PheromoneType> {
+    config
+        .pipeline
+        .wiring(role)
+        .requires
+        .iter()
+        .copied()
+        .filter(|ptype| !handled.contains(ptype) && view.intensity(*ptype) < ptype.threshold(config))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_wiring_is_complete() {
+        let pipeline = PipelineConfig::default();
+        assert!(pipeline.warnings().is_empty(), "{:?}", pipeline.warnings());
+        
+        let guardian = pipeline.wiring(AgentRole::Guardian);
+        assert!(guardian.requires(PheromoneType::RebalanceOpportunity));
+        assert!(guardian.requires(PheromoneType::DataQuality));
+        assert!(pipeline.wiring(AgentRole::Trader).requires(PheromoneType::ExecutionPermit));
+    }
+
+    #[test]
+    fn test_wiring_from_config() {
+        let pipeline: PipelineConfig = toml::from_str(r#"
+            [guardian]
+            requires = ["RebalanceOpportunity"]
+            deposits = ["ExecutionPermit"]
+        "#).unwrap();
+        
+        assert!(!pipeline.wiring(AgentRole::Guardian).requires(PheromoneType::DataQuality));
+        // Unlisted agents keep their defaults
+        assert!(pipeline.wiring(AgentRole::Sensor).deposits(PheromoneType::DataQuality));
+        assert!(pipeline.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_role_parsing() {
+        assert_eq!("Trader".parse::<AgentRole>().unwrap(), AgentRole::Trader);
+        assert_eq!("sensor".parse::<AgentRole>().unwrap(), AgentRole::Sensor);
+        assert!("broker".parse::<AgentRole>().is_err());
+        
+        #[derive(Deserialize)]
+        struct Roles { roles: Vec<AgentRole> }
+        let parsed: Roles = toml::from_str(r#"roles = ["sensor", "analyst"]"#).unwrap();
+        assert_eq!(parsed.roles, vec![AgentRole::Sensor, AgentRole::Analyst]);
+    }
+    
+    #[test]
+    fn test_unfed_and_missing_primary_inputs_warn() {
+        let mut pipeline = PipelineConfig::default();
+        pipeline.sensor.deposits = vec![PheromoneType::PriceFreshness];
+        pipeline.trader.requires = vec![];
+        
+        let warnings = pipeline.warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("Guardian requires Data Quality"));
+        assert!(warnings[1].starts_with("Trader always reads Execution Permit"));
+    }
+}
@@ -0,0 +1,274 @@
+//! Trade Plan
+//!
+//! `driftguard plan` and `GET /api/plan` show the rebalance the Trader
+//! would send right now, for users who place their orders by hand. The
+//! plan is built from the blackboard the way the Trader builds its legs
+//! (achievable target, deltas, sells first) and adds what the Trader
+//! checks on the way: estimated costs and tax, the wash-sale guard, the
+//! execution window and the blackout calendar. It only reads: no pheromone
+//! is sniffed or deposited and nothing is queued. The Guardian's volatility
+//! rule is not re-run; `permit_active` tells whether it has currently
+//! granted a permit.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+use crate::agents::sensor::MarketSnapshot;
+use crate::core::blackboard::{PortfolioState, TargetAllocation};
+use crate::core::holdings::{self, ImportedHoldings};
+use crate::core::money::{self, MoneyFormat, ReportLocale};
+use crate::core::physics::PheromoneType;
+use crate::core::rebalance::{self, TaxImpact};
+use crate::core::wash_sale::{self, RecentFill};
+use crate::core::{bootstrap, calendar, order_queue, Blackboard, Config};
+use crate::execution::{OrderLeg, Side};
+
+/// The rebalance the swarm would execute now, and what stands in its way
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradePlan {
+    pub generated_at: String,
+    pub currency: String,
+    /// Target set for the swarm
+    pub target: TargetAllocation,
+    /// Target the legs rebalance to (differs when locked holdings pin a sleeve)
+    pub achievable_target: TargetAllocation,
+    pub drift_pct: Decimal,
+    pub threshold_pct: f64,
+    /// Sells first, as the Trader sends them
+    pub legs: Vec<OrderLeg>,
+    pub before: PortfolioState,
+    pub after: PortfolioState,
+    pub estimated_cost: Decimal,
+    /// None without imported cost basis for a sleeve that would be sold
+    pub tax: Option<TaxImpact>,
+    /// Rules that shaped the legs
+    pub constraints: Vec<String>,
+    /// Why the Trader would not send the legs now (empty: it would)
+    pub held_back: Vec<String>,
+    /// Whether the Guardian currently has an execution permit out
+    pub permit_active: bool,
+}
+
+impl TradePlan {
+    /// Plan the rebalance of `state` to `achievable` (the closest target to
+    /// `target` that keeps locked holdings), checked against the wash-sale
+    /// guard's `recent` fills
+    pub fn build(
+        config: &Config,
+        state: &PortfolioState,
+        target: &TargetAllocation,
+        achievable: &TargetAllocation,
+        holdings: Option<&ImportedHoldings>,
+        recent: &[RecentFill],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let preview = rebalance::preview_allocation(config, state, target, achievable, holdings);
+        let drift_pct = (state.stocks_pct - money::from_f64(target.stocks_pct)).abs();
+        let threshold_pct = config.portfolio.drift_threshold;
+        let mut constraints = Vec::new();
+        let mut held_back = Vec::new();
+        
+        if achievable != target {
+            constraints.push(format!(
+                "locked holdings: stocks target {:.2}% moved to {:.2}%",
+                target.stocks_pct, achievable.stocks_pct
+            ));
+        }
+        if money::to_f64(drift_pct) <= threshold_pct {
+            held_back.push(format!(
+                "drift {:.2} pts is within the {:.2} pt threshold",
+                drift_pct, threshold_pct
+            ));
+        }
+        if preview.legs.is_empty() {
+            held_back.push("no leg is worth sending".to_string());
+        }
+        
+        let wash_sale = &config.wash_sale;
+        if wash_sale.enabled {
+            let realized_gain = |leg: &OrderLeg| {
+                holdings
+                    .and_then(|h| TaxImpact::estimate(std::slice::from_ref(leg), state, h, config))
+                    .map(|tax| tax.realized_gain)
+            };
+            for conflict in wash_sale::conflicts(&preview.legs, realized_gain, recent, now, wash_sale) {
+                held_back.push(format!("wash sale: {}", conflict.reason));
+            }
+        }
+        
+        let queue = &config.order_queue;
+        if queue.enabled {
+            match order_queue::open_until(now, queue, config.exchange.timezone) {
+                Some(close) => constraints.push(format!("execution window open until {}", close.to_rfc3339())),
+                None => held_back.push(match order_queue::next_open(now, queue, config.exchange.timezone) {
+                    Some(open) => format!("execution window closed (opens {})", open.to_rfc3339()),
+                    None => "execution window closed".to_string(),
+                }),
+            }
+        }
+        
+        Self {
+            generated_at: now.to_rfc3339(),
+            currency: config.portfolio.base_currency.clone(),
+            target: target.clone(),
+            achievable_target: achievable.clone(),
+            drift_pct,
+            threshold_pct,
+            legs: preview.legs,
+            before: preview.before,
+            after: preview.after,
+            estimated_cost: preview.estimated_cost,
+            tax: preview.tax,
+            constraints,
+            held_back,
+            permit_active: false,
+        }
+    }
+
+    /// Plain-text report, amounts formatted for `locale`
+    pub fn render(&self, swarm: &str, locale: ReportLocale) -> String {
+        let money = MoneyFormat::new(&self.currency, locale);
+        let mut out = String::new();
+        let _ = writeln!(out, "Trade plan for swarm '{}' at {}", swarm, self.generated_at);
+        let _ = writeln!(
+            out,
+            "Portfolio  {}: stocks {:.2}% / bonds {:.2}%",
+            money.format(self.before.total_value),
+            self.before.stocks_pct,
+            self.before.bonds_pct
+        );
+        let _ = writeln!(
+            out,
+            "Target     stocks {:.2}% / bonds {:.2}% (drift {:.2} pts, threshold {:.2})",
+            self.achievable_target.stocks_pct, self.achievable_target.bonds_pct, self.drift_pct, self.threshold_pct
+        );
+        if self.legs.is_empty() {
+            let _ = writeln!(out, "Legs       none");
+        }
+        for (i, leg) in self.legs.iter().enumerate() {
+            let side = match leg.side {
+                Side::Buy => "BUY ",
+                Side::Sell => "SELL",
+            };
+            let label = if i == 0 { "Legs" } else { "" };
+            let _ = writeln!(out, "{:<10} {} {:<6} {}", label, side, leg.symbol, money.format(leg.notional));
+        }
+        let _ = writeln!(
+            out,
+            "After      stocks {:.2}% / bonds {:.2}%",
+            self.after.stocks_pct, self.after.bonds_pct
+        );
+        let _ = writeln!(out, "Costs      {}", money.format(self.estimated_cost));
+        let _ = match &self.tax {
+            Some(tax) => writeln!(
+                out,
+                "Tax        {} on {} realized ({:.0}%)",
+                money.format(tax.estimated_tax),
+                money.format(tax.realized_gain),
+                tax.rate * 100.0
+            ),
+            None => writeln!(out, "Tax        unknown (no cost basis for a sold sleeve)"),
+        };
+        for constraint in &self.constraints {
+            let _ = writeln!(out, "Applied    {}", constraint);
+        }
+        for reason in &self.held_back {
+            let _ = writeln!(out, "Held back  {}", reason);
+        }
+        let _ = writeln!(
+            out,
+            "Permit     {}",
+            if self.permit_active { "active" } else { "none (the Guardian has not granted one)" }
+        );
+        out
+    }
+}
+
+/// Plan the rebalance of the swarm's current portfolio; none before the
+/// portfolio is initialized
+pub async fn plan(board: &Blackboard) -> Result<Option<TradePlan>> {
+    let Some(state) = board.get_portfolio_state().await? else {
+        return Ok(None);
+    };
+    let config = board.config();
+    let now = Utc::now();
+    let target = board.get_target_allocation().await?;
+    let snapshot = board.peek::<MarketSnapshot>(PheromoneType::PriceFreshness).await?.map(|reading| reading.data);
+    let achievable = holdings::achievable_target(board, &state, &target, snapshot.as_ref()).await?;
+    let imported = board.get_holdings().await?;
+    let recent = if config.wash_sale.enabled {
+        board.get_recent_fills(config.wash_sale.max_fills).await?
+    } else {
+        Vec::new()
+    };
+    
+    let mut plan = TradePlan::build(config, &state, &target, &achievable, imported.as_ref(), &recent, now);
+    plan.currency = bootstrap::current(board).await?.base_currency;
+    plan.permit_active = board
+        .peek::<serde_json::Value>(PheromoneType::ExecutionPermit)
+        .await?
+        .is_some_and(|reading| reading.is_active);
+    
+    let blackout = &config.blackout;
+    // As for the Guardian, an unreadable calendar disables event blackouts
+    let event_days = match &blackout.events_file {
+        Some(path) if blackout.enabled => calendar::load_event_days(path).unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    if let Some(reason) = calendar::blackout_reason(now, blackout, &event_days, config.exchange.timezone) {
+        plan.held_back.push(format!("blackout: {}", reason));
+    }
+    Ok(Some(plan))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::WashSaleConfig;
+
+    fn target(stocks_pct: f64) -> TargetAllocation {
+        TargetAllocation { stocks_pct, bonds_pct: 100.0 - stocks_pct }
+    }
+
+    #[test]
+    fn test_build() {
+        let mut config = Config::default();
+        config.costs.commission_per_trade = 1.0;
+        config.costs.spread_bps = 0.0;
+        let now = Utc::now();
+        let state = PortfolioState::from_values(money::from_f64(70_000.0), money::from_f64(30_000.0), None);
+        
+        let plan = TradePlan::build(&config, &state, &target(60.0), &target(60.0), None, &[], now);
+        assert_eq!(plan.legs.len(), 2);
+        assert_eq!(plan.legs[0].side, Side::Sell);
+        assert_eq!(plan.legs[0].notional, money::from_f64(10_000.0));
+        assert_eq!(plan.estimated_cost, money::from_f64(2.0));
+        assert!(plan.held_back.is_empty());
+        assert!(plan.render("default", ReportLocale::EN_US).contains("SELL SPY    $10,000.00"));
+        
+        // Locked stocks narrow the rebalance
+        let plan = TradePlan::build(&config, &state, &target(60.0), &target(67.0), None, &[], now);
+        assert_eq!(plan.legs[0].notional, money::from_f64(3_000.0));
+        assert_eq!(plan.constraints.len(), 1);
+        
+        // A sale at a loss right after buying trips the wash-sale guard
+        config.wash_sale = WashSaleConfig { enabled: true, assume_loss_without_basis: true, ..WashSaleConfig::default() };
+        let bought = RecentFill {
+            symbol: "SPY".to_string(),
+            side: Side::Buy,
+            notional: money::from_f64(1_000.0),
+            realized_gain: None,
+            timestamp: now.to_rfc3339(),
+        };
+        let plan = TradePlan::build(&config, &state, &target(60.0), &target(60.0), None, &[bought], now);
+        assert!(plan.held_back.iter().any(|reason| reason.starts_with("wash sale")));
+        
+        let balanced = PortfolioState::from_values(money::from_f64(60_000.0), money::from_f64(40_000.0), None);
+        let plan = TradePlan::build(&config, &balanced, &target(60.0), &target(60.0), None, &[], now);
+        assert!(plan.legs.is_empty());
+        assert_eq!(plan.held_back.len(), 2);
+    }
+}
@@ -0,0 +1,325 @@
+//! Rebalance Math
+//!
+//! How far each asset is from its target share and how that is split into
+//! order legs. The Trader sizes its rebalances with these functions, and
+//! the what-ifs use the same legs and leg sizes: the contribution what-if
+//! (`POST /api/whatif/contribution`) plans buy-only legs for new cash, and
+//! the allocation what-if (`POST /api/whatif/allocation`) previews the
+//! trades, costs and taxes of a target change before it is applied.
+//! Locked holdings narrow the targets that can be reached without selling
+//! them; `achievable_target` picks the closest one.
+
+use anyhow::{bail, Result};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::core::analysis::{self, AnalysisPolicy, PriceInputs};
+use crate::core::blackboard::{PortfolioState, TargetAllocation};
+use crate::core::config::{Config, CostConfig, PortfolioConfig};
+use crate::core::holdings::{ImportedHoldings, LockedHoldings};
+use crate::core::money;
+use crate::execution::{OrderLeg, Side};
+
+/// Legs smaller than this ($0.01) are not worth sending
+pub const MIN_LEG_NOTIONAL: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// Dollar change to stocks and bonds that brings a portfolio of
+/// `total_value` to the target weights
+pub fn deltas(state: &PortfolioState, total_value: Decimal, target: &TargetAllocation) -> (Decimal, Decimal) {
+    let share = |pct: f64| money::from_f64(pct) / Decimal::ONE_HUNDRED;
+    (
+        total_value * share(target.stocks_pct) - state.stocks_value,
+        total_value * share(target.bonds_pct) - state.bonds_value,
+    )
+}
+
+/// The closest target to `target` that keeps each sleeve at least as large
+/// as its locked holdings, so reaching it sells none of them
+pub fn achievable_target(state: &PortfolioState, target: &TargetAllocation, locked: &LockedHoldings) -> TargetAllocation {
+    if state.total_value <= Decimal::ZERO {
+        return target.clone();
+    }
+    let share = |value: Decimal| money::to_f64(value / state.total_value * Decimal::ONE_HUNDRED);
+    let lower = share(locked.stocks_value).min(100.0);
+    let upper = (100.0 - share(locked.bonds_value)).max(lower);
+    let stocks_pct = target.stocks_pct.clamp(lower, upper);
+    if stocks_pct == target.stocks_pct {
+        return target.clone();
+    }
+    TargetAllocation { stocks_pct, bonds_pct: 100.0 - stocks_pct }
+}
+
+/// Split a rebalance into legs, sells first so they fund the buys
+pub fn plan_legs(portfolio: &PortfolioConfig, stocks_delta: Decimal, bonds_delta: Decimal) -> Vec<OrderLeg> {
+    let mut legs: Vec<OrderLeg> = [(&portfolio.stocks_symbol, stocks_delta), (&portfolio.bonds_symbol, bonds_delta)]
+        .into_iter()
+        .filter(|(_, delta)| delta.abs() > MIN_LEG_NOTIONAL)
+        .map(|(symbol, delta)| OrderLeg {
+            symbol: symbol.clone(),
+            side: if delta > Decimal::ZERO { Side::Buy } else { Side::Sell },
+            notional: delta.abs(),
+        })
+        .collect();
+    legs.sort_by_key(|leg| leg.side == Side::Buy);
+    legs
+}
+
+/// Buy-only allocation of new cash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContributionPlan {
+    pub contribution: Decimal,
+    pub target: TargetAllocation,
+    pub legs: Vec<OrderLeg>,
+    pub before: PortfolioState,
+    /// Portfolio once the legs are filled
+    pub after: PortfolioState,
+    pub drift_before: Decimal,
+    pub drift_after: Decimal,
+    /// Cash left over from legs too small to send
+    pub unallocated: Decimal,
+}
+
+/// Put `contribution` into the underweight asset(s) without selling: the
+/// target is computed on the enlarged portfolio, and when the cash cannot
+/// close the gap it all goes to the underweight side
+pub fn plan_contribution(
+    portfolio: &PortfolioConfig,
+    state: &PortfolioState,
+    target: &TargetAllocation,
+    contribution: Decimal,
+) -> Result<ContributionPlan> {
+    if contribution <= Decimal::ZERO {
+        bail!("contribution must be positive");
+    }
+    let contribution = money::round(contribution, &portfolio.base_currency);
+    
+    let (stocks_need, _) = deltas(state, state.total_value + contribution, target);
+    let stocks_buy = money::round(stocks_need.clamp(Decimal::ZERO, contribution), &portfolio.base_currency);
+    let legs = plan_legs(portfolio, stocks_buy, contribution - stocks_buy);
+    
+    let bought = |symbol: &str| legs.iter().filter(|l| l.symbol == symbol).map(|l| l.notional).sum::<Decimal>();
+    let stocks_bought = bought(&portfolio.stocks_symbol);
+    let bonds_bought = bought(&portfolio.bonds_symbol);
+    let after = PortfolioState::from_values(
+        state.stocks_value + stocks_bought,
+        state.bonds_value + bonds_bought,
+        state.last_trade_time.clone(),
+    );
+    let drift = |s: &PortfolioState| (s.stocks_pct - money::from_f64(target.stocks_pct)).abs();
+    
+    Ok(ContributionPlan {
+        contribution,
+        target: target.clone(),
+        drift_before: drift(state),
+        drift_after: drift(&after),
+        unallocated: contribution - stocks_bought - bonds_bought,
+        legs,
+        before: state.clone(),
+        after,
+    })
+}
+
+/// Commissions plus half-spread on the legs' turnover: the one cost model
+/// behind the Analyst's cost-benefit and opportunity score, the what-ifs
+/// and simulated fills
+pub fn leg_costs(legs: &[OrderLeg], costs: &CostConfig) -> Decimal {
+    let turnover: Decimal = legs.iter().map(|leg| leg.notional).sum();
+    money::from_f64(costs.commission_per_trade) * Decimal::from(legs.len())
+        + turnover * money::from_f64(costs.spread_bps) / Decimal::from(10_000)
+}
+
+/// Estimated tax on the gains sells would realize
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxImpact {
+    /// Gain (negative: loss) realized by the sells, at average cost
+    pub realized_gain: Decimal,
+    /// Tax on the net realized gain; losses are not credited
+    pub estimated_tax: Decimal,
+    pub rate: f64,
+}
+
+impl TaxImpact {
+    /// Average-cost estimate: each sell realizes the sleeve's gain in
+    /// proportion to the share of the sleeve sold. Cost basis comes from
+    /// the imported holdings, so trades since the import are not reflected.
+    /// None when a sold sleeve has no cost basis.
+    pub fn estimate(
+        legs: &[OrderLeg],
+        state: &PortfolioState,
+        holdings: &ImportedHoldings,
+        config: &Config,
+    ) -> Option<Self> {
+        let mut realized_gain = Decimal::ZERO;
+        for leg in legs.iter().filter(|leg| leg.side == Side::Sell) {
+            let bonds = leg.symbol == config.portfolio.bonds_symbol;
+            let value = if bonds { state.bonds_value } else { state.stocks_value };
+            let cost = holdings.sleeve_cost_basis(&config.holdings_import, bonds)?;
+            if value > Decimal::ZERO {
+                realized_gain += leg.notional * (value - cost) / value;
+            }
+        }
+        let rate = config.costs.capital_gains_rate;
+        Some(Self {
+            realized_gain: money::round(realized_gain, &config.portfolio.base_currency),
+            estimated_tax: money::round(realized_gain.max(Decimal::ZERO) * money::from_f64(rate), &config.portfolio.base_currency),
+            rate,
+        })
+    }
+}
+
+/// Consequences of switching to a target allocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllocationPreview {
+    pub current_target: TargetAllocation,
+    pub target: TargetAllocation,
+    /// Drift from the new target
+    pub drift_pct: Decimal,
+    pub threshold_pct: f64,
+    /// Whether the Analyst would call for a rebalance as soon as the target
+    /// is applied (see `analysis::evaluate`; no momentum or staleness assumed)
+    pub triggers_rebalance: bool,
+    /// Legs of the rebalance to the new target
+    pub legs: Vec<OrderLeg>,
+    pub before: PortfolioState,
+    pub after: PortfolioState,
+    pub estimated_cost: Decimal,
+    /// None without imported cost basis for a sleeve that would be sold
+    pub tax: Option<TaxImpact>,
+}
+
+/// Preview a target change: the rebalance it implies, what that costs and
+/// the tax on the gains it realizes
+pub fn preview_allocation(
+    config: &Config,
+    state: &PortfolioState,
+    current_target: &TargetAllocation,
+    target: &TargetAllocation,
+    holdings: Option<&ImportedHoldings>,
+) -> AllocationPreview {
+    let (stocks_delta, bonds_delta) = deltas(state, state.total_value, target);
+    let legs = plan_legs(&config.portfolio, stocks_delta, bonds_delta);
+    let signed = |symbol: &str| legs.iter().filter(|l| l.symbol == symbol).map(|l| l.signed(l.notional)).sum::<Decimal>();
+    let after = PortfolioState::from_values(
+        state.stocks_value + signed(&config.portfolio.stocks_symbol),
+        state.bonds_value + signed(&config.portfolio.bonds_symbol),
+        state.last_trade_time.clone(),
+    );
+    let drift_pct = (state.stocks_pct - money::from_f64(target.stocks_pct)).abs();
+    let threshold_pct = config.portfolio.drift_threshold;
+    let assessment = analysis::evaluate(state, &PriceInputs::default(), target, None, &AnalysisPolicy::from_config(config));
+    let tax = match holdings {
+        Some(holdings) => TaxImpact::estimate(&legs, state, holdings, config),
+        None if legs.iter().all(|leg| leg.side == Side::Buy) => Some(TaxImpact {
+            realized_gain: Decimal::ZERO,
+            estimated_tax: Decimal::ZERO,
+            rate: config.costs.capital_gains_rate,
+        }),
+        None => None,
+    };
+    
+    AllocationPreview {
+        current_target: current_target.clone(),
+        target: target.clone(),
+        triggers_rebalance: assessment.triggered,
+        drift_pct,
+        threshold_pct,
+        estimated_cost: money::round(leg_costs(&legs, &config.costs), &config.portfolio.base_currency),
+        legs,
+        before: state.clone(),
+        after,
+        tax,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::holdings::Holding;
+
+    fn target(stocks_pct: f64) -> TargetAllocation {
+        TargetAllocation { stocks_pct, bonds_pct: 100.0 - stocks_pct }
+    }
+
+    #[test]
+    fn test_contribution_closes_gap_without_selling() {
+        let config = Config::default();
+        // 70k / 30k against 60/40: a 10k contribution all goes to bonds
+        let state = PortfolioState::from_values(money::from_f64(70_000.0), money::from_f64(30_000.0), None);
+        let plan = plan_contribution(&config.portfolio, &state, &target(60.0), money::from_f64(10_000.0)).unwrap();
+        assert_eq!(plan.legs.len(), 1);
+        assert_eq!(plan.legs[0].symbol, config.portfolio.bonds_symbol);
+        assert_eq!(plan.legs[0].side, Side::Buy);
+        assert_eq!(plan.after.bonds_value, money::from_f64(40_000.0));
+        assert!(plan.drift_after < plan.drift_before);
+        
+        // Enough cash to reach target: 60k/30k + 10k splits 0/10k → 60/40
+        let state = PortfolioState::from_values(money::from_f64(60_000.0), money::from_f64(30_000.0), None);
+        let plan = plan_contribution(&config.portfolio, &state, &target(60.0), money::from_f64(10_000.0)).unwrap();
+        assert_eq!(plan.drift_after, Decimal::ZERO);
+        assert_eq!(plan.unallocated, Decimal::ZERO);
+        
+        // Split between both when both are under target afterwards
+        let state = PortfolioState::from_values(money::from_f64(50_000.0), money::from_f64(40_000.0), None);
+        let plan = plan_contribution(&config.portfolio, &state, &target(60.0), money::from_f64(10_000.0)).unwrap();
+        assert!(plan.legs.iter().all(|l| l.side == Side::Buy));
+        assert_eq!(plan.after.stocks_value, money::from_f64(60_000.0));
+        
+        assert!(plan_contribution(&config.portfolio, &state, &target(60.0), Decimal::ZERO).is_err());
+    }
+    
+    #[test]
+    fn test_allocation_preview_costs_and_tax() {
+        let mut config = Config::default();
+        config.costs.commission_per_trade = 1.0;
+        config.costs.spread_bps = 10.0;
+        let state = PortfolioState::from_values(money::from_f64(60_000.0), money::from_f64(40_000.0), None);
+        
+        // 60/40 → 50/50 sells 10k of stocks and buys 10k of bonds
+        let preview = preview_allocation(&config, &state, &target(60.0), &target(50.0), None);
+        assert!(preview.triggers_rebalance);
+        assert_eq!(preview.legs.len(), 2);
+        assert_eq!(preview.legs[0].side, Side::Sell);
+        assert_eq!(preview.after.stocks_value, money::from_f64(50_000.0));
+        // $1 per leg + 10 bps on $20k turnover
+        assert_eq!(preview.estimated_cost, money::from_f64(22.0));
+        assert!(preview.tax.is_none());
+        
+        // Stocks bought for 45k: selling a sixth of the sleeve realizes a sixth of the 15k gain
+        let holdings = ImportedHoldings {
+            holdings: vec![
+                Holding { symbol: "SPY".to_string(), quantity: None, price: None, market_value: 60_000.0, cost_basis: Some(45_000.0) },
+                Holding { symbol: "BND".to_string(), quantity: None, price: None, market_value: 40_000.0, cost_basis: None },
+            ],
+            cash: 0.0,
+            source: "test".to_string(),
+            imported_at: String::new(),
+        };
+        let tax = preview_allocation(&config, &state, &target(60.0), &target(50.0), Some(&holdings)).tax.unwrap();
+        assert_eq!(tax.realized_gain, money::from_f64(2_500.0));
+        assert_eq!(tax.estimated_tax, money::from_f64(375.0));
+        
+        // Selling bonds needs their (missing) cost basis
+        let preview = preview_allocation(&config, &state, &target(60.0), &target(70.0), Some(&holdings));
+        assert!(preview.tax.is_none());
+        assert!(!preview_allocation(&config, &state, &target(60.0), &target(62.0), None).triggers_rebalance);
+    }
+    
+    #[test]
+    fn test_achievable_target_keeps_locked_holdings() {
+        let state = PortfolioState::from_values(money::from_f64(70_000.0), money::from_f64(30_000.0), None);
+        let locked = |stocks: f64, bonds: f64| LockedHoldings {
+            stocks_value: money::from_f64(stocks),
+            bonds_value: money::from_f64(bonds),
+        };
+        
+        // 65k of locked stocks can't be sold down to a 60% target
+        let achievable = achievable_target(&state, &target(60.0), &locked(65_000.0, 0.0));
+        assert!((achievable.stocks_pct - 65.0).abs() < 1e-9);
+        assert!((achievable.bonds_pct - 35.0).abs() < 1e-9);
+        // Small locked positions leave the target reachable
+        assert_eq!(achievable_target(&state, &target(60.0), &locked(10_000.0, 5_000.0)), target(60.0));
+        // Locked bonds cap how far stocks can be bought
+        let achievable = achievable_target(&state, &target(90.0), &locked(0.0, 20_000.0));
+        assert!((achievable.stocks_pct - 80.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,136 @@
+//! Reset Scopes
+//!
+//! Resetting used to clear pheromones and restore the initial portfolio in
+//! one step, leaving a trade log that no longer matched the portfolio.
+//! Resets now come in three escalating scopes:
+//!
+//! - `soft`: clear pheromones only (agents go dormant and recover)
+//! - `portfolio`: also restore the initial portfolio, baseline, drawdown and
+//!   benchmarks, keeping trade log and history
+//! - `hard`: also wipe the trade log, portfolio/price history, snapshots,
+//!   daily summaries, statements and agent activity
+//!
+//! Portfolio and hard resets need the operator token (when configured) and
+//! an explicit confirmation: `"portfolio"`, or the swarm name for a hard
+//! reset. Holdings, the target allocation and the audit log always survive.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{error, warn};
+
+use crate::core::allocation::{self, AllocationError};
+use crate::core::blackboard::PortfolioState;
+use crate::core::holdings;
+use crate::core::Blackboard;
+
+/// How much state a reset discards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResetScope {
+    /// Pheromones only
+    #[default]
+    Soft,
+    /// Pheromones and portfolio state; history is kept
+    Portfolio,
+    /// Everything except holdings, target allocation and the audit log
+    Hard,
+}
+
+impl ResetScope {
+    /// Confirmation the client must send for this scope, if any
+    pub fn confirmation(&self, swarm: &str) -> Option<String> {
+        match self {
+            Self::Soft => None,
+            Self::Portfolio => Some("portfolio".to_string()),
+            Self::Hard => Some(swarm.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ResetError {
+    #[error("operator token missing or invalid")]
+    Unauthorized,
+    #[error("{scope:?} reset requires confirm = \"{expected}\"")]
+    NotConfirmed { scope: ResetScope, expected: String },
+}
+
+/// Check a reset request's token and confirmation
+pub fn authorize(
+    scope: ResetScope,
+    expected_token: Option<&str>,
+    token: Option<&str>,
+    confirm: Option<&str>,
+    swarm: &str,
+) -> Result<(), ResetError> {
+    let Some(expected) = scope.confirmation(swarm) else {
+        return Ok(());
+    };
+    if let Err(AllocationError::Unauthorized) = allocation::authorize(expected_token, token) {
+        return Err(ResetError::Unauthorized);
+    }
+    if confirm != Some(expected.as_str()) {
+        return Err(ResetError::NotConfirmed { scope, expected });
+    }
+    Ok(())
+}
+
+/// Apply an authorized reset, returning the restored portfolio for the
+/// portfolio and hard scopes
+pub async fn reset(board: &Blackboard, scope: ResetScope) -> Result<Option<PortfolioState>> {
+    board.clear_all().await?;
+    if scope == ResetScope::Soft {
+        return Ok(None);
+    }
+    
+    if scope == ResetScope::Hard {
+        let removed = board.clear_history().await?;
+        warn!("🧨 Hard reset removed {} history keys", removed);
+    }
+    
+    // Restart from the initial portfolio (imported holdings if present)
+    let initial = match holdings::initial_portfolio(board).await {
+        Ok(initial) => initial,
+        Err(e) => {
+            error!("Failed to load initial holdings: {}", e);
+            holdings::synthetic_portfolio(board.config())
+        }
+    };
+    holdings::restart_from(board, &initial, "reset").await?;
+    
+    Ok(Some(initial))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escalating_authorization() {
+        // Soft resets need nothing
+        assert!(authorize(ResetScope::Soft, Some("secret"), None, None, "default").is_ok());
+        
+        // Portfolio resets need the token and a confirmation
+        assert_eq!(
+            authorize(ResetScope::Portfolio, Some("secret"), None, Some("portfolio"), "default"),
+            Err(ResetError::Unauthorized)
+        );
+        assert!(matches!(
+            authorize(ResetScope::Portfolio, None, None, None, "default"),
+            Err(ResetError::NotConfirmed { .. })
+        ));
+        assert!(authorize(ResetScope::Portfolio, Some("secret"), Some("secret"), Some("portfolio"), "default").is_ok());
+        
+        // Hard resets must name the swarm
+        assert!(authorize(ResetScope::Hard, None, None, Some("portfolio"), "retirement").is_err());
+        assert!(authorize(ResetScope::Hard, None, None, Some("retirement"), "retirement").is_ok());
+    }
+
+    #[test]
+    fn test_scope_names() {
+        let scope: ResetScope = serde_json::from_str(r#""hard""#).unwrap();
+        assert_eq!(scope, ResetScope::Hard);
+        assert_eq!(ResetScope::default(), ResetScope::Soft);
+    }
+}
@@ -0,0 +1,193 @@
+//! Retention
+//!
+//! Every history on the blackboard is a newest-first list capped by count
+//! when it is written, but a cap only bites on the next write and nothing
+//! ages out, so a long-running deployment slowly fills Redis. The janitor
+//! (in the services process, every `[retention] interval_secs`) re-applies
+//! each list's entry cap, trims entries older than its configured age off
+//! the tail, and deletes daily snapshots and summaries past theirs. It then
+//! measures the swarm's own keys with `MEMORY USAGE` and stores the result
+//! for `GET /api/storage`, warning when it exceeds `memory_budget_mb`.
+//! Cash flows, wash-sale fills and the run registry feed calculations
+//! rather than displays, and month-end statements are the permanent
+//! record, so none of them are aged out.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{debug, info, warn};
+
+use crate::core::{Blackboard, Config};
+
+/// A newest-first list (or lists, for a key pattern) kept by count and age
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListRetention {
+    /// Key or key pattern within the swarm's namespace
+    pub pattern: &'static str,
+    pub max_entries: usize,
+    /// 0 = kept by count only
+    pub max_age_days: u32,
+}
+
+/// Retention of every list the janitor maintains
+pub fn list_policies(config: &Config) -> Vec<ListRetention> {
+    let retention = &config.retention;
+    let list = |pattern, max_entries, max_age_days| ListRetention { pattern, max_entries, max_age_days };
+    vec![
+        list("trade_log", config.trade_log.max_entries, retention.trade_log_max_age_days),
+        list("agent:*:activity", config.agent.activity_max_entries, retention.events_max_age_days),
+        list("audit:commands", config.audit.max_entries, retention.events_max_age_days),
+        list("shadow:divergences", config.shadow.max_divergences, retention.events_max_age_days),
+        list("orders:dead_letter", config.execution.queue.dead_letter_max_entries, retention.events_max_age_days),
+        list("notify:dead_letter", config.alerting.delivery.dead_letter_max_entries, retention.events_max_age_days),
+        list("history:portfolio", config.valuation.history_max_entries, retention.history_max_age_days),
+        list("history:price:*", config.market.realized_vol.max_samples, retention.history_max_age_days),
+        list("history:pheromones", config.dashboard.trail_max_entries, retention.history_max_age_days),
+    ]
+}
+
+/// Cutoff for entries kept `max_age_days` at `now`, if they age out at all
+pub fn cutoff(max_age_days: u32, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    (max_age_days > 0).then(|| now - ChronoDuration::days(max_age_days as i64))
+}
+
+/// How many entries at the end of a newest-first `page` are older than
+/// `cutoff`, judged by their `timestamp` field (unreadable entries count
+/// as expired, since nothing could display them)
+pub fn expired_tail(page: &[String], cutoff: DateTime<Utc>) -> usize {
+    page.iter()
+        .rev()
+        .take_while(|entry| entry_time(entry).is_none_or(|at| at < cutoff))
+        .count()
+}
+
+fn entry_time(entry: &str) -> Option<DateTime<Utc>> {
+    let value: serde_json::Value = serde_json::from_str(entry).ok()?;
+    let timestamp = value.get("timestamp")?.as_str()?;
+    DateTime::parse_from_rfc3339(timestamp).ok().map(|at| at.with_timezone(&Utc))
+}
+
+/// Whether a daily snapshot key (`…snapshots:daily:YYYY-MM-DD`) is older than `cutoff`
+pub fn snapshot_expired(key: &str, cutoff: NaiveDate) -> bool {
+    key.rsplit(':')
+        .next()
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .is_some_and(|date| date < cutoff)
+}
+
+/// The swarm's Redis footprint as of the last janitor pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageReport {
+    pub timestamp: String,
+    /// Keys in the swarm's namespace
+    pub keys: usize,
+    /// Their total `MEMORY USAGE`
+    pub memory_bytes: u64,
+    /// None when no budget is configured
+    pub budget_bytes: Option<u64>,
+    pub over_budget: bool,
+    /// Entries and keys the pass removed
+    pub removed: usize,
+}
+
+/// Enforce retention and measure the footprint every `interval_secs`
+pub async fn run_janitor(board: Arc<Blackboard>) -> Result<()> {
+    let settings = &board.config().retention;
+    let mut ticker = interval(Duration::from_secs(settings.interval_secs.max(1)));
+    let budget_bytes = (settings.memory_budget_mb > 0).then(|| settings.memory_budget_mb * 1024 * 1024);
+    
+    info!("🧹 Retention janitor started (every {}s)", settings.interval_secs);
+    
+    loop {
+        ticker.tick().await;
+        let now = Utc::now();
+        
+        let removed = match enforce(&board, now).await {
+            Ok(removed) => removed,
+            Err(e) => {
+                warn!("Retention: Failed to enforce retention: {:#}", e);
+                0
+            }
+        };
+        if removed > 0 {
+            info!("🧹 Retention: removed {} expired entries", removed);
+        }
+        
+        let (keys, memory_bytes) = match board.memory_usage().await {
+            Ok(usage) => usage,
+            Err(e) => {
+                warn!("Retention: Failed to measure Redis memory: {:#}", e);
+                continue;
+            }
+        };
+        let over_budget = budget_bytes.is_some_and(|budget| memory_bytes > budget);
+        if over_budget {
+            warn!(
+                "💾 Redis footprint {:.1} MB exceeds the {} MB budget ({} keys)",
+                memory_bytes as f64 / (1024.0 * 1024.0),
+                settings.memory_budget_mb,
+                keys
+            );
+        } else {
+            debug!("Retention: {} keys, {} bytes", keys, memory_bytes);
+        }
+        
+        let report = StorageReport {
+            timestamp: now.to_rfc3339(),
+            keys,
+            memory_bytes,
+            budget_bytes,
+            over_budget,
+            removed,
+        };
+        if let Err(e) = board.set_storage_report(&report).await {
+            warn!("Retention: Failed to store storage report: {}", e);
+        }
+    }
+}
+
+/// One retention pass; returns the entries and keys removed
+async fn enforce(board: &Blackboard, now: DateTime<Utc>) -> Result<usize> {
+    let mut removed = 0;
+    for policy in list_policies(board.config()) {
+        let cutoff = cutoff(policy.max_age_days, now);
+        for key in board.matching_keys(policy.pattern).await? {
+            removed += board.trim_list(&key, policy.max_entries, cutoff).await?;
+        }
+    }
+    if let Some(cutoff) = cutoff(board.config().retention.snapshots_max_age_days, now) {
+        removed += board.delete_daily_snapshots_before(cutoff.date_naive()).await?;
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_expiry() {
+        let day = |d| Utc.with_ymd_and_hms(2024, 7, d, 12, 0, 0).unwrap();
+        let entry = |d| serde_json::json!({"timestamp": day(d).to_rfc3339(), "action": "BUY"}).to_string();
+        let cutoff = day(10);
+        
+        // Newest first: the two oldest are past the cutoff
+        let page = vec![entry(20), entry(15), entry(10), entry(5), entry(1)];
+        assert_eq!(expired_tail(&page, cutoff), 2);
+        // An entry without a timestamp ages out with its neighbours
+        let page = vec![entry(20), "not json".to_string(), entry(1)];
+        assert_eq!(expired_tail(&page, cutoff), 2);
+        assert_eq!(expired_tail(&[entry(20)], cutoff), 0);
+        
+        assert_eq!(super::cutoff(0, day(20)), None);
+        assert_eq!(super::cutoff(10, day(20)), Some(cutoff));
+        
+        let date = NaiveDate::from_ymd_opt(2024, 7, 10).unwrap();
+        assert!(snapshot_expired("lab:snapshots:daily:2024-07-09", date));
+        assert!(!snapshot_expired("snapshots:daily:2024-07-10", date));
+        assert!(!snapshot_expired("snapshots:daily:garbage", date));
+    }
+}
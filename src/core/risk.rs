@@ -0,0 +1,279 @@
+//! Risk Decision
+//!
+//! The Guardian's permit rules as a pure function of what it observed and
+//! the configured rules: the system mode, the blackout calendar, the
+//! assets' weight limits, the data-quality floor and the volatility
+//! ceiling. The live Guardian and shadow mode both decide through it, and
+//! anything else that needs to know whether a trade would be permitted
+//! (what-ifs, replays) can call it without a blackboard. A `guardian_rule` script, which replaces the
+//! volatility ceiling, is run by `PermitModel` and handed in as a verdict.
+//! Warmup stays with the Guardian: it depends on how long it has run.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use std::fmt;
+
+use crate::agents::analyst::DriftAnalysis;
+use crate::agents::guardian::VolatilityReading;
+use crate::core::allocation;
+use crate::core::blackboard::TargetAllocation;
+use crate::core::calendar::{self, EventDay};
+use crate::core::config::{BlackoutConfig, PortfolioConfig};
+use crate::core::mode::SystemMode;
+use crate::core::physics::PheromoneType;
+use crate::core::pipeline::AgentRole;
+use crate::core::Config;
+
+/// Why a permit was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum PermitRefusal {
+    /// The swarm is HALTED, or in SIMULATION without `permit_in_simulation`
+    SystemMode(SystemMode),
+    Blackout(String),
+    /// The analysis targets a weight outside an asset's `min_pct`/`max_pct`
+    AssetLimit(String),
+    /// Data quality below the floor, or no data-quality signal
+    DataQuality(Option<f64>),
+    /// No volatility reading could be taken
+    VolatilityUnavailable,
+    /// Volatility above the ceiling (the threshold, lowered during a
+    /// correlation spike), or the permit script said no
+    Volatility { reading: VolatilityReading, ceiling: f64, by_script: bool },
+}
+
+impl fmt::Display for PermitRefusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SystemMode(mode) => write!(f, "system mode {}", mode),
+            Self::Blackout(reason) => write!(f, "blackout: {}", reason),
+            Self::AssetLimit(reason) => write!(f, "target {}", reason),
+            Self::DataQuality(Some(score)) => write!(f, "data quality {:.2}", score),
+            Self::DataQuality(None) => write!(f, "no data quality signal"),
+            Self::VolatilityUnavailable => write!(f, "volatility unavailable"),
+            Self::Volatility { reading, .. } => write!(f, "{} {:.1}", reading.signal.label(), reading.value),
+        }
+    }
+}
+
+/// What the Guardian observed for one rebalance opportunity
+#[derive(Debug, Clone)]
+pub struct RiskInputs<'a> {
+    pub now: DateTime<Utc>,
+    pub analysis: &'a DriftAnalysis,
+    /// The system mode as last derived; none before the monitor has run
+    pub mode: Option<SystemMode>,
+    /// The Sensor's data-quality score, if its pheromone is active
+    pub data_quality: Option<f64>,
+    /// Volatility on the VIX scale; none when it could not be read
+    pub volatility: Option<VolatilityReading>,
+    /// Verdict of a `guardian_rule` script, which replaces the volatility
+    /// ceiling
+    pub script_permit: Option<bool>,
+    /// Whether the assets' correlations have spiked (`core::correlation`)
+    pub correlation_spike: bool,
+}
+
+/// The permit rules under one configuration
+#[derive(Debug, Clone)]
+pub struct RiskRules<'a> {
+    pub permit_in_simulation: bool,
+    pub blackout: &'a BlackoutConfig,
+    /// Exchange time zone the session and event days are in
+    pub timezone: Tz,
+    /// Economic event days from the blackout calendar
+    pub event_days: &'a [EventDay],
+    pub portfolio: &'a PortfolioConfig,
+    /// Whether the pipeline makes the Guardian require `DataQuality`
+    pub require_data_quality: bool,
+    pub min_data_quality: f64,
+    pub vix_low_threshold: f64,
+    pub vix_high_threshold: f64,
+    /// Multiplies the volatility ceiling during a correlation spike
+    pub correlation_caution_factor: f64,
+}
+
+impl<'a> RiskRules<'a> {
+    pub fn from_config(config: &'a Config, event_days: &'a [EventDay]) -> Self {
+        Self {
+            permit_in_simulation: config.system_mode.permit_in_simulation,
+            blackout: &config.blackout,
+            timezone: config.exchange.timezone,
+            event_days,
+            portfolio: &config.portfolio,
+            require_data_quality: config.pipeline.wiring(AgentRole::Guardian).requires(PheromoneType::DataQuality),
+            min_data_quality: config.data_quality.min_score,
+            vix_low_threshold: config.market.vix_low_threshold,
+            vix_high_threshold: config.market.vix_high_threshold,
+            correlation_caution_factor: config.correlation.guardian_caution_factor,
+        }
+    }
+
+    /// Highest volatility permitted, lower while correlations have spiked
+    pub fn volatility_ceiling(&self, correlation_spike: bool) -> f64 {
+        if correlation_spike {
+            self.vix_high_threshold * self.correlation_caution_factor
+        } else {
+            self.vix_high_threshold
+        }
+    }
+
+    /// LOW | MODERATE | HIGH against the configured thresholds
+    pub fn volatility_status(&self, vix: f64) -> &'static str {
+        if vix < self.vix_low_threshold {
+            "LOW"
+        } else if vix > self.vix_high_threshold {
+            "HIGH"
+        } else {
+            "MODERATE"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Permit,
+    Deny,
+}
+
+/// The Guardian's decision on one opportunity
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub verdict: Verdict,
+    /// Every rule that refused, in the order the Guardian checks them
+    /// (system mode, blackout, asset limits, data quality, volatility);
+    /// empty on a permit
+    pub reasons: Vec<PermitRefusal>,
+    /// Share of the recommended rebalance to trade: all of it on a permit,
+    /// none on a denial
+    pub size_factor: f64,
+    /// Data-quality score the decision was made on (0 without a signal)
+    pub data_quality: f64,
+}
+
+impl Decision {
+    pub fn is_permit(&self) -> bool {
+        self.verdict == Verdict::Permit
+    }
+}
+
+/// Decide whether the Guardian permits the opportunity
+pub fn decide(inputs: &RiskInputs<'_>, rules: &RiskRules<'_>) -> Decision {
+    let mut reasons = Vec::new();
+
+    match inputs.mode {
+        Some(mode @ SystemMode::Halted) => reasons.push(PermitRefusal::SystemMode(mode)),
+        Some(mode @ SystemMode::Simulation) if !rules.permit_in_simulation => reasons.push(PermitRefusal::SystemMode(mode)),
+        _ => {}
+    }
+
+    if let Some(reason) = calendar::blackout_reason(inputs.now, rules.blackout, rules.event_days, rules.timezone) {
+        reasons.push(PermitRefusal::Blackout(reason));
+    }
+
+    let target = TargetAllocation {
+        stocks_pct: inputs.analysis.target_stocks_pct,
+        bonds_pct: inputs.analysis.target_bonds_pct,
+    };
+    if let Err(e) = allocation::check_asset_limits(&target, rules.portfolio) {
+        reasons.push(PermitRefusal::AssetLimit(e.to_string()));
+    }
+
+    if rules.require_data_quality {
+        match inputs.data_quality {
+            Some(score) if score >= rules.min_data_quality => {}
+            score => reasons.push(PermitRefusal::DataQuality(score)),
+        }
+    }
+
+    match inputs.volatility {
+        None => reasons.push(PermitRefusal::VolatilityUnavailable),
+        Some(reading) => {
+            let ceiling = rules.volatility_ceiling(inputs.correlation_spike);
+            let permitted = inputs.script_permit.unwrap_or(reading.value <= ceiling);
+            if !permitted {
+                reasons.push(PermitRefusal::Volatility { reading, ceiling, by_script: inputs.script_permit.is_some() });
+            }
+        }
+    }
+
+    let verdict = if reasons.is_empty() { Verdict::Permit } else { Verdict::Deny };
+    Decision {
+        verdict,
+        size_factor: if verdict == Verdict::Permit { 1.0 } else { 0.0 },
+        reasons,
+        data_quality: inputs.data_quality.unwrap_or(0.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::guardian::VolatilitySignal;
+    use crate::agents::sensor::MarketSnapshot;
+
+    fn analysis(target_stocks_pct: f64) -> DriftAnalysis {
+        DriftAnalysis {
+            current_stocks_pct: 67.0,
+            current_bonds_pct: 33.0,
+            target_stocks_pct,
+            target_bonds_pct: 100.0 - target_stocks_pct,
+            drift_pct: 7.0,
+            recommended_action: "SELL stocks, BUY bonds".to_string(),
+            market_snapshot: MarketSnapshot::new(Vec::new()),
+            explanation: Default::default(),
+            opportunity: None,
+            cost_benefit: None,
+            trigger: Default::default(),
+            script_score: None,
+            analyzed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_decide() {
+        let mut config = Config::default();
+        config.blackout.enabled = false;
+        let rules = RiskRules::from_config(&config, &[]);
+        let analysis = analysis(60.0);
+        let vix = |value| Some(VolatilityReading { value, signal: VolatilitySignal::Vix });
+        let inputs = RiskInputs {
+            now: Utc::now(),
+            analysis: &analysis,
+            mode: Some(SystemMode::Live),
+            data_quality: Some(1.0),
+            volatility: vix(15.0),
+            script_permit: None,
+            correlation_spike: false,
+        };
+
+        let calm = decide(&inputs, &rules);
+        assert!(calm.is_permit());
+        assert_eq!(calm.size_factor, 1.0);
+        assert!(calm.reasons.is_empty());
+
+        let stormy = decide(&RiskInputs { volatility: vix(40.0), ..inputs.clone() }, &rules);
+        assert_eq!(stormy.verdict, Verdict::Deny);
+        assert_eq!(stormy.size_factor, 0.0);
+        assert_eq!(stormy.reasons.len(), 1);
+        assert_eq!(stormy.reasons[0].to_string(), "VIX 40.0");
+
+        // A correlation spike lowers the ceiling (25 × 0.8)
+        assert!(decide(&RiskInputs { volatility: vix(22.0), ..inputs.clone() }, &rules).is_permit());
+        let correlated = decide(&RiskInputs { volatility: vix(22.0), correlation_spike: true, ..inputs.clone() }, &rules);
+        assert!(matches!(correlated.reasons[..], [PermitRefusal::Volatility { ceiling, .. }] if ceiling == 20.0));
+
+        // A script's verdict replaces the ceiling
+        assert!(decide(&RiskInputs { volatility: vix(40.0), script_permit: Some(true), ..inputs.clone() }, &rules).is_permit());
+
+        // Every refusal is listed, in checking order
+        let blind = decide(&RiskInputs { data_quality: None, volatility: None, ..inputs.clone() }, &rules);
+        assert_eq!(blind.reasons, vec![PermitRefusal::DataQuality(None), PermitRefusal::VolatilityUnavailable]);
+
+        // Halted outranks everything; simulation only refuses when configured to
+        let halted = decide(&RiskInputs { mode: Some(SystemMode::Halted), ..inputs.clone() }, &rules);
+        assert_eq!(halted.reasons, vec![PermitRefusal::SystemMode(SystemMode::Halted)]);
+        let simulated = RiskInputs { mode: Some(SystemMode::Simulation), ..inputs.clone() };
+        assert!(decide(&simulated, &rules).is_permit());
+        assert!(!decide(&simulated, &RiskRules { permit_in_simulation: false, ..rules.clone() }).is_permit());
+    }
+}
@@ -0,0 +1,264 @@
+//! Composite Risk Index
+//!
+//! VIX prices the expected volatility of US large caps, which says little
+//! about a bond-heavy, gold or crypto portfolio. The composite index is the
+//! volatility of the portfolio itself: each asset's volatility and their
+//! correlations, estimated from the daily closes loaded at startup (with
+//! the latest live price as today's close), combined at the target weights
+//! as √(wᵀΣw) and mapped to the VIX scale.
+//!
+//! A task in the services process recomputes it every
+//! `[risk_index] interval_secs` and publishes it on the blackboard
+//! (`risk:composite_index`) and at `GET /api/risk-index`. With
+//! `use_for_guardian` the Guardian thresholds on it in place of VIX, falling
+//! back to its usual signals while the index is missing or stale.
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{debug, info, warn};
+
+use crate::core::{clock, Blackboard, Config};
+use crate::market::daily_history::DailyBar;
+use crate::market::realized_vol;
+use crate::market::QuoteSource;
+
+/// Trading sessions in a year, for annualizing daily returns
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// One asset's part in the index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetRisk {
+    pub symbol: String,
+    /// Target weight, as a fraction of the portfolio
+    pub weight: f64,
+    /// Annualized volatility, as a fraction
+    pub volatility: f64,
+}
+
+/// The portfolio's volatility on the VIX scale
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompositeRiskIndex {
+    /// On the VIX scale
+    pub value: f64,
+    /// Annualized portfolio volatility, as a fraction
+    pub portfolio_volatility: f64,
+    pub assets: Vec<AssetRisk>,
+    /// Pairwise correlations of daily returns, in `assets` order
+    pub correlations: Vec<Vec<f64>>,
+    /// Daily returns the estimate rests on
+    pub returns: usize,
+    pub computed_at: DateTime<Utc>,
+}
+
+impl CompositeRiskIndex {
+    /// Whether it was computed within `max_age_secs` of `now`
+    pub fn is_fresh(&self, now: DateTime<Utc>, max_age_secs: u64) -> bool {
+        (now - self.computed_at).num_seconds() <= max_age_secs as i64
+    }
+}
+
+/// An asset's weight and daily closes, oldest first
+#[derive(Debug, Clone)]
+pub struct AssetSeries {
+    pub symbol: String,
+    pub weight: f64,
+    pub bars: Vec<DailyBar>,
+}
+
+/// Replace or extend today's close with a live price
+pub fn with_live_close(mut bars: Vec<DailyBar>, today: NaiveDate, price: f64) -> Vec<DailyBar> {
+    if price > 0.0 {
+        bars.retain(|bar| bar.date < today);
+        bars.push(DailyBar { date: today, close: price });
+    }
+    bars
+}
+
+/// The index over the days every asset has a close on; needs `min_returns`
+/// returns and at least one weighted asset
+pub fn compute(
+    series: &[AssetSeries],
+    min_returns: usize,
+    vix_multiplier: f64,
+    now: DateTime<Utc>,
+) -> Option<CompositeRiskIndex> {
+    let total_weight: f64 = series.iter().map(|s| s.weight.max(0.0)).sum();
+    if series.is_empty() || total_weight <= 0.0 {
+        return None;
+    }
+
+    let returns = common_returns(series);
+    if returns.len() < min_returns.max(2) {
+        return None;
+    }
+
+    // Annualized sample covariance of daily log returns
+    let n = returns.len() as f64;
+    let k = series.len();
+    let means: Vec<f64> = (0..k).map(|i| returns.iter().map(|r| r[i]).sum::<f64>() / n).collect();
+    let covariance = |i: usize, j: usize| {
+        returns.iter().map(|r| (r[i] - means[i]) * (r[j] - means[j])).sum::<f64>() / (n - 1.0) * TRADING_DAYS_PER_YEAR
+    };
+    let sigma: Vec<Vec<f64>> = (0..k).map(|i| (0..k).map(|j| covariance(i, j)).collect()).collect();
+
+    let weights: Vec<f64> = series.iter().map(|s| s.weight.max(0.0) / total_weight).collect();
+    let variance: f64 = (0..k).flat_map(|i| (0..k).map(move |j| (i, j))).map(|(i, j)| weights[i] * weights[j] * sigma[i][j]).sum();
+    let portfolio_volatility = variance.max(0.0).sqrt();
+
+    let vols: Vec<f64> = (0..k).map(|i| sigma[i][i].max(0.0).sqrt()).collect();
+    let correlations = (0..k)
+        .map(|i| {
+            (0..k)
+                .map(|j| match (i == j, vols[i] * vols[j]) {
+                    (true, _) => 1.0,
+                    (false, scale) if scale > 0.0 => sigma[i][j] / scale,
+                    _ => 0.0,
+                })
+                .collect()
+        })
+        .collect();
+
+    Some(CompositeRiskIndex {
+        value: realized_vol::vix_equivalent(portfolio_volatility, vix_multiplier),
+        portfolio_volatility,
+        assets: series
+            .iter()
+            .zip(weights.iter().zip(&vols))
+            .map(|(s, (weight, volatility))| AssetRisk { symbol: s.symbol.clone(), weight: *weight, volatility: *volatility })
+            .collect(),
+        correlations,
+        returns: returns.len(),
+        computed_at: now,
+    })
+}
+
+/// Daily log returns over the dates every asset has a close on, oldest
+/// first, one value per asset in `series` order
+pub fn common_returns(series: &[AssetSeries]) -> Vec<Vec<f64>> {
+    let mut by_date: BTreeMap<NaiveDate, Vec<Option<f64>>> = BTreeMap::new();
+    for (i, s) in series.iter().enumerate() {
+        for bar in s.bars.iter().filter(|bar| bar.close > 0.0) {
+            by_date.entry(bar.date).or_insert_with(|| vec![None; series.len()])[i] = Some(bar.close);
+        }
+    }
+    let rows: Vec<Vec<f64>> = by_date.into_values().filter_map(|row| row.into_iter().collect()).collect();
+    rows.windows(2)
+        .map(|pair| pair[0].iter().zip(&pair[1]).map(|(prev, next)| (next / prev).ln()).collect())
+        .collect()
+}
+
+/// Every configured asset's weight and closes; the symbols with no daily
+/// history come back separately
+pub async fn read_series(board: &Blackboard, config: &Config) -> Result<(Vec<AssetSeries>, Vec<String>)> {
+    let today = clock::today(Utc::now(), config.exchange.timezone);
+    let market = board.get_market_update().await?;
+    let live_price = |symbol: &str| {
+        market.as_ref().and_then(|update| {
+            update
+                .quotes
+                .iter()
+                .find(|q| q.symbol == symbol && q.source != QuoteSource::Simulated)
+                .map(|q| q.price)
+        })
+    };
+
+    let mut series = Vec::new();
+    let mut missing = Vec::new();
+    for asset in config.assets() {
+        match board.get_daily_history(&asset.symbol).await? {
+            Some(history) => {
+                let bars = match live_price(&asset.symbol) {
+                    Some(price) => with_live_close(history.bars, today, price),
+                    None => history.bars,
+                };
+                series.push(AssetSeries { symbol: asset.symbol, weight: asset.target_pct, bars });
+            }
+            None => missing.push(asset.symbol),
+        }
+    }
+    Ok((series, missing))
+}
+
+/// Recompute and publish the index every `[risk_index] interval_secs`
+pub async fn run_index(board: Arc<Blackboard>) -> Result<()> {
+    let config = &board.config().risk_index;
+    let mut ticker = interval(Duration::from_secs(config.interval_secs.max(1)));
+
+    info!("🌐 Composite risk index started (every {}s)", config.interval_secs);
+
+    loop {
+        ticker.tick().await;
+        let (series, missing) = match read_series(&board, board.config()).await {
+            Ok(read) => read,
+            Err(e) => {
+                warn!("🌐 Composite risk index: Failed to read prices: {:#}", e);
+                continue;
+            }
+        };
+        if !missing.is_empty() {
+            debug!("🌐 Composite risk index: No daily history for {}", missing.join(", "));
+            continue;
+        }
+        match compute(&series, config.min_returns, config.vix_multiplier, Utc::now()) {
+            Some(index) => {
+                debug!("🌐 Composite risk index {:.1} ({:.1}% annualized over {} returns)",
+                    index.value, index.portfolio_volatility * 100.0, index.returns);
+                if let Err(e) = board.put_composite_risk_index(&index).await {
+                    warn!("🌐 Failed to store composite risk index: {:#}", e);
+                }
+            }
+            None => debug!("🌐 Composite risk index: Not enough common history yet"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(symbol: &str, weight: f64, closes: &[f64]) -> AssetSeries {
+        let start = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let bars = closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| DailyBar { date: start + chrono::Duration::days(i as i64), close: *close })
+            .collect();
+        AssetSeries { symbol: symbol.to_string(), weight, bars }
+    }
+
+    #[test]
+    fn test_composite_index() {
+        let now = Utc::now();
+        let stocks = [100.0, 102.0, 99.0, 103.0, 100.0, 104.0];
+        let mirrored = [100.0, 98.0, 101.0, 97.0, 100.0, 96.0];
+
+        // A single asset is its own volatility
+        let alone = compute(&[series("SPY", 100.0, &stocks)], 2, 1.0, now).unwrap();
+        assert_eq!(alone.returns, 5);
+        assert!((alone.portfolio_volatility - alone.assets[0].volatility).abs() < 1e-12);
+        assert!((alone.value - alone.portfolio_volatility * 100.0).abs() < 1e-9);
+
+        // Offsetting assets diversify well below either one
+        let hedged = compute(&[series("SPY", 50.0, &stocks), series("TLT", 50.0, &mirrored)], 2, 1.0, now).unwrap();
+        assert!(hedged.correlations[0][1] < -0.9, "{:?}", hedged.correlations);
+        assert_eq!(hedged.correlations[0][0], 1.0);
+        assert!(hedged.portfolio_volatility < 0.25 * hedged.assets[0].volatility);
+        assert_eq!(hedged.assets[1].weight, 0.5);
+
+        // Only the dates both assets trade on count
+        let short = compute(&[series("SPY", 50.0, &stocks), series("TLT", 50.0, &mirrored[..3])], 2, 1.0, now).unwrap();
+        assert_eq!(short.returns, 2);
+        assert!(compute(&[series("SPY", 50.0, &stocks), series("TLT", 50.0, &mirrored[..3])], 3, 1.0, now).is_none());
+
+        // A live price stands in for today's close
+        let bars = series("SPY", 100.0, &stocks).bars;
+        let today = bars[5].date;
+        let live = with_live_close(bars, today, 110.0);
+        assert_eq!(live.len(), 6);
+        assert_eq!(live[5].close, 110.0);
+    }
+}
@@ -0,0 +1,131 @@
+//! Run Registry
+//!
+//! Each `driftguard run` process registers itself on startup as
+//! `run:{id}`: crate version, a hash of the effective configuration, start
+//! time, market data mode, providers, agents and simulation seed. Trades
+//! and events it produces carry the run ID, so a trade log or event stream
+//! spanning restarts and config changes can be split by run.
+//!
+//! Runs are listed newest first in `runs` (`GET /api/runs`); the services
+//! process's run is the swarm's current one (`state:run`, `GET /api/run`).
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::core::pipeline::AgentRole;
+use crate::core::simulation;
+use crate::core::{Blackboard, Config};
+
+/// Runs returned by `GET /api/runs` when no limit is given, and at most
+pub const DEFAULT_LIST_LIMIT: usize = 50;
+pub const MAX_LIST_LIMIT: usize = 500;
+
+/// Backends a run uses
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunProviders {
+    /// Market data, `alpha_vantage` or `polygon`
+    pub market: String,
+    /// Provider prices are cross-checked against, if enabled
+    pub cross_check: Option<String>,
+    /// Execution backend, e.g. `simulated`
+    pub executor: String,
+}
+
+/// One process's run of the swarm
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetadata {
+    #[serde(default)]
+    pub id: String,
+    pub started_at: String,
+    /// Crate version of the binary
+    #[serde(default)]
+    pub version: String,
+    /// Hash of the effective configuration, after command-line overrides
+    /// (comparable between runs of the same version)
+    #[serde(default)]
+    pub config_hash: String,
+    /// `live` with an Alpha Vantage key, `demo` (simulated fallback) without
+    #[serde(default)]
+    pub mode: String,
+    /// Config profile merged over the base file (`--profile`)
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub providers: RunProviders,
+    /// Agents run by this process
+    #[serde(default)]
+    pub roles: Vec<AgentRole>,
+    /// Whether this process runs the background services
+    #[serde(default)]
+    pub services: bool,
+    pub seed: u64,
+    /// config | cli | random
+    pub seed_source: String,
+}
+
+impl RunMetadata {
+    /// A new run of `config` starting now
+    pub fn new(config: &Config, mode: &str, providers: RunProviders, seed: u64, seed_source: &str) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            config_hash: config_hash(config),
+            mode: mode.to_string(),
+            profile: config.profile.clone(),
+            providers,
+            roles: config.agent.roles.clone(),
+            services: config.agent.run_services,
+            seed,
+            seed_source: seed_source.to_string(),
+        }
+    }
+}
+
+/// Short hex hash of the configuration's `Debug` form, which covers every
+/// setting including defaults
+pub fn config_hash(config: &Config) -> String {
+    format!("{:016x}", simulation::fnv1a(format!("{:?}", config).as_bytes()))
+}
+
+/// Register the run, tag this process's trades and events with its ID and,
+/// for the services process, make it the swarm's current run
+pub async fn start(board: &Blackboard, run: &RunMetadata) -> Result<()> {
+    board.register_run(run).await?;
+    board.set_run_id(&run.id);
+    if run.services {
+        board.set_current_run(run).await?;
+    }
+    info!(
+        "🏷️  Run {} (v{}, config {}, {} mode, seed {} from {}); rerun with `--seed {}`",
+        run.id, run.version, run.config_hash, run.mode, run.seed, run.seed_source, run.seed
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_hash_tracks_changes() {
+        let config = Config::default();
+        assert_eq!(config_hash(&config), config_hash(&Config::default()));
+        assert_eq!(config_hash(&config).len(), 16);
+        
+        let mut changed = Config::default();
+        changed.portfolio.drift_threshold += 1.0;
+        assert_ne!(config_hash(&config), config_hash(&changed));
+        
+        let run = RunMetadata::new(&config, "demo", RunProviders::default(), 42, "cli");
+        assert_eq!(run.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(run.roles, AgentRole::ALL.to_vec());
+        
+        // Runs recorded before the registry carried only the seed
+        let old: RunMetadata =
+            serde_json::from_str(r#"{"seed": 7, "seed_source": "random", "started_at": "2026-10-01T00:00:00Z"}"#).unwrap();
+        assert!(old.id.is_empty());
+    }
+}
@@ -0,0 +1,302 @@
+//! Agent Runtime
+//!
+//! Lightweight self-reporting of how each agent's loop performs, so a
+//! regression in an agent shows up as numbers rather than as a sluggish
+//! swarm. Each agent task is wrapped in `instrument`, which times every
+//! poll of its future (busy time, i.e. CPU spent in the agent) and makes
+//! the agent's monitor available task-locally. Agents mark their loop
+//! boundaries with `tick` in place of `ticker.tick()`: a loop runs from a
+//! tick firing until the agent waits for the next one, and whatever of it
+//! wasn't spent polling was spent awaiting (Redis, HTTP, locks). The blackboard counts its Redis calls
+//! against the monitor of the task making them. `run_reporter` publishes a
+//! window of loops per agent to the blackboard every
+//! `[agent] runtime_report_secs` (`GET /api/agents/runtime`).
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::{interval, Instant, Interval};
+use tracing::warn;
+
+use crate::core::blackboard::Blackboard;
+
+tokio::task_local! {
+    static MONITOR: Arc<RuntimeMonitor>;
+}
+
+/// Runtime of one agent over a reporting window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentRuntime {
+    pub name: String,
+    /// Loops completed since the agent started
+    pub loops: u64,
+    /// Loops completed in this window
+    pub window_loops: u64,
+    pub mean_loop_ms: f64,
+    pub max_loop_ms: f64,
+    /// Time spent polling the agent's future (its own CPU time)
+    pub mean_busy_ms: f64,
+    /// Time spent waiting on I/O, timers and locks within a loop
+    pub mean_awaited_ms: f64,
+    /// Blackboard operations touching Redis per loop
+    pub mean_redis_calls: f64,
+    pub max_redis_calls: u64,
+    /// Resident memory of the reporting process (Linux only)
+    #[serde(default)]
+    pub process_rss_bytes: Option<u64>,
+    pub reported_at: DateTime<Utc>,
+}
+
+/// One completed loop
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct LoopSample {
+    duration: Duration,
+    busy: Duration,
+    redis_calls: u64,
+}
+
+#[derive(Debug, Default)]
+struct MonitorState {
+    /// When the current poll started, while one is running
+    poll_started: Option<Instant>,
+    /// When the current loop started, while one is open
+    loop_started: Option<Instant>,
+    busy: Duration,
+    redis_calls: u64,
+    loops: u64,
+    window: Vec<LoopSample>,
+}
+
+/// Collects one agent's loop samples until the next report
+#[derive(Debug)]
+pub struct RuntimeMonitor {
+    name: String,
+    state: Mutex<MonitorState>,
+}
+
+/// Samples kept between reports; a stalled reporter doesn't grow memory
+const MAX_WINDOW_SAMPLES: usize = 10_000;
+
+impl RuntimeMonitor {
+    pub fn new(name: impl Into<String>) -> Arc<Self> {
+        Arc::new(Self { name: name.into(), state: Mutex::new(MonitorState::default()) })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MonitorState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn poll_started(&self, now: Instant) {
+        self.state().poll_started = Some(now);
+    }
+
+    fn poll_finished(&self, now: Instant) {
+        let mut state = self.state();
+        if let Some(started) = state.poll_started.take() {
+            if state.loop_started.is_some() {
+                state.busy += now.duration_since(started);
+            }
+        }
+    }
+
+    /// Close the open loop (if any) at `now`, counting the running poll's
+    /// time so far as busy
+    fn end_loop(&self, now: Instant) {
+        let mut state = self.state();
+        let Some(started) = state.loop_started.take() else {
+            return;
+        };
+        if let Some(poll) = state.poll_started.replace(now) {
+            state.busy += now.duration_since(poll);
+        }
+        let sample = LoopSample {
+            duration: now.duration_since(started),
+            busy: std::mem::take(&mut state.busy),
+            redis_calls: std::mem::take(&mut state.redis_calls),
+        };
+        state.loops += 1;
+        if state.window.len() < MAX_WINDOW_SAMPLES {
+            state.window.push(sample);
+        }
+    }
+
+    fn start_loop(&self, now: Instant) {
+        let mut state = self.state();
+        state.loop_started = Some(now);
+        state.busy = Duration::ZERO;
+        state.redis_calls = 0;
+        if state.poll_started.is_some() {
+            state.poll_started = Some(now);
+        }
+    }
+
+    fn redis_call(&self) {
+        let mut state = self.state();
+        if state.loop_started.is_some() {
+            state.redis_calls += 1;
+        }
+    }
+
+    /// Summarize the loops since the last report and start a new window;
+    /// none if no loop completed
+    pub fn report(&self, now: DateTime<Utc>) -> Option<AgentRuntime> {
+        let (loops, window) = {
+            let mut state = self.state();
+            (state.loops, std::mem::take(&mut state.window))
+        };
+        if window.is_empty() {
+            return None;
+        }
+        let n = window.len() as f64;
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let total = |f: fn(&LoopSample) -> Duration| window.iter().map(f).sum::<Duration>();
+        let mean_loop_ms = ms(total(|s| s.duration)) / n;
+        let mean_busy_ms = ms(total(|s| s.busy)) / n;
+        Some(AgentRuntime {
+            name: self.name.clone(),
+            loops,
+            window_loops: window.len() as u64,
+            mean_loop_ms,
+            max_loop_ms: window.iter().map(|s| ms(s.duration)).fold(0.0, f64::max),
+            mean_busy_ms,
+            mean_awaited_ms: (mean_loop_ms - mean_busy_ms).max(0.0),
+            mean_redis_calls: window.iter().map(|s| s.redis_calls).sum::<u64>() as f64 / n,
+            max_redis_calls: window.iter().map(|s| s.redis_calls).max().unwrap_or(0),
+            process_rss_bytes: process_rss_bytes(),
+            reported_at: now,
+        })
+    }
+}
+
+/// A future whose polls are timed against a monitor
+pub struct Instrumented<F> {
+    inner: Pin<Box<tokio::task::futures::TaskLocalFuture<Arc<RuntimeMonitor>, F>>>,
+    monitor: Arc<RuntimeMonitor>,
+}
+
+impl<F: Future> Future for Instrumented<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.monitor.poll_started(Instant::now());
+        let result = self.inner.as_mut().poll(cx);
+        self.monitor.poll_finished(Instant::now());
+        result
+    }
+}
+
+/// Run `future` (an agent's `run`) under `monitor`
+pub fn instrument<F: Future>(monitor: Arc<RuntimeMonitor>, future: F) -> Instrumented<F> {
+    Instrumented { inner: Box::pin(MONITOR.scope(monitor.clone(), future)), monitor }
+}
+
+/// Wait for the agent's next tick, ending the current loop and starting
+/// the next; a plain tick outside an instrumented task
+pub async fn tick(ticker: &mut Interval) {
+    let monitor = MONITOR.try_with(Arc::clone).ok();
+    if let Some(monitor) = &monitor {
+        monitor.end_loop(Instant::now());
+    }
+    ticker.tick().await;
+    if let Some(monitor) = &monitor {
+        monitor.start_loop(Instant::now());
+    }
+}
+
+/// `tick`, or sooner once `notify` fires (but not before `not_before`);
+/// whether the notification won
+pub async fn tick_or_notified(ticker: &mut Interval, notify: &Notify, not_before: Instant) -> bool {
+    let monitor = MONITOR.try_with(Arc::clone).ok();
+    if let Some(monitor) = &monitor {
+        monitor.end_loop(Instant::now());
+    }
+    let notified = tokio::select! {
+        _ = ticker.tick() => false,
+        _ = async {
+            notify.notified().await;
+            tokio::time::sleep_until(not_before).await;
+        } => true,
+    };
+    if let Some(monitor) = &monitor {
+        monitor.start_loop(Instant::now());
+    }
+    notified
+}
+
+/// Count a Redis call against the calling agent's loop, if any
+pub fn record_redis_call() {
+    let _ = MONITOR.try_with(|monitor| monitor.redis_call());
+}
+
+/// Resident set size from `/proc/self/status`
+fn process_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// Publish each monitor's window every `[agent] runtime_report_secs`
+pub async fn run_reporter(board: Arc<Blackboard>, monitors: Vec<Arc<RuntimeMonitor>>) -> Result<()> {
+    let mut ticker = interval(Duration::from_secs(board.config().agent.runtime_report_secs.max(1)));
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+
+        let now = Utc::now();
+        let reports: Vec<AgentRuntime> = monitors.iter().filter_map(|monitor| monitor.report(now)).collect();
+        if reports.is_empty() {
+            continue;
+        }
+        if let Err(e) = board.put_agent_runtime(&reports).await {
+            warn!("⏱️ Failed to publish agent runtime: {:#}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_loop_accounting() {
+        let monitor = RuntimeMonitor::new("Analyst");
+        let agent = async {
+            let mut ticker = interval(Duration::from_millis(500));
+            for _ in 0..3 {
+                tick(&mut ticker).await;
+                record_redis_call();
+                record_redis_call();
+                // Awaited, not busy
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            tick(&mut ticker).await;
+        };
+        instrument(monitor.clone(), agent).await;
+        // Outside the task nothing is counted
+        record_redis_call();
+
+        let report = monitor.report(Utc::now()).unwrap();
+        assert_eq!(report.name, "Analyst");
+        assert_eq!((report.loops, report.window_loops), (3, 3));
+        assert_eq!(report.max_redis_calls, 2);
+        assert_eq!(report.mean_redis_calls, 2.0);
+        // A loop is the work after a tick, not the wait for the next one
+        assert!((report.mean_loop_ms - 100.0).abs() < 1.0, "{}", report.mean_loop_ms);
+        assert!(report.mean_busy_ms < report.mean_awaited_ms);
+
+        // The window starts over after each report
+        assert!(monitor.report(Utc::now()).is_none());
+    }
+}
@@ -0,0 +1,107 @@
+//! Event Sampling
+//!
+//! Every agent sniffs its input every `sniff_interval_ms`, and each sniff
+//! broadcasts a Sniffed (or Decayed) event. With four agents that is a
+//! constant stream which fills the broadcast channel and buries the
+//! deposits, anomalies and failures the dashboard actually cares about.
+//! Sniff observations are therefore coalesced per pheromone type and
+//! action: at most one event per `[event_sampling]` interval is broadcast,
+//! carrying the number of observations it stands for. Deposits and other
+//! transitions are never sampled. Pheromone types listed in `muted` emit
+//! no sniff observations at all; their deposits and threshold crossings
+//! still do.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::core::config::EventSamplingConfig;
+use crate::core::physics::PheromoneType;
+
+/// Rate limiter for repeated observations, keyed by pheromone and action
+#[derive(Debug, Default)]
+pub struct EventSampler {
+    /// Last broadcast and observations suppressed since
+    windows: HashMap<String, (Instant, u32)>,
+}
+
+impl EventSampler {
+    /// Record an observation; returns how many observations the event
+    /// should stand for (itself plus suppressed ones) if it may be
+    /// broadcast, or `None` to drop it
+    pub fn admit(&mut self, key: &str, interval: Duration, now: Instant) -> Option<u32> {
+        if interval.is_zero() {
+            return Some(1);
+        }
+        
+        match self.windows.get_mut(key) {
+            Some((last, suppressed)) if now.duration_since(*last) < interval => {
+                *suppressed += 1;
+                None
+            }
+            Some((last, suppressed)) => {
+                let count = *suppressed + 1;
+                *last = now;
+                *suppressed = 0;
+                Some(count)
+            }
+            None => {
+                self.windows.insert(key.to_string(), (now, 0));
+                Some(1)
+            }
+        }
+    }
+}
+
+impl EventSamplingConfig {
+    /// Minimum spacing of broadcast Sniffed / Decayed observations
+    pub fn interval(&self, decayed: bool) -> Duration {
+        if !self.enabled {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(if decayed { self.decayed_interval_ms } else { self.sniffed_interval_ms })
+    }
+
+    /// Whether sniffs of `pheromone_type` are broadcast as Sniffed / Decayed
+    pub fn observes(&self, pheromone_type: PheromoneType) -> bool {
+        !self.muted.contains(&pheromone_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coalesces_within_interval() {
+        let mut sampler = EventSampler::default();
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        
+        assert_eq!(sampler.admit("price:sniffed", second, at(0)), Some(1));
+        assert_eq!(sampler.admit("price:sniffed", second, at(250)), None);
+        assert_eq!(sampler.admit("price:sniffed", second, at(500)), None);
+        // Other keys are limited independently
+        assert_eq!(sampler.admit("permit:sniffed", second, at(500)), Some(1));
+        // The next admitted event stands for the two suppressed ones too
+        assert_eq!(sampler.admit("price:sniffed", second, at(1000)), Some(3));
+        assert_eq!(sampler.admit("price:sniffed", second, at(1200)), None);
+    }
+
+    #[test]
+    fn test_disabled_admits_everything() {
+        let config = EventSamplingConfig { enabled: false, ..Default::default() };
+        let mut sampler = EventSampler::default();
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(sampler.admit("price:sniffed", config.interval(false), now), Some(1));
+        }
+    }
+
+    #[test]
+    fn test_muted_types() {
+        let config: EventSamplingConfig = toml::from_str(r#"muted = ["PriceFreshness"]"#).unwrap();
+        assert!(!config.observes(PheromoneType::PriceFreshness));
+        assert!(config.observes(PheromoneType::ExecutionPermit));
+    }
+}
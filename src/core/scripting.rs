@@ -0,0 +1,193 @@
+//! Scripting Hooks
+//!
+//! Small Rhai scripts can take over two decisions without recompiling:
+//! the Analyst's opportunity score (`fn score(ctx)`, a number in [0, 1])
+//! and the Guardian's permit rule (`fn permit(ctx)`, true to permit).
+//! Scripts are referenced from `[scripting]`, compiled once at agent start
+//! and receive the decision's inputs as a map.
+//!
+//! Scripts are sandboxed: modules cannot be imported (so no file access),
+//! each call is aborted after `max_operations` steps, and call depth,
+//! string, array and map sizes are bounded. `print` goes to the log.
+
+use anyhow::{anyhow, bail, Context, Result};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{CallFnOptions, Dynamic, Engine, Scope, AST};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::core::config::ScriptingConfig;
+
+/// Largest string, array or map a script may build
+const MAX_COLLECTION_SIZE: usize = 10_000;
+
+/// A compiled user script and its sandboxed engine
+pub struct ScriptHook {
+    path: String,
+    engine: Engine,
+    ast: AST,
+}
+
+impl std::fmt::Debug for ScriptHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptHook").field("path", &self.path).finish()
+    }
+}
+
+impl ScriptHook {
+    /// Read and compile a script file
+    pub fn load(path: &str, config: &ScriptingConfig) -> Result<Self> {
+        let source = std::fs::read_to_string(path).with_context(|| format!("Failed to read script {}", path))?;
+        Self::compile(path, &source, config)
+    }
+
+    /// Compile script source; `path` names it in logs and errors
+    pub fn compile(path: &str, source: &str, config: &ScriptingConfig) -> Result<Self> {
+        let mut engine = sandboxed_engine(config);
+        let name = path.to_string();
+        engine.on_print(move |text| info!("📜 {}: {}", name, text));
+        let name = path.to_string();
+        engine.on_debug(move |text, _, pos| info!("📜 {} {}: {}", name, pos, text));
+        
+        let ast = engine.compile(source).map_err(|e| anyhow!("{}: {}", path, e))?;
+        Ok(Self { path: path.to_string(), engine, ast })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Whether the script defines a function `name`
+    pub fn defines(&self, name: &str) -> bool {
+        self.ast.iter_functions().any(|f| f.name == name)
+    }
+
+    /// Call `func(ctx)` with the context serialized to a Rhai map
+    pub fn call(&self, func: &str, ctx: &impl Serialize) -> Result<Dynamic> {
+        let ctx = rhai::serde::to_dynamic(ctx).map_err(|e| anyhow!("{}: {}", self.path, e))?;
+        // Functions only: top-level statements ran (if at all) at load
+        let options = CallFnOptions::new().eval_ast(false);
+        self.engine
+            .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &self.ast, func, (ctx,))
+            .map_err(|e| anyhow!("{}: {}", self.path, e))
+    }
+
+    /// Call a function expected to return a number
+    pub fn call_f64(&self, func: &str, ctx: &impl Serialize) -> Result<f64> {
+        let value = self.call(func, ctx)?;
+        match value.as_float() {
+            Ok(x) => Ok(x),
+            Err(_) => match value.as_int() {
+                Ok(i) => Ok(i as f64),
+                Err(t) => bail!("{}: {}() returned {}, expected a number", self.path, func, t),
+            },
+        }
+    }
+
+    /// Call a function expected to return a bool
+    pub fn call_bool(&self, func: &str, ctx: &impl Serialize) -> Result<bool> {
+        let value = self.call(func, ctx)?;
+        value
+            .as_bool()
+            .map_err(|t| anyhow!("{}: {}() returned {}, expected a bool", self.path, func, t))
+    }
+}
+
+/// A Rhai engine with the sandbox limits applied (no imports, bounded
+/// operations, call depth and collection sizes)
+pub fn sandboxed_engine(config: &ScriptingConfig) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(DummyModuleResolver::new());
+    engine.set_max_operations(config.max_operations);
+    engine.set_max_call_levels(config.max_call_levels);
+    engine.set_max_string_size(MAX_COLLECTION_SIZE);
+    engine.set_max_array_size(MAX_COLLECTION_SIZE);
+    engine.set_max_map_size(MAX_COLLECTION_SIZE);
+    engine
+}
+
+/// Load the script at `path` if one is configured and defines `entry`;
+/// otherwise warn and leave the built-in rule in place
+pub fn load_hook(path: Option<&str>, entry: &str, config: &ScriptingConfig) -> Option<ScriptHook> {
+    let path = path?;
+    match ScriptHook::load(path, config) {
+        Ok(hook) if hook.defines(entry) => {
+            info!("📜 Loaded script {} ({})", path, entry);
+            Some(hook)
+        }
+        Ok(_) => {
+            warn!("Script {} does not define {}(ctx). Using the built-in rule.", path, entry);
+            None
+        }
+        Err(e) => {
+            warn!("{:#}. Using the built-in rule.", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Ctx {
+        drift_pct: f64,
+        signal: &'static str,
+    }
+
+    fn hook(source: &str) -> ScriptHook {
+        ScriptHook::compile("test.rhai", source, &ScriptingConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn test_calls_with_context() {
+        let script = hook(r#"
+            fn score(ctx) { if ctx.drift_pct > 5.0 { 1 } else { ctx.drift_pct / 10.0 } }
+            fn permit(ctx) { ctx.signal == "vix" }
+        "#);
+        assert!(script.defines("score") && !script.defines("other"));
+        
+        let ctx = Ctx { drift_pct: 2.5, signal: "vix" };
+        assert_eq!(script.call_f64("score", &ctx).unwrap(), 0.25);
+        assert_eq!(script.call_f64("score", &Ctx { drift_pct: 8.0, ..ctx }).unwrap(), 1.0);
+        assert!(script.call_bool("permit", &Ctx { drift_pct: 0.0, signal: "vix" }).unwrap());
+        assert!(script.call_bool("score", &Ctx { drift_pct: 0.0, signal: "vix" }).is_err());
+    }
+
+    #[test]
+    fn test_sandbox_limits() {
+        let ctx = Ctx { drift_pct: 0.0, signal: "vix" };
+        
+        let runaway = hook("fn score(ctx) { loop { } }");
+        assert!(runaway.call_f64("score", &ctx).is_err());
+        
+        let recursive = hook("fn score(ctx) { score(ctx) }");
+        assert!(recursive.call_f64("score", &ctx).is_err());
+        
+        let import = hook(r#"fn score(ctx) { import "config" as c; 1.0 }"#);
+        assert!(import.call_f64("score", &ctx).is_err());
+    }
+
+    #[test]
+    fn test_example_scripts_compile() {
+        let config = ScriptingConfig::default();
+        let analyst = ScriptHook::load("scripts/analyst_score.rhai", &config).unwrap();
+        let ctx = serde_json::json!({
+            "drift_pct": 7.5,
+            "drift_threshold": 5.0,
+            "cost_benefit": { "estimated_cost": 10.0, "expected_benefit": 50.0 },
+        });
+        assert_eq!(analyst.call_f64("score", &ctx).unwrap(), 0.75);
+        
+        let guardian = ScriptHook::load("scripts/guardian_rule.rhai", &config).unwrap();
+        let ctx = serde_json::json!({
+            "volatility": 28.0,
+            "volatility_signal": "vix",
+            "vix_low_threshold": 15.0,
+            "vix_high_threshold": 25.0,
+            "analysis": { "drift_pct": 12.0 },
+        });
+        assert!(guardian.call_bool("permit", &ctx).unwrap());
+    }
+}
@@ -0,0 +1,172 @@
+//! Secret Handling
+//!
+//! Credentials are compared in constant time, so a caller can't recover
+//! the operator token or an API key from how quickly a wrong guess is
+//! rejected. At startup every configured secret (market provider keys,
+//! operator token, user API keys, alert webhook URLs and the Redis URL) is
+//! registered, and everything logged passes through `redact`: registered
+//! values and the values of `apikey=`, `api_key=` and `token=` query
+//! parameters are replaced with `[REDACTED]`. HTTP clients additionally
+//! drop request URLs from their errors, so a key embedded in a URL never
+//! reaches an error message, alert or dead letter.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::sync::RwLock;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::core::allocation;
+use crate::core::Config;
+use crate::market;
+
+/// What a secret is replaced with
+const REDACTED: &str = "[REDACTED]";
+
+/// Query parameters whose values are always redacted
+const SECRET_PARAMS: [&str; 3] = ["apikey=", "api_key=", "token="];
+
+/// Secrets shorter than this aren't registered: redacting them would
+/// mangle ordinary text (and `demo` isn't secret)
+const MIN_SECRET_LEN: usize = 6;
+
+/// Registered secret values, longest first
+static SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Compare two credentials in time that depends only on their lengths
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Redact `secret` from everything logged from now on
+pub fn register(secret: &str) {
+    if secret.len() < MIN_SECRET_LEN {
+        return;
+    }
+    let mut secrets = SECRETS.write().unwrap_or_else(|e| e.into_inner());
+    if !secrets.iter().any(|s| s == secret) {
+        secrets.push(secret.to_string());
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+    }
+}
+
+/// Register every secret the configuration points at that is set
+pub fn register_configured(config: &Config) {
+    // The key of every provider that may quote: the configured one, each
+    // member of the failover chain and the cross-check provider
+    let providers = std::iter::once(&config.market.provider)
+        .chain(&config.market.providers.chain)
+        .chain(std::iter::once(&config.market.cross_check.provider));
+    let mut envs: Vec<String> =
+        providers.map(|provider| market::provider_key_env(&config.market, provider).to_string()).collect();
+    envs.extend([
+        "CROSS_CHECK_API_KEY".to_string(),
+        "REDIS_URL".to_string(),
+        "REDIS_REPLICA_URL".to_string(),
+        config.alerting.slack_webhook_env.clone(),
+        config.alerting.webhook_url_env.clone(),
+    ]);
+    envs.extend(config.auth.users.iter().map(|user| user.api_key_env.clone()));
+    for env in envs {
+        if let Ok(value) = std::env::var(&env) {
+            register(&value);
+        }
+    }
+    if let Some(token) = allocation::operator_token(&config.allocation) {
+        register(&token);
+    }
+}
+
+/// `text` with registered secrets and secret query parameters redacted
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let secrets = SECRETS.read().unwrap_or_else(|e| e.into_inner());
+    let mut redacted = Cow::Borrowed(text);
+    for secret in secrets.iter() {
+        if redacted.contains(secret.as_str()) {
+            redacted = Cow::Owned(redacted.replace(secret.as_str(), REDACTED));
+        }
+    }
+    for param in SECRET_PARAMS {
+        if redacted.contains(param) {
+            redacted = Cow::Owned(redact_param(&redacted, param));
+        }
+    }
+    redacted
+}
+
+/// Replace the value following each `param` (up to `&`, whitespace or a
+/// closing bracket/quote)
+fn redact_param(text: &str, param: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find(param) {
+        let value_start = at + param.len();
+        out.push_str(&rest[..value_start]);
+        let value = &rest[value_start..];
+        let value_len = value
+            .find(|c: char| c == '&' || c.is_whitespace() || matches!(c, ')' | ']' | '"' | '\''))
+            .unwrap_or(value.len());
+        if value_len > 0 && !value[..value_len].starts_with(REDACTED) {
+            out.push_str(REDACTED);
+        } else {
+            out.push_str(&value[..value_len]);
+        }
+        rest = &value[value_len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Log output with secrets redacted
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RedactingStdout;
+
+impl Write for RedactingStdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        io::stdout().write_all(redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for RedactingStdout {
+    type Writer = RedactingStdout;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        *self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redaction() {
+        assert!(constant_time_eq(b"s3cret-token", b"s3cret-token"));
+        assert!(!constant_time_eq(b"s3cret-token", b"s3cret-tokem"));
+        assert!(!constant_time_eq(b"s3cret", b"s3cret-token"));
+
+        // Secret query parameters, wherever they appear
+        assert_eq!(
+            redact("error sending request for url (https://www.alphavantage.co/query?function=GLOBAL_QUOTE&apikey=ABC123XYZ)"),
+            "error sending request for url (https://www.alphavantage.co/query?function=GLOBAL_QUOTE&apikey=[REDACTED])"
+        );
+        assert_eq!(redact("/ws?api_key=k-alice&swarm=a token=t0k"), "/ws?api_key=[REDACTED]&swarm=a token=[REDACTED]");
+        assert_eq!(redact("nothing secret here"), "nothing secret here");
+
+        // Registered values anywhere, but not short ones
+        register("hooks.slack.com/services/T000/B000/XXXX");
+        register("demo");
+        assert_eq!(
+            redact("POST https://hooks.slack.com/services/T000/B000/XXXX failed: demo mode"),
+            "POST https://[REDACTED] failed: demo mode"
+        );
+    }
+}
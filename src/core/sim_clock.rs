@@ -0,0 +1,195 @@
+//! Simulated Market Clock
+//!
+//! A VIX replay normally advances one observation every `step_secs` of
+//! wall-clock time, so a demo of March 2020 takes as long as the demo
+//! runner is willing to wait. The replay instead follows a simulated clock
+//! that `POST /api/sim/speed` accelerates (or slows), `POST /api/sim/pause`
+//! stops and resumes, and `POST /api/sim/jump` moves forward, so demo
+//! drivers and tests can skip quiet stretches and hold on interesting ones.
+//!
+//! The clock is stored on the blackboard (`sim:clock`) as a speed and the
+//! simulated time elapsed at an anchoring instant, so every process computes
+//! the same simulated time; each process running a replay follows changes
+//! within a second. The services process restarts the clock at startup.
+//! The controls are only accepted while a VIX history is being replayed.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{info, warn};
+
+use crate::core::{Blackboard, Config};
+
+/// How often a process picks up clock changes from the blackboard
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Simulated time as a rate from an anchor
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClockState {
+    /// Simulated seconds per wall-clock second
+    pub speed: f64,
+    pub paused: bool,
+    /// Simulated seconds elapsed at `anchored_at`
+    pub elapsed_secs: f64,
+    pub anchored_at: DateTime<Utc>,
+}
+
+impl ClockState {
+    /// A clock running at real time from `now`
+    pub fn start(now: DateTime<Utc>) -> Self {
+        Self { speed: 1.0, paused: false, elapsed_secs: 0.0, anchored_at: now }
+    }
+
+    /// Simulated seconds elapsed at `now`
+    pub fn elapsed_secs_at(&self, now: DateTime<Utc>) -> f64 {
+        if self.paused {
+            return self.elapsed_secs;
+        }
+        let wall_secs = (now - self.anchored_at).num_milliseconds().max(0) as f64 / 1000.0;
+        self.elapsed_secs + wall_secs * self.speed
+    }
+
+    /// The same simulated time, anchored at `now`
+    fn anchored(&self, now: DateTime<Utc>) -> Self {
+        Self { elapsed_secs: self.elapsed_secs_at(now), anchored_at: now, ..*self }
+    }
+
+    /// Run at `speed` from `now` on
+    pub fn with_speed(&self, speed: f64, now: DateTime<Utc>) -> std::result::Result<Self, String> {
+        if !speed.is_finite() || speed <= 0.0 {
+            return Err(format!("speed must be a positive number, got {}", speed));
+        }
+        Ok(Self { speed, ..self.anchored(now) })
+    }
+
+    /// Stop or resume the clock at `now`
+    pub fn with_paused(&self, paused: bool, now: DateTime<Utc>) -> Self {
+        Self { paused, ..self.anchored(now) }
+    }
+
+    /// Move simulated time forward by `secs`
+    pub fn jumped(&self, secs: f64, now: DateTime<Utc>) -> std::result::Result<Self, String> {
+        if !secs.is_finite() || secs <= 0.0 {
+            return Err(format!("jump must be a positive number of seconds, got {}", secs));
+        }
+        let anchored = self.anchored(now);
+        Ok(Self { elapsed_secs: anchored.elapsed_secs + secs, ..anchored })
+    }
+}
+
+/// A process's copy of the clock, shared with what it drives
+#[derive(Debug, Clone)]
+pub struct SimClock(Arc<RwLock<ClockState>>);
+
+impl SimClock {
+    pub fn new(state: ClockState) -> Self {
+        Self(Arc::new(RwLock::new(state)))
+    }
+
+    pub fn state(&self) -> ClockState {
+        *self.0.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set(&self, state: ClockState) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = state;
+    }
+
+    /// Simulated time elapsed now
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64(self.state().elapsed_secs_at(Utc::now()).max(0.0))
+    }
+}
+
+/// Body of `POST /api/sim/speed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeedRequest {
+    pub speed: f64,
+}
+
+/// Body of `POST /api/sim/pause`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PauseRequest {
+    pub paused: bool,
+}
+
+/// Body of `POST /api/sim/jump`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JumpRequest {
+    /// Simulated seconds to skip
+    pub secs: f64,
+}
+
+/// Whether this swarm runs on the simulated clock
+pub fn controllable(config: &Config) -> bool {
+    config.market.vix_source == "history"
+}
+
+/// Follow clock changes made through the API
+pub async fn watch(board: Arc<Blackboard>, clock: SimClock) {
+    let mut ticker = interval(WATCH_INTERVAL);
+
+    loop {
+        ticker.tick().await;
+        match board.get_sim_clock().await {
+            Ok(Some(state)) if state != clock.state() => {
+                info!(
+                    "⏱️ Simulated clock: {}x{}, {:.0}s elapsed",
+                    state.speed,
+                    if state.paused { " (paused)" } else { "" },
+                    state.elapsed_secs_at(Utc::now())
+                );
+                clock.set(state);
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Simulated clock: Failed to read the clock: {}", e),
+        }
+    }
+}
+
+/// This process's copy of the swarm's clock; with `restart` (the services
+/// process at startup) it starts over at real time
+pub async fn attach(board: &Blackboard, restart: bool) -> Result<SimClock> {
+    let stored = if restart { None } else { board.get_sim_clock().await? };
+    let state = match stored {
+        Some(state) => state,
+        None => {
+            let state = ClockState::start(Utc::now());
+            board.put_sim_clock(&state).await?;
+            state
+        }
+    };
+    Ok(SimClock::new(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_controls() {
+        let t0 = Utc::now();
+        let at = |secs: i64| t0 + chrono::Duration::seconds(secs);
+        let clock = ClockState::start(t0);
+        assert_eq!(clock.elapsed_secs_at(at(10)), 10.0);
+
+        // 60x from t+10: a minute per second
+        let fast = clock.with_speed(60.0, at(10)).unwrap();
+        assert_eq!(fast.elapsed_secs_at(at(12)), 130.0);
+        assert!(clock.with_speed(0.0, at(10)).is_err());
+
+        // Paused at t+12, time stands still until resumed
+        let paused = fast.with_paused(true, at(12));
+        assert_eq!(paused.elapsed_secs_at(at(100)), 130.0);
+        let resumed = paused.with_paused(false, at(100));
+        assert_eq!(resumed.elapsed_secs_at(at(101)), 190.0);
+
+        // Jumps skip ahead without changing the rate
+        let jumped = resumed.jumped(3600.0, at(101)).unwrap();
+        assert_eq!(jumped.elapsed_secs_at(at(101)), 3790.0);
+        assert_eq!(jumped.speed, 60.0);
+        assert!(resumed.jumped(-5.0, at(101)).is_err());
+    }
+}
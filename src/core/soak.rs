@@ -0,0 +1,404 @@
+//! Soak Test
+//!
+//! `driftguard soak` runs the whole swarm on simulated quotes for hours of
+//! simulated time, `[soak] speed` times faster than real time: pheromones
+//! decay, and agents, the Sensor, valuation and simulated fills loop, that
+//! much faster (see `configure`). It runs as its own swarm, `<name>-soak`,
+//! so the portfolio it bootstraps is not the real one. Every
+//! `check_interval_ms` the blackboard is checked for what no run may break:
+//! - the stocks, bonds and cash weights sum to 100%
+//! - cash and holdings are never negative
+//! - nothing trades while the swarm is HALTED (the Trader checks right
+//!   before sending legs, so only legs already filling may still land)
+//! - no simulated hour holds more than `max_trades_per_hour` trades
+//!
+//! The kill switch is engaged for `halt_minutes` every `halt_every_hours`,
+//! so the halt is exercised. The first violation ends the run and is
+//! written to `report_file` with the seed and command that replay it, and
+//! the portfolio, mode and trades it was found in.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{info, warn};
+
+use crate::core::blackboard::{PortfolioState, TradeLogEntry};
+use crate::core::config::{Config, SoakConfig};
+use crate::core::mode::{self, KillSwitch, SystemMode, SystemStatus};
+use crate::core::{money, Blackboard};
+
+/// Shortest loop interval an accelerated swarm is given
+const MIN_INTERVAL_MS: u64 = 10;
+
+/// Rounding slack in weight sums and balances
+const TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// Run the swarm of `config` on simulated quotes, as `<name>-soak`, with
+/// outside deliveries off and its clock compressed by `[soak] speed`
+pub fn configure(config: &mut Config) {
+    config.swarm.name = format!("{}-soak", config.swarm.name);
+    config.market.provider = "alpha_vantage".to_string();
+    config.market.yahoo.fallback = false;
+    config.market.vix_source = "simulation".to_string();
+    config.market.cross_check.enabled = false;
+    config.bridge.enabled = false;
+    config.alerting.enabled = false;
+    config.ledger.journal_file = None;
+
+    let speed = config.soak.speed;
+    let interval = |ms: u64| ((ms as f64 / speed).round() as u64).max(MIN_INTERVAL_MS);
+    let delay = |ms: u64| (ms as f64 / speed).round() as u64;
+    let pheromones = &mut config.pheromones;
+    for rate in [
+        &mut pheromones.price_freshness_decay,
+        &mut pheromones.rebalance_opportunity_decay,
+        &mut pheromones.execution_permit_decay,
+        &mut pheromones.trade_executed_decay,
+        &mut pheromones.data_quality_decay,
+        &mut pheromones.drift_forecast_decay,
+    ] {
+        *rate *= speed;
+    }
+    config.market.poll_interval_ms = interval(config.market.poll_interval_ms);
+    config.market.adaptive_polling.min_interval_ms = interval(config.market.adaptive_polling.min_interval_ms);
+    config.market.adaptive_polling.max_interval_ms = interval(config.market.adaptive_polling.max_interval_ms);
+    config.agent.sniff_interval_ms = interval(config.agent.sniff_interval_ms);
+    config.valuation.interval_ms = interval(config.valuation.interval_ms);
+    config.decay_scheduler.max_sleep_ms = interval(config.decay_scheduler.max_sleep_ms);
+    config.execution.latency_ms = delay(config.execution.latency_ms);
+    config.execution.latency_jitter_ms = delay(config.execution.latency_jitter_ms);
+    config.execution.queue.retry_backoff_ms = delay(config.execution.queue.retry_backoff_ms);
+}
+
+/// One invariant of a soak run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Invariant {
+    WeightsSumTo100,
+    NonNegativeBalances,
+    NoTradesWhileHalted,
+    TradeBudget,
+}
+
+/// An invariant found broken
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    pub invariant: Invariant,
+    pub detail: String,
+}
+
+impl Violation {
+    fn new(invariant: Invariant, detail: String) -> Self {
+        Self { invariant, detail }
+    }
+}
+
+/// A stretch the swarm was seen HALTED (wall-clock times)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Halt {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+}
+
+/// Wall-clock limits of an accelerated run
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub max_trades_per_hour: usize,
+    /// Wall-clock length of one simulated hour
+    pub hour: chrono::Duration,
+    /// How long legs sent just before a halt may take to fill and commit
+    pub in_flight: chrono::Duration,
+}
+
+impl Limits {
+    /// Limits of a swarm already `configure`d
+    pub fn new(config: &Config) -> Self {
+        // A stock and a bond leg, each filled in up to 1 + max_fill_retries attempts
+        let execution = &config.execution;
+        let fill_ms = (execution.latency_ms + execution.latency_jitter_ms) * 2 * (1 + execution.max_fill_retries as u64);
+        Self {
+            max_trades_per_hour: config.soak.max_trades_per_hour,
+            hour: chrono::Duration::milliseconds((3_600_000.0 / config.soak.speed) as i64),
+            in_flight: chrono::Duration::milliseconds(fill_ms as i64),
+        }
+    }
+}
+
+/// Weights that don't sum to 100%, or negative cash or holdings
+pub fn check_portfolio(portfolio: &PortfolioState) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let cash = portfolio.total_value - portfolio.stocks_value - portfolio.bonds_value;
+    for (name, value) in [("stocks", portfolio.stocks_value), ("bonds", portfolio.bonds_value), ("cash", cash)] {
+        if value < -TOLERANCE {
+            violations.push(Violation::new(Invariant::NonNegativeBalances, format!("{} is {}", name, value)));
+        }
+    }
+    if portfolio.total_value > Decimal::ZERO {
+        let weights = portfolio.stocks_pct + portfolio.bonds_pct + money::percent(cash, portfolio.total_value);
+        if (weights - Decimal::ONE_HUNDRED).abs() > TOLERANCE {
+            violations.push(Violation::new(
+                Invariant::WeightsSumTo100,
+                format!(
+                    "stocks {}% + bonds {}% + cash {}% = {}%",
+                    portfolio.stocks_pct.round_dp(4),
+                    portfolio.bonds_pct.round_dp(4),
+                    money::percent(cash, portfolio.total_value).round_dp(4),
+                    weights.round_dp(4)
+                ),
+            ));
+        }
+    }
+    violations
+}
+
+/// Executed trades inside a halt (past legs still in flight), and simulated
+/// hours holding more trades than the budget
+pub fn check_trades(trades: &[(DateTime<Utc>, &TradeLogEntry)], halts: &[Halt], limits: &Limits) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for (at, trade) in trades {
+        if let Some(halt) = halts.iter().find(|halt| *at > halt.since + limits.in_flight && *at <= halt.until) {
+            violations.push(Violation::new(
+                Invariant::NoTradesWhileHalted,
+                format!("trade {} ({}) at {} while HALTED since {}", trade.id, trade.action, at, halt.since),
+            ));
+        }
+    }
+
+    let mut times: Vec<DateTime<Utc>> = trades.iter().map(|(at, _)| *at).collect();
+    times.sort();
+    let mut first = 0;
+    for (last, at) in times.iter().enumerate() {
+        while *at - times[first] >= limits.hour {
+            first += 1;
+        }
+        let count = last - first + 1;
+        if count > limits.max_trades_per_hour {
+            violations.push(Violation::new(
+                Invariant::TradeBudget,
+                format!(
+                    "{} trades in the simulated hour to {} (budget {})",
+                    count, at, limits.max_trades_per_hour
+                ),
+            ));
+            break;
+        }
+    }
+    violations
+}
+
+/// Checks a running soak, remembering the halts it has seen
+#[derive(Debug)]
+pub struct Checker {
+    limits: Limits,
+    started_at: DateTime<Utc>,
+    halts: Vec<Halt>,
+}
+
+impl Checker {
+    pub fn new(limits: Limits, started_at: DateTime<Utc>) -> Self {
+        Self { limits, started_at, halts: Vec::new() }
+    }
+
+    /// Record the stored mode; a HALTED one opens or extends a halt
+    pub fn observe(&mut self, status: &SystemStatus) {
+        if status.mode != SystemMode::Halted {
+            return;
+        }
+        match self.halts.last_mut() {
+            Some(halt) if halt.since == status.since => halt.until = halt.until.max(status.updated_at),
+            _ => self.halts.push(Halt { since: status.since, until: status.updated_at }),
+        }
+    }
+
+    pub fn halts(&self) -> &[Halt] {
+        &self.halts
+    }
+
+    /// Trades of this run that were executed (not skipped), with their times
+    pub fn executed<'a>(&self, trades: &'a [TradeLogEntry]) -> Vec<(DateTime<Utc>, &'a TradeLogEntry)> {
+        trades
+            .iter()
+            .filter(|trade| trade.skipped.is_none())
+            .filter_map(|trade| {
+                let at = DateTime::parse_from_rfc3339(&trade.timestamp).ok()?.with_timezone(&Utc);
+                (at >= self.started_at).then_some((at, trade))
+            })
+            .collect()
+    }
+
+    /// Every invariant the portfolio and trade log break
+    pub fn check(&self, portfolio: Option<&PortfolioState>, trades: &[TradeLogEntry]) -> Vec<Violation> {
+        let mut violations = portfolio.map(check_portfolio).unwrap_or_default();
+        violations.extend(check_trades(&self.executed(trades), &self.halts, &self.limits));
+        violations
+    }
+}
+
+/// What `report_file` holds after a violation
+#[derive(Debug, Clone, Serialize)]
+pub struct SoakReport {
+    pub violations: Vec<Violation>,
+    pub seed: u64,
+    /// Command that runs the same soak again
+    pub replay: String,
+    pub swarm: String,
+    pub settings: SoakConfig,
+    pub started_at: DateTime<Utc>,
+    pub failed_at: DateTime<Utc>,
+    pub simulated_hours: f64,
+    pub checks: u64,
+    pub portfolio: Option<PortfolioState>,
+    pub status: Option<SystemStatus>,
+    pub halts: Vec<Halt>,
+    /// Trades of this run, newest first
+    pub trades: Vec<TradeLogEntry>,
+}
+
+/// Check the invariants until `[soak] hours` of simulated time have
+/// passed, engaging the kill switch on schedule; the report of the first
+/// violation, if any
+pub async fn run(board: Arc<Blackboard>, seed: u64, replay: String) -> Result<Option<SoakReport>> {
+    let config = board.config();
+    let soak = &config.soak;
+    let started_at = Utc::now();
+    let mut checker = Checker::new(Limits::new(config), started_at);
+    let mut ticker = interval(Duration::from_millis(soak.check_interval_ms.max(1)));
+    let mut checks = 0;
+    let mut halted = false;
+
+    // A previous run may have stopped with its kill switch engaged
+    board.set_kill_switch(None).await?;
+    info!("🧪 Soak: {} simulated hours at {}x (seed {})", soak.hours, soak.speed, seed);
+
+    let report = loop {
+        ticker.tick().await;
+        let now = Utc::now();
+        let simulated_hours = (now - started_at).num_milliseconds() as f64 / 1000.0 * soak.speed / 3600.0;
+        if simulated_hours >= soak.hours {
+            break None;
+        }
+
+        // Engaged for the last halt_minutes of every halt_every_hours
+        let halt = soak.halt_every_hours > 0.0
+            && simulated_hours % soak.halt_every_hours >= soak.halt_every_hours - soak.halt_minutes / 60.0;
+        if halt != halted {
+            let switch = halt.then(|| KillSwitch {
+                reason: "soak test halt".to_string(),
+                engaged_by: "soak".to_string(),
+                engaged_at: now,
+            });
+            board.set_kill_switch(switch.as_ref()).await?;
+            mode::refresh(&board).await;
+            info!("🧪 Soak: kill switch {} at {:.2} simulated hours", if halt { "engaged" } else { "released" }, simulated_hours);
+            halted = halt;
+        }
+
+        let status = board.get_system_status().await?;
+        if let Some(status) = &status {
+            checker.observe(status);
+        }
+        let portfolio = board.get_portfolio_state().await?;
+        let trades = board.get_trade_history(config.trade_log.max_entries).await?;
+        checks += 1;
+        let violations = checker.check(portfolio.as_ref(), &trades);
+        if !violations.is_empty() {
+            let executed: Vec<TradeLogEntry> =
+                checker.executed(&trades).into_iter().map(|(_, trade)| trade.clone()).collect();
+            break Some(SoakReport {
+                violations,
+                seed,
+                replay,
+                swarm: board.swarm_name().to_string(),
+                settings: soak.clone(),
+                started_at,
+                failed_at: now,
+                simulated_hours,
+                checks,
+                portfolio,
+                status,
+                halts: checker.halts().to_vec(),
+                trades: executed,
+            });
+        }
+    };
+
+    if halted {
+        board.set_kill_switch(None).await?;
+        mode::refresh(&board).await;
+    }
+    match &report {
+        Some(report) => {
+            for violation in &report.violations {
+                warn!("🧪 Soak: {:?} violated: {}", violation.invariant, violation.detail);
+            }
+        }
+        None => info!("🧪 Soak: {} simulated hours, {} checks, no violations", soak.hours, checks),
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(id: &str, at: DateTime<Utc>) -> TradeLogEntry {
+        TradeLogEntry {
+            id: id.to_string(),
+            timestamp: at.to_rfc3339(),
+            action: "SELL stocks".to_string(),
+            symbol: "SPY".to_string(),
+            amount: Decimal::ONE_HUNDRED,
+            price: Decimal::ONE_HUNDRED,
+            portfolio_value: Decimal::ONE_HUNDRED,
+            drift_before: Decimal::ZERO,
+            drift_after: Decimal::ZERO,
+            explanation: None,
+            stocks_delta: -Decimal::ONE_HUNDRED,
+            bonds_delta: Decimal::ONE_HUNDRED,
+            run_id: None,
+            skipped: None,
+            plan_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_invariants() {
+        // Weights and balances
+        let sound = PortfolioState::from_values(Decimal::new(6000, 0), Decimal::new(4000, 0), None);
+        assert!(check_portfolio(&sound).is_empty());
+        let mut stale = sound.clone();
+        stale.stocks_pct = Decimal::new(70, 0);
+        assert_eq!(check_portfolio(&stale)[0].invariant, Invariant::WeightsSumTo100);
+        let overdrawn = PortfolioState { total_value: Decimal::new(9000, 0), ..sound.clone() };
+        assert!(check_portfolio(&overdrawn).iter().any(|v| v.invariant == Invariant::NonNegativeBalances));
+
+        // Trades inside a halt, past legs still in flight
+        let start = Utc::now();
+        let secs = chrono::Duration::seconds;
+        let limits = Limits { max_trades_per_hour: 2, hour: secs(360), in_flight: secs(2) };
+        let mut checker = Checker::new(limits, start);
+        let status = |since, updated_at| SystemStatus { mode: SystemMode::Halted, reasons: vec![], since, updated_at };
+        checker.observe(&status(start + secs(10), start + secs(11)));
+        checker.observe(&status(start + secs(10), start + secs(20)));
+        assert_eq!(checker.halts().len(), 1);
+        let in_flight = [trade("a", start + secs(11))];
+        assert!(checker.check(Some(&sound), &in_flight).is_empty());
+        let halted = [trade("b", start + secs(15))];
+        assert_eq!(checker.check(Some(&sound), &halted)[0].invariant, Invariant::NoTradesWhileHalted);
+        let mut skipped = trade("c", start + secs(15));
+        skipped.skipped = Some("blackout".to_string());
+        assert!(checker.check(Some(&sound), &[skipped]).is_empty());
+
+        // Budget of 2 trades per simulated hour (360s of wall clock)
+        let spread = [trade("d", start + secs(30)), trade("e", start + secs(200)), trade("f", start + secs(400))];
+        assert!(checker.check(None, &spread).is_empty());
+        let burst = [trade("g", start + secs(30)), trade("h", start + secs(200)), trade("i", start + secs(300))];
+        assert_eq!(checker.check(None, &burst)[0].invariant, Invariant::TradeBudget);
+        // Trades before the run are not counted
+        let earlier = [trade("j", start - secs(100)), trade("k", start - secs(50)), trade("l", start + secs(30))];
+        assert!(checker.check(None, &earlier).is_empty());
+    }
+}
@@ -0,0 +1,371 @@
+//! Daily Snapshots and Monthly Statements
+//!
+//! A scheduled job that records one immutable snapshot per day of
+//! portfolio values, allocation and drift (`snapshots:daily:<date>`), and
+//! after each month ends summarises that month's snapshots and trades
+//! into a statement stored as JSON and rendered text (`statements:<month>`).
+//! Each snapshot is followed by the day's summary (see `daily_summary`).
+
+use anyhow::Result;
+use chrono::{Datelike, Months, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{debug, info, warn};
+
+use crate::core::blackboard::{PortfolioState, TargetAllocation, TradeLogEntry};
+use crate::core::{bootstrap, clock, daily_summary};
+use crate::core::money::{self, MoneyFormat, ReportLocale};
+use crate::core::Blackboard;
+use crate::export::{self, DateRange};
+
+/// End-of-day record of the portfolio; written once and never updated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailySnapshot {
+    pub date: NaiveDate,
+    pub taken_at: String,
+    pub total_value: f64,
+    pub stocks_value: f64,
+    pub bonds_value: f64,
+    pub stocks_pct: f64,
+    pub bonds_pct: f64,
+    pub target_stocks_pct: f64,
+    pub target_bonds_pct: f64,
+    /// Stock allocation minus target (percentage points)
+    pub drift_pct: f64,
+    pub drawdown_pct: f64,
+}
+
+impl DailySnapshot {
+    pub fn new(date: NaiveDate, portfolio: &PortfolioState, target: &TargetAllocation, drawdown_pct: f64) -> Self {
+        Self {
+            date,
+            taken_at: Utc::now().to_rfc3339(),
+            total_value: money::to_f64(portfolio.total_value),
+            stocks_value: money::to_f64(portfolio.stocks_value),
+            bonds_value: money::to_f64(portfolio.bonds_value),
+            stocks_pct: money::to_f64(portfolio.stocks_pct),
+            bonds_pct: money::to_f64(portfolio.bonds_pct),
+            target_stocks_pct: target.stocks_pct,
+            target_bonds_pct: target.bonds_pct,
+            drift_pct: money::to_f64(portfolio.stocks_pct) - target.stocks_pct,
+            drawdown_pct,
+        }
+    }
+}
+
+/// Month-end summary built from daily snapshots and the trade log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonthlyStatement {
+    /// `YYYY-MM`
+    pub month: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub snapshot_count: usize,
+    pub opening_value: f64,
+    pub closing_value: f64,
+    pub change: f64,
+    pub return_pct: f64,
+    pub high_value: f64,
+    pub low_value: f64,
+    pub max_drawdown_pct: f64,
+    pub closing_stocks_pct: f64,
+    pub closing_bonds_pct: f64,
+    pub target_stocks_pct: f64,
+    pub target_bonds_pct: f64,
+    /// Largest absolute end-of-day drift (percentage points)
+    pub max_drift_pct: f64,
+    pub trade_count: usize,
+    /// Sum of traded notional
+    pub trade_volume: f64,
+    /// Base currency of all values
+    #[serde(default = "default_currency")]
+    pub currency: String,
+    pub generated_at: String,
+}
+
+/// Statements stored before the currency was recorded were in USD
+fn default_currency() -> String {
+    "USD".to_string()
+}
+
+impl MonthlyStatement {
+    /// Summarise a month. `opening` is the last snapshot before the month,
+    /// if any; otherwise the month's first snapshot opens it. Returns
+    /// `None` when the month has no snapshots.
+    pub fn build(
+        month_start: NaiveDate,
+        opening: Option<&DailySnapshot>,
+        snapshots: &[DailySnapshot],
+        trades: &[TradeLogEntry],
+        currency: &str,
+    ) -> Option<Self> {
+        let first = snapshots.first()?;
+        let last = snapshots.last()?;
+        let opening_value = opening.unwrap_or(first).total_value;
+        let change = last.total_value - opening_value;
+        let values = || snapshots.iter().map(|s| s.total_value);
+        
+        Some(Self {
+            month: month_id(month_start),
+            period_start: month_start,
+            period_end: month_end(month_start),
+            snapshot_count: snapshots.len(),
+            opening_value,
+            closing_value: last.total_value,
+            change,
+            return_pct: if opening_value > 0.0 { change / opening_value * 100.0 } else { 0.0 },
+            high_value: values().fold(f64::MIN, f64::max),
+            low_value: values().fold(f64::MAX, f64::min),
+            max_drawdown_pct: snapshots.iter().map(|s| s.drawdown_pct).fold(0.0, f64::max),
+            closing_stocks_pct: last.stocks_pct,
+            closing_bonds_pct: last.bonds_pct,
+            target_stocks_pct: last.target_stocks_pct,
+            target_bonds_pct: last.target_bonds_pct,
+            max_drift_pct: snapshots.iter().map(|s| s.drift_pct.abs()).fold(0.0, f64::max),
+            trade_count: trades.len(),
+            trade_volume: money::to_f64(money::sum(trades.iter().map(|t| t.amount), currency)),
+            currency: currency.to_string(),
+            generated_at: Utc::now().to_rfc3339(),
+        })
+    }
+    
+    /// Plain-text rendering of the statement, amounts formatted for `locale`
+    pub fn render_text(&self, locale: ReportLocale) -> String {
+        let money = MoneyFormat::new(&self.currency, locale);
+        let amount = |v: f64| money.format(money::from_f64(v));
+        let sign = |v: f64| if v < 0.0 { "-" } else { "+" };
+        let title = month_start_from_id(&self.month)
+            .map(|d| d.format("%B %Y").to_string())
+            .unwrap_or_else(|| self.month.clone());
+        
+        let lines = [
+            format!("DriftGuard Monthly Statement - {}", title),
+            format!(
+                "Period: {} to {} ({} daily snapshots)",
+                self.period_start, self.period_end, self.snapshot_count
+            ),
+            String::new(),
+            format!("{:<22}{}", "Opening value", amount(self.opening_value)),
+            format!("{:<22}{}", "Closing value", amount(self.closing_value)),
+            format!(
+                "{:<22}{}{} ({}{:.2}%)",
+                "Change",
+                sign(self.change),
+                amount(self.change.abs()),
+                sign(self.return_pct),
+                self.return_pct.abs()
+            ),
+            format!("{:<22}{} / {}", "High / Low", amount(self.high_value), amount(self.low_value)),
+            format!("{:<22}{:.2}%", "Max drawdown", self.max_drawdown_pct),
+            String::new(),
+            format!(
+                "{:<22}{:.1}% stocks / {:.1}% bonds (target {:.1}% / {:.1}%)",
+                "Closing allocation",
+                self.closing_stocks_pct,
+                self.closing_bonds_pct,
+                self.target_stocks_pct,
+                self.target_bonds_pct
+            ),
+            format!("{:<22}{:.2} pts", "Max end-of-day drift", self.max_drift_pct),
+            String::new(),
+            format!("{:<22}{} ({} traded)", "Trades", self.trade_count, amount(self.trade_volume)),
+            String::new(),
+            format!("Generated {}", self.generated_at),
+            String::new(),
+        ];
+        lines.join("\n")
+    }
+}
+
+pub fn month_id(date: NaiveDate) -> String {
+    date.format("%Y-%m").to_string()
+}
+
+/// Parse a `YYYY-MM` month id into the month's first day
+pub fn month_start_from_id(month: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").ok()
+}
+
+fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn month_end(month_start: NaiveDate) -> NaiveDate {
+    month_start
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(month_start)
+}
+
+/// Months whose statement can be generated on `today`: the previous month,
+/// and the current one once its last day has been snapshotted
+pub fn months_due(today: NaiveDate) -> Vec<NaiveDate> {
+    let current = month_start(today);
+    let mut due: Vec<NaiveDate> = current.checked_sub_months(Months::new(1)).into_iter().collect();
+    if today == month_end(current) {
+        due.push(current);
+    }
+    due
+}
+
+/// Every day from `from` to `to`, inclusive
+pub fn days(from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    from.iter_days().take_while(|d| *d <= to).collect()
+}
+
+/// Record today's snapshot (no-op if one already exists)
+async fn take_snapshot(board: &Blackboard, date: NaiveDate) -> Result<()> {
+    let Some(portfolio) = board.get_portfolio_state().await? else {
+        return Ok(());
+    };
+    let target = board.get_target_allocation().await?;
+    let drawdown_pct = board.get_drawdown().await?.map_or(0.0, |d| d.drawdown_pct);
+    
+    let snapshot = DailySnapshot::new(date, &portfolio, &target, drawdown_pct);
+    if board.put_daily_snapshot(&snapshot).await? {
+        info!(
+            "📸 Daily snapshot {}: ${:.2} ({:.1}% stocks, drift {:+.2} pts)",
+            date, snapshot.total_value, snapshot.stocks_pct, snapshot.drift_pct
+        );
+    } else {
+        debug!("Statements: Snapshot for {} already exists", date);
+    }
+    Ok(())
+}
+
+/// Build and store a month's statement unless it already exists
+async fn generate_statement(board: &Blackboard, month_start: NaiveDate) -> Result<()> {
+    let month = month_id(month_start);
+    if board.get_statement(&month).await?.is_some() {
+        return Ok(());
+    }
+    
+    let end = month_end(month_start);
+    let snapshots = board.get_daily_snapshots(&days(month_start, end)).await?;
+    let opening = match month_start.pred_opt() {
+        Some(day) => board.get_daily_snapshots(&[day]).await?.pop(),
+        None => None,
+    };
+    let trades = export::collect_trades(board, DateRange { from: Some(month_start), to: Some(end) }).await?;
+    
+    let currency = bootstrap::current(board).await?.base_currency;
+    let Some(statement) = MonthlyStatement::build(month_start, opening.as_ref(), &snapshots, &trades, &currency) else {
+        debug!("Statements: No snapshots for {}, skipping statement", month);
+        return Ok(());
+    };
+    let locale = board.config().reporting.locale;
+    if board.put_statement(&statement, &statement.render_text(locale)).await? {
+        let money = MoneyFormat::new(&currency, locale);
+        info!(
+            "🧾 Statement {}: {} -> {} ({:+.2}%), {} trades",
+            month,
+            money.format(money::from_f64(statement.opening_value)),
+            money.format(money::from_f64(statement.closing_value)),
+            statement.return_pct,
+            statement.trade_count
+        );
+    }
+    Ok(())
+}
+
+/// Daily snapshot / month-end statement scheduler
+pub async fn run_statements(board: Arc<Blackboard>) -> Result<()> {
+    let settings = &board.config().statements;
+    let tz = board.config().exchange.timezone;
+    let at = NaiveTime::parse_from_str(&settings.snapshot_at, "%H:%M").unwrap_or_else(|_| {
+        warn!("Statements: Invalid snapshot_at '{}', using 21:30", settings.snapshot_at);
+        NaiveTime::from_hms_opt(21, 30, 0).unwrap_or_default()
+    });
+    let mut ticker = interval(Duration::from_secs(settings.check_interval_secs.max(1)));
+    let mut last_run: Option<NaiveDate> = None;
+    
+    info!("🧾 Statement scheduler started (daily snapshot at {} {})", at.format("%H:%M"), tz);
+    
+    loop {
+        ticker.tick().await;
+        
+        let now = Utc::now();
+        let today = clock::today(now, tz);
+        if last_run == Some(today) || clock::time_of_day(now, tz) < at {
+            continue;
+        }
+        
+        take_snapshot(&board, today).await?;
+        if settings.daily_summary {
+            if let Err(e) = daily_summary::consolidate(&board, today).await {
+                warn!("Statements: Failed to consolidate {}: {:#}", today, e);
+            }
+        }
+        for month_start in months_due(today) {
+            if let Err(e) = generate_statement(&board, month_start).await {
+                warn!("Statements: Failed to generate {}: {}", month_id(month_start), e);
+            }
+        }
+        last_run = Some(today);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+    
+    fn snapshot(day: NaiveDate, total_value: f64, drift_pct: f64, drawdown_pct: f64) -> DailySnapshot {
+        DailySnapshot {
+            date: day,
+            taken_at: String::new(),
+            total_value,
+            stocks_value: total_value * 0.6,
+            bonds_value: total_value * 0.4,
+            stocks_pct: 60.0 + drift_pct,
+            bonds_pct: 40.0 - drift_pct,
+            target_stocks_pct: 60.0,
+            target_bonds_pct: 40.0,
+            drift_pct,
+            drawdown_pct,
+        }
+    }
+    
+    #[test]
+    fn test_months_due() {
+        assert_eq!(months_due(date(2026, 3, 15)), vec![date(2026, 2, 1)]);
+        assert_eq!(months_due(date(2026, 3, 31)), vec![date(2026, 2, 1), date(2026, 3, 1)]);
+        assert_eq!(months_due(date(2026, 1, 5)), vec![date(2025, 12, 1)]);
+    }
+    
+    #[test]
+    fn test_build_statement() {
+        let opening = snapshot(date(2026, 2, 28), 100_000.0, 0.0, 0.0);
+        let snapshots = vec![
+            snapshot(date(2026, 3, 2), 102_000.0, 3.0, 0.0),
+            snapshot(date(2026, 3, 16), 98_000.0, -4.5, 3.9),
+            snapshot(date(2026, 3, 31), 101_000.0, 1.0, 1.0),
+        ];
+        let statement = MonthlyStatement::build(date(2026, 3, 1), Some(&opening), &snapshots, &[], "USD").unwrap();
+        
+        assert_eq!(statement.month, "2026-03");
+        assert_eq!(statement.period_end, date(2026, 3, 31));
+        assert_eq!(statement.change, 1_000.0);
+        assert!((statement.return_pct - 1.0).abs() < 1e-9);
+        assert_eq!(statement.high_value, 102_000.0);
+        assert_eq!(statement.low_value, 98_000.0);
+        assert_eq!(statement.max_drift_pct, 4.5);
+        assert_eq!(statement.max_drawdown_pct, 3.9);
+        
+        let text = statement.render_text(ReportLocale::EN_US);
+        assert!(text.starts_with("DriftGuard Monthly Statement - March 2026"));
+        assert!(text.contains("+$1,000.00 (+1.00%)"));
+        
+        let text = statement.render_text(ReportLocale::parse("de-DE").unwrap());
+        assert!(text.contains("+1.000,00 $ (+1.00%)"));
+    }
+    
+    #[test]
+    fn test_no_snapshots_no_statement() {
+        assert!(MonthlyStatement::build(date(2026, 3, 1), None, &[], &[], "USD").is_none());
+    }
+}
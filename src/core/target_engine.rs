@@ -0,0 +1,195 @@
+//! Target Weight Engine
+//!
+//! Recomputes the stocks/bonds target from recent volatilities and their
+//! correlation, then writes it as the target allocation for the normal
+//! drift pipeline to maintain. Returns come from the Sensor's published
+//! market snapshots, sampled into a rolling window.
+//!
+//! With two assets, equal risk contribution (risk parity) reduces to
+//! inverse-volatility weighting; the correlation only matters for the
+//! minimum-variance method.
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{debug, info, warn};
+
+use crate::core::allocation;
+use crate::core::Blackboard;
+
+/// How target weights are derived from risk estimates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightingMethod {
+    RiskParity,
+    InverseVolatility,
+    MinVariance,
+}
+
+/// Per-sample return volatilities and correlation of stocks vs bonds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskEstimate {
+    pub stocks_vol: f64,
+    pub bonds_vol: f64,
+    pub correlation: f64,
+}
+
+impl RiskEstimate {
+    /// Estimate from a series of (stocks, bonds) prices; needs at least
+    /// three prices and non-zero volatility on both sides
+    pub fn from_prices(prices: &[(f64, f64)]) -> Option<Self> {
+        let returns: Vec<(f64, f64)> = prices
+            .windows(2)
+            .filter(|w| w[0].0 > 0.0 && w[0].1 > 0.0)
+            .map(|w| (w[1].0 / w[0].0 - 1.0, w[1].1 / w[0].1 - 1.0))
+            .collect();
+        if returns.len() < 2 {
+            return None;
+        }
+        
+        let n = returns.len() as f64;
+        let mean_s = returns.iter().map(|r| r.0).sum::<f64>() / n;
+        let mean_b = returns.iter().map(|r| r.1).sum::<f64>() / n;
+        let (mut var_s, mut var_b, mut cov) = (0.0, 0.0, 0.0);
+        for (s, b) in &returns {
+            var_s += (s - mean_s).powi(2);
+            var_b += (b - mean_b).powi(2);
+            cov += (s - mean_s) * (b - mean_b);
+        }
+        
+        let stocks_vol = (var_s / (n - 1.0)).sqrt();
+        let bonds_vol = (var_b / (n - 1.0)).sqrt();
+        if stocks_vol <= 0.0 || bonds_vol <= 0.0 {
+            return None;
+        }
+        let correlation = (cov / (n - 1.0) / (stocks_vol * bonds_vol)).clamp(-1.0, 1.0);
+        
+        Some(Self { stocks_vol, bonds_vol, correlation })
+    }
+}
+
+/// Stocks weight (%) implied by the risk estimate
+pub fn stocks_weight_pct(method: WeightingMethod, risk: &RiskEstimate) -> f64 {
+    let (vs, vb, rho) = (risk.stocks_vol, risk.bonds_vol, risk.correlation);
+    let weight = match method {
+        WeightingMethod::RiskParity | WeightingMethod::InverseVolatility => {
+            (1.0 / vs) / (1.0 / vs + 1.0 / vb)
+        }
+        WeightingMethod::MinVariance => {
+            let denominator = vs * vs + vb * vb - 2.0 * rho * vs * vb;
+            if denominator > 0.0 {
+                (vb * vb - rho * vs * vb) / denominator
+            } else {
+                0.5
+            }
+        }
+    };
+    weight.clamp(0.0, 1.0) * 100.0
+}
+
+/// Sample market snapshots and periodically rewrite the target allocation
+pub async fn run_engine(board: Arc<Blackboard>) -> Result<()> {
+    let config = board.config();
+    let engine = &config.target_engine;
+    let (stocks_symbol, bonds_symbol) = (&config.portfolio.stocks_symbol, &config.portfolio.bonds_symbol);
+    
+    let mut sampler = interval(Duration::from_millis(config.market.poll_interval_ms.max(1000)));
+    let mut recompute = interval(Duration::from_secs(engine.recompute_interval_secs.max(1)));
+    let mut window: VecDeque<(f64, f64)> = VecDeque::with_capacity(engine.lookback_samples);
+    let mut last_timestamp: Option<String> = None;
+    
+    info!(
+        "🧮 Target engine started ({:?}, {} sample lookback, recompute every {}s)",
+        engine.method, engine.lookback_samples, engine.recompute_interval_secs
+    );
+    
+    loop {
+        tokio::select! {
+            _ = sampler.tick() => {
+                let Ok(Some(update)) = board.get_market_update().await else { continue };
+                if last_timestamp.as_deref() == Some(update.timestamp.as_str()) {
+                    continue;
+                }
+                let price = |symbol: &str| update.quotes.iter().find(|q| q.symbol == symbol).map(|q| q.price);
+                if let (Some(stocks), Some(bonds)) = (price(stocks_symbol), price(bonds_symbol)) {
+                    if window.len() == engine.lookback_samples.max(3) {
+                        window.pop_front();
+                    }
+                    window.push_back((stocks, bonds));
+                }
+                last_timestamp = Some(update.timestamp);
+            }
+            
+            _ = recompute.tick() => {
+                if window.len() < engine.min_samples.max(3) {
+                    debug!("Target engine: {} of {} samples collected", window.len(), engine.min_samples);
+                    continue;
+                }
+                let prices: Vec<(f64, f64)> = window.iter().copied().collect();
+                let Some(risk) = RiskEstimate::from_prices(&prices) else {
+                    debug!("Target engine: Not enough price variation to estimate risk");
+                    continue;
+                };
+                
+                // Round to basis points and keep inside the allocation bounds
+                // and the stocks and bonds assets' own limits
+                let bounds = &config.allocation;
+                let (asset_lower, asset_upper) = allocation::stocks_limits(&config.portfolio);
+                let lower = bounds.min_asset_pct.max(100.0 - bounds.max_asset_pct).max(asset_lower);
+                let upper = bounds.max_asset_pct.min(100.0 - bounds.min_asset_pct).min(asset_upper);
+                let stocks_pct = (stocks_weight_pct(engine.method, &risk) * 100.0).round() / 100.0;
+                let stocks_pct = if lower <= upper { stocks_pct.clamp(lower, upper) } else { stocks_pct };
+                let target = match allocation::validate(stocks_pct, 100.0 - stocks_pct, bounds, &config.portfolio) {
+                    Ok(target) => target,
+                    Err(e) => {
+                        warn!("Target engine: Computed allocation rejected: {}", e);
+                        continue;
+                    }
+                };
+                
+                match board.set_target_allocation(target.stocks_pct, target.bonds_pct).await {
+                    Ok(()) => info!(
+                        "🧮 Target engine: σ stocks {:.3}%, σ bonds {:.3}%, ρ {:.2} → target {:.2}% / {:.2}%",
+                        risk.stocks_vol * 100.0,
+                        risk.bonds_vol * 100.0,
+                        risk.correlation,
+                        target.stocks_pct,
+                        target.bonds_pct
+                    ),
+                    Err(e) => warn!("Target engine: Failed to update target allocation: {}", e),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_risk_based_weights() {
+        let risk = RiskEstimate { stocks_vol: 0.15, bonds_vol: 0.05, correlation: 0.0 };
+        
+        assert!((stocks_weight_pct(WeightingMethod::InverseVolatility, &risk) - 25.0).abs() < 1e-9);
+        assert_eq!(
+            stocks_weight_pct(WeightingMethod::RiskParity, &risk),
+            stocks_weight_pct(WeightingMethod::InverseVolatility, &risk)
+        );
+        // Uncorrelated min-variance weights are proportional to 1/σ²
+        assert!((stocks_weight_pct(WeightingMethod::MinVariance, &risk) - 10.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_risk_estimate_from_prices() {
+        // Stocks swing ±2%, bonds ±1%, moving together
+        let prices = [(100.0, 50.0), (102.0, 50.5), (99.96, 49.995), (101.96, 50.49)];
+        let risk = RiskEstimate::from_prices(&prices).unwrap();
+        
+        assert!((risk.stocks_vol / risk.bonds_vol - 2.0).abs() < 0.05);
+        assert!(risk.correlation > 0.99);
+        assert_eq!(RiskEstimate::from_prices(&prices[..2]), None);
+    }
+}
@@ -0,0 +1,201 @@
+//! Decision Timelines
+//!
+//! A trade is the end of a chain that crosses every agent: the Sensor's
+//! snapshot, the Analyst's drift analysis, the Guardian's permit, and the
+//! Trader's execution and fills. The permit carries the analysis and the
+//! analysis its snapshot, so once a trade commits the Trader has the whole
+//! chain in hand; it stores it under the trade's ID (also the ID of its
+//! trade log entry) for `GET /api/trades/<id>/timeline`. Timelines expire
+//! with the trade log when `[retention] trade_log_max_age_days` is set.
+
+use serde::{Deserialize, Serialize};
+
+use crate::agents::guardian::ExecutionPermit;
+use crate::agents::trader::TradeRecord;
+use crate::execution::{Fill, Side};
+
+/// Link in a trade's causal chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineStage {
+    SensorSnapshot,
+    DriftAnalysis,
+    GuardianDecision,
+    Execution,
+    Fill,
+}
+
+/// One step of the chain, summarized for display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineStep {
+    pub stage: TimelineStage,
+    pub agent: String,
+    /// None where the stage wasn't timestamped (fills, older analyses)
+    pub timestamp: Option<String>,
+    pub summary: String,
+}
+
+/// Everything that led to one trade, in causal order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeTimeline {
+    /// Correlation ID: the trade's ID and its trade log entry's
+    pub trade_id: String,
+    pub run_id: Option<String>,
+    pub steps: Vec<TimelineStep>,
+    /// The Guardian's permit, with the analysis and snapshot behind it
+    pub permit: ExecutionPermit,
+    pub trade: TradeRecord,
+    pub fills: Vec<Fill>,
+}
+
+impl TradeTimeline {
+    pub fn assemble(permit: &ExecutionPermit, trade: &TradeRecord, fills: &[Fill], run_id: Option<String>) -> Self {
+        let analysis = &permit.drift_analysis;
+        let snapshot = &analysis.market_snapshot;
+        let step = |stage, agent: &str, timestamp: Option<&String>, summary: String| TimelineStep {
+            stage,
+            agent: agent.to_string(),
+            timestamp: timestamp.cloned(),
+            summary,
+        };
+        
+        let quotes: Vec<String> = snapshot
+            .quotes
+            .iter()
+            .map(|q| format!("{} {:.2}", q.symbol, q.price))
+            .collect();
+        let mut steps = vec![
+            step(
+                TimelineStage::SensorSnapshot,
+                "Sensor",
+                Some(&snapshot.timestamp),
+                if quotes.is_empty() { "no quotes".to_string() } else { quotes.join(", ") },
+            ),
+            step(
+                TimelineStage::DriftAnalysis,
+                "Analyst",
+                analysis.analyzed_at.as_ref(),
+                format!(
+                    "{:.1}% drift (stocks {:.1}% vs target {:.1}%): {}",
+                    analysis.drift_pct, analysis.current_stocks_pct, analysis.target_stocks_pct, analysis.recommended_action
+                ),
+            ),
+            step(
+                TimelineStage::GuardianDecision,
+                "Guardian",
+                Some(&permit.timestamp),
+                format!(
+                    "Permitted at {} {:.1} ({}), data quality {:.2}",
+                    permit.volatility_signal.label(),
+                    permit.vix_value,
+                    permit.volatility_status,
+                    permit.data_quality
+                ),
+            ),
+            step(TimelineStage::Execution, "Trader", Some(&trade.timestamp), trade.action.clone()),
+        ];
+        steps.extend(fills.iter().map(|fill| {
+            let side = match fill.leg.side {
+                Side::Buy => "BUY",
+                Side::Sell => "SELL",
+            };
+            let summary = if fill.filled_notional == fill.leg.notional {
+                format!("{} {} filled {:.2}", side, fill.leg.symbol, fill.filled_notional)
+            } else {
+                format!(
+                    "{} {} filled {:.2} of {:.2}",
+                    side, fill.leg.symbol, fill.filled_notional, fill.leg.notional
+                )
+            };
+            step(TimelineStage::Fill, "Trader", None, summary)
+        }));
+        
+        Self {
+            trade_id: trade.trade_id.clone(),
+            run_id,
+            steps,
+            permit: permit.clone(),
+            trade: trade.clone(),
+            fills: fills.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::analyst::{DriftAnalysis, DriftExplanation};
+    use crate::agents::sensor::{AssetQuote, MarketSnapshot};
+    use crate::core::blackboard::PortfolioState;
+    use crate::execution::OrderLeg;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_assemble() {
+        let permit = ExecutionPermit {
+            vix_value: 14.2,
+            volatility_signal: Default::default(),
+            volatility_status: "LOW".to_string(),
+            data_quality: 0.98,
+            drift_analysis: DriftAnalysis {
+                current_stocks_pct: 66.0,
+                current_bonds_pct: 34.0,
+                target_stocks_pct: 60.0,
+                target_bonds_pct: 40.0,
+                drift_pct: 6.0,
+                recommended_action: "SELL stocks, BUY bonds".to_string(),
+                market_snapshot: MarketSnapshot::new(vec![AssetQuote::new("SPY", 512.25)]),
+                explanation: DriftExplanation::default(),
+                opportunity: None,
+                cost_benefit: None,
+                trigger: Default::default(),
+                script_score: None,
+                analyzed_at: None,
+            },
+            legs: Vec::new(),
+            target: None,
+            plan_hash: None,
+            timestamp: "2026-03-02T15:00:00Z".to_string(),
+        };
+        let trade = TradeRecord {
+            trade_id: "t1".to_string(),
+            action: "SELL $6,000.00 stocks, BUY $6,000.00 bonds".to_string(),
+            stocks_delta: Decimal::from(-6_000),
+            bonds_delta: Decimal::from(6_000),
+            before_state: PortfolioState::default(),
+            after_state: PortfolioState::default(),
+            vix_at_execution: 14.2,
+            volatility_signal: Default::default(),
+            explanation: DriftExplanation::default(),
+            timestamp: "2026-03-02T15:00:01Z".to_string(),
+        };
+        let fill = |symbol: &str, side, notional: i64, filled: i64| Fill {
+            leg: OrderLeg { symbol: symbol.to_string(), side, notional: Decimal::from(notional) },
+            filled_notional: Decimal::from(filled),
+        };
+        let fills = [fill("SPY", Side::Sell, 6_000, 6_000), fill("BND", Side::Buy, 6_000, 5_500)];
+        
+        let timeline = TradeTimeline::assemble(&permit, &trade, &fills, Some("run-1".to_string()));
+        assert_eq!(timeline.trade_id, "t1");
+        let stages: Vec<TimelineStage> = timeline.steps.iter().map(|s| s.stage).collect();
+        assert_eq!(
+            stages,
+            [
+                TimelineStage::SensorSnapshot,
+                TimelineStage::DriftAnalysis,
+                TimelineStage::GuardianDecision,
+                TimelineStage::Execution,
+                TimelineStage::Fill,
+                TimelineStage::Fill,
+            ]
+        );
+        assert_eq!(timeline.steps[0].summary, "SPY 512.25");
+        assert_eq!(timeline.steps[1].timestamp, None);
+        assert_eq!(timeline.steps[2].summary, "Permitted at VIX 14.2 (LOW), data quality 0.98");
+        assert_eq!(timeline.steps[4].summary, "SELL SPY filled 6000.00");
+        assert_eq!(timeline.steps[5].summary, "BUY BND filled 5500.00 of 6000.00");
+        
+        let json = serde_json::to_value(&timeline).unwrap();
+        assert_eq!(json["steps"][2]["stage"], "guardian_decision");
+    }
+}
@@ -0,0 +1,176 @@
+//! Pheromone Trails
+//!
+//! A pheromone key only holds the latest deposit, and the dashboard's
+//! event stream only what it saw while connected. Each deposit is also
+//! appended to a capped trail (`history:pheromones`, `[dashboard]
+//! trail_max_entries`). Decay is deterministic, so the trail is enough to
+//! reconstruct any pheromone's intensity at any moment it covers, and
+//! `GET /api/pheromones/heatmap` serves it pre-bucketed: one row per
+//! pheromone type, one cell per time bucket holding the peak intensity
+//! reached in it.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::physics::{curve, Pheromone, PheromoneType};
+use crate::core::Config;
+
+/// One recorded deposit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrailEntry {
+    pub timestamp: DateTime<Utc>,
+    /// Pheromone type label
+    pub pheromone_type: String,
+    /// Initial intensity of the deposit
+    pub intensity: f64,
+    pub decay_rate: f64,
+}
+
+impl TrailEntry {
+    pub fn new(pheromone_type: PheromoneType, pheromone: &Pheromone) -> Self {
+        Self {
+            timestamp: pheromone.created_at,
+            pheromone_type: pheromone_type.label().to_string(),
+            intensity: pheromone.initial_intensity,
+            decay_rate: pheromone.decay_rate,
+        }
+    }
+
+    /// Intensity of this deposit at `at`, had nothing replaced it
+    pub fn intensity_at(&self, at: DateTime<Utc>) -> f64 {
+        let elapsed = curve::elapsed_secs(self.timestamp.timestamp_millis(), at.timestamp_millis());
+        curve::intensity_at(self.intensity, self.decay_rate, elapsed)
+    }
+}
+
+/// One pheromone type's row of the heatmap
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatmapRow {
+    pub pheromone_type: String,
+    pub threshold: f64,
+    /// Peak intensity per bucket, oldest first
+    pub intensities: Vec<f64>,
+    /// Deposits per bucket
+    pub deposits: Vec<u32>,
+}
+
+/// Pheromone intensity by type and time bucket over a trailing window
+#[derive(Debug, Clone, Serialize)]
+pub struct PheromoneHeatmap {
+    pub window_secs: u64,
+    pub bucket_secs: f64,
+    /// Start of each bucket, oldest first
+    pub bucket_starts: Vec<DateTime<Utc>>,
+    pub rows: Vec<HeatmapRow>,
+}
+
+/// Bucket a trail (in any order) into a heatmap of the `window` ending at
+/// `now`. Intensity before the oldest recorded deposit of a type reads 0.
+pub fn heatmap(
+    trail: &[TrailEntry],
+    types: &[PheromoneType],
+    config: &Config,
+    now: DateTime<Utc>,
+    window: Duration,
+    buckets: usize,
+) -> PheromoneHeatmap {
+    let start = now - window;
+    let buckets = buckets.max(1);
+    let bucket_ms = (window.num_milliseconds() / buckets as i64).max(1);
+    let bucket_starts: Vec<DateTime<Utc>> = (0..buckets)
+        .map(|i| start + Duration::milliseconds(bucket_ms * i as i64))
+        .collect();
+    
+    let rows = types
+        .iter()
+        .map(|ptype| {
+            let mut deposits: Vec<&TrailEntry> = trail
+                .iter()
+                .filter(|e| e.pheromone_type == ptype.label() && e.timestamp <= now)
+                .collect();
+            deposits.sort_by_key(|e| e.timestamp);
+            
+            let mut intensities = vec![0.0; buckets];
+            let mut counts = vec![0u32; buckets];
+            // Deposits before `next` have been placed; the one just before
+            // it is in force at the start of the current bucket
+            let mut next = 0;
+            for (bucket, &bucket_start) in bucket_starts.iter().enumerate() {
+                while next < deposits.len() && deposits[next].timestamp < bucket_start {
+                    next += 1;
+                }
+                let mut peak = next.checked_sub(1).map_or(0.0, |i| deposits[i].intensity_at(bucket_start));
+                let end = bucket_starts.get(bucket + 1).copied().unwrap_or(now);
+                while next < deposits.len() && (bucket + 1 == buckets || deposits[next].timestamp < end) {
+                    peak = peak.max(deposits[next].intensity);
+                    counts[bucket] += 1;
+                    next += 1;
+                }
+                intensities[bucket] = peak;
+            }
+            
+            HeatmapRow {
+                pheromone_type: ptype.label().to_string(),
+                threshold: ptype.threshold(config),
+                intensities,
+                deposits: counts,
+            }
+        })
+        .collect();
+    
+    PheromoneHeatmap {
+        window_secs: window.num_seconds().max(0) as u64,
+        bucket_secs: bucket_ms as f64 / 1000.0,
+        bucket_starts,
+        rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heatmap() {
+        let now = Utc::now();
+        let deposit = |ptype: PheromoneType, minutes_ago: i64, intensity: f64| TrailEntry {
+            timestamp: now - Duration::minutes(minutes_ago),
+            pheromone_type: ptype.label().to_string(),
+            intensity,
+            decay_rate: 0.01,
+        };
+        // Newest first, as stored; one deposit predates the window
+        let trail = vec![
+            deposit(PheromoneType::PriceFreshness, 5, 1.0),
+            deposit(PheromoneType::PriceFreshness, 20, 0.8),
+            deposit(PheromoneType::ExecutionPermit, 25, 0.6),
+            deposit(PheromoneType::PriceFreshness, 50, 0.9),
+            deposit(PheromoneType::PriceFreshness, 70, 1.0),
+        ];
+        let types = [PheromoneType::PriceFreshness, PheromoneType::ExecutionPermit];
+        let map = heatmap(&trail, &types, &Config::default(), now, Duration::hours(1), 4);
+        
+        assert_eq!(map.window_secs, 3600);
+        assert_eq!(map.bucket_secs, 900.0);
+        assert_eq!(map.bucket_starts.len(), 4);
+        
+        let price = &map.rows[0];
+        assert_eq!(price.deposits, vec![1, 0, 1, 1]);
+        // The deposit from 70 minutes ago carries into the first bucket
+        // but the one 50 minutes ago peaks higher
+        assert_eq!(price.intensities[0], 0.9);
+        // Nothing deposited: the 50-minute deposit, decayed 5 minutes
+        let carried = 0.9 * (-0.01_f64 * 300.0).exp();
+        assert!((price.intensities[1] - carried).abs() < 1e-9);
+        assert_eq!(price.intensities[2], 0.8);
+        assert_eq!(price.intensities[3], 1.0);
+        
+        let permit = &map.rows[1];
+        assert_eq!(permit.pheromone_type, "Execution Permit");
+        assert_eq!(permit.intensities[0], 0.0);
+        assert_eq!(permit.intensities[1], 0.0);
+        assert_eq!(permit.intensities[2], 0.6);
+        assert!(permit.intensities[3] > 0.0 && permit.intensities[3] < 0.6);
+        assert_eq!(permit.deposits.iter().sum::<u32>(), 1);
+    }
+}
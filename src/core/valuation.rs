@@ -0,0 +1,247 @@
+//! Portfolio Valuation
+//!
+//! A periodic task that marks the portfolio to market from the Sensor's
+//! latest quotes and tracks the high-water mark and drawdown on the
+//! blackboard (`state:drawdown`). Benchmark ghost portfolios are marked
+//! from the same price moves, and a sampled history of both is kept under
+//! `history:portfolio` for export.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::time::{interval, Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::core::benchmarks::BenchmarkSet;
+use crate::core::blackboard::PortfolioState;
+use crate::core::money;
+use crate::core::Blackboard;
+
+/// High-water mark and drawdown of the portfolio value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrawdownState {
+    pub high_water_mark: f64,
+    pub high_water_mark_time: String,
+    pub current_value: f64,
+    /// Decline from the high-water mark (%)
+    pub drawdown_pct: f64,
+    /// Deepest drawdown seen since tracking began (%)
+    pub max_drawdown_pct: f64,
+    pub updated_at: String,
+}
+
+impl DrawdownState {
+    pub fn new(value: f64) -> Self {
+        let now = chrono::Utc::now().to_rfc3339();
+        Self {
+            high_water_mark: value,
+            high_water_mark_time: now.clone(),
+            current_value: value,
+            drawdown_pct: 0.0,
+            max_drawdown_pct: 0.0,
+            updated_at: now,
+        }
+    }
+    
+    /// Record a new valuation
+    pub fn update(&mut self, value: f64) {
+        let now = chrono::Utc::now().to_rfc3339();
+        if value > self.high_water_mark {
+            self.high_water_mark = value;
+            self.high_water_mark_time = now.clone();
+        }
+        self.current_value = value;
+        self.drawdown_pct = if self.high_water_mark > 0.0 {
+            (1.0 - value / self.high_water_mark) * 100.0
+        } else {
+            0.0
+        };
+        self.max_drawdown_pct = self.max_drawdown_pct.max(self.drawdown_pct);
+        self.updated_at = now;
+    }
+}
+
+/// One sample of the portfolio history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioHistoryPoint {
+    pub timestamp: String,
+    pub total_value: f64,
+    pub stocks_value: f64,
+    pub bonds_value: f64,
+    pub stocks_pct: f64,
+    pub bonds_pct: f64,
+    pub target_stocks_pct: f64,
+    /// Stock allocation minus target (percentage points)
+    pub drift_pct: f64,
+    pub drawdown_pct: f64,
+    /// Ghost portfolio values (see `core::benchmarks`)
+    #[serde(default)]
+    pub no_rebalance_value: Option<f64>,
+    #[serde(default)]
+    pub calendar_rebalance_value: Option<f64>,
+    #[serde(default)]
+    pub benchmark_value: Option<f64>,
+}
+
+impl PortfolioHistoryPoint {
+    pub fn new(
+        portfolio: &PortfolioState,
+        target_stocks_pct: f64,
+        drawdown: &DrawdownState,
+        benchmarks: Option<&BenchmarkSet>,
+    ) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            total_value: money::to_f64(portfolio.total_value),
+            stocks_value: money::to_f64(portfolio.stocks_value),
+            bonds_value: money::to_f64(portfolio.bonds_value),
+            stocks_pct: money::to_f64(portfolio.stocks_pct),
+            bonds_pct: money::to_f64(portfolio.bonds_pct),
+            target_stocks_pct,
+            drift_pct: money::to_f64(portfolio.stocks_pct) - target_stocks_pct,
+            drawdown_pct: drawdown.drawdown_pct,
+            no_rebalance_value: benchmarks.map(|b| b.no_rebalance.total_value()),
+            calendar_rebalance_value: benchmarks.map(|b| b.calendar_rebalanced.total_value()),
+            benchmark_value: benchmarks.map(|b| b.benchmark.total_value()),
+        }
+    }
+}
+
+/// Revalue holdings after the given price moves (new / old price)
+pub fn mark_to_market(portfolio: &PortfolioState, stocks_ratio: f64, bonds_ratio: f64) -> PortfolioState {
+    PortfolioState::from_values(
+        portfolio.stocks_value * money::from_f64(stocks_ratio),
+        portfolio.bonds_value * money::from_f64(bonds_ratio),
+        portfolio.last_trade_time.clone(),
+    )
+}
+
+/// Periodically mark the portfolio to market and update the drawdown
+pub async fn run_valuation(board: Arc<Blackboard>) -> Result<()> {
+    let config = board.config();
+    let valuation = &config.valuation;
+    let (stocks_symbol, bonds_symbol) = (&config.portfolio.stocks_symbol, &config.portfolio.bonds_symbol);
+    let mut ticker = interval(Duration::from_millis(valuation.interval_ms.max(100)));
+    let mut last_prices: HashMap<String, f64> = HashMap::new();
+    let mut last_timestamp: Option<String> = None;
+    let history_interval = Duration::from_secs(valuation.history_interval_secs);
+    let mut last_history: Option<Instant> = None;
+    
+    info!(
+        "📐 Valuation task started (every {}ms, mark-to-market {})",
+        valuation.interval_ms,
+        if valuation.mark_to_market { "on" } else { "off" }
+    );
+    
+    loop {
+        ticker.tick().await;
+        
+        let Some((mut portfolio, version)) = board.get_portfolio_snapshot().await? else {
+            continue;
+        };
+        
+        let target = board.get_target_allocation().await?;
+        let mut benchmarks = if config.benchmarks.enabled {
+            let today = chrono::Utc::now().date_naive();
+            let mut set = board
+                .get_benchmarks()
+                .await?
+                .unwrap_or_else(|| BenchmarkSet::new(money::to_f64(portfolio.total_value), &target, config, today));
+            if set.rebalance_calendar(&target, config, today) {
+                debug!("Valuation: Calendar benchmark rebalanced for {}", set.calendar_period);
+            }
+            Some(set)
+        } else {
+            None
+        };
+        
+        if valuation.mark_to_market {
+            if let Some(update) = board.get_market_update().await? {
+                if last_timestamp.as_deref() != Some(update.timestamp.as_str()) {
+                    let prices: HashMap<String, f64> = update
+                        .quotes
+                        .iter()
+                        .filter(|q| q.price > 0.0)
+                        .map(|q| (q.symbol.clone(), q.price))
+                        .collect();
+                    let ratio = |symbol: &str| match (prices.get(symbol), last_prices.get(symbol)) {
+                        (Some(price), Some(prev)) => price / prev,
+                        _ => 1.0,
+                    };
+                    let priced = |symbol: &String| prices.contains_key(symbol) && last_prices.contains_key(symbol);
+                    
+                    if priced(stocks_symbol) && priced(bonds_symbol) {
+                        let marked = mark_to_market(&portfolio, ratio(stocks_symbol), ratio(bonds_symbol));
+                        // Never overwrite a trade committed since the read;
+                        // the move is applied on the next tick instead
+                        if !board.compare_and_set_portfolio(&version, &marked).await? {
+                            debug!("Valuation: Portfolio changed mid-update, retrying next tick");
+                            continue;
+                        }
+                        portfolio = marked;
+                        debug!("Valuation: Portfolio marked at ${:.2}", portfolio.total_value);
+                    }
+                    if let Some(set) = benchmarks.as_mut() {
+                        set.mark(&ratio);
+                    }
+                    last_prices.extend(prices);
+                    last_timestamp = Some(update.timestamp);
+                }
+            }
+        }
+        
+        if let Some(set) = &benchmarks {
+            if let Err(e) = board.set_benchmarks(set).await {
+                warn!("Valuation: Failed to store benchmarks: {}", e);
+            }
+        }
+        
+        let mut drawdown = board
+            .get_drawdown()
+            .await?
+            .unwrap_or_else(|| DrawdownState::new(money::to_f64(portfolio.total_value)));
+        drawdown.update(money::to_f64(portfolio.total_value));
+        if let Err(e) = board.set_drawdown(&drawdown).await {
+            warn!("Valuation: Failed to store drawdown: {}", e);
+        }
+        
+        if valuation.history_max_entries > 0 && last_history.is_none_or(|t| t.elapsed() >= history_interval) {
+            let point = PortfolioHistoryPoint::new(&portfolio, target.stocks_pct, &drawdown, benchmarks.as_ref());
+            if let Err(e) = board.push_portfolio_history(&point, valuation.history_max_entries).await {
+                warn!("Valuation: Failed to record history: {}", e);
+            }
+            last_history = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    
+    #[test]
+    fn test_drawdown_tracking() {
+        let mut state = DrawdownState::new(100_000.0);
+        
+        state.update(110_000.0);
+        assert_eq!(state.high_water_mark, 110_000.0);
+        assert_eq!(state.drawdown_pct, 0.0);
+        
+        state.update(99_000.0);
+        assert!((state.drawdown_pct - 10.0).abs() < 1e-9);
+        
+        state.update(104_500.0);
+        assert!((state.drawdown_pct - 5.0).abs() < 1e-9);
+        assert!((state.max_drawdown_pct - 10.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_mark_to_market() {
+        let marked = mark_to_market(&PortfolioState::default(), 1.1, 1.0);
+        
+        assert_eq!(marked.total_value, Decimal::from(106_000));
+        assert!((money::to_f64(marked.stocks_pct) - 66_000.0 / 106_000.0 * 100.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,110 @@
+//! Cold-Start Warmup
+//!
+//! Right after startup the swarm has one price and no history: the first
+//! poll can put drift over the threshold and the Guardian would permit a
+//! trade on a single data point, judged on a simulated VIX because the
+//! realized-volatility window is still empty. With `[warmup] enabled` the
+//! Guardian withholds permits until the Sensor has published
+//! `min_snapshots` snapshots since the Guardian started and, while the
+//! Sensor receives live prices for the realized-volatility symbol, that
+//! window yields an estimate. (On simulated data the window never fills,
+//! so it is not waited for. Daily closes loaded at startup, see
+//! `market::daily_history`, fill it at once.) Warmup is judged from the
+//! blackboard, so it
+//! works when the Sensor runs in another process; once complete it does
+//! not apply again until the Guardian restarts.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::core::config::WarmupConfig;
+use crate::core::{Blackboard, Config};
+use crate::market::{daily_history, realized_vol};
+use crate::market::QuoteSource;
+
+/// Realized-volatility window as the Guardian would read it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolWindow {
+    pub samples: usize,
+    /// Enough usable returns for an estimate
+    pub filled: bool,
+}
+
+/// History the Sensor has built since the Guardian started
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WarmupProgress {
+    pub snapshots: usize,
+    /// None when the window isn't waited for
+    pub realized_vol: Option<VolWindow>,
+}
+
+impl WarmupProgress {
+    /// What warmup is still waiting for, if anything
+    pub fn pending(&self, config: &WarmupConfig, min_returns: usize) -> Option<String> {
+        if !config.enabled {
+            return None;
+        }
+        let mut waiting = Vec::new();
+        if self.snapshots < config.min_snapshots {
+            waiting.push(format!("{}/{} snapshots", self.snapshots, config.min_snapshots));
+        }
+        if let Some(window) = self.realized_vol.filter(|w| !w.filled) {
+            waiting.push(format!("realized vol {} samples, needs {} returns", window.samples, min_returns));
+        }
+        (!waiting.is_empty()).then(|| waiting.join("; "))
+    }
+}
+
+/// Read warmup progress for a Guardian that started at `started`
+pub async fn progress(board: &Blackboard, config: &Config, started: DateTime<Utc>) -> Result<WarmupProgress> {
+    let snapshots = board
+        .get_sensor_snapshot_times()
+        .await?
+        .iter()
+        .filter(|at| **at >= started)
+        .count();
+    
+    let vol = &config.market.realized_vol;
+    let mut realized_vol = None;
+    if config.warmup.require_realized_vol && vol.enabled {
+        let live = board.get_market_update().await?.is_some_and(|update| {
+            update.quotes.iter().any(|q| q.symbol == vol.symbol && q.source == QuoteSource::Live)
+        });
+        if live {
+            let samples = board.get_price_samples(&vol.symbol).await?;
+            let daily = board.get_daily_history(&vol.symbol).await?;
+            realized_vol = Some(VolWindow {
+                samples: samples.len(),
+                filled: realized_vol::annualized_volatility(&samples, vol.min_returns).is_some()
+                    || daily.is_some_and(|h| daily_history::daily_volatility(&h.bars, vol.min_returns).is_some()),
+            });
+        }
+    }
+    
+    Ok(WarmupProgress { snapshots, realized_vol })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending() {
+        let config = WarmupConfig::default();
+        let progress = |snapshots, realized_vol| WarmupProgress { snapshots, realized_vol };
+        let window = |samples, filled| Some(VolWindow { samples, filled });
+        
+        assert_eq!(progress(1, None).pending(&config, 20), Some("1/5 snapshots".to_string()));
+        assert_eq!(
+            progress(2, window(8, false)).pending(&config, 20),
+            Some("2/5 snapshots; realized vol 8 samples, needs 20 returns".to_string())
+        );
+        assert!(progress(5, window(8, false)).pending(&config, 20).is_some());
+        assert_eq!(progress(5, window(21, true)).pending(&config, 20), None);
+        // Not waiting on the window (simulated prices or not required)
+        assert_eq!(progress(5, None).pending(&config, 20), None);
+        
+        let disabled = WarmupConfig { enabled: false, ..config };
+        assert_eq!(progress(0, window(0, false)).pending(&disabled, 20), None);
+    }
+}
@@ -0,0 +1,121 @@
+//! Wash-Sale Guard
+//!
+//! Keeps recent filled legs per symbol (`wash_sale:fills`) and has the
+//! Trader skip a rebalance whose legs would complete a wash sale within
+//! `[wash_sale] window_days`: re-buying a symbol sold at a loss, or selling
+//! at a loss a symbol bought. Skipped legs are recorded in the trade log.
+//!
+//! Gains are average-cost estimates from the imported cost basis (see
+//! `rebalance::TaxImpact`); per-lot tracking would make them exact. Without
+//! a basis, `assume_loss_without_basis` decides.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::core::config::WashSaleConfig;
+use crate::execution::{OrderLeg, Side};
+
+/// A filled leg remembered for the wash-sale window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentFill {
+    pub symbol: String,
+    pub side: Side,
+    pub notional: Decimal,
+    /// Estimated gain (negative: loss) realized by a sell; none for buys
+    /// and for sells without a cost basis
+    pub realized_gain: Option<Decimal>,
+    pub timestamp: String,
+}
+
+/// A leg the guard refused
+#[derive(Debug, Clone, Serialize)]
+pub struct WashSaleConflict {
+    pub leg: OrderLeg,
+    pub reason: String,
+}
+
+fn is_loss(gain: Option<Decimal>, config: &WashSaleConfig) -> bool {
+    gain.map_or(config.assume_loss_without_basis, |gain| gain < Decimal::ZERO)
+}
+
+/// Legs that would complete a wash sale with a fill inside the window;
+/// `realized_gain` estimates the gain a sell leg would realize
+pub fn conflicts(
+    legs: &[OrderLeg],
+    realized_gain: impl Fn(&OrderLeg) -> Option<Decimal>,
+    recent: &[RecentFill],
+    now: DateTime<Utc>,
+    config: &WashSaleConfig,
+) -> Vec<WashSaleConflict> {
+    let since = now - Duration::days(config.window_days.max(0));
+    let in_window = |fill: &&RecentFill| {
+        DateTime::parse_from_rfc3339(&fill.timestamp).is_ok_and(|at| at.with_timezone(&Utc) >= since)
+    };
+    
+    legs.iter()
+        .filter_map(|leg| {
+            let mut fills = recent
+                .iter()
+                .filter(|fill| fill.symbol.eq_ignore_ascii_case(&leg.symbol))
+                .filter(in_window);
+            let reason = match leg.side {
+                Side::Buy => fills
+                    .find(|fill| fill.side == Side::Sell && is_loss(fill.realized_gain, config))
+                    .map(|fill| format!("{} was sold at a loss at {}", leg.symbol, fill.timestamp)),
+                Side::Sell if is_loss(realized_gain(leg), config) => fills
+                    .find(|fill| fill.side == Side::Buy)
+                    .map(|fill| format!("{} would be sold at a loss after a buy at {}", leg.symbol, fill.timestamp)),
+                Side::Sell => None,
+            }?;
+            Some(WashSaleConflict { leg: leg.clone(), reason })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg(symbol: &str, side: Side) -> OrderLeg {
+        OrderLeg { symbol: symbol.to_string(), side, notional: Decimal::from(1_000) }
+    }
+
+    fn fill(symbol: &str, side: Side, gain: Option<i64>, days_ago: i64, now: DateTime<Utc>) -> RecentFill {
+        RecentFill {
+            symbol: symbol.to_string(),
+            side,
+            notional: Decimal::from(1_000),
+            realized_gain: gain.map(Decimal::from),
+            timestamp: (now - Duration::days(days_ago)).to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_wash_sale_conflicts() {
+        let config = WashSaleConfig { enabled: true, ..WashSaleConfig::default() };
+        let now = Utc::now();
+        let no_gain = |_: &OrderLeg| None;
+        
+        // BND sold at a loss 10 days ago: buying it back is refused
+        let recent = vec![fill("BND", Side::Sell, Some(-200), 10, now)];
+        let found = conflicts(&[leg("SPY", Side::Sell), leg("BND", Side::Buy)], no_gain, &recent, now, &config);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].leg.symbol, "BND");
+        
+        // ...but not once the window has passed, or after a gain
+        let recent = vec![fill("BND", Side::Sell, Some(-200), 31, now), fill("BND", Side::Sell, Some(50), 1, now)];
+        assert!(conflicts(&[leg("BND", Side::Buy)], no_gain, &recent, now, &config).is_empty());
+        
+        // Selling at a loss right after a buy is refused; at a gain it is fine
+        let recent = vec![fill("SPY", Side::Buy, None, 5, now)];
+        let loss = |_: &OrderLeg| Some(Decimal::from(-100));
+        assert_eq!(conflicts(&[leg("SPY", Side::Sell)], loss, &recent, now, &config).len(), 1);
+        assert!(conflicts(&[leg("SPY", Side::Sell)], |_| Some(Decimal::ONE), &recent, now, &config).is_empty());
+        
+        // Unknown basis counts as a loss only when configured to
+        assert!(conflicts(&[leg("SPY", Side::Sell)], no_gain, &recent, now, &config).is_empty());
+        let strict = WashSaleConfig { assume_loss_without_basis: true, ..config };
+        assert_eq!(conflicts(&[leg("SPY", Side::Sell)], no_gain, &recent, now, &strict).len(), 1);
+    }
+}
@@ -0,0 +1,120 @@
+//! Execution Module
+//!
+//! Order execution backends used by the Trader. A rebalance is split into
+//! legs (one per symbol); each leg reports how much of it was filled so
+//! the Trader can commit the result or compensate filled legs on failure.
+//! Backends that can report positions are reconciled against the blackboard.
+//! A `QueuedExecutor` in front of a backend paces, retries and dead-letters
+//! its legs.
+
+pub mod queue;
+pub mod reconcile;
+pub mod simulated;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub use queue::QueuedExecutor;
+pub use simulated::SimulatedExecutor;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Buy => Self::Sell,
+            Self::Sell => Self::Buy,
+        }
+    }
+}
+
+/// What to do with the part of a leg the venue did not fill
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnfilledPolicy {
+    /// Resubmit the remainder while the execution permit is still active
+    #[default]
+    Retry,
+    /// Accept the partial fill and move on
+    Drop,
+}
+
+/// One order of a rebalance, sized in dollars
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderLeg {
+    pub symbol: String,
+    pub side: Side,
+    pub notional: Decimal,
+}
+
+impl OrderLeg {
+    /// Signed change in holdings this leg represents once filled
+    pub fn signed(&self, notional: Decimal) -> Decimal {
+        match self.side {
+            Side::Buy => notional,
+            Side::Sell => -notional,
+        }
+    }
+}
+
+/// Result of executing one leg
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub leg: OrderLeg,
+    pub filled_notional: Decimal,
+}
+
+impl Fill {
+    /// Notional of the leg that is still unfilled
+    pub fn remaining(&self) -> Decimal {
+        (self.leg.notional - self.filled_notional).max(Decimal::ZERO)
+    }
+    
+    /// Signed change in holdings from this fill
+    pub fn signed_notional(&self) -> Decimal {
+        self.leg.signed(self.filled_notional)
+    }
+}
+
+/// Holdings as reported by the execution backend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BrokerPositions {
+    pub cash: f64,
+    /// Market value ($) per symbol
+    pub positions: HashMap<String, f64>,
+}
+
+impl BrokerPositions {
+    /// Market value held in `symbol` (zero if not held)
+    pub fn value(&self, symbol: &str) -> f64 {
+        self.positions.get(symbol).copied().unwrap_or(0.0)
+    }
+}
+
+/// Trait for execution backends
+#[async_trait]
+pub trait Executor: Send + Sync {
+    /// Execute one leg, returning how much of it was filled
+    async fn execute(&self, leg: &OrderLeg) -> Result<Fill>;
+    
+    /// Execute one leg under an idempotency key (the venue's client order
+    /// ID), so a resubmission after an ambiguous failure is not filled
+    /// twice. Backends without one just execute.
+    async fn execute_keyed(&self, leg: &OrderLeg, _idempotency_key: &str) -> Result<Fill> {
+        self.execute(leg).await
+    }
+    
+    /// Current positions and cash, or `None` if the backend keeps no
+    /// account of its own (e.g. the simulator)
+    async fn positions(&self) -> Result<Option<BrokerPositions>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,238 @@
+//! Execution Command Queue
+//!
+//! Brokers cap order rates and reject orders for transient reasons
+//! (throttling, a venue hiccup). `QueuedExecutor` wraps a backend and
+//! submits legs one at a time, at most `[execution.queue]
+//! max_orders_per_minute` per rolling minute. A rejected leg is resubmitted
+//! up to `max_retries` times with doubling backoff, always under the same
+//! idempotency key, so a submission that reached the venue before failing
+//! is not filled twice. A leg that still fails is appended to the
+//! dead-letter list (`orders:dead_letter`, `GET /api/orders` and the
+//! dashboard) before the error reaches the Trader, which compensates as it
+//! does for any failed leg.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tracing::{debug, error, warn};
+use uuid::Uuid;
+
+use super::{BrokerPositions, Executor, Fill, OrderLeg};
+use crate::core::config::ExecutionQueueConfig;
+use crate::core::Blackboard;
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Submissions within the last minute
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    max_per_minute: u32,
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(max_per_minute: u32) -> Self {
+        Self { max_per_minute, sent: VecDeque::new() }
+    }
+
+    /// How long a submission at `now` has to wait (zero if it may go now)
+    pub fn delay(&mut self, now: Instant) -> Duration {
+        while self.sent.front().is_some_and(|at| now.saturating_duration_since(*at) >= WINDOW) {
+            self.sent.pop_front();
+        }
+        match self.sent.front() {
+            Some(oldest) if self.max_per_minute > 0 && self.sent.len() >= self.max_per_minute as usize => {
+                WINDOW - now.saturating_duration_since(*oldest)
+            }
+            _ => Duration::ZERO,
+        }
+    }
+
+    pub fn record(&mut self, at: Instant) {
+        self.sent.push_back(at);
+    }
+}
+
+/// A leg given up on after its retries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub idempotency_key: String,
+    pub leg: OrderLeg,
+    pub attempts: u32,
+    /// Last rejection
+    pub error: String,
+    pub first_attempt_at: String,
+    /// When it was given up on
+    pub timestamp: String,
+}
+
+/// Rate-limited, retrying front for an execution backend
+pub struct QueuedExecutor {
+    inner: Arc<dyn Executor>,
+    config: ExecutionQueueConfig,
+    /// Held for the whole of a leg's submission, which serializes legs
+    limiter: Mutex<RateLimiter>,
+    board: Option<Arc<Blackboard>>,
+}
+
+impl QueuedExecutor {
+    pub fn new(inner: Arc<dyn Executor>, config: &ExecutionQueueConfig) -> Self {
+        Self {
+            inner,
+            config: config.clone(),
+            limiter: Mutex::new(RateLimiter::new(config.max_orders_per_minute)),
+            board: None,
+        }
+    }
+
+    /// Record dead letters on the blackboard (otherwise they are only logged)
+    pub fn with_dead_letters(mut self, board: Arc<Blackboard>) -> Self {
+        self.board = Some(board);
+        self
+    }
+
+    async fn dead_letter(&self, letter: DeadLetter) {
+        error!(
+            "☠️ Command queue: {:?} ${:.2} {} failed after {} attempts: {}",
+            letter.leg.side, letter.leg.notional, letter.leg.symbol, letter.attempts, letter.error
+        );
+        let Some(board) = &self.board else {
+            return;
+        };
+        if let Err(e) = board.push_dead_letter(&letter, self.config.dead_letter_max_entries).await {
+            warn!("Command queue: Failed to record dead letter {}: {}", letter.idempotency_key, e);
+        }
+    }
+}
+
+#[async_trait]
+impl Executor for QueuedExecutor {
+    async fn execute(&self, leg: &OrderLeg) -> Result<Fill> {
+        let mut limiter = self.limiter.lock().await;
+        let key = Uuid::new_v4().to_string();
+        let first_attempt_at = Utc::now().to_rfc3339();
+        let mut backoff = Duration::from_millis(self.config.retry_backoff_ms);
+        let mut attempts = 0;
+        
+        loop {
+            let delay = limiter.delay(Instant::now());
+            if !delay.is_zero() {
+                debug!("Command queue: Rate limit reached, waiting {}ms", delay.as_millis());
+                sleep(delay).await;
+            }
+            limiter.record(Instant::now());
+            attempts += 1;
+            
+            match self.inner.execute_keyed(leg, &key).await {
+                Ok(fill) => return Ok(fill),
+                Err(e) if attempts <= self.config.max_retries => {
+                    warn!(
+                        "Command queue: {} {} rejected ({:#}); retry {}/{} in {}ms",
+                        leg.symbol,
+                        key,
+                        e,
+                        attempts,
+                        self.config.max_retries,
+                        backoff.as_millis()
+                    );
+                    sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    self.dead_letter(DeadLetter {
+                        idempotency_key: key,
+                        leg: leg.clone(),
+                        attempts,
+                        error: format!("{:#}", e),
+                        first_attempt_at,
+                        timestamp: Utc::now().to_rfc3339(),
+                    })
+                    .await;
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    async fn positions(&self) -> Result<Option<BrokerPositions>> {
+        self.inner.positions().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::Side;
+    use anyhow::bail;
+    use rust_decimal::Decimal;
+    use std::sync::Mutex as StdMutex;
+
+    #[test]
+    fn test_rate_limiter() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(2);
+        assert_eq!(limiter.delay(start), Duration::ZERO);
+        limiter.record(start);
+        limiter.record(start + Duration::from_secs(10));
+        assert_eq!(limiter.delay(start + Duration::from_secs(20)), Duration::from_secs(40));
+        // The first submission leaves the window
+        assert_eq!(limiter.delay(start + Duration::from_secs(60)), Duration::ZERO);
+        
+        let mut unlimited = RateLimiter::new(0);
+        for _ in 0..100 {
+            unlimited.record(start);
+        }
+        assert_eq!(unlimited.delay(start), Duration::ZERO);
+    }
+
+    /// Rejects the first `failures` submissions, recording each key
+    struct Flaky {
+        failures: u32,
+        keys: StdMutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Executor for Flaky {
+        async fn execute(&self, _leg: &OrderLeg) -> Result<Fill> {
+            unreachable!("the queue submits with a key")
+        }
+        
+        async fn execute_keyed(&self, leg: &OrderLeg, idempotency_key: &str) -> Result<Fill> {
+            let mut keys = self.keys.lock().unwrap();
+            keys.push(idempotency_key.to_string());
+            if keys.len() as u32 <= self.failures {
+                bail!("throttled");
+            }
+            Ok(Fill { leg: leg.clone(), filled_notional: leg.notional })
+        }
+    }
+
+    fn queue(failures: u32) -> (Arc<Flaky>, QueuedExecutor) {
+        let flaky = Arc::new(Flaky { failures, keys: StdMutex::new(Vec::new()) });
+        let config = ExecutionQueueConfig { retry_backoff_ms: 1, ..ExecutionQueueConfig::default() };
+        (flaky.clone(), QueuedExecutor::new(flaky, &config))
+    }
+
+    #[tokio::test]
+    async fn test_retries_under_one_key() {
+        let leg = OrderLeg { symbol: "SPY".to_string(), side: Side::Sell, notional: Decimal::from(1_000) };
+        
+        let (flaky, executor) = queue(2);
+        let fill = executor.execute(&leg).await.unwrap();
+        assert_eq!(fill.filled_notional, Decimal::from(1_000));
+        let keys = flaky.keys.lock().unwrap().clone();
+        assert_eq!(keys.len(), 3);
+        assert!(keys.iter().all(|key| *key == keys[0]));
+        
+        // Three retries, then the leg is given up on
+        let (flaky, executor) = queue(10);
+        assert!(executor.execute(&leg).await.is_err());
+        assert_eq!(flaky.keys.lock().unwrap().len(), 4);
+    }
+}
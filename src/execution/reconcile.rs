@@ -0,0 +1,200 @@
+//! Position Reconciliation
+//!
+//! Periodically compares the blackboard's holdings against the positions
+//! reported by the execution backend. Divergences beyond a tolerance are
+//! either reported (alert) or written back to the blackboard (correct),
+//! so fills that never made it into state cannot drift silently.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{debug, info, warn};
+
+use super::{BrokerPositions, Executor};
+use crate::core::blackboard::PortfolioState;
+use crate::core::money;
+use crate::core::Blackboard;
+
+/// What to do when blackboard and broker holdings disagree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchAction {
+    /// Report the mismatch and leave the blackboard untouched
+    #[default]
+    Alert,
+    /// Overwrite blackboard holdings with the broker's
+    Correct,
+}
+
+/// One symbol whose holdings disagree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Divergence {
+    pub symbol: String,
+    pub blackboard_value: f64,
+    pub broker_value: f64,
+}
+
+impl Divergence {
+    pub fn difference(&self) -> f64 {
+        self.broker_value - self.blackboard_value
+    }
+}
+
+/// Outcome of the latest reconciliation run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    pub checked_at: String,
+    pub broker_cash: f64,
+    pub divergences: Vec<Divergence>,
+    /// Whether the blackboard was overwritten with broker holdings
+    pub corrected: bool,
+}
+
+/// Symbols whose blackboard and broker values differ by more than `tolerance_usd`
+pub fn find_divergences(
+    portfolio: &PortfolioState,
+    broker: &BrokerPositions,
+    stocks_symbol: &str,
+    bonds_symbol: &str,
+    tolerance_usd: f64,
+) -> Vec<Divergence> {
+    [(stocks_symbol, portfolio.stocks_value), (bonds_symbol, portfolio.bonds_value)]
+        .into_iter()
+        .map(|(symbol, blackboard_value)| Divergence {
+            symbol: symbol.to_string(),
+            blackboard_value: money::to_f64(blackboard_value),
+            broker_value: broker.value(symbol),
+        })
+        .filter(|d| d.difference().abs() > tolerance_usd)
+        .collect()
+}
+
+/// Portfolio state rebuilt from broker holdings. Cash is not modelled on
+/// the blackboard, so only the two tracked positions are carried over.
+pub fn corrected_state(
+    portfolio: &PortfolioState,
+    broker: &BrokerPositions,
+    stocks_symbol: &str,
+    bonds_symbol: &str,
+) -> PortfolioState {
+    PortfolioState::from_values(
+        money::from_f64(broker.value(stocks_symbol)),
+        money::from_f64(broker.value(bonds_symbol)),
+        portfolio.last_trade_time.clone(),
+    )
+}
+
+/// Reconciliation loop; exits immediately if the backend does not report positions
+pub async fn run_reconciliation(board: Arc<Blackboard>, executor: Arc<dyn Executor>) -> Result<()> {
+    let config = board.config();
+    let settings = &config.reconciliation;
+    let (stocks_symbol, bonds_symbol) = (&config.portfolio.stocks_symbol, &config.portfolio.bonds_symbol);
+    let mut ticker = interval(Duration::from_secs(settings.interval_secs.max(1)));
+    
+    info!(
+        "🧾 Reconciliation started (every {}s, tolerance ${:.2}, on mismatch: {:?})",
+        settings.interval_secs, settings.tolerance_usd, settings.on_mismatch
+    );
+    
+    loop {
+        ticker.tick().await;
+        
+        let broker = match executor.positions().await {
+            Ok(Some(broker)) => broker,
+            Ok(None) => {
+                info!("🧾 Execution backend does not report positions; reconciliation stopped");
+                return Ok(());
+            }
+            Err(e) => {
+                warn!("Reconciliation: Failed to fetch broker positions: {}", e);
+                continue;
+            }
+        };
+        
+        let Some((portfolio, version)) = board.get_portfolio_snapshot().await? else {
+            continue;
+        };
+        
+        let divergences = find_divergences(&portfolio, &broker, stocks_symbol, bonds_symbol, settings.tolerance_usd);
+        let mut corrected = false;
+        
+        if divergences.is_empty() {
+            debug!("Reconciliation: Holdings match broker (cash ${:.2})", broker.cash);
+        } else {
+            let detail = divergences
+                .iter()
+                .map(|d| format!("{} blackboard ${:.2} vs broker ${:.2}", d.symbol, d.blackboard_value, d.broker_value))
+                .collect::<Vec<_>>()
+                .join("; ");
+            
+            if settings.on_mismatch == MismatchAction::Correct {
+                let fixed = corrected_state(&portfolio, &broker, stocks_symbol, bonds_symbol);
+                // A trade committed since the read wins; re-check next tick
+                corrected = board.compare_and_set_portfolio(&version, &fixed).await?;
+                if corrected {
+                    warn!("🧾 Reconciliation: Corrected holdings from broker ({})", detail);
+                }
+            }
+            board.report_position_mismatch(if corrected { format!("corrected: {}", detail) } else { detail }).await?;
+        }
+        
+        let report = ReconciliationReport {
+            checked_at: chrono::Utc::now().to_rfc3339(),
+            broker_cash: broker.cash,
+            divergences,
+            corrected,
+        };
+        if let Err(e) = board.set_reconciliation(&report).await {
+            warn!("Reconciliation: Failed to store report: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use std::collections::HashMap;
+    
+    fn portfolio() -> PortfolioState {
+        PortfolioState::from_values(Decimal::from(60_000), Decimal::from(40_000), None)
+    }
+    
+    fn broker(spy: f64, bnd: f64) -> BrokerPositions {
+        BrokerPositions {
+            cash: 250.0,
+            positions: HashMap::from([("SPY".to_string(), spy), ("BND".to_string(), bnd)]),
+        }
+    }
+    
+    #[test]
+    fn test_within_tolerance_is_clean() {
+        let found = find_divergences(&portfolio(), &broker(60_000.5, 39_999.5), "SPY", "BND", 1.0);
+        assert!(found.is_empty());
+    }
+    
+    #[test]
+    fn test_divergence_detected() {
+        let found = find_divergences(&portfolio(), &broker(55_000.0, 40_000.0), "SPY", "BND", 1.0);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].symbol, "SPY");
+        assert_eq!(found[0].difference(), -5_000.0);
+    }
+    
+    #[test]
+    fn test_missing_position_counts_as_zero() {
+        let broker = BrokerPositions { cash: 0.0, positions: HashMap::from([("SPY".to_string(), 60_000.0)]) };
+        let found = find_divergences(&portfolio(), &broker, "SPY", "BND", 1.0);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].broker_value, 0.0);
+    }
+    
+    #[test]
+    fn test_corrected_state_uses_broker_holdings() {
+        let fixed = corrected_state(&portfolio(), &broker(45_000.0, 45_000.0), "SPY", "BND");
+        assert_eq!(fixed.total_value, Decimal::from(90_000));
+        assert_eq!(fixed.stocks_pct, Decimal::from(50));
+        assert_eq!(fixed.bonds_pct, Decimal::from(50));
+    }
+}
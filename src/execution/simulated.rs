@@ -0,0 +1,238 @@
+//! Simulated Executor
+//!
+//! Paper-trading backend. By default every leg fills immediately and in
+//! full; latency and partial fills can be configured (`[execution]`) to
+//! exercise the race between fills and the decaying ExecutionPermit.
+//! `simulate_trade` runs proposed legs against a given portfolio without
+//! touching the blackboard, for external strategies
+//! (`POST /api/simulate/trade`).
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use rand::Rng;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration};
+use tracing::debug;
+
+use super::{Executor, Fill, OrderLeg, Side};
+use crate::core::blackboard::{PortfolioState, TargetAllocation};
+use crate::core::config::{Config, ExecutionConfig};
+use crate::core::money;
+use crate::core::rebalance;
+use crate::core::simulation::SimulationRng;
+
+#[derive(Debug, Default)]
+pub struct SimulatedExecutor {
+    latency: Duration,
+    jitter: Duration,
+    partial_fill_probability: f64,
+    min_fill_ratio: f64,
+    rng: SimulationRng,
+}
+
+impl SimulatedExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn from_config(config: &ExecutionConfig) -> Self {
+        Self {
+            latency: Duration::from_millis(config.latency_ms),
+            jitter: Duration::from_millis(config.latency_jitter_ms),
+            partial_fill_probability: config.partial_fill_probability.clamp(0.0, 1.0),
+            min_fill_ratio: config.min_fill_ratio.clamp(0.0, 1.0),
+            rng: SimulationRng::from_entropy(),
+        }
+    }
+    
+    /// Draw latency and partial fills from a seeded generator
+    pub fn with_rng(mut self, rng: SimulationRng) -> Self {
+        self.rng = rng;
+        self
+    }
+    
+    /// Fraction of a leg to fill: 1.0, or a random ratio in
+    /// `[min_fill_ratio, 1)` with probability `partial_fill_probability`
+    fn fill_ratio(&self) -> f64 {
+        self.rng.with(|rng| {
+            if self.min_fill_ratio < 1.0 && rng.gen_bool(self.partial_fill_probability) {
+                rng.gen_range(self.min_fill_ratio..1.0)
+            } else {
+                1.0
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl Executor for SimulatedExecutor {
+    async fn execute(&self, leg: &OrderLeg) -> Result<Fill> {
+        let mut delay = self.latency;
+        if !self.jitter.is_zero() {
+            delay += self.jitter.mul_f64(self.rng.unit());
+        }
+        if !delay.is_zero() {
+            sleep(delay).await;
+        }
+        
+        let ratio = self.fill_ratio();
+        let filled_notional = if ratio < 1.0 { leg.notional * money::from_f64(ratio) } else { leg.notional };
+        debug!(
+            "Simulated fill: {:?} ${:.2}/{:.2} {} after {}ms",
+            leg.side, filled_notional, leg.notional, leg.symbol, delay.as_millis()
+        );
+        Ok(Fill {
+            leg: leg.clone(),
+            filled_notional,
+        })
+    }
+}
+
+/// Outcome of running proposed legs through the simulator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedTrade {
+    pub fills: Vec<Fill>,
+    pub before: PortfolioState,
+    pub after: PortfolioState,
+    /// Commissions plus half-spread on the filled notional
+    pub estimated_cost: Decimal,
+    pub target: TargetAllocation,
+    pub drift_before_pct: Decimal,
+    pub drift_after_pct: Decimal,
+}
+
+/// Fill `legs` against `state` the way the Trader would (sells first,
+/// partial fills as configured, settled in whole minor units of
+/// `currency`) and report the resulting portfolio, costs and drift from
+/// `target`. Latency is skipped.
+pub async fn simulate_trade(
+    config: &Config,
+    state: &PortfolioState,
+    target: &TargetAllocation,
+    legs: &[OrderLeg],
+    currency: &str,
+    rng: SimulationRng,
+) -> Result<SimulatedTrade> {
+    let symbols = [&config.portfolio.stocks_symbol, &config.portfolio.bonds_symbol];
+    for leg in legs {
+        if !symbols.contains(&&leg.symbol) {
+            bail!("unknown symbol '{}' (expected {} or {})", leg.symbol, symbols[0], symbols[1]);
+        }
+        if leg.notional <= Decimal::ZERO {
+            bail!("{} leg notional must be positive", leg.symbol);
+        }
+    }
+    let sold = |symbol: &str| legs.iter().filter(|l| l.symbol == symbol && l.side == Side::Sell).map(|l| l.notional).sum::<Decimal>();
+    for (symbol, held) in [(symbols[0], state.stocks_value), (symbols[1], state.bonds_value)] {
+        if sold(symbol) > held {
+            bail!("sells of {} exceed the {} held", symbol, held);
+        }
+    }
+    
+    let executor = SimulatedExecutor::from_config(&ExecutionConfig {
+        latency_ms: 0,
+        latency_jitter_ms: 0,
+        ..config.execution.clone()
+    })
+    .with_rng(rng);
+    let mut ordered: Vec<&OrderLeg> = legs.iter().collect();
+    ordered.sort_by_key(|leg| leg.side != Side::Sell);
+    let mut fills = Vec::with_capacity(ordered.len());
+    for leg in ordered {
+        fills.push(executor.execute(leg).await?);
+    }
+    
+    let filled = |symbol: &str| money::sum(fills.iter().filter(|f| f.leg.symbol == symbol).map(Fill::signed_notional), currency);
+    let after = PortfolioState::from_values(
+        money::sum([state.stocks_value, filled(symbols[0])], currency),
+        money::sum([state.bonds_value, filled(symbols[1])], currency),
+        state.last_trade_time.clone(),
+    );
+    let filled_legs: Vec<OrderLeg> = fills
+        .iter()
+        .map(|f| OrderLeg { notional: f.filled_notional, ..f.leg.clone() })
+        .collect();
+    let drift = |state: &PortfolioState| (state.stocks_pct - money::from_f64(target.stocks_pct)).abs();
+    
+    Ok(SimulatedTrade {
+        estimated_cost: money::round(rebalance::leg_costs(&filled_legs, &config.costs), currency),
+        drift_before_pct: drift(state),
+        drift_after_pct: drift(&after),
+        before: state.clone(),
+        after,
+        target: target.clone(),
+        fills,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use crate::core::simulation::EXECUTION_STREAM;
+    use crate::execution::Side;
+    
+    fn leg() -> OrderLeg {
+        OrderLeg { symbol: "SPY".to_string(), side: Side::Buy, notional: Decimal::from(1_000) }
+    }
+    
+    #[tokio::test]
+    async fn test_default_fills_in_full() {
+        let fill = SimulatedExecutor::new().execute(&leg()).await.unwrap();
+        assert_eq!(fill.filled_notional, Decimal::from(1_000));
+        assert_eq!(fill.remaining(), Decimal::ZERO);
+    }
+    
+    #[tokio::test]
+    async fn test_partial_fill_respects_min_ratio() {
+        let executor = SimulatedExecutor::from_config(&ExecutionConfig {
+            partial_fill_probability: 1.0,
+            min_fill_ratio: 0.4,
+            ..ExecutionConfig::default()
+        });
+        for _ in 0..50 {
+            let fill = executor.execute(&leg()).await.unwrap();
+            assert!((Decimal::from(400)..Decimal::from(1_000)).contains(&fill.filled_notional));
+            assert!(fill.remaining() > Decimal::ZERO);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_seeded_fills_reproduce() {
+        async fn fills(seed: u64) -> Vec<Decimal> {
+            let config = ExecutionConfig { partial_fill_probability: 0.5, ..ExecutionConfig::default() };
+            let executor = SimulatedExecutor::from_config(&config).with_rng(SimulationRng::new(seed, EXECUTION_STREAM));
+            let mut fills = Vec::new();
+            for _ in 0..20 {
+                fills.push(executor.execute(&leg()).await.unwrap().filled_notional);
+            }
+            fills
+        }
+        assert_eq!(fills(7).await, fills(7).await);
+        assert_ne!(fills(7).await, fills(8).await);
+    }
+    
+    #[tokio::test]
+    async fn test_simulate_trade() {
+        let config = Config::default();
+        let state = PortfolioState::from_values(Decimal::from(70_000), Decimal::from(30_000), None);
+        let target = TargetAllocation { stocks_pct: 60.0, bonds_pct: 40.0 };
+        let leg = |symbol: &str, side, notional: i64| OrderLeg { symbol: symbol.to_string(), side, notional: Decimal::from(notional) };
+        let legs = [leg("BND", Side::Buy, 10_000), leg("SPY", Side::Sell, 10_000)];
+        
+        let trade = simulate_trade(&config, &state, &target, &legs, "USD", SimulationRng::new(1, EXECUTION_STREAM)).await.unwrap();
+        // Sells go first
+        assert_eq!(trade.fills[0].leg.side, Side::Sell);
+        assert_eq!(trade.after.stocks_value, Decimal::from(60_000));
+        assert_eq!(trade.after.bonds_value, Decimal::from(40_000));
+        assert_eq!(trade.drift_before_pct, Decimal::from(10));
+        assert_eq!(trade.drift_after_pct, Decimal::ZERO);
+        assert!(trade.estimated_cost > Decimal::ZERO);
+        
+        let oversold = [leg("BND", Side::Sell, 40_000)];
+        assert!(simulate_trade(&config, &state, &target, &oversold, "USD", SimulationRng::new(1, EXECUTION_STREAM)).await.is_err());
+        let unknown = [leg("QQQ", Side::Buy, 1_000)];
+        assert!(simulate_trade(&config, &state, &target, &unknown, "USD", SimulationRng::new(1, EXECUTION_STREAM)).await.is_err());
+    }
+}
@@ -0,0 +1,348 @@
+//! Plain-Text Accounting Export
+//!
+//! Renders the trade log as a ledger-cli or beancount journal so the
+//! portfolio can be reconciled alongside the rest of a user's books: one
+//! opening-balance transaction for the starting portfolio, then one
+//! transaction per executed rebalance moving value between the holding
+//! accounts (`<account_prefix>:<SYMBOL>`). Amounts are in the portfolio's
+//! base currency, to its minor unit; any part of a rebalance that did not net to zero (a
+//! partial fill) is balanced against `<account_prefix>:Cash`.
+//!
+//! Served as `/api/export/trades.ledger` / `trades.beancount` and, when
+//! `[ledger] journal_file` is set, kept up to date on disk by the services
+//! process.
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{info, warn};
+
+use super::{collect_trades, parse_timestamp, DateRange};
+use crate::core::blackboard::{PortfolioState, TradeLogEntry};
+use crate::core::config::Config;
+use crate::core::money;
+use crate::core::{bootstrap, holdings, Blackboard};
+
+/// Journal syntax
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LedgerFormat {
+    #[default]
+    Ledger,
+    Beancount,
+}
+
+/// Starting value of each holding, posted against the opening account
+#[derive(Debug, Clone)]
+pub struct Opening {
+    pub date: NaiveDate,
+    /// (symbol, value); cash is posted under "Cash"
+    pub holdings: Vec<(String, Decimal)>,
+}
+
+impl Opening {
+    pub fn from_portfolio(date: NaiveDate, portfolio: &PortfolioState, config: &Config) -> Self {
+        let cash = portfolio.total_value - portfolio.stocks_value - portfolio.bonds_value;
+        Self {
+            date,
+            holdings: vec![
+                (config.portfolio.stocks_symbol.clone(), portfolio.stocks_value),
+                (config.portfolio.bonds_symbol.clone(), portfolio.bonds_value),
+                ("Cash".to_string(), cash),
+            ],
+        }
+    }
+}
+
+/// One dated, balanced entry of the journal
+struct Transaction {
+    date: NaiveDate,
+    narration: String,
+    trade_id: Option<String>,
+    /// (account, minor units); `None` is the balancing posting
+    postings: Vec<(String, Option<i64>)>,
+}
+
+/// Account for a holding under `prefix`, reduced to characters both
+/// ledger-cli and beancount accept (beancount components start upper case)
+pub fn account(prefix: &str, symbol: &str) -> String {
+    let name: String = symbol
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let mut chars = name.trim_matches('-').chars();
+    match chars.next() {
+        Some(first) => format!("{}:{}{}", prefix, first.to_ascii_uppercase(), chars.as_str()),
+        None => format!("{}:Unknown", prefix),
+    }
+}
+
+/// Filled (symbol, amount) legs of a trade. Entries logged before the
+/// deltas were recorded fall back to `amount`, signed by the action;
+/// skipped legs have none.
+pub fn legs(trade: &TradeLogEntry, config: &Config) -> Vec<(String, Decimal)> {
+    if trade.skipped.is_some() {
+        return Vec::new();
+    }
+    let (stocks, bonds) = if !trade.stocks_delta.is_zero() || !trade.bonds_delta.is_zero() {
+        (trade.stocks_delta, trade.bonds_delta)
+    } else {
+        let stocks = if trade.action.starts_with("SELL") { -trade.amount } else { trade.amount };
+        (stocks, -stocks)
+    };
+    [(&config.portfolio.stocks_symbol, stocks), (&config.portfolio.bonds_symbol, bonds)]
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(symbol, amount)| (symbol.clone(), amount))
+        .collect()
+}
+
+/// Postings for (symbol, amount) pairs in `currency`'s minor units,
+/// balanced against `balance_account` when they do not net to zero
+fn postings(
+    prefix: &str,
+    amounts: &[(String, Decimal)],
+    currency: &str,
+    balance_account: &str,
+) -> Vec<(String, Option<i64>)> {
+    let mut postings: Vec<_> = amounts
+        .iter()
+        .map(|(symbol, amount)| (account(prefix, symbol), money::to_minor(*amount, currency)))
+        .filter(|(_, units)| *units != 0)
+        .map(|(account, units)| (account, Some(units)))
+        .collect();
+    let net: i64 = postings.iter().filter_map(|(_, units)| *units).sum();
+    if net != 0 {
+        postings.push((balance_account.to_string(), None));
+    }
+    postings
+}
+
+/// Quote a beancount string
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Render the opening balance and trades (oldest first) as a journal
+pub fn render(
+    format: LedgerFormat,
+    config: &Config,
+    currency: &str,
+    opening: Option<&Opening>,
+    trades: &[TradeLogEntry],
+) -> String {
+    let settings = &config.ledger;
+    let prefix = settings.account_prefix.as_str();
+    let cash_account = account(prefix, "Cash");
+    let digits = money::minor_unit_digits(currency) as usize;
+    
+    let mut transactions = Vec::new();
+    if let Some(opening) = opening {
+        transactions.push(Transaction {
+            date: opening.date,
+            narration: "Opening balance".to_string(),
+            trade_id: None,
+            postings: postings(prefix, &opening.holdings, currency, &settings.opening_account),
+        });
+    }
+    for trade in trades {
+        let Some(at) = parse_timestamp(&trade.timestamp) else {
+            continue;
+        };
+        let postings = postings(prefix, &legs(trade, config), currency, &cash_account);
+        if postings.is_empty() {
+            continue;
+        }
+        transactions.push(Transaction {
+            date: at.date_naive(),
+            narration: trade.action.clone(),
+            trade_id: Some(trade.id.clone()),
+            postings,
+        });
+    }
+    
+    let mut out = format!("; DriftGuard journal, swarm \"{}\"\n", config.swarm.name);
+    if format == LedgerFormat::Beancount {
+        let _ = writeln!(out, "option \"operating_currency\" {}", quoted(currency));
+        // Accounts must be opened before (or on) the day of first use
+        if let Some(first) = transactions.iter().map(|t| t.date).min() {
+            let mut accounts: Vec<&str> = transactions
+                .iter()
+                .flat_map(|t| t.postings.iter().map(|(account, _)| account.as_str()))
+                .collect();
+            accounts.sort();
+            accounts.dedup();
+            out.push('\n');
+            for account in accounts {
+                let _ = writeln!(out, "{} open {}", first, account);
+            }
+        }
+    }
+    
+    for tx in &transactions {
+        out.push('\n');
+        match format {
+            LedgerFormat::Ledger => {
+                let _ = writeln!(out, "{} * DriftGuard", tx.date);
+                let _ = writeln!(out, "    ; {}", tx.narration);
+                if let Some(id) = &tx.trade_id {
+                    let _ = writeln!(out, "    ; trade_id: {}", id);
+                }
+            }
+            LedgerFormat::Beancount => {
+                let _ = writeln!(out, "{} * \"DriftGuard\" {}", tx.date, quoted(&tx.narration));
+                if let Some(id) = &tx.trade_id {
+                    let _ = writeln!(out, "  trade_id: {}", quoted(id));
+                }
+            }
+        }
+        let indent = if format == LedgerFormat::Ledger { "    " } else { "  " };
+        for (account, amount) in &tx.postings {
+            match amount {
+                Some(units) => {
+                    let amount = money::from_minor(*units, currency);
+                    let _ = writeln!(out, "{}{:<40} {:>14.*} {}", indent, account, digits, amount, currency);
+                }
+                None => {
+                    let _ = writeln!(out, "{}{}", indent, account);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Fetch the opening balance and trades within `range` and render them.
+/// The opening balance is only included for ranges open at the start.
+pub async fn journal(board: &Blackboard, format: LedgerFormat, range: DateRange) -> Result<Vec<u8>> {
+    let config = board.config();
+    let trades = collect_trades(board, range).await?;
+    let init = bootstrap::current(board).await?;
+    
+    let opening = if range.from.is_none() {
+        let portfolio = holdings::initial_portfolio(board).await?;
+        let date = init
+            .initialized_at
+            .as_deref()
+            .and_then(parse_timestamp)
+            .or_else(|| trades.first().and_then(|t| parse_timestamp(&t.timestamp)))
+            .map(|t| t.date_naive())
+            .unwrap_or_else(|| Utc::now().date_naive());
+        Some(Opening::from_portfolio(date, &portfolio, config))
+    } else {
+        None
+    };
+    
+    Ok(render(format, config, &init.base_currency, opening.as_ref(), &trades).into_bytes())
+}
+
+/// Replace a file without readers ever seeing it half written
+fn write_atomic(path: &str, body: &[u8]) -> Result<()> {
+    let tmp = format!("{}.tmp", path);
+    std::fs::write(&tmp, body).with_context(|| format!("Failed to write {}", tmp))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path))?;
+    Ok(())
+}
+
+/// Keep `[ledger] journal_file` up to date, rewriting it whenever a trade
+/// is executed or the portfolio is re-initialized
+pub async fn run_journal_writer(board: Arc<Blackboard>) -> Result<()> {
+    let settings = board.config().ledger.clone();
+    let Some(path) = settings.journal_file.clone() else {
+        return Ok(());
+    };
+    let mut ticker = interval(Duration::from_secs(settings.write_interval_secs.max(1)));
+    let mut written = None;
+    
+    info!("📒 Journal writer started ({:?} journal at {})", settings.format, path);
+    
+    loop {
+        ticker.tick().await;
+        
+        let newest = board.get_trade_log_page(0, 1).await?.first().map(|t| t.id.clone());
+        let initialized_at = bootstrap::current(&board).await?.initialized_at;
+        let version = Some((newest, initialized_at));
+        if written == version {
+            continue;
+        }
+        
+        let body = journal(&board, settings.format, DateRange::default()).await?;
+        match write_atomic(&path, &body) {
+            Ok(()) => {
+                info!("📒 Journal {} updated ({} bytes)", path, body.len());
+                written = version;
+            }
+            Err(e) => warn!("Journal: {:#}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(id: &str, action: &str, stocks_delta: i64, bonds_delta: i64) -> TradeLogEntry {
+        TradeLogEntry {
+            id: id.to_string(),
+            timestamp: "2026-03-05T10:00:00Z".to_string(),
+            action: action.to_string(),
+            symbol: "SPY".to_string(),
+            amount: Decimal::from(250),
+            price: Decimal::from(580),
+            portfolio_value: Decimal::from(100_000),
+            drift_before: Decimal::new(60, 1),
+            drift_after: Decimal::new(1, 1),
+            explanation: None,
+            stocks_delta: Decimal::from(stocks_delta),
+            bonds_delta: Decimal::from(bonds_delta),
+            run_id: None,
+            skipped: None,
+            plan_hash: None,
+        }
+    }
+
+    fn opening(config: &Config) -> Opening {
+        let portfolio = holdings::split_balance(Decimal::from(100_000), config);
+        Opening::from_portfolio(NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(), &portfolio, config)
+    }
+
+    #[test]
+    fn test_ledger_journal() {
+        let config = Config::default();
+        let trades = [
+            trade("t1", "SELL $250.00 stocks, BUY $250.00 bonds", -250, 250),
+            // Partial fill: only the stocks leg executed
+            trade("t2", "BUY $100.00 stocks, SELL $100.00 bonds", 100, 0),
+        ];
+        let journal = render(LedgerFormat::Ledger, &config, "USD", Some(&opening(&config)), &trades);
+        
+        let stocks = account("Assets:DriftGuard", &config.portfolio.stocks_symbol);
+        assert!(journal.contains("2026-01-02 * DriftGuard\n    ; Opening balance\n"));
+        assert!(journal.contains("\n    Equity:Opening-Balances\n"));
+        assert!(journal.contains("    ; trade_id: t1\n"));
+        assert!(journal.lines().any(|l| l.starts_with(&format!("    {} ", stocks)) && l.ends_with(" -250.00 USD")));
+        assert!(journal.trim_end().ends_with("Assets:DriftGuard:Cash"));
+        assert!(!journal.contains("-0.00"));
+    }
+
+    #[test]
+    fn test_beancount_journal() {
+        let config = Config::default();
+        let legacy = trade("t\"1", "SELL $250.00 stocks, BUY $250.00 bonds", 0, 0);
+        assert_eq!(legs(&legacy, &config).iter().map(|(_, a)| *a).collect::<Vec<_>>(), vec![Decimal::from(-250), Decimal::from(250)]);
+        
+        let journal = render(LedgerFormat::Beancount, &config, "EUR", Some(&opening(&config)), &[legacy]);
+        assert!(journal.contains("option \"operating_currency\" \"EUR\"\n"));
+        assert!(journal.contains("2026-01-02 open Equity:Opening-Balances\n"));
+        assert!(journal.contains("2026-03-05 * \"DriftGuard\" \"SELL $250.00 stocks, BUY $250.00 bonds\"\n"));
+        assert!(journal.contains("  trade_id: \"t\\\"1\"\n"));
+        assert!(!journal.contains("Assets:DriftGuard:Cash"));
+        
+        assert_eq!(account("Assets:X", "^gspc"), "Assets:X:Gspc");
+        assert_eq!(account("Assets:X", "BRK.B"), "Assets:X:BRK-B");
+    }
+}
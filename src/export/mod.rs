@@ -0,0 +1,253 @@
+//! Data Export
+//!
+//! Reads the trade log and portfolio history off the blackboard page by
+//! page and encodes them as CSV or Parquet for analysis outside Redis
+//! (pandas, Excel), or the trades as a ledger-cli / beancount journal.
+//! Used by the `/api/export/*` routes and the
+//! `driftguard export` CLI subcommand.
+
+pub mod ledger;
+pub mod parquet;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use self::ledger::LedgerFormat;
+use crate::core::blackboard::TradeLogEntry;
+use crate::core::valuation::PortfolioHistoryPoint;
+use crate::core::Blackboard;
+
+/// Entries fetched from Redis per round trip
+const PAGE_SIZE: usize = 500;
+
+/// What to export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dataset {
+    Trades,
+    History,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+    Ledger,
+    Beancount,
+}
+
+impl Dataset {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Trades => "trades",
+            Self::History => "history",
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Parquet => "parquet",
+            Self::Ledger => "ledger",
+            Self::Beancount => "beancount",
+        }
+    }
+    
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Parquet => "application/vnd.apache.parquet",
+            Self::Ledger | Self::Beancount => "text/plain; charset=utf-8",
+        }
+    }
+    
+    /// Journals only exist for trades
+    pub fn supports(&self, dataset: Dataset) -> bool {
+        !matches!((dataset, self), (Dataset::History, Self::Ledger | Self::Beancount))
+    }
+}
+
+impl std::str::FromStr for Dataset {
+    type Err = anyhow::Error;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "trades" => Ok(Self::Trades),
+            "history" => Ok(Self::History),
+            other => bail!("unknown dataset '{}' (expected trades or history)", other),
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = anyhow::Error;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "parquet" => Ok(Self::Parquet),
+            "ledger" => Ok(Self::Ledger),
+            "beancount" => Ok(Self::Beancount),
+            other => bail!("unknown format '{}' (expected csv, parquet, ledger or beancount)", other),
+        }
+    }
+}
+
+/// Inclusive date filter on entry timestamps (UTC days)
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct DateRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl DateRange {
+    pub fn contains(&self, timestamp: &DateTime<Utc>) -> bool {
+        let day = timestamp.date_naive();
+        self.from.is_none_or(|from| day >= from) && self.to.is_none_or(|to| day <= to)
+    }
+    
+    /// True once entries (read newest first) are older than the range
+    fn is_before(&self, timestamp: &DateTime<Utc>) -> bool {
+        self.from.is_some_and(|from| timestamp.date_naive() < from)
+    }
+}
+
+/// Parse an RFC 3339 timestamp as stored on the blackboard
+pub fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Trade log entries within `range`, oldest first
+pub async fn collect_trades(board: &Blackboard, range: DateRange) -> Result<Vec<TradeLogEntry>> {
+    let mut offset = 0;
+    collect(range, |entry: &TradeLogEntry| entry.timestamp.clone(), || {
+        let page = board.get_trade_log_page(offset, PAGE_SIZE);
+        offset += PAGE_SIZE;
+        page
+    })
+    .await
+}
+
+/// Portfolio history samples within `range`, oldest first
+pub async fn collect_history(board: &Blackboard, range: DateRange) -> Result<Vec<PortfolioHistoryPoint>> {
+    let mut offset = 0;
+    collect(range, |point: &PortfolioHistoryPoint| point.timestamp.clone(), || {
+        let page = board.get_portfolio_history_page(offset, PAGE_SIZE);
+        offset += PAGE_SIZE;
+        page
+    })
+    .await
+}
+
+/// Page through a newest-first list, stopping once entries predate the range
+async fn collect<T, F, Fut>(range: DateRange, timestamp: impl Fn(&T) -> String, mut next_page: F) -> Result<Vec<T>>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<T>>>,
+{
+    let mut rows = Vec::new();
+    'pages: loop {
+        let page = next_page().await?;
+        if page.is_empty() {
+            break;
+        }
+        for row in page {
+            let Some(at) = parse_timestamp(&timestamp(&row)) else {
+                continue;
+            };
+            if range.is_before(&at) {
+                break 'pages;
+            }
+            if range.contains(&at) {
+                rows.push(row);
+            }
+        }
+    }
+    rows.reverse();
+    Ok(rows)
+}
+
+/// Encode rows as CSV with a header line
+pub fn to_csv<T: Serialize>(rows: &[T]) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    Ok(writer.into_inner().map_err(|e| e.into_error())?)
+}
+
+/// Fetch and encode a dataset
+pub async fn export(board: &Blackboard, dataset: Dataset, format: ExportFormat, range: DateRange) -> Result<Vec<u8>> {
+    match (dataset, format) {
+        (Dataset::Trades, ExportFormat::Csv) => to_csv(&collect_trades(board, range).await?),
+        (Dataset::Trades, ExportFormat::Parquet) => parquet::trades(&collect_trades(board, range).await?),
+        (Dataset::History, ExportFormat::Csv) => to_csv(&collect_history(board, range).await?),
+        (Dataset::History, ExportFormat::Parquet) => parquet::history(&collect_history(board, range).await?),
+        (Dataset::Trades, ExportFormat::Ledger) => ledger::journal(board, LedgerFormat::Ledger, range).await,
+        (Dataset::Trades, ExportFormat::Beancount) => ledger::journal(board, LedgerFormat::Beancount, range).await,
+        (Dataset::History, ExportFormat::Ledger | ExportFormat::Beancount) => {
+            bail!("history has no journal form; export trades as {}", format.extension())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    
+    fn trade(timestamp: &str) -> TradeLogEntry {
+        TradeLogEntry {
+            id: timestamp.to_string(),
+            timestamp: timestamp.to_string(),
+            action: "BUY $100.00 stocks, SELL $100.00 bonds".to_string(),
+            symbol: "SPY".to_string(),
+            amount: Decimal::from(100),
+            price: Decimal::from(580),
+            portfolio_value: Decimal::from(100_000),
+            drift_before: Decimal::new(60, 1),
+            drift_after: Decimal::new(1, 1),
+            explanation: None,
+            stocks_delta: Decimal::from(100),
+            bonds_delta: Decimal::from(-100),
+            run_id: None,
+            skipped: None,
+            plan_hash: None,
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_collect_filters_and_orders_oldest_first() {
+        // Newest first, as stored on the blackboard, split across two pages
+        let mut pages = vec![
+            vec![trade("2026-03-05T10:00:00Z"), trade("2026-03-02T10:00:00Z")],
+            vec![trade("2026-03-01T10:00:00Z"), trade("2026-02-27T10:00:00Z")],
+        ]
+        .into_iter();
+        let range = DateRange {
+            from: NaiveDate::from_ymd_opt(2026, 3, 1),
+            to: NaiveDate::from_ymd_opt(2026, 3, 4),
+        };
+        
+        let rows = collect(range, |t: &TradeLogEntry| t.timestamp.clone(), || {
+            let page = pages.next().unwrap_or_default();
+            async move { Ok(page) }
+        })
+        .await
+        .unwrap();
+        
+        let ids: Vec<_> = rows.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["2026-03-01T10:00:00Z", "2026-03-02T10:00:00Z"]);
+    }
+    
+    #[test]
+    fn test_csv_has_header_and_rows() {
+        let csv = String::from_utf8(to_csv(&[trade("2026-03-05T10:00:00Z")]).unwrap()).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("id,timestamp,action,symbol,amount"));
+        assert!(lines.next().unwrap().contains("\"BUY $100.00 stocks, SELL $100.00 bonds\""));
+        assert!(lines.next().is_none());
+    }
+}
@@ -0,0 +1,178 @@
+//! Parquet encoding for exports
+//!
+//! Columns are written with the low-level parquet writer as a single
+//! uncompressed row group; exports are small enough that this keeps the
+//! dependency free of the Arrow stack.
+
+use anyhow::{anyhow, Result};
+use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+use super::parse_timestamp;
+use crate::core::blackboard::TradeLogEntry;
+use crate::core::money;
+use crate::core::valuation::PortfolioHistoryPoint;
+
+enum Values {
+    /// Milliseconds since the Unix epoch (UTC)
+    Timestamp(Vec<i64>),
+    Double(Vec<f64>),
+    OptionalDouble(Vec<Option<f64>>),
+    Utf8(Vec<String>),
+    OptionalUtf8(Vec<Option<String>>),
+}
+
+struct Column {
+    name: &'static str,
+    values: Values,
+}
+
+impl Column {
+    fn new(name: &'static str, values: Values) -> Self {
+        Self { name, values }
+    }
+    
+    fn schema_field(&self) -> String {
+        match self.values {
+            Values::Timestamp(_) => format!("REQUIRED INT64 {} (TIMESTAMP(MILLIS,true));", self.name),
+            Values::Double(_) => format!("REQUIRED DOUBLE {};", self.name),
+            Values::OptionalDouble(_) => format!("OPTIONAL DOUBLE {};", self.name),
+            Values::Utf8(_) => format!("REQUIRED BYTE_ARRAY {} (UTF8);", self.name),
+            Values::OptionalUtf8(_) => format!("OPTIONAL BYTE_ARRAY {} (UTF8);", self.name),
+        }
+    }
+}
+
+fn millis(timestamp: &str) -> i64 {
+    parse_timestamp(timestamp).map_or(0, |t| t.timestamp_millis())
+}
+
+fn byte_arrays<'a>(values: impl Iterator<Item = &'a String>) -> Vec<ByteArray> {
+    values.map(|v| ByteArray::from(v.as_str())).collect()
+}
+
+/// Encode columns (all the same length) as a Parquet file
+fn write(message: &str, columns: Vec<Column>) -> Result<Vec<u8>> {
+    let fields: String = columns.iter().map(Column::schema_field).collect();
+    let schema = Arc::new(parse_message_type(&format!("message {} {{ {} }}", message, fields))?);
+    let mut writer = SerializedFileWriter::new(Vec::new(), schema, Arc::new(WriterProperties::builder().build()))?;
+    
+    let mut row_group = writer.next_row_group()?;
+    for column in columns {
+        let mut column_writer = row_group
+            .next_column()?
+            .ok_or_else(|| anyhow!("schema has no column '{}'", column.name))?;
+        match column.values {
+            Values::Timestamp(v) => {
+                column_writer.typed::<Int64Type>().write_batch(&v, None, None)?;
+            }
+            Values::Double(v) => {
+                column_writer.typed::<DoubleType>().write_batch(&v, None, None)?;
+            }
+            Values::OptionalDouble(v) => {
+                let definition_levels: Vec<i16> = v.iter().map(|x| i16::from(x.is_some())).collect();
+                let present: Vec<f64> = v.iter().flatten().copied().collect();
+                column_writer.typed::<DoubleType>().write_batch(&present, Some(&definition_levels), None)?;
+            }
+            Values::Utf8(v) => {
+                column_writer.typed::<ByteArrayType>().write_batch(&byte_arrays(v.iter()), None, None)?;
+            }
+            Values::OptionalUtf8(v) => {
+                let definition_levels: Vec<i16> = v.iter().map(|s| i16::from(s.is_some())).collect();
+                let present = byte_arrays(v.iter().flatten());
+                column_writer.typed::<ByteArrayType>().write_batch(&present, Some(&definition_levels), None)?;
+            }
+        }
+        column_writer.close()?;
+    }
+    row_group.close()?;
+    
+    Ok(writer.into_inner()?)
+}
+
+/// Trade log as Parquet
+pub fn trades(rows: &[TradeLogEntry]) -> Result<Vec<u8>> {
+    let doubles = |f: fn(&TradeLogEntry) -> Decimal| Values::Double(rows.iter().map(|r| money::to_f64(f(r))).collect());
+    let strings = |f: fn(&TradeLogEntry) -> &String| Values::Utf8(rows.iter().map(|r| f(r).clone()).collect());
+    
+    write("trades", vec![
+        Column::new("id", strings(|r| &r.id)),
+        Column::new("timestamp", Values::Timestamp(rows.iter().map(|r| millis(&r.timestamp)).collect())),
+        Column::new("action", strings(|r| &r.action)),
+        Column::new("symbol", strings(|r| &r.symbol)),
+        Column::new("amount", doubles(|r| r.amount)),
+        Column::new("price", doubles(|r| r.price)),
+        Column::new("portfolio_value", doubles(|r| r.portfolio_value)),
+        Column::new("drift_before", doubles(|r| r.drift_before)),
+        Column::new("drift_after", doubles(|r| r.drift_after)),
+        Column::new("explanation", Values::OptionalUtf8(rows.iter().map(|r| r.explanation.clone()).collect())),
+        Column::new("run_id", Values::OptionalUtf8(rows.iter().map(|r| r.run_id.clone()).collect())),
+        Column::new("plan_hash", Values::OptionalUtf8(rows.iter().map(|r| r.plan_hash.clone()).collect())),
+    ])
+}
+
+/// Portfolio history as Parquet
+pub fn history(rows: &[PortfolioHistoryPoint]) -> Result<Vec<u8>> {
+    let doubles = |f: fn(&PortfolioHistoryPoint) -> f64| Values::Double(rows.iter().map(f).collect());
+    let optional = |f: fn(&PortfolioHistoryPoint) -> Option<f64>| Values::OptionalDouble(rows.iter().map(f).collect());
+    
+    write("portfolio_history", vec![
+        Column::new("timestamp", Values::Timestamp(rows.iter().map(|r| millis(&r.timestamp)).collect())),
+        Column::new("total_value", doubles(|r| r.total_value)),
+        Column::new("stocks_value", doubles(|r| r.stocks_value)),
+        Column::new("bonds_value", doubles(|r| r.bonds_value)),
+        Column::new("stocks_pct", doubles(|r| r.stocks_pct)),
+        Column::new("bonds_pct", doubles(|r| r.bonds_pct)),
+        Column::new("target_stocks_pct", doubles(|r| r.target_stocks_pct)),
+        Column::new("drift_pct", doubles(|r| r.drift_pct)),
+        Column::new("drawdown_pct", doubles(|r| r.drawdown_pct)),
+        Column::new("no_rebalance_value", optional(|r| r.no_rebalance_value)),
+        Column::new("calendar_rebalance_value", optional(|r| r.calendar_rebalance_value)),
+        Column::new("benchmark_value", optional(|r| r.benchmark_value)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::RowAccessor;
+    
+    #[test]
+    fn test_trades_round_trip() {
+        let row = |id: &str, explanation: Option<&str>| TradeLogEntry {
+            id: id.to_string(),
+            timestamp: "2026-03-05T10:00:00Z".to_string(),
+            action: "BUY".to_string(),
+            symbol: "SPY".to_string(),
+            amount: Decimal::from(100),
+            price: Decimal::from(580),
+            portfolio_value: Decimal::from(100_000),
+            drift_before: Decimal::new(60, 1),
+            drift_after: Decimal::new(1, 1),
+            explanation: explanation.map(str::to_string),
+            stocks_delta: Decimal::from(100),
+            bonds_delta: Decimal::from(-100),
+            run_id: None,
+            skipped: None,
+            plan_hash: None,
+        };
+        let encoded = trades(&[row("a", Some("drift")), row("b", None)]).unwrap();
+        
+        let reader = SerializedFileReader::new(bytes::Bytes::from(encoded)).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 2);
+        assert_eq!(metadata.schema_descr().num_columns(), 12);
+        
+        let explanations: Vec<_> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap().get_string(9).ok().cloned())
+            .collect();
+        assert_eq!(explanations, vec![Some("drift".to_string()), None]);
+    }
+}
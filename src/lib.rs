@@ -0,0 +1,25 @@
+//! DriftGuard: Stigmergic Portfolio Stabilizer
+//!
+//! Library crate exposing the swarm building blocks (pheromone physics,
+//! blackboard, agents, market data, execution, export, dashboard server
+//! and event bridge) so they can be reused by the binary, benchmarks and
+//! integration tooling. The `client` feature adds a typed SDK for the
+//! dashboard protocol, the `python` feature a PyO3 module, and the `nats`
+//! and `kafka` features the event bridge backends.
+
+// The dashboard's chain of warp routes outgrows the default limit
+#![recursion_limit = "256"]
+
+pub mod core;
+pub mod agents;
+pub mod market;
+pub mod execution;
+pub mod export;
+pub mod server;
+pub mod bridge;
+
+#[cfg(feature = "client")]
+pub mod client;
+
+#[cfg(feature = "python")]
+mod python;
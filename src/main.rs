@@ -8,96 +8,690 @@
 //! downstream agents to go dormant - failing safely rather than
 //! acting on stale data.
 
-pub mod core;
-pub mod agents;
-pub mod market;
-pub mod server;
+// Spawning the dashboard server instantiates its chain of warp routes
+#![recursion_limit = "256"]
 
 use anyhow::Result;
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::{info, Level};
-use tracing_subscriber::FmtSubscriber;
+use tracing::info;
 
-use crate::agents::{Agent, AnalystAgent, GuardianAgent, SensorAgent, TraderAgent};
-use crate::core::blackboard::PortfolioState;
-use crate::core::{Blackboard, Config};
-use crate::market::AlphaVantageProvider;
-use crate::server::start_websocket_server;
+use driftguard::bridge;
+use driftguard::agents::{Agent, AnalystAgent, ForecastAgent, GuardianAgent, SensorAgent, TraderAgent};
+use driftguard::core::audit::{self, AuditEntry, ClientIdentity};
+use driftguard::core::config::{profile_path, FinnhubConfig};
+use driftguard::core::valuation;
+use driftguard::core::pipeline::AgentRole;
+use driftguard::core::bootstrap::{self, PortfolioInit};
+use driftguard::core::error::DriftGuardError;
+use driftguard::core::runs::{self, RunMetadata, RunProviders};
+use driftguard::core::runtime::{self, RuntimeMonitor};
+use driftguard::core::simulation::{self, SimulationRng};
+use driftguard::core::{alerts, auth, correlation, decay, federation, glide_path, groups, holdings, logging, migrate, mode, money, notify_queue, plan, retention, risk_index, secrets, sim_clock, soak, statements, target_engine, Blackboard, Config};
+use driftguard::execution::{reconcile, Executor, QueuedExecutor, SimulatedExecutor};
+use driftguard::export::{self, ledger, DateRange, Dataset, ExportFormat};
+use driftguard::market::vix_feed::{VixFeed, VixFeedKind};
+use driftguard::market::vix_history::{self, VixHistory, VixReplay};
+use driftguard::market::vol_proxy::{ProxyKind, VolProxy};
+use driftguard::market::daily_history;
+use driftguard::market::listing::{self, Listing};
+use driftguard::market::{
+    self, AlphaVantageProvider, FailoverProvider, FinnhubProvider, ListedMarket, MarketDataProvider, PolygonProvider, VixSource,
+    YahooFinanceProvider,
+};
+use driftguard::server::start_websocket_server;
+
+#[derive(Parser)]
+#[command(name = "driftguard", version, about = "Stigmergic Portfolio Stabilizer")]
+struct Cli {
+    /// Configuration file
+    #[arg(long, global = true, default_value = "config.toml")]
+    config: PathBuf,
+    /// Swarm name on a shared Redis (overrides `[swarm] name`)
+    #[arg(long, global = true)]
+    swarm: Option<String>,
+    /// Profile merged over the configuration, e.g. `live` for `profiles/live.toml`
+    #[arg(long, global = true, env = "DRIFTGUARD_PROFILE")]
+    profile: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the agent swarm and dashboard server (default)
+    Run {
+        /// Only run these agents, e.g. `sensor,analyst` (overrides `[agent] roles`)
+        #[arg(long, value_delimiter = ',')]
+        agents: Option<Vec<AgentRole>>,
+        /// Don't bootstrap state or run background services (another process does)
+        #[arg(long)]
+        no_services: bool,
+        /// Seed of simulated prices and fills, to replay a run (overrides `[simulation] seed`)
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Run only the dashboard server (no agents or services)
+    Serve {
+        /// Serve read-only endpoints from `REDIS_REPLICA_URL` (analytics replica)
+        #[arg(long)]
+        read_only: bool,
+    },
+    /// Export the trade log or portfolio history from the blackboard
+    Export {
+        /// trades | history
+        dataset: Dataset,
+        /// csv | parquet | ledger | beancount (the last two for trades only)
+        #[arg(long, default_value = "csv")]
+        format: ExportFormat,
+        /// First day to include (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// Last day to include (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        to: Option<NaiveDate>,
+        /// Output file (default `<dataset>.<format>`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Set the starting cash, holdings and base currency and restart the
+    /// portfolio from them (also works while the swarm is running)
+    Init {
+        /// Starting cash, read exactly (default `initial_balance`, or the
+        /// export's cash with --holdings)
+        #[arg(long)]
+        cash: Option<Decimal>,
+        /// Base currency, e.g. EUR (default `[portfolio] base_currency`)
+        #[arg(long)]
+        currency: Option<String>,
+        /// Initial positions from a broker positions CSV
+        #[arg(long)]
+        holdings: Option<PathBuf>,
+    },
+    /// Start from actual holdings: import a broker positions export
+    Import {
+        /// Positions CSV (Fidelity, Schwab, Vanguard or generic)
+        #[arg(long)]
+        holdings: PathBuf,
+    },
+    /// Download CBOE's daily VIX history for `vix_source = "history"`
+    FetchVix {
+        /// Output file (default `[market.vix_history] file`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Print the rebalance the swarm would execute right now, without trading
+    Plan {
+        /// Print the plan as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Copy this swarm's blackboard to another store and verify the copy
+    Migrate {
+        /// Source store (default `REDIS_URL`)
+        #[arg(long)]
+        from: Option<String>,
+        /// Destination store, e.g. redis://new-host:6379
+        #[arg(long)]
+        to: String,
+        /// Swarm name at the destination (default: unchanged)
+        #[arg(long)]
+        to_swarm: Option<String>,
+        /// Replace keys the destination swarm already has
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Run the swarm on simulated quotes at accelerated time, checking
+    /// invariants until the run ends or one is broken
+    Soak {
+        /// Simulated hours to run (overrides `[soak] hours`)
+        #[arg(long)]
+        hours: Option<f64>,
+        /// Simulated seconds per second (overrides `[soak] speed`)
+        #[arg(long)]
+        speed: Option<f64>,
+        /// Seed of simulated prices and fills, to replay a failure
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Failure report file (overrides `[soak] report_file`)
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    
     // Load environment variables
     dotenv::dotenv().ok();
     
-    // Initialize logging
-    FmtSubscriber::builder()
-        .with_max_level(Level::DEBUG)
-        .with_target(true)
-        .with_thread_ids(false)
-        .with_file(false)
-        .with_line_number(false)
-        .pretty()
-        .init();
+    // Initialize logging (`[logging]` levels apply once config loads)
+    logging::init("debug");
     
     info!("🚀 DriftGuard: Stigmergic Portfolio Stabilizer");
     info!("================================================");
     
     // Load configuration
-    let config = Arc::new(Config::load_default().unwrap_or_else(|e| {
-        tracing::warn!("Failed to load config.toml: {}. Using defaults.", e);
-        Config::default()
-    }));
+    let mut config = match &cli.profile {
+        // A profile asked for by name is never replaced by the defaults
+        Some(profile) => {
+            let config = Config::load_profile(&cli.config, profile)?;
+            info!("🗂️ Config profile '{}' ({})", profile, profile_path(&cli.config, profile).display());
+            config
+        }
+        None => Config::load(&cli.config).unwrap_or_else(|e| {
+            tracing::warn!("Failed to load {}: {}. Using defaults.", cli.config.display(), e);
+            Config::default()
+        }),
+    };
+    if let Some(swarm) = cli.swarm {
+        config.swarm.name = swarm;
+    }
+    let mut cli_seed = None;
+    if let Some(Command::Run { agents, no_services, seed }) = &cli.command {
+        if let Some(agents) = agents {
+            config.agent.roles = agents.clone();
+        }
+        if *no_services {
+            config.agent.run_services = false;
+        }
+        cli_seed = *seed;
+    }
+    if let Some(Command::Serve { read_only: true }) = &cli.command {
+        config.dashboard.read_only = true;
+    }
+    let mut soak_replay = String::new();
+    if let Some(Command::Soak { hours, speed, seed, report }) = &cli.command {
+        if let Some(hours) = hours {
+            config.soak.hours = *hours;
+        }
+        if let Some(speed) = speed {
+            config.soak.speed = *speed;
+        }
+        if let Some(report) = report {
+            config.soak.report_file = report.display().to_string();
+        }
+        anyhow::ensure!(config.soak.speed.is_finite() && config.soak.speed > 0.0, "soak speed must be positive");
+        cli_seed = Some(simulation::resolve_seed(*seed, config.simulation.seed).0);
+        soak_replay = format!(
+            "driftguard --config {}{} --swarm {} soak --hours {} --speed {} --seed {}",
+            cli.config.display(),
+            cli.profile.as_ref().map(|profile| format!(" --profile {}", profile)).unwrap_or_default(),
+            config.swarm.name,
+            config.soak.hours,
+            config.soak.speed,
+            cli_seed.unwrap_or_default()
+        );
+        soak::configure(&mut config);
+    }
+    secrets::register_configured(&config);
+    if let Err(e) = logging::apply(&config.logging, &BTreeMap::new()) {
+        tracing::warn!("Ignoring [logging] levels: {:#}", e);
+    }
+    let config = Arc::new(config);
     
-    // Get Redis URL and API key from environment
+    // Needs no Redis
+    if let Some(Command::FetchVix { output }) = &cli.command {
+        let output = output.clone().unwrap_or_else(|| PathBuf::from(&config.market.vix_history.file));
+        let csv = vix_history::download_cboe().await?;
+        if let Some(dir) = output.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&output, &csv)?;
+        info!("📈 Saved CBOE VIX history to {}", output.display());
+        return Ok(());
+    }
+    
+    // Get Redis URL from environment (a read-only server prefers the replica)
     let redis_url = std::env::var("REDIS_URL")
         .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
-    let api_key = std::env::var("ALPHA_VANTAGE_API_KEY")
-        .unwrap_or_else(|_| "demo".to_string());
-    let ws_port: u16 = std::env::var("WS_PORT")
-        .unwrap_or_else(|_| "8080".to_string())
-        .parse()
-        .unwrap_or(8080);
+    
+    // Connects to both stores itself
+    if let Some(Command::Migrate { from, to, to_swarm, overwrite }) = &cli.command {
+        let from = from.as_deref().unwrap_or(&redis_url);
+        let to_swarm = to_swarm.as_deref().unwrap_or(&config.swarm.name);
+        let report = migrate::migrate(from, to, &config.swarm.name, to_swarm, *overwrite).await?;
+        if !report.mismatched.is_empty() {
+            anyhow::bail!("keys failed verification: {}", report.mismatched.join(", "));
+        }
+        return Ok(());
+    }
+    let redis_url = match std::env::var("REDIS_REPLICA_URL") {
+        Ok(replica) if config.dashboard.read_only => replica,
+        _ => redis_url,
+    };
     
     // Initialize blackboard (Redis connection)
     let board = Arc::new(Blackboard::new(&redis_url, config.clone()).await?);
     
-    // Initialize portfolio state
-    let initial_portfolio = PortfolioState {
-        total_value: config.portfolio.initial_balance,
-        stocks_value: config.portfolio.initial_balance * (config.portfolio.default_stocks_pct / 100.0),
-        bonds_value: config.portfolio.initial_balance * (config.portfolio.default_bonds_pct / 100.0),
-        stocks_pct: config.portfolio.default_stocks_pct,
-        bonds_pct: config.portfolio.default_bonds_pct,
-        last_trade_time: None,
+    // A server alone quotes nothing; it guards the swarm its config runs
+    if let Some(Command::Serve { .. }) = &cli.command {
+        let api_key = std::env::var(market::api_key_env(&config.market)).ok().filter(|key| !key.is_empty());
+        let provider_name = market::resolve_provider(&config.market, api_key.as_deref());
+        let live = quotes_live(provider_name, api_key.as_deref(), &failover_chain(&config)?);
+        auth::check_required(&config, auth::live_execution(&config, live))?;
+    }
+    
+    // Follow runtime log level changes made through the API
+    if matches!(cli.command, None | Some(Command::Run { .. }) | Some(Command::Serve { .. })) {
+        tokio::spawn(logging::watch(board.clone()));
+    }
+    
+    match cli.command {
+        None | Some(Command::Run { .. }) => {
+            let until = async { Ok(tokio::signal::ctrl_c().await?) };
+            run_swarm(config, board, cli_seed, false, until).await
+        }
+        Some(Command::Serve { .. }) => start_websocket_server(dashboard_port(), board).await,
+        Some(Command::Export { dataset, format, from, to, output }) => {
+            let output = output.unwrap_or_else(|| PathBuf::from(format!("{}.{}", dataset.name(), format.extension())));
+            let body = export::export(&board, dataset, format, DateRange { from, to }).await?;
+            std::fs::write(&output, &body)?;
+            info!("📤 Exported {} ({} bytes) to {}", dataset.name(), body.len(), output.display());
+            Ok(())
+        }
+        Some(Command::Init { cash, currency, holdings: path }) => {
+            let mut init = PortfolioInit { starting_cash: cash, base_currency: currency, ..Default::default() };
+            if let Some(path) = path {
+                let text = std::fs::read_to_string(&path)?;
+                let imported = holdings::parse_broker_csv(&text, &path.display().to_string())?;
+                init.starting_cash = init.starting_cash.or(Some(money::from_f64(imported.cash)));
+                init.holdings = imported.holdings;
+            }
+            let entry = AuditEntry::new("init_portfolio", &ClientIdentity::cli())
+                .request(&init)
+                .previous(board.get_portfolio_state().await?);
+            match bootstrap::initialize(&board, &init, "cli").await {
+                Ok((_, portfolio)) => {
+                    audit::record(&board, entry.new_value(portfolio)).await;
+                    Ok(())
+                }
+                Err(e) => {
+                    audit::record(&board, entry.failed(format!("{:#}", e))).await;
+                    Err(e)
+                }
+            }
+        }
+        Some(Command::Import { holdings: path }) => {
+            let text = std::fs::read_to_string(&path)?;
+            let imported = holdings::parse_broker_csv(&text, &path.display().to_string())?;
+            let entry = AuditEntry::new("import_holdings", &ClientIdentity::cli())
+                .request(serde_json::json!({"source": imported.source}))
+                .previous(board.get_portfolio_state().await?);
+            match holdings::apply_import(&board, &imported).await {
+                Ok(portfolio) => {
+                    audit::record(&board, entry.new_value(portfolio)).await;
+                    Ok(())
+                }
+                Err(e) => {
+                    audit::record(&board, entry.failed(format!("{:#}", e))).await;
+                    Err(e)
+                }
+            }
+        }
+        Some(Command::Plan { json }) => {
+            let Some(plan) = plan::plan(&board).await? else {
+                anyhow::bail!("no portfolio yet; run `driftguard init` or start the swarm first");
+            };
+            if json {
+                println!("{}", serde_json::to_string_pretty(&plan)?);
+            } else {
+                print!("{}", plan.render(board.swarm_name(), config.reporting.locale));
+            }
+            Ok(())
+        }
+        Some(Command::Soak { .. }) => {
+            let seed = cli_seed.unwrap_or_default();
+            let soak_board = board.clone();
+            let report_file = config.soak.report_file.clone();
+            let until = async move {
+                tokio::select! {
+                    report = soak::run(soak_board, seed, soak_replay) => match report? {
+                        Some(report) => {
+                            std::fs::write(&report_file, serde_json::to_string_pretty(&report)?)?;
+                            anyhow::bail!("soak test failed ({} violations); report written to {}",
+                                report.violations.len(), report_file);
+                        }
+                        None => Ok(()),
+                    },
+                    signal = tokio::signal::ctrl_c() => Ok(signal?),
+                }
+            };
+            run_swarm(config, board, cli_seed, true, until).await
+        }
+        Some(Command::FetchVix { .. }) => Ok(()), // handled before connecting
+        Some(Command::Migrate { .. }) => Ok(()),
+    }
+}
+
+/// Dashboard server port (`WS_PORT`, default 8080)
+fn dashboard_port() -> u16 {
+    std::env::var("WS_PORT")
+        .unwrap_or_else(|_| "8080".to_string())
+        .parse()
+        .unwrap_or(8080)
+}
+
+/// `[market.providers] chain` with each provider's key, leaving out those
+/// without one (Yahoo Finance needs none)
+fn failover_chain(config: &Config) -> Result<Vec<(String, Option<String>)>> {
+    let mut chain = Vec::new();
+    for name in &config.market.providers.chain {
+        if !matches!(name.as_str(), "alpha_vantage" | "polygon" | "finnhub" | "yahoo") {
+            anyhow::bail!("unknown provider '{}' in [market.providers] chain", name);
+        }
+        let key = std::env::var(market::provider_key_env(&config.market, name))
+            .ok()
+            .filter(|key| !key.is_empty() && key != "demo");
+        if key.is_none() && name != "yahoo" {
+            tracing::warn!("📡 No {} key: left out of the provider chain", name);
+            continue;
+        }
+        chain.push((name.clone(), key));
+    }
+    Ok(chain)
+}
+
+/// Whether quotes are real: a failover chain (only members with a key, or
+/// Yahoo Finance, are in it), Yahoo Finance itself, or a keyed provider
+fn quotes_live(provider_name: &str, api_key: Option<&str>, chain: &[(String, Option<String>)]) -> bool {
+    !chain.is_empty() || provider_name == "yahoo" || api_key.is_some_and(|key| key != "demo")
+}
+
+/// The named market data provider; `proxy_symbol`, the VIX proxy's, is
+/// streamed along with the listings
+fn market_provider(
+    name: &str,
+    api_key: Option<String>,
+    config: &Config,
+    listings: &[Listing],
+    rng: SimulationRng,
+    proxy_symbol: Option<&str>,
+) -> Arc<dyn MarketDataProvider> {
+    // A provider's own VIX, if it has one, also backs up CBOE or FRED
+    let index_vix = config.market.vix_source == name || VixFeedKind::from_source(&config.market.vix_source).is_some();
+    match name {
+        "polygon" => {
+            let settings = &config.market.polygon;
+            let mut provider = PolygonProvider::new(api_key, settings).with_rng(rng);
+            if index_vix {
+                provider = provider.with_index_vix();
+            }
+            info!("📡 Quotes from Polygon.io (cached {}s)", settings.cache_ttl_secs);
+            Arc::new(provider)
+        }
+        "finnhub" => {
+            // The stream feeds the Sensor; other processes poll on demand
+            let settings = FinnhubConfig {
+                stream: config.market.finnhub.stream && config.agent.roles.contains(&AgentRole::Sensor),
+                ..config.market.finnhub.clone()
+            };
+            let mut symbols: Vec<String> =
+                listings.iter().filter_map(|listing| listing.provider_symbol("finnhub")).collect();
+            symbols.extend(proxy_symbol.map(str::to_string));
+            let provider = Arc::new(FinnhubProvider::new(api_key, &settings).with_rng(rng));
+            let streaming = provider.price_updates().is_some();
+            tokio::spawn(provider.clone().stream(symbols));
+            info!("📡 Quotes from Finnhub ({}, polled quotes cached {}s)",
+                if streaming { "trades streamed" } else { "not streaming" }, settings.cache_ttl_secs);
+            provider
+        }
+        "yahoo" => {
+            let settings = &config.market.yahoo;
+            let mut provider = YahooFinanceProvider::new(settings).with_rng(rng);
+            if index_vix {
+                provider = provider.with_index_vix();
+            }
+            info!("📡 Quotes from Yahoo Finance (cached {}s)", settings.cache_ttl_secs);
+            Arc::new(provider)
+        }
+        _ => Arc::new(AlphaVantageProvider::new(api_key.unwrap_or_else(|| "demo".to_string())).with_rng(rng)),
+    }
+}
+
+/// Bootstrap the blackboard and run the swarm until `until` completes,
+/// returning its outcome (`simulated` ignores any API key)
+async fn run_swarm(
+    config: Arc<Config>,
+    board: Arc<Blackboard>,
+    cli_seed: Option<u64>,
+    simulated: bool,
+    until: impl std::future::Future<Output = Result<()>>,
+) -> Result<()> {
+    // Get API key and dashboard port from environment
+    let api_key = std::env::var(market::api_key_env(&config.market))
+        .ok()
+        .filter(|key| !key.is_empty() && !simulated);
+    let ws_port = dashboard_port();
+    
+    let roles = &config.agent.roles;
+    board.start_metrics_writer();
+    
+    // Initialize portfolio state (imported holdings if present). When the
+    // swarm is split across processes only the services process does this,
+    // so restarting e.g. the Sensor host doesn't reset the portfolio.
+    if config.agent.run_services {
+        bootstrap::bootstrap(&board).await?;
+    }
+    
+    // Exchange-qualified symbols, checked before anything is quoted
+    let listings = listing::resolve_all(&config.assets())?;
+    if !matches!(config.market.provider.as_str(), "alpha_vantage" | "polygon" | "finnhub" | "yahoo") {
+        tracing::warn!("Unknown market provider '{}'. Using Alpha Vantage.", config.market.provider);
+    }
+    let provider_name = market::resolve_provider(&config.market, api_key.as_deref());
+    if provider_name != market::provider_name(&config.market) {
+        tracing::warn!("📡 No {} key: quoting from Yahoo Finance (unofficial and rate-limited) instead of simulating",
+            market::provider_name(&config.market));
+    }
+    // A failover chain, when configured, replaces the single provider
+    let chain = if simulated { Vec::new() } else { failover_chain(&config)? };
+    if chain.is_empty() && !config.market.providers.chain.is_empty() && !simulated {
+        tracing::warn!("📡 No provider of [market.providers] chain has a key; quoting from {}", provider_name);
+    }
+    if !simulated {
+        let live = quotes_live(provider_name, api_key.as_deref(), &chain);
+        auth::check_required(&config, auth::live_execution(&config, live))?;
+    }
+    let quoted_by: Vec<&str> = if chain.is_empty() {
+        vec![provider_name]
+    } else {
+        chain.iter().map(|(name, _)| name.as_str()).collect()
     };
-    board.set_portfolio_state(&initial_portfolio).await?;
-    board.set_target_allocation(
-        config.portfolio.default_stocks_pct,
-        config.portfolio.default_bonds_pct,
-    ).await?;
-    
-    info!("📊 Initial portfolio: ${:.2} ({:.0}% stocks / {:.0}% bonds)",
-        initial_portfolio.total_value,
-        initial_portfolio.stocks_pct,
-        initial_portfolio.bonds_pct
-    );
+    let market_name = quoted_by.join(">");
+    let unquoted: Vec<&str> = listings
+        .iter()
+        .filter(|listing| quoted_by.iter().all(|name| listing.provider_symbol(name).is_none()))
+        .map(|listing| listing.symbol.as_str())
+        .collect();
+    let keyed = if chain.is_empty() {
+        api_key.as_deref().is_some_and(|key| key != "demo")
+    } else {
+        chain.iter().any(|(_, key)| key.is_some())
+    };
+    if !unquoted.is_empty() {
+        if keyed {
+            anyhow::bail!("{} doesn't quote {}", market_name, unquoted.join(", "));
+        }
+        tracing::warn!("{} doesn't quote {}; their prices will be simulated", market_name, unquoted.join(", "));
+    }
+    
+    // One seed drives every simulated draw, so the run can be replayed
+    let (seed, seed_source) = simulation::resolve_seed(cli_seed, config.simulation.seed);
+    
+    // VIX replayed from history, derived from a proxy or read from CBOE or
+    // FRED, whichever provider quotes
+    let (mut vix_replay, mut vol_proxy, mut vix_feed) = (None, None, None);
+    if config.market.vix_source == "history" {
+        let settings = &config.market.vix_history;
+        let mut history = VixHistory::load(&settings.file)?;
+        if let Some(start) = settings.start {
+            history = history.starting_at(start);
+        }
+        info!("📈 Replaying {} VIX observations from {}, one every {}s",
+            history.observations().len(), settings.file, settings.step_secs);
+        // On the swarm's simulated clock, which the API can speed up, pause and jump
+        let clock = sim_clock::attach(&board, config.agent.run_services).await?;
+        tokio::spawn(sim_clock::watch(board.clone(), clock.clone()));
+        let step = std::time::Duration::from_secs(settings.step_secs);
+        vix_replay = Some(VixReplay::new(history, step).with_clock(clock));
+    } else if let Some(kind) = ProxyKind::from_source(&config.market.vix_source) {
+        let proxy = VolProxy::new(kind, &config.market.vix_proxy)?;
+        info!("📈 Deriving VIX from {} ({:?} proxy)", proxy.symbol(), proxy.kind());
+        vol_proxy = Some(proxy);
+    } else if let Some(kind) = VixFeedKind::from_source(&config.market.vix_source) {
+        let settings = &config.market.vix_feed;
+        info!("📈 Reading VIX from {} ({})", kind.name(),
+            if settings.simulate_on_failure { "simulated while unavailable" } else { "no simulated fallback" });
+        vix_feed = Some(VixFeed::new(kind, settings));
+    }
     
     // Initialize market data provider
-    let market: Arc<dyn crate::market::MarketDataProvider> = Arc::new(
-        AlphaVantageProvider::new(&api_key)
-    );
+    let market_rng = SimulationRng::new(seed, simulation::MARKET_STREAM);
+    let proxy_symbol = vol_proxy.as_ref().map(VolProxy::symbol);
+    let quoting: Arc<dyn MarketDataProvider> = if chain.is_empty() {
+        let provider = market_provider(provider_name, api_key.clone(), &config, &listings, market_rng, proxy_symbol);
+        Arc::new(ListedMarket::new(provider, provider_name, listings.clone(), board.clone()))
+    } else {
+        // Each provider quotes listings in its own format
+        let members = chain
+            .iter()
+            .map(|(name, key)| {
+                let rng = SimulationRng::new(seed, &format!("{}:{}", simulation::MARKET_STREAM, name));
+                let provider = market_provider(name, key.clone(), &config, &listings, rng, proxy_symbol);
+                let listed: Arc<dyn MarketDataProvider> =
+                    Arc::new(ListedMarket::new(provider, name, listings.clone(), board.clone()));
+                (name.clone(), listed)
+            })
+            .collect();
+        let provider = FailoverProvider::new(members, &config.market.providers).with_rng(market_rng);
+        info!("📡 Quotes from the first of {} to answer (skipped {}s after {} failures)",
+            market_name, config.market.providers.cooldown_secs, config.market.providers.max_failures);
+        Arc::new(provider)
+    };
+    // VIX from the configured source, whichever provider or chain quotes
+    let mut market = VixSource::new(quoting);
+    if let Some(replay) = vix_replay {
+        market = market.with_vix_history(replay);
+    }
+    if let Some(proxy) = vol_proxy {
+        market = market.with_vol_proxy(proxy);
+    }
+    if let Some(feed) = vix_feed {
+        market = market.with_vix_feed(feed);
+    }
+    let market: Arc<dyn MarketDataProvider> = Arc::new(market);
+    let vix_source = config.market.vix_source.as_str();
+    if matches!(vix_source, "polygon" | "yahoo") && !quoted_by.contains(&vix_source) {
+        tracing::warn!("vix_source = \"{}\" needs quotes from {}; VIX will be simulated", vix_source, vix_source);
+    }
+    // Seed history-dependent features with daily closes
+    if config.agent.run_services && config.warmup.history_days > 0 {
+        match daily_history::load(&board, market.as_ref()).await {
+            Ok(fetched) => info!("📚 Daily history ready ({} symbols fetched, others cached today)", fetched),
+            Err(e) => tracing::warn!("📚 Daily history unavailable: {:#}", e),
+        }
+    }
+    
+    // Check the sniff/deposit wiring before the agents start
+    for warning in config.pipeline.warnings() {
+        tracing::warn!("🔌 Pipeline: {}", warning);
+    }
+    for warning in groups::warnings(&config.portfolio.groups) {
+        tracing::warn!("🗂️ Asset groups: {}", warning);
+    }
     
-    // Create agents
-    let sensor = Arc::new(SensorAgent::new(config.clone(), market.clone()));
-    let analyst = Arc::new(AnalystAgent::new(config.clone()));
-    let guardian = Arc::new(GuardianAgent::new(config.clone(), market.clone()));
-    let trader = Arc::new(TraderAgent::new(config.clone()));
+    // Create the agents this process runs
+    let mut executor: Arc<dyn Executor> = Arc::new(
+        SimulatedExecutor::from_config(&config.execution)
+            .with_rng(SimulationRng::new(seed, simulation::EXECUTION_STREAM))
+    );
+    if config.execution.queue.enabled {
+        executor = Arc::new(QueuedExecutor::new(executor, &config.execution.queue).with_dead_letters(board.clone()));
+    }
+    let mut agents: Vec<Arc<dyn Agent>> = Vec::new();
     
     info!("🐝 Initializing agent swarm...");
-    info!("  👁️  Sensor  - Ingests market data");
-    info!("  🧠 Analyst - Calculates drift");
-    info!("  🛡️  Guardian - Volatility circuit breaker");
-    info!("  💰 Trader  - Executes trades");
+    if roles.contains(&AgentRole::Sensor) {
+        let mut sensor = SensorAgent::new(config.clone(), market.clone());
+        let cross_check = &config.market.cross_check;
+        if cross_check.enabled {
+            let key = |env: &str| std::env::var("CROSS_CHECK_API_KEY").or_else(|_| std::env::var(env)).ok();
+            let secondary: Option<Arc<dyn MarketDataProvider>> = match cross_check.provider.as_str() {
+                "alpha_vantage" => {
+                    let key = key("ALPHA_VANTAGE_API_KEY").unwrap_or_else(|| "demo".to_string());
+                    let provider = AlphaVantageProvider::new(key)
+                        .with_rng(SimulationRng::new(seed, simulation::CROSS_CHECK_STREAM));
+                    Some(Arc::new(provider))
+                }
+                "polygon" => {
+                    let provider = PolygonProvider::new(key(&config.market.polygon.api_key_env), &config.market.polygon)
+                        .with_rng(SimulationRng::new(seed, simulation::CROSS_CHECK_STREAM));
+                    Some(Arc::new(provider))
+                }
+                "finnhub" => {
+                    let settings = FinnhubConfig { stream: false, ..config.market.finnhub.clone() };
+                    let provider = FinnhubProvider::new(key(&settings.api_key_env), &settings)
+                        .with_rng(SimulationRng::new(seed, simulation::CROSS_CHECK_STREAM));
+                    Some(Arc::new(provider))
+                }
+                "yahoo" => {
+                    let provider = YahooFinanceProvider::new(&config.market.yahoo)
+                        .with_rng(SimulationRng::new(seed, simulation::CROSS_CHECK_STREAM));
+                    Some(Arc::new(provider))
+                }
+                other => {
+                    tracing::warn!("Unknown cross-check provider '{}'. Cross-checking disabled.", other);
+                    None
+                }
+            };
+            if let Some(secondary) = secondary {
+                let secondary = Arc::new(ListedMarket::new(secondary, &cross_check.provider, listings.clone(), board.clone()));
+                sensor = sensor.with_cross_check(secondary);
+                info!("🔀 Cross-checking prices against {} (tolerance {:.2}%)",
+                    cross_check.provider, cross_check.tolerance_pct);
+            }
+        }
+        info!("  👁️  Sensor  - Ingests market data");
+        agents.push(Arc::new(sensor));
+    }
+    if roles.contains(&AgentRole::Analyst) {
+        info!("  🧠 Analyst - Calculates drift");
+        agents.push(Arc::new(AnalystAgent::new(config.clone())));
+    }
+    if roles.contains(&AgentRole::Guardian) {
+        info!("  🛡️  Guardian - Volatility circuit breaker");
+        agents.push(Arc::new(GuardianAgent::new(config.clone(), market.clone())));
+    }
+    if roles.contains(&AgentRole::Trader) {
+        info!("  💰 Trader  - Executes trades");
+        agents.push(Arc::new(TraderAgent::new(config.clone(), executor.clone())));
+    }
+    if roles.contains(&AgentRole::Forecast) {
+        info!("  🔮 Forecast - Projects days until rebalance");
+        agents.push(Arc::new(ForecastAgent::new(config.clone())));
+    }
+    if agents.len() < AgentRole::ALL.len() {
+        info!("  🔗 Other agents are expected in other processes on this Redis");
+    }
+    
+    // Register the run before anything trades or emits events under it
+    let cross_check = &config.market.cross_check;
+    let cross_checked = roles.contains(&AgentRole::Sensor)
+        && cross_check.enabled
+        && matches!(cross_check.provider.as_str(), "alpha_vantage" | "polygon" | "finnhub" | "yahoo");
+    let providers = RunProviders {
+        market: market_name.clone(),
+        cross_check: cross_checked.then(|| cross_check.provider.clone()),
+        executor: "simulated".to_string(),
+    };
+    let mode = if keyed { "live" } else { "demo" };
+    runs::start(&board, &RunMetadata::new(&config, mode, providers, seed, seed_source)).await?;
     
     // Start WebSocket server for dashboard
     let ws_board = board.clone();
@@ -107,29 +701,196 @@ async fn main() -> Result<()> {
         }
     });
     
-    // Start all agents concurrently
-    let sensor_board = board.clone();
-    let sensor_clone = sensor.clone();
-    let sensor_handle = tokio::spawn(async move {
-        sensor_clone.run(sensor_board).await
-    });
+    // Forward this process's events to NATS / Kafka
+    if config.bridge.enabled {
+        let bridge_board = board.clone();
+        tokio::spawn(async move {
+            if let Err(e) = bridge::run_bridge(bridge_board).await {
+                tracing::error!("Event bridge error: {:#}", e);
+            }
+        });
+    }
     
-    let analyst_board = board.clone();
-    let analyst_clone = analyst.clone();
-    let analyst_handle = tokio::spawn(async move {
-        analyst_clone.run(analyst_board).await
-    });
+    // Background services run in exactly one process
+    if config.agent.run_services {
+        // Derive the system mode (LIVE ... HALTED) the Guardian permits under
+        let mode_board = board.clone();
+        tokio::spawn(async move {
+            if let Err(e) = mode::run_monitor(mode_board).await {
+                tracing::error!("System mode monitor error: {}", e);
+            }
+        });
+        
+        // Volatility of the portfolio itself, for non-equity portfolios
+        if config.risk_index.enabled {
+            let index_board = board.clone();
+            tokio::spawn(async move {
+                if let Err(e) = risk_index::run_index(index_board).await {
+                    tracing::error!("Composite risk index error: {}", e);
+                }
+            });
+        }
+        
+        // Rolling correlations: diversification breakdowns make the Guardian cautious
+        if config.correlation.enabled {
+            let correlation_board = board.clone();
+            tokio::spawn(async move {
+                if let Err(e) = correlation::run_monitor(correlation_board).await {
+                    tracing::error!("Correlation monitor error: {}", e);
+                }
+            });
+        }
+        
+        // Announce this swarm to others sharing the Redis
+        let heartbeat_board = board.clone();
+        tokio::spawn(async move {
+            if let Err(e) = federation::run_heartbeat(heartbeat_board, ws_port).await {
+                tracing::error!("Federation heartbeat error: {}", e);
+            }
+        });
+        
+        // Start valuation task (mark-to-market, high-water mark, drawdown)
+        let valuation_board = board.clone();
+        tokio::spawn(async move {
+            if let Err(e) = valuation::run_valuation(valuation_board).await {
+                tracing::error!("Valuation task error: {}", e);
+            }
+        });
+        
+        // Deliver queued Slack and webhook notifications, retrying failures
+        let notify_board = board.clone();
+        tokio::spawn(async move {
+            if let Err(e) = notify_queue::run_delivery(notify_board).await {
+                tracing::error!("Notification delivery error: {:#}", e);
+            }
+        });
+        
+        // Notify about conditions matching `[[alerts]]` rules
+        if config.alerting.enabled && !config.alerts.is_empty() {
+            let alerts_board = board.clone();
+            tokio::spawn(async move {
+                if let Err(e) = alerts::run_alerts(alerts_board).await {
+                    tracing::error!("Alerts error: {:#}", e);
+                }
+            });
+        }
+        
+        // Age out old history and measure the Redis footprint
+        if config.retention.enabled {
+            let janitor_board = board.clone();
+            tokio::spawn(async move {
+                if let Err(e) = retention::run_janitor(janitor_board).await {
+                    tracing::error!("Retention janitor error: {:#}", e);
+                }
+            });
+        }
+        
+        // Start position reconciliation (only meaningful for backends that report positions)
+        if config.reconciliation.enabled {
+            let reconcile_board = board.clone();
+            let reconcile_executor = executor.clone();
+            tokio::spawn(async move {
+                if let Err(e) = reconcile::run_reconciliation(reconcile_board, reconcile_executor).await {
+                    tracing::error!("Reconciliation error: {}", e);
+                }
+            });
+        }
+        
+        // Start decay scheduler (announces decay even while agents are dormant)
+        if config.decay_scheduler.enabled {
+            let decay_board = board.clone();
+            tokio::spawn(async move {
+                if let Err(e) = decay::run_decay_scheduler(decay_board).await {
+                    tracing::error!("Decay scheduler error: {}", e);
+                }
+            });
+        }
+        
+        // Start shadow mode (second Analyst/Guardian config, decisions only logged)
+        if config.shadow.enabled {
+            let shadow_board = board.clone();
+            let shadow_market = market.clone();
+            tokio::spawn(async move {
+                if let Err(e) = driftguard::agents::shadow::run_shadow(shadow_board, shadow_market).await {
+                    tracing::error!("Shadow mode error: {}", e);
+                }
+            });
+        }
+        
+        // Start daily snapshot / month-end statement scheduler
+        if config.statements.enabled {
+            let statements_board = board.clone();
+            tokio::spawn(async move {
+                if let Err(e) = statements::run_statements(statements_board).await {
+                    tracing::error!("Statement scheduler error: {}", e);
+                }
+            });
+        }
+        
+        // Start glide path scheduler (time-varying target allocation)
+        if config.portfolio.glide_path.enabled {
+            let glide_board = board.clone();
+            tokio::spawn(async move {
+                if let Err(e) = glide_path::run_scheduler(glide_board).await {
+                    tracing::error!("Glide path scheduler error: {}", e);
+                }
+            });
+        }
+        
+        // Keep the plain-text accounting journal up to date
+        if config.ledger.journal_file.is_some() {
+            let journal_board = board.clone();
+            tokio::spawn(async move {
+                if let Err(e) = ledger::run_journal_writer(journal_board).await {
+                    tracing::error!("Journal writer error: {:#}", e);
+                }
+            });
+        }
+        
+        // Start risk-based target engine
+        if config.target_engine.enabled {
+            if config.portfolio.glide_path.enabled {
+                tracing::warn!("Both glide_path and target_engine are enabled; whichever writes last sets the target.");
+            }
+            let engine_board = board.clone();
+            tokio::spawn(async move {
+                if let Err(e) = target_engine::run_engine(engine_board).await {
+                    tracing::error!("Target engine error: {}", e);
+                }
+            });
+        }
+    }
     
-    let guardian_board = board.clone();
-    let guardian_clone = guardian.clone();
-    let guardian_handle = tokio::spawn(async move {
-        guardian_clone.run(guardian_board).await
-    });
+    // Start the agents concurrently, each timed by its own runtime monitor
+    let monitors: Vec<_> = agents.iter().map(|agent| RuntimeMonitor::new(agent.name())).collect();
+    let handles: Vec<_> = agents
+        .iter()
+        .zip(&monitors)
+        .map(|(agent, monitor)| {
+            let agent = agent.clone();
+            let agent_board = board.clone();
+            let monitor = monitor.clone();
+            tokio::spawn(async move {
+                let result = runtime::instrument(monitor, agent.run(agent_board.clone())).await;
+                if let Err(e) = &result {
+                    // An agent only returns an error when it can no longer run
+                    let error = DriftGuardError::classify(e, DriftGuardError::Internal);
+                    tracing::error!("{} agent stopped: {}", agent.name(), error);
+                    if let Ok(role) = agent.name().parse::<AgentRole>() {
+                        agent_board.report_error(role.primary_output(), &error).await;
+                    }
+                }
+                result
+            })
+        })
+        .collect();
     
-    let trader_board = board.clone();
-    let trader_clone = trader.clone();
-    let trader_handle = tokio::spawn(async move {
-        trader_clone.run(trader_board).await
+    // Publish the agents' loop timings
+    let runtime_board = board.clone();
+    tokio::spawn(async move {
+        if let Err(e) = runtime::run_reporter(runtime_board, monitors).await {
+            tracing::error!("Runtime reporter error: {:#}", e);
+        }
     });
     
     info!("================================================");
@@ -138,27 +899,30 @@ async fn main() -> Result<()> {
     info!("================================================");
     info!("Press Ctrl+C to stop the swarm");
     
-    // Wait for shutdown signal
-    tokio::signal::ctrl_c().await?;
+    // Wait for Ctrl+C (or the end of a soak run)
+    let outcome = until.await;
     
     info!("🛑 Shutting down swarm...");
     
     // Signal all agents to stop gracefully
-    sensor.stop();
-    analyst.stop();
-    guardian.stop();
-    trader.stop();
+    for agent in &agents {
+        agent.stop();
+    }
     
     // Await agent handles with a timeout for graceful shutdown
     let shutdown_timeout = tokio::time::Duration::from_secs(5);
     let _ = tokio::time::timeout(shutdown_timeout, async {
-        let _ = sensor_handle.await;
-        let _ = analyst_handle.await;
-        let _ = guardian_handle.await;
-        let _ = trader_handle.await;
+        for handle in handles {
+            let _ = handle.await;
+        }
     }).await;
     
+    // Leave the federation so other dashboards don't wait for a timeout
+    if config.agent.run_services {
+        let _ = board.remove_swarm_heartbeat(board.swarm_name()).await;
+    }
+    
     info!("👋 DriftGuard stopped");
     
-    Ok(())
+    outcome
 }
@@ -1,42 +1,37 @@
 //! Alpha Vantage Market Data Provider
 //!
-//! Fetches real-time stock prices and VIX data from Alpha Vantage API.
+//! Fetches real-time stock prices and VIX data from Alpha Vantage API,
+//! and daily closes for startup history.
 //! Includes caching to respect rate limits (25 requests/day on free tier).
+//! After its first failure it serves simulated prices for the rest of the
+//! run, since a key over its daily limit won't recover until tomorrow.
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::Deserialize;
-use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
-use crate::market::MarketDataProvider;
+use crate::core::simulation::SimulationRng;
+use crate::market::daily_history::{self, DailyBar};
+use crate::market::quote_cache::QuoteCache;
+use crate::market::simulated;
+use crate::market::{MarketDataProvider, Quote, QuoteSource};
 
 const ALPHA_VANTAGE_BASE_URL: &str = "https://www.alphavantage.co/query";
 
-/// Cache entry with TTL
-struct CacheEntry {
-    value: f64,
-    cached_at: Instant,
-}
-
-impl CacheEntry {
-    fn is_valid(&self, ttl: Duration) -> bool {
-        self.cached_at.elapsed() < ttl
-    }
-}
-
 /// Alpha Vantage API provider with caching
 pub struct AlphaVantageProvider {
     client: Client,
     api_key: String,
-    cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
-    cache_ttl: Duration,
+    cache: QuoteCache,
     /// Simulated prices for demo mode when API errors occur
     simulation_mode: Arc<RwLock<bool>>,
+    /// Source of the simulated variation
+    rng: SimulationRng,
 }
 
 impl AlphaVantageProvider {
@@ -44,37 +39,22 @@ impl AlphaVantageProvider {
         Self {
             client: Client::new(),
             api_key: api_key.into(),
-            cache: Arc::new(RwLock::new(HashMap::new())),
-            cache_ttl: Duration::from_secs(60), // Cache for 60 seconds
+            cache: QuoteCache::new(Duration::from_secs(60)), // Cache for 60 seconds
             simulation_mode: Arc::new(RwLock::new(false)),
+            rng: SimulationRng::from_entropy(),
         }
     }
     
     /// Create provider with custom cache TTL
     pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
-        self.cache_ttl = ttl;
+        self.cache = QuoteCache::new(ttl);
         self
     }
     
-    /// Check cache for valid entry
-    async fn get_cached(&self, key: &str) -> Option<f64> {
-        let cache = self.cache.read().await;
-        cache.get(key).and_then(|entry| {
-            if entry.is_valid(self.cache_ttl) {
-                Some(entry.value)
-            } else {
-                None
-            }
-        })
-    }
-    
-    /// Store value in cache
-    async fn set_cached(&self, key: &str, value: f64) {
-        let mut cache = self.cache.write().await;
-        cache.insert(key.to_string(), CacheEntry {
-            value,
-            cached_at: Instant::now(),
-        });
+    /// Draw simulated prices and VIX from a seeded generator
+    pub fn with_rng(mut self, rng: SimulationRng) -> Self {
+        self.rng = rng;
+        self
     }
     
     /// Fetch quote from Alpha Vantage GLOBAL_QUOTE endpoint
@@ -88,16 +68,19 @@ impl AlphaVantageProvider {
         
         debug!("Fetching quote for {} from Alpha Vantage", symbol);
         
+        // Errors carry the URL, and with it the key, unless it's dropped
         let response = self.client
             .get(&url)
             .timeout(Duration::from_secs(10))
             .send()
             .await
+            .map_err(reqwest::Error::without_url)
             .context("Failed to send request to Alpha Vantage")?;
         
         let data: GlobalQuoteResponse = response
             .json()
             .await
+            .map_err(reqwest::Error::without_url)
             .context("Failed to parse Alpha Vantage response")?;
         
         // Check for rate limit or error
@@ -126,77 +109,159 @@ impl AlphaVantageProvider {
         Ok(price)
     }
     
+    /// Fetch the newest `days` daily closes from TIME_SERIES_DAILY
+    /// (beyond 100 days needs a plan serving the full series)
+    pub async fn fetch_daily_closes(&self, symbol: &str, days: usize) -> Result<Vec<DailyBar>> {
+        let url = format!(
+            "{}?function=TIME_SERIES_DAILY&symbol={}&outputsize={}&apikey={}",
+            ALPHA_VANTAGE_BASE_URL,
+            symbol,
+            if days > 100 { "full" } else { "compact" },
+            self.api_key
+        );
+        
+        debug!("Fetching daily history for {} from Alpha Vantage", symbol);
+        
+        let body: serde_json::Value = self.client
+            .get(&url)
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .map_err(reqwest::Error::without_url)
+            .context("Failed to send request to Alpha Vantage")?
+            .json()
+            .await
+            .map_err(reqwest::Error::without_url)
+            .context("Failed to parse Alpha Vantage response")?;
+        
+        daily_history::parse_time_series_daily(&body, days)
+    }
+    
+    /// Fetch the current `from`/`to` rate from CURRENCY_EXCHANGE_RATE
+    async fn fetch_fx_rate(&self, from: &str, to: &str) -> Result<f64> {
+        if *self.simulation_mode.read().await {
+            anyhow::bail!("Alpha Vantage is in simulation mode");
+        }
+        
+        let url = format!(
+            "{}?function=CURRENCY_EXCHANGE_RATE&from_currency={}&to_currency={}&apikey={}",
+            ALPHA_VANTAGE_BASE_URL,
+            from,
+            to,
+            self.api_key
+        );
+        
+        let body: serde_json::Value = self.client
+            .get(&url)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(reqwest::Error::without_url)
+            .context("Failed to send request to Alpha Vantage")?
+            .json()
+            .await
+            .map_err(reqwest::Error::without_url)
+            .context("Failed to parse Alpha Vantage response")?;
+        
+        let rate: f64 = body
+            .pointer("/Realtime Currency Exchange Rate/5. Exchange Rate")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("No {}/{} rate in response", from, to))?
+            .parse()
+            .context("Failed to parse exchange rate")?;
+        
+        info!("Alpha Vantage: {}/{} = {:.4}", from, to, rate);
+        
+        Ok(rate)
+    }
+    
+    fn fresh_quote(symbol: &str, price: f64, source: QuoteSource) -> Quote {
+        Quote {
+            symbol: symbol.to_string(),
+            price,
+            source,
+            age_secs: 0.0,
+        }
+    }
+    
     /// Get simulated price for demo mode
     fn get_simulated_price(&self, symbol: &str) -> Result<f64> {
-        // Base prices for common ETFs
-        let base_price = match symbol {
-            "SPY" => 580.0,  // S&P 500 ETF
-            "BND" => 72.0,   // Bond ETF
-            "VIX" => 18.0,   // Volatility index
-            "QQQ" => 490.0,  // NASDAQ ETF
-            "IWM" => 220.0,  // Russell 2000 ETF
-            _ => 100.0,
-        };
-        
-        // Add small random variation (±2%)
-        let variation = (rand_variation() - 0.5) * 0.04;
-        let price = base_price * (1.0 + variation);
-        
-        warn!("Using simulated price for {}: ${:.2}", symbol, price);
-        Ok(price)
+        Ok(simulated::price(symbol, &self.rng))
     }
 }
 
 #[async_trait]
 impl MarketDataProvider for AlphaVantageProvider {
     async fn get_price(&self, symbol: &str) -> Result<f64> {
+        Ok(self.get_quote(symbol).await?.price)
+    }
+    
+    async fn get_vix(&self) -> Result<f64> {
+        Ok(self.get_vix_quote().await?.price)
+    }
+    
+    async fn get_vix_quote(&self) -> Result<Quote> {
+        // VIX is available via CBOE, but Alpha Vantage doesn't provide it directly
+        // We'll use a simulated VIX that fluctuates realistically
+        
+        if let Some(cached) = self.cache.get("VIX").await {
+            return Ok(cached);
+        }
+        
+        let vix = simulated::vix(&self.rng);
+        let quote = Self::fresh_quote("VIX", vix, QuoteSource::Simulated);
+        self.cache.set(&quote).await;
+        
+        info!("VIX (simulated): {:.2}", vix);
+        Ok(quote)
+    }
+    
+    async fn fetch_daily_closes(&self, symbol: &str, days: usize) -> Result<Vec<DailyBar>> {
+        AlphaVantageProvider::fetch_daily_closes(self, symbol, days).await
+    }
+    
+    async fn get_fx_rate(&self, from: &str, to: &str) -> Result<f64> {
+        self.fetch_fx_rate(from, to).await
+    }
+    
+    async fn get_quote(&self, symbol: &str) -> Result<Quote> {
         // Check cache first
-        if let Some(cached) = self.get_cached(symbol).await {
-            debug!("Cache hit for {}: ${:.2}", symbol, cached);
+        if let Some(cached) = self.cache.get(symbol).await {
+            debug!("Cache hit for {}: ${:.2}", symbol, cached.price);
             return Ok(cached);
         }
         
         // Check if we're in simulation mode
         if *self.simulation_mode.read().await {
             let price = self.get_simulated_price(symbol)?;
-            self.set_cached(symbol, price).await;
-            return Ok(price);
+            let quote = Self::fresh_quote(symbol, price, QuoteSource::Simulated);
+            self.cache.set(&quote).await;
+            return Ok(quote);
         }
         
         // Fetch from API
         match self.fetch_quote(symbol).await {
             Ok(price) => {
-                self.set_cached(symbol, price).await;
-                Ok(price)
+                // fetch_quote may itself have dropped into simulation mode
+                let source = if *self.simulation_mode.read().await {
+                    QuoteSource::Simulated
+                } else {
+                    QuoteSource::Live
+                };
+                let quote = Self::fresh_quote(symbol, price, source);
+                self.cache.set(&quote).await;
+                Ok(quote)
             }
             Err(e) => {
                 warn!("API error, falling back to simulation: {}", e);
                 *self.simulation_mode.write().await = true;
                 let price = self.get_simulated_price(symbol)?;
-                self.set_cached(symbol, price).await;
-                Ok(price)
+                let quote = Self::fresh_quote(symbol, price, QuoteSource::Simulated);
+                self.cache.set(&quote).await;
+                Ok(quote)
             }
         }
     }
-    
-    async fn get_vix(&self) -> Result<f64> {
-        // VIX is available via CBOE, but Alpha Vantage doesn't provide it directly
-        // We'll use a simulated VIX that fluctuates realistically
-        
-        if let Some(cached) = self.get_cached("VIX").await {
-            return Ok(cached);
-        }
-        
-        // Simulate VIX between 12 and 30
-        let base_vix = 18.0;
-        let variation = (rand_variation() - 0.5) * 16.0; // ±8 points
-        let vix = (base_vix + variation).max(10.0).min(40.0);
-        
-        self.set_cached("VIX", vix).await;
-        
-        info!("VIX (simulated): {:.2}", vix);
-        Ok(vix)
-    }
 }
 
 /// Alpha Vantage GLOBAL_QUOTE response structure
@@ -215,24 +280,3 @@ struct GlobalQuote {
     #[serde(rename = "05. price")]
     price: String,
 }
-
-/// Random variation using the `rand` crate for proper distribution
-fn rand_variation() -> f64 {
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
-    rng.gen::<f64>()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    
-    #[tokio::test]
-    async fn test_simulated_price() {
-        let provider = AlphaVantageProvider::new("demo");
-        let price = provider.get_simulated_price("SPY").unwrap();
-        
-        // Should be around $580 ±2%
-        assert!(price > 560.0 && price < 600.0);
-    }
-}
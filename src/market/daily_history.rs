@@ -0,0 +1,173 @@
+//! Daily Price History
+//!
+//! Daily closes from the market provider (Alpha Vantage's
+//! TIME_SERIES_DAILY or Polygon's daily aggregates), loaded once at
+//! startup (`[warmup] history_days`) for the portfolio, realized-volatility
+//! and benchmark symbols (every asset with the composite risk index), so
+//! history-dependent features have something to go on before the Sensor
+//! has collected its own: the Guardian estimates realized volatility from
+//! daily returns until the live window fills, the Analyst seeds its
+//! momentum, the composite risk index estimates correlations, and
+//! `GET /api/benchmarks` reports trailing returns. Histories are cached on the blackboard (`history:daily`) and
+//! fetched again only on a new exchange day, so restarts don't spend the
+//! free tier's daily request allowance.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use tracing::{info, warn};
+
+use crate::core::{clock, Blackboard, Config};
+use crate::market::MarketDataProvider;
+
+/// Trading sessions in a year, for annualizing daily returns
+const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// One session's closing price
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyBar {
+    pub date: NaiveDate,
+    pub close: f64,
+}
+
+/// A symbol's recent daily closes as cached on the blackboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyHistory {
+    pub symbol: String,
+    /// Oldest first
+    pub bars: Vec<DailyBar>,
+    /// Exchange date it was fetched on
+    pub fetched_on: NaiveDate,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Daily closes from a TIME_SERIES_DAILY response, the newest `days`,
+/// oldest first
+pub fn parse_time_series_daily(body: &serde_json::Value, days: usize) -> Result<Vec<DailyBar>> {
+    if let Some(note) = body.get("Note").or_else(|| body.get("Information")).and_then(|v| v.as_str()) {
+        return Err(anyhow!("Alpha Vantage: {}", note));
+    }
+    if let Some(error) = body.get("Error Message").and_then(|v| v.as_str()) {
+        return Err(anyhow!("Alpha Vantage: {}", error));
+    }
+    let series = body
+        .get("Time Series (Daily)")
+        .and_then(|v| v.as_object())
+        .ok_or_else(|| anyhow!("No daily time series in response"))?;
+
+    let mut bars = series
+        .iter()
+        .map(|(date, bar)| {
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .with_context(|| format!("Invalid date '{}'", date))?;
+            let close = bar
+                .get("4. close")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("No close on {}", date))?
+                .parse::<f64>()
+                .with_context(|| format!("Invalid close on {}", date))?;
+            Ok(DailyBar { date, close })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    bars.sort_by_key(|bar| bar.date);
+    let skip = bars.len().saturating_sub(days);
+    Ok(bars.split_off(skip))
+}
+
+/// Annualized volatility (as a fraction) of daily log returns; needs
+/// `min_returns` returns
+pub fn daily_volatility(bars: &[DailyBar], min_returns: usize) -> Option<f64> {
+    let returns: Vec<f64> = bars
+        .windows(2)
+        .filter(|pair| pair[0].close > 0.0 && pair[1].close > 0.0)
+        .map(|pair| (pair[1].close / pair[0].close).ln())
+        .collect();
+    if returns.len() < min_returns.max(2) {
+        return None;
+    }
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    Some((variance * TRADING_DAYS_PER_YEAR).sqrt())
+}
+
+/// Return over the whole history (%)
+pub fn trailing_return_pct(bars: &[DailyBar]) -> Option<f64> {
+    let (first, last) = (bars.first()?, bars.last()?);
+    (first.close > 0.0 && bars.len() > 1).then(|| (last.close / first.close - 1.0) * 100.0)
+}
+
+/// Symbols whose history the swarm uses
+pub fn symbols(config: &Config) -> Vec<String> {
+    let mut symbols = BTreeSet::from([
+        config.portfolio.stocks_symbol.clone(),
+        config.portfolio.bonds_symbol.clone(),
+        config.benchmarks.benchmark_symbol.clone(),
+    ]);
+    if config.market.realized_vol.enabled {
+        symbols.insert(config.market.realized_vol.symbol.clone());
+    }
+    if config.risk_index.enabled {
+        symbols.extend(config.assets().into_iter().map(|asset| asset.symbol));
+    }
+    symbols.into_iter().collect()
+}
+
+/// Fetch the history of every symbol not already cached today; returns
+/// how many were fetched. A symbol that can't be fetched keeps its cached
+/// history, if any, and is otherwise left to live collection.
+pub async fn load(board: &Blackboard, provider: &dyn MarketDataProvider) -> Result<usize> {
+    let config = board.config();
+    let days = config.warmup.history_days;
+    let today = clock::today(Utc::now(), config.exchange.timezone);
+    let mut fetched = 0;
+
+    for symbol in symbols(config) {
+        if board.get_daily_history(&symbol).await?.is_some_and(|cached| cached.fetched_on == today) {
+            continue;
+        }
+        match provider.fetch_daily_closes(&symbol, days).await {
+            Ok(bars) if !bars.is_empty() => {
+                info!("📚 Loaded {} daily closes for {} ({} to {})",
+                    bars.len(), symbol, bars[0].date, bars[bars.len() - 1].date);
+                let history = DailyHistory { symbol: symbol.clone(), bars, fetched_on: today, fetched_at: Utc::now() };
+                board.put_daily_history(&history).await?;
+                fetched += 1;
+            }
+            Ok(_) => warn!("📚 No daily history for {}", symbol),
+            Err(e) => warn!("📚 Failed to load daily history for {}: {:#}", symbol, e),
+        }
+    }
+    Ok(fetched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_estimate() {
+        let body = serde_json::json!({
+            "Meta Data": { "2. Symbol": "SPY" },
+            "Time Series (Daily)": {
+                "2024-07-30": { "1. open": "100.0", "4. close": "101.0" },
+                "2024-07-26": { "1. open": "100.0", "4. close": "100.0" },
+                "2024-07-29": { "1. open": "100.0", "4. close": "99.0" },
+                "2024-07-31": { "1. open": "100.0", "4. close": "100.0" },
+            }
+        });
+        let bars = parse_time_series_daily(&body, 3).unwrap();
+        let closes: Vec<f64> = bars.iter().map(|bar| bar.close).collect();
+        assert_eq!(closes, vec![99.0, 101.0, 100.0]);
+        assert_eq!(bars[0].date, NaiveDate::from_ymd_opt(2024, 7, 29).unwrap());
+        assert!((trailing_return_pct(&bars).unwrap() - (100.0 / 99.0 - 1.0) * 100.0).abs() < 1e-9);
+
+        let vol = daily_volatility(&bars, 2).unwrap();
+        assert!(vol > 0.1 && vol < 0.5, "{}", vol);
+        assert_eq!(daily_volatility(&bars, 3), None);
+
+        let limited = serde_json::json!({ "Information": "rate limit" });
+        assert!(parse_time_series_daily(&limited, 3).is_err());
+    }
+}
@@ -0,0 +1,277 @@
+//! Provider Failover Chain
+//!
+//! `[market.providers] chain` lists providers in order of preference. Each
+//! quote is asked of them in turn and comes from the first that answers
+//! with a real (live or cached) price; an error or a simulated price (how
+//! providers answer when their request failed or was rate-limited) falls
+//! through to the next. A provider failing `max_failures` quotes in a row
+//! is skipped for `cooldown_secs`, then tried again; one success restores
+//! it. When the whole chain fails the quote is simulated here.
+//!
+//! VIX falls through the same way; a VIX request that fails counts
+//! against the provider's health and a real VIX for it, but a simulated
+//! one doesn't (most providers have no VIX and simulate it by design).
+//! Daily history and exchange rates fall through without counting, since
+//! providers lack some history or currency pairs. `health` reaches the
+//! Sensor's market snapshot (`GET /api/market`) and the system mode.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::core::config::ProvidersConfig;
+use crate::core::simulation::SimulationRng;
+use crate::market::daily_history::DailyBar;
+use crate::market::simulated;
+use crate::market::{MarketDataProvider, PriceUpdates, Quote, QuoteSource};
+
+/// How one provider of the chain has been doing
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    pub name: String,
+    /// Quotes it answered with a real price
+    pub served: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    /// Seconds until it is tried again, while cooling down
+    pub cooldown_remaining_secs: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct Health {
+    served: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    last_error: Option<String>,
+    cooling_until: Option<Instant>,
+}
+
+struct Member {
+    name: String,
+    provider: Arc<dyn MarketDataProvider>,
+    health: Mutex<Health>,
+}
+
+/// Providers tried in order until one quotes a real price
+pub struct FailoverProvider {
+    members: Vec<Member>,
+    max_failures: u32,
+    cooldown: Duration,
+    /// Source of the prices simulated when every provider fails
+    rng: SimulationRng,
+}
+
+impl FailoverProvider {
+    /// A chain of named providers, most preferred first
+    pub fn new(members: Vec<(String, Arc<dyn MarketDataProvider>)>, config: &ProvidersConfig) -> Self {
+        Self {
+            members: members
+                .into_iter()
+                .map(|(name, provider)| Member { name, provider, health: Mutex::new(Health::default()) })
+                .collect(),
+            max_failures: config.max_failures.max(1),
+            cooldown: Duration::from_secs(config.cooldown_secs),
+            rng: SimulationRng::from_entropy(),
+        }
+    }
+
+    /// Draw simulated prices and VIX from a seeded generator
+    pub fn with_rng(mut self, rng: SimulationRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Health of each provider, in chain order
+    pub fn health(&self) -> Vec<ProviderHealth> {
+        let now = Instant::now();
+        self.members
+            .iter()
+            .map(|member| {
+                let health = member.health.lock().unwrap();
+                ProviderHealth {
+                    name: member.name.clone(),
+                    served: health.served,
+                    failures: health.failures,
+                    consecutive_failures: health.consecutive_failures,
+                    last_error: health.last_error.clone(),
+                    cooldown_remaining_secs: health
+                        .cooling_until
+                        .filter(|until| *until > now)
+                        .map(|until| (until - now).as_secs_f64()),
+                }
+            })
+            .collect()
+    }
+
+    /// Members not cooling down at `now`, in chain order
+    fn available(&self, now: Instant) -> impl Iterator<Item = &Member> {
+        self.members
+            .iter()
+            .filter(move |member| member.health.lock().unwrap().cooling_until.is_none_or(|until| now >= until))
+    }
+
+    fn record_success(&self, member: &Member) {
+        let mut health = member.health.lock().unwrap();
+        if health.consecutive_failures >= self.max_failures {
+            info!("📡 {} is quoting again", member.name);
+        }
+        health.served += 1;
+        health.consecutive_failures = 0;
+        health.cooling_until = None;
+    }
+
+    fn record_failure(&self, member: &Member, error: String) {
+        let mut health = member.health.lock().unwrap();
+        health.failures += 1;
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= self.max_failures {
+            warn!(
+                "📡 {} failed {} times in a row ({}); skipping it for {}s",
+                member.name, health.consecutive_failures, error, self.cooldown.as_secs()
+            );
+            health.cooling_until = Some(Instant::now() + self.cooldown);
+        }
+        health.last_error = Some(error);
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for FailoverProvider {
+    async fn get_price(&self, symbol: &str) -> Result<f64> {
+        Ok(self.get_quote(symbol).await?.price)
+    }
+
+    async fn get_vix(&self) -> Result<f64> {
+        Ok(self.get_vix_quote().await?.price)
+    }
+
+    async fn get_quote(&self, symbol: &str) -> Result<Quote> {
+        for member in self.available(Instant::now()) {
+            match member.provider.get_quote(symbol).await {
+                Ok(quote) if quote.source != QuoteSource::Simulated => {
+                    self.record_success(member);
+                    return Ok(quote);
+                }
+                Ok(_) => self.record_failure(member, format!("simulated {}", symbol)),
+                Err(e) => self.record_failure(member, format!("{:#}", e)),
+            }
+        }
+        warn!("📡 No provider quoted {}, simulating this quote", symbol);
+        let price = simulated::price(symbol, &self.rng);
+        Ok(Quote { symbol: symbol.to_string(), price, source: QuoteSource::Simulated, age_secs: 0.0 })
+    }
+
+    async fn get_vix_quote(&self) -> Result<Quote> {
+        for member in self.available(Instant::now()) {
+            match member.provider.get_vix_quote().await {
+                Ok(quote) if quote.source != QuoteSource::Simulated => {
+                    self.record_success(member);
+                    return Ok(quote);
+                }
+                Ok(_) => {}
+                Err(e) => self.record_failure(member, format!("VIX: {:#}", e)),
+            }
+        }
+        let vix = simulated::vix(&self.rng);
+        info!("VIX (simulated): {:.2}", vix);
+        Ok(Quote { symbol: "VIX".to_string(), price: vix, source: QuoteSource::Simulated, age_secs: 0.0 })
+    }
+
+    async fn fetch_daily_closes(&self, symbol: &str, days: usize) -> Result<Vec<DailyBar>> {
+        let mut errors = Vec::new();
+        for member in self.available(Instant::now()) {
+            match member.provider.fetch_daily_closes(symbol, days).await {
+                Ok(bars) => return Ok(bars),
+                Err(e) => errors.push(format!("{}: {:#}", member.name, e)),
+            }
+        }
+        Err(anyhow!("no provider has daily history for {} ({})", symbol, errors.join("; ")))
+    }
+
+    async fn get_fx_rate(&self, from: &str, to: &str) -> Result<f64> {
+        let mut errors = Vec::new();
+        for member in self.available(Instant::now()) {
+            match member.provider.get_fx_rate(from, to).await {
+                Ok(rate) => return Ok(rate),
+                Err(e) => errors.push(format!("{}: {:#}", member.name, e)),
+            }
+        }
+        Err(anyhow!("no provider has a {}/{} exchange rate ({})", from, to, errors.join("; ")))
+    }
+
+    /// The first streaming provider's updates
+    fn price_updates(&self) -> Option<PriceUpdates> {
+        self.members.iter().find_map(|member| member.provider.price_updates())
+    }
+
+    fn provider_health(&self) -> Vec<ProviderHealth> {
+        self.health()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Quotes 100 live, or a simulated 1 while `down`
+    #[derive(Default)]
+    struct Fake {
+        down: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl MarketDataProvider for Fake {
+        async fn get_price(&self, symbol: &str) -> Result<f64> {
+            Ok(self.get_quote(symbol).await?.price)
+        }
+
+        async fn get_vix(&self) -> Result<f64> {
+            Ok(20.0)
+        }
+
+        async fn get_quote(&self, symbol: &str) -> Result<Quote> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let (price, source) =
+                if self.down.load(Ordering::SeqCst) { (1.0, QuoteSource::Simulated) } else { (100.0, QuoteSource::Live) };
+            Ok(Quote { symbol: symbol.to_string(), price, source, age_secs: 0.0 })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover_chain() {
+        let primary = Arc::new(Fake::default());
+        let backup = Arc::new(Fake::default());
+        let config = ProvidersConfig { chain: vec![], max_failures: 2, cooldown_secs: 3600 };
+        let chain = FailoverProvider::new(
+            vec![("primary".to_string(), primary.clone() as Arc<dyn MarketDataProvider>), ("backup".to_string(), backup.clone())],
+            &config,
+        );
+
+        assert_eq!(chain.get_quote("SPY").await.unwrap().source, QuoteSource::Live);
+        assert_eq!(backup.calls.load(Ordering::SeqCst), 0);
+
+        // A simulated answer falls through; two in a row cool the primary down
+        primary.down.store(true, Ordering::SeqCst);
+        for _ in 0..2 {
+            assert_eq!(chain.get_quote("SPY").await.unwrap().price, 100.0);
+        }
+        let health = chain.health();
+        assert_eq!((health[0].failures, health[1].served), (2, 2));
+        assert!(health[0].cooldown_remaining_secs.is_some());
+        primary.down.store(false, Ordering::SeqCst);
+        chain.get_quote("SPY").await.unwrap();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 3, "skipped while cooling down");
+
+        // Every provider failing: simulated here
+        backup.down.store(true, Ordering::SeqCst);
+        let quote = chain.get_quote("SPY").await.unwrap();
+        assert_eq!(quote.source, QuoteSource::Simulated);
+        assert_ne!(quote.price, 1.0);
+    }
+}
@@ -0,0 +1,381 @@
+//! Finnhub Market Data Provider
+//!
+//! Prices arrive trade by trade over Finnhub's websocket rather than being
+//! polled: `stream` subscribes to every configured symbol and keeps the
+//! last trade of each in memory, waking the Sensor (through
+//! `price_updates`) so it deposits Price_Freshness as prices move, at most
+//! once per `[market.finnhub] min_deposit_interval_ms`. A quote reports
+//! its age from the trade's timestamp.
+//!
+//! A symbol with no trade in `stale_after_secs` (before the first trade,
+//! outside trading hours, or while the stream reconnects) is quoted from
+//! the REST quote endpoint, cached for `cache_ttl_secs`; a failed request
+//! simulates that quote only. Daily closes for startup history come from
+//! daily candles, which need a plan that includes them. The REST key
+//! travels in a header; the websocket only accepts it in its URL, which is
+//! never logged.
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{SinkExt, StreamExt};
+use reqwest::{Client, StatusCode};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Notify, RwLock};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+use crate::core::config::FinnhubConfig;
+use crate::core::simulation::SimulationRng;
+use crate::market::daily_history::DailyBar;
+use crate::market::quote_cache::QuoteCache;
+use crate::market::simulated;
+use crate::market::{MarketDataProvider, PriceUpdates, Quote, QuoteSource};
+
+/// First and longest wait between stream reconnect attempts
+const RECONNECT_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_MAX: Duration = Duration::from_secs(60);
+
+/// One trade from the stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    pub symbol: String,
+    pub price: f64,
+    pub at: DateTime<Utc>,
+}
+
+/// Finnhub provider: streamed last prices, REST quotes behind a cache
+pub struct FinnhubProvider {
+    client: Client,
+    /// None serves simulated prices without calling the API
+    api_key: Option<String>,
+    base_url: String,
+    stream_url: String,
+    /// The newest trade per symbol
+    last_trades: RwLock<HashMap<String, Trade>>,
+    /// Woken when a streamed price changes
+    updates: Arc<Notify>,
+    /// Whether the stream is on, so the Sensor should wait on `updates`
+    streaming: bool,
+    stale_after: Duration,
+    min_deposit_interval: Duration,
+    cache: QuoteCache,
+    /// Source of the simulated variation
+    rng: SimulationRng,
+}
+
+impl FinnhubProvider {
+    pub fn new(api_key: Option<String>, config: &FinnhubConfig) -> Self {
+        let api_key = api_key.filter(|key| !key.is_empty());
+        Self {
+            client: Client::new(),
+            streaming: config.stream && api_key.is_some(),
+            api_key,
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            stream_url: config.stream_url.clone(),
+            last_trades: RwLock::new(HashMap::new()),
+            updates: Arc::new(Notify::new()),
+            stale_after: Duration::from_secs(config.stale_after_secs),
+            min_deposit_interval: Duration::from_millis(config.min_deposit_interval_ms),
+            cache: QuoteCache::new(Duration::from_secs(config.cache_ttl_secs)),
+            rng: SimulationRng::from_entropy(),
+        }
+    }
+
+    /// Draw simulated prices and VIX from a seeded generator
+    pub fn with_rng(mut self, rng: SimulationRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Keep the trade stream for `symbols` open until the process exits,
+    /// reconnecting with exponential backoff; returns at once when
+    /// streaming is off
+    pub async fn stream(self: Arc<Self>, symbols: Vec<String>) {
+        let Some(key) = self.api_key.clone().filter(|_| self.streaming) else {
+            return;
+        };
+        let url = format!("{}?token={}", self.stream_url.trim_end_matches('/'), key);
+        let mut attempt = 0;
+
+        loop {
+            match tokio_tungstenite::connect_async(url.as_str()).await {
+                Ok((socket, _)) => {
+                    info!("📡 Finnhub: Streaming trades for {}", symbols.join(", "));
+                    attempt = 0;
+                    let (mut sink, mut source) = socket.split();
+                    for symbol in &symbols {
+                        let subscribe = serde_json::json!({"type": "subscribe", "symbol": symbol}).to_string();
+                        if let Err(e) = sink.send(Message::Text(subscribe)).await {
+                            warn!("Finnhub: Failed to subscribe to {}: {}", symbol, e);
+                        }
+                    }
+                    while let Some(Ok(frame)) = source.next().await {
+                        match frame {
+                            Message::Text(text) => match parse_stream_message(&text) {
+                                Ok(trades) => self.record(trades).await,
+                                Err(e) => warn!("Finnhub stream: {:#}", e),
+                            },
+                            Message::Close(_) => break,
+                            _ => {}
+                        }
+                    }
+                    warn!("Finnhub: Trade stream disconnected; reconnecting");
+                }
+                Err(e) => warn!("Finnhub: Failed to connect to the trade stream: {}", e),
+            }
+            tokio::time::sleep(reconnect_delay(attempt)).await;
+            attempt = attempt.saturating_add(1);
+        }
+    }
+
+    /// Keep each symbol's newest trade, waking the Sensor if a price moved
+    async fn record(&self, trades: Vec<Trade>) {
+        let mut moved = false;
+        let mut last_trades = self.last_trades.write().await;
+        for trade in trades {
+            let previous = last_trades.get(&trade.symbol);
+            if previous.is_some_and(|previous| previous.at > trade.at) {
+                continue;
+            }
+            moved |= previous.is_none_or(|previous| previous.price != trade.price);
+            last_trades.insert(trade.symbol.clone(), trade);
+        }
+        if moved {
+            self.updates.notify_one();
+        }
+    }
+
+    /// The streamed price of `symbol`, unless it is older than `stale_after`
+    async fn streamed_quote(&self, symbol: &str) -> Option<Quote> {
+        let trade = self.last_trades.read().await.get(symbol).cloned()?;
+        let age_secs = ((Utc::now() - trade.at).num_milliseconds() as f64 / 1000.0).max(0.0);
+        (age_secs <= self.stale_after.as_secs_f64())
+            .then(|| Quote { symbol: symbol.to_string(), price: trade.price, source: QuoteSource::Live, age_secs })
+    }
+
+    /// GET `path` as JSON
+    async fn get(&self, path: &str, timeout: Duration) -> Result<serde_json::Value> {
+        let key = self.api_key.as_deref().ok_or_else(|| anyhow!("no Finnhub API key"))?;
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .header("X-Finnhub-Token", key)
+            .timeout(timeout)
+            .send()
+            .await
+            .context("Failed to send request to Finnhub")?;
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            bail!("Finnhub: Rate limited");
+        }
+        let body: serde_json::Value = response.json().await.context("Failed to parse Finnhub response")?;
+        if !status.is_success() {
+            bail!("Finnhub: {} ({})", body.get("error").and_then(|v| v.as_str()).unwrap_or("request failed"), status);
+        }
+        Ok(body)
+    }
+
+    /// The current price and its age from the REST quote
+    async fn fetch_quote(&self, symbol: &str) -> Result<Quote> {
+        let body = self.get(&format!("/api/v1/quote?symbol={}", symbol), Duration::from_secs(10)).await?;
+        let (price, age_secs) = parse_quote(&body, symbol, Utc::now())?;
+        info!("Finnhub: {} = ${:.2} ({:.0}s old)", symbol, price, age_secs);
+        Ok(Quote { symbol: symbol.to_string(), price, source: QuoteSource::Live, age_secs })
+    }
+
+    /// A simulated quote for `symbol`, cached like a real one
+    async fn simulated_quote(&self, symbol: &str, price: f64) -> Quote {
+        let quote = Quote { symbol: symbol.to_string(), price, source: QuoteSource::Simulated, age_secs: 0.0 };
+        self.cache.set(&quote).await;
+        quote
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for FinnhubProvider {
+    async fn get_price(&self, symbol: &str) -> Result<f64> {
+        Ok(self.get_quote(symbol).await?.price)
+    }
+
+    async fn get_vix(&self) -> Result<f64> {
+        Ok(self.get_vix_quote().await?.price)
+    }
+
+    async fn get_quote(&self, symbol: &str) -> Result<Quote> {
+        if let Some(streamed) = self.streamed_quote(symbol).await {
+            return Ok(streamed);
+        }
+        if let Some(cached) = self.cache.get(symbol).await {
+            debug!("Cache hit for {}: ${:.2}", symbol, cached.price);
+            return Ok(cached);
+        }
+        if self.api_key.is_none() {
+            return Ok(self.simulated_quote(symbol, simulated::price(symbol, &self.rng)).await);
+        }
+        match self.fetch_quote(symbol).await {
+            Ok(quote) => {
+                self.cache.set(&quote).await;
+                Ok(quote)
+            }
+            Err(e) => {
+                warn!("Finnhub error for {}, simulating this quote: {:#}", symbol, e);
+                Ok(self.simulated_quote(symbol, simulated::price(symbol, &self.rng)).await)
+            }
+        }
+    }
+
+    async fn get_vix_quote(&self) -> Result<Quote> {
+        if let Some(cached) = self.cache.get("VIX").await {
+            return Ok(cached);
+        }
+        let vix = simulated::vix(&self.rng);
+        info!("VIX (simulated): {:.2}", vix);
+        Ok(self.simulated_quote("VIX", vix).await)
+    }
+
+    async fn fetch_daily_closes(&self, symbol: &str, days: usize) -> Result<Vec<DailyBar>> {
+        // Calendar days enough to cover `days` sessions, weekends and holidays included
+        let to = Utc::now();
+        let from = to - chrono::Duration::days(days as i64 * 7 / 5 + 10);
+        let path = format!("/api/v1/stock/candle?symbol={}&resolution=D&from={}&to={}", symbol, from.timestamp(), to.timestamp());
+
+        debug!("Fetching daily history for {} from Finnhub", symbol);
+
+        parse_daily_candles(&self.get(&path, Duration::from_secs(30)).await?, days)
+    }
+
+    fn price_updates(&self) -> Option<PriceUpdates> {
+        self.streaming.then(|| PriceUpdates { notify: self.updates.clone(), min_interval: self.min_deposit_interval })
+    }
+}
+
+/// Wait before reconnect attempt `attempt` (0-based)
+fn reconnect_delay(attempt: u32) -> Duration {
+    RECONNECT_MIN.saturating_mul(2u32.saturating_pow(attempt)).min(RECONNECT_MAX)
+}
+
+/// The trades in a stream message; pings carry none, errors fail
+pub fn parse_stream_message(text: &str) -> Result<Vec<Trade>> {
+    let message: serde_json::Value = serde_json::from_str(text).context("Unreadable message")?;
+    match message.get("type").and_then(|v| v.as_str()) {
+        Some("trade") => {}
+        Some("error") => bail!("{}", message.get("msg").and_then(|v| v.as_str()).unwrap_or("unknown error")),
+        _ => return Ok(Vec::new()),
+    }
+    let trades = message.get("data").and_then(|v| v.as_array()).map(Vec::as_slice).unwrap_or_default();
+    Ok(trades
+        .iter()
+        .filter_map(|trade| {
+            Some(Trade {
+                symbol: trade.get("s")?.as_str()?.to_string(),
+                price: trade.get("p")?.as_f64().filter(|price| *price > 0.0)?,
+                at: DateTime::<Utc>::from_timestamp_millis(trade.get("t")?.as_i64()?)?,
+            })
+        })
+        .collect())
+}
+
+/// Current price and its age from a REST quote; an unknown symbol comes
+/// back as all zeros
+pub fn parse_quote(body: &serde_json::Value, symbol: &str, now: DateTime<Utc>) -> Result<(f64, f64)> {
+    let price = body
+        .get("c")
+        .and_then(|v| v.as_f64())
+        .filter(|price| *price > 0.0)
+        .ok_or_else(|| anyhow!("Finnhub has no quote for {}", symbol))?;
+    let age_secs = body
+        .get("t")
+        .and_then(|v| v.as_i64())
+        .filter(|t| *t > 0)
+        .and_then(|t| DateTime::<Utc>::from_timestamp(t, 0))
+        .map_or(0.0, |at| ((now - at).num_milliseconds() as f64 / 1000.0).max(0.0));
+    Ok((price, age_secs))
+}
+
+/// Daily closes from a candles response, the newest `days`, oldest first
+pub fn parse_daily_candles(body: &serde_json::Value, days: usize) -> Result<Vec<DailyBar>> {
+    match body.get("s").and_then(|v| v.as_str()) {
+        Some("ok") => {}
+        Some("no_data") => return Ok(Vec::new()),
+        _ => bail!("Finnhub: Unexpected candles response"),
+    }
+    let closes = body.get("c").and_then(|v| v.as_array()).ok_or_else(|| anyhow!("No closes in candles"))?;
+    let times = body.get("t").and_then(|v| v.as_array()).ok_or_else(|| anyhow!("No timestamps in candles"))?;
+    let mut bars = closes
+        .iter()
+        .zip(times)
+        .map(|(close, time)| {
+            // Daily candles are stamped at midnight UTC of their session
+            let date = time
+                .as_i64()
+                .and_then(|t| DateTime::<Utc>::from_timestamp(t, 0))
+                .ok_or_else(|| anyhow!("Bad candle timestamp"))?
+                .date_naive();
+            let close = close.as_f64().ok_or_else(|| anyhow!("No close on {}", date))?;
+            Ok(DailyBar { date, close })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    bars.sort_by_key(|bar| bar.date);
+    bars.dedup_by_key(|bar| bar.date);
+    let skip = bars.len().saturating_sub(days);
+    Ok(bars.split_off(skip))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_streamed_trades() {
+        let now = Utc::now();
+        let millis = |secs_ago: i64| (now - chrono::Duration::seconds(secs_ago)).timestamp_millis();
+        let message = json!({"type": "trade", "data": [
+            {"s": "SPY", "p": 545.2, "t": millis(2), "v": 100},
+            {"s": "SPY", "p": 545.3, "t": millis(1), "v": 50},
+            {"s": "BND", "p": 0.0, "t": millis(1)},
+        ]});
+        let trades = parse_stream_message(&message.to_string()).unwrap();
+        assert_eq!(trades.len(), 2);
+        assert!(parse_stream_message(r#"{"type":"ping"}"#).unwrap().is_empty());
+        assert!(parse_stream_message(r#"{"type":"error","msg":"Invalid symbol"}"#).unwrap_err().to_string().contains("Invalid"));
+
+        // The newest trade wins and wakes the Sensor; a late, older one doesn't
+        let config = FinnhubConfig { stale_after_secs: 60, ..Default::default() };
+        let provider = FinnhubProvider::new(Some("key".to_string()), &config);
+        let updates = provider.price_updates().unwrap();
+        provider.record(trades).await;
+        tokio::time::timeout(Duration::from_secs(1), updates.notify.notified()).await.unwrap();
+        let late = Trade { symbol: "SPY".to_string(), price: 540.0, at: now - chrono::Duration::seconds(30) };
+        provider.record(vec![late]).await;
+        let quote = provider.get_quote("SPY").await.unwrap();
+        assert_eq!((quote.price, quote.source), (545.3, QuoteSource::Live));
+        assert!(quote.age_secs < 5.0);
+        assert!(tokio::time::timeout(Duration::from_millis(10), updates.notify.notified()).await.is_err());
+
+        // Stale trades aren't served; no key means no stream
+        let old = Trade { symbol: "TLT".to_string(), price: 90.0, at: now - chrono::Duration::seconds(120) };
+        provider.record(vec![old]).await;
+        assert!(provider.streamed_quote("TLT").await.is_none());
+        assert!(FinnhubProvider::new(None, &config).price_updates().is_none());
+    }
+
+    #[test]
+    fn test_parse_rest_responses() {
+        let now = DateTime::parse_from_rfc3339("2024-07-02T14:00:00Z").unwrap().with_timezone(&Utc);
+        let quote = json!({"c": 545.2, "d": 1.1, "pc": 544.1, "t": now.timestamp() - 900});
+        assert_eq!(parse_quote(&quote, "SPY", now).unwrap(), (545.2, 900.0));
+        assert!(parse_quote(&json!({"c": 0, "t": 0}), "NOPE", now).is_err());
+
+        let day = |date: &str| DateTime::parse_from_rfc3339(&format!("{}T00:00:00Z", date)).unwrap().timestamp();
+        let candles = json!({"s": "ok", "c": [544.2, 545.3, 546.0], "t": [day("2024-06-27"), day("2024-06-28"), day("2024-07-01")]});
+        let bars = parse_daily_candles(&candles, 2).unwrap();
+        assert_eq!(bars[0], DailyBar { date: NaiveDate::from_ymd_opt(2024, 6, 28).unwrap(), close: 545.3 });
+        assert_eq!(bars.len(), 2);
+        assert!(parse_daily_candles(&json!({"s": "no_data"}), 5).unwrap().is_empty());
+    }
+}
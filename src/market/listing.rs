@@ -0,0 +1,311 @@
+//! Exchange Listings
+//!
+//! Symbols may be exchange-qualified with the usual suffix (`VOD.L`,
+//! `SHOP.TO`, `7203.T`), or `[[portfolio.assets]]` may give the listing's
+//! `exchange` and `currency` explicitly. The configured symbol stays the
+//! asset's name everywhere in DriftGuard, so `VOD` (the US ADR) and `VOD.L`
+//! never collide; each is resolved here to the exchange it trades on, the
+//! currency it is quoted in, and the symbol each provider knows it by
+//! (Alpha Vantage quotes `VOD.L` as `VOD.LON`; Yahoo Finance uses the
+//! same suffixes as DriftGuard). London prices quoted in
+//! pence (GBX) are scaled to pounds.
+//!
+//! `ListedMarket` wraps the configured provider: it asks for each asset
+//! under the provider's symbol and converts the price to the portfolio's
+//! base currency, so holdings are never valued in the wrong currency. Rates
+//! come from the provider (cached for an hour), or from `[market.fx_rates]`
+//! when it has none; a quote with no rate available is an error rather
+//! than a mis-valued price. Daily histories are converted at today's rate.
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::core::bootstrap;
+use crate::core::config::AssetConfig;
+use crate::core::Blackboard;
+use crate::market::daily_history::DailyBar;
+use crate::market::quote_cache::QuoteCache;
+use crate::market::{MarketDataProvider, PriceUpdates, ProviderHealth, Quote, QuoteSource};
+
+/// How long a provider's exchange rate is reused
+const FX_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// A stock exchange symbols can be qualified for
+#[derive(Debug, PartialEq)]
+pub struct Exchange {
+    /// Short code accepted as `exchange = "…"`
+    pub code: &'static str,
+    /// Symbol suffix (none for US listings)
+    pub suffix: &'static str,
+    /// Currency its prices are quoted in
+    pub currency: &'static str,
+    /// Alpha Vantage's suffix for it, if it quotes the exchange
+    alpha_vantage: Option<&'static str>,
+    /// Whether Polygon and Finnhub quote it (US stocks only)
+    polygon: bool,
+}
+
+/// Exchanges by code; the first is the default for unqualified symbols
+pub const EXCHANGES: [Exchange; 14] = [
+    Exchange { code: "US", suffix: "", currency: "USD", alpha_vantage: Some(""), polygon: true },
+    Exchange { code: "LSE", suffix: "L", currency: "GBX", alpha_vantage: Some("LON"), polygon: false },
+    Exchange { code: "TSX", suffix: "TO", currency: "CAD", alpha_vantage: Some("TRT"), polygon: false },
+    Exchange { code: "TSXV", suffix: "V", currency: "CAD", alpha_vantage: Some("TRV"), polygon: false },
+    Exchange { code: "XETRA", suffix: "DE", currency: "EUR", alpha_vantage: Some("DEX"), polygon: false },
+    Exchange { code: "BSE", suffix: "BO", currency: "INR", alpha_vantage: Some("BSE"), polygon: false },
+    Exchange { code: "SSE", suffix: "SS", currency: "CNY", alpha_vantage: Some("SHH"), polygon: false },
+    Exchange { code: "SZSE", suffix: "SZ", currency: "CNY", alpha_vantage: Some("SHZ"), polygon: false },
+    Exchange { code: "TSE", suffix: "T", currency: "JPY", alpha_vantage: None, polygon: false },
+    Exchange { code: "HKEX", suffix: "HK", currency: "HKD", alpha_vantage: None, polygon: false },
+    Exchange { code: "ASX", suffix: "AX", currency: "AUD", alpha_vantage: None, polygon: false },
+    Exchange { code: "EPA", suffix: "PA", currency: "EUR", alpha_vantage: None, polygon: false },
+    Exchange { code: "AMS", suffix: "AS", currency: "EUR", alpha_vantage: None, polygon: false },
+    Exchange { code: "SIX", suffix: "SW", currency: "CHF", alpha_vantage: None, polygon: false },
+];
+
+/// Where a configured asset trades and what its prices are in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listing {
+    /// As configured, e.g. `VOD.L`
+    pub symbol: String,
+    /// The symbol without its exchange suffix, e.g. `VOD`
+    pub ticker: String,
+    pub exchange: &'static Exchange,
+    /// ISO 4217 currency prices are converted from
+    pub currency: String,
+    /// Multiplier taking quoted prices to `currency` (0.01 for pence)
+    pub price_scale: f64,
+}
+
+impl Listing {
+    /// Resolve an asset's listing from its suffix or explicit metadata
+    pub fn resolve(asset: &AssetConfig) -> Result<Self> {
+        let by_suffix = asset.symbol.rsplit_once('.').and_then(|(ticker, suffix)| {
+            let suffix = suffix.to_ascii_uppercase();
+            EXCHANGES[1..].iter().find(|e| e.suffix == suffix).map(|e| (ticker, e))
+        });
+        let (ticker, exchange) = match (&asset.exchange, by_suffix) {
+            (Some(code), suffixed) => {
+                let code = code.to_ascii_uppercase();
+                let exchange = EXCHANGES
+                    .iter()
+                    .find(|e| e.code == code || (!e.suffix.is_empty() && e.suffix == code))
+                    .ok_or_else(|| anyhow!("{}: unknown exchange '{}'", asset.symbol, code))?;
+                match suffixed {
+                    Some((_, suffix_exchange)) if suffix_exchange != exchange => {
+                        bail!("{}: suffix says {} but exchange is {}", asset.symbol, suffix_exchange.code, exchange.code)
+                    }
+                    Some((ticker, _)) => (ticker, exchange),
+                    None => (asset.symbol.as_str(), exchange),
+                }
+            }
+            (None, Some((ticker, exchange))) => (ticker, exchange),
+            (None, None) => (asset.symbol.as_str(), &EXCHANGES[0]),
+        };
+
+        let quoted = asset.currency.as_deref().unwrap_or(exchange.currency).trim().to_string();
+        let (currency, price_scale) = match quoted.as_str() {
+            // London quotes most stocks in pence
+            "GBX" | "GBp" | "gbx" => ("GBP".to_string(), 0.01),
+            _ => (bootstrap::parse_currency(&quoted).map_err(|e| anyhow!("{}: {:#}", asset.symbol, e))?, 1.0),
+        };
+        Ok(Self { symbol: asset.symbol.clone(), ticker: ticker.to_string(), exchange, currency, price_scale })
+    }
+
+    /// The symbol `provider` quotes this listing under, if it quotes it
+    pub fn provider_symbol(&self, provider: &str) -> Option<String> {
+        let suffix = match provider {
+            "polygon" | "finnhub" => self.exchange.polygon.then_some("")?,
+            "yahoo" => self.exchange.suffix,
+            _ => self.exchange.alpha_vantage?,
+        };
+        Some(if suffix.is_empty() { self.ticker.clone() } else { format!("{}.{}", self.ticker, suffix) })
+    }
+
+    /// A US-listed dollar asset, quoted as configured
+    pub fn is_domestic(&self) -> bool {
+        self.exchange.suffix.is_empty() && self.currency == "USD" && self.price_scale == 1.0
+    }
+}
+
+/// Resolve every asset, refusing two spellings of the same listing
+pub fn resolve_all(assets: &[AssetConfig]) -> Result<Vec<Listing>> {
+    let listings = assets.iter().map(Listing::resolve).collect::<Result<Vec<_>>>()?;
+    let mut seen: HashMap<(&str, &str), &str> = HashMap::new();
+    for listing in &listings {
+        if let Some(other) = seen.insert((listing.exchange.code, &listing.ticker), &listing.symbol) {
+            bail!("{} and {} are the same {} listing", other, listing.symbol, listing.exchange.code);
+        }
+    }
+    Ok(listings)
+}
+
+/// Listings `provider` can't quote
+pub fn unsupported<'a>(listings: &'a [Listing], provider: &str) -> Vec<&'a Listing> {
+    listings.iter().filter(|listing| listing.provider_symbol(provider).is_none()).collect()
+}
+
+/// A provider quoting configured symbols in the base currency
+pub struct ListedMarket {
+    inner: Arc<dyn MarketDataProvider>,
+    provider: String,
+    listings: HashMap<String, Listing>,
+    board: Arc<Blackboard>,
+    /// Fallback rates, `GBPUSD = 1.27` being dollars per pound
+    fx_rates: BTreeMap<String, f64>,
+    fx_cache: QuoteCache,
+}
+
+impl ListedMarket {
+    pub fn new(inner: Arc<dyn MarketDataProvider>, provider: &str, listings: Vec<Listing>, board: Arc<Blackboard>) -> Self {
+        let fx_rates = board.config().market.fx_rates.clone();
+        Self {
+            inner,
+            provider: provider.to_string(),
+            listings: listings.into_iter().map(|listing| (listing.symbol.clone(), listing)).collect(),
+            board,
+            fx_rates,
+            fx_cache: QuoteCache::new(FX_CACHE_TTL),
+        }
+    }
+
+    /// Units of `to` per unit of `from`
+    async fn fx_rate(&self, from: &str, to: &str) -> Result<f64> {
+        if from == to {
+            return Ok(1.0);
+        }
+        let pair = format!("{}{}", from, to);
+        if let Some(cached) = self.fx_cache.get(&pair).await {
+            return Ok(cached.price);
+        }
+        let rate = match self.inner.get_fx_rate(from, to).await {
+            Ok(rate) if rate > 0.0 => rate,
+            result => {
+                if let Err(e) = result {
+                    debug!("No {} rate from the provider ({:#})", pair, e);
+                }
+                let inverse = self.fx_rates.get(&format!("{}{}", to, from)).map(|rate| 1.0 / rate);
+                self.fx_rates
+                    .get(&pair)
+                    .copied()
+                    .or(inverse)
+                    .ok_or_else(|| anyhow!("no {} exchange rate (add it to [market.fx_rates])", pair))?
+            }
+        };
+        self.fx_cache.set(&Quote { symbol: pair, price: rate, source: QuoteSource::Live, age_secs: 0.0 }).await;
+        Ok(rate)
+    }
+
+    /// The listing of `symbol` if it needs mapping or converting, with the
+    /// factor taking its quoted prices to the base currency
+    async fn conversion(&self, symbol: &str) -> Result<Option<(&Listing, f64)>> {
+        let Some(listing) = self.listings.get(symbol).filter(|listing| !listing.is_domestic()) else {
+            return Ok(None);
+        };
+        let base = bootstrap::current(&self.board).await?.base_currency;
+        let rate = self.fx_rate(&listing.currency, &base).await?;
+        Ok(Some((listing, listing.price_scale * rate)))
+    }
+
+    fn provider_symbol(&self, listing: &Listing) -> String {
+        listing.provider_symbol(&self.provider).unwrap_or_else(|| {
+            warn!("{} isn't quoted by {}", listing.symbol, self.provider);
+            listing.symbol.clone()
+        })
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for ListedMarket {
+    async fn get_price(&self, symbol: &str) -> Result<f64> {
+        Ok(self.get_quote(symbol).await?.price)
+    }
+
+    async fn get_vix(&self) -> Result<f64> {
+        self.inner.get_vix().await
+    }
+
+    async fn get_vix_quote(&self) -> Result<Quote> {
+        self.inner.get_vix_quote().await
+    }
+
+    async fn get_quote(&self, symbol: &str) -> Result<Quote> {
+        let Some((listing, factor)) = self.conversion(symbol).await? else {
+            return self.inner.get_quote(symbol).await;
+        };
+        let quote = self.inner.get_quote(&self.provider_symbol(listing)).await?;
+        // Simulated prices are made up in the base currency already
+        let price = if quote.source == QuoteSource::Simulated { quote.price } else { quote.price * factor };
+        Ok(Quote { symbol: symbol.to_string(), price, ..quote })
+    }
+
+    async fn fetch_daily_closes(&self, symbol: &str, days: usize) -> Result<Vec<DailyBar>> {
+        let Some((listing, factor)) = self.conversion(symbol).await? else {
+            return self.inner.fetch_daily_closes(symbol, days).await;
+        };
+        let bars = self.inner.fetch_daily_closes(&self.provider_symbol(listing), days).await?;
+        Ok(bars.into_iter().map(|bar| DailyBar { close: bar.close * factor, ..bar }).collect())
+    }
+
+    async fn get_fx_rate(&self, from: &str, to: &str) -> Result<f64> {
+        self.fx_rate(from, to).await
+    }
+
+    fn price_updates(&self) -> Option<PriceUpdates> {
+        self.inner.price_updates()
+    }
+
+    fn provider_health(&self) -> Vec<ProviderHealth> {
+        self.inner.provider_health()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(symbol: &str, exchange: Option<&str>, currency: Option<&str>) -> AssetConfig {
+        AssetConfig {
+            symbol: symbol.to_string(),
+            name: String::new(),
+            target_pct: 25.0,
+            min_pct: None,
+            max_pct: None,
+            exchange: exchange.map(str::to_string),
+            currency: currency.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_resolve_listings() {
+        let vod = Listing::resolve(&asset("VOD.L", None, None)).unwrap();
+        assert_eq!((vod.ticker.as_str(), vod.exchange.code), ("VOD", "LSE"));
+        assert_eq!((vod.currency.as_str(), vod.price_scale), ("GBP", 0.01));
+        assert_eq!(vod.provider_symbol("alpha_vantage").as_deref(), Some("VOD.LON"));
+        assert_eq!(vod.provider_symbol("polygon"), None);
+        assert_eq!(vod.provider_symbol("finnhub"), None);
+        assert_eq!(vod.provider_symbol("yahoo").as_deref(), Some("VOD.L"));
+
+        // The ADR is a different, domestic asset; class shares keep their dot
+        let adr = Listing::resolve(&asset("VOD", None, None)).unwrap();
+        assert!(adr.is_domestic());
+        assert_eq!(Listing::resolve(&asset("BRK.B", None, None)).unwrap().ticker, "BRK.B");
+
+        // Explicit metadata: an LSE ETF priced in dollars
+        let etf = Listing::resolve(&asset("CSPX", Some("lse"), Some("USD"))).unwrap();
+        assert_eq!((etf.currency.as_str(), etf.price_scale), ("USD", 1.0));
+        assert!(!etf.is_domestic());
+        let toyota = Listing::resolve(&asset("7203.T", None, None)).unwrap();
+        assert_eq!((toyota.exchange.code, toyota.currency.as_str()), ("TSE", "JPY"));
+        assert_eq!(unsupported(&[toyota, etf], "alpha_vantage").len(), 1);
+
+        assert!(Listing::resolve(&asset("VOD.L", Some("TSX"), None)).is_err());
+        assert!(Listing::resolve(&asset("VOD", Some("NOPE"), None)).is_err());
+        assert!(resolve_all(&[asset("VOD.L", None, None), asset("VOD", Some("LSE"), None)]).is_err());
+        assert!(resolve_all(&[asset("VOD.L", None, None), asset("VOD", None, None)]).is_ok());
+    }
+}
@@ -1,14 +1,80 @@
 //! Market Data Module
 //!
-//! Provides real-time market data from Alpha Vantage API.
-//! Includes stock prices and VIX volatility index.
+//! Provides real-time market data from Alpha Vantage, Polygon.io, Finnhub
+//! or Yahoo Finance (`[market] provider`, or several in a failover chain),
+//! all behind a shared quote cache; Yahoo Finance also stands in for a
+//! provider without a key, and Finnhub streams trades as they happen.
+//! Includes stock prices, VIX volatility index (real from CBOE or FRED, or
+//! simulated), a realized-volatility estimate used when VIX is
+//! unavailable, historical VIX replay, VIX derived from volatility ETFs or
+//! futures, and daily price history.
+//! Exchange-qualified symbols (`VOD.L`) are mapped to each provider's
+//! format and valued in the portfolio's base currency.
 
 pub mod alpha_vantage;
+pub mod daily_history;
+pub mod failover;
+pub mod finnhub;
+pub mod listing;
+pub mod polygon;
+pub mod quote_cache;
+pub mod realized_vol;
+pub mod simulated;
+pub mod vix_feed;
+pub mod vix_history;
+pub mod vix_source;
+pub mod vol_proxy;
+pub mod yahoo;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::core::config::MarketConfig;
+use daily_history::DailyBar;
 
 pub use alpha_vantage::AlphaVantageProvider;
+pub use failover::{FailoverProvider, ProviderHealth};
+pub use finnhub::FinnhubProvider;
+pub use listing::ListedMarket;
+pub use polygon::PolygonProvider;
+pub use vix_source::VixSource;
+pub use yahoo::YahooFinanceProvider;
+
+/// Where a quote's price came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteSource {
+    /// Fetched from the upstream API on this call
+    Live,
+    /// Served from the provider's local cache
+    Cached,
+    /// Generated locally because the upstream API was unavailable
+    Simulated,
+}
+
+/// A price observation with provenance, used for data-quality scoring
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Quote {
+    pub symbol: String,
+    pub price: f64,
+    pub source: QuoteSource,
+    /// Seconds since the price was originally obtained
+    pub age_secs: f64,
+}
+
+/// Wakeups from a provider whose prices are pushed rather than polled
+#[derive(Debug, Clone)]
+pub struct PriceUpdates {
+    /// Notified when a streamed price changes; changes while nobody waits
+    /// collapse into one pending wakeup
+    pub notify: Arc<Notify>,
+    /// Shortest gap between the deposits the updates should cause
+    pub min_interval: Duration,
+}
 
 /// Trait for market data providers
 #[async_trait]
@@ -18,4 +84,86 @@ pub trait MarketDataProvider: Send + Sync {
     
     /// Get current VIX (CBOE Volatility Index)
     async fn get_vix(&self) -> Result<f64>;
+    
+    /// Get current price along with its provenance
+    ///
+    /// Providers that cannot tell cached from live data report every
+    /// price as a fresh live quote.
+    async fn get_quote(&self, symbol: &str) -> Result<Quote> {
+        let price = self.get_price(symbol).await?;
+        Ok(Quote {
+            symbol: symbol.to_string(),
+            price,
+            source: QuoteSource::Live,
+            age_secs: 0.0,
+        })
+    }
+    
+    /// Get current VIX along with its provenance
+    async fn get_vix_quote(&self) -> Result<Quote> {
+        let vix = self.get_vix().await?;
+        Ok(Quote {
+            symbol: "VIX".to_string(),
+            price: vix,
+            source: QuoteSource::Live,
+            age_secs: 0.0,
+        })
+    }
+    
+    /// The newest `days` daily closes of a symbol, oldest first
+    async fn fetch_daily_closes(&self, symbol: &str, _days: usize) -> Result<Vec<DailyBar>> {
+        bail!("this provider has no daily history for {}", symbol)
+    }
+    
+    /// Units of `to` per unit of `from` (ISO 4217 codes)
+    async fn get_fx_rate(&self, from: &str, to: &str) -> Result<f64> {
+        bail!("this provider has no {}/{} exchange rate", from, to)
+    }
+    
+    /// Wakeups when streamed prices change, for providers that stream
+    fn price_updates(&self) -> Option<PriceUpdates> {
+        None
+    }
+    
+    /// Health of each provider of a failover chain; empty for one provider
+    fn provider_health(&self) -> Vec<ProviderHealth> {
+        Vec::new()
+    }
+}
+
+/// The configured provider, unknown names falling back to Alpha Vantage
+pub fn provider_name(config: &MarketConfig) -> &'static str {
+    match config.provider.as_str() {
+        "polygon" => "polygon",
+        "finnhub" => "finnhub",
+        "yahoo" => "yahoo",
+        _ => "alpha_vantage",
+    }
+}
+
+/// The provider quotes actually come from: the configured one, or Yahoo
+/// Finance when it has no key (`api_key` none or "demo") and
+/// `[market.yahoo] fallback` is on
+pub fn resolve_provider(config: &MarketConfig, api_key: Option<&str>) -> &'static str {
+    let provider = provider_name(config);
+    let keyless = api_key.is_none_or(|key| key.is_empty() || key == "demo");
+    if provider != "yahoo" && keyless && config.yahoo.fallback {
+        "yahoo"
+    } else {
+        provider
+    }
+}
+
+/// Environment variable holding the key of the configured provider
+pub fn api_key_env(config: &MarketConfig) -> &str {
+    provider_key_env(config, &config.provider)
+}
+
+/// Environment variable holding the key of the named provider
+pub fn provider_key_env<'a>(config: &'a MarketConfig, provider: &str) -> &'a str {
+    match provider {
+        "polygon" => &config.polygon.api_key_env,
+        "finnhub" => &config.finnhub.api_key_env,
+        _ => "ALPHA_VANTAGE_API_KEY",
+    }
 }
@@ -0,0 +1,347 @@
+//! Polygon.io Market Data Provider
+//!
+//! Quotes from Polygon's ticker snapshot, which is real-time or 15 minutes
+//! delayed depending on the plan. Each quote reports its age from the last
+//! trade's timestamp, so delayed data scores as such in the data-quality
+//! assessment. A plan without snapshots (the free tier) falls back to the
+//! previous session's close. VIX is read from the I:VIX index snapshot
+//! with `[market] vix_source = "polygon"` (an indices plan), and daily
+//! closes for startup history from daily aggregates.
+//!
+//! Quotes are cached for `[market.polygon] cache_ttl_secs`. A failed
+//! request serves a simulated price for that call only: unlike Alpha
+//! Vantage's daily allowance, Polygon's limits reset within a minute. The
+//! key travels in the `Authorization` header, never in a URL.
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::{Client, StatusCode};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::core::config::PolygonConfig;
+use crate::core::simulation::SimulationRng;
+use crate::market::daily_history::DailyBar;
+use crate::market::quote_cache::QuoteCache;
+use crate::market::simulated;
+use crate::market::{MarketDataProvider, Quote, QuoteSource};
+
+/// Polygon's ticker for the CBOE Volatility Index
+const VIX_TICKER: &str = "I:VIX";
+
+/// Polygon API provider with caching
+pub struct PolygonProvider {
+    client: Client,
+    /// None serves simulated prices without calling the API
+    api_key: Option<String>,
+    base_url: String,
+    cache: QuoteCache,
+    /// Set once the plan turns out not to include ticker snapshots
+    snapshots_denied: AtomicBool,
+    /// Read VIX from the I:VIX index
+    index_vix: bool,
+    /// Source of the simulated variation
+    rng: SimulationRng,
+}
+
+impl PolygonProvider {
+    pub fn new(api_key: Option<String>, config: &PolygonConfig) -> Self {
+        Self {
+            client: Client::new(),
+            api_key: api_key.filter(|key| !key.is_empty()),
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            cache: QuoteCache::new(Duration::from_secs(config.cache_ttl_secs)),
+            snapshots_denied: AtomicBool::new(false),
+            index_vix: false,
+            rng: SimulationRng::from_entropy(),
+        }
+    }
+
+    /// Draw simulated prices and VIX from a seeded generator
+    pub fn with_rng(mut self, rng: SimulationRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Read VIX from Polygon's I:VIX index instead of simulating it
+    pub fn with_index_vix(mut self) -> Self {
+        self.index_vix = true;
+        self
+    }
+
+    /// GET `path` as JSON; a plan lacking the data is `Ok(None)`
+    async fn get(&self, path: &str, timeout: Duration) -> Result<Option<serde_json::Value>> {
+        let key = self.api_key.as_deref().ok_or_else(|| anyhow!("no Polygon API key"))?;
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .bearer_auth(key)
+            .timeout(timeout)
+            .send()
+            .await
+            .context("Failed to send request to Polygon")?;
+        if response.status() == StatusCode::FORBIDDEN {
+            return Ok(None);
+        }
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.context("Failed to parse Polygon response")?;
+        if !status.is_success() {
+            bail!("Polygon: {} ({})", error_message(&body), status);
+        }
+        Ok(Some(body))
+    }
+
+    /// Last trade price and its age from the ticker snapshot, or the
+    /// previous close on plans without snapshots
+    async fn fetch_quote(&self, symbol: &str) -> Result<Quote> {
+        let now = Utc::now();
+        if !self.snapshots_denied.load(Ordering::Relaxed) {
+            let path = format!("/v2/snapshot/locale/us/markets/stocks/tickers/{}", symbol);
+            match self.get(&path, Duration::from_secs(10)).await? {
+                Some(body) => {
+                    let (price, age_secs) = parse_snapshot(&body, now)?;
+                    info!("Polygon: {} = ${:.2} ({:.0}s old)", symbol, price, age_secs);
+                    return Ok(Quote { symbol: symbol.to_string(), price, source: QuoteSource::Live, age_secs });
+                }
+                None => {
+                    warn!("Polygon: The plan doesn't include snapshots, using previous closes");
+                    self.snapshots_denied.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+        let path = format!("/v2/aggs/ticker/{}/prev?adjusted=true", symbol);
+        let body = self
+            .get(&path, Duration::from_secs(10))
+            .await?
+            .ok_or_else(|| anyhow!("Polygon: The plan doesn't include {}", symbol))?;
+        let (price, age_secs) = parse_previous_close(&body, now)?;
+        info!("Polygon: {} = ${:.2} (previous close)", symbol, price);
+        Ok(Quote { symbol: symbol.to_string(), price, source: QuoteSource::Live, age_secs })
+    }
+
+    /// The I:VIX index value and its age
+    async fn fetch_index_vix(&self) -> Result<Quote> {
+        let path = format!("/v3/snapshot/indices?ticker.any_of={}", VIX_TICKER);
+        let body = self
+            .get(&path, Duration::from_secs(10))
+            .await?
+            .ok_or_else(|| anyhow!("Polygon: The plan doesn't include indices"))?;
+        let (vix, age_secs) = parse_index_value(&body, Utc::now())?;
+        info!("VIX (Polygon): {:.2}", vix);
+        Ok(Quote { symbol: "VIX".to_string(), price: vix, source: QuoteSource::Live, age_secs })
+    }
+
+    /// A simulated quote for `symbol`, cached like a real one
+    async fn simulated_quote(&self, symbol: &str, price: f64) -> Quote {
+        let quote = Quote { symbol: symbol.to_string(), price, source: QuoteSource::Simulated, age_secs: 0.0 };
+        self.cache.set(&quote).await;
+        quote
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for PolygonProvider {
+    async fn get_price(&self, symbol: &str) -> Result<f64> {
+        Ok(self.get_quote(symbol).await?.price)
+    }
+
+    async fn get_vix(&self) -> Result<f64> {
+        Ok(self.get_vix_quote().await?.price)
+    }
+
+    async fn get_quote(&self, symbol: &str) -> Result<Quote> {
+        if let Some(cached) = self.cache.get(symbol).await {
+            debug!("Cache hit for {}: ${:.2}", symbol, cached.price);
+            return Ok(cached);
+        }
+        if self.api_key.is_none() {
+            return Ok(self.simulated_quote(symbol, simulated::price(symbol, &self.rng)).await);
+        }
+        match self.fetch_quote(symbol).await {
+            Ok(quote) => {
+                self.cache.set(&quote).await;
+                Ok(quote)
+            }
+            Err(e) => {
+                warn!("Polygon error for {}, simulating this quote: {:#}", symbol, e);
+                Ok(self.simulated_quote(symbol, simulated::price(symbol, &self.rng)).await)
+            }
+        }
+    }
+
+    async fn get_vix_quote(&self) -> Result<Quote> {
+        if let Some(cached) = self.cache.get("VIX").await {
+            return Ok(cached);
+        }
+        if self.index_vix && self.api_key.is_some() {
+            match self.fetch_index_vix().await {
+                Ok(quote) => {
+                    self.cache.set(&quote).await;
+                    return Ok(quote);
+                }
+                Err(e) => warn!("Polygon VIX unavailable, simulating it: {:#}", e),
+            }
+        }
+
+        let vix = simulated::vix(&self.rng);
+        info!("VIX (simulated): {:.2}", vix);
+        Ok(self.simulated_quote("VIX", vix).await)
+    }
+
+    async fn fetch_daily_closes(&self, symbol: &str, days: usize) -> Result<Vec<DailyBar>> {
+        // Calendar days enough to cover `days` sessions, weekends and holidays included
+        let to = Utc::now().date_naive();
+        let from = to - chrono::Duration::days(days as i64 * 7 / 5 + 10);
+        let path = format!("/v2/aggs/ticker/{}/range/1/day/{}/{}?adjusted=true&sort=asc&limit=50000", symbol, from, to);
+
+        debug!("Fetching daily history for {} from Polygon", symbol);
+
+        let body = self
+            .get(&path, Duration::from_secs(30))
+            .await?
+            .ok_or_else(|| anyhow!("Polygon: The plan doesn't include daily aggregates for {}", symbol))?;
+        parse_daily_aggregates(&body, days)
+    }
+
+    async fn get_fx_rate(&self, from: &str, to: &str) -> Result<f64> {
+        let path = format!("/v2/aggs/ticker/C:{}{}/prev", from, to);
+        let body = self
+            .get(&path, Duration::from_secs(10))
+            .await?
+            .ok_or_else(|| anyhow!("Polygon: The plan doesn't include forex"))?;
+        Ok(parse_previous_close(&body, Utc::now())?.0)
+    }
+}
+
+/// What an error response says went wrong
+fn error_message(body: &serde_json::Value) -> String {
+    body.get("error")
+        .or_else(|| body.get("message"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("request failed")
+        .to_string()
+}
+
+/// Seconds from a nanosecond (snapshot) timestamp to `now`
+fn age_from_nanos(nanos: i64, now: DateTime<Utc>) -> f64 {
+    let at = DateTime::<Utc>::from_timestamp_nanos(nanos);
+    ((now - at).num_milliseconds() as f64 / 1000.0).max(0.0)
+}
+
+/// Last trade price and its age from a ticker snapshot
+pub fn parse_snapshot(body: &serde_json::Value, now: DateTime<Utc>) -> Result<(f64, f64)> {
+    let ticker = body.get("ticker").ok_or_else(|| anyhow!("Polygon: {}", error_message(body)))?;
+    let trade = ticker.get("lastTrade");
+    let price = trade
+        .and_then(|t| t.get("p"))
+        .and_then(|v| v.as_f64())
+        // Outside trading hours the last trade can be missing; today's close stands in
+        .or_else(|| ticker.pointer("/day/c").and_then(|v| v.as_f64()).filter(|c| *c > 0.0))
+        .ok_or_else(|| anyhow!("No last trade in snapshot"))?;
+    let traded_at = trade
+        .and_then(|t| t.get("t"))
+        .or_else(|| ticker.get("updated"))
+        .and_then(|v| v.as_i64());
+    Ok((price, traded_at.map_or(0.0, |nanos| age_from_nanos(nanos, now))))
+}
+
+/// The previous session's close and its age
+pub fn parse_previous_close(body: &serde_json::Value, now: DateTime<Utc>) -> Result<(f64, f64)> {
+    let bar = body
+        .get("results")
+        .and_then(|v| v.as_array())
+        .and_then(|results| results.first())
+        .ok_or_else(|| anyhow!("No previous close in response"))?;
+    let close = bar.get("c").and_then(|v| v.as_f64()).ok_or_else(|| anyhow!("No close in previous-close bar"))?;
+    let age_secs = bar
+        .get("t")
+        .and_then(|v| v.as_i64())
+        .and_then(DateTime::<Utc>::from_timestamp_millis)
+        .map_or(0.0, |at| ((now - at).num_milliseconds() as f64 / 1000.0).max(0.0));
+    Ok((close, age_secs))
+}
+
+/// The first index value in an indices snapshot, and its age
+pub fn parse_index_value(body: &serde_json::Value, now: DateTime<Utc>) -> Result<(f64, f64)> {
+    let index = body
+        .get("results")
+        .and_then(|v| v.as_array())
+        .and_then(|results| results.first())
+        .ok_or_else(|| anyhow!("No index in response"))?;
+    if let Some(error) = index.get("error").and_then(|v| v.as_str()) {
+        bail!("Polygon {}: {}", error, index.get("message").and_then(|v| v.as_str()).unwrap_or_default());
+    }
+    let value = index.get("value").and_then(|v| v.as_f64()).ok_or_else(|| anyhow!("No index value"))?;
+    let age_secs = index.get("last_updated").and_then(|v| v.as_i64()).map_or(0.0, |nanos| age_from_nanos(nanos, now));
+    Ok((value, age_secs))
+}
+
+/// Daily closes from an aggregates response, the newest `days`, oldest first
+pub fn parse_daily_aggregates(body: &serde_json::Value, days: usize) -> Result<Vec<DailyBar>> {
+    let Some(results) = body.get("results").and_then(|v| v.as_array()) else {
+        // No sessions in the range comes back without results
+        if body.get("resultsCount").and_then(|v| v.as_u64()) == Some(0) {
+            return Ok(Vec::new());
+        }
+        bail!("Polygon: {}", error_message(body));
+    };
+    let mut bars = results
+        .iter()
+        .map(|bar| {
+            // Bars start at midnight New York time, which is the same date in UTC
+            let date: NaiveDate = bar
+                .get("t")
+                .and_then(|v| v.as_i64())
+                .and_then(DateTime::<Utc>::from_timestamp_millis)
+                .ok_or_else(|| anyhow!("No timestamp on daily bar"))?
+                .date_naive();
+            let close = bar.get("c").and_then(|v| v.as_f64()).ok_or_else(|| anyhow!("No close on {}", date))?;
+            Ok(DailyBar { date, close })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    bars.sort_by_key(|bar| bar.date);
+    let skip = bars.len().saturating_sub(days);
+    Ok(bars.split_off(skip))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_responses() {
+        let now = DateTime::parse_from_rfc3339("2024-07-02T14:00:00Z").unwrap().with_timezone(&Utc);
+        let nanos = |secs_ago: i64| (now - chrono::Duration::seconds(secs_ago)).timestamp_nanos_opt().unwrap();
+
+        // A 15-minute delayed snapshot is 900s old
+        let snapshot = json!({"status": "OK", "ticker": {"ticker": "SPY", "lastTrade": {"p": 545.2, "t": nanos(900)}}});
+        assert_eq!(parse_snapshot(&snapshot, now).unwrap(), (545.2, 900.0));
+        let closed = json!({"ticker": {"day": {"c": 544.0}, "updated": nanos(60)}});
+        assert_eq!(parse_snapshot(&closed, now).unwrap(), (544.0, 60.0));
+        let denied = json!({"status": "NOT_AUTHORIZED", "message": "not entitled"});
+        assert!(parse_snapshot(&denied, now).unwrap_err().to_string().contains("not entitled"));
+
+        let prev = json!({"results": [{"T": "SPY", "c": 542.0, "t": (now - chrono::Duration::hours(18)).timestamp_millis()}]});
+        assert_eq!(parse_previous_close(&prev, now).unwrap(), (542.0, 64800.0));
+
+        let vix = json!({"results": [{"ticker": "I:VIX", "value": 12.4, "last_updated": nanos(1)}]});
+        assert_eq!(parse_index_value(&vix, now).unwrap(), (12.4, 1.0));
+        let no_plan = json!({"results": [{"ticker": "I:VIX", "error": "NOT_AUTHORIZED", "message": "upgrade"}]});
+        assert!(parse_index_value(&no_plan, now).is_err());
+
+        // Midnight New York (04:00 UTC in summer), newest `days` kept
+        let day = |date: &str, close: f64| {
+            let at = DateTime::parse_from_rfc3339(&format!("{}T04:00:00Z", date)).unwrap();
+            json!({"c": close, "t": at.timestamp_millis()})
+        };
+        let aggs = json!({"results": [day("2024-06-28", 544.2), day("2024-07-01", 545.3), day("2024-06-27", 546.0)]});
+        let bars = parse_daily_aggregates(&aggs, 2).unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0], DailyBar { date: NaiveDate::from_ymd_opt(2024, 6, 28).unwrap(), close: 544.2 });
+        assert_eq!(bars[1].close, 545.3);
+        assert!(parse_daily_aggregates(&json!({"resultsCount": 0}), 5).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+//! Quote Cache
+//!
+//! Every provider answers repeat requests for a symbol from a short-lived
+//! cache, so a Sensor polling every few seconds stays inside the upstream
+//! rate limit. A cached quote keeps a simulated price's provenance (a real
+//! one becomes `Cached`) and reports how long ago it was obtained.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::market::{Quote, QuoteSource};
+
+/// Cache entry with TTL
+struct CacheEntry {
+    value: f64,
+    source: QuoteSource,
+    /// How old the price already was when it was cached
+    age_secs: f64,
+    cached_at: Instant,
+}
+
+impl CacheEntry {
+    fn is_valid(&self, ttl: Duration) -> bool {
+        self.cached_at.elapsed() < ttl
+    }
+}
+
+/// Quotes by symbol, each valid for `ttl`
+pub struct QuoteCache {
+    entries: RwLock<HashMap<String, CacheEntry>>,
+    ttl: Duration,
+}
+
+impl QuoteCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { entries: RwLock::new(HashMap::new()), ttl }
+    }
+
+    /// The cached quote for `symbol`, reporting its original source and age
+    pub async fn get(&self, symbol: &str) -> Option<Quote> {
+        let entries = self.entries.read().await;
+        let entry = entries.get(symbol).filter(|entry| entry.is_valid(self.ttl))?;
+        let source = match entry.source {
+            QuoteSource::Simulated => QuoteSource::Simulated,
+            _ => QuoteSource::Cached,
+        };
+        Some(Quote {
+            symbol: symbol.to_string(),
+            price: entry.value,
+            source,
+            age_secs: entry.age_secs + entry.cached_at.elapsed().as_secs_f64(),
+        })
+    }
+
+    /// Store a quote obtained now
+    pub async fn set(&self, quote: &Quote) {
+        self.entries.write().await.insert(
+            quote.symbol.clone(),
+            CacheEntry { value: quote.price, source: quote.source, age_secs: quote.age_secs, cached_at: Instant::now() },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cached_provenance() {
+        let cache = QuoteCache::new(Duration::from_secs(60));
+        assert!(cache.get("SPY").await.is_none());
+
+        let quote = |symbol: &str, source| Quote { symbol: symbol.to_string(), price: 100.0, source, age_secs: 900.0 };
+        cache.set(&quote("SPY", QuoteSource::Live)).await;
+        cache.set(&quote("BND", QuoteSource::Simulated)).await;
+
+        // A delayed live price stays as old as it was
+        let spy = cache.get("SPY").await.unwrap();
+        assert_eq!(spy.source, QuoteSource::Cached);
+        assert!(spy.age_secs >= 900.0);
+        assert_eq!(cache.get("BND").await.unwrap().source, QuoteSource::Simulated);
+
+        let expired = QuoteCache::new(Duration::ZERO);
+        expired.set(&quote("SPY", QuoteSource::Live)).await;
+        assert!(expired.get("SPY").await.is_none());
+    }
+}
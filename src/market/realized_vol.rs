@@ -0,0 +1,79 @@
+//! Realized Volatility
+//!
+//! Annualized volatility from recent live price samples, mapped onto the
+//! VIX scale so the Guardian can apply its usual thresholds when no real
+//! VIX reading is available.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Seconds of regular trading in a year (252 sessions × 6.5 hours)
+const TRADING_SECONDS_PER_YEAR: f64 = 252.0 * 6.5 * 3600.0;
+
+/// Returns spanning more than this (e.g. overnight) are left out
+const MAX_GAP_SECS: f64 = 3600.0;
+
+/// A live price observation kept for volatility estimation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceSample {
+    pub price: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Annualized volatility (as a fraction) from time-ordered samples
+/// 
+/// Uses log returns scaled by the time between samples, so an irregular
+/// poll interval doesn't bias the estimate. Needs `min_returns` usable
+/// returns.
+pub fn annualized_volatility(samples: &[PriceSample], min_returns: usize) -> Option<f64> {
+    let mut squared_returns = 0.0;
+    let mut elapsed_secs = 0.0;
+    let mut count = 0;
+    
+    for pair in samples.windows(2) {
+        let dt = (pair[1].timestamp - pair[0].timestamp).num_milliseconds() as f64 / 1000.0;
+        if dt <= 0.0 || dt > MAX_GAP_SECS || pair[0].price <= 0.0 || pair[1].price <= 0.0 {
+            continue;
+        }
+        squared_returns += (pair[1].price / pair[0].price).ln().powi(2);
+        elapsed_secs += dt;
+        count += 1;
+    }
+    
+    if count < min_returns.max(1) || elapsed_secs <= 0.0 {
+        return None;
+    }
+    Some((squared_returns / elapsed_secs * TRADING_SECONDS_PER_YEAR).sqrt())
+}
+
+/// Express annualized volatility on the VIX scale (vol points)
+/// 
+/// `multiplier` approximates the premium implied volatility usually
+/// carries over realized.
+pub fn vix_equivalent(annual_vol: f64, multiplier: f64) -> f64 {
+    annual_vol * 100.0 * multiplier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_annualized_volatility() {
+        // Alternate ±0.1% every minute
+        let start = Utc::now();
+        let samples: Vec<PriceSample> = (0..31)
+            .map(|i| PriceSample {
+                price: if i % 2 == 0 { 100.0 } else { 100.1 },
+                timestamp: start + chrono::Duration::minutes(i),
+            })
+            .collect();
+        
+        let vol = annualized_volatility(&samples, 10).unwrap();
+        let expected = (100.1f64 / 100.0).ln().abs() * (TRADING_SECONDS_PER_YEAR / 60.0).sqrt();
+        assert!((vol - expected).abs() < 1e-9);
+        assert!((vix_equivalent(vol, 1.0) - vol * 100.0).abs() < 1e-9);
+        
+        assert_eq!(annualized_volatility(&samples[..5], 10), None);
+    }
+}
@@ -0,0 +1,51 @@
+//! Simulated Quotes
+//!
+//! What a provider serves when it has no usable upstream answer (the demo
+//! key, a rate limit, an outage or a plan without the data): prices near a
+//! typical level for common ETFs and a VIX between 10 and 40, drawn from
+//! the run's seeded generator. Such quotes are marked `Simulated`, which
+//! the data-quality score and system mode account for.
+
+use tracing::warn;
+
+use crate::core::simulation::SimulationRng;
+
+/// A price within ±2% of the symbol's typical level
+pub fn price(symbol: &str, rng: &SimulationRng) -> f64 {
+    // Base prices for common ETFs
+    let base_price = match symbol {
+        "SPY" => 580.0,  // S&P 500 ETF
+        "BND" => 72.0,   // Bond ETF
+        "VIX" => 18.0,   // Volatility index
+        "QQQ" => 490.0,  // NASDAQ ETF
+        "IWM" => 220.0,  // Russell 2000 ETF
+        _ => 100.0,
+    };
+
+    // Add small random variation (±2%)
+    let variation = (rng.unit() - 0.5) * 0.04;
+    let price = base_price * (1.0 + variation);
+
+    warn!("Using simulated price for {}: ${:.2}", symbol, price);
+    price
+}
+
+/// A VIX of 18 ± 8, within 10 to 40
+pub fn vix(rng: &SimulationRng) -> f64 {
+    let base_vix = 18.0;
+    let variation = (rng.unit() - 0.5) * 16.0; // ±8 points
+    (base_vix + variation).clamp(10.0, 40.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulated_price() {
+        let price = price("SPY", &SimulationRng::from_entropy());
+
+        // Should be around $580 ±2%
+        assert!(price > 560.0 && price < 600.0);
+    }
+}
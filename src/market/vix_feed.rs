@@ -0,0 +1,182 @@
+//! Real VIX
+//!
+//! Providers without index data otherwise simulate VIX, so the Guardian's
+//! circuit breaker would act on a random walk. With `[market] vix_source =
+//! "cboe"` VIX is read from CBOE's delayed `_VIX` quote (about 15 minutes
+//! behind during the session), and with `"fred"` from FRED's VIXCLS series
+//! (the daily close, a day or more behind); neither needs a key. Readings
+//! are cached for `[market.vix_feed] cache_ttl_secs` and report their age,
+//! so the data-quality score reflects how old they are.
+//!
+//! When the source fails, the provider's own VIX is tried (see
+//! `vix_source`); if that is simulated too, VIX is simulated for that read
+//! when `simulate_on_failure` is set (the default), and otherwise the read
+//! fails and the Guardian halts as it would on any VIX outage.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::America::New_York;
+use reqwest::Client;
+use std::time::Duration;
+use tracing::info;
+
+use crate::core::config::VixFeedConfig;
+use crate::market::quote_cache::QuoteCache;
+use crate::market::vix_history::VixHistory;
+use crate::market::{Quote, QuoteSource};
+
+/// VIX settles at 16:15 New York time
+const CLOSE_TIME: (u32, u32) = (16, 15);
+
+/// Where real VIX is read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VixFeedKind {
+    /// CBOE's delayed quote
+    Cboe,
+    /// FRED's VIXCLS daily closes
+    Fred,
+}
+
+impl VixFeedKind {
+    /// Kind selected by `[market] vix_source`, if it names a feed
+    pub fn from_source(source: &str) -> Option<Self> {
+        match source {
+            "cboe" => Some(Self::Cboe),
+            "fred" => Some(Self::Fred),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cboe => "CBOE",
+            Self::Fred => "FRED",
+        }
+    }
+}
+
+/// Reads and caches real VIX from one source
+pub struct VixFeed {
+    kind: VixFeedKind,
+    client: Client,
+    url: String,
+    cache: QuoteCache,
+    simulate_on_failure: bool,
+}
+
+impl VixFeed {
+    pub fn new(kind: VixFeedKind, config: &VixFeedConfig) -> Self {
+        let url = match kind {
+            VixFeedKind::Cboe => &config.cboe_url,
+            VixFeedKind::Fred => &config.fred_url,
+        };
+        Self {
+            kind,
+            client: Client::new(),
+            url: url.clone(),
+            cache: QuoteCache::new(Duration::from_secs(config.cache_ttl_secs)),
+            simulate_on_failure: config.simulate_on_failure,
+        }
+    }
+
+    pub fn kind(&self) -> VixFeedKind {
+        self.kind
+    }
+
+    /// Whether VIX may be simulated once every real source has failed
+    pub fn simulate_on_failure(&self) -> bool {
+        self.simulate_on_failure
+    }
+
+    /// Real VIX, cached, or why the source failed
+    pub async fn read(&self) -> Result<Quote> {
+        if let Some(cached) = self.cache.get("VIX").await {
+            return Ok(cached);
+        }
+        let quote = self.fetch().await.with_context(|| format!("{} VIX unavailable", self.kind.name()))?;
+        info!("VIX ({}): {:.2} ({:.0}s old)", self.kind.name(), quote.price, quote.age_secs);
+        self.cache.set(&quote).await;
+        Ok(quote)
+    }
+
+    async fn fetch(&self) -> Result<Quote> {
+        let response = self
+            .client
+            .get(&self.url)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .with_context(|| format!("requesting {}", self.url))?
+            .error_for_status()?;
+        let now = Utc::now();
+        let (price, age_secs) = match self.kind {
+            VixFeedKind::Cboe => parse_cboe_quote(&response.json().await?, now)?,
+            VixFeedKind::Fred => parse_fred_csv(&response.text().await?, now)?,
+        };
+        Ok(Quote { symbol: "VIX".to_string(), price, source: QuoteSource::Live, age_secs })
+    }
+}
+
+/// Seconds from `at` to `now`
+fn age_secs(at: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    ((now - at).num_milliseconds() as f64 / 1000.0).max(0.0)
+}
+
+/// VIX and its age from CBOE's delayed quote, whose times are New York's
+pub fn parse_cboe_quote(body: &serde_json::Value, now: DateTime<Utc>) -> Result<(f64, f64)> {
+    let data = body.get("data").ok_or_else(|| anyhow!("No data in CBOE quote"))?;
+    let price = data
+        .get("current_price")
+        .and_then(|v| v.as_f64())
+        .filter(|price| *price > 0.0)
+        .ok_or_else(|| anyhow!("No current price in CBOE quote"))?;
+    let age_secs = data
+        .get("last_trade_time")
+        .and_then(|v| v.as_str())
+        .and_then(|s| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").ok())
+        .and_then(|at| at.and_local_timezone(New_York).earliest())
+        .map_or(0.0, |at| age_secs(at.with_timezone(&Utc), now));
+    Ok((price, age_secs))
+}
+
+/// The newest close in FRED's VIXCLS CSV and its age from that day's
+/// settlement (holidays are blank or ".")
+pub fn parse_fred_csv(text: &str, now: DateTime<Utc>) -> Result<(f64, f64)> {
+    let history = VixHistory::parse_csv(text).context("FRED VIXCLS")?;
+    let Some(latest) = history.observations().last() else {
+        bail!("no VIX observations in FRED VIXCLS");
+    };
+    let (hour, minute) = CLOSE_TIME;
+    let close = latest
+        .at
+        .date_naive()
+        .and_time(NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or_default())
+        .and_local_timezone(New_York)
+        .earliest()
+        .map_or(latest.at, |at| at.with_timezone(&Utc));
+    Ok((latest.value, age_secs(close, now)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sources() {
+        let now = DateTime::parse_from_rfc3339("2024-05-10T20:30:00Z").unwrap().with_timezone(&Utc);
+
+        // 16:15 EDT is 20:15 UTC
+        let cboe = serde_json::json!({
+            "timestamp": "2024-05-10 16:15:02",
+            "data": {"symbol": "^VIX", "current_price": 12.55, "last_trade_time": "2024-05-10T16:15:00"}
+        });
+        assert_eq!(parse_cboe_quote(&cboe, now).unwrap(), (12.55, 900.0));
+        assert!(parse_cboe_quote(&serde_json::json!({"data": {"current_price": 0}}), now).is_err());
+
+        // The holiday's blank is skipped; the newest close settled a day ago
+        let fred = "observation_date,VIXCLS\n2024-05-08,13.00\n2024-05-09,12.69\n2024-05-10,.\n";
+        let (vix, age) = parse_fred_csv(fred, now).unwrap();
+        assert_eq!((vix, age), (12.69, 86_400.0 + 900.0));
+        assert!(parse_fred_csv("observation_date,VIXCLS\n", now).is_err());
+    }
+}
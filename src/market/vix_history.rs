@@ -0,0 +1,206 @@
+//! Historical VIX
+//!
+//! Simulated runs normally draw VIX from a random walk, so the Guardian's
+//! circuit breaker is never tested against the volatility regimes that
+//! matter (2008, March 2020). With `[market] vix_source = "history"` the
+//! provider replays a historical series instead: CBOE's daily
+//! `VIX_History.csv` (`driftguard fetch-vix` downloads it) or any CSV with
+//! a date or timestamp column and a close/value column, daily or minute
+//! bars. Replay advances one observation every `step_secs` of simulated
+//! time from `start` (default the first row), on a clock the API can speed
+//! up, pause and jump (see `core::sim_clock`); once the series is exhausted
+//! VIX reads fail, and the Guardian halts as it would on any VIX outage.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+use crate::core::sim_clock::{ClockState, SimClock};
+use crate::market::{Quote, QuoteSource};
+
+/// CBOE's daily VIX history (DATE, OPEN, HIGH, LOW, CLOSE)
+pub const CBOE_VIX_HISTORY_URL: &str = "https://cdn.cboe.com/api/global/us_indices/daily_prices/VIX_History.csv";
+
+const TIME_HEADERS: &[&str] = &["date", "observation_date", "timestamp", "datetime", "time"];
+const VALUE_HEADERS: &[&str] = &["close", "vix", "value", "price"];
+
+/// One VIX reading (a bar's close)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VixObservation {
+    pub at: DateTime<Utc>,
+    pub value: f64,
+}
+
+/// A historical VIX series, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct VixHistory {
+    observations: Vec<VixObservation>,
+}
+
+impl VixHistory {
+    /// Parse a CSV with a header row naming a date/timestamp column and a
+    /// close/value column (the last column if none is named). Rows that
+    /// don't parse are skipped.
+    pub fn parse_csv(text: &str) -> Result<Self> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(text.as_bytes());
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_lowercase()).collect();
+        let find = |names: &[&str]| names.iter().find_map(|name| headers.iter().position(|h| h == name));
+        let Some(time_col) = find(TIME_HEADERS) else {
+            bail!("no date or timestamp column in {:?}", headers);
+        };
+        let value_col = find(VALUE_HEADERS).unwrap_or(headers.len().saturating_sub(1));
+        
+        let mut observations: Vec<VixObservation> = reader
+            .records()
+            .filter_map(|record| {
+                let record = record.ok()?;
+                let at = parse_time(record.get(time_col)?)?;
+                let value: f64 = record.get(value_col)?.parse().ok()?;
+                (value > 0.0).then_some(VixObservation { at, value })
+            })
+            .collect();
+        if observations.is_empty() {
+            bail!("no VIX observations found");
+        }
+        observations.sort_by_key(|o| o.at);
+        observations.dedup_by_key(|o| o.at);
+        Ok(Self { observations })
+    }
+
+    /// Load a VIX history CSV from disk
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading VIX history {}", path.display()))?;
+        Self::parse_csv(&text).with_context(|| format!("parsing VIX history {}", path.display()))
+    }
+
+    /// Drop observations before `start`
+    pub fn starting_at(mut self, start: NaiveDate) -> Self {
+        let start = start.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        self.observations.retain(|o| o.at >= start);
+        self
+    }
+
+    pub fn observations(&self) -> &[VixObservation] {
+        &self.observations
+    }
+}
+
+/// Dates as CBOE writes them (MM/DD/YYYY), ISO dates, or timestamps
+fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+        return Some(at.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%m/%d/%Y %H:%M"] {
+        if let Ok(at) = NaiveDateTime::parse_from_str(s, format) {
+            return Some(at.and_utc());
+        }
+    }
+    ["%m/%d/%Y", "%Y-%m-%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(s, format).ok())
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|at| at.and_utc())
+}
+
+/// A VIX history played back against the simulated clock
+#[derive(Debug, Clone)]
+pub struct VixReplay {
+    history: VixHistory,
+    clock: SimClock,
+    step: Duration,
+}
+
+impl VixReplay {
+    /// Replay on a clock of its own, running at real time from now
+    pub fn new(history: VixHistory, step: Duration) -> Self {
+        Self { history, clock: SimClock::new(ClockState::start(Utc::now())), step }
+    }
+
+    /// Follow the swarm's simulated clock
+    pub fn with_clock(mut self, clock: SimClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// The observation being replayed `elapsed` into the replay, or none
+    /// past the end
+    pub fn at(&self, elapsed: Duration) -> Option<VixObservation> {
+        let index = elapsed.as_millis() / self.step.as_millis().max(1);
+        self.history.observations.get(usize::try_from(index).ok()?).copied()
+    }
+
+    /// The observation being replayed now
+    pub fn current(&self) -> Option<VixObservation> {
+        self.at(self.clock.elapsed())
+    }
+
+    /// Date of the last observation
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        self.history.observations.last().map(|o| o.at)
+    }
+
+    /// The observation being replayed now, as a quote (real volatility,
+    /// served from the local file); an error past the end
+    pub fn quote(&self) -> Result<Quote> {
+        let Some(observation) = self.current() else {
+            let end = self.end().map(|at| at.to_rfc3339()).unwrap_or_default();
+            bail!("VIX history exhausted (last observation {})", end);
+        };
+        info!("VIX (history {}): {:.2}", observation.at.format("%Y-%m-%d %H:%M"), observation.value);
+        Ok(Quote { symbol: "VIX".to_string(), price: observation.value, source: QuoteSource::Cached, age_secs: 0.0 })
+    }
+}
+
+/// Download CBOE's daily VIX history CSV
+pub async fn download_cboe() -> Result<String> {
+    let response = reqwest::get(CBOE_VIX_HISTORY_URL)
+        .await
+        .context("requesting CBOE VIX history")?
+        .error_for_status()?;
+    let text = response.text().await?;
+    // Fail here rather than at replay time if CBOE changed the format
+    VixHistory::parse_csv(&text).context("CBOE VIX history")?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_and_replay() {
+        // CBOE layout, out of order, with a bad row
+        let cboe = "DATE,OPEN,HIGH,LOW,CLOSE\n\
+                    03/17/2020,76.91,83.56,70.37,75.91\n\
+                    03/16/2020,57.83,83.56,57.83,82.69\n\
+                    03/18/2020,n/a,n/a,n/a,n/a\n";
+        let history = VixHistory::parse_csv(cboe).unwrap();
+        let observations = history.observations();
+        assert_eq!(observations.len(), 2);
+        assert_eq!(observations[0].at, Utc.with_ymd_and_hms(2020, 3, 16, 0, 0, 0).unwrap());
+        assert_eq!(observations[0].value, 82.69);
+        
+        // Minute bars with a value column
+        let minutes = "timestamp,vix\n2024-08-05 13:30,38.6\n2024-08-05 13:31,41.2\n";
+        let history = VixHistory::parse_csv(minutes).unwrap();
+        assert_eq!(history.observations()[1].at, Utc.with_ymd_and_hms(2024, 8, 5, 13, 31, 0).unwrap());
+        assert!(VixHistory::parse_csv("symbol,close\nVIX,20\n").is_err());
+        
+        let history = VixHistory::parse_csv(cboe).unwrap().starting_at(NaiveDate::from_ymd_opt(2020, 3, 17).unwrap());
+        assert_eq!(history.observations().len(), 1);
+        
+        // One observation per step, then nothing
+        let replay = VixReplay::new(VixHistory::parse_csv(cboe).unwrap(), Duration::from_secs(60));
+        assert_eq!(replay.at(Duration::ZERO).map(|o| o.value), Some(82.69));
+        assert_eq!(replay.at(Duration::from_secs(90)).map(|o| o.value), Some(75.91));
+        assert_eq!(replay.at(Duration::from_secs(120)), None);
+    }
+}
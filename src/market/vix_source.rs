@@ -0,0 +1,177 @@
+//! VIX Source
+//!
+//! Most providers have no VIX of their own and simulate it. `[market]
+//! vix_source` can put a better one in front of whichever provider (or
+//! failover chain) quotes: a replayed historical series, VIX derived from
+//! a volatility ETF or futures quoted through that provider, or real VIX
+//! read from CBOE or FRED. `VixSource` wraps the quoting provider once and
+//! answers VIX from that source, passing everything else through.
+//!
+//! A proxy only stands in while its own quote is real. When the CBOE or
+//! FRED read fails, the provider's own VIX is tried next (Yahoo's `^VIX`,
+//! Polygon's `I:VIX`, any member of a chain); only when that is simulated
+//! too is VIX simulated or, without `simulate_on_failure`, the read failed.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::market::daily_history::DailyBar;
+use crate::market::vix_feed::VixFeed;
+use crate::market::vix_history::VixReplay;
+use crate::market::vol_proxy::VolProxy;
+use crate::market::{MarketDataProvider, PriceUpdates, ProviderHealth, Quote, QuoteSource};
+
+/// A provider whose VIX comes from the configured source
+pub struct VixSource {
+    inner: Arc<dyn MarketDataProvider>,
+    /// Historical VIX replayed instead of the provider's
+    replay: Option<VixReplay>,
+    /// Instrument VIX is derived from, quoted through the provider
+    proxy: Option<VolProxy>,
+    /// Real VIX read from CBOE or FRED
+    feed: Option<VixFeed>,
+}
+
+impl VixSource {
+    /// `inner`'s own VIX until a source is added
+    pub fn new(inner: Arc<dyn MarketDataProvider>) -> Self {
+        Self { inner, replay: None, proxy: None, feed: None }
+    }
+
+    /// Replay a historical VIX series
+    pub fn with_vix_history(mut self, replay: VixReplay) -> Self {
+        self.replay = Some(replay);
+        self
+    }
+
+    /// Derive VIX from a volatility ETF or futures quote
+    pub fn with_vol_proxy(mut self, proxy: VolProxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Read real VIX from CBOE or FRED
+    pub fn with_vix_feed(mut self, feed: VixFeed) -> Self {
+        self.feed = Some(feed);
+        self
+    }
+
+    /// Real VIX from the feed, else the provider's if real, else the
+    /// provider's simulated one or the feed's error
+    async fn feed_quote(&self, feed: &VixFeed) -> Result<Quote> {
+        let error = match feed.read().await {
+            Ok(quote) => return Ok(quote),
+            Err(e) => e,
+        };
+        match self.inner.get_vix_quote().await {
+            Ok(quote) if quote.source != QuoteSource::Simulated => {
+                warn!("{:#}; using the provider's VIX", error);
+                Ok(quote)
+            }
+            quote if feed.simulate_on_failure() => {
+                warn!("{:#}; simulating it", error);
+                quote
+            }
+            _ => Err(error),
+        }
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for VixSource {
+    async fn get_price(&self, symbol: &str) -> Result<f64> {
+        self.inner.get_price(symbol).await
+    }
+
+    async fn get_vix(&self) -> Result<f64> {
+        Ok(self.get_vix_quote().await?.price)
+    }
+
+    async fn get_quote(&self, symbol: &str) -> Result<Quote> {
+        self.inner.get_quote(symbol).await
+    }
+
+    async fn get_vix_quote(&self) -> Result<Quote> {
+        if let Some(replay) = &self.replay {
+            return replay.quote();
+        }
+        if let Some(proxy) = &self.proxy {
+            if let Some(quote) = proxy.quote(self.inner.as_ref()).await {
+                return Ok(quote);
+            }
+        }
+        match &self.feed {
+            Some(feed) => self.feed_quote(feed).await,
+            None => self.inner.get_vix_quote().await,
+        }
+    }
+
+    async fn fetch_daily_closes(&self, symbol: &str, days: usize) -> Result<Vec<DailyBar>> {
+        self.inner.fetch_daily_closes(symbol, days).await
+    }
+
+    async fn get_fx_rate(&self, from: &str, to: &str) -> Result<f64> {
+        self.inner.get_fx_rate(from, to).await
+    }
+
+    fn price_updates(&self) -> Option<PriceUpdates> {
+        self.inner.price_updates()
+    }
+
+    fn provider_health(&self) -> Vec<ProviderHealth> {
+        self.inner.provider_health()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::VixFeedConfig;
+    use crate::market::vix_feed::VixFeedKind;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// VIX of 25, live or (while `simulated`) simulated
+    #[derive(Default)]
+    struct Fake {
+        simulated: AtomicBool,
+    }
+
+    #[async_trait]
+    impl MarketDataProvider for Fake {
+        async fn get_price(&self, _symbol: &str) -> Result<f64> {
+            Ok(100.0)
+        }
+
+        async fn get_vix(&self) -> Result<f64> {
+            Ok(25.0)
+        }
+
+        async fn get_vix_quote(&self) -> Result<Quote> {
+            let source = if self.simulated.load(Ordering::SeqCst) { QuoteSource::Simulated } else { QuoteSource::Live };
+            Ok(Quote { symbol: "VIX".to_string(), price: 25.0, source, age_secs: 0.0 })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_feed_falls_through() {
+        // Nothing listens on the discard port, so the feed fails at once
+        let config = |simulate_on_failure| VixFeedConfig {
+            cboe_url: "http://127.0.0.1:9/_VIX.json".to_string(),
+            simulate_on_failure,
+            ..VixFeedConfig::default()
+        };
+        let provider = Arc::new(Fake::default());
+        let strict = VixSource::new(provider.clone()).with_vix_feed(VixFeed::new(VixFeedKind::Cboe, &config(false)));
+
+        // The provider's real VIX stands in for the failed feed
+        assert_eq!(strict.get_vix_quote().await.unwrap().source, QuoteSource::Live);
+
+        // Every real source failed: an error, or a simulated VIX if allowed
+        provider.simulated.store(true, Ordering::SeqCst);
+        assert!(strict.get_vix_quote().await.is_err());
+        let lenient = VixSource::new(provider).with_vix_feed(VixFeed::new(VixFeedKind::Cboe, &config(true)));
+        assert_eq!(lenient.get_vix_quote().await.unwrap().source, QuoteSource::Simulated);
+    }
+}
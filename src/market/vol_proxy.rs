@@ -0,0 +1,173 @@
+//! Volatility Proxies
+//!
+//! Deployments without index data can still give the Guardian a real
+//! volatility signal from instruments their provider does quote. With
+//! `[market] vix_source = "etf"` VIX is derived from a volatility ETF
+//! (VIXY, UVXY, or an inverse one such as SVXY): the ETF tracks short-term
+//! VIX futures at a fixed leverage, so its move since a reference price,
+//! with the leverage undone, scales a reference VIX level. With
+//! `vix_source = "futures"` a front-month VX futures quote is read directly
+//! in vol points, divided by `futures_scale` for providers quoting in other
+//! units. Both read futures rather than spot VIX, so they run a little above
+//! VIX in calm markets (contango); ETF readings also drift low as roll decay
+//! accumulates, until `reference_price` is refreshed.
+
+use anyhow::{bail, ensure, Result};
+use std::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::core::config::VixProxyConfig;
+use crate::market::{MarketDataProvider, Quote, QuoteSource};
+
+/// Instrument a VIX-equivalent is derived from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyKind {
+    /// Exchange-traded product on short-term VIX futures
+    Etf,
+    /// Front-month VX futures
+    Futures,
+}
+
+impl ProxyKind {
+    /// Kind selected by `[market] vix_source`, if it names a proxy
+    pub fn from_source(source: &str) -> Option<Self> {
+        match source {
+            "etf" => Some(Self::Etf),
+            "futures" => Some(Self::Futures),
+            _ => None,
+        }
+    }
+
+    fn default_symbol(self) -> &'static str {
+        match self {
+            Self::Etf => "VIXY",
+            Self::Futures => "VX",
+        }
+    }
+}
+
+/// VIX level implied by an ETF at `price`, given that VIX read
+/// `reference_vix` when the ETF was at `reference_price`
+pub fn etf_vix(price: f64, reference_price: f64, reference_vix: f64, leverage: f64) -> f64 {
+    reference_vix * (price / reference_price).powf(1.0 / leverage)
+}
+
+/// VIX level of a futures quote in `scale` price units per vol point
+pub fn futures_vix(price: f64, scale: f64) -> f64 {
+    price / scale
+}
+
+/// Converts quotes of the proxy instrument to VIX-equivalents
+#[derive(Debug)]
+pub struct VolProxy {
+    kind: ProxyKind,
+    symbol: String,
+    leverage: f64,
+    reference_vix: f64,
+    futures_scale: f64,
+    /// Configured, or the first price converted
+    reference_price: Mutex<Option<f64>>,
+}
+
+impl VolProxy {
+    pub fn new(kind: ProxyKind, config: &VixProxyConfig) -> Result<Self> {
+        ensure!(config.leverage.is_finite() && config.leverage != 0.0, "vix_proxy.leverage must be non-zero");
+        ensure!(config.reference_vix > 0.0, "vix_proxy.reference_vix must be positive");
+        ensure!(config.futures_scale > 0.0, "vix_proxy.futures_scale must be positive");
+        if let Some(price) = config.reference_price {
+            ensure!(price > 0.0, "vix_proxy.reference_price must be positive");
+        }
+        Ok(Self {
+            kind,
+            symbol: config.symbol.clone().unwrap_or_else(|| kind.default_symbol().to_string()),
+            leverage: config.leverage,
+            reference_vix: config.reference_vix,
+            futures_scale: config.futures_scale,
+            reference_price: Mutex::new(config.reference_price),
+        })
+    }
+
+    pub fn kind(&self) -> ProxyKind {
+        self.kind
+    }
+
+    /// Symbol quoted for the proxy
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// VIX-equivalent of a live `price` of the proxy
+    pub fn vix_equivalent(&self, price: f64) -> Result<f64> {
+        if !(price.is_finite() && price > 0.0) {
+            bail!("{} price {} can't be converted to VIX", self.symbol, price);
+        }
+        let vix = match self.kind {
+            ProxyKind::Etf => {
+                let mut reference = self.reference_price.lock().unwrap_or_else(|e| e.into_inner());
+                let reference_price = *reference.get_or_insert(price);
+                etf_vix(price, reference_price, self.reference_vix, self.leverage)
+            }
+            ProxyKind::Futures => futures_vix(price, self.futures_scale),
+        };
+        Ok(vix)
+    }
+
+    /// VIX derived from `market`'s quote of the proxy; none unless that
+    /// quote is real
+    pub async fn quote(&self, market: &dyn MarketDataProvider) -> Option<Quote> {
+        match market.get_quote(&self.symbol).await {
+            Ok(quote) if quote.source != QuoteSource::Simulated => match self.vix_equivalent(quote.price) {
+                Ok(vix) => {
+                    info!("VIX ({} {:.2}): {:.2}", self.symbol, quote.price, vix);
+                    Some(Quote { symbol: "VIX".to_string(), price: vix, ..quote })
+                }
+                Err(e) => {
+                    debug!("{}, not deriving VIX", e);
+                    None
+                }
+            },
+            Ok(_) => {
+                debug!("No live {} quote, not deriving VIX", self.symbol);
+                None
+            }
+            Err(e) => {
+                debug!("No {} quote ({}), not deriving VIX", self.symbol, e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vix_equivalent() {
+        assert_eq!(ProxyKind::from_source("etf"), Some(ProxyKind::Etf));
+        assert_eq!(ProxyKind::from_source("history"), None);
+        
+        // Unlevered: VIX moves with the ETF
+        assert!((etf_vix(55.0, 50.0, 20.0, 1.0) - 22.0).abs() < 1e-9);
+        // UVXY up 21% is short-term futures up 13.5%
+        assert!((etf_vix(12.1, 10.0, 20.0, 1.5) - 20.0 * 1.21f64.powf(1.0 / 1.5)).abs() < 1e-9);
+        // An inverse ETF falling means volatility rising
+        assert!(etf_vix(45.0, 50.0, 20.0, -0.5) > 20.0);
+        assert_eq!(futures_vix(2_150.0, 100.0), 21.5);
+        
+        // The first price anchors the ETF at reference_vix
+        let config = VixProxyConfig { symbol: Some("UVXY".to_string()), leverage: 1.5, ..VixProxyConfig::default() };
+        let proxy = VolProxy::new(ProxyKind::Etf, &config).unwrap();
+        assert_eq!(proxy.symbol(), "UVXY");
+        assert!((proxy.vix_equivalent(30.0).unwrap() - 18.0).abs() < 1e-9);
+        assert!(proxy.vix_equivalent(36.0).unwrap() > 18.0);
+        assert!(proxy.vix_equivalent(0.0).is_err());
+        
+        let futures = VolProxy::new(ProxyKind::Futures, &VixProxyConfig::default()).unwrap();
+        assert_eq!(futures.symbol(), "VX");
+        assert_eq!(futures.vix_equivalent(19.25).unwrap(), 19.25);
+        
+        let levered_to_zero = VixProxyConfig { leverage: 0.0, ..VixProxyConfig::default() };
+        assert!(VolProxy::new(ProxyKind::Etf, &levered_to_zero).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+//! Yahoo Finance Market Data Provider
+//!
+//! Quotes from Yahoo Finance's public chart endpoint, which needs no API
+//! key. It is unofficial and unversioned, rate-limits by address and may
+//! change without notice, so it serves as a fallback: with `[market.yahoo]
+//! fallback` on, the Sensor uses it when the configured provider has no
+//! key, rather than simulating every price. `provider = "yahoo"` selects it
+//! outright.
+//!
+//! Each quote reports its age from the exchange's last trade time. VIX is
+//! read from `^VIX` with `[market] vix_source = "yahoo"`, exchange rates
+//! from the `GBPUSD=X` style pairs, and daily closes for startup history
+//! from the same chart endpoint. Symbols use Yahoo's own exchange suffixes
+//! (`VOD.L`, `7203.T`), so every listing is quoted as configured.
+//!
+//! Quotes are cached for `cache_ttl_secs`. A failed request serves a
+//! simulated price for that call only.
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::{Client, StatusCode};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::core::config::YahooConfig;
+use crate::core::simulation::SimulationRng;
+use crate::market::daily_history::DailyBar;
+use crate::market::quote_cache::QuoteCache;
+use crate::market::simulated;
+use crate::market::{MarketDataProvider, Quote, QuoteSource};
+
+/// Yahoo's symbol for the CBOE Volatility Index
+const VIX_SYMBOL: &str = "^VIX";
+
+/// Requests without a browser-like user agent are often refused
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; DriftGuard)";
+
+/// Yahoo Finance provider with caching
+pub struct YahooFinanceProvider {
+    client: Client,
+    base_url: String,
+    cache: QuoteCache,
+    /// Read VIX from `^VIX`
+    index_vix: bool,
+    /// Source of the simulated variation
+    rng: SimulationRng,
+}
+
+impl YahooFinanceProvider {
+    pub fn new(config: &YahooConfig) -> Self {
+        Self {
+            client: Client::builder().user_agent(USER_AGENT).build().unwrap_or_default(),
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            cache: QuoteCache::new(Duration::from_secs(config.cache_ttl_secs)),
+            index_vix: false,
+            rng: SimulationRng::from_entropy(),
+        }
+    }
+
+    /// Draw simulated prices and VIX from a seeded generator
+    pub fn with_rng(mut self, rng: SimulationRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Read VIX from `^VIX` instead of simulating it
+    pub fn with_index_vix(mut self) -> Self {
+        self.index_vix = true;
+        self
+    }
+
+    /// The chart of `symbol` with `query` parameters
+    async fn chart(&self, symbol: &str, query: &str, timeout: Duration) -> Result<serde_json::Value> {
+        let response = self
+            .client
+            .get(format!("{}/v8/finance/chart/{}?{}", self.base_url, symbol.replace('^', "%5E"), query))
+            .timeout(timeout)
+            .send()
+            .await
+            .context("Failed to send request to Yahoo Finance")?;
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            bail!("Yahoo Finance: Rate limited");
+        }
+        // Unknown symbols come back as 404 with an error body
+        response.json().await.context("Failed to parse Yahoo Finance response")
+    }
+
+    /// Last price of `symbol` and its age
+    async fn fetch_quote(&self, symbol: &str) -> Result<Quote> {
+        let body = self.chart(symbol, "interval=1d&range=1d", Duration::from_secs(10)).await?;
+        let (price, age_secs) = parse_chart_quote(&body, Utc::now())?;
+        info!("Yahoo Finance: {} = {:.2} ({:.0}s old)", symbol, price, age_secs);
+        Ok(Quote { symbol: symbol.to_string(), price, source: QuoteSource::Live, age_secs })
+    }
+
+    /// A simulated quote for `symbol`, cached like a real one
+    async fn simulated_quote(&self, symbol: &str, price: f64) -> Quote {
+        let quote = Quote { symbol: symbol.to_string(), price, source: QuoteSource::Simulated, age_secs: 0.0 };
+        self.cache.set(&quote).await;
+        quote
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for YahooFinanceProvider {
+    async fn get_price(&self, symbol: &str) -> Result<f64> {
+        Ok(self.get_quote(symbol).await?.price)
+    }
+
+    async fn get_vix(&self) -> Result<f64> {
+        Ok(self.get_vix_quote().await?.price)
+    }
+
+    async fn get_quote(&self, symbol: &str) -> Result<Quote> {
+        if let Some(cached) = self.cache.get(symbol).await {
+            debug!("Cache hit for {}: ${:.2}", symbol, cached.price);
+            return Ok(cached);
+        }
+        match self.fetch_quote(symbol).await {
+            Ok(quote) => {
+                self.cache.set(&quote).await;
+                Ok(quote)
+            }
+            Err(e) => {
+                warn!("Yahoo Finance error for {}, simulating this quote: {:#}", symbol, e);
+                Ok(self.simulated_quote(symbol, simulated::price(symbol, &self.rng)).await)
+            }
+        }
+    }
+
+    async fn get_vix_quote(&self) -> Result<Quote> {
+        if let Some(cached) = self.cache.get("VIX").await {
+            return Ok(cached);
+        }
+        if self.index_vix {
+            match self.fetch_quote(VIX_SYMBOL).await {
+                Ok(quote) => {
+                    let quote = Quote { symbol: "VIX".to_string(), ..quote };
+                    self.cache.set(&quote).await;
+                    return Ok(quote);
+                }
+                Err(e) => warn!("Yahoo Finance VIX unavailable, simulating it: {:#}", e),
+            }
+        }
+
+        let vix = simulated::vix(&self.rng);
+        info!("VIX (simulated): {:.2}", vix);
+        Ok(self.simulated_quote("VIX", vix).await)
+    }
+
+    async fn fetch_daily_closes(&self, symbol: &str, days: usize) -> Result<Vec<DailyBar>> {
+        // Calendar days enough to cover `days` sessions, weekends and holidays included
+        let to = Utc::now();
+        let from = to - chrono::Duration::days(days as i64 * 7 / 5 + 10);
+        let query = format!("interval=1d&period1={}&period2={}", from.timestamp(), to.timestamp());
+
+        debug!("Fetching daily history for {} from Yahoo Finance", symbol);
+
+        let body = self.chart(symbol, &query, Duration::from_secs(30)).await?;
+        parse_chart_closes(&body, days)
+    }
+
+    async fn get_fx_rate(&self, from: &str, to: &str) -> Result<f64> {
+        let body = self.chart(&format!("{}{}=X", from, to), "interval=1d&range=1d", Duration::from_secs(10)).await?;
+        Ok(parse_chart_quote(&body, Utc::now())?.0)
+    }
+}
+
+/// The chart result, or what the error says went wrong
+fn chart_result(body: &serde_json::Value) -> Result<&serde_json::Value> {
+    if let Some(result) = body.pointer("/chart/result/0") {
+        return Ok(result);
+    }
+    let error = body
+        .pointer("/chart/error/description")
+        .or_else(|| body.pointer("/finance/error/description"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("no chart in response");
+    bail!("Yahoo Finance: {}", error)
+}
+
+/// Last price and its age from a chart's metadata
+pub fn parse_chart_quote(body: &serde_json::Value, now: DateTime<Utc>) -> Result<(f64, f64)> {
+    let meta = chart_result(body)?.get("meta").ok_or_else(|| anyhow!("No metadata in chart"))?;
+    let price = meta
+        .get("regularMarketPrice")
+        .and_then(|v| v.as_f64())
+        .filter(|price| *price > 0.0)
+        .ok_or_else(|| anyhow!("No market price in chart"))?;
+    let age_secs = meta
+        .get("regularMarketTime")
+        .and_then(|v| v.as_i64())
+        .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+        .map_or(0.0, |at| ((now - at).num_milliseconds() as f64 / 1000.0).max(0.0));
+    Ok((price, age_secs))
+}
+
+/// Daily closes from a chart, the newest `days`, oldest first
+pub fn parse_chart_closes(body: &serde_json::Value, days: usize) -> Result<Vec<DailyBar>> {
+    let result = chart_result(body)?;
+    // Sessions are stamped at the exchange's open; its offset gives the local date
+    let offset = result.pointer("/meta/gmtoffset").and_then(|v| v.as_i64()).unwrap_or(0);
+    let Some(timestamps) = result.get("timestamp").and_then(|v| v.as_array()) else {
+        // No sessions in the range come back without timestamps
+        return Ok(Vec::new());
+    };
+    let closes = result
+        .pointer("/indicators/quote/0/close")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("No closes in chart"))?;
+
+    let mut bars: Vec<DailyBar> = Vec::new();
+    for (at, close) in timestamps.iter().zip(closes) {
+        // Halted sessions have no close
+        let (Some(at), Some(close)) = (at.as_i64(), close.as_f64()) else {
+            continue;
+        };
+        let date: NaiveDate = DateTime::<Utc>::from_timestamp(at + offset, 0)
+            .ok_or_else(|| anyhow!("Bad timestamp {} on daily bar", at))?
+            .date_naive();
+        // A live session shows up as a second bar for today
+        bars.retain(|bar| bar.date != date);
+        bars.push(DailyBar { date, close });
+    }
+    bars.sort_by_key(|bar| bar.date);
+    let skip = bars.len().saturating_sub(days);
+    Ok(bars.split_off(skip))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_chart() {
+        let now = DateTime::parse_from_rfc3339("2024-07-02T14:00:00Z").unwrap().with_timezone(&Utc);
+
+        let quote = json!({"chart": {"result": [{"meta": {
+            "symbol": "VOD.L", "currency": "GBp", "regularMarketPrice": 71.5,
+            "regularMarketTime": now.timestamp() - 900,
+        }}], "error": null}});
+        assert_eq!(parse_chart_quote(&quote, now).unwrap(), (71.5, 900.0));
+        let unknown = json!({"chart": {"result": null, "error": {"code": "Not Found", "description": "No data found, symbol may be delisted"}}});
+        assert!(parse_chart_quote(&unknown, now).unwrap_err().to_string().contains("delisted"));
+
+        // Stamped 09:30 New York (-4h), a null close skipped, newest `days` kept
+        let open = |date: &str| DateTime::parse_from_rfc3339(&format!("{}T13:30:00Z", date)).unwrap().timestamp();
+        let chart = json!({"chart": {"result": [{
+            "meta": {"gmtoffset": -14400},
+            "timestamp": [open("2024-06-27"), open("2024-06-28"), open("2024-07-01"), open("2024-07-02")],
+            "indicators": {"quote": [{"close": [546.0, 544.2, null, 545.3]}]},
+        }]}});
+        let bars = parse_chart_closes(&chart, 2).unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0], DailyBar { date: NaiveDate::from_ymd_opt(2024, 6, 28).unwrap(), close: 544.2 });
+        assert_eq!(bars[1].date, NaiveDate::from_ymd_opt(2024, 7, 2).unwrap());
+        assert!(parse_chart_closes(&json!({"chart": {"result": [{"meta": {}}]}}), 5).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,144 @@
+//! Python Bindings
+//!
+//! PyO3 module exposing the pheromone physics to notebooks, so decay
+//! parameters can be explored against the same math the swarm runs.
+//! Built with maturin (see `pyproject.toml`) from the `python` feature:
+//!
+//! ```text
+//! maturin develop --release
+//! >>> import driftguard
+//! >>> p = driftguard.Pheromone("price", 1.0, 0.1)
+//! >>> p.forecast(30.0, 7)
+//! ```
+//!
+//! Pheromones read from Redis can be loaded with `Pheromone.from_json`.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::core::physics::{Pheromone, PheromoneType};
+use crate::core::Config;
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+/// A time-decaying signal, I(t) = I₀ × e^(-λt)
+#[pyclass(name = "Pheromone", module = "driftguard")]
+#[derive(Clone)]
+struct PyPheromone {
+    inner: Pheromone,
+}
+
+#[pymethods]
+impl PyPheromone {
+    #[new]
+    #[pyo3(signature = (label, intensity = 1.0, decay_rate = 0.1))]
+    fn new(label: String, intensity: f64, decay_rate: f64) -> PyResult<Self> {
+        if !(decay_rate > 0.0 && decay_rate.is_finite()) {
+            return Err(value_error("decay_rate must be a positive number"));
+        }
+        Ok(Self { inner: Pheromone::new(label, intensity, decay_rate) })
+    }
+
+    /// Parse a pheromone as stored on the blackboard
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let inner = serde_json::from_str(json).map_err(value_error)?;
+        Ok(Self { inner })
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.inner).map_err(value_error)
+    }
+
+    #[getter]
+    fn label(&self) -> &str {
+        &self.inner.label
+    }
+
+    #[getter]
+    fn initial_intensity(&self) -> f64 {
+        self.inner.initial_intensity
+    }
+
+    #[getter]
+    fn decay_rate(&self) -> f64 {
+        self.inner.decay_rate
+    }
+
+    /// Deposit time (RFC 3339)
+    #[getter]
+    fn created_at(&self) -> String {
+        self.inner.created_at.to_rfc3339()
+    }
+
+    fn current_intensity(&self) -> f64 {
+        self.inner.current_intensity()
+    }
+
+    fn intensity_in(&self, secs_ahead: f64) -> f64 {
+        self.inner.intensity_in(secs_ahead)
+    }
+
+    #[pyo3(signature = (horizon_secs, samples = 21))]
+    fn forecast(&self, horizon_secs: f64, samples: usize) -> Vec<f64> {
+        self.inner.forecast(horizon_secs, samples)
+    }
+
+    fn is_active(&self, threshold: f64) -> bool {
+        self.inner.is_active(threshold)
+    }
+
+    fn time_until_inactive(&self, threshold: f64) -> Option<f64> {
+        self.inner.time_until_inactive(threshold)
+    }
+
+    fn half_life(&self) -> f64 {
+        self.inner.half_life()
+    }
+
+    fn age_secs(&self) -> f64 {
+        self.inner.age_secs()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Pheromone(label={:?}, intensity={:.3}, decay_rate={})",
+            self.inner.label,
+            self.inner.current_intensity(),
+            self.inner.decay_rate
+        )
+    }
+}
+
+/// Decay rate and threshold of every pheromone type, from `config_path`
+/// or the built-in defaults
+#[pyfunction]
+#[pyo3(signature = (config_path = None))]
+fn pheromone_types<'py>(py: Python<'py>, config_path: Option<&str>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let config = match config_path {
+        Some(path) => Config::load(path).map_err(value_error)?,
+        None => Config::default(),
+    };
+    
+    PheromoneType::ALL
+        .iter()
+        .map(|ptype| {
+            let entry = PyDict::new(py);
+            entry.set_item("key", ptype.key())?;
+            entry.set_item("label", ptype.label())?;
+            entry.set_item("decay_rate", ptype.decay_rate(&config))?;
+            entry.set_item("threshold", ptype.threshold(&config))?;
+            Ok(entry)
+        })
+        .collect()
+}
+
+#[pymodule]
+fn driftguard(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyPheromone>()?;
+    m.add_function(wrap_pyfunction!(pheromone_types, m)?)?;
+    Ok(())
+}
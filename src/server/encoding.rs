@@ -0,0 +1,97 @@
+//! Dashboard Wire Encoding
+//!
+//! Dashboard messages are JSON text frames by default. A client can ask
+//! for MessagePack binary frames instead — with `/ws?encoding=msgpack`
+//! or a `{"type": "hello", "encoding": "msgpack"}` first message — to cut
+//! bandwidth for the high-frequency pheromone updates. Both encodings
+//! carry the same message schema (`SCHEMA_VERSION`), and client messages
+//! are accepted in either form regardless of the negotiated encoding.
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use warp::ws::Message;
+
+/// Version of the dashboard message schema, announced in the hello reply
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Encoding of server-to-dashboard frames
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WireEncoding {
+    /// JSON text frames
+    #[default]
+    Json,
+    /// MessagePack binary frames (maps with field names, same schema as JSON)
+    #[serde(alias = "messagepack")]
+    Msgpack,
+}
+
+/// Query parameters of the `/ws` upgrade request
+#[derive(Debug, Default, Deserialize)]
+pub struct WsParams {
+    #[serde(default)]
+    pub encoding: WireEncoding,
+    /// Operator name recorded in the audit log for this connection
+    #[serde(default)]
+    pub operator: Option<String>,
+}
+
+/// Encode a message as a frame in the given encoding
+pub fn encode<T: Serialize>(msg: &T, encoding: WireEncoding) -> Result<Message> {
+    Ok(match encoding {
+        WireEncoding::Json => Message::text(serde_json::to_string(msg)?),
+        WireEncoding::Msgpack => Message::binary(rmp_serde::to_vec_named(msg)?),
+    })
+}
+
+/// Decode a client frame: text frames as JSON, binary frames as MessagePack
+pub fn decode<T: DeserializeOwned>(frame: &Message) -> Option<T> {
+    if let Ok(text) = frame.to_str() {
+        serde_json::from_str(text).ok()
+    } else if frame.is_binary() {
+        rmp_serde::from_slice(frame.as_bytes()).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    #[serde(tag = "type")]
+    enum Sample {
+        #[serde(rename = "pheromone_update")]
+        Update { name: String, intensity: f64, detail: Option<String> },
+    }
+
+    #[test]
+    fn test_msgpack_matches_json_schema() {
+        let msg = Sample::Update { name: "Price Freshness".to_string(), intensity: 0.82, detail: None };
+        
+        let json = encode(&msg, WireEncoding::Json).unwrap();
+        let binary = encode(&msg, WireEncoding::Msgpack).unwrap();
+        assert!(json.is_text());
+        assert!(binary.is_binary());
+        assert!(binary.as_bytes().len() < json.as_bytes().len());
+        
+        // Both decode to the same document
+        let from_json: serde_json::Value = decode(&json).unwrap();
+        let from_binary: serde_json::Value = decode(&binary).unwrap();
+        assert_eq!(from_json, from_binary);
+        assert_eq!(from_binary["type"], "pheromone_update");
+        assert_eq!(decode::<Sample>(&binary).unwrap(), msg);
+    }
+
+    #[test]
+    fn test_encoding_names() {
+        let params: WsParams = serde_json::from_str(r#"{"encoding": "msgpack"}"#).unwrap();
+        assert_eq!(params.encoding, WireEncoding::Msgpack);
+        let params: WsParams = serde_json::from_str(r#"{"encoding": "messagepack"}"#).unwrap();
+        assert_eq!(params.encoding, WireEncoding::Msgpack);
+        let params: WsParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.encoding, WireEncoding::Json);
+    }
+}
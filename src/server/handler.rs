@@ -4,94 +4,486 @@
 //! Provides real-time pheromone intensity and agent status updates.
 
 use anyhow::Result;
+use chrono_tz::Tz;
 use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use tokio::sync::broadcast;
-use tracing::{error, info};
-use warp::ws::{Message, WebSocket};
+use tracing::{error, info, warn};
+use warp::ws::WebSocket;
 use warp::Filter;
 
-use crate::core::blackboard::{AgentMetrics, PheromoneEvent, PortfolioState, TargetAllocation, TradeLogEntry};
+use crate::core::activity;
+use crate::core::alerts;
+use crate::core::logging::{self, LogLevelRequest};
+use crate::core::mode::{self, KillSwitch, KillSwitchRequest, SystemStatus};
+use crate::core::optimizer::{self, OptimizeError, OptimizeRequest};
+use crate::core::sim_clock::{self, ClockState, JumpRequest, PauseRequest, SpeedRequest};
+use crate::core::allocation::{self, AllocationError, AllocationRequest};
+use crate::core::audit::{self, AuditEntry, AuditQuery, ClientIdentity};
+use crate::core::auth::{self, ApiUser, ApiUsers, AuthError};
+use crate::core::bootstrap::{self, PortfolioInit};
+use crate::core::clock;
+use crate::core::groups;
+use crate::core::order_queue;
+use crate::core::holdings;
+use crate::core::performance;
+use crate::core::pipeline::AgentRole;
+use crate::core::plan;
+use crate::core::rebalance;
+use crate::core::reset;
+use crate::core::risk_index;
+use crate::agents::forecast::DriftForecast;
+use crate::agents::shadow;
+use crate::core::runs::{self, RunMetadata};
+use crate::core::simulation::{self, SimulationRng};
+use crate::core::statements;
+use crate::core::trails;
+use crate::core::blackboard::{PheromoneEvent, PortfolioState, TargetAllocation};
 use crate::core::physics::PheromoneType;
 use crate::core::Blackboard;
-
-/// Message sent to dashboard
-#[derive(Debug, Clone, Serialize)]
-#[serde(tag = "type")]
-pub enum DashboardMessage {
-    #[serde(rename = "pheromone_update")]
-    PheromoneUpdate {
-        pheromones: Vec<PheromoneStatus>,
-    },
-    #[serde(rename = "portfolio_update")]
-    PortfolioUpdate {
-        portfolio: PortfolioState,
-    },
-    #[serde(rename = "event")]
-    Event {
-        event_type: String,
-        pheromone: String,
-        intensity: f64,
-    },
-    #[serde(rename = "agent_metrics")]
-    AgentMetricsUpdate {
-        agents: Vec<AgentMetrics>,
-    },
-    #[serde(rename = "trade_history")]
-    TradeHistory {
-        trades: Vec<TradeLogEntry>,
-    },
-}
-
-#[derive(Debug, Clone, Serialize)]
-pub struct PheromoneStatus {
-    pub name: String,
-    pub intensity: f64,
-    pub threshold: f64,
-    pub is_active: bool,
-}
-
-/// Message received from dashboard
-#[derive(Debug, Clone, Deserialize)]
-#[serde(tag = "type")]
-pub enum ClientMessage {
-    #[serde(rename = "set_allocation")]
-    SetAllocation { stocks_pct: f64, bonds_pct: f64 },
-    #[serde(rename = "get_status")]
-    GetStatus,
-    #[serde(rename = "reset")]
-    Reset,
-}
+use crate::execution::{simulated, OrderLeg};
+use crate::export::{self, DateRange, Dataset, ExportFormat};
+use crate::market::daily_history;
+use crate::server::encoding::{self, WireEncoding, WsParams};
+use crate::server::protocol::{ClientMessage, DashboardMessage, PheromoneStatus};
 
 /// Start the WebSocket server
 pub async fn start_websocket_server(
     port: u16,
     board: Arc<Blackboard>,
 ) -> Result<()> {
+    let read_only = board.config().dashboard.read_only;
+    let timezone = board.config().exchange.timezone;
+    let users = Arc::new(ApiUsers::from_config(&board.config().auth));
+    let swarm = board.swarm_name().to_string();
     let board_filter = warp::any().map(move || board.clone());
     
-    // WebSocket route
+    // The `[[auth.users]]` user presenting an API key for this swarm (none
+    // when no users are configured)
+    let api_user = warp::header::optional::<String>("x-api-key")
+        .and(warp::query::<ApiKeyQuery>())
+        .and_then(move |header: Option<String>, query: ApiKeyQuery| {
+            let (users, swarm) = (users.clone(), swarm.clone());
+            async move {
+                users
+                    .authenticate(header.or(query.api_key).as_deref(), &swarm)
+                    .map_err(warp::reject::custom)
+            }
+        });
+    let authenticated = api_user.clone().map(|_| ()).untuple_one();
+    
+    // Command endpoints don't exist on a read-only server
+    let writable = warp::any()
+        .and_then(move || async move {
+            if read_only {
+                Err(warp::reject::not_found())
+            } else {
+                Ok(())
+            }
+        })
+        .untuple_one();
+    
+    // Who sent a REST command, for the audit log
+    let rest_client = warp::header::optional::<String>("x-operator-id")
+        .and(warp::addr::remote())
+        .and(warp::header::optional::<String>("x-forwarded-for"))
+        .and(api_user.clone())
+        .map(|operator, remote, forwarded_for, user: Option<ApiUser>| {
+            ClientIdentity::rest(operator, remote, forwarded_for).with_user(user.map(|user| user.name))
+        });
+    
+    // WebSocket route (`?encoding=msgpack` selects binary frames, `?operator=` names the client)
     let ws_route = warp::path("ws")
         .and(warp::ws())
+        .and(warp::query::<WsParams>())
+        .and(warp::addr::remote())
+        .and(warp::header::optional::<String>("x-forwarded-for"))
+        .and(api_user.clone())
         .and(board_filter.clone())
-        .map(|ws: warp::ws::Ws, board: Arc<Blackboard>| {
-            ws.on_upgrade(move |socket| handle_websocket(socket, board))
+        .map(|ws: warp::ws::Ws, params: WsParams, remote, forwarded_for, user: Option<ApiUser>, board: Arc<Blackboard>| {
+            let client = ClientIdentity::websocket(params.operator, remote, forwarded_for)
+                .with_user(user.map(|user| user.name));
+            ws.on_upgrade(move |socket| handle_websocket(socket, board, params.encoding, client))
         });
     
     // Health check route
     let health = warp::path("health")
         .map(|| warp::reply::json(&serde_json::json!({"status": "ok"})));
     
+    // Every pheromone with its payload, read without sniffing (no events)
+    let pheromones = warp::path!("api" / "pheromones")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_pheromones);
+    
+    // Pheromone intensity by type and time bucket (?window_secs=3600&buckets=60)
+    let pheromone_heatmap = warp::path!("api" / "pheromones" / "heatmap")
+        .and(warp::get())
+        .and(warp::query::<HeatmapQuery>())
+        .and(board_filter.clone())
+        .and_then(get_pheromone_heatmap);
+    
+    // Latest market quotes
+    let market = warp::path!("api" / "market")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_market);
+    
+    // High-water mark and drawdown
+    let drawdown = warp::path!("api" / "drawdown")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_drawdown);
+    
+    // Time- and money-weighted returns, e.g. /api/performance?range=ytd
+    let performance = warp::path!("api" / "performance")
+        .and(warp::get())
+        .and(warp::query::<PerformanceQuery>())
+        .and(board_filter.clone())
+        .and_then(get_performance);
+    
+    // Current run (version, config hash, providers, seed) and the run registry
+    let current_run = warp::path!("api" / "run")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_current_run);
+    let run_list = warp::path!("api" / "runs")
+        .and(warp::get())
+        .and(warp::query::<RunsQuery>())
+        .and(board_filter.clone())
+        .and_then(get_runs);
+    let run = warp::path!("api" / "runs" / String)
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_run);
+    
+    // Shadow-mode comparison and recent divergences, e.g. /api/shadow?limit=20
+    let shadow = warp::path!("api" / "shadow")
+        .and(warp::get())
+        .and(warp::query::<RunsQuery>())
+        .and(board_filter.clone())
+        .and_then(get_shadow);
+    
+    // Drift of each asset group and its members
+    let groups = warp::path!("api" / "groups")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_groups);
+    
+    // Rebalances waiting for the execution window
+    let orders = warp::path!("api" / "orders")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_orders);
+    
+    // Alert rules and whether each is firing
+    let alerts = warp::path!("api" / "alerts")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_alerts);
+    
+    // Notifications waiting for delivery and those given up on
+    let notifications = warp::path!("api" / "notifications")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_notifications);
+    
+    // Redis footprint as of the last retention pass
+    let storage = warp::path!("api" / "storage")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_storage);
+    
+    // Snapshot → analysis → permit → execution → fills behind a trade
+    let trade_timeline = warp::path!("api" / "trades" / String / "timeline")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_trade_timeline);
+    
+    // Latest position reconciliation report
+    let reconciliation = warp::path!("api" / "reconciliation")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_reconciliation);
+    
+    // Trade log / portfolio history export, e.g. /api/export/trades.csv?from=2026-01-01
+    let export = warp::path!("api" / "export" / String)
+        .and(warp::get())
+        .and(warp::query::<DateRange>())
+        .and(board_filter.clone())
+        .and_then(get_export);
+    
+    // Ghost portfolios (never rebalanced, calendar rebalanced, buy-and-hold)
+    let benchmarks = warp::path!("api" / "benchmarks")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_benchmarks);
+    
+    // Per-agent activity over a trailing window (?window_secs=3600&buckets=12)
+    let agent_activity = warp::path!("api" / "agents" / String / "activity")
+        .and(warp::get())
+        .and(warp::query::<ActivityQuery>())
+        .and(board_filter.clone())
+        .and_then(get_agent_activity);
+    
+    // Agents' loop duration, busy vs awaited time and Redis calls per loop
+    let agent_runtime = warp::path!("api" / "agents" / "runtime")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_agent_runtime);
+    
+    // Every swarm sharing this Redis, with heartbeat liveness
+    let swarms = warp::path!("api" / "swarms")
+        .and(warp::get())
+        .and(api_user.clone())
+        .and(board_filter.clone())
+        .and_then(get_swarms);
+    
+    // Daily snapshots and summaries (?from=&to=, default last 30 days) and
+    // monthly statements
+    let snapshots = warp::path!("api" / "snapshots")
+        .and(warp::get())
+        .and(warp::query::<DateRange>())
+        .and(board_filter.clone())
+        .and_then(get_snapshots);
+    let summaries = warp::path!("api" / "summaries")
+        .and(warp::get())
+        .and(warp::query::<DateRange>())
+        .and(board_filter.clone())
+        .and_then(get_summaries);
+    let statement_list = warp::path!("api" / "statements")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_statement_list);
+    let statement = warp::path!("api" / "statements" / String)
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_statement);
+    
+    // Imported broker holdings; POST a positions CSV to replace them
+    let get_holdings = warp::path!("api" / "holdings")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_holdings);
+    let post_holdings = warp::path!("api" / "holdings")
+        .and(warp::post())
+        .and(writable)
+        .and(warp::header::optional::<String>("x-operator-token"))
+        .and(rest_client.clone())
+        .and(warp::query::<HoldingsUpload>())
+        .and(warp::body::content_length_limit(MAX_HOLDINGS_UPLOAD_BYTES))
+        .and(warp::body::bytes())
+        .and(board_filter.clone())
+        .and_then(post_holdings);
+    
+    // Starting cash, holdings and base currency; POST to (re)initialize
+    let get_portfolio_init = warp::path!("api" / "portfolio" / "init")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_portfolio_init);
+    let post_portfolio_init = warp::path!("api" / "portfolio" / "init")
+        .and(warp::post())
+        .and(writable)
+        .and(warp::header::optional::<String>("x-operator-token"))
+        .and(rest_client.clone())
+        .and(warp::body::json())
+        .and(board_filter.clone())
+        .and_then(post_portfolio_init);
+    
+    // Allocation presets
+    let presets = warp::path!("api" / "presets")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .map(|board: Arc<Blackboard>| warp::reply::json(&board.config().allocation.presets));
+    
+    // Log levels; POST {"target": "sensor", "level": "trace"} to override one
+    let get_logging = warp::path!("api" / "logging")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_logging);
+    let post_logging = warp::path!("api" / "logging")
+        .and(warp::post())
+        .and(writable)
+        .and(warp::header::optional::<String>("x-operator-token"))
+        .and(rest_client.clone())
+        .and(warp::body::json())
+        .and(board_filter.clone())
+        .and_then(post_logging);
+    
+    // System mode; POST {"engaged": true, "reason": "..."} to halt trading
+    let get_mode = warp::path!("api" / "mode")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_mode);
+    let kill_switch = warp::path!("api" / "kill-switch")
+        .and(warp::post())
+        .and(writable)
+        .and(warp::header::optional::<String>("x-operator-token"))
+        .and(rest_client.clone())
+        .and(warp::body::json())
+        .and(board_filter.clone())
+        .and_then(post_kill_switch);
+    
+    // Simulated clock of a VIX replay; POST {"speed": 60}, {"paused": true}
+    // or {"secs": 3600} to control it
+    let sim_clock = warp::path!("api" / "sim" / "clock")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_sim_clock);
+    let sim_speed = warp::path!("api" / "sim" / "speed")
+        .and(warp::post())
+        .and(writable)
+        .and(warp::header::optional::<String>("x-operator-token"))
+        .and(rest_client.clone())
+        .and(warp::body::json())
+        .and(board_filter.clone())
+        .and_then(post_sim_speed);
+    let sim_pause = warp::path!("api" / "sim" / "pause")
+        .and(warp::post())
+        .and(writable)
+        .and(warp::header::optional::<String>("x-operator-token"))
+        .and(rest_client.clone())
+        .and(warp::body::json())
+        .and(board_filter.clone())
+        .and_then(post_sim_pause);
+    let sim_jump = warp::path!("api" / "sim" / "jump")
+        .and(warp::post())
+        .and(writable)
+        .and(warp::header::optional::<String>("x-operator-token"))
+        .and(rest_client.clone())
+        .and(warp::body::json())
+        .and(board_filter.clone())
+        .and_then(post_sim_jump);
+    
+    // Composite risk index of the portfolio's own assets
+    let risk_index = warp::path!("api" / "risk-index")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_risk_index);
+    
+    // Rolling asset correlations and their recent series
+    let correlation = warp::path!("api" / "correlation")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_correlation);
+    
+    // Change target allocation (preset name or explicit weights)
+    let allocation = warp::path!("api" / "allocation")
+        .and(warp::post())
+        .and(writable)
+        .and(warp::header::optional::<String>("x-operator-token"))
+        .and(rest_client.clone())
+        .and(warp::body::json())
+        .and(board_filter.clone())
+        .and_then(post_allocation);
+    
+    // Previews of new cash or a target change (read-only; nothing is traded)
+    let whatif_contribution = warp::path!("api" / "whatif" / "contribution")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(board_filter.clone())
+        .and_then(post_whatif_contribution);
+    let whatif_allocation = warp::path!("api" / "whatif" / "allocation")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(board_filter.clone())
+        .and_then(post_whatif_allocation);
+    
+    // Suggested target weights (read-only; applied through /api/allocation)
+    let optimize = warp::path!("api" / "optimize")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(board_filter.clone())
+        .and_then(post_optimize);
+    
+    // The simulator's fills, costs and drift for externally proposed legs
+    let simulate_trade = warp::path!("api" / "simulate" / "trade")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(board_filter.clone())
+        .and_then(post_simulate_trade);
+    
+    // The rebalance the swarm would execute right now
+    let trade_plan = warp::path!("api" / "plan")
+        .and(warp::get())
+        .and(board_filter.clone())
+        .and_then(get_plan);
+    
+    // Audit log of client commands (?command=&operator=&limit=)
+    let audit_log = warp::path!("api" / "audit")
+        .and(warp::get())
+        .and(warp::header::optional::<String>("x-operator-token"))
+        .and(warp::query::<AuditQuery>())
+        .and(board_filter.clone())
+        .and_then(get_audit);
+    
     // CORS for development
     let cors = warp::cors()
         .allow_any_origin()
         .allow_methods(vec!["GET", "POST"])
-        .allow_headers(vec!["content-type"]);
+        .allow_headers(vec!["content-type", "x-operator-token", "x-operator-id", "x-api-key"]);
     
-    let routes = ws_route.or(health).with(cors);
+    // Everything but the health check needs a key once users are configured
+    let api = pheromones
+        .or(pheromone_heatmap)
+        .or(market)
+        .or(drawdown)
+        .or(performance)
+        .or(current_run)
+        .or(run_list)
+        .or(run)
+        .or(shadow)
+        .or(groups)
+        .or(orders)
+        .or(reconciliation)
+        .or(alerts)
+        .or(notifications)
+        .or(storage)
+        .or(trade_timeline)
+        .or(export)
+        .or(benchmarks)
+        .or(swarms)
+        .or(agent_activity)
+        .or(agent_runtime)
+        .or(snapshots)
+        .or(summaries)
+        .or(statement_list)
+        .or(statement)
+        .or(get_holdings)
+        .or(post_holdings)
+        .or(get_portfolio_init)
+        .or(post_portfolio_init)
+        .or(presets)
+        .or(get_logging)
+        .or(post_logging)
+        .or(get_mode)
+        .or(kill_switch)
+        .or(sim_clock)
+        .or(sim_speed)
+        .or(sim_pause)
+        .or(sim_jump)
+        .or(risk_index)
+        .or(correlation)
+        .or(allocation)
+        .or(whatif_contribution)
+        .or(whatif_allocation)
+        .or(optimize)
+        .or(simulate_trade)
+        .or(trade_plan)
+        .or(audit_log)
+        .and_then(move |reply| local_timestamps(reply, timezone));
+    let routes = health
+        .or(ws_route)
+        .or(authenticated.and(api))
+        .recover(auth_rejection)
+        .with(cors);
     
-    info!("🌐 WebSocket server starting on port {}", port);
+    if read_only {
+        info!("🌐 WebSocket server starting on port {} (read-only)", port);
+    } else {
+        info!("🌐 WebSocket server starting on port {}", port);
+    }
     
     warp::serve(routes)
         .run(([0, 0, 0, 0], port))
@@ -100,11 +492,66 @@ pub async fn start_websocket_server(
     Ok(())
 }
 
+/// `?api_key=`, for clients that can't set headers (browser WebSockets)
+#[derive(Debug, Default, Deserialize)]
+struct ApiKeyQuery {
+    #[serde(default)]
+    api_key: Option<String>,
+}
+
+impl warp::reject::Reject for AuthError {}
+
+/// Answer a missing, unknown or out-of-scope API key
+async fn auth_rejection(rejection: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
+    let Some(e) = rejection.find::<AuthError>() else {
+        return Err(rejection);
+    };
+    let status = match e {
+        AuthError::Forbidden { .. } => warp::http::StatusCode::FORBIDDEN,
+        AuthError::MissingKey | AuthError::UnknownKey => warp::http::StatusCode::UNAUTHORIZED,
+    };
+    Ok(warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+        status,
+    ))
+}
+
+/// Add exchange-local copies of the timestamps in a JSON response
+async fn local_timestamps(reply: impl warp::Reply, tz: Tz) -> Result<warp::reply::Response, warp::Rejection> {
+    let response = reply.into_response();
+    let is_json = response
+        .headers()
+        .get(warp::http::header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    if tz == Tz::UTC || !is_json {
+        return Ok(response);
+    }
+    
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = warp::hyper::body::to_bytes(body).await else {
+        return Ok(warp::http::Response::from_parts(parts, warp::hyper::Body::empty()));
+    };
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(mut value) => {
+            clock::localize(&mut value, tz);
+            parts.headers.remove(warp::http::header::CONTENT_LENGTH);
+            serde_json::to_vec(&value).map_or(bytes, Into::into)
+        }
+        Err(_) => bytes,
+    };
+    Ok(warp::http::Response::from_parts(parts, body.into()))
+}
+
 /// Handle individual WebSocket connection
-async fn handle_websocket(ws: WebSocket, board: Arc<Blackboard>) {
+async fn handle_websocket(ws: WebSocket, board: Arc<Blackboard>, encoding: WireEncoding, client: ClientIdentity) {
     let (mut tx, mut rx) = ws.split();
+    let mut wire = encoding;
     
-    info!("📱 Dashboard connected");
+    info!(
+        "📱 Dashboard connected ({:?}) from {}",
+        wire,
+        client.ip.as_deref().unwrap_or("unknown address")
+    );
     
     // Subscribe to pheromone events
     let mut event_rx = board.subscribe();
@@ -112,27 +559,37 @@ async fn handle_websocket(ws: WebSocket, board: Arc<Blackboard>) {
     // Send initial state
     if let Ok(status) = get_pheromone_status(&board).await {
         let msg = DashboardMessage::PheromoneUpdate { pheromones: status };
-        if let Ok(json) = serde_json::to_string(&msg) {
-            let _ = tx.send(Message::text(json)).await;
+        if let Ok(frame) = encoding::encode(&msg, wire) {
+            let _ = tx.send(frame).await;
         }
     }
     
     if let Ok(Some(portfolio)) = board.get_portfolio_state().await {
         let msg = DashboardMessage::PortfolioUpdate { portfolio };
-        if let Ok(json) = serde_json::to_string(&msg) {
-            let _ = tx.send(Message::text(json)).await;
+        if let Ok(frame) = encoding::encode(&msg, wire) {
+            let _ = tx.send(frame).await;
         }
     }
     
+    let msg = DashboardMessage::Presets {
+        presets: board.config().allocation.presets.clone(),
+    };
+    if let Ok(frame) = encoding::encode(&msg, wire) {
+        let _ = tx.send(frame).await;
+    }
+    
+    // Replies to client requests are forwarded by the outgoing task
+    let (reply_tx, mut reply_rx) = tokio::sync::mpsc::unbounded_channel::<DashboardMessage>();
+    
     // Spawn task to handle incoming messages
     let board_clone = board.clone();
     let incoming = tokio::spawn(async move {
         while let Some(result) = rx.next().await {
             match result {
                 Ok(msg) => {
-                    if let Ok(text) = msg.to_str() {
-                        if let Ok(client_msg) = serde_json::from_str::<ClientMessage>(text) {
-                            handle_client_message(&board_clone, client_msg).await;
+                    if let Some(client_msg) = encoding::decode::<ClientMessage>(&msg) {
+                        if let Some(reply) = handle_client_message(&board_clone, &client, client_msg).await {
+                            let _ = reply_tx.send(reply);
                         }
                     }
                 }
@@ -148,15 +605,41 @@ async fn handle_websocket(ws: WebSocket, board: Arc<Blackboard>) {
     let outgoing = tokio::spawn(async move {
         // Periodic status updates
         let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(500));
+        // Only forward each market snapshot once
+        let mut last_market_timestamp: Option<String> = None;
+        // Forward target changes (dashboard, glide path) as they happen
+        let mut last_target: Option<TargetAllocation> = None;
+        // Only forward each drift forecast once
+        let mut last_forecast_timestamp: Option<String> = None;
+        // Only forward each correlation reading once
+        let mut last_correlation: Option<chrono::DateTime<chrono::Utc>> = None;
+        // Forward the latest daily summary on connect, then each new one
+        let mut last_summary_date: Option<chrono::NaiveDate> = None;
+        // Forward dead-lettered orders when a new one is recorded
+        let mut last_dead_letter: Option<String> = None;
         
         loop {
             tokio::select! {
                 _ = interval.tick() => {
+                    // Send the system mode; an unreachable store is itself HALTED
+                    let status = match board.get_system_status().await {
+                        Ok(status) => status,
+                        Err(e) => Some(SystemStatus::unreachable(e, chrono::Utc::now())),
+                    };
+                    if let Some(status) = status {
+                        let msg = DashboardMessage::SystemMode { status };
+                        if let Ok(frame) = encoding::encode(&msg, wire) {
+                            if tx.send(frame).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    
                     // Send pheromone status
                     if let Ok(status) = get_pheromone_status(&board).await {
                         let msg = DashboardMessage::PheromoneUpdate { pheromones: status };
-                        if let Ok(json) = serde_json::to_string(&msg) {
-                            if tx.send(Message::text(json)).await.is_err() {
+                        if let Ok(frame) = encoding::encode(&msg, wire) {
+                            if tx.send(frame).await.is_err() {
                                 break;
                             }
                         }
@@ -165,8 +648,8 @@ async fn handle_websocket(ws: WebSocket, board: Arc<Blackboard>) {
                     // Send portfolio status
                     if let Ok(Some(portfolio)) = board.get_portfolio_state().await {
                         let msg = DashboardMessage::PortfolioUpdate { portfolio };
-                        if let Ok(json) = serde_json::to_string(&msg) {
-                            if tx.send(Message::text(json)).await.is_err() {
+                        if let Ok(frame) = encoding::encode(&msg, wire) {
+                            if tx.send(frame).await.is_err() {
                                 break;
                             }
                         }
@@ -176,8 +659,90 @@ async fn handle_websocket(ws: WebSocket, board: Arc<Blackboard>) {
                     if let Ok(agents) = board.get_all_agent_metrics().await {
                         if !agents.is_empty() {
                             let msg = DashboardMessage::AgentMetricsUpdate { agents };
-                            if let Ok(json) = serde_json::to_string(&msg) {
-                                if tx.send(Message::text(json)).await.is_err() {
+                            if let Ok(frame) = encoding::encode(&msg, wire) {
+                                if tx.send(frame).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    
+                    // Send target allocation when it changes
+                    if let Ok(target) = board.get_target_allocation().await {
+                        if last_target.as_ref() != Some(&target) {
+                            last_target = Some(target.clone());
+                            let msg = DashboardMessage::TargetUpdate { target };
+                            if let Ok(frame) = encoding::encode(&msg, wire) {
+                                if tx.send(frame).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    
+                    // Send market quotes when a new snapshot arrives
+                    if let Ok(Some(market)) = board.get_market_update().await {
+                        if last_market_timestamp.as_deref() != Some(&market.timestamp) {
+                            last_market_timestamp = Some(market.timestamp.clone());
+                            let msg = DashboardMessage::MarketUpdate { market };
+                            if let Ok(frame) = encoding::encode(&msg, wire) {
+                                if tx.send(frame).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    
+                    // Send the drift forecast when the Forecast agent deposits a new one
+                    if let Ok(Some(reading)) = board.peek::<DriftForecast>(PheromoneType::DriftForecast).await {
+                        let forecast = reading.data;
+                        if last_forecast_timestamp.as_deref() != Some(&forecast.timestamp) {
+                            last_forecast_timestamp = Some(forecast.timestamp.clone());
+                            let msg = DashboardMessage::ForecastUpdate { forecast };
+                            if let Ok(frame) = encoding::encode(&msg, wire) {
+                                if tx.send(frame).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    
+                    // Send the correlation series when the monitor adds a reading
+                    if let Ok(mut history) = board.get_correlation_history(board.config().correlation.history_len).await {
+                        let newest = history.first().map(|reading| reading.computed_at);
+                        if newest.is_some() && last_correlation != newest {
+                            last_correlation = newest;
+                            history.reverse();
+                            let msg = DashboardMessage::CorrelationUpdate { history };
+                            if let Ok(frame) = encoding::encode(&msg, wire) {
+                                if tx.send(frame).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    
+                    // Send the daily summary when a new day is consolidated
+                    if let Ok(Some(summary)) = board.get_latest_daily_summary().await {
+                        if last_summary_date != Some(summary.date) {
+                            last_summary_date = Some(summary.date);
+                            let msg = DashboardMessage::DailySummary { summary };
+                            if let Ok(frame) = encoding::encode(&msg, wire) {
+                                if tx.send(frame).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    
+                    // Send the dead letters when the execution queue gives up on a leg
+                    if let Ok(orders) = board.get_dead_letters(20).await {
+                        let newest = orders.first().map(|letter| letter.idempotency_key.clone());
+                        if newest.is_some() && last_dead_letter != newest {
+                            last_dead_letter = newest;
+                            let msg = DashboardMessage::DeadLetters { orders };
+                            if let Ok(frame) = encoding::encode(&msg, wire) {
+                                if tx.send(frame).await.is_err() {
                                     break;
                                 }
                             }
@@ -188,8 +753,8 @@ async fn handle_websocket(ws: WebSocket, board: Arc<Blackboard>) {
                     if let Ok(trades) = board.get_trade_history(20).await {
                         if !trades.is_empty() {
                             let msg = DashboardMessage::TradeHistory { trades };
-                            if let Ok(json) = serde_json::to_string(&msg) {
-                                if tx.send(Message::text(json)).await.is_err() {
+                            if let Ok(frame) = encoding::encode(&msg, wire) {
+                                if tx.send(frame).await.is_err() {
                                     break;
                                 }
                             }
@@ -197,15 +762,23 @@ async fn handle_websocket(ws: WebSocket, board: Arc<Blackboard>) {
                     }
                 }
                 
+                Some(reply) = reply_rx.recv() => {
+                    if let Ok(frame) = encoding::encode(&reply, wire) {
+                        if tx.send(frame).await.is_err() {
+                            break;
+                        }
+                    }
+                    // The hello reply goes out in the old encoding; switch after it
+                    if let DashboardMessage::Hello { encoding, .. } = reply {
+                        wire = encoding;
+                    }
+                }
+                
                 event = event_rx.recv() => {
                     if let Ok(evt) = event {
-                        let msg = DashboardMessage::Event {
-                            event_type: format!("{:?}", evt.action),
-                            pheromone: evt.pheromone_type,
-                            intensity: evt.intensity,
-                        };
-                        if let Ok(json) = serde_json::to_string(&msg) {
-                            if tx.send(Message::text(json)).await.is_err() {
+                        let msg = event_message(evt);
+                        if let Ok(frame) = encoding::encode(&msg, wire) {
+                            if tx.send(frame).await.is_err() {
                                 break;
                             }
                         }
@@ -224,46 +797,1560 @@ async fn handle_websocket(ws: WebSocket, board: Arc<Blackboard>) {
     info!("📱 Dashboard disconnected");
 }
 
-/// Get current pheromone status for all types
+/// REST: current pheromones and payloads (absent ones omitted)
+async fn get_pheromones(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let mut readings = Vec::new();
+    for ptype in PheromoneType::ALL {
+        match board.peek::<serde_json::Value>(ptype).await {
+            Ok(Some(reading)) => readings.push(reading),
+            Ok(None) => {}
+            Err(e) => {
+                return Ok(warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                    warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                ));
+            }
+        }
+    }
+    Ok(warp::reply::with_status(
+        warp::reply::json(&readings),
+        warp::http::StatusCode::OK,
+    ))
+}
+
+/// REST: latest market quotes published by the Sensor
+async fn get_market(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match board.get_market_update().await {
+        Ok(Some(market)) => Ok(warp::reply::with_status(
+            warp::reply::json(&market),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "no market data yet"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: the composite risk index as last computed
+async fn get_risk_index(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match board.get_composite_risk_index().await {
+        Ok(Some(index)) => Ok(warp::reply::with_status(
+            warp::reply::json(&index),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "no composite risk index yet"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: the latest correlation reading and the series, newest first
+async fn get_correlation(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let history_len = board.config().correlation.history_len;
+    let read = async { Ok::<_, anyhow::Error>((board.get_correlation().await?, board.get_correlation_history(history_len).await?)) };
+    match read.await {
+        Ok((latest, history)) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "enabled": board.config().correlation.enabled,
+                "latest": latest,
+                "history": history,
+            })),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: configured alert rules with their notification state
+async fn get_alerts(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match board.get_alert_states().await {
+        Ok(states) => {
+            let config = board.config();
+            let rules = alerts::statuses(&config.alerts, &config.alerting, &states);
+            Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({ "enabled": config.alerting.enabled, "rules": rules })),
+                warp::http::StatusCode::OK,
+            ))
+        }
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: the notification delivery queue and its dead letters
+async fn get_notifications(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let config = board.config();
+    let result = async {
+        let pending = board.get_notifications().await?;
+        let dead_letters = board.get_notify_dead_letters(config.alerting.delivery.dead_letter_max_entries).await?;
+        anyhow::Ok((pending, dead_letters))
+    };
+    match result.await {
+        Ok((pending, dead_letters)) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "pending": pending, "dead_letters": dead_letters })),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: the swarm's Redis footprint from the retention janitor
+async fn get_storage(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match board.get_storage_report().await {
+        Ok(Some(report)) => Ok(warp::reply::with_status(
+            warp::reply::json(&report),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "no storage report yet"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: each agent's latest runtime report
+async fn get_agent_runtime(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match board.get_agent_runtime().await {
+        Ok(reports) => Ok(warp::reply::with_status(
+            warp::reply::json(&reports),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: the decision timeline behind one trade
+async fn get_trade_timeline(id: String, board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match board.get_trade_timeline(&id).await {
+        Ok(Some(timeline)) => Ok(warp::reply::with_status(
+            warp::reply::json(&timeline),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": format!("no timeline for trade '{}'", id)})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: latest blackboard-vs-broker reconciliation report
+async fn get_reconciliation(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match board.get_reconciliation().await {
+        Ok(Some(report)) => Ok(warp::reply::with_status(
+            warp::reply::json(&report),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "no reconciliation yet"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: export a dataset as `<trades|history>.<csv|parquet>` or
+/// `trades.<ledger|beancount>`
+async fn get_export(
+    file: String,
+    range: DateRange,
+    board: Arc<Blackboard>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    use warp::Reply;
+    
+    let parsed = file
+        .split_once('.')
+        .and_then(|(dataset, format)| Some((dataset.parse::<Dataset>().ok()?, format.parse::<ExportFormat>().ok()?)))
+        .filter(|(dataset, format)| format.supports(*dataset));
+    let Some((dataset, format)) = parsed else {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": format!("unknown export '{}'", file)})),
+            warp::http::StatusCode::NOT_FOUND,
+        )
+        .into_response());
+    };
+    
+    match export::export(&board, dataset, format, range).await {
+        Ok(body) => Ok(warp::reply::with_header(
+            warp::reply::with_header(body, "content-type", format.content_type()),
+            "content-disposition",
+            format!("attachment; filename=\"{}\"", file),
+        )
+        .into_response()),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .into_response()),
+    }
+}
+
+/// REST: benchmark ghost portfolios next to the real one
+async fn get_benchmarks(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let result = async {
+        let benchmarks = board.get_benchmarks().await?;
+        let portfolio = board.get_portfolio_state().await?;
+        // Trailing returns over the daily closes loaded at startup
+        let mut history = BTreeMap::new();
+        for symbol in daily_history::symbols(board.config()) {
+            if let Some(h) = board.get_daily_history(&symbol).await? {
+                history.insert(symbol, serde_json::json!({
+                    "from": h.bars.first().map(|bar| bar.date),
+                    "to": h.bars.last().map(|bar| bar.date),
+                    "return_pct": daily_history::trailing_return_pct(&h.bars),
+                }));
+            }
+        }
+        anyhow::Ok(benchmarks.map(|b| {
+            serde_json::json!({
+                "portfolio_value": portfolio.map(|p| p.total_value),
+                "no_rebalance_value": b.no_rebalance.total_value(),
+                "calendar_rebalance_value": b.calendar_rebalanced.total_value(),
+                "benchmark_value": b.benchmark.total_value(),
+                "benchmarks": b,
+                "history": history,
+            })
+        }))
+    }
+    .await;
+    
+    match result {
+        Ok(Some(body)) => Ok(warp::reply::with_status(
+            warp::reply::json(&body),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "benchmarks not started"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Query parameters for `GET /api/agents/<name>/activity`
+#[derive(Debug, Deserialize)]
+struct ActivityQuery {
+    window_secs: Option<u64>,
+    buckets: Option<usize>,
+}
+
+/// Longest window and most buckets served for agent activity
+const MAX_ACTIVITY_WINDOW_SECS: u64 = 7 * 24 * 3600;
+const MAX_ACTIVITY_BUCKETS: usize = 288;
+
+/// REST: how often an agent acted over a trailing window, with a sparkline
+async fn get_agent_activity(
+    name: String,
+    query: ActivityQuery,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if name.parse::<AgentRole>().is_err() {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": format!("unknown agent '{}'", name)})),
+            warp::http::StatusCode::NOT_FOUND,
+        ));
+    }
+    
+    let window_secs = query.window_secs.unwrap_or(3600).clamp(1, MAX_ACTIVITY_WINDOW_SECS);
+    let buckets = query.buckets.unwrap_or(12).clamp(1, MAX_ACTIVITY_BUCKETS);
+    
+    match board.get_agent_activity(&name, board.config().agent.activity_max_entries).await {
+        Ok(entries) => {
+            let summary = activity::summarize(
+                &name.to_lowercase(),
+                entries,
+                chrono::Utc::now(),
+                chrono::Duration::seconds(window_secs as i64),
+                buckets,
+            );
+            Ok(warp::reply::with_status(
+                warp::reply::json(&summary),
+                warp::http::StatusCode::OK,
+            ))
+        }
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Query parameters for `GET /api/pheromones/heatmap`
+#[derive(Debug, Deserialize)]
+struct HeatmapQuery {
+    window_secs: Option<u64>,
+    buckets: Option<usize>,
+}
+
+/// Most buckets served for the pheromone heatmap (the window is capped
+/// like agent activity's)
+const MAX_HEATMAP_BUCKETS: usize = 720;
+
+/// REST: pheromone intensity per type and time bucket, from the deposit trail
+async fn get_pheromone_heatmap(
+    query: HeatmapQuery,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let window_secs = query.window_secs.unwrap_or(3600).clamp(1, MAX_ACTIVITY_WINDOW_SECS);
+    let buckets = query.buckets.unwrap_or(60).clamp(1, MAX_HEATMAP_BUCKETS);
+    
+    match board.get_pheromone_trail(board.config().dashboard.trail_max_entries).await {
+        Ok(trail) => {
+            let heatmap = trails::heatmap(
+                &trail,
+                &PheromoneType::ALL,
+                board.config(),
+                chrono::Utc::now(),
+                chrono::Duration::seconds(window_secs as i64),
+                buckets,
+            );
+            Ok(warp::reply::with_status(
+                warp::reply::json(&heatmap),
+                warp::http::StatusCode::OK,
+            ))
+        }
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: swarms registered in the federation (this one flagged as
+/// `current`), limited to the caller's when users are configured
+async fn get_swarms(user: Option<ApiUser>, board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match board.get_swarm_heartbeats().await {
+        Ok(heartbeats) => {
+            let now = chrono::Utc::now();
+            let timeout = board.config().swarm.heartbeat_timeout_secs;
+            let swarms: Vec<_> = heartbeats
+                .into_iter()
+                .filter(|h| user.as_ref().is_none_or(|user| user.can_access(&h.name)))
+                .map(|h| h.status(now, timeout))
+                .collect();
+            Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({
+                    "current": board.swarm_name(),
+                    "swarms": swarms,
+                })),
+                warp::http::StatusCode::OK,
+            ))
+        }
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Longest date range served by `GET /api/snapshots` and `/api/summaries`
+const MAX_SNAPSHOT_DAYS: i64 = 366;
+
+/// REST: daily snapshots within a date range
+async fn get_snapshots(range: DateRange, board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let to = range.to.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let from = range.from.unwrap_or(to - chrono::Duration::days(29));
+    if from > to || (to - from).num_days() >= MAX_SNAPSHOT_DAYS {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": format!("date range must span 1-{} days", MAX_SNAPSHOT_DAYS)})),
+            warp::http::StatusCode::BAD_REQUEST,
+        ));
+    }
+    
+    match board.get_daily_snapshots(&statements::days(from, to)).await {
+        Ok(snapshots) => Ok(warp::reply::with_status(
+            warp::reply::json(&snapshots),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: daily summaries within a date range
+async fn get_summaries(range: DateRange, board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let to = range.to.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let from = range.from.unwrap_or(to - chrono::Duration::days(29));
+    if from > to || (to - from).num_days() >= MAX_SNAPSHOT_DAYS {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": format!("date range must span 1-{} days", MAX_SNAPSHOT_DAYS)})),
+            warp::http::StatusCode::BAD_REQUEST,
+        ));
+    }
+    
+    match board.get_daily_summaries(&statements::days(from, to)).await {
+        Ok(summaries) => Ok(warp::reply::with_status(
+            warp::reply::json(&summaries),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: months with a stored statement
+async fn get_statement_list(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match board.list_statements().await {
+        Ok(months) => Ok(warp::reply::with_status(
+            warp::reply::json(&months),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: one month's statement, `YYYY-MM` (JSON) or `YYYY-MM.txt` (text)
+async fn get_statement(file: String, board: Arc<Blackboard>) -> Result<warp::reply::Response, warp::Rejection> {
+    use warp::Reply;
+    
+    let not_found = || {
+        warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": format!("no statement '{}'", file)})),
+            warp::http::StatusCode::NOT_FOUND,
+        )
+        .into_response()
+    };
+    let (month, text) = match file.strip_suffix(".txt") {
+        Some(month) => (month, true),
+        None => (file.as_str(), false),
+    };
+    if statements::month_start_from_id(month).is_none() {
+        return Ok(not_found());
+    }
+    
+    let result = if text {
+        board.get_statement_text(month).await.map(|t| t.map(|t| t.into_response()))
+    } else {
+        board.get_statement(month).await.map(|s| s.map(|s| warp::reply::json(&s).into_response()))
+    };
+    match result {
+        Ok(Some(reply)) => Ok(reply),
+        Ok(None) => Ok(not_found()),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .into_response()),
+    }
+}
+
+/// Largest positions CSV accepted by `POST /api/holdings`
+const MAX_HOLDINGS_UPLOAD_BYTES: u64 = 1024 * 1024;
+
+/// Query parameters for a holdings upload
+#[derive(Debug, Deserialize)]
+struct HoldingsUpload {
+    /// Original file name, recorded as the import source
+    filename: Option<String>,
+}
+
+/// REST: imported holdings
+async fn get_holdings(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match board.get_holdings().await {
+        Ok(Some(imported)) => Ok(warp::reply::with_status(
+            warp::reply::json(&imported),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "no holdings imported"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: import holdings from a broker positions CSV (request body)
+async fn post_holdings(
+    token: Option<String>,
+    client: ClientIdentity,
+    upload: HoldingsUpload,
+    body: warp::hyper::body::Bytes,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let source = upload.filename.unwrap_or_else(|| "upload".to_string());
+    let entry = AuditEntry::new("import_holdings", &client)
+        .request(serde_json::json!({"source": source, "bytes": body.len()}));
+    
+    if let Err(e) = auth::authorize(&client, board.config(), token.as_deref()) {
+        audit::record(&board, entry.rejected(&e)).await;
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::UNAUTHORIZED,
+        ));
+    }
+    
+    let parsed = std::str::from_utf8(&body)
+        .map_err(anyhow::Error::from)
+        .and_then(|text| holdings::parse_broker_csv(text, &source));
+    let imported = match parsed {
+        Ok(imported) => imported,
+        Err(e) => {
+            audit::record(&board, entry.rejected(format!("{:#}", e))).await;
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": format!("{:#}", e)})),
+                warp::http::StatusCode::BAD_REQUEST,
+            ));
+        }
+    };
+    
+    let previous = board.get_portfolio_state().await.ok().flatten();
+    let entry = entry.previous(previous);
+    match holdings::apply_import(&board, &imported).await {
+        Ok(portfolio) => {
+            audit::record(&board, entry.new_value(&portfolio)).await;
+            Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"holdings": imported, "portfolio": portfolio})),
+                warp::http::StatusCode::OK,
+            ))
+        }
+        Err(e) => {
+            audit::record(&board, entry.failed(format!("{:#}", e))).await;
+            Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": format!("{:#}", e)})),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
+
+/// REST: the deployment's starting point
+async fn get_portfolio_init(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match bootstrap::current(&board).await {
+        Ok(init) => Ok(warp::reply::with_status(
+            warp::reply::json(&init),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: set starting cash, holdings and base currency and restart the
+/// portfolio from them
+async fn post_portfolio_init(
+    token: Option<String>,
+    client: ClientIdentity,
+    init: PortfolioInit,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let mut entry = AuditEntry::new("init_portfolio", &client).request(&init);
+    
+    if let Err(e) = auth::authorize(&client, board.config(), token.as_deref()) {
+        audit::record(&board, entry.rejected(&e)).await;
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::UNAUTHORIZED,
+        ));
+    }
+    if let Err(e) = init.resolve(board.config(), "api") {
+        audit::record(&board, entry.rejected(&e)).await;
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::BAD_REQUEST,
+        ));
+    }
+    
+    if let Ok(Some(previous)) = board.get_portfolio_state().await {
+        entry = entry.previous(previous);
+    }
+    match bootstrap::initialize(&board, &init, "api").await {
+        Ok((init, portfolio)) => {
+            audit::record(&board, entry.new_value(&portfolio)).await;
+            Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"init": init, "portfolio": portfolio})),
+                warp::http::StatusCode::OK,
+            ))
+        }
+        Err(e) => {
+            audit::record(&board, entry.failed(format!("{:#}", e))).await;
+            Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": format!("{:#}", e)})),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
+
+/// REST: audited client commands, most recent first (operator token required when configured)
+async fn get_audit(
+    token: Option<String>,
+    query: AuditQuery,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let expected = allocation::operator_token(&board.config().allocation);
+    if let Err(e) = allocation::authorize(expected.as_deref(), token.as_deref()) {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::UNAUTHORIZED,
+        ));
+    }
+    
+    match audit::query(&board, &query).await {
+        Ok(entries) => Ok(warp::reply::with_status(
+            warp::reply::json(&entries),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Body of `POST /api/whatif/contribution`
+#[derive(Debug, Deserialize)]
+struct ContributionRequest {
+    amount: rust_decimal::Decimal,
+}
+
+/// REST: how a cash contribution would be invested to reduce drift without selling
+async fn post_whatif_contribution(
+    request: ContributionRequest,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let state = async {
+        let portfolio = board.get_portfolio_state().await?;
+        let target = board.get_target_allocation().await?;
+        anyhow::Ok(portfolio.map(|portfolio| (portfolio, target)))
+    };
+    let (portfolio, target) = match state.await {
+        Ok(Some(state)) => state,
+        Ok(None) => {
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": "no portfolio yet"})),
+                warp::http::StatusCode::NOT_FOUND,
+            ))
+        }
+        Err(e) => {
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+    
+    match rebalance::plan_contribution(&board.config().portfolio, &portfolio, &target, request.amount) {
+        Ok(plan) => Ok(warp::reply::with_status(
+            warp::reply::json(&plan),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+/// REST: trades, costs and tax impact of a target allocation (preset or
+/// weights, as for `POST /api/allocation`) without applying it
+async fn post_whatif_allocation(
+    request: AllocationRequest,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let config = board.config();
+    let target = match allocation::resolve(&request, &config.allocation, &config.portfolio) {
+        Ok(target) => target,
+        Err(e) => {
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                warp::http::StatusCode::BAD_REQUEST,
+            ))
+        }
+    };
+    
+    let state = async {
+        let portfolio = board.get_portfolio_state().await?;
+        let current_target = board.get_target_allocation().await?;
+        let holdings = board.get_holdings().await?;
+        anyhow::Ok(portfolio.map(|portfolio| (portfolio, current_target, holdings)))
+    };
+    match state.await {
+        Ok(Some((portfolio, current_target, holdings))) => {
+            let preview = rebalance::preview_allocation(config, &portfolio, &current_target, &target, holdings.as_ref());
+            Ok(warp::reply::with_status(
+                warp::reply::json(&preview),
+                warp::http::StatusCode::OK,
+            ))
+        }
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "no portfolio yet"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Body of `POST /api/simulate/trade`
+#[derive(Debug, Deserialize)]
+struct TradeSimulationRequest {
+    legs: Vec<OrderLeg>,
+    /// Holdings to trade from (default: the swarm's portfolio)
+    #[serde(default)]
+    state: Option<SimulationState>,
+    /// Target to measure drift against (default: the swarm's target)
+    #[serde(default)]
+    target: Option<TargetAllocation>,
+    /// Seed for partial fills, to reproduce a simulation
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SimulationState {
+    stocks_value: rust_decimal::Decimal,
+    bonds_value: rust_decimal::Decimal,
+}
+
+/// REST: run proposed legs through the simulated executor (read-only;
+/// the swarm's portfolio is not changed)
+async fn post_simulate_trade(
+    request: TradeSimulationRequest,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let state = async {
+        let portfolio = match request.state {
+            Some(s) => Some(PortfolioState::from_values(s.stocks_value, s.bonds_value, None)),
+            None => board.get_portfolio_state().await?,
+        };
+        let target = match request.target.clone() {
+            Some(target) => target,
+            None => board.get_target_allocation().await?,
+        };
+        let currency = bootstrap::current(&board).await?.base_currency;
+        anyhow::Ok(portfolio.map(|portfolio| (portfolio, target, currency)))
+    };
+    let (portfolio, target, currency) = match state.await {
+        Ok(Some(state)) => state,
+        Ok(None) => {
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": "no portfolio yet"})),
+                warp::http::StatusCode::NOT_FOUND,
+            ))
+        }
+        Err(e) => {
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+    
+    let rng = match request.seed {
+        Some(seed) => SimulationRng::new(seed, simulation::EXECUTION_STREAM),
+        None => SimulationRng::from_entropy(),
+    };
+    match simulated::simulate_trade(board.config(), &portfolio, &target, &request.legs, &currency, rng).await {
+        Ok(trade) => Ok(warp::reply::with_status(
+            warp::reply::json(&trade),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+/// REST: target weights suggested by the optimizer; nothing is applied
+async fn post_optimize(
+    request: OptimizeRequest,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let config = board.config();
+    if !config.optimizer.enabled {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "the optimizer is disabled ([optimizer] enabled)"})),
+            warp::http::StatusCode::NOT_FOUND,
+        ));
+    }
+    
+    let read = async {
+        let (series, missing) = risk_index::read_series(&board, config).await?;
+        Ok::<_, anyhow::Error>((series, missing, board.get_target_allocation().await?))
+    };
+    let (mut series, missing, target) = match read.await {
+        Ok(read) => read,
+        Err(e) => {
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+    // The stocks/bonds pair compares against the live target, not the configured one
+    for asset in series.iter_mut() {
+        if asset.symbol == config.portfolio.stocks_symbol {
+            asset.weight = target.stocks_pct;
+        } else if asset.symbol == config.portfolio.bonds_symbol {
+            asset.weight = target.bonds_pct;
+        }
+    }
+    
+    let suggestion = if missing.is_empty() {
+        optimizer::optimize(&series, &request, config, chrono::Utc::now())
+    } else {
+        Err(OptimizeError::MissingHistory(missing.join(", ")))
+    };
+    match suggestion {
+        Ok(suggestion) => Ok(warp::reply::with_status(
+            warp::reply::json(&suggestion),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => {
+            let status = match e {
+                OptimizeError::MissingHistory(_) | OptimizeError::NotEnoughHistory { .. } => warp::http::StatusCode::CONFLICT,
+                _ => warp::http::StatusCode::BAD_REQUEST,
+            };
+            Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                status,
+            ))
+        }
+    }
+}
+
+/// REST: legs, costs, tax and constraints of the rebalance the Trader
+/// would send now
+async fn get_plan(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match plan::plan(&board).await {
+        Ok(Some(plan)) => Ok(warp::reply::with_status(
+            warp::reply::json(&plan),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "no portfolio yet"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Query parameters for `GET /api/runs` and `GET /api/shadow`
+#[derive(Debug, Deserialize)]
+struct RunsQuery {
+    limit: Option<usize>,
+}
+
+/// REST: the swarm's current run
+async fn get_current_run(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(run_reply(board.get_current_run().await))
+}
+
+/// REST: one registered run
+async fn get_run(id: String, board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(run_reply(board.get_run(&id).await))
+}
+
+fn run_reply(run: Result<Option<RunMetadata>>) -> warp::reply::WithStatus<warp::reply::Json> {
+    match run {
+        Ok(Some(run)) => warp::reply::with_status(
+            warp::reply::json(&run),
+            warp::http::StatusCode::OK,
+        ),
+        Ok(None) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "run not found"})),
+            warp::http::StatusCode::NOT_FOUND,
+        ),
+        Err(e) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    }
+}
+
+/// REST: registered runs, most recent first
+async fn get_runs(query: RunsQuery, board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let limit = query.limit.unwrap_or(runs::DEFAULT_LIST_LIMIT).min(runs::MAX_LIST_LIMIT);
+    match board.get_runs_page(0, limit).await {
+        Ok(runs) => Ok(warp::reply::with_status(
+            warp::reply::json(&runs),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: shadow-mode totals and the most recent divergences
+async fn get_shadow(query: RunsQuery, board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let limit = query.limit.unwrap_or(shadow::DEFAULT_DIVERGENCE_LIMIT).min(board.config().shadow.max_divergences);
+    let result = async {
+        let report = board.get_shadow_report().await?;
+        let divergences = board.get_shadow_divergences(limit).await?;
+        anyhow::Ok(report.map(|report| serde_json::json!({"report": report, "divergences": divergences})))
+    };
+    match result.await {
+        Ok(Some(body)) => Ok(warp::reply::with_status(
+            warp::reply::json(&body),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "shadow mode has not run"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: the order queue's window, the orders waiting for it and the legs
+/// the execution queue gave up on
+async fn get_orders(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let config = board.config();
+    let (queue, tz) = (&config.order_queue, config.exchange.timezone);
+    let now = chrono::Utc::now();
+    let result = async {
+        let orders = board.get_pending_orders().await?;
+        let dead_letters = board.get_dead_letters(config.execution.queue.dead_letter_max_entries).await?;
+        anyhow::Ok((orders, dead_letters))
+    };
+    match result.await {
+        Ok((orders, dead_letters)) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "enabled": queue.enabled,
+                "window_open": order_queue::open_until(now, queue, tz).is_some(),
+                "next_open": order_queue::next_open(now, queue, tz).map(|at| at.to_rfc3339()),
+                "orders": orders,
+                "dead_letters": dead_letters,
+            })),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: group and member drift of the imported holdings
+async fn get_groups(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let config = board.config();
+    if config.portfolio.groups.is_empty() {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "no asset groups configured"})),
+            warp::http::StatusCode::NOT_FOUND,
+        ));
+    }
+    let result = async {
+        let holdings = board.get_holdings().await?;
+        let update = board.get_market_update().await?;
+        let price = |symbol: &str| {
+            let quotes = update.as_ref().map(|u| u.quotes.as_slice()).unwrap_or_default();
+            quotes.iter().find(|q| q.symbol.eq_ignore_ascii_case(symbol)).map(|q| q.price)
+        };
+        anyhow::Ok(holdings.map(|holdings| groups::positions(&holdings, price)))
+    };
+    match result.await {
+        Ok(Some(positions)) => Ok(warp::reply::with_status(
+            warp::reply::json(&groups::hierarchical_drift(
+                &config.portfolio.groups,
+                &positions,
+                config.portfolio.drift_threshold,
+            )),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "no holdings imported"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Query parameters for `GET /api/performance`: a named range, or
+/// explicit `from`/`to` dates
+#[derive(Debug, Deserialize)]
+struct PerformanceQuery {
+    range: Option<String>,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+}
+
+/// REST: time-weighted and money-weighted returns over a range
+async fn get_performance(query: PerformanceQuery, board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let explicit = query.from.is_some() || query.to.is_some();
+    let name = if explicit { "custom" } else { query.range.as_deref().unwrap_or("all") };
+    let range = if explicit {
+        Ok(DateRange { from: query.from, to: query.to })
+    } else {
+        performance::named_range(name, chrono::Utc::now().date_naive())
+    };
+    let range = match range {
+        Ok(range) => range,
+        Err(e) => {
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                warp::http::StatusCode::BAD_REQUEST,
+            ))
+        }
+    };
+    
+    let result = async {
+        let max = board.config().valuation.history_max_entries;
+        let mut history = board.get_portfolio_history_page(0, max).await?;
+        history.retain(|p| export::parse_timestamp(&p.timestamp).is_some_and(|at| range.contains(&at)));
+        history.reverse();
+        let flows = board.get_cash_flows(max).await?;
+        anyhow::Ok(performance::compute(name, &history, &flows))
+    };
+    match result.await {
+        Ok(Some(performance)) => Ok(warp::reply::with_status(
+            warp::reply::json(&performance),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "not enough portfolio history in range"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: high-water mark and current drawdown
+async fn get_drawdown(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    match board.get_drawdown().await {
+        Ok(Some(drawdown)) => Ok(warp::reply::with_status(
+            warp::reply::json(&drawdown),
+            warp::http::StatusCode::OK,
+        )),
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "no valuation yet"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: change the target allocation
+async fn post_allocation(
+    token: Option<String>,
+    client: ClientIdentity,
+    request: AllocationRequest,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (body, status) = match apply_allocation(&board, &client, &request, token.as_deref()).await {
+        Ok(Ok(target)) => (serde_json::json!(target), warp::http::StatusCode::OK),
+        Ok(Err(e)) => {
+            let status = match e {
+                AllocationError::Unauthorized => warp::http::StatusCode::UNAUTHORIZED,
+                _ => warp::http::StatusCode::BAD_REQUEST,
+            };
+            (serde_json::json!({"error": e.to_string()}), status)
+        }
+        Err(e) => (
+            serde_json::json!({"error": e.to_string()}),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&body), status))
+}
+
+/// Authorize, validate and store a target allocation change, recording it
+/// in the audit log
+/// 
+/// The outer error is a storage failure; the inner one a rejected request.
+async fn apply_allocation(
+    board: &Blackboard,
+    client: &ClientIdentity,
+    request: &AllocationRequest,
+    token: Option<&str>,
+) -> Result<std::result::Result<TargetAllocation, AllocationError>> {
+    let command = match request {
+        AllocationRequest::Preset { .. } => "apply_preset",
+        AllocationRequest::Weights { .. } => "set_allocation",
+    };
+    let mut entry = AuditEntry::new(command, client).request(request);
+    if let Ok(previous) = board.get_target_allocation().await {
+        entry = entry.previous(previous);
+    }
+    
+    let config = board.config();
+    let target = auth::authorize(client, config, token)
+        .and_then(|_| allocation::resolve(request, &config.allocation, &config.portfolio));
+    
+    match target {
+        Ok(target) => {
+            info!("📊 Setting allocation: {}% / {}%", target.stocks_pct, target.bonds_pct);
+            if let Err(e) = board.set_target_allocation(target.stocks_pct, target.bonds_pct).await {
+                audit::record(board, entry.new_value(&target).failed(&e)).await;
+                return Err(e);
+            }
+            audit::record(board, entry.new_value(&target)).await;
+            Ok(Ok(target))
+        }
+        Err(e) => {
+            warn!("🚫 Rejected allocation change: {}", e);
+            audit::record(board, entry.rejected(&e)).await;
+            Ok(Err(e))
+        }
+    }
+}
+
+/// REST: default, configured and runtime log levels
+async fn get_logging(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let config = &board.config().logging;
+    match board.get_log_levels().await {
+        Ok(overrides) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "level": config.level,
+                "configured": config.levels,
+                "overrides": overrides,
+                "directives": logging::directives(config, &overrides).ok(),
+            })),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// REST: override (or clear) an agent's or target's log level
+async fn post_logging(
+    token: Option<String>,
+    client: ClientIdentity,
+    request: LogLevelRequest,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (body, status) = match apply_log_level(&board, &client, &request, token.as_deref()).await {
+        Ok(levels) => (serde_json::json!({ "overrides": levels }), warp::http::StatusCode::OK),
+        Err((status, message)) => (serde_json::json!({ "error": message }), status),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&body), status))
+}
+
+/// Authorize, validate and store a log level override, apply it to this
+/// process (others follow within `reload_interval_secs`) and record it in
+/// the audit log; returns every override now in force
+async fn apply_log_level(
+    board: &Blackboard,
+    client: &ClientIdentity,
+    request: &LogLevelRequest,
+    token: Option<&str>,
+) -> std::result::Result<BTreeMap<String, String>, (warp::http::StatusCode, String)> {
+    let internal = |e: anyhow::Error| (warp::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    let mut entry = AuditEntry::new("set_log_level", client).request(request);
+    let mut levels = board.get_log_levels().await.map_err(internal)?;
+    entry = entry.previous(&levels);
+    
+    let config = board.config();
+    if let Err(e) = auth::authorize(client, config, token) {
+        warn!("🚫 Rejected log level change: {}", e);
+        audit::record(board, entry.rejected(&e)).await;
+        return Err((warp::http::StatusCode::UNAUTHORIZED, e.to_string()));
+    }
+    match &request.level {
+        Some(level) => levels.insert(request.target.clone(), level.to_lowercase()),
+        None => levels.remove(&request.target),
+    };
+    if let Err(e) = logging::directives(&config.logging, &levels) {
+        audit::record(board, entry.rejected(&e)).await;
+        return Err((warp::http::StatusCode::BAD_REQUEST, e.to_string()));
+    }
+    
+    if let Err(e) = board.set_log_levels(&levels).await {
+        audit::record(board, entry.failed(&e)).await;
+        return Err(internal(e));
+    }
+    match logging::apply(&config.logging, &levels) {
+        Ok(directives) => info!("🔊 Log levels: {}", directives),
+        Err(e) => warn!("Failed to apply log levels: {:#}", e),
+    }
+    audit::record(board, entry.new_value(&levels)).await;
+    Ok(levels)
+}
+
+/// REST: the system mode as last derived, and the kill switch
+async fn get_mode(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let (status, kill_switch) = match (board.get_system_status().await, board.get_kill_switch().await) {
+        (Ok(status), Ok(kill_switch)) => (status, kill_switch),
+        (Err(e), _) | (_, Err(e)) => (Some(SystemStatus::unreachable(e, chrono::Utc::now())), None),
+    };
+    match status {
+        Some(status) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "mode": status.mode,
+                "reasons": status.reasons,
+                "since": status.since,
+                "updated_at": status.updated_at,
+                "kill_switch": kill_switch,
+            })),
+            warp::http::StatusCode::OK,
+        )),
+        None => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "system mode not derived yet"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+/// REST: engage or release the kill switch; the mode is re-derived at once
+async fn post_kill_switch(
+    token: Option<String>,
+    client: ClientIdentity,
+    request: KillSwitchRequest,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let error = |message: String, status| {
+        Ok(warp::reply::with_status(warp::reply::json(&serde_json::json!({"error": message})), status))
+    };
+    let mut entry = AuditEntry::new("kill_switch", &client).request(&request);
+    match board.get_kill_switch().await {
+        Ok(previous) => entry = entry.previous(&previous),
+        Err(e) => return error(e.to_string(), warp::http::StatusCode::INTERNAL_SERVER_ERROR),
+    }
+    if let Err(e) = auth::authorize(&client, board.config(), token.as_deref()) {
+        warn!("🚫 Rejected kill switch change: {}", e);
+        audit::record(&board, entry.rejected(&e)).await;
+        return error(e.to_string(), warp::http::StatusCode::UNAUTHORIZED);
+    }
+    
+    let engaged_by = client.user.clone()
+        .or_else(|| client.operator.clone())
+        .or_else(|| client.ip.clone())
+        .unwrap_or_else(|| client.channel.clone());
+    let switch = request.engaged.then(|| KillSwitch {
+        reason: request.reason.clone().unwrap_or_else(|| "no reason given".to_string()),
+        engaged_by,
+        engaged_at: chrono::Utc::now(),
+    });
+    if let Err(e) = board.set_kill_switch(switch.as_ref()).await {
+        audit::record(&board, entry.failed(&e)).await;
+        return error(e.to_string(), warp::http::StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    match &switch {
+        Some(switch) => warn!("🛑 Kill switch engaged by {}: {}", switch.engaged_by, switch.reason),
+        None => info!("🟢 Kill switch released"),
+    }
+    audit::record(&board, entry.new_value(&switch)).await;
+    
+    let status = mode::refresh(&board).await;
+    Ok(warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({ "kill_switch": switch, "status": status })),
+        warp::http::StatusCode::OK,
+    ))
+}
+
+/// REST: the simulated clock and the simulated time elapsed on it
+async fn get_sim_clock(board: Arc<Blackboard>) -> Result<impl warp::Reply, warp::Rejection> {
+    let (body, status) = match board.get_sim_clock().await {
+        Ok(Some(state)) => (sim_clock_body(&state), warp::http::StatusCode::OK),
+        Ok(None) => (
+            serde_json::json!({"error": "no simulated clock (no VIX history is being replayed)"}),
+            warp::http::StatusCode::NOT_FOUND,
+        ),
+        Err(e) => (serde_json::json!({"error": e.to_string()}), warp::http::StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&body), status))
+}
+
+/// REST: run the simulated clock at a multiple of real time
+async fn post_sim_speed(
+    token: Option<String>,
+    client: ClientIdentity,
+    request: SpeedRequest,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let now = chrono::Utc::now();
+    let result = change_sim_clock(&board, &client, "sim_speed", &request, token.as_deref(), |clock| {
+        clock.with_speed(request.speed, now)
+    }).await;
+    Ok(sim_clock_reply(result))
+}
+
+/// REST: stop or resume the simulated clock
+async fn post_sim_pause(
+    token: Option<String>,
+    client: ClientIdentity,
+    request: PauseRequest,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let now = chrono::Utc::now();
+    let result = change_sim_clock(&board, &client, "sim_pause", &request, token.as_deref(), |clock| {
+        Ok(clock.with_paused(request.paused, now))
+    }).await;
+    Ok(sim_clock_reply(result))
+}
+
+/// REST: skip simulated time ahead
+async fn post_sim_jump(
+    token: Option<String>,
+    client: ClientIdentity,
+    request: JumpRequest,
+    board: Arc<Blackboard>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let now = chrono::Utc::now();
+    let result = change_sim_clock(&board, &client, "sim_jump", &request, token.as_deref(), |clock| {
+        clock.jumped(request.secs, now)
+    }).await;
+    Ok(sim_clock_reply(result))
+}
+
+/// Authorize and store a change to the simulated clock (every replaying
+/// process follows within a second) and record it in the audit log
+async fn change_sim_clock(
+    board: &Blackboard,
+    client: &ClientIdentity,
+    command: &str,
+    request: impl Serialize,
+    token: Option<&str>,
+    change: impl FnOnce(&ClockState) -> std::result::Result<ClockState, String>,
+) -> std::result::Result<ClockState, (warp::http::StatusCode, String)> {
+    let internal = |e: anyhow::Error| (warp::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    let mut entry = AuditEntry::new(command, client).request(request);
+    let current = board.get_sim_clock().await.map_err(internal)?;
+    entry = entry.previous(current);
+    
+    if let Err(e) = auth::authorize(client, board.config(), token) {
+        warn!("🚫 Rejected simulated clock change: {}", e);
+        audit::record(board, entry.rejected(&e)).await;
+        return Err((warp::http::StatusCode::UNAUTHORIZED, e.to_string()));
+    }
+    let Some(current) = current.filter(|_| sim_clock::controllable(board.config())) else {
+        let message = "the simulated clock only runs while a VIX history is replayed (vix_source = \"history\")";
+        audit::record(board, entry.rejected(message)).await;
+        return Err((warp::http::StatusCode::CONFLICT, message.to_string()));
+    };
+    let changed = match change(&current) {
+        Ok(changed) => changed,
+        Err(e) => {
+            audit::record(board, entry.rejected(&e)).await;
+            return Err((warp::http::StatusCode::BAD_REQUEST, e));
+        }
+    };
+    
+    if let Err(e) = board.put_sim_clock(&changed).await {
+        audit::record(board, entry.failed(&e)).await;
+        return Err(internal(e));
+    }
+    audit::record(board, entry.new_value(changed)).await;
+    Ok(changed)
+}
+
+fn sim_clock_body(state: &ClockState) -> serde_json::Value {
+    serde_json::json!({
+        "speed": state.speed,
+        "paused": state.paused,
+        "elapsed_secs": state.elapsed_secs_at(chrono::Utc::now()),
+        "changed_at": state.anchored_at,
+    })
+}
+
+fn sim_clock_reply(result: std::result::Result<ClockState, (warp::http::StatusCode, String)>) -> impl warp::Reply {
+    let (body, status) = match result {
+        Ok(state) => (sim_clock_body(&state), warp::http::StatusCode::OK),
+        Err((status, message)) => (serde_json::json!({ "error": message }), status),
+    };
+    warp::reply::with_status(warp::reply::json(&body), status)
+}
+
+/// Get current pheromone status, with projected decay, for all types
 async fn get_pheromone_status(board: &Blackboard) -> Result<Vec<PheromoneStatus>> {
+    let dashboard = &board.config().dashboard;
+    let samples = dashboard.forecast_samples;
+    let forecast_step_secs = if samples > 1 {
+        dashboard.forecast_horizon_secs / (samples - 1) as f64
+    } else {
+        0.0
+    };
     let mut statuses = Vec::new();
     
     for ptype in PheromoneType::ALL {
-        let intensity = board.get_intensity(ptype).await?;
         let threshold = ptype.threshold(board.config());
+        let pheromone = board.get_pheromone(ptype).await?;
+        let intensity = pheromone.as_ref().map_or(0.0, |p| p.current_intensity());
         statuses.push(PheromoneStatus {
             name: ptype.label().to_string(),
             intensity,
             threshold,
             is_active: intensity > threshold,
+            decay_rate: pheromone.as_ref().map_or(ptype.decay_rate(board.config()), |p| p.decay_rate),
+            time_until_threshold_secs: pheromone.as_ref().and_then(|p| p.time_until_inactive(threshold)),
+            forecast: match &pheromone {
+                Some(p) => p.forecast(dashboard.forecast_horizon_secs, samples),
+                None => vec![0.0; samples],
+            },
+            forecast_step_secs,
         });
     }
     
     Ok(statuses)
 }
 
-/// Handle message from dashboard client
-async fn handle_client_message(board: &Blackboard, msg: ClientMessage) {
+/// Handle message from dashboard client, returning any reply for it
+async fn handle_client_message(
+    board: &Blackboard,
+    client: &ClientIdentity,
+    msg: ClientMessage,
+) -> Option<DashboardMessage> {
+    if board.config().dashboard.read_only && msg.is_command() {
+        return Some(DashboardMessage::Error { message: "this server is read-only".to_string() });
+    }
+    
     match msg {
-        ClientMessage::SetAllocation { stocks_pct, bonds_pct } => {
-            info!("📊 Dashboard setting allocation: {}% / {}%", stocks_pct, bonds_pct);
-            if let Err(e) = board.set_target_allocation(stocks_pct, bonds_pct).await {
-                error!("Failed to set allocation: {}", e);
-            }
+        ClientMessage::SetAllocation { stocks_pct, bonds_pct, token } => {
+            let request = AllocationRequest::Weights { stocks_pct, bonds_pct };
+            allocation_reply(board, client, &request, token.as_deref()).await
+        }
+        ClientMessage::ApplyPreset { preset, token } => {
+            let request = AllocationRequest::Preset { preset };
+            allocation_reply(board, client, &request, token.as_deref()).await
+        }
+        ClientMessage::Hello { encoding } => {
+            info!("📱 Dashboard negotiated {:?} frames", encoding);
+            Some(DashboardMessage::Hello { encoding, schema_version: encoding::SCHEMA_VERSION })
         }
         ClientMessage::GetStatus => {
             // Status is sent automatically by the broadcast loop
+            None
+        }
+        ClientMessage::Replay { since } => {
+            let events: Vec<_> = board.events_since(since).into_iter().map(event_message).collect();
+            let trades = match board.get_trade_history(board.config().trade_log.max_entries).await {
+                Ok(trades) => trades
+                    .into_iter()
+                    .rev()
+                    .filter(|t| export::parse_timestamp(&t.timestamp).is_some_and(|ts| ts > since))
+                    .collect(),
+                Err(e) => {
+                    error!("Failed to read trade log for replay: {}", e);
+                    Vec::new()
+                }
+            };
+            info!("⏪ Replaying {} events and {} trades since {}", events.len(), trades.len(), since);
+            Some(DashboardMessage::Replay { events, trades })
         }
-        ClientMessage::Reset => {
-            info!("🔄 Dashboard requested reset");
-            if let Err(e) = board.clear_all().await {
-                error!("Failed to reset: {}", e);
+        ClientMessage::Reset { scope, token, confirm } => {
+            info!("🔄 Dashboard requested {:?} reset", scope);
+            let mut entry = AuditEntry::new("reset", client).request(serde_json::json!({"scope": scope}));
+            if let Ok(Some(previous)) = board.get_portfolio_state().await {
+                entry = entry.previous(previous);
             }
-            // Reset portfolio to initial state
-            let initial = PortfolioState::default();
-            if let Err(e) = board.set_portfolio_state(&initial).await {
-                error!("Failed to reset portfolio: {}", e);
+            
+            // An authenticated user still has to confirm, but needs no token
+            let expected = allocation::operator_token(&board.config().allocation).filter(|_| client.user.is_none());
+            if let Err(e) = reset::authorize(scope, expected.as_deref(), token.as_deref(), confirm.as_deref(), board.swarm_name()) {
+                warn!("🚫 Rejected {:?} reset: {}", scope, e);
+                audit::record(board, entry.rejected(&e)).await;
+                return Some(DashboardMessage::Error { message: e.to_string() });
             }
+            
+            match reset::reset(board, scope).await {
+                Ok(restored) => {
+                    audit::record(board, entry.new_value(restored)).await;
+                    Some(DashboardMessage::ResetComplete { scope })
+                }
+                Err(e) => {
+                    error!("Failed to reset: {}", e);
+                    audit::record(board, entry.failed(&e)).await;
+                    Some(DashboardMessage::Error { message: "reset failed".to_string() })
+                }
+            }
+        }
+        ClientMessage::SetLogLevel { target, level, token } => {
+            let request = LogLevelRequest { target, level };
+            match apply_log_level(board, client, &request, token.as_deref()).await {
+                Ok(levels) => Some(DashboardMessage::LogLevels { levels }),
+                Err((_, message)) => Some(DashboardMessage::Error { message }),
+            }
+        }
+    }
+}
+
+/// Dashboard form of a blackboard event
+fn event_message(evt: PheromoneEvent) -> DashboardMessage {
+    DashboardMessage::Event {
+        seq: evt.seq,
+        timestamp: evt.timestamp.to_rfc3339(),
+        event_type: format!("{:?}", evt.action),
+        pheromone: evt.pheromone_type,
+        intensity: evt.intensity,
+        detail: evt.detail,
+        count: evt.count,
+        run_id: evt.run_id,
+        error: evt.error,
+    }
+}
+
+/// Apply an allocation change from the dashboard; rejections become an error reply
+async fn allocation_reply(
+    board: &Blackboard,
+    client: &ClientIdentity,
+    request: &AllocationRequest,
+    token: Option<&str>,
+) -> Option<DashboardMessage> {
+    match apply_allocation(board, client, request, token).await {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(DashboardMessage::Error { message: e.to_string() }),
+        Err(e) => {
+            error!("Failed to set allocation: {}", e);
+            Some(DashboardMessage::Error { message: "failed to store allocation".to_string() })
         }
     }
 }
@@ -1,8 +1,12 @@
 //! WebSocket Server Module
 //!
 //! Provides real-time updates to the React dashboard via WebSocket.
-//! Broadcasts pheromone intensity changes and agent status.
+//! Broadcasts pheromone intensity changes and agent status, as JSON or
+//! (when negotiated) MessagePack frames.
 
+pub mod encoding;
 pub mod handler;
+pub mod protocol;
 
 pub use handler::start_websocket_server;
+pub use protocol::{ClientMessage, DashboardMessage, PheromoneStatus};
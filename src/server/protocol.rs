@@ -0,0 +1,234 @@
+//! Dashboard Protocol
+//!
+//! Messages exchanged over `/ws`: `DashboardMessage` from the server and
+//! `ClientMessage` from clients. Both sides (the server handler and the
+//! `client` SDK) use these types, so the protocol is declared once.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::agents::forecast::DriftForecast;
+use crate::core::blackboard::{AgentMetrics, MarketUpdate, PortfolioState, TargetAllocation, TradeLogEntry};
+use crate::core::config::AllocationPreset;
+use crate::core::correlation::CorrelationReading;
+use crate::core::daily_summary::DailySummary;
+use crate::core::error::DriftGuardError;
+use crate::core::mode::SystemStatus;
+use crate::core::reset::ResetScope;
+use crate::execution::queue::DeadLetter;
+use crate::server::encoding::WireEncoding;
+
+/// Message sent to dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DashboardMessage {
+    #[serde(rename = "pheromone_update")]
+    PheromoneUpdate {
+        pheromones: Vec<PheromoneStatus>,
+    },
+    #[serde(rename = "portfolio_update")]
+    PortfolioUpdate {
+        portfolio: PortfolioState,
+    },
+    #[serde(rename = "event")]
+    Event {
+        seq: u64,
+        timestamp: String,
+        event_type: String,
+        pheromone: String,
+        intensity: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        detail: Option<String>,
+        /// Sniff observations coalesced into this event
+        count: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        run_id: Option<String>,
+        /// Categorized error of Error, DataAnomaly and ExecutionFailed events
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<DriftGuardError>,
+    },
+    #[serde(rename = "agent_metrics")]
+    AgentMetricsUpdate {
+        agents: Vec<AgentMetrics>,
+    },
+    #[serde(rename = "trade_history")]
+    TradeHistory {
+        trades: Vec<TradeLogEntry>,
+    },
+    #[serde(rename = "target_update")]
+    TargetUpdate {
+        target: TargetAllocation,
+    },
+    #[serde(rename = "market_update")]
+    MarketUpdate {
+        #[serde(flatten)]
+        market: MarketUpdate,
+    },
+    /// Current system mode and why, sent every tick
+    #[serde(rename = "system_mode")]
+    SystemMode {
+        status: SystemStatus,
+    },
+    /// Latest Forecast agent projection ("days until rebalance")
+    #[serde(rename = "forecast_update")]
+    ForecastUpdate {
+        forecast: DriftForecast,
+    },
+    /// Recent rolling correlations, oldest first
+    #[serde(rename = "correlation_update")]
+    CorrelationUpdate {
+        history: Vec<CorrelationReading>,
+    },
+    /// Latest end-of-day summary
+    #[serde(rename = "daily_summary")]
+    DailySummary {
+        summary: DailySummary,
+    },
+    /// Orders the execution queue gave up on, most recent first
+    #[serde(rename = "dead_letters")]
+    DeadLetters {
+        orders: Vec<DeadLetter>,
+    },
+    #[serde(rename = "presets")]
+    Presets {
+        presets: Vec<AllocationPreset>,
+    },
+    /// Acknowledges a hello; later frames use `encoding`
+    #[serde(rename = "hello")]
+    Hello {
+        encoding: WireEncoding,
+        schema_version: u32,
+    },
+    /// Events and trades missed while disconnected, oldest first
+    #[serde(rename = "replay")]
+    Replay {
+        events: Vec<DashboardMessage>,
+        trades: Vec<TradeLogEntry>,
+    },
+    /// A reset finished
+    #[serde(rename = "reset_complete")]
+    ResetComplete {
+        scope: ResetScope,
+    },
+    /// Runtime log level overrides after a `set_log_level`
+    #[serde(rename = "log_levels")]
+    LogLevels {
+        levels: BTreeMap<String, String>,
+    },
+    /// A client request was rejected
+    #[serde(rename = "error")]
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PheromoneStatus {
+    pub name: String,
+    pub intensity: f64,
+    pub threshold: f64,
+    pub is_active: bool,
+    /// λ in I(t) = I₀ × e^(-λt), for animating decay client-side
+    pub decay_rate: f64,
+    /// Seconds until the intensity drops to the threshold (absent when inactive)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_until_threshold_secs: Option<f64>,
+    /// Projected intensities from now, `forecast_step_secs` apart
+    pub forecast: Vec<f64>,
+    pub forecast_step_secs: f64,
+}
+
+/// Message received from dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ClientMessage {
+    #[serde(rename = "set_allocation")]
+    SetAllocation {
+        stocks_pct: f64,
+        bonds_pct: f64,
+        #[serde(default)]
+        token: Option<String>,
+    },
+    #[serde(rename = "apply_preset")]
+    ApplyPreset {
+        preset: String,
+        #[serde(default)]
+        token: Option<String>,
+    },
+    /// Negotiate the frame encoding (usually the first message)
+    #[serde(rename = "hello")]
+    Hello {
+        #[serde(default)]
+        encoding: WireEncoding,
+    },
+    #[serde(rename = "get_status")]
+    GetStatus,
+    /// Backfill events and trades after `since` (e.g. the last event seen)
+    #[serde(rename = "replay")]
+    Replay {
+        since: chrono::DateTime<chrono::Utc>,
+    },
+    /// Soft (pheromones), portfolio or hard (history too) reset; the
+    /// larger scopes need the operator token and `confirm`
+    #[serde(rename = "reset")]
+    Reset {
+        #[serde(default)]
+        scope: ResetScope,
+        #[serde(default)]
+        token: Option<String>,
+        #[serde(default)]
+        confirm: Option<String>,
+    },
+    /// Override an agent's or tracing target's log level (`level` null clears it)
+    #[serde(rename = "set_log_level")]
+    SetLogLevel {
+        target: String,
+        #[serde(default)]
+        level: Option<String>,
+        #[serde(default)]
+        token: Option<String>,
+    },
+}
+
+impl ClientMessage {
+    /// Whether the message changes blackboard state
+    pub fn is_command(&self) -> bool {
+        matches!(
+            self,
+            Self::SetAllocation { .. } | Self::ApplyPreset { .. } | Self::Reset { .. } | Self::SetLogLevel { .. }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_round_trip() {
+        let msg = DashboardMessage::Replay {
+            events: vec![DashboardMessage::Event {
+                seq: 7,
+                timestamp: "2026-03-02T15:00:00+00:00".to_string(),
+                event_type: "Deposited".to_string(),
+                pheromone: "Price Freshness".to_string(),
+                intensity: 1.0,
+                detail: None,
+                count: 1,
+                run_id: None,
+                error: None,
+            }],
+            trades: vec![],
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        let DashboardMessage::Replay { events, .. } = serde_json::from_str(&json).unwrap() else {
+            panic!("expected replay: {}", json);
+        };
+        assert!(matches!(&events[0], DashboardMessage::Event { seq: 7, .. }));
+        
+        // Clients may omit optional fields
+        let reset: ClientMessage = serde_json::from_str(r#"{"type": "reset"}"#).unwrap();
+        assert!(matches!(reset, ClientMessage::Reset { scope: ResetScope::Soft, token: None, .. }));
+        assert!(reset.is_command());
+    }
+}
@@ -0,0 +1,92 @@
+//! DriftGuard Physics for the Browser
+//!
+//! wasm-bindgen exports of the pheromone decay curve, compiled from the
+//! same source file the swarm uses (`src/core/physics/curve.rs`), so the
+//! dashboard can animate intensities between server pushes without
+//! drifting from the server's numbers. Build the npm package with:
+//!
+//! ```text
+//! wasm-pack build wasm --target web
+//! ```
+//!
+//! Timestamps are JavaScript milliseconds (`Date.now()`).
+
+use wasm_bindgen::prelude::*;
+
+#[path = "../../src/core/physics/curve.rs"]
+mod curve;
+
+/// Intensity `elapsedSecs` after deposit (or after an observed intensity)
+#[wasm_bindgen(js_name = intensityAt)]
+pub fn intensity_at(initial_intensity: f64, decay_rate: f64, elapsed_secs: f64) -> f64 {
+    curve::intensity_at(initial_intensity, decay_rate, elapsed_secs)
+}
+
+/// Seconds between two `Date.now()` timestamps
+#[wasm_bindgen(js_name = elapsedSecs)]
+pub fn elapsed_secs(created_at_ms: f64, now_ms: f64) -> f64 {
+    curve::elapsed_secs(created_at_ms as i64, now_ms as i64)
+}
+
+/// Seconds after deposit at which the intensity reaches `threshold`
+#[wasm_bindgen(js_name = timeToThreshold)]
+pub fn time_to_threshold(initial_intensity: f64, decay_rate: f64, threshold: f64) -> f64 {
+    curve::time_to_threshold(initial_intensity, decay_rate, threshold)
+}
+
+#[wasm_bindgen(js_name = halfLife)]
+pub fn half_life(decay_rate: f64) -> f64 {
+    curve::half_life(decay_rate)
+}
+
+/// Intensities at `samples` points from `elapsedSecs` to `horizonSecs` later
+#[wasm_bindgen]
+pub fn forecast(
+    initial_intensity: f64,
+    decay_rate: f64,
+    elapsed_secs: f64,
+    horizon_secs: f64,
+    samples: usize,
+) -> Vec<f64> {
+    curve::forecast(initial_intensity, decay_rate, elapsed_secs, horizon_secs, samples)
+}
+
+/// A pheromone as deposited on the blackboard (`created_at` parsed with
+/// `Date.parse`)
+#[wasm_bindgen]
+pub struct Pheromone {
+    initial_intensity: f64,
+    decay_rate: f64,
+    created_at_ms: f64,
+}
+
+#[wasm_bindgen]
+impl Pheromone {
+    #[wasm_bindgen(constructor)]
+    pub fn new(initial_intensity: f64, decay_rate: f64, created_at_ms: f64) -> Self {
+        Self { initial_intensity, decay_rate, created_at_ms }
+    }
+
+    /// Intensity at `nowMs`
+    #[wasm_bindgen(js_name = intensityAt)]
+    pub fn intensity_at(&self, now_ms: f64) -> f64 {
+        intensity_at(self.initial_intensity, self.decay_rate, elapsed_secs(self.created_at_ms, now_ms))
+    }
+
+    /// Whether the pheromone is above `threshold` at `nowMs`
+    #[wasm_bindgen(js_name = isActive)]
+    pub fn is_active(&self, threshold: f64, now_ms: f64) -> bool {
+        self.intensity_at(now_ms) > threshold
+    }
+
+    /// Seconds from `nowMs` until the pheromone drops to `threshold`
+    /// (`undefined` once it has)
+    #[wasm_bindgen(js_name = timeUntilInactive)]
+    pub fn time_until_inactive(&self, threshold: f64, now_ms: f64) -> Option<f64> {
+        if !self.is_active(threshold, now_ms) {
+            return None;
+        }
+        let total = time_to_threshold(self.initial_intensity, self.decay_rate, threshold);
+        Some((total - elapsed_secs(self.created_at_ms, now_ms)).max(0.0))
+    }
+}